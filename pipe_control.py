@@ -12,41 +12,49 @@ if sys.platform == "win32":
     import win32file
     import pywintypes
     
+    def open_pipe(pipe_name, attempts=50):
+        """Connect to the pipe server created by fisherman, retrying while it starts up."""
+        for _ in range(attempts):
+            try:
+                return win32file.CreateFile(
+                    pipe_name,
+                    win32file.GENERIC_WRITE,
+                    0, None,
+                    win32file.OPEN_EXISTING,
+                    0, None
+                )
+            except pywintypes.error:
+                time.sleep(0.1)
+        return None
+
+    def send(pipe_name, message):
+        # fisherman serves each client in turn, so connect once per signal
+        pipe = open_pipe(pipe_name)
+        if pipe is None:
+            print("Could not connect to fisherman's pipe")
+            return False
+        try:
+            win32file.WriteFile(pipe, message.encode())
+        finally:
+            win32file.CloseHandle(pipe)
+        return True
+
     def main():
         pipe_name = r'\\.\pipe\fisherman_signals'
         
-        print(f"Creating named pipe: {pipe_name}")
-        print()
-        
-        # Create the named pipe
-        try:
-            pipe = win32pipe.CreateNamedPipe(
-                pipe_name,
-                win32pipe.PIPE_ACCESS_DUPLEX,
-                win32pipe.PIPE_TYPE_MESSAGE | win32pipe.PIPE_READMODE_MESSAGE | win32pipe.PIPE_WAIT,
-                1, 65536, 65536, 0, None
-            )
-        except pywintypes.error as e:
-            print(f"Failed to create pipe: {e}")
-            return
-        
-        # Start the game in a new terminal
+        # Start the game in a new terminal; it creates the named pipe server
         cmd = f'start "Fisherman Game" cmd /c ".\\fisherman.exe --pipe {pipe_name}"'
         subprocess.Popen(cmd, shell=True)
         print("Game opened in separate window!")
-        print("Waiting for game to connect to pipe...")
+        print(f"Signals are delivered through: {pipe_name}")
+        print()
+        print("Control the game:")
+        print("  s - Send SUCCESS signal")
+        print("  f - Send FAILURE signal")
+        print("  q - Quit")
+        print()
         
         try:
-            # Wait for the game to connect
-            win32pipe.ConnectNamedPipe(pipe, None)
-            print("Game connected to pipe!")
-            print()
-            print("Control the game:")
-            print("  s - Send SUCCESS signal")
-            print("  f - Send FAILURE signal")
-            print("  q - Quit")
-            print()
-            
             while True:
                 command = input("Enter command (s/f/q): ").strip().lower()
                 
@@ -55,23 +63,18 @@ if sys.platform == "win32":
                     break
                     
                 elif command == 's':
-                    message = "SUCCESS:Great job!\n"
-                    win32file.WriteFile(pipe, message.encode())
-                    print("✓ Sent SUCCESS signal")
+                    if send(pipe_name, "SUCCESS:Great job!\n"):
+                        print("✓ Sent SUCCESS signal")
                     
                 elif command == 'f':
-                    message = "FAILURE:Try again!\n"
-                    win32file.WriteFile(pipe, message.encode())
-                    print("✗ Sent FAILURE signal")
+                    if send(pipe_name, "FAILURE:Try again!\n"):
+                        print("✗ Sent FAILURE signal")
                     
                 else:
                     print("Invalid command")
                     
         except KeyboardInterrupt:
             print("\nClosing...")
-        finally:
-            win32file.CloseHandle(pipe)
-            print("Pipe closed")
 
 else:
     # Unix/Linux version using FIFO
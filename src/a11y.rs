@@ -0,0 +1,171 @@
+//! Accessible event output (`--a11y-fifo`) for screen readers and braille displays.
+//!
+//! Events are short plain-ASCII lines written to a FIFO as they happen, e.g.
+//! `SUCCESS: deploy done` or `Caught Large Trout, 64 cm`. Routine updates such as
+//! progress are rate limited so a refreshable braille line is not flooded; outcomes
+//! and catches are always sent at once. Writing `?` to the companion `<path>.in`
+//! FIFO asks for a one-line summary of the current scene. The request can't go to the
+//! event FIFO itself: a FIFO is a single stream, so the game reading requests from it
+//! would take event lines away from the screen reader too.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Minimum gap between two routine event lines.
+const ROUTINE_GAP: Duration = Duration::from_secs(2);
+
+/// Whether an event must be delivered now or may be coalesced with later ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Immediate,
+    Routine,
+}
+
+pub struct A11yChannel {
+    tx: Sender<String>,
+    summary_requested: Arc<AtomicBool>,
+    last_routine: Option<Instant>,
+    pending: Option<String>,
+    created: Vec<PathBuf>,
+}
+
+/// The companion FIFO that summary requests are read from.
+pub fn query_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".in");
+    PathBuf::from(name)
+}
+
+impl A11yChannel {
+    /// Create the event FIFO at `path` (and its `.in` query FIFO) and start serving it.
+    #[cfg(unix)]
+    pub fn open(path: PathBuf) -> io::Result<A11yChannel> {
+        let query = query_path(&path);
+        let mut created = Vec::new();
+        for p in [&path, &query] {
+            if crate::signals::ensure_fifo(p)? {
+                created.push(p.clone());
+            }
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let hint = summary_hint(&query);
+        thread::spawn(move || serve_events(&path, &hint, rx));
+
+        let summary_requested = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&summary_requested);
+        thread::spawn(move || {
+            loop {
+                let Ok(file) = fs::File::open(&query) else { return };
+                for line in BufReader::new(file).lines().map_while(Result::ok) {
+                    if line.trim() == "?" {
+                        flag.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        });
+
+        Ok(A11yChannel {
+            tx,
+            summary_requested,
+            last_routine: None,
+            pending: None,
+            created,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn open(_path: PathBuf) -> io::Result<A11yChannel> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "--a11y-fifo requires a Unix platform"))
+    }
+
+    /// Queue an event line. Routine events arriving faster than the rate limit replace
+    /// each other, so only the newest is written once the gap has passed.
+    pub fn event(&mut self, urgency: Urgency, text: impl Into<String>) {
+        let text = text.into();
+        match urgency {
+            Urgency::Immediate => self.send(text),
+            Urgency::Routine => {
+                self.pending = Some(text);
+                self.flush(Instant::now());
+            }
+        }
+    }
+
+    /// Write a pending routine event if the rate limit allows it. Call once per frame.
+    pub fn flush(&mut self, now: Instant) {
+        if self.pending.is_none()
+            || self.last_routine.is_some_and(|t| now.duration_since(t) < ROUTINE_GAP)
+        {
+            return;
+        }
+        self.last_routine = Some(now);
+        if let Some(text) = self.pending.take() {
+            self.send(text);
+        }
+    }
+
+    /// Whether a reader asked for a summary since the last call.
+    pub fn take_summary_request(&self) -> bool {
+        self.summary_requested.swap(false, Ordering::Relaxed)
+    }
+
+    fn send(&self, text: String) {
+        let _ = self.tx.send(text);
+    }
+
+    /// Remove the FIFOs this channel created.
+    pub fn cleanup(&self) {
+        for p in &self.created {
+            let _ = fs::remove_file(p);
+        }
+    }
+}
+
+/// The first line each reader gets, saying where summaries are asked for.
+fn summary_hint(query: &Path) -> String {
+    format!("Fisherman ready. Write ? to {} for a summary.", query.display())
+}
+
+/// Write event lines to the FIFO, reopening it (which waits for the next reader)
+/// whenever the current reader goes away. Each reader is greeted with `hint`. Events
+/// queued while nobody was reading are stale by the time someone opens the FIFO, so
+/// they are dropped.
+fn serve_events(path: &Path, hint: &str, rx: Receiver<String>) {
+    loop {
+        let Ok(mut out) = OpenOptions::new().write(true).open(path) else { return };
+        while rx.try_recv().is_ok() {}
+        let mut reader_gone = false;
+        for line in std::iter::once(hint.to_string()).chain(rx.iter()) {
+            if writeln!(out, "{}", braille_safe(&line)).and_then(|_| out.flush()).is_err() {
+                reader_gone = true;
+                break;
+            }
+        }
+        if !reader_gone {
+            return;
+        }
+    }
+}
+
+/// Reduce a line to printable ASCII on one line, which every braille table can show.
+fn braille_safe(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\r' | '\n' | '\t' => ' ',
+            '—' | '–' => '-',
+            '·' => ',',
+            c if c.is_ascii_graphic() || c == ' ' => c,
+            _ => '?',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,197 @@
+//! Milestones unlocked by catching fish, kept in the profile next to the catch journal.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use serde::{Deserialize, Serialize};
+
+use crate::fishing_game::{CaughtFish, SizeCategory};
+use crate::season;
+
+const CENTURY: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Achievement {
+    FirstCatch,
+    HundredCatches,
+    EverySpecies,
+    MassiveOfEach,
+    StormCatch,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 5] = [
+        Achievement::FirstCatch,
+        Achievement::HundredCatches,
+        Achievement::EverySpecies,
+        Achievement::MassiveOfEach,
+        Achievement::StormCatch,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::FirstCatch => "First Catch",
+            Achievement::HundredCatches => "Centurion",
+            Achievement::EverySpecies => "Full Set",
+            Achievement::MassiveOfEach => "Giant Hunter",
+            Achievement::StormCatch => "Storm Chaser",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstCatch => "Land your first fish",
+            Achievement::HundredCatches => "Land 100 fish",
+            Achievement::EverySpecies => "Land one of every species",
+            Achievement::MassiveOfEach => "Land a Massive fish of every species",
+            Achievement::StormCatch => "Land a fish during a storm",
+        }
+    }
+}
+
+/// One fish landed, as the journal logs it for `fisherman export`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedCatch {
+    /// The day it was landed, as `YYYY-MM-DD`.
+    pub date: String,
+    pub species: String,
+    pub size: f32,
+    pub category: String,
+    pub shiny: bool,
+}
+
+/// Lifetime record of landed fish that the milestones are judged against.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Journal {
+    pub catches: u32,
+    pub species: BTreeSet<String>,
+    pub massive: BTreeSet<String>,
+    /// Species landed as a shiny variant.
+    pub shiny: BTreeSet<String>,
+    /// The biggest landed of each species, in cm.
+    pub best: BTreeMap<String, f32>,
+    pub unlocked: BTreeSet<Achievement>,
+    /// Every fish landed since the journal began logging them, oldest first.
+    pub log: Vec<LoggedCatch>,
+}
+
+impl Journal {
+    /// Whether `fish` beats the biggest yet landed of its species. The first of a
+    /// species sets a record without beating one, so it doesn't count.
+    pub fn is_record(&self, fish: &CaughtFish) -> bool {
+        self.best.get(&fish.species_name).is_some_and(|&best| fish.size > best)
+    }
+
+    /// Log a landed fish and return the achievements it unlocks. `all_species` is every
+    /// species currently in the sea.
+    pub fn record(&mut self, fish: &CaughtFish, in_storm: bool, all_species: &[String]) -> Vec<Achievement> {
+        self.catches += 1;
+        self.species.insert(fish.species_name.clone());
+        if fish.size_category == SizeCategory::Massive {
+            self.massive.insert(fish.species_name.clone());
+        }
+        if fish.shiny {
+            self.shiny.insert(fish.species_name.clone());
+        }
+        let best = self.best.entry(fish.species_name.clone()).or_insert(0.0);
+        *best = best.max(fish.size);
+        self.log.push(LoggedCatch {
+            date: season::today_stamp(),
+            species: fish.species_name.clone(),
+            size: fish.size,
+            category: fish.size_category.as_str().trim_end_matches('!').to_string(),
+            shiny: fish.shiny,
+        });
+        let has_all = |set: &BTreeSet<String>| !all_species.is_empty() && all_species.iter().all(|s| set.contains(s));
+
+        let earned: Vec<Achievement> = Achievement::ALL
+            .into_iter()
+            .filter(|a| !self.unlocked.contains(a))
+            .filter(|a| match a {
+                Achievement::FirstCatch => true,
+                Achievement::HundredCatches => self.catches >= CENTURY,
+                Achievement::EverySpecies => has_all(&self.species),
+                Achievement::MassiveOfEach => has_all(&self.massive),
+                Achievement::StormCatch => in_storm,
+            })
+            .collect();
+        self.unlocked.extend(earned.iter().copied());
+        earned
+    }
+}
+
+/// Cells in a stock's gauge.
+const STOCK_GAUGE: usize = 10;
+
+/// Lists every achievement, unlocked ones highlighted, with the journal totals and how
+/// stocked each species is in the local waters.
+pub struct AchievementsView<'a> {
+    pub journal: &'a Journal,
+    pub species_count: usize,
+    /// Each species' name and stock, from 0.0 to 1.0.
+    pub stocks: &'a [(&'a str, f32)],
+}
+
+impl Widget for AchievementsView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(format!(
+                "Fish landed: {}   Species: {}/{}   Massive: {}/{}",
+                self.journal.catches,
+                self.journal.species.len(),
+                self.species_count,
+                self.journal.massive.len(),
+                self.species_count
+            )),
+            Line::from(format!("Shiny: {}/{}", self.journal.shiny.len(), self.species_count)),
+            Line::from(""),
+        ];
+        for a in Achievement::ALL {
+            let (mark, style) = if self.journal.unlocked.contains(&a) {
+                ("★", Style::default().fg(Color::Yellow))
+            } else {
+                ("☆", Style::default().fg(Color::DarkGray))
+            };
+            lines.push(Line::from(Span::styled(format!(" {} {:<14} {}", mark, a.title(), a.description()), style)));
+        }
+        if !self.stocks.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from("Local stocks"));
+        }
+        for &(name, stock) in self.stocks {
+            let filled = (stock.clamp(0.0, 1.0) * STOCK_GAUGE as f32).round() as usize;
+            let color = if stock < 0.34 {
+                Color::Red
+            } else if stock < 0.67 {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!(" {:<14} ", name)),
+                Span::styled("█".repeat(filled), Style::default().fg(color)),
+                Span::styled("░".repeat(STOCK_GAUGE - filled), Style::default().fg(Color::DarkGray)),
+                Span::raw(format!(" {:>3}%", (stock * 100.0).round() as u32)),
+            ]));
+        }
+
+        let width = 60.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let rect = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        Clear.render(rect, buf);
+        Paragraph::new(lines)
+            .block(Block::default().title("Achievements").borders(Borders::ALL))
+            .render(rect, buf);
+    }
+}
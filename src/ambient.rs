@@ -0,0 +1,238 @@
+//! Background actors that keep a long wait from looking frozen: flocks of birds crossing
+//! the daytime sky and a small boat drifting along the horizon. Each kind has its own
+//! spawn timer, so they turn up now and then rather than all at once, and each actor is
+//! an [`Entity`] in the sky's [`Scene`].
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::StatefulWidget;
+
+use crate::scene::{Entity, Scene, SceneContext};
+use crate::theme::Theme;
+
+/// Seconds between flocks and between boats, picked at random from these ranges.
+const FLOCK_INTERVAL: (f32, f32) = (30.0, 90.0);
+const BOAT_INTERVAL: (f32, f32) = (120.0, 300.0);
+/// Columns per second.
+const FLOCK_SPEED: (f32, f32) = (5.0, 9.0);
+const BOAT_SPEED: (f32, f32) = (0.3, 0.6);
+/// Wing beats per second.
+const FLAP_RATE: f32 = 3.0;
+const WINGS: [char; 2] = ['v', '-'];
+/// Sails up, hull down, for a boat heading right; mirrored for one heading left.
+const BOAT_RIGHT: [&str; 2] = ["  |\\ ", "\\___/"];
+const BOAT_LEFT: [&str; 2] = [" /|  ", "\\___/"];
+const BOAT_WIDTH: u16 = 5;
+
+/// Birds flying in a loose V, each some columns behind the leader and rows above or
+/// below it.
+#[derive(Debug, Clone)]
+struct Flock {
+    x: f32,
+    y: i32,
+    vx: f32,
+    birds: Vec<(i32, i32)>,
+    flying: Duration,
+    color: Color,
+    gone: bool,
+}
+
+impl Entity for Flock {
+    fn update(&mut self, dt: Duration, ctx: &SceneContext) {
+        self.x += self.vx * dt.as_secs_f32();
+        self.flying += dt;
+        self.color = ctx.theme.bird;
+        let margin = self.birds.len() as f32 * 2.0 + 2.0;
+        self.gone = self.x <= -margin || self.x >= ctx.area.width as f32 + margin;
+    }
+
+    fn render(&self, buf: &mut Buffer, area: Rect) {
+        let flap = (self.flying.as_secs_f32() * FLAP_RATE) as usize;
+        // The V trails behind the leader, whichever way the flock is flying
+        let behind = -self.vx.signum() as i32;
+        for (i, &(dx, dy)) in self.birds.iter().enumerate() {
+            let x = area.x as i32 + self.x.round() as i32 + dx * behind;
+            let wing = WINGS[(flap + i) % WINGS.len()];
+            put(buf, area, x, area.y as i32 + self.y + dy, wing, self.color);
+        }
+    }
+
+    fn alive(&self) -> bool {
+        !self.gone
+    }
+}
+
+/// A boat sitting on the bottom row of the sky, the horizon.
+#[derive(Debug, Clone)]
+struct Boat {
+    x: f32,
+    vx: f32,
+    color: Color,
+    gone: bool,
+}
+
+impl Entity for Boat {
+    fn update(&mut self, dt: Duration, ctx: &SceneContext) {
+        self.x += self.vx * dt.as_secs_f32();
+        self.color = ctx.theme.boat;
+        self.gone = self.x <= -(BOAT_WIDTH as f32) - 1.0 || self.x >= ctx.area.width as f32 + 1.0;
+    }
+
+    fn render(&self, buf: &mut Buffer, area: Rect) {
+        let hull_y = area.bottom() as i32 - 1;
+        let sprite = if self.vx > 0.0 { BOAT_RIGHT } else { BOAT_LEFT };
+        for (row, line) in sprite.iter().enumerate() {
+            let y = hull_y - (sprite.len() - 1 - row) as i32;
+            for (i, ch) in line.chars().enumerate().filter(|(_, ch)| *ch != ' ') {
+                put(buf, area, area.x as i32 + self.x.round() as i32 + i as i32, y, ch, self.color);
+            }
+        }
+    }
+
+    fn alive(&self) -> bool {
+        !self.gone
+    }
+}
+
+/// The actors currently on screen and when the next ones are due.
+#[derive(Debug)]
+pub struct Ambient {
+    rng: StdRng,
+    scene: Scene,
+    until_flock: Duration,
+    until_boat: Duration,
+}
+
+impl Ambient {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let until_flock = interval(&mut rng, FLOCK_INTERVAL);
+        let until_boat = interval(&mut rng, BOAT_INTERVAL);
+        Ambient { rng, scene: Scene::default(), until_flock, until_boat }
+    }
+
+    /// Move the actors on by `dt` across the sky in `ctx`, and send in new ones when
+    /// their timers run out. Birds only fly by day.
+    pub fn tick(&mut self, dt: Duration, ctx: &SceneContext) {
+        self.scene.update(dt, ctx);
+        let width = ctx.area.width as f32;
+
+        self.until_flock = self.until_flock.saturating_sub(dt);
+        if self.until_flock.is_zero() {
+            if ctx.daylight >= 0.5 {
+                self.spawn_flock(width, ctx.theme);
+            }
+            self.until_flock = interval(&mut self.rng, FLOCK_INTERVAL);
+        }
+        self.until_boat = self.until_boat.saturating_sub(dt);
+        if self.until_boat.is_zero() {
+            if self.scene.count::<Boat>() == 0 {
+                self.spawn_boat(width, ctx.theme);
+            }
+            self.until_boat = interval(&mut self.rng, BOAT_INTERVAL);
+        }
+    }
+
+    fn spawn_flock(&mut self, width: f32, theme: Theme) {
+        let count = self.rng.gen_range(3..=6);
+        // Birds fall in behind the leader on alternate arms of the V
+        let birds = (0..count)
+            .map(|i: i32| {
+                let rank = (i + 1) / 2;
+                (rank * 2, if i % 2 == 0 { rank } else { -rank })
+            })
+            .collect();
+        let speed = self.rng.gen_range(FLOCK_SPEED.0..FLOCK_SPEED.1);
+        let rightward = self.rng.gen_bool(0.5);
+        let margin = count as f32 * 2.0;
+        self.scene.spawn(Flock {
+            x: if rightward { -margin } else { width + margin },
+            y: self.rng.gen_range(3..6),
+            vx: if rightward { speed } else { -speed },
+            birds,
+            flying: Duration::ZERO,
+            color: theme.bird,
+            gone: false,
+        });
+    }
+
+    fn spawn_boat(&mut self, width: f32, theme: Theme) {
+        let speed = self.rng.gen_range(BOAT_SPEED.0..BOAT_SPEED.1);
+        let rightward = self.rng.gen_bool(0.5);
+        self.scene.spawn(Boat {
+            x: if rightward { -(BOAT_WIDTH as f32) } else { width },
+            vx: if rightward { speed } else { -speed },
+            color: theme.boat,
+            gone: false,
+        });
+    }
+
+    /// Whether anything is on screen.
+    pub fn is_empty(&self) -> bool {
+        self.scene.is_empty()
+    }
+}
+
+fn interval(rng: &mut StdRng, (min, max): (f32, f32)) -> Duration {
+    Duration::from_secs_f32(rng.gen_range(min..max))
+}
+
+/// Draws the [`Ambient`] actors into the sky: birds up high and boats sitting on its
+/// bottom row, the horizon. Only symbols and foregrounds are set, so the sky shows
+/// through.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AmbientActors;
+
+impl StatefulWidget for AmbientActors {
+    type State = Ambient;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Ambient) {
+        state.scene.render(buf, area);
+    }
+}
+
+fn put(buf: &mut Buffer, area: Rect, x: i32, y: i32, ch: char, color: Color) {
+    if x < area.x as i32 || x >= area.right() as i32 || y < area.y as i32 || y >= area.bottom() as i32 {
+        return;
+    }
+    buf[(x as u16, y as u16)].set_char(ch).set_style(Style::default().fg(color));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actors_arrive_cross_and_leave() {
+        let area = Rect::new(0, 0, 40, 8);
+        let day = SceneContext { area, daylight: 1.0, theme: Theme::default() };
+        let mut ambient = Ambient::new(9);
+        ambient.until_flock = Duration::from_secs(1);
+        ambient.until_boat = Duration::from_secs(1);
+        ambient.tick(Duration::from_secs(1), &day);
+        assert_eq!((ambient.scene.count::<Flock>(), ambient.scene.count::<Boat>()), (1, 1));
+
+        // Flying in from off screen, the flock is in view a few seconds later
+        ambient.tick(Duration::from_secs(3), &day);
+        let mut buf = Buffer::empty(area);
+        AmbientActors.render(area, &mut buf, &mut ambient);
+        assert!(area.positions().any(|p| WINGS.iter().any(|w| buf[p].symbol() == w.to_string())));
+
+        // The boat is slower to come into sight on the horizon
+        ambient.tick(Duration::from_secs(10), &day);
+        let mut buf = Buffer::empty(area);
+        AmbientActors.render(area, &mut buf, &mut ambient);
+        assert!((0..40).any(|x| buf[(x, 7)].symbol() == "_"));
+
+        // Long after, both are gone and birds don't come out at night
+        ambient.until_flock = Duration::from_secs(1);
+        ambient.until_boat = Duration::from_secs(1000);
+        ambient.tick(Duration::from_secs(200), &SceneContext { daylight: 0.0, ..day });
+        assert!(ambient.is_empty());
+    }
+}
@@ -0,0 +1,4568 @@
+//! The game without the terminal: input goes in through [`App::handle_event`], time
+//! through [`App::update`] and the scene comes out of [`App::draw`]. `main` only parses
+//! flags, wires up the signal sources and drives the loop.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use ratatui::Frame;
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::layout::{Alignment, Position, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Widget};
+
+use crate::a11y::{A11yChannel, Urgency};
+use crate::achievements::AchievementsView;
+use crate::ambient::{Ambient, AmbientActors};
+use crate::color::{self, ColorDepth};
+use crate::conditions::{self, Conditions, ConditionsPanel};
+use crate::config_file::{self, Value};
+use crate::console::{self, Command, Console, ConsoleKey, ConsoleLine, Setting};
+use crate::csv_frames::{self, CrabSprites, FishSpecies, SpeciesFrames, SpriteFrame};
+use crate::dock::{self, DockSettings, Shore, Shoreline};
+use crate::economy::{self, Bait, MAX_ROD_HOLDERS, Profile};
+use crate::effects::{self, EffectLayer, Effects};
+use crate::encyclopedia::{Encyclopedia, EncyclopediaState};
+use crate::events::{EventBus, GameEvent};
+use crate::fish::{self, BuiltFrames, Fish, SpawnSettings, SpeciesTraits};
+use crate::fisherman::{self, Fisherman, FishermanAnimation, Pose, Skin};
+use crate::fog::{Fog, FogLayer};
+use crate::fishing_game::{self, BitePhase, BiteTracker, Catch, CaughtFish, HookSet, ItemTable, SizeCategory, SizeDistribution, TAUT_TENSION};
+use crate::aquarium::{Aquarium, AquariumState};
+use crate::autopilot::{Autopilot, Glance};
+use crate::fishing_line::{self, FightGauge, FishingLine, FishingState, GAUGE_WIDTH, MIN_CAST_DISTANCE};
+use crate::game_state::GameState;
+use crate::graphics::{self, GraphicsProtocol, SpriteId, SpriteImages, SpriteRenderer};
+use crate::handoff::Handoff;
+use crate::hooks::Hooks;
+use crate::help::HelpPanel;
+use crate::layer::Layer;
+use crate::layout::{Layout, TIDE_RANGE, TooSmall};
+use crate::leaderboard::{Leaderboard, NameEntry, NamePrompt};
+use crate::jellyfish::{Jellyfish, JellyfishLayer};
+use crate::keymap::{self, Action, Keymap};
+use crate::lighting::{self, Glow};
+use crate::menu::{Screen, Settings, SettingsField, SettingsMenu, TitleChoice, TitleMenu};
+use crate::metrics::Metrics;
+use crate::moon;
+use crate::netplay::{self, Link, LinkEvent, NetCatch, NetLine, NetMessage};
+use crate::ocean::{Ocean, OceanState};
+use crate::photo::{self, PhotoCard};
+use crate::plugin::{PluginContext, ScenePlugin};
+use crate::shop::{Shop, ShopAction, ShopView};
+use crate::scene::SceneContext;
+use crate::signals::{SharedSignal, SignalMessage, SignalPolicy};
+use crate::seabed::Seabed;
+use crate::season::{self, Calendar, Date, DecorationView, DriftOverlay, Season, SeasonalScene};
+use crate::stars::Stars;
+use crate::stats::{SessionStats, StatsPanel};
+use crate::theme::Theme;
+use crate::weather::{Weather, WeatherOverlay};
+use crate::status_bar::StatusBar;
+use crate::symbols;
+use crate::tail::{LogTail, TailPanel};
+use crate::tasks::{TaskAnglers, TaskBoard};
+use crate::banner::{ArtBanner, Banners};
+use crate::history::{HistoryPanel, SignalHistory};
+use crate::tournament::{HighScores, Scoreboard, Tournament};
+use crate::underwater::{CrossSection, SunkHook};
+use crate::watchdog::{TimeoutPolicy, Watchdog};
+use crate::wind::{Wind, WindIndicator};
+use crate::widgets::{Crab, DockLife, FishermanDock, Gloom, Mirrored, NightWater, SkyBackdrop};
+
+/// From one high tide to the next.
+const TIDE_PERIOD: Duration = Duration::from_secs(20 * 60);
+
+const SPAWN_CHECK_INTERVAL: Duration = Duration::from_secs(3);
+/// Chance at each spawn check that a boss turns up, if none is about.
+const BOSS_CHANCE: f64 = 0.01;
+/// How long the screen shakes when a boss takes the hook.
+const BOSS_SHAKE: Duration = Duration::from_millis(600);
+/// How long it shakes when a Massive fish takes the hook, a boss turns up or a FAILURE
+/// comes in.
+const IMPACT_SHAKE: Duration = Duration::from_millis(300);
+const MAX_CAST_TIME: Duration = Duration::from_secs(2);
+/// How far out the line hangs to show a task's progress.
+const PROGRESS_CAST_DISTANCE: u16 = 16;
+const CAST_ANIMATION_DURATION: Duration = Duration::from_millis(800);
+/// How long the hook takes to come back up the line to the rod tip.
+const RETRIEVE_DURATION: Duration = Duration::from_millis(500);
+/// How taut the line hangs with nothing on it, from 0 (slack) to 1 (straight).
+const RESTING_TENSION: f32 = 0.2;
+/// How quickly the line's tension settles towards what's pulling on it, per second.
+const TENSION_RATE: f32 = 4.0;
+/// Columns the rings spread to when a fish is pulled out of the water.
+const CATCH_RIPPLE_RADIUS: u16 = 3;
+/// How often each fish lets a bubble go, on average.
+const BUBBLES_PER_SECOND: f32 = 0.3;
+/// How light it has to be for fish to cast shadows on the sea floor.
+const SHADOW_DAYLIGHT: f32 = 0.4;
+/// How often each vent on the sea floor sends up a column of bubbles, on average.
+const VENT_COLUMNS_PER_SECOND: f32 = 0.1;
+/// Raindrops a second striking each column of sea, at full storm intensity.
+const RAIN_SPLASHES_PER_COLUMN: f32 = 0.15;
+const CATCH_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+const SIGNAL_BANNER_DURATION: Duration = Duration::from_secs(3);
+const INFO_BANNER_DURATION: Duration = Duration::from_secs(5);
+/// How long the sea churns after a FAILURE, and how long before its banner goes up.
+const SQUALL_DURATION: Duration = Duration::from_secs(4);
+const SQUALL_LEAD: Duration = Duration::from_secs(2);
+/// How much a full squall darkens the scene.
+const SQUALL_GLOOM: f32 = 0.45;
+const STAR_DENSITY: f32 = 0.02;
+/// How finely the moon's phase is drawn; the backdrop is redrawn at each step.
+const MOON_PHASE_STEPS: f32 = 16.0;
+/// Sprites in a checkout, which override the ones built into the binary.
+const REPO_FISH_DIR: &str = "src/fish";
+const REPO_MOON: &str = "moon.csv";
+const REPO_CRAB_DIR: &str = "src/crab";
+const REPO_SEASONS_DIR: &str = "src/seasons";
+const REPO_SKINS_DIR: &str = "src/skins";
+/// Columns at the seaward end of the dock, where the fisherman stands, that the crab
+/// keeps clear of.
+const CRAB_LANE_OFFSET: u16 = 4;
+/// A fish landed within this many columns of the crab sends it into hiding.
+const CRAB_SPOOK_DISTANCE: u16 = 30;
+/// How many times slower fish swim with `--reduce-motion`.
+const REDUCED_MOTION_SLOWDOWN: u32 = 4;
+/// The tournament countdown turns red for the last stretch.
+const TOURNAMENT_HURRY: Duration = Duration::from_secs(30);
+
+/// Rows the tide has lifted the sea `elapsed` into the scene, negative when it's out.
+/// Scenes start at half tide on the flood.
+fn tide_rows(elapsed: Duration) -> i16 {
+    let turn = elapsed.as_secs_f32() / TIDE_PERIOD.as_secs_f32();
+    (TIDE_RANGE as f32 * (turn * std::f32::consts::TAU).sin()).round() as i16
+}
+
+/// How the line hangs to show a task `percent` done while nobody is fishing: cast a
+/// short way out and reeled in from the deepest fish lane to the surface as the task
+/// goes on.
+fn progress_hook(layout: &Layout, shore: Shore, percent: u8) -> FishingState {
+    let anchor = layout.anchor(shore);
+    let landing_x = anchor.further_out(anchor.seaward(dock::ROD_REACH + PROGRESS_CAST_DISTANCE), layout.nearest_landing(shore));
+    let landing_x = landing_x.clamp(layout.ocean.x, layout.ocean.right().saturating_sub(1));
+    let deepest = layout.fish.bottom().saturating_sub(layout.ocean.y + 1);
+    let depth = (deepest as f32 * (100 - percent.min(100)) as f32 / 100.0).round() as u16;
+    FishingState::Landed { landing_x, landing_y: layout.ocean.y, depth }
+}
+
+/// A hook in the water as the fish see it: its column, the lane whose rows it hangs in
+/// and how far it draws them with `bait` on it.
+fn hook_in_lane(state: FishingState, bait: Option<Bait>, fish_area: Rect) -> Option<(u16, usize, f32)> {
+    let FishingState::Landed { landing_x, landing_y, depth } = state else {
+        return None;
+    };
+    let reach = bait.map_or(1.0, |bait| bait.attraction());
+    fish::lane_at(fish_area, landing_y.saturating_add(depth)).map(|lane| (landing_x, lane, reach))
+}
+
+/// Top row, width and height of the box a hook has to be in to reach `fish`: its
+/// sprite as drawn, using the size measured when the species was loaded and scaled up
+/// for a boss.
+fn hitbox(species: &[FishSpecies], frames: &[SpeciesFrames], fish: &Fish, fish_area: Rect) -> (u16, u16, u16) {
+    let (width, height) = match species.get(fish.species) {
+        Some(species) => species.size,
+        None => frames.get(fish.species).map_or((0, 0), csv_frames::frames_size),
+    };
+    let top = fish::sprite_top(fish, fish_area).max(0) as u16;
+    let (width, height) = fish.drawn_size((width, height));
+    (top, width, height)
+}
+
+/// What the cached [`Backdrop`] was drawn for; it is redrawn when any of these change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BackdropKey {
+    layout: Layout,
+    sky: Option<Color>,
+    night: bool,
+    theme: Theme,
+    /// Where the moon hangs and its phase, in steps of [`MOON_PHASE_STEPS`].
+    moon: Rect,
+    moon_phase: u8,
+}
+
+/// The parts of the scene that hold still: sky, moon and dock. Drawn once into a buffer
+/// and copied into every frame, so only the moving parts are rebuilt each tick.
+struct Backdrop {
+    key: BackdropKey,
+    buffer: Buffer,
+    /// Cells of the moon and dock sprites, which sit in front of the stars and the rain
+    /// respectively and are copied back over them.
+    moon: Vec<Position>,
+    dock: Vec<Position>,
+}
+
+/// Placeholder symbol for telling which cells a sprite draws on.
+const UNTOUCHED: &str = "\0";
+
+impl Backdrop {
+    fn new(key: BackdropKey, daylight: f32, moon: Option<&Text<'static>>) -> Backdrop {
+        let layout = key.layout;
+        let theme = key.theme;
+        let mut buffer = Buffer::empty(layout.size);
+        SkyBackdrop { daylight, theme }.render(layout.sky, &mut buffer);
+        let moon = match moon {
+            Some(moon) if key.night => Self::sprite(&mut buffer, |buf| {
+                let moon = SpriteFrame::bake(&moon::shade(moon, key.moon_phase as f32 / MOON_PHASE_STEPS));
+                moon.blit(buf, (key.moon.x as i32, key.moon.y as i32), 1, key.moon, &[])
+            }),
+            _ => Vec::new(),
+        };
+        let dock = Self::sprite(&mut buffer, |buf| {
+            let (home, far) = (FishermanDock { width: layout.dock.width, theme }, FishermanDock { width: layout.far_dock.width, theme });
+            match layout.anchor.shore {
+                Shore::Right => home.render(layout.dock, buf),
+                Shore::Left => Mirrored(home).render(layout.dock, buf),
+            }
+            if !layout.far_dock.is_empty() {
+                match layout.anchor.shore {
+                    Shore::Right => Mirrored(far).render(layout.far_dock, buf),
+                    Shore::Left => far.render(layout.far_dock, buf),
+                }
+            }
+        });
+        Backdrop { key, buffer, moon, dock }
+    }
+
+    /// Draw a sprite into `buffer` and return the cells it covers.
+    fn sprite(buffer: &mut Buffer, draw: impl Fn(&mut Buffer)) -> Vec<Position> {
+        let mut scratch = Buffer::filled(buffer.area, Cell::new(UNTOUCHED));
+        draw(&mut scratch);
+        draw(buffer);
+        scratch.area.positions().filter(|p| scratch[*p].symbol() != UNTOUCHED).collect()
+    }
+
+    /// Copy `cells` back over whatever moving layer was drawn on them.
+    fn restore(&self, cells: &[Position], buf: &mut Buffer) {
+        for &p in cells {
+            if buf.area.contains(p) {
+                buf[p] = self.buffer[p].clone();
+            }
+        }
+    }
+}
+
+/// Sprites for the species in the sea and the moon.
+pub struct Assets {
+    pub species: Vec<FishSpecies>,
+    /// Frames by species index.
+    pub frames: Vec<SpeciesFrames>,
+    /// `frames` recoloured for shiny fish.
+    pub shiny: Vec<SpeciesFrames>,
+    pub moon: Option<Text<'static>>,
+    pub crab: CrabSprites,
+    /// The fisherman's skins, by name.
+    pub skins: BTreeMap<String, Skin>,
+    /// Pictures to draw in place of the text sprites with `--graphics`.
+    pub images: SpriteImages,
+    /// What each season and holiday brings to the scene.
+    pub calendar: Calendar,
+    /// The sprites that couldn't be loaded, such as `fish` or `moon`. Placeholder fish
+    /// swim in an empty sea.
+    pub missing: Vec<String>,
+}
+
+impl Assets {
+    /// The embedded sprites, overridden by any in `src/fish`, `src/crab`, `src/skins`
+    /// and `moon.csv` on disk.
+    pub fn load() -> Assets {
+        Assets::load_layered(&[])
+    }
+
+    /// Like [`Assets::load`], with sprite directories laid over the built-in sprites,
+    /// later ones winning. Each holds `<species>/{left,right}/*.csv` and optionally a
+    /// `moon.csv`, a `crab` folder of frames, a `skins` folder of the fisherman's skins
+    /// and a `seasons` folder of seasonal manifests and decorations, and for `--graphics` any `right.png`, `left.png` and
+    /// `moon.png` pictures beside them.
+    pub fn load_layered(dirs: &[PathBuf]) -> Assets {
+        let moon_paths: Vec<PathBuf> =
+            std::iter::once(PathBuf::from(REPO_MOON)).chain(dirs.iter().map(|dir| dir.join("moon.csv"))).collect();
+        let moon = csv_frames::load_layered_moon(&moon_paths);
+        let crab_dirs: Vec<PathBuf> =
+            std::iter::once(PathBuf::from(REPO_CRAB_DIR)).chain(dirs.iter().map(|dir| dir.join("crab"))).collect();
+        let crab = csv_frames::load_layered_crab(&crab_dirs);
+        let skin_dirs: Vec<PathBuf> =
+            std::iter::once(PathBuf::from(REPO_SKINS_DIR)).chain(dirs.iter().map(|dir| dir.join("skins"))).collect();
+        let skins = fisherman::load_layered_skins(&skin_dirs);
+        let seasons_dirs: Vec<PathBuf> =
+            std::iter::once(PathBuf::from(REPO_SEASONS_DIR)).chain(dirs.iter().map(|dir| dir.join("seasons"))).collect();
+        let calendar = Calendar::load_layered(&seasons_dirs);
+
+        let species_dirs: Vec<PathBuf> = std::iter::once(PathBuf::from(REPO_FISH_DIR)).chain(dirs.iter().cloned()).collect();
+        let species = csv_frames::load_layered_fish_species(&species_dirs);
+        let names: Vec<String> = species.iter().map(|s| s.name.clone()).collect();
+        let images = SpriteImages::load(&species_dirs, &names, &moon_paths);
+        let mut assets = Assets { species, frames: Vec::new(), shiny: Vec::new(), moon, crab, skins, images, calendar, missing: Vec::new() };
+        assets.stand_in();
+        assets
+    }
+
+    /// Note what's missing, filling an empty sea with placeholder fish, and bake the
+    /// frames from the species.
+    fn stand_in(&mut self) {
+        let mut missing = Vec::new();
+        if self.species.is_empty() {
+            missing.push("fish");
+            self.species = csv_frames::placeholder_species();
+        }
+        if self.moon.is_none() {
+            missing.push("moon");
+        }
+        if self.crab.walk.is_empty() {
+            missing.push("crab");
+        }
+        if self.skins.is_empty() {
+            missing.push("fisherman");
+        }
+        self.missing = missing.into_iter().map(str::to_string).collect();
+        self.frames = self.species.iter().map(|s| s.frames.clone()).collect();
+        self.shiny = fish::shiny_frames(&self.frames);
+    }
+
+    /// A warning naming the sprites that couldn't be loaded, if any were missing.
+    pub fn warning(&self) -> Option<String> {
+        if self.missing.is_empty() {
+            return None;
+        }
+        let mut text = format!("Couldn't load sprites: {}", self.missing.join(", "));
+        if self.missing.iter().any(|what| what == "fish") {
+            text.push_str(" (placeholder fish swim instead)");
+        }
+        Some(text)
+    }
+
+    /// Everything on disk [`Assets::load_layered`] reads from, for watching.
+    pub fn source_paths(dirs: &[PathBuf]) -> Vec<PathBuf> {
+        [REPO_FISH_DIR, REPO_MOON, REPO_CRAB_DIR, REPO_SKINS_DIR, REPO_SEASONS_DIR].into_iter().map(PathBuf::from).chain(dirs.iter().cloned()).collect()
+    }
+}
+
+/// A note that pops up in the top-right corner for a few seconds.
+enum Toast {
+    Achievement(String),
+    /// An `INFO:` signal, or a line that wasn't a signal with `--unknown-lines show`.
+    Info(String),
+}
+
+/// One player's rod in a two-player game (`--players 2`, or `fisherman host` and
+/// `join`): the line, what's on it and their tally. The rod being played sits in
+/// [`App`]'s own fields and the other waits here, to be swapped in by
+/// [`App::with_rival`] for its turn.
+struct Rod {
+    shore: Shore,
+    /// Played on another machine, which reports the line: this is the line as last
+    /// reported. A remote rod never takes a turn here.
+    remote: Option<NetLine>,
+    fishing_state: FishingState,
+    hook_bait: Option<Bait>,
+    /// The second player fishes with a copy of the first's tackle, which isn't saved.
+    profile: Profile,
+    bites: BiteTracker,
+    anim: FishermanAnimation,
+    line_tension: f32,
+    cast_charge_start: Option<Duration>,
+    cast_animation_start: Option<Duration>,
+    retrieve_start: Option<Duration>,
+    retrieved_fish: Option<Fish>,
+    stats: SessionStats,
+}
+
+impl Rod {
+    fn new(shore: Shore, remote: bool, profile: Profile, fidget: bool) -> Rod {
+        Rod {
+            shore,
+            remote: remote.then_some(NetLine::Idle),
+            fishing_state: FishingState::Idle,
+            hook_bait: None,
+            profile,
+            bites: BiteTracker::new(),
+            anim: FishermanAnimation::new().with_fidget(fidget),
+            line_tension: RESTING_TENSION,
+            cast_charge_start: None,
+            cast_animation_start: None,
+            retrieve_start: None,
+            retrieved_fish: None,
+            stats: SessionStats::default(),
+        }
+    }
+
+    /// Trade places with the rod being played in `app`.
+    fn swap(&mut self, app: &mut App) {
+        std::mem::swap(&mut self.shore, &mut app.shore);
+        std::mem::swap(&mut self.fishing_state, &mut app.game.fishing_state);
+        std::mem::swap(&mut self.hook_bait, &mut app.game.hook_bait);
+        std::mem::swap(&mut self.profile, &mut app.game.profile);
+        std::mem::swap(&mut self.bites, &mut app.bites);
+        std::mem::swap(&mut self.anim, &mut app.fisherman_anim);
+        std::mem::swap(&mut self.line_tension, &mut app.line_tension);
+        std::mem::swap(&mut self.cast_charge_start, &mut app.cast_charge_start);
+        std::mem::swap(&mut self.cast_animation_start, &mut app.cast_animation_start);
+        std::mem::swap(&mut self.retrieve_start, &mut app.retrieve_start);
+        std::mem::swap(&mut self.retrieved_fish, &mut app.retrieved_fish);
+        std::mem::swap(&mut self.stats, &mut app.session_stats);
+    }
+}
+
+/// One of the first player's lines, left fishing in a rod holder (bought as
+/// [`economy::ShopItem::RodHolder`]) while another rod is in hand. The line in hand sits in [`App`]'s own fields; each
+/// held line is swapped in by [`App::with_held_lines`] for its turn.
+struct HeldLine {
+    fishing_state: FishingState,
+    hook_bait: Option<Bait>,
+    bites: BiteTracker,
+    line_tension: f32,
+    cast_charge_start: Option<Duration>,
+    cast_animation_start: Option<Duration>,
+    retrieve_start: Option<Duration>,
+    retrieved_fish: Option<Fish>,
+}
+
+impl HeldLine {
+    fn new() -> HeldLine {
+        HeldLine {
+            fishing_state: FishingState::Idle,
+            hook_bait: None,
+            bites: BiteTracker::new(),
+            line_tension: RESTING_TENSION,
+            cast_charge_start: None,
+            cast_animation_start: None,
+            retrieve_start: None,
+            retrieved_fish: None,
+        }
+    }
+
+    /// Trade places with the line in hand in `app`.
+    fn swap(&mut self, app: &mut App) {
+        std::mem::swap(&mut self.fishing_state, &mut app.game.fishing_state);
+        std::mem::swap(&mut self.hook_bait, &mut app.game.hook_bait);
+        std::mem::swap(&mut self.bites, &mut app.bites);
+        std::mem::swap(&mut self.line_tension, &mut app.line_tension);
+        std::mem::swap(&mut self.cast_charge_start, &mut app.cast_charge_start);
+        std::mem::swap(&mut self.cast_animation_start, &mut app.cast_animation_start);
+        std::mem::swap(&mut self.retrieve_start, &mut app.retrieve_start);
+        std::mem::swap(&mut self.retrieved_fish, &mut app.retrieved_fish);
+    }
+}
+
+/// `config`'s theme, in the season's colours if it's the default one: a theme picked
+/// on purpose keeps its own.
+fn seasonal_theme(config: &Config, seasonal: &SeasonalScene) -> Theme {
+    if config.theme == Theme::default() { seasonal.theme(config.theme) } else { config.theme }
+}
+
+/// The skin `config` asks for out of `skins`, or the default one if there's no such skin.
+fn wear(config: &Config, mut skins: BTreeMap<String, Skin>) -> Skin {
+    skins.remove(&config.skin).or_else(|| skins.remove(fisherman::DEFAULT_SKIN)).unwrap_or_default()
+}
+
+/// Draw a fisherman in `skin` and `pose` on the dock at `shore`, facing out to sea.
+fn draw_fisherman(buf: &mut Buffer, layout: &Layout, shore: Shore, skin: &Skin, pose: Pose, theme: Theme) {
+    let fisher = Fisherman { skin, offset_from_right: 1, pose, theme };
+    match shore {
+        Shore::Right => fisher.render(layout.fisherman_at(shore), buf),
+        Shore::Left => Mirrored(fisher).render(layout.fisherman_at(shore), buf),
+    }
+}
+
+/// What every layer of a frame is drawn from, worked out once before drawing them.
+#[derive(Clone, Copy)]
+struct Drawing {
+    layout: Layout,
+    elapsed: Duration,
+    daylight: f32,
+    night: bool,
+    theme: Theme,
+    /// A panel covers the scene, so pictures aren't drawn under it.
+    covered: bool,
+    /// The player's line, hanging a task's progress in the water while the rod is free.
+    line_state: FishingState,
+    preview: Option<(u16, u16)>,
+    rival_preview: Option<(u16, u16)>,
+}
+
+/// What's drawn of one player's line.
+struct LineView<'a> {
+    rod_tip: (u16, u16),
+    state: FishingState,
+    bites: &'a BiteTracker,
+    tension: f32,
+    retrieved_fish: Option<&'a Fish>,
+    /// Where the cast being charged would land, from [`App::cast_preview`].
+    preview: Option<(u16, u16)>,
+}
+
+/// Options fixed for the whole run, mostly from the command line.
+pub struct Config {
+    /// Inbox of the external signal sources; `None` enables the `s`/`f` test keys instead.
+    pub signals: Option<SharedSignal>,
+    /// The signal sources in use, as listed in the help overlay.
+    pub sources: Vec<String>,
+    pub on_signal: SignalPolicy,
+    /// Warn when no signal or heartbeat has arrived for this long (`--timeout`).
+    pub timeout: Option<Duration>,
+    pub on_timeout: TimeoutPolicy,
+    /// Light the dock lantern at night once it's been bought (the `lantern` setting).
+    pub lantern: bool,
+    /// Draw the line and ripples in braille dots, for smoother curves (`--braille`).
+    pub braille: bool,
+    /// Pick out a few constellations among the stars (`--constellations`).
+    pub constellations: bool,
+    /// Draw fish to the nearest column, out of step with each other, and turn them
+    /// round at the edges with easing (`--smooth-motion`).
+    pub smooth_motion: bool,
+    /// Plain ASCII in the terminal's own colours, for dumb terminals, serial consoles
+    /// and screen readers (`--ascii`).
+    pub ascii: bool,
+    /// Draw fish and the moon from pictures with this protocol (`--graphics`).
+    pub graphics: Option<GraphicsProtocol>,
+    /// Keep the scene calm (`--reduce-motion`): no twinkling, drifting weather or leg
+    /// kicking, slow fish and bites shown as text.
+    pub reduce_motion: bool,
+    /// Colours of the scene (`--theme`).
+    pub theme: Theme,
+    /// The name of the fishermen's skin (`--skin`).
+    pub skin: String,
+    /// The dock's side and length and the shore beyond it (`--dock-side`,
+    /// `--dock-width`, `--shoreline` or `[dock]`).
+    pub dock: DockSettings,
+    /// How busy the sea is kept (`--density`, `--max-fish` or `[spawning]`).
+    pub spawning: SpawnSettings,
+    /// The day of the year the season and any holiday follow (`--date`, today by
+    /// default); `None` leaves the scene out of season (`--season off`).
+    pub date: Option<Date>,
+    /// A season to keep whatever the date (`--season`).
+    pub season: Option<Season>,
+    /// What the terminal can show; every colour is mapped down to it (`--color-depth`).
+    pub color_depth: ColorDepth,
+    /// Show the in-game day, time and weather in the title (`--timelapse`).
+    pub show_clock: bool,
+    /// How many times faster than real time the in-game clock runs.
+    pub time_scale: f64,
+    pub keys: Keymap,
+    /// Where purchases and achievements are saved as they happen.
+    pub save_path: Option<PathBuf>,
+    /// Where photo cards of record and Massive catches are saved (`--photo-dir` or
+    /// `[photos]`).
+    pub photo_dir: Option<PathBuf>,
+    pub items: ItemTable,
+    pub a11y: Option<A11yChannel>,
+    /// Seed for everything random in the scene (`--seed`); `None` picks one at random.
+    pub seed: Option<u64>,
+    /// Let the AI fisherman play (`--demo`).
+    pub demo: bool,
+    /// Hand the rod to the AI fisherman after this long without a key press or mouse
+    /// movement (`--attract`), until the player is back.
+    pub attract_after: Option<Duration>,
+    /// The terminal reports key releases, so a cast goes out when the key is let go.
+    /// Without them pressing the key a second time casts instead.
+    pub key_release: bool,
+    /// Show the status bar along the bottom of the frame.
+    pub status_bar: bool,
+    /// A log file followed in a panel in the sky (`--tail`).
+    pub tail: Option<LogTail>,
+    /// Named tasks waited on together, each with its own fisherman (`--task`).
+    pub tasks: Vec<String>,
+    /// Shell commands run on game events (`[hooks]` in the config file).
+    pub hooks: Hooks,
+    /// How the SUCCESS and FAILURE banners look (`[banners]` in the config file).
+    pub banners: Banners,
+    /// Players sharing the keyboard (`--players`), 1 or 2. The second fishes from the
+    /// left shore with keys of their own.
+    pub players: u8,
+    /// The connection to another player's game sharing the sea (`fisherman host` or
+    /// `fisherman join`).
+    pub link: Option<Link>,
+    /// A timed round scoring the player's catches (`--tournament`).
+    pub tournament: Option<Tournament>,
+    /// Counters reported on the `--listen` endpoint's `/metrics`.
+    pub metrics: Option<Metrics>,
+    /// Actors of their own added to the scene (`--plugin`, `[plugins]` in the config file).
+    pub plugins: Vec<Box<dyn ScenePlugin>>,
+    /// Open on the title menu rather than straight into the game.
+    pub menu: bool,
+    /// The config file the settings screen writes its changes to.
+    pub config_path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            signals: None,
+            sources: Vec::new(),
+            on_signal: SignalPolicy::default(),
+            timeout: None,
+            on_timeout: TimeoutPolicy::default(),
+            lantern: true,
+            braille: false,
+            constellations: false,
+            smooth_motion: false,
+            ascii: false,
+            graphics: None,
+            reduce_motion: false,
+            theme: Theme::default(),
+            skin: fisherman::DEFAULT_SKIN.to_string(),
+            dock: DockSettings::default(),
+            spawning: SpawnSettings::default(),
+            date: None,
+            season: None,
+            color_depth: ColorDepth::default(),
+            show_clock: false,
+            time_scale: 1.0,
+            keys: Keymap::default(),
+            save_path: None,
+            photo_dir: None,
+            items: ItemTable::default(),
+            a11y: None,
+            seed: None,
+            demo: false,
+            status_bar: true,
+            tail: None,
+            tasks: Vec::new(),
+            hooks: Hooks::default(),
+            banners: Banners::default(),
+            players: 1,
+            link: None,
+            tournament: None,
+            metrics: None,
+            plugins: Vec::new(),
+            menu: false,
+            config_path: None,
+            attract_after: None,
+            key_release: false,
+        }
+    }
+}
+
+pub struct App {
+    config: Config,
+    /// The seed everything random follows from, picked at random if the config had none.
+    seed: u64,
+    species: Vec<FishSpecies>,
+    frames: Vec<SpeciesFrames>,
+    shiny_frames: Vec<SpeciesFrames>,
+    /// `frames` and `shiny_frames` baked for drawing.
+    baked: BuiltFrames,
+    baked_shiny: BuiltFrames,
+    traits: Vec<SpeciesTraits>,
+    species_names: Vec<String>,
+    moon: Option<Text<'static>>,
+    crab_sprites: CrabSprites,
+    /// What the fishermen look like (`--skin`).
+    skin: Skin,
+    /// The season's colours, drift and species, and the holiday's decoration.
+    seasonal: SeasonalScene,
+    /// Takes the sprites it has pictures for, when drawing with `--graphics`.
+    sprites: Box<dyn SpriteRenderer>,
+    crab: Crab,
+    /// Days into the lunar cycle the session started at.
+    moon_days: f64,
+    rng: StdRng,
+    ocean: OceanState,
+    /// Splashes and ripples on the water.
+    effects: Effects,
+    seabed: Seabed,
+    /// Birds and boats passing in the background.
+    ambient: Ambient,
+    jellyfish: Jellyfish,
+    wind: Wind,
+    fog: Fog,
+    /// The AI fisherman at the rod, if it's playing.
+    autopilot: Option<Autopilot>,
+    /// When the player last pressed a key or moved the mouse.
+    last_input: Duration,
+    /// The player's profile and stats from before the AI fisherman took over while they
+    /// were away, put back when they return.
+    attract: Option<(Profile, SessionStats)>,
+    backdrop: Option<Backdrop>,
+    /// Laid out for the terminal size, kept up to date from resize events.
+    layout: Layout,
+    /// The shore the rod in play casts from: the right, bar the second player's turns
+    /// and a joined networked game.
+    shore: Shore,
+    /// The other player's rod in a two-player game.
+    rival: Option<Rod>,
+    /// One line per rod the first player owns, the first being the rod they started
+    /// with; the one in hand is an idle stand-in here, its line being in play.
+    rods: Vec<HeldLine>,
+    rod_in_hand: usize,
+    /// The held line whose turn it is, while [`App::with_held_lines`] runs one.
+    held_turn: Option<usize>,
+    /// Time since the start of the run, advanced by [`App::update`].
+    elapsed: Duration,
+    game: GameState,
+    bites: BiteTracker,
+    fisherman_anim: FishermanAnimation,
+    stars: Stars,
+    last_spawn_check: Duration,
+    cast_charge_start: Option<Duration>,
+    cast_animation_start: Option<Duration>,
+    retrieve_start: Option<Duration>,
+    /// A landed fish riding the hook back in.
+    retrieved_fish: Option<Fish>,
+    /// How taut the line is, tightening as it's reeled and as fish pull on it.
+    line_tension: f32,
+    /// The latest catch, the coins it paid and when it was landed
+    caught: Option<(Catch, u32, Duration)>,
+    shop: Option<Shop>,
+    screen: Screen,
+    /// Where leaving the journal or the settings goes back to.
+    back_to: Screen,
+    /// The line selected on the title menu or the settings screen.
+    menu_selected: usize,
+    /// A saved game was loaded, so the title menu offers to continue it.
+    can_continue: bool,
+    /// The name being typed for a finished tournament round.
+    name_entry: NameEntry,
+    /// The high-score table on the leaderboard, whose lengths the selected line picks
+    /// between.
+    high_scores: HighScores,
+    /// The tank of fish landed, while it's open.
+    aquarium: Option<AquariumState>,
+    encyclopedia: Option<EncyclopediaState>,
+    show_stats: bool,
+    show_help: bool,
+    /// The `:` console, kept with its history while closed.
+    console: Console,
+    console_open: bool,
+    signal_history: SignalHistory,
+    /// How far the history panel is scrolled back, while it's open.
+    history_scroll: Option<usize>,
+    /// The underwater view is up in place of the scene.
+    underwater: bool,
+    /// Paused scenes still draw but nothing in them moves or times out.
+    paused: bool,
+    session_stats: SessionStats,
+    /// Achievement unlocks waiting to be shown, one at a time
+    toasts: VecDeque<Toast>,
+    toast_shown_at: Option<Duration>,
+    local_signal: Option<(bool, String)>,
+    signal_shown_at: Option<Duration>,
+    /// Outcome of the most recent external signal; decides the process exit code
+    last_outcome: Option<bool>,
+    progress: Option<(u8, String)>,
+    tasks: TaskBoard,
+    banner: Option<(String, Duration)>,
+    /// Time left of the squall a FAILURE stirs up.
+    squall: Duration,
+    /// Watching for the task to go quiet, until its wait is over.
+    watchdog: Option<Watchdog>,
+    /// The watchdog ran out under `--on-timeout exit`.
+    timed_out: bool,
+    events: EventBus,
+    was_night: bool,
+    /// A screenshot was asked for and the next drawn frame should be saved.
+    screenshot: bool,
+    /// An asset pack asked for on the console, waiting to be loaded.
+    pack_request: Option<String>,
+    quit: bool,
+}
+
+impl App {
+    /// Start a run on a screen of `size`, continuing `saved` if given or stocking a
+    /// fresh sea otherwise.
+    pub fn new(mut config: Config, assets: Assets, saved: Option<GameState>, size: Rect) -> App {
+        // Whoever joins a networked game stands on the left shore and the host on the
+        // right, whichever side their docks are set to
+        if let Some(ref link) = config.link {
+            config.dock.side = if link.hosting() { Shore::Right } else { Shore::Left };
+        }
+        let can_continue = saved.is_some();
+        let warning = assets.warning();
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let traits: Vec<SpeciesTraits> = assets.species.iter().map(|s| s.traits).collect();
+        let species_names: Vec<String> = assets.species.iter().map(|s| s.name.clone()).collect();
+        let seasonal = assets.calendar.scene(config.date, config.season);
+        let tasks = TaskBoard::new(&config.tasks);
+        let mut layout = Layout::with_tide(size, 0, tasks.len() as u16, config.dock);
+        let game = match saved {
+            Some(mut state) => {
+                state.adopt(&species_names, layout.lanes, size.width, config.time_scale);
+                state
+            }
+            None => {
+                let mut state = GameState::new(&mut rng, &assets.frames, &traits, size.width as f32, layout.lanes, config.spawning, config.time_scale);
+                state.fishes.retain(|fish| traits.get(fish.species).is_none_or(|t| seasonal.in_season(t.seasons)));
+                state
+            }
+        };
+        let shore = config.dock.side;
+        let away = shore.opposite();
+        let rival = if config.link.is_some() {
+            Some(Rod::new(away, true, Profile::default(), !config.reduce_motion))
+        } else {
+            (config.players > 1).then(|| Rod::new(away, false, game.profile.clone(), !config.reduce_motion))
+        };
+        if rival.is_some() {
+            layout = layout.with_far_shore();
+        }
+        let rods = (0..=game.profile.rod_holders.min(MAX_ROD_HOLDERS)).map(|_| HeldLine::new()).collect();
+        let theme = seasonal_theme(&config, &seasonal);
+        let stars = Stars::new(&mut rng, layout.sky, STAR_DENSITY).with_theme(theme).with_constellations(config.constellations.then_some(seed));
+        let seabed = Seabed::new(seed, layout.seabed).with_theme(theme);
+        let was_night = game.clock.daylight() < 0.5;
+        let fisherman_anim = FishermanAnimation::new().with_fidget(!config.reduce_motion);
+        let autopilot = config.demo.then(|| Autopilot::new(seed.wrapping_add(5)));
+        let screen = if config.menu { Screen::Title } else { Screen::Playing };
+        let watchdog = config.timeout.map(Watchdog::new);
+        let sprites = graphics::renderer(config.graphics, assets.images);
+        let greeting = seasonal.holiday.as_ref().map(|holiday| holiday.greeting.clone()).filter(|g| !g.is_empty());
+        let skin = wear(&config, assets.skins);
+        let mut app = App {
+            config,
+            seed,
+            species: assets.species,
+            baked: fish::bake_builds(&assets.frames),
+            baked_shiny: fish::bake_builds(&assets.shiny),
+            frames: assets.frames,
+            shiny_frames: assets.shiny,
+            traits,
+            species_names,
+            moon: assets.moon,
+            crab_sprites: assets.crab,
+            skin,
+            seasonal,
+            sprites,
+            crab: Crab::default(),
+            moon_days: (seed % moon::LUNAR_CYCLE_DAYS.ceil() as u64) as f64,
+            rng,
+            ocean: OceanState::new(seed),
+            effects: Effects::new(seed.wrapping_add(1)),
+            seabed,
+            ambient: Ambient::new(seed.wrapping_add(2)),
+            jellyfish: Jellyfish::new(seed.wrapping_add(3)),
+            wind: Wind::new(seed.wrapping_add(4)),
+            fog: Fog::new(seed.wrapping_add(6)),
+            autopilot,
+            last_input: Duration::ZERO,
+            attract: None,
+            backdrop: None,
+            layout,
+            shore,
+            rival,
+            rods,
+            rod_in_hand: 0,
+            held_turn: None,
+            elapsed: Duration::ZERO,
+            game,
+            bites: BiteTracker::new(),
+            fisherman_anim,
+            stars,
+            last_spawn_check: Duration::ZERO,
+            cast_charge_start: None,
+            cast_animation_start: None,
+            retrieve_start: None,
+            line_tension: RESTING_TENSION,
+            retrieved_fish: None,
+            caught: None,
+            shop: None,
+            screen,
+            back_to: Screen::Title,
+            menu_selected: 0,
+            can_continue,
+            name_entry: NameEntry::default(),
+            high_scores: HighScores::default(),
+            aquarium: None,
+            encyclopedia: None,
+            show_stats: false,
+            show_help: false,
+            console: Console::default(),
+            console_open: false,
+            signal_history: SignalHistory::default(),
+            history_scroll: None,
+            underwater: false,
+            paused: false,
+            session_stats: SessionStats::default(),
+            toasts: VecDeque::new(),
+            toast_shown_at: None,
+            local_signal: None,
+            signal_shown_at: None,
+            last_outcome: None,
+            progress: None,
+            tasks,
+            banner: None,
+            squall: Duration::ZERO,
+            watchdog,
+            timed_out: false,
+            events: EventBus::default(),
+            was_night,
+            screenshot: false,
+            pack_request: None,
+            quit: false,
+        };
+        // A warning about missing sprites goes up over any greeting
+        if let Some(text) = warning.or(greeting) {
+            app.show_banner(text);
+        }
+        let ctx = app.plugin_context();
+        for plugin in &mut app.config.plugins {
+            plugin.init(&ctx);
+        }
+        app
+    }
+
+    pub fn size(&self) -> Rect {
+        self.layout.size
+    }
+
+    /// The parts of the screen that move on their own forever, the waves and the
+    /// seaweed, which shouldn't keep the frame pacer from going idle.
+    pub fn ambient_areas(&self) -> [Rect; 2] {
+        [self.layout.ocean, self.seabed.swaying_area()]
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn species_names(&self) -> &[String] {
+        &self.species_names
+    }
+
+    pub fn last_outcome(&self) -> Option<bool> {
+        self.last_outcome
+    }
+
+    pub fn session_stats(&self) -> &SessionStats {
+        &self.session_stats
+    }
+
+    /// How long the session has run, not counting time spent paused.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn a11y(&self) -> Option<&A11yChannel> {
+        self.config.a11y.as_ref()
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Take back a quit asked for from the keyboard, for a scene that outlives whoever
+    /// is looking at it (`fisherman daemon`).
+    pub fn stay_open(&mut self) {
+        self.quit = false;
+    }
+
+    /// Whether the game quit because the task went quiet (`--on-timeout exit`).
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Write out the pictures taken for this frame (`--graphics`), once it's drawn.
+    pub fn flush_sprites(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        self.sprites.flush(out)
+    }
+
+    /// Whether a panel or another screen is over the scene, which pictures, drawn by
+    /// the terminal over everything, would hide.
+    fn scene_covered(&self) -> bool {
+        self.underwater
+            || self.show_stats
+            || self.show_help
+            || self.history_scroll.is_some()
+            || self.shop.is_some()
+            || self.aquarium.is_some()
+            || self.encyclopedia.is_some()
+            || self.screen != Screen::Playing
+    }
+
+    /// Whether a screenshot was asked for since the last call. The app can't see what
+    /// was drawn, so whoever owns the terminal saves it.
+    pub fn take_screenshot_request(&mut self) -> bool {
+        std::mem::take(&mut self.screenshot)
+    }
+
+    /// The asset pack asked for on the console since the last call, for whoever loads
+    /// sprites to swap in.
+    pub fn take_pack_request(&mut self) -> Option<String> {
+        self.pack_request.take()
+    }
+
+    /// Swap in freshly loaded sprites (`--watch-sprites`). Fish keep swimming if their
+    /// species is still there and vanish otherwise.
+    pub fn reload_sprites(&mut self, assets: Assets) {
+        if let Some(warning) = assets.warning() {
+            self.show_banner(warning);
+        }
+        let names: Vec<String> = assets.species.iter().map(|s| s.name.clone()).collect();
+        let old_names = std::mem::take(&mut self.species_names);
+        let before = self.game.fishes.len();
+        self.game.fishes.retain_mut(|fish| {
+            match old_names.get(fish.species).and_then(|name| names.iter().position(|n| n == name)) {
+                Some(species) => {
+                    fish.species = species;
+                    true
+                }
+                None => false,
+            }
+        });
+        // A hooked fish may have gone with its species, and the rest have moved
+        if self.game.fishes.len() != before {
+            self.bites = BiteTracker::new();
+            for rod in &mut self.rods {
+                rod.bites = BiteTracker::new();
+            }
+        }
+        self.traits = assets.species.iter().map(|s| s.traits).collect();
+        self.species = assets.species;
+        self.baked = fish::bake_builds(&assets.frames);
+        self.baked_shiny = fish::bake_builds(&assets.shiny);
+        self.frames = assets.frames;
+        self.shiny_frames = assets.shiny;
+        self.species_names = names;
+        self.moon = assets.moon;
+        self.crab_sprites = assets.crab;
+        self.skin = wear(&self.config, assets.skins);
+        self.seasonal = assets.calendar.scene(self.config.date, self.config.season);
+        self.sprites = graphics::renderer(self.config.graphics, assets.images);
+        self.backdrop = None;
+    }
+
+    /// Jolt the screen `cells` either way for `duration`, unless motion is reduced.
+    fn shake(&mut self, duration: Duration, cells: u16) {
+        if !self.config.reduce_motion {
+            self.effects.shake(duration, cells);
+        }
+    }
+
+    pub fn show_banner(&mut self, text: impl Into<String>) {
+        self.banner = Some((text.into(), self.elapsed));
+    }
+
+    /// Pick up exactly where a previous run left off.
+    pub fn restore(&mut self, handoff: Handoff) {
+        if let Some(ref inbox) = self.config.signals {
+            inbox.lock().unwrap().extend(handoff.pending_messages());
+        }
+        self.game = handoff.state;
+        self.game.adopt(&self.species_names, self.layout.lanes, self.layout.size.width, self.config.time_scale);
+        self.fit_rod_holders();
+        self.progress = handoff.progress;
+        self.banner = handoff.banner.map(|text| (text, self.elapsed));
+        self.last_outcome = handoff.last_outcome;
+        self.was_night = self.game.clock.daylight() < 0.5;
+    }
+
+    /// The live scene, for the next run to pick up.
+    pub fn handoff(&self) -> Handoff {
+        Handoff {
+            state: self.snapshot(),
+            progress: self.progress.clone(),
+            banner: self.banner.as_ref().map(|(text, _)| text.clone()),
+            last_outcome: self.last_outcome,
+            pending: match self.config.signals {
+                Some(ref inbox) => inbox.lock().unwrap().iter().map(SignalMessage::to_line).collect(),
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// The game fit for saving. Lines left in rod holders aren't kept, so the bait on
+    /// them goes back in the box.
+    fn snapshot(&self) -> GameState {
+        let mut state = self.game.snapshot(&self.species_names);
+        for bait in self.rods.iter().filter_map(|rod| rod.hook_bait) {
+            state.profile.add_bait(bait, 1);
+        }
+        state
+    }
+
+    fn save(&mut self) {
+        // What the AI fisherman or the second player at this keyboard gets up to isn't
+        // the player's to keep
+        if self.attract.is_some() || (self.shore != self.config.dock.side && self.config.link.is_none()) {
+            return;
+        }
+        if let Some(ref path) = self.config.save_path
+            && let Err(e) = self.snapshot().save(path, &self.species_names)
+        {
+            self.show_banner(format!("Could not save game: {}", e));
+        }
+    }
+
+    fn announce(&mut self, urgency: Urgency, text: impl Into<String>) {
+        if let Some(ref mut a11y) = self.config.a11y {
+            a11y.event(urgency, text);
+        }
+    }
+
+    /// Publish `event` on the event bus. Each subsystem that reacts to it does so
+    /// straight away, while the rod it's about is still the one being played, and then
+    /// it waits for [`App::take_events`].
+    fn publish(&mut self, event: GameEvent) {
+        tracing::debug!("event {:?}", event);
+        self.react(&event);
+        self.keep_records(&event);
+        self.config.hooks.notify(&event);
+        self.announce_event(&event);
+        for plugin in &mut self.config.plugins {
+            plugin.on_event(&event);
+        }
+        self.events.push(event);
+    }
+
+    /// The events published since the last call, oldest first.
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        self.events.take()
+    }
+
+    /// The scene's part in an event: splashes, the signal banner and the fisherman
+    /// cheering or slumping.
+    fn react(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::CastLanded { x, y } if !self.config.reduce_motion => {
+                self.effects.splash(x, y, self.theme().foam);
+            }
+            GameEvent::SignalReceived { success, ref message } => {
+                self.local_signal = Some((success, message.clone()));
+                self.signal_shown_at = Some(self.elapsed);
+                if success {
+                    self.fisherman_anim.celebrate();
+                    if !self.config.reduce_motion {
+                        self.stars.meteor_shower();
+                        let show = self.launch_fireworks();
+                        // The banner goes up once the fireworks have burst, unless the
+                        // game is to quit as soon as it's drawn
+                        if self.config.on_signal != SignalPolicy::Exit {
+                            self.signal_shown_at = Some(self.elapsed + show);
+                        }
+                    }
+                } else {
+                    self.fisherman_anim.slump();
+                    self.shake(IMPACT_SHAKE, 2);
+                    if !self.config.reduce_motion {
+                        self.squall = SQUALL_DURATION;
+                        // The fish scatter before the banner goes up, unless the game
+                        // is to quit as soon as it's drawn
+                        if self.config.on_signal != SignalPolicy::Exit {
+                            self.signal_shown_at = Some(self.elapsed + SQUALL_LEAD);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// How stormy the sea is after a FAILURE, from 0.0 (calm) to 1.0: at full strength
+    /// until the squall's last second, then dying away.
+    fn agitation(&self) -> f32 {
+        self.squall.as_secs_f32().min(1.0)
+    }
+
+    /// Send fireworks up from the sea to burst in the upper half of the sky, returning
+    /// how long until the last one bursts.
+    fn launch_fireworks(&mut self) -> Duration {
+        let (ocean, sky) = (self.layout.ocean, self.layout.sky);
+        let columns = ocean.x + 2..ocean.right().saturating_sub(2);
+        let burst_rows = sky.y + 1..sky.y + sky.height / 2;
+        self.effects.particles.fireworks(columns, ocean.y, burst_rows)
+    }
+
+    /// The record keepers' part in a catch: the species' stock, the journal with its
+    /// records and achievements, the session stats and the tournament score.
+    fn keep_records(&mut self, event: &GameEvent) {
+        let GameEvent::FishCaught { ref fish, rarity, autopilot } = *event else {
+            return;
+        };
+        self.game.population.take(&fish.species_name);
+        let in_storm = self.game.weather.current == Weather::Storm;
+        let record = self.game.profile.journal.is_record(fish);
+        let unlocked = self.game.profile.journal.record(fish, in_storm, &self.species_names);
+        if record {
+            let text = format!("New record! A {:.1} cm {}", fish.size, fish.species_name);
+            self.announce(Urgency::Immediate, text.clone());
+            self.toasts.push_back(Toast::Info(text));
+        }
+        if !autopilot && photo::notable(fish, record) {
+            self.take_photo(fish, record);
+        }
+        for achievement in &unlocked {
+            self.announce(Urgency::Immediate, format!("Achievement unlocked: {}", achievement.title()));
+            self.toasts.push_back(Toast::Achievement(format!("★ {}: {}", achievement.title(), achievement.description())));
+        }
+        if !unlocked.is_empty() {
+            self.save();
+        }
+        self.session_stats.record_catch(fish);
+        if let Some(ref metrics) = self.config.metrics {
+            metrics.catch(&fish.species_name);
+        }
+        if let Some(points) = self.config.tournament.as_mut().and_then(|round| round.score(fish, rarity)) {
+            self.announce(Urgency::Routine, format!("{} points", points));
+        }
+    }
+
+    /// Save a photo card of `fish` in the photo folder, if there is one, saying where.
+    fn take_photo(&mut self, fish: &CaughtFish, record: bool) {
+        let Some(ref dir) = self.config.photo_dir else {
+            return;
+        };
+        let species = self.species_names.iter().position(|name| *name == fish.species_name);
+        let shiny = species.and_then(|i| self.shiny_frames.get(i)).filter(|_| fish.shiny);
+        let sprite = shiny
+            .into_iter()
+            .chain(species.and_then(|i| self.frames.get(i)))
+            .find_map(|(right, left)| [right, left].into_iter().find(|frames| !frames.is_empty()))
+            .map(|frames| SpriteFrame::bake(fish::frame_at(frames, Duration::ZERO)));
+        let (hour, minute) = self.game.clock.hour_minute();
+        let date = season::today_stamp();
+        let mut lines = vec![self.game.weather.current.as_str().to_lowercase()];
+        lines.extend(self.conditions().modifiers().iter().map(conditions::Modifier::describe));
+        let card = PhotoCard {
+            fish,
+            sprite: sprite.as_ref(),
+            taken: format!("{} · {:02}:{:02}", date, hour, minute),
+            conditions: lines,
+            record,
+            theme: self.theme(),
+        };
+        let text = match photo::save(&card, &format!("{} {:02}:{:02}", date, hour, minute), dir) {
+            Ok(path) => format!("Photo saved to {}", path.display()),
+            Err(e) => format!("Could not save photo: {}", e),
+        };
+        self.toasts.push_back(Toast::Info(text));
+    }
+
+    /// The screen reader feed's part in an event.
+    fn announce_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::CastLanded { .. } => self.announce(Urgency::Routine, "Line in the water"),
+            GameEvent::SignalReceived { success, message } => {
+                let outcome = if *success { "SUCCESS" } else { "FAILURE" };
+                self.announce(Urgency::Immediate, format!("{}: {}", outcome, message.replace(['\r', '\n'], " ")));
+            }
+            GameEvent::WeatherChanged(weather) => {
+                self.announce(Urgency::Routine, format!("Weather now {}", weather.as_str()));
+            }
+            GameEvent::RoundOver { points, catches } => {
+                self.announce(Urgency::Immediate, format!("Time's up: {} points from {} fish", points, catches));
+            }
+            GameEvent::Cast | GameEvent::FishCaught { .. } => {}
+        }
+    }
+
+    /// Advance the scene by `dt` of real time. While paused only signals are taken
+    /// in, so paused time never counts towards the session or any timer.
+    pub fn update(&mut self, dt: Duration) {
+        // The game stands still behind the menus
+        if !self.paused && self.screen == Screen::Playing {
+            self.advance(dt);
+        }
+        if let Some(ref mut aquarium) = self.aquarium {
+            aquarium.tick(dt);
+        }
+        if let Some(ref mut encyclopedia) = self.encyclopedia {
+            encyclopedia.tick(dt);
+        }
+        // A hung task hangs whether or not the game is paused
+        if self.watchdog.as_mut().is_some_and(|watchdog| watchdog.tick(dt)) {
+            self.task_went_quiet();
+        }
+        self.take_signals();
+        self.sync_link();
+
+        if let Some(a11y) = self.config.a11y.as_ref()
+            && a11y.take_summary_request()
+        {
+            let summary = self.summary(self.was_night);
+            self.announce(Urgency::Immediate, summary);
+        }
+        if let Some(ref mut a11y) = self.config.a11y {
+            a11y.flush(Instant::now());
+        }
+    }
+
+    /// The watchdog ran out: doze off and warn, or give up on the task.
+    fn task_went_quiet(&mut self) {
+        let window = self.watchdog.as_ref().map(Watchdog::describe_window).unwrap_or_default();
+        tracing::warn!("no signal or heartbeat for {}", window);
+        match self.config.on_timeout {
+            TimeoutPolicy::Warn => {
+                self.fisherman_anim.fall_asleep();
+                self.announce(Urgency::Immediate, format!("The task may be stuck: nothing heard for {}", window));
+            }
+            TimeoutPolicy::Exit => {
+                self.timed_out = true;
+                self.quit = true;
+            }
+        }
+    }
+
+    /// Check for signals from subprocess stdin, pipe, socket, HTTP or signal file.
+    fn take_signals(&mut self) {
+        let messages: Vec<SignalMessage> = match self.config.signals {
+            Some(ref inbox) => inbox.lock().map(|mut inbox| inbox.drain(..).collect()).unwrap_or_default(),
+            None => Vec::new(),
+        };
+        for message in messages {
+            self.signal_history.record(self.elapsed, &message);
+            self.receive(message);
+        }
+    }
+
+    /// Trade news with the other player over the network, if there is one: take in
+    /// their line, catches and (joining) the host's school, and send ours.
+    fn sync_link(&mut self) {
+        let Some(ref link) = self.config.link else {
+            return;
+        };
+        for event in link.take() {
+            self.receive_from_link(event);
+        }
+
+        let width = self.layout.size.width;
+        let surface = self.layout.ocean.y;
+        if let Some(ref mut rival) = self.rival
+            && let Some(line) = rival.remote
+        {
+            rival.fishing_state = line.to_state(width, surface, self.layout.rod_tip(rival.shore));
+        }
+        let Some(ref mut link) = self.config.link else {
+            return;
+        };
+        link.send_line(NetLine::from_state(self.game.fishing_state, width, surface));
+        if link.school_due(self.elapsed) {
+            let species = self.species_names.clone();
+            link.send(&NetMessage::School { width, species, fishes: self.game.fishes.clone() });
+        }
+    }
+
+    fn receive_from_link(&mut self, event: LinkEvent) {
+        match event {
+            LinkEvent::Joined(addr) => {
+                self.show_banner(format!("Fishing with {}", addr));
+                self.announce(Urgency::Immediate, "Your rival is here");
+                if let Some(ref mut link) = self.config.link {
+                    link.resend();
+                }
+            }
+            LinkEvent::Left => {
+                if let Some(ref mut rival) = self.rival {
+                    rival.remote = Some(NetLine::Idle);
+                }
+                self.show_banner("Your rival has gone home");
+                self.announce(Urgency::Immediate, "Your rival left");
+            }
+            LinkEvent::Message(NetMessage::Line(line)) => {
+                if let Some(ref mut rival) = self.rival {
+                    rival.remote = Some(line);
+                }
+            }
+            LinkEvent::Message(NetMessage::Caught(catch)) => {
+                // The fish is theirs: gone from this sea too, even off our own hook
+                if let Some(i) = catch.find(&self.game.fishes, &self.species_names, self.layout.size.width) {
+                    self.game.fishes.remove(i);
+                    self.bites.removed(i);
+                    self.fish_removed(i);
+                }
+                let species = self.species_names.iter().position(|name| *name == catch.species);
+                let sizes = species.and_then(|i| self.traits.get(i)).map_or_else(SizeDistribution::default, |t| t.sizes);
+                let landed = CaughtFish::new(catch.species, catch.size, catch.shiny, &sizes);
+                let text = format!("Your rival landed a {:.1} cm {}", landed.size, landed.species_name);
+                self.show_banner(text.clone());
+                self.announce(Urgency::Routine, text);
+                if let Some(ref mut rival) = self.rival {
+                    rival.stats.record_catch(&landed);
+                    rival.anim.celebrate();
+                }
+            }
+            LinkEvent::Message(NetMessage::School { width, species, fishes }) => {
+                // Not while one of them is on the hook, or it would slip off
+                if !self.hosting() && self.bites.held_fish().is_empty() && self.held_elsewhere().is_empty() {
+                    let lanes = self.layout.lanes;
+                    self.game.fishes =
+                        netplay::adopt_school(fishes, width, &species, &self.species_names, lanes, self.layout.size.width);
+                }
+            }
+        }
+    }
+
+    /// Whether the fish are this game's own: they are unless it joined someone else's.
+    fn hosting(&self) -> bool {
+        self.config.link.as_ref().is_none_or(Link::hosting)
+    }
+
+    fn advance(&mut self, dt: Duration) {
+        self.elapsed += dt;
+        let now = self.elapsed;
+
+        self.expire_messages(now);
+
+        let sim_dt = self.game.clock.advance(dt);
+        if self.game.weather.update(&mut self.rng, sim_dt) {
+            self.publish(GameEvent::WeatherChanged(self.game.weather.current));
+        }
+        let night = self.game.clock.daylight() < 0.5;
+        if night != self.was_night {
+            self.announce(Urgency::Routine, if night { "Night falls" } else { "Day breaks" });
+        }
+        self.was_night = night;
+        self.wind.tick(dt, self.game.weather.current);
+        if self.fog.tick(dt, self.layout.size.width, self.game.weather.current, self.wind.strength()) {
+            let text = if self.fog.is_foggy() { "Fog rolling in" } else { "The fog has lifted" };
+            self.announce(Urgency::Routine, text);
+        }
+        let tide = tide_rows(now);
+        if tide != self.layout.tide {
+            self.turn_tide(tide);
+        }
+
+        self.tasks.tick(dt);
+        self.tick_tournament(dt);
+        self.squall = self.squall.saturating_sub(dt);
+        self.ocean.agitation = self.agitation();
+        if !self.config.reduce_motion {
+            self.stars.update(now);
+            self.seabed.update(now);
+            let sky = SceneContext { area: self.layout.sky, daylight: self.game.clock.daylight(), theme: self.theme() };
+            self.ambient.tick(dt, &sky);
+            let room = self.crab_lane(self.layout).width.saturating_sub(self.crab_sprites.size().0);
+            self.crab.tick(dt, room);
+            self.ocean.advance(dt, self.game.weather.current.intensity());
+        }
+        let ctx = self.plugin_context();
+        for plugin in &mut self.config.plugins {
+            plugin.update(dt, &ctx);
+        }
+        self.effects.tick(dt);
+        self.game.population.replenish(dt);
+
+        let spawning = self.config.spawning;
+        if now - self.last_spawn_check >= spawning.check_interval(SPAWN_CHECK_INTERVAL) && self.hosting() {
+            self.last_spawn_check = now;
+            let lanes = self.layout.lanes;
+            let width = self.layout.size.width as f32;
+            let target = spawning.target(lanes, width);
+            if self.game.fishes.len() < target {
+                let weights = self.spawn_weights();
+                let density = spawning.density * self.conditions().spawn();
+                let mut new_fish = fish::spawn_fishes(
+                    &mut self.rng,
+                    &self.frames,
+                    &self.traits,
+                    width,
+                    lanes,
+                    density,
+                    self.game.hook_bait,
+                    &weights,
+                );
+                if let Some(max) = spawning.max_fish {
+                    new_fish.truncate(max.saturating_sub(self.game.fishes.len()));
+                }
+                self.game.fishes.append(&mut new_fish);
+            }
+            if !self.game.fishes.iter().any(Fish::is_boss)
+                && self.rng.gen_bool(BOSS_CHANCE)
+                && let Some(boss) = fish::spawn_boss(
+                    &mut self.rng,
+                    &self.frames,
+                    &self.traits,
+                    self.layout.size.width as f32,
+                    lanes,
+                )
+            {
+                let name = self.species.get(boss.species).map_or("fish", |s| s.name.as_str());
+                let text = format!("Something huge is down there: a giant {}", name);
+                self.announce(Urgency::Immediate, text);
+                self.game.fishes.push(boss);
+                self.shake(IMPACT_SHAKE, 1);
+            }
+        }
+
+        self.advance_rod(dt, now);
+        self.with_held_lines(|app| app.advance_line(dt, now));
+        self.with_rival(|app| app.advance_rod(dt, now));
+        if let Some(ref mut rival) = self.rival
+            && rival.remote.is_some()
+        {
+            rival.anim.tick(dt);
+        }
+
+        if let Some(after) = self.config.attract_after
+            && self.autopilot.is_none()
+            && now - self.last_input >= after
+        {
+            self.start_attract();
+        }
+        if let Some(mut autopilot) = self.autopilot.take() {
+            if let Some((action, kind)) = autopilot.tick(dt, &self.glance()) {
+                self.act(action, kind);
+            }
+            self.autopilot = Some(autopilot);
+        }
+
+        let swim_dt = if self.config.reduce_motion { dt / REDUCED_MOTION_SLOWDOWN } else { dt };
+        if !self.game.fishes.is_empty() {
+            self.update_fishes(swim_dt, now);
+        }
+        self.jellyfish.tick(swim_dt, self.layout.water);
+        if !self.config.reduce_motion {
+            self.emit_particles(dt, now);
+        }
+        self.check_snag(dt, now);
+        self.with_held_lines(|app| app.check_snag(dt, now));
+        self.with_rival(|app| app.check_snag(dt, now));
+    }
+
+    /// Run the tournament clock, and once time is up bring the line in for good and
+    /// ask for a name to put the round down under.
+    fn tick_tournament(&mut self, dt: Duration) {
+        let Some(ref mut round) = self.config.tournament else {
+            return;
+        };
+        if !round.tick(dt) {
+            return;
+        }
+        let (points, catches, name) = (round.points, round.catches, round.default_name());
+        self.stop_line();
+        self.with_held_lines(App::stop_line);
+        self.publish(GameEvent::RoundOver { points, catches });
+        self.name_entry = NameEntry::new(&name);
+        self.show_screen(Screen::EnterName);
+    }
+
+    /// Put the finished round in the high-score table under the name entered, and
+    /// show where it placed.
+    fn finish_round(&mut self) {
+        if let Some(ref mut round) = self.config.tournament
+            && let Err(e) = round.finish(self.name_entry.name())
+        {
+            self.show_banner(format!("Could not save high scores: {}", e));
+        }
+        self.show_screen(Screen::GameOver);
+    }
+
+    /// Bring the line in play in on the spot, for good, its bait back in the box.
+    fn stop_line(&mut self) {
+        self.cast_charge_start = None;
+        self.cast_animation_start = None;
+        self.bites = BiteTracker::new();
+        self.game.reel_in();
+    }
+
+    /// Move the rod in play along: its fisherman and its line.
+    fn advance_rod(&mut self, dt: Duration, now: Duration) {
+        self.fisherman_anim.tick(dt);
+        self.advance_line(dt, now);
+    }
+
+    /// Move the line in play along: the cast flying out, the line tightening or going
+    /// slack, the hook coming back in and the charge building.
+    fn advance_line(&mut self, dt: Duration, now: Duration) {
+        if let Some(anim_start) = self.cast_animation_start {
+            let anim_elapsed = now - anim_start;
+            if anim_elapsed < CAST_ANIMATION_DURATION {
+                if let FishingState::Casting { start_x, start_y, target_x, progress: _ } = self.game.fishing_state {
+                    self.game.fishing_state = FishingState::Casting {
+                        start_x,
+                        start_y,
+                        target_x,
+                        progress: anim_elapsed.as_secs_f32() / CAST_ANIMATION_DURATION.as_secs_f32(),
+                    };
+                }
+            } else {
+                if let FishingState::Casting { target_x, start_y, .. } = self.game.fishing_state {
+                    self.game.fishing_state = FishingState::Landed {
+                        landing_x: target_x,
+                        landing_y: start_y,
+                        depth: 0,
+                    };
+                    self.publish(GameEvent::CastLanded { x: target_x, y: start_y });
+                }
+                self.cast_animation_start = None;
+            }
+        }
+
+        let pull = match self.bites.phase() {
+            Some(BitePhase::Fighting) => 1.0,
+            Some(BitePhase::Biting) => 0.8,
+            Some(BitePhase::Nibbling) => 0.5,
+            // The line goes slack on a hooked fish unless it's reeled
+            Some(BitePhase::Hooked) | None => RESTING_TENSION,
+        };
+        self.line_tension += (pull - self.line_tension) * (dt.as_secs_f32() * TENSION_RATE).min(1.0);
+        self.bites.tire(dt, self.line_tension, &mut self.game.fishes);
+
+        if let Some(start) = self.retrieve_start {
+            let progress = (now - start).as_secs_f32() / RETRIEVE_DURATION.as_secs_f32();
+            if let FishingState::Retrieving { from_x, from_y, .. } = self.game.fishing_state {
+                self.game.fishing_state = if progress < 1.0 {
+                    FishingState::Retrieving { from_x, from_y, progress }
+                } else {
+                    FishingState::Idle
+                };
+            }
+            if progress >= 1.0 {
+                self.retrieve_start = None;
+                self.retrieved_fish = None;
+            }
+        }
+
+        if let Some(charge_start) = self.cast_charge_start {
+            let power = ((now - charge_start).as_secs_f32() / MAX_CAST_TIME.as_secs_f32()).min(1.0);
+            self.game.fishing_state = FishingState::Charging { power };
+        }
+    }
+
+    /// Run `turn` with the other player's rod in play, if they're playing here rather
+    /// than on another machine. Meanwhile [`App::rival`] holds the rod put down, so the
+    /// turn sees its opponent's too.
+    fn with_rival(&mut self, turn: impl FnOnce(&mut App)) {
+        let Some(mut rod) = self.rival.take_if(|rod| rod.remote.is_none()) else {
+            return;
+        };
+        rod.swap(self);
+        self.rival = Some(rod);
+        turn(self);
+        if let Some(mut rod) = self.rival.take() {
+            rod.swap(self);
+            self.rival = Some(rod);
+        }
+    }
+
+    /// Run `turn` with each line left in a rod holder in play in turn. Meanwhile the
+    /// line in hand waits in that line's place in [`App::rods`].
+    fn with_held_lines(&mut self, mut turn: impl FnMut(&mut App)) {
+        for i in 0..self.rods.len() {
+            if i == self.rod_in_hand {
+                continue;
+            }
+            let mut line = std::mem::replace(&mut self.rods[i], HeldLine::new());
+            line.swap(self);
+            self.rods[i] = line;
+            self.held_turn = Some(i);
+            turn(self);
+            self.held_turn = None;
+            let mut line = std::mem::replace(&mut self.rods[i], HeldLine::new());
+            line.swap(self);
+            self.rods[i] = line;
+        }
+    }
+
+    /// Lay the rod in hand down in its holder and take up rod `rod` (from 0) instead.
+    /// A cast still charging is let go of rather than thrown.
+    fn take_up_rod(&mut self, rod: usize) {
+        if rod == self.rod_in_hand {
+            return;
+        }
+        if rod >= self.rods.len() {
+            let text = if self.rods.len() == 1 {
+                "No rod holders yet: the tackle shop sells them".to_string()
+            } else {
+                format!("Only {} rods on the dock", self.rods.len())
+            };
+            self.announce(Urgency::Immediate, text.clone());
+            self.show_banner(text);
+            return;
+        }
+        if matches!(self.game.fishing_state, FishingState::Charging { .. }) {
+            self.cast_charge_start = None;
+            self.game.fishing_state = FishingState::Idle;
+        }
+        let mut line = std::mem::replace(&mut self.rods[rod], HeldLine::new());
+        line.swap(self);
+        self.rods[self.rod_in_hand] = line;
+        self.rod_in_hand = rod;
+        self.announce(Urgency::Immediate, format!("Rod {} in hand", rod + 1));
+    }
+
+    /// Whether the dock lantern hangs lit: it's been bought and not put out in the settings.
+    fn lantern_lit(&self) -> bool {
+        self.game.profile.lantern && self.config.lantern
+    }
+
+    /// One rod per rod holder owned besides the one in hand, keeping the lines out.
+    fn fit_rod_holders(&mut self) {
+        let rods = 1 + self.game.profile.rod_holders.min(MAX_ROD_HOLDERS);
+        if rods > self.rods.len() {
+            self.rods.resize_with(rods, HeldLine::new);
+        }
+    }
+
+    /// What to call out when a fish bites the line in play: which rod it's on and the
+    /// key to take it up, if that's left in a holder.
+    fn bite_call(&self) -> String {
+        let Some(rod) = self.held_turn else {
+            return "Bite! Press space".to_string();
+        };
+        let key = Action::ALL
+            .into_iter()
+            .find(|action| action.rod() == Some(rod))
+            .and_then(|action| self.config.keys.keys(action).next())
+            .map_or_else(|| (rod + 1).to_string(), keymap::key_name);
+        format!("Bite on rod {}! Press {}", rod + 1, key)
+    }
+
+    /// The fish on every line but the one in play, which its hook can't take.
+    fn held_elsewhere(&self) -> Vec<usize> {
+        let mut held: Vec<usize> = self.rods.iter().flat_map(|rod| rod.bites.held_fish()).collect();
+        if let Some(ref rival) = self.rival {
+            held.extend(rival.bites.held_fish());
+        }
+        held
+    }
+
+    /// A fish at `index` left the school, landed on the line in play or on another
+    /// machine: the other lines forget it.
+    fn fish_removed(&mut self, index: usize) {
+        for rod in &mut self.rods {
+            rod.bites.removed(index);
+        }
+        if let Some(ref mut rival) = self.rival {
+            rival.bites.removed(index);
+        }
+    }
+
+    /// The layout for a screen of `size` with the sea `tide` rows up, with room for the
+    /// task fishermen and the second player.
+    fn lay_out(&self, size: Rect, tide: i16) -> Layout {
+        let layout = Layout::with_tide(size, tide, self.tasks.len() as u16, self.config.dock);
+        if self.rival.is_some() { layout.with_far_shore() } else { layout }
+    }
+
+    /// Hand the rod to the AI fisherman while the player is away. The line comes in
+    /// first so the bait on it goes back in the box the player gets back.
+    fn start_attract(&mut self) {
+        self.take_line_in();
+        self.attract = Some((self.game.profile.clone(), self.session_stats.clone()));
+        self.autopilot = Some(Autopilot::new(self.rng.r#gen()));
+        self.show_banner("Demo: press any key to play");
+        self.announce(Urgency::Routine, "Demo started");
+    }
+
+    /// Give the rod back to the player as it was when they left: the AI fisherman's
+    /// line comes in and whatever it caught or spent is forgotten. Also run before
+    /// saving on exit.
+    pub fn end_attract(&mut self) {
+        let Some((profile, stats)) = self.attract.take() else {
+            return;
+        };
+        self.autopilot = None;
+        self.take_line_in();
+        self.game.hook_bait = None;
+        self.game.profile = profile;
+        self.session_stats = stats;
+        self.banner = None;
+        self.announce(Urgency::Routine, "Demo ended");
+    }
+
+    /// Bring the line in from wherever it is, bait and all.
+    fn take_line_in(&mut self) {
+        self.bites.spook(&mut self.game.fishes);
+        self.cast_charge_start = None;
+        self.cast_animation_start = None;
+        match self.game.fishing_state {
+            FishingState::Landed { landing_x, landing_y, depth } => {
+                self.game.reel_in();
+                self.retrieve(landing_x, landing_y.saturating_add(depth), None);
+            }
+            FishingState::Retrieving { .. } => {}
+            _ => self.game.reel_in(),
+        }
+    }
+
+    /// What the autopilot sees: the line, a bite, and where each fish in the water is.
+    fn glance(&self) -> Glance {
+        let fish = self.game.fishes.iter()
+            .filter(|f| self.elapsed.as_millis() >= f.spawn_delay_ms as u128)
+            .map(|f| {
+                let (top, width, height) = hitbox(&self.species, &self.frames, f, self.layout.fish);
+                (f.x + width as f32 / 2.0, top + height / 2)
+            })
+            .filter(|&(x, _)| x >= 0.0 && x < self.layout.size.width as f32)
+            .collect();
+        Glance {
+            state: self.game.fishing_state,
+            biting: self.bites.phase() == Some(BitePhase::Biting),
+            hooked: self.bites.phase() == Some(BitePhase::Hooked),
+            fish,
+            rod_tip_x: self.rod_tip_x(),
+            reach: self.cast_reach(),
+            full_charge: MAX_CAST_TIME,
+            reel_step: self.game.profile.rod().reel_step,
+        }
+    }
+
+    /// Bubbles from the fish and the sea floor, and rain striking the sea.
+    fn emit_particles(&mut self, dt: Duration, now: Duration) {
+        let theme = self.theme();
+        let particles = &mut self.effects.particles;
+        let fish_area = self.layout.fish;
+        for fish in &self.game.fishes {
+            if now.as_millis() < fish.spawn_delay_ms as u128 || particles.occurrences(BUBBLES_PER_SECOND, dt) == 0 {
+                continue;
+            }
+            let (top, width, _height) = hitbox(&self.species, &self.frames, fish, fish_area);
+            let mouth = if fish.facing_right { fish.x as i32 + width as i32 } else { fish.x as i32 - 1 };
+            if let Ok(x) = u16::try_from(mouth) {
+                particles.bubble(x, top, theme.foam);
+            }
+        }
+
+        let floor = self.seabed.floor();
+        let rise = floor.saturating_sub(self.layout.ocean.bottom());
+        for &x in self.seabed.vents() {
+            if particles.occurrences(VENT_COLUMNS_PER_SECOND, dt) > 0 {
+                particles.bubble_column(x, floor.saturating_sub(1), rise, theme.foam);
+            }
+        }
+
+        let rain = self.game.weather.current.intensity();
+        if rain > 0.0 {
+            let width = self.layout.size.width;
+            let per_second = rain * width as f32 * RAIN_SPLASHES_PER_COLUMN;
+            particles.rain(0..width, self.layout.ocean.y, per_second, dt, theme.rain);
+        }
+    }
+
+    /// Clear messages that have been up long enough and apply the `--on-signal` policy
+    /// to a signal that has been drawn.
+    fn expire_messages(&mut self, now: Duration) {
+        if let Some((_, shown_at)) = self.banner
+            && now - shown_at >= INFO_BANNER_DURATION
+        {
+            self.banner = None;
+        }
+        if !self.toasts.is_empty() {
+            let shown_at = *self.toast_shown_at.get_or_insert(now);
+            if now - shown_at >= TOAST_DURATION {
+                self.toasts.pop_front();
+                self.toast_shown_at = None;
+            }
+        }
+        if let Some((_, _, shown_at)) = self.caught
+            && now - shown_at > CATCH_MESSAGE_DURATION
+        {
+            self.caught = None;
+        }
+        if let Some(shown_at) = self.signal_shown_at
+            && now >= shown_at
+        {
+            let shown_for = now - shown_at;
+            match self.config.on_signal {
+                SignalPolicy::Exit => self.quit = true,
+                SignalPolicy::ExitAfter(delay) if shown_for >= delay => self.quit = true,
+                SignalPolicy::KeepPlaying if shown_for >= SIGNAL_BANNER_DURATION => {
+                    self.local_signal = None;
+                    self.signal_shown_at = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Act on one message from an external signal source.
+    pub fn receive(&mut self, message: SignalMessage) {
+        tracing::info!("signal {}", message.to_line());
+        if let Some(ref metrics) = self.config.metrics {
+            metrics.signal(message.status());
+        }
+        let now = self.elapsed;
+        if self.watchdog.as_mut().is_some_and(Watchdog::heard) {
+            self.fisherman_anim.wake();
+            self.announce(Urgency::Routine, "The task is back".to_string());
+        }
+        match message {
+            SignalMessage::Progress { percent, label } => {
+                self.announce(Urgency::Routine, format!("Progress {}% {}", percent, label));
+                self.progress = Some((percent, label));
+            }
+            SignalMessage::Spawn(name) => match self.species_named(&name) {
+                Some(species) => self.spawn(species),
+                None => self.show_banner(format!("No fish called '{}'", name)),
+            },
+            SignalMessage::Banner(text) => {
+                self.announce(Urgency::Immediate, format!("Note: {}", text));
+                self.banner = Some((text, now));
+            }
+            SignalMessage::Info(text) => {
+                self.announce(Urgency::Routine, format!("Info: {}", text));
+                self.toasts.push_back(Toast::Info(text));
+            }
+            SignalMessage::Heartbeat => {}
+            SignalMessage::Task { name, message } => self.receive_task(name, *message),
+            outcome => {
+                if let Some((success, msg)) = outcome.outcome() {
+                    // Only a game that keeps playing waits on anything after the outcome
+                    if self.config.on_signal != SignalPolicy::KeepPlaying {
+                        self.watchdog = None;
+                    }
+                    self.last_outcome = Some(success);
+                    self.publish(GameEvent::SignalReceived { success, message: msg.to_string() });
+                }
+            }
+        }
+    }
+
+    /// Act on a message about one of the named tasks. It moves only that task's
+    /// fisherman, and whichever task finishes last ends the wait for all of them.
+    fn receive_task(&mut self, name: String, message: SignalMessage) {
+        let known = self.tasks.len();
+        let finished = self.tasks.outcome().is_some();
+        self.tasks.update(&name, &message);
+        if self.tasks.len() != known {
+            self.layout = self.lay_out(self.layout.size, self.layout.tide);
+        }
+        match message {
+            SignalMessage::Progress { percent, label } => {
+                self.announce(Urgency::Routine, format!("{}: progress {}% {}", name, percent, label));
+            }
+            SignalMessage::Success(msg) | SignalMessage::Failure(msg) | SignalMessage::Banner(msg) => {
+                self.receive(SignalMessage::Banner(format!("{}: {}", name, msg)));
+                if !finished && let Some((success, summary)) = self.tasks.outcome() {
+                    self.receive(if success { SignalMessage::Success(summary) } else { SignalMessage::Failure(summary) });
+                }
+            }
+            SignalMessage::Info(msg) => self.receive(SignalMessage::Info(format!("{}: {}", name, msg))),
+            other => self.receive(other),
+        }
+    }
+
+    fn update_fishes(&mut self, dt: Duration, elapsed: Duration) {
+        // Fish notice a hook when it hangs in the rows their lane's sprites are drawn in
+        let fish_area = self.layout.fish;
+        let mut hooks: Vec<_> = hook_in_lane(self.game.fishing_state, self.game.hook_bait, fish_area).into_iter().collect();
+        let mut held = self.bites.held_fish();
+        held.extend(self.held_elsewhere());
+        for rod in &self.rods {
+            hooks.extend(hook_in_lane(rod.fishing_state, rod.hook_bait, fish_area));
+        }
+        if let Some(ref rival) = self.rival {
+            hooks.extend(hook_in_lane(rival.fishing_state, rival.hook_bait, fish_area));
+        }
+        let agitation = self.agitation();
+        fish::steer_toward_hook(&mut self.game.fishes, &self.frames, &hooks, &held, dt, elapsed, agitation);
+        // In a rough sea fish bolt off the edge rather than turn
+        if self.config.smooth_motion && agitation <= 0.0 {
+            fish::turn_at_edges(&mut self.game.fishes, &self.frames, &held, elapsed, self.layout.size.width as f32);
+        }
+        fish::update_fishes(&mut self.game.fishes, dt, elapsed, self.layout.size.width as f32, agitation);
+        // A guest's fish come and go with the host's school
+        if self.hosting() {
+            let width = self.layout.size.width as f32;
+            let lanes = self.layout.lanes;
+            for i in fish::lifecycle(&mut self.rng, &mut self.game.fishes, &self.frames, &held, dt, elapsed, width, lanes) {
+                self.bites.removed(i);
+                self.fish_removed(i);
+            }
+        }
+        self.check_bites(dt, elapsed);
+        self.with_held_lines(|app| app.check_bites(dt, elapsed));
+        self.with_rival(|app| app.check_bites(dt, elapsed));
+    }
+
+    /// Move along the bite on the line in play, and let a fish at its hook take the bait.
+    /// A fish already at another hook can't.
+    fn check_bites(&mut self, dt: Duration, elapsed: Duration) {
+        let fish_area = self.layout.fish;
+        let was_biting = self.bites.phase() == Some(BitePhase::Biting);
+        let got_away = self.bites.tick(dt, &mut self.game.fishes);
+        if !was_biting && self.bites.phase() == Some(BitePhase::Biting) {
+            let call = self.bite_call();
+            if self.held_turn.is_some() {
+                self.show_banner(call.clone());
+            }
+            self.announce(Urgency::Immediate, call);
+        } else if got_away {
+            self.announce(Urgency::Immediate, "It got away");
+        }
+
+        // A fish with the hook drags it along, pulling the line taut as it runs out to sea
+        if let FishingState::Landed { landing_x, landing_y, depth } = self.game.fishing_state
+            && let Some(x) = self.bites.drag(landing_x, self.landing_range(), &mut self.game.fishes)
+        {
+            let rod_tip_x = self.rod_tip_x();
+            if x.abs_diff(rod_tip_x) > landing_x.abs_diff(rod_tip_x) {
+                self.line_tension = 1.0;
+            }
+            self.game.fishing_state = FishingState::Landed { landing_x: x, landing_y, depth };
+        }
+
+        if let FishingState::Landed { landing_x, landing_y, depth } = self.game.fishing_state
+            && self.bites.can_start()
+        {
+            let hook_x = landing_x;
+            let hook_y = landing_y.saturating_add(depth);
+
+            // A fish that swims into the hook starts nibbling if the bait tempts it
+            let taken = self.held_elsewhere();
+            let bite_odds = self.conditions().bite();
+            let mut nibbling = false;
+            for (i, fish) in self.game.fishes.iter_mut().enumerate() {
+                if elapsed.as_millis() < fish.spawn_delay_ms as u128 || !fish.hook_shy.is_zero() || taken.contains(&i) {
+                    continue;
+                }
+
+                let (fish_y, width, height) = hitbox(&self.species, &self.frames, fish, fish_area);
+                if fishing_game::check_collision(hook_x, hook_y, fish.x, fish_y, width, height) {
+                    let rarity = self.traits.get(fish.species).map_or(0.0, |t| t.rarity);
+                    let chance = (fishing_game::bite_chance(self.game.hook_bait, rarity) * bite_odds).min(1.0);
+                    if self.bites.start(&mut self.rng, i, fish, chance) {
+                        nibbling = true;
+                        break;
+                    }
+                }
+            }
+            if nibbling {
+                self.announce(Urgency::Routine, "Something is nibbling");
+            }
+        }
+    }
+
+    /// A hook resting with nothing on it occasionally snags junk or treasure.
+    fn check_snag(&mut self, dt: Duration, now: Duration) {
+        if let FishingState::Landed { landing_y, depth, .. } = self.game.fishing_state
+            && self.bites.phase().is_none()
+            && self.caught.is_none()
+        {
+            let ocean_area = self.layout.ocean;
+            let water_depth = self.layout.size.height.saturating_sub(ocean_area.y).max(1);
+            let hook_depth = landing_y.saturating_add(depth).saturating_sub(ocean_area.y);
+            if self.config.items.snags(&mut self.rng, hook_depth as f32 / water_depth as f32, dt)
+                && let Some(item) = self.config.items.roll(&mut self.rng)
+            {
+                let catch = Catch::Item(item);
+                self.session_stats.snags += 1;
+                let coins = economy::coins_for(&catch, 0.0);
+                self.game.profile.coins += coins;
+                self.announce(Urgency::Immediate, catch.describe());
+                self.caught = Some((catch, coins, now));
+                self.game.hook_bait = None;
+                self.game.fishing_state = FishingState::Idle;
+            }
+        }
+    }
+
+    /// The row above the dock planks the crab walks along, short of the sea end.
+    fn crab_lane(&self, layout: Layout) -> Rect {
+        let height = self.crab_sprites.size().1;
+        let dock = layout.dock;
+        let x = if layout.anchor.shore == Shore::Right { dock.x + CRAB_LANE_OFFSET } else { dock.x };
+        Rect::new(x, dock.y.saturating_sub(height), dock.width.saturating_sub(CRAB_LANE_OFFSET), height)
+    }
+
+    /// The moon's phase, running on with the scene's days from a start that varies
+    /// from session to session.
+    fn moon_phase(&self) -> f32 {
+        moon::phase(self.game.clock.days() + self.moon_days)
+    }
+
+    /// What the weather, the hour, the bait and the moon are doing to the fishing.
+    fn conditions(&self) -> Conditions {
+        Conditions::new(self.game.weather.current, self.game.clock.time_of_day(), self.game.hook_bait, self.moon_phase())
+    }
+
+    /// One-sentence description of the scene for the accessibility channel.
+    fn summary(&self, night: bool) -> String {
+        let (hour, minute) = self.game.clock.hour_minute();
+        let line = match self.game.fishing_state {
+            FishingState::Idle => "line reeled in".to_string(),
+            FishingState::Charging { power } => format!("charging cast {:.0}%", power * 100.0),
+            FishingState::Casting { .. } => "casting".to_string(),
+            FishingState::Landed { depth, .. } => format!("line at depth {}", depth),
+            FishingState::Retrieving { .. } => "reeling in".to_string(),
+        };
+        let swimming = self.game.fishes.iter()
+            .filter(|f| self.elapsed.as_millis() >= f.spawn_delay_ms as u128)
+            .count();
+        let mut summary = format!(
+            "{:02}:{:02} {}, {}, {}. {}, {} fish.",
+            hour,
+            minute,
+            if night { format!("night, {}", moon::phase_name(self.moon_phase())) } else { "day".to_string() },
+            self.game.weather.current.as_str(),
+            self.wind.describe(),
+            line,
+            swimming
+        );
+        if let Some((percent, ref label)) = self.progress {
+            summary.push_str(&format!(" Progress {}% {}.", percent, label));
+        }
+        match self.local_signal {
+            Some((true, ref msg)) => summary.push_str(&format!(" SUCCESS: {}", msg)),
+            Some((false, ref msg)) => summary.push_str(&format!(" FAILURE: {}", msg)),
+            None if self.config.signals.is_some() => summary.push_str(" Waiting for signal."),
+            None => {}
+        }
+        summary
+    }
+
+    pub fn handle_event(&mut self, event: Event) {
+        if matches!(event, Event::Key(_) | Event::Mouse(_)) {
+            self.last_input = self.elapsed;
+            // The first key or mouse movement back only takes the rod back, bar Ctrl+C
+            if self.attract.is_some() {
+                self.end_attract();
+                if !matches!(event, Event::Key(key) if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)) {
+                    return;
+                }
+            }
+        }
+        match event {
+            Event::Resize(width, height) if (width, height) != (self.layout.size.width, self.layout.size.height) => {
+                self.layout = self.lay_out(Rect::new(0, 0, width, height), self.layout.tide);
+                self.stars.resize(self.layout.sky, STAR_DENSITY);
+                self.seabed.resize(self.layout.seabed);
+            }
+            Event::Key(key) if self.screen != Screen::Playing && key.kind == KeyEventKind::Press => self.handle_menu_key(key),
+            Event::Key(_) | Event::Mouse(_) if self.screen != Screen::Playing => {}
+            Event::Key(key) if self.console_open => {
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.quit = true;
+                    return;
+                }
+                if key.kind != KeyEventKind::Press {
+                    return;
+                }
+                match self.console.key(key.code, &self.species_names) {
+                    ConsoleKey::Run(line) => self.run_command(&line),
+                    ConsoleKey::Close => self.console_open = false,
+                    ConsoleKey::Typing => {}
+                }
+            }
+            Event::Key(key) if self.shop.is_some() => {
+                if key.kind == KeyEventKind::Press
+                    && let Some(ref mut shop) = self.shop
+                {
+                    match shop.handle_key(key.code, &mut self.game.profile) {
+                        ShopAction::Bought => {
+                            self.fit_rod_holders();
+                            self.save();
+                        }
+                        ShopAction::Close => self.shop = None,
+                        ShopAction::None => {}
+                    }
+                }
+            }
+            Event::Key(key) => self.handle_key(key),
+            Event::Mouse(mouse) if self.shop.is_none() => self.handle_mouse(mouse),
+            _ => {}
+        }
+    }
+
+    /// A key pressed on one of the screens around the game.
+    fn handle_menu_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.quit = true;
+            return;
+        }
+        if self.screen == Screen::EnterName {
+            if self.name_entry.key(key.code) {
+                self.finish_round();
+            }
+            return;
+        }
+        let action = self.config.keys.action(key.code);
+        let lines = match self.screen {
+            Screen::Title => TitleChoice::offered(self.can_continue).len(),
+            Screen::Settings => SettingsField::ALL.len(),
+            _ => 0,
+        };
+        let lengths = self.high_scores.lengths().len().max(1);
+        match (key.code, action) {
+            (_, Some(Action::Quit)) => self.quit = true,
+            (KeyCode::Up, _) | (_, Some(Action::ReelUp)) if lines > 0 => {
+                self.menu_selected = (self.menu_selected + lines - 1) % lines;
+            }
+            (KeyCode::Down, _) | (_, Some(Action::ReelDown)) if lines > 0 => {
+                self.menu_selected = (self.menu_selected + 1) % lines;
+            }
+            (KeyCode::Left, _) if self.screen == Screen::Settings => self.change_setting(-1),
+            (KeyCode::Right, _) if self.screen == Screen::Settings => self.change_setting(1),
+            (KeyCode::Left, _) if self.screen == Screen::Leaderboard => {
+                self.menu_selected = (self.menu_selected + lengths - 1) % lengths;
+            }
+            (KeyCode::Right, _) if self.screen == Screen::Leaderboard => {
+                self.menu_selected = (self.menu_selected + 1) % lengths;
+            }
+            (KeyCode::Esc, _) | (_, Some(Action::Journal)) if self.screen != Screen::Title => self.go_back(),
+            (KeyCode::Enter, _) | (_, Some(Action::Cast)) => match self.screen {
+                Screen::Title => self.choose(TitleChoice::offered(self.can_continue)[self.menu_selected]),
+                Screen::Settings => self.change_setting(1),
+                Screen::Journal | Screen::Leaderboard => self.go_back(),
+                Screen::GameOver => {
+                    self.back_to = if self.config.menu { Screen::Title } else { Screen::GameOver };
+                    self.show_leaderboard();
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn show_screen(&mut self, screen: Screen) {
+        tracing::info!("screen {:?}", screen);
+        self.screen = screen;
+        self.menu_selected = 0;
+    }
+
+    /// The leaderboard, read afresh from the table beside the saved game, on the
+    /// length of any round just played.
+    fn show_leaderboard(&mut self) {
+        let scores = match (&self.config.save_path, &self.config.tournament) {
+            (Some(path), _) => HighScores::load(&Tournament::scores_path_beside(path)),
+            (None, Some(round)) => Ok(round.scores.clone()),
+            (None, None) => Ok(HighScores::default()),
+        };
+        self.high_scores = scores.unwrap_or_else(|e| {
+            self.show_banner(format!("Could not read high scores: {}", e));
+            HighScores::default()
+        });
+        self.show_screen(Screen::Leaderboard);
+        if let Some(ref round) = self.config.tournament {
+            let length = round.length().as_secs();
+            self.menu_selected = self.high_scores.lengths().iter().position(|&l| l == length).unwrap_or(0);
+        }
+    }
+
+    /// Leave the journal or the settings for wherever they were opened from.
+    fn go_back(&mut self) {
+        self.show_screen(self.back_to);
+    }
+
+    fn choose(&mut self, choice: TitleChoice) {
+        match choice {
+            TitleChoice::Continue => self.show_screen(Screen::Playing),
+            TitleChoice::NewGame => {
+                self.new_game();
+                self.show_screen(Screen::Playing);
+            }
+            TitleChoice::Journal | TitleChoice::Settings => {
+                self.back_to = Screen::Title;
+                let screen = if choice == TitleChoice::Journal { Screen::Journal } else { Screen::Settings };
+                self.show_screen(screen);
+            }
+            TitleChoice::HighScores => {
+                self.back_to = Screen::Title;
+                self.show_leaderboard();
+            }
+            TitleChoice::Quit => self.quit = true,
+        }
+    }
+
+    /// Start over with a fresh sea, an empty wallet and the line reeled in.
+    fn new_game(&mut self) {
+        let width = self.layout.size.width as f32;
+        self.game = GameState::new(&mut self.rng, &self.frames, &self.traits, width, self.layout.lanes, self.config.spawning, self.config.time_scale);
+        self.bites = BiteTracker::new();
+        self.rods = vec![HeldLine::new()];
+        self.rod_in_hand = 0;
+        self.can_continue = false;
+    }
+
+    /// The settings as they stand.
+    fn settings(&self) -> Settings {
+        Settings {
+            reduce_motion: self.config.reduce_motion,
+            status_bar: self.config.status_bar,
+            lantern: self.config.lantern,
+            braille: self.config.braille,
+            constellations: self.config.constellations,
+        }
+    }
+
+    /// The name of the built-in theme in use, or `custom`.
+    fn theme_name(&self) -> &'static str {
+        Theme::BUILT_IN.iter().find(|(_, theme)| *theme == self.config.theme).map_or("custom", |(name, _)| name)
+    }
+
+    /// Change the selected setting, `step` along for the theme, and write it to the
+    /// config file.
+    fn change_setting(&mut self, step: isize) {
+        let field = SettingsField::ALL[self.menu_selected];
+        let value = match field {
+            SettingsField::Theme => {
+                let count = Theme::BUILT_IN.len() as isize;
+                let at = Theme::BUILT_IN.iter().position(|(name, _)| *name == self.theme_name());
+                let next = match at {
+                    Some(at) => (at as isize + step).rem_euclid(count) as usize,
+                    None => 0,
+                };
+                let (name, theme) = Theme::BUILT_IN[next];
+                self.set_theme(theme);
+                Value::String(name.to_string())
+            }
+            SettingsField::ReduceMotion => {
+                self.config.reduce_motion = !self.config.reduce_motion;
+                self.fisherman_anim = FishermanAnimation::new().with_fidget(!self.config.reduce_motion);
+                Value::Bool(self.config.reduce_motion)
+            }
+            SettingsField::StatusBar => {
+                self.config.status_bar = !self.config.status_bar;
+                Value::Bool(self.config.status_bar)
+            }
+            SettingsField::Lantern => {
+                self.config.lantern = !self.config.lantern;
+                Value::Bool(self.config.lantern)
+            }
+            SettingsField::Braille => {
+                self.config.braille = !self.config.braille;
+                Value::Bool(self.config.braille)
+            }
+            SettingsField::Constellations => {
+                self.config.constellations = !self.config.constellations;
+                self.stars.set_constellations(self.config.constellations.then_some(self.seed));
+                Value::Bool(self.config.constellations)
+            }
+            SettingsField::Back => return self.go_back(),
+        };
+        if let (Some(path), Some((section, key))) = (&self.config.config_path, field.key())
+            && let Err(e) = config_file::write_value(path, section, key, &value)
+        {
+            self.show_banner(format!("Could not save settings: {}", e));
+        }
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.config.theme = theme;
+        let theme = self.theme();
+        self.stars.set_theme(theme);
+        self.seabed = Seabed::new(self.seed, self.layout.seabed).with_theme(theme);
+    }
+
+    fn species_named(&self, name: &str) -> Option<usize> {
+        self.species.iter().position(|sp| sp.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Add a fish of `species` to the scene.
+    fn spawn(&mut self, species: usize) {
+        self.game.fishes.push(fish::spawn_species(
+            &mut self.rng,
+            &self.frames,
+            &self.traits,
+            species,
+            self.layout.size.width as f32,
+            self.layout.lanes,
+        ));
+        let text = format!("A {} appears", self.species[species].name);
+        self.announce(Urgency::Routine, text);
+    }
+
+    /// Carry out a line typed into the console, saying there what came of it.
+    fn run_command(&mut self, line: &str) {
+        let said = match line.parse::<Command>() {
+            Ok(Command::Spawn { species, count }) => match self.species_named(&species) {
+                Some(index) => {
+                    (0..count).for_each(|_| self.spawn(index));
+                    format!("{} × {}", count, self.species[index].name)
+                }
+                None => format!("No fish called '{}'", species),
+            },
+            Ok(Command::Weather(weather)) => {
+                self.game.weather.set(&mut self.rng, weather);
+                self.publish(GameEvent::WeatherChanged(weather));
+                format!("Weather: {}", weather.as_str())
+            }
+            Ok(Command::Signal(message)) => {
+                let line = message.to_line();
+                self.receive(message);
+                format!("Received {}", line)
+            }
+            Ok(Command::Set(Setting::Density(density))) => {
+                self.config.spawning.density = density;
+                format!("Density {}", density)
+            }
+            Ok(Command::Set(Setting::MaxFish(max))) => {
+                self.config.spawning.max_fish = Some(max);
+                format!("At most {} fish", max)
+            }
+            Ok(Command::Set(Setting::Coins(coins))) => {
+                self.game.profile.coins = coins;
+                format!("{} coins", coins)
+            }
+            Ok(Command::Pack(name)) => {
+                let said = format!("Loading pack {}", name);
+                self.pack_request = Some(name);
+                said
+            }
+            Ok(Command::Help) => console::HELP.to_string(),
+            Err(e) => e,
+        };
+        self.console.say(said);
+    }
+
+    /// The colours the scene is drawn in: the theme, in the season's colours if it's
+    /// the default one.
+    fn theme(&self) -> Theme {
+        seasonal_theme(&self.config, &self.seasonal)
+    }
+
+    /// The scene as the plugins see it.
+    fn plugin_context(&self) -> PluginContext {
+        let layout = self.layout;
+        PluginContext {
+            screen: layout.size,
+            sky: layout.sky,
+            ocean: layout.ocean,
+            daylight: self.game.clock.daylight(),
+            theme: self.theme(),
+            still: self.config.reduce_motion,
+        }
+    }
+
+    /// How much each species counts for when picking a new fish's: by how many are
+    /// left, and not at all out of season, unless none of them are in season.
+    fn spawn_weights(&self) -> Vec<f32> {
+        let mut weights = self.game.population.spawn_weights(&self.species_names);
+        let in_season: Vec<bool> = self.traits.iter().map(|t| self.seasonal.in_season(t.seasons)).collect();
+        if in_season.contains(&true) {
+            for (weight, _) in weights.iter_mut().zip(&in_season).filter(|(_, in_season)| !**in_season) {
+                *weight = 0.0;
+            }
+        }
+        // The plugins' say, unless between them they'd rule out every species
+        let weighed: Vec<f32> = weights.iter()
+            .zip(&self.species_names)
+            .map(|(weight, name)| weight * self.config.plugins.iter().map(|plugin| plugin.spawn_weight(name)).product::<f32>())
+            .collect();
+        if weighed.iter().any(|weight| *weight > 0.0) { weighed } else { weights }
+    }
+
+    /// Click the water to cast there, scroll to move the hook and click the fisherman
+    /// for the stats panel.
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let at = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if self.layout.fisherman_at(self.shore).contains(at) => {
+                self.show_stats = !self.show_stats;
+            }
+            _ if self.paused => {}
+            MouseEventKind::Down(MouseButton::Left)
+                if mouse.row >= self.layout.ocean.y
+                    && matches!(self.game.fishing_state, FishingState::Idle) =>
+            {
+                self.cast_toward(mouse.column);
+            }
+            MouseEventKind::ScrollDown => self.lower_hook(),
+            MouseEventKind::ScrollUp => self.raise_hook(),
+            _ => {}
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        // Raw mode turns Ctrl+C into a key press instead of SIGINT
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.quit = true;
+            return;
+        }
+        let action = self.config.keys.action(key.code);
+        // Letting go of a key only matters to a charging cast
+        if key.kind == KeyEventKind::Release && !matches!(action, Some(Action::Cast | Action::P2Cast)) {
+            return;
+        }
+        let Some(action) = action else {
+            self.handle_test_key(key.code);
+            return;
+        };
+        self.act(action, key.kind);
+    }
+
+    /// Carry out `action` for a key pressed, held or let go, whether the player's or
+    /// the autopilot's.
+    fn act(&mut self, action: Action, kind: KeyEventKind) {
+        let now = self.elapsed;
+        let press = kind == KeyEventKind::Press;
+        match action {
+            Action::Quit => self.quit = true,
+            Action::Shop if press => self.shop = Some(Shop::new()),
+            Action::Journal if press => {
+                self.back_to = Screen::Playing;
+                self.show_screen(Screen::Journal);
+            }
+            Action::Aquarium if press => self.toggle_aquarium(),
+            Action::Encyclopedia if press => {
+                self.encyclopedia = if self.encyclopedia.is_some() { None } else { Some(EncyclopediaState::default()) };
+            }
+            Action::Stats if press => self.show_stats = !self.show_stats,
+            Action::Help if press => self.show_help = !self.show_help,
+            Action::History if press => {
+                self.history_scroll = if self.history_scroll.is_some() { None } else { Some(0) };
+            }
+            // The reel keys leaf through the encyclopedia while it's open
+            Action::ReelUp | Action::ReelDown if self.encyclopedia.is_some() => {
+                let step = if action == Action::ReelUp { -1 } else { 1 };
+                let count = self.species_names.len();
+                if let Some(ref mut encyclopedia) = self.encyclopedia {
+                    encyclopedia.select(step, count);
+                }
+            }
+            // The reel keys scroll the history while it's open
+            Action::ReelUp if self.history_scroll.is_some() => {
+                self.history_scroll = self.history_scroll.map(|scroll| scroll.saturating_sub(1));
+            }
+            Action::ReelDown if self.history_scroll.is_some() => {
+                let last = self.signal_history.len().saturating_sub(1);
+                self.history_scroll = self.history_scroll.map(|scroll| (scroll + 1).min(last));
+            }
+            Action::Underwater if press => {
+                self.underwater = !self.underwater;
+                self.announce(Urgency::Routine, if self.underwater { "Underwater view" } else { "Back on the dock" });
+            }
+            Action::Screenshot if press => self.screenshot = true,
+            Action::Console if press => self.console_open = true,
+            Action::Pause if press => {
+                self.paused = !self.paused;
+                self.announce(Urgency::Immediate, if self.paused { "Paused" } else { "Resumed" });
+            }
+            // Nothing on the water moves while paused, nor once the tournament is over
+            Action::Cast | Action::ReelUp | Action::ReelDown | Action::Bait
+                if self.paused || self.config.tournament.as_ref().is_some_and(Tournament::is_over) => {}
+            // Cycle through the baits in stock before casting
+            Action::Bait if press && matches!(self.game.fishing_state, FishingState::Idle) => {
+                let text = match self.game.profile.cycle_bait() {
+                    Some(bait) => format!("Bait: {} ({} left)", bait.as_str(), self.game.profile.bait_count(bait)),
+                    None => "Bait: bare hook".to_string(),
+                };
+                self.announce(Urgency::Immediate, text.clone());
+                self.banner = Some((text, now));
+            }
+            Action::Cast => match (kind, self.game.fishing_state) {
+                (KeyEventKind::Press, FishingState::Idle) => self.cast_charge_start = Some(now),
+                // Set the hook: lands a biting fish, spooks a nibbling one
+                (KeyEventKind::Press, FishingState::Landed { .. }) => self.set_hook(),
+                (KeyEventKind::Release, FishingState::Charging { power }) => self.cast(power),
+                // Without release events, pressing the key again casts instead
+                (KeyEventKind::Press, FishingState::Charging { power }) if !self.config.key_release => self.cast(power),
+                _ => {}
+            },
+            Action::ReelDown => self.lower_hook(),
+            Action::ReelUp => self.raise_hook(),
+            Action::Rod1 | Action::Rod2 | Action::Rod3 if press => {
+                self.take_up_rod(action.rod().unwrap_or(0));
+            }
+            // The second player's keys work their rod the same way, if they're playing
+            Action::P2Cast | Action::P2ReelUp | Action::P2ReelDown => {
+                self.with_rival(|app| app.act(action.first_player(), kind));
+            }
+            _ => {}
+        }
+    }
+
+    /// Open the aquarium, stocked afresh from the journal, or close it.
+    fn toggle_aquarium(&mut self) {
+        self.aquarium = match self.aquarium {
+            Some(_) => None,
+            None => Some(AquariumState::new(
+                &mut self.rng,
+                &self.game.profile.journal,
+                &self.species_names,
+                &self.frames,
+                &self.traits,
+                self.layout.size.width,
+            )),
+        };
+    }
+
+    /// Keys that fake a signal, for trying the banners and hooks out without a signal
+    /// source.
+    fn handle_test_key(&mut self, code: KeyCode) {
+        match code {
+            // Test signal: SUCCESS (works when not using external signals)
+            KeyCode::Char('s') if self.config.signals.is_none() => {
+                self.publish(GameEvent::SignalReceived { success: true, message: "Success! Task completed.".to_string() });
+            }
+            // Test signal: FAILURE (works when not using external signals)
+            KeyCode::Char('f') if self.config.signals.is_none() => {
+                self.publish(GameEvent::SignalReceived { success: false, message: "Failed! Please try again.".to_string() });
+            }
+            _ => {}
+        }
+    }
+
+    /// Let go of a charged cast: the hook flies out from the rod tip, further the more
+    /// `power` was built up.
+    fn cast(&mut self, power: f32) {
+        self.cast_to(self.out_to_sea(fishing_line::cast_distance(self.cast_reach(), power)));
+    }
+
+    /// Columns out from the rod tip the rod in play casts at full power.
+    fn cast_reach(&self) -> u16 {
+        fishing_line::cast_reach(self.layout.size.width, self.game.profile.rod().cast_range)
+    }
+
+    /// Where on the water a cast charging in play would land if let go of now, wind and
+    /// all.
+    fn cast_preview(&self) -> Option<(u16, u16)> {
+        let FishingState::Charging { power } = self.game.fishing_state else {
+            return None;
+        };
+        let target_x = self.out_to_sea(fishing_line::cast_distance(self.cast_reach(), power));
+        Some((self.landing_for(target_x), self.layout.ocean.y))
+    }
+
+    /// The column `distance` columns out to sea from the rod tip.
+    fn out_to_sea(&self, distance: u16) -> u16 {
+        self.layout.anchor(self.shore).seaward(dock::ROD_REACH + distance)
+    }
+
+    fn rod_tip_x(&self) -> u16 {
+        self.layout.rod_tip(self.shore)
+    }
+
+    /// The leftmost and rightmost columns a line cast from the rod in play can land in:
+    /// anywhere out to sea from the end of a short cast.
+    fn landing_range(&self) -> (u16, u16) {
+        let nearest = self.layout.nearest_landing(self.shore);
+        match self.shore {
+            Shore::Right => (0, nearest),
+            Shore::Left => {
+                let last = self.layout.size.width.saturating_sub(1);
+                (nearest.min(last), last)
+            }
+        }
+    }
+
+    /// Cast at the spot on the water that was clicked, as near as the rod reaches.
+    fn cast_toward(&mut self, x: u16) {
+        let distance = self.rod_tip_x().abs_diff(x).clamp(MIN_CAST_DISTANCE, self.cast_reach().max(MIN_CAST_DISTANCE));
+        self.cast_to(self.out_to_sea(distance));
+    }
+
+    /// Where a cast aimed at `target_x` lands, once the wind has carried the line.
+    fn landing_for(&self, target_x: u16) -> u16 {
+        let drift = self.wind.drift(self.rod_tip_x().abs_diff(target_x));
+        let (low, high) = self.landing_range();
+        (target_x as i32 + drift).clamp(low as i32, high as i32) as u16
+    }
+
+    /// Cast aiming for `target_x`, landing wherever the wind carries the line.
+    fn cast_to(&mut self, target_x: u16) {
+        self.game.fishing_state = FishingState::Casting {
+            start_x: self.rod_tip_x(),
+            start_y: self.layout.ocean.y,
+            target_x: self.landing_for(target_x),
+            progress: 0.0,
+        };
+        self.cast_animation_start = Some(self.elapsed);
+        self.cast_charge_start = None;
+        self.publish(GameEvent::Cast);
+        self.game.hook_bait = self.game.profile.take_bait();
+        self.session_stats.casts += 1;
+    }
+
+    fn lower_hook(&mut self) {
+        if let FishingState::Landed { landing_x, landing_y, depth } = self.game.fishing_state {
+            self.bites.spook(&mut self.game.fishes);
+            let max_depth = self.layout.size.height.saturating_sub(landing_y);
+            self.game.fishing_state = FishingState::Landed {
+                landing_x,
+                landing_y,
+                depth: depth.saturating_add(1).min(max_depth),
+            };
+            self.check_sting();
+        }
+    }
+
+    /// Reel the hook up, bringing the line in once it reaches the surface. With a fish
+    /// hooked the reel pulls the line taut instead, and brings the fish in once it tires.
+    fn raise_hook(&mut self) {
+        if self.bites.phase() == Some(BitePhase::Hooked) {
+            match self.bites.reel_in() {
+                Some(i) => self.land(i),
+                None => self.line_tension = 1.0,
+            }
+            return;
+        }
+        if let FishingState::Landed { landing_x, landing_y, depth } = self.game.fishing_state {
+            self.bites.spook(&mut self.game.fishes);
+            if depth == 0 {
+                self.game.reel_in();
+                self.retrieve(landing_x, landing_y, None);
+            } else {
+                self.game.fishing_state = FishingState::Landed {
+                    landing_x,
+                    landing_y,
+                    depth: depth.saturating_sub(self.game.profile.rod().reel_step),
+                };
+                self.line_tension = 1.0;
+                self.check_sting();
+            }
+        }
+    }
+
+    /// Lay the scene out again for the sea `tide` rows up, keeping a line in the water
+    /// afloat on the new surface.
+    fn turn_tide(&mut self, tide: i16) {
+        let rise = tide - self.layout.tide;
+        self.layout = self.lay_out(self.layout.size, tide);
+        self.float_line(rise);
+        self.with_held_lines(|app| app.float_line(rise));
+        self.with_rival(|app| app.float_line(rise));
+    }
+
+    /// Keep the line in play afloat on a sea risen `rise` rows.
+    fn float_line(&mut self, rise: i16) {
+        let shift = |y: u16| (y as i16 - rise).max(0) as u16;
+        self.game.fishing_state = match self.game.fishing_state {
+            FishingState::Casting { start_x, start_y, target_x, progress } => {
+                FishingState::Casting { start_x, start_y: shift(start_y), target_x, progress }
+            }
+            FishingState::Landed { landing_x, landing_y, depth } => {
+                FishingState::Landed { landing_x, landing_y: shift(landing_y), depth }
+            }
+            FishingState::Retrieving { from_x, from_y, progress } => {
+                FishingState::Retrieving { from_x, from_y: shift(from_y), progress }
+            }
+            state => state,
+        };
+    }
+
+    /// Bring the hook back up the line from `(from_x, from_y)`, with a landed fish on it
+    /// if there is one.
+    fn retrieve(&mut self, from_x: u16, from_y: u16, fish: Option<Fish>) {
+        self.game.fishing_state = FishingState::Retrieving { from_x, from_y, progress: 0.0 };
+        self.retrieve_start = Some(self.elapsed);
+        self.retrieved_fish = fish;
+    }
+
+    /// A hook reeled into a jellyfish gets zapped: the line snaps and the cast, bait
+    /// included, is lost.
+    fn check_sting(&mut self) {
+        let FishingState::Landed { landing_x, landing_y, depth } = self.game.fishing_state else {
+            return;
+        };
+        let hook_y = landing_y.saturating_add(depth);
+        if !self.jellyfish.touches(self.layout.water, landing_x, hook_y) {
+            return;
+        }
+        if !self.config.reduce_motion {
+            self.effects.particles.zap(landing_x, hook_y, self.theme().lightning);
+        }
+        self.game.fishing_state = FishingState::Idle;
+        self.game.hook_bait = None;
+        self.show_banner("Zap! A jellyfish snapped the line");
+        self.announce(Urgency::Immediate, "A jellyfish snapped the line");
+    }
+
+    fn set_hook(&mut self) {
+        let (i, fighting, hooked) = match self.bites.set_hook(&mut self.game.fishes, &self.traits) {
+            None => return,
+            Some(HookSet::Landed(i)) => (i, false, false),
+            Some(HookSet::Fighting(i)) => (i, true, false),
+            Some(HookSet::Hooked(i)) => (i, false, true),
+        };
+        let fish = &self.game.fishes[i];
+        let sizes = self.traits.get(fish.species).map_or_else(SizeDistribution::default, |t| t.sizes);
+        if sizes.category(fish.size) == SizeCategory::Massive {
+            self.shake(IMPACT_SHAKE, 1);
+        }
+        if !self.game.profile.line().holds(&mut self.rng, self.game.fishes[i].size) {
+            // Too heavy for the line: the fish swims off with the bait
+            if fighting || hooked {
+                self.bites.spook(&mut self.game.fishes);
+            }
+            self.show_banner(format!(
+                "The line snapped! Get a stronger line than {}",
+                self.game.profile.line().name
+            ));
+            self.announce(Urgency::Immediate, "The line snapped");
+        } else if fighting {
+            self.shake(BOSS_SHAKE, 2);
+            let left = self.game.fishes[i].boss_fights;
+            self.announce(Urgency::Immediate, format!("It's fighting! Set the hook {} more times", left));
+            return;
+        } else if hooked {
+            self.announce(Urgency::Immediate, "Hooked! Keep the line taut to tire it");
+            return;
+        } else {
+            self.land(i);
+            return;
+        }
+        self.game.fishing_state = FishingState::Idle;
+        self.game.hook_bait = None;
+    }
+
+    /// Take the fish at `i` off the hook: it's caught, paid for and rides the hook in.
+    fn land(&mut self, i: usize) {
+        let fish = self.game.fishes.remove(i);
+        self.fish_removed(i);
+        if let Some(ref link) = self.config.link {
+            let name = self.species_names.get(fish.species).cloned().unwrap_or_default();
+            link.send(&NetMessage::Caught(NetCatch::new(&fish, name, self.layout.size.width)));
+        }
+        if !self.config.reduce_motion
+            && let FishingState::Landed { landing_x, landing_y, .. } = self.game.fishing_state
+        {
+            self.effects.ripple(landing_x, landing_y, CATCH_RIPPLE_RADIUS);
+            self.effects.particles.confetti(landing_x, landing_y.saturating_sub(1));
+            let crab_x = self.crab_lane(self.layout).x + self.crab.x() as u16;
+            if landing_x.abs_diff(crab_x) <= CRAB_SPOOK_DISTANCE {
+                self.crab.hide();
+            }
+        }
+        let species_name = match self.species.get(fish.species) {
+            Some(species) => species.name.clone(),
+            None => "Unknown Fish".to_string(),
+        };
+        if fish.is_boss() {
+            self.shake(BOSS_SHAKE * 2, 2);
+            self.show_banner(format!("BOSS LANDED! A {:.1} cm giant {}", fish.size, species_name));
+        }
+
+        let traits = self.traits.get(fish.species).copied().unwrap_or_default();
+        let landed = CaughtFish::new(species_name, fish.size, fish.shiny, &traits.sizes);
+        let rarity = traits.rarity;
+        self.publish(GameEvent::FishCaught { fish: landed.clone(), rarity, autopilot: self.autopilot.is_some() });
+        let catch = Catch::Fish(landed);
+        let coins = economy::coins_for(&catch, rarity);
+        self.game.profile.coins += coins;
+        self.announce(Urgency::Immediate, format!("{}, {} coins", catch.describe(), coins));
+        self.caught = Some((catch, coins, self.elapsed));
+        if let FishingState::Landed { landing_x, landing_y, depth } = self.game.fishing_state {
+            self.game.hook_bait = None;
+            self.retrieve(landing_x, landing_y.saturating_add(depth), Some(fish));
+            return;
+        }
+        self.game.fishing_state = FishingState::Idle;
+        self.game.hook_bait = None;
+    }
+
+    /// Draw one player's line, with a landed fish riding it in, `!` over a bite and how
+    /// the fight is going beside it while a fish is hooked.
+    fn draw_line(&self, buf: &mut Buffer, size: Rect, line: LineView) {
+        let elapsed = self.elapsed;
+        let still = self.config.reduce_motion;
+        FishingLine::new(line.rod_tip.0, line.rod_tip.1)
+            .with_theme(self.theme())
+            .with_state(line.state)
+            .with_twitch(!still && line.bites.twitching(elapsed))
+            .with_tension(line.tension)
+            .with_wind_skew(if still { 0.0 } else { self.wind.skew() })
+            .with_braille(self.config.braille)
+            .with_preview(line.preview)
+            .render(size, buf);
+
+        if let FishingState::Retrieving { from_x, from_y, progress } = line.state
+            && let Some(fish) = line.retrieved_fish
+            && let Some(frame) = fish::sprite(fish, &self.baked[fish.build as usize], &self.baked_shiny[fish.build as usize], elapsed)
+        {
+            // The catch hangs from the hook by its mouth on the way in
+            let (hook_x, hook_y) = fishing_line::retrieving_hook(line.rod_tip, (from_x, from_y), progress);
+            let width = frame.width * fish.scale();
+            let x = if fish.facing_right { hook_x.saturating_sub(width) } else { hook_x.saturating_add(1) };
+            frame.blit(buf, (x as i32, hook_y as i32 + 1), fish.scale(), size, &[]);
+        }
+        if !still
+            && line.bites.phase() == Some(BitePhase::Biting)
+            && let FishingState::Landed { landing_x, landing_y, .. } = line.state
+            && landing_y > 0
+        {
+            buf.set_string(landing_x, landing_y - 1, "!", Style::default().fg(Color::Yellow));
+        }
+    }
+
+    /// Draw the fight gauge beside a line with a fish hooked on it, over the rest of the
+    /// scene so the effects never hide it.
+    fn draw_fight_gauge(&self, buf: &mut Buffer, size: Rect, rod_tip: (u16, u16), state: FishingState, bites: &BiteTracker, tension: f32) {
+        if let Some(stamina) = bites.stamina()
+            && let FishingState::Landed { landing_x, landing_y, .. } = state
+        {
+            // Beside the line where it meets the water, on the side away from the rod,
+            // and kept inside the frame when the line lands near its edge
+            let x = if rod_tip.0 > landing_x { landing_x.saturating_sub(GAUGE_WIDTH + 2) } else { landing_x + 2 };
+            let x = x.min(size.right().saturating_sub(GAUGE_WIDTH + 1)).max(size.x + 1);
+            let area = Rect::new(x, landing_y.saturating_sub(2), GAUGE_WIDTH, 2).intersection(size);
+            FightGauge { stamina, tension, taut: TAUT_TENSION }.render(area, buf);
+        }
+    }
+
+    /// Draw the rods left in holders on the planks behind the fisherman, with their
+    /// lines out, each numbered for the key that takes it up or marked `!` while a fish
+    /// bites at it.
+    fn draw_rod_holders(&self, buf: &mut Buffer, layout: &Layout) {
+        let size = layout.size;
+        let theme = self.theme();
+        let (lean, step) = match self.shore {
+            Shore::Right => ("\\", 1),
+            Shore::Left => ("/", -1),
+        };
+        for (i, rod) in self.rods.iter().enumerate() {
+            if i == self.rod_in_hand {
+                continue;
+            }
+            // The first rod goes in the holder of whichever was taken up
+            let slot = if i == 0 { self.rod_in_hand } else { i };
+            let (x, y) = layout.anchor(self.shore).holder_tip(slot);
+            for row in 1..=2 {
+                let column = x as i32 + step * row;
+                if (0..size.width as i32).contains(&column) && y + (row as u16) < size.height {
+                    buf.set_string(column as u16, y + row as u16, lean, Style::default().fg(theme.rod));
+                }
+            }
+            if rod.fishing_state != FishingState::Idle {
+                let line = LineView {
+                    rod_tip: (x, y),
+                    state: rod.fishing_state,
+                    bites: &rod.bites,
+                    tension: rod.line_tension,
+                    retrieved_fish: rod.retrieved_fish.as_ref(),
+                    preview: None,
+                };
+                self.draw_line(buf, size, line);
+            }
+            if y > 0 && x < size.width {
+                let (label, color) = match rod.bites.phase() {
+                    Some(BitePhase::Biting) => ("!".to_string(), Color::Yellow),
+                    _ => ((i + 1).to_string(), Color::DarkGray),
+                };
+                buf.set_string(x, y - 1, label, Style::default().fg(color));
+            }
+        }
+    }
+
+    pub fn draw(&mut self, f: &mut Frame) {
+        // The frame can briefly differ from the size the last resize event gave
+        let layout = if f.area() == self.layout.size { self.layout } else { self.lay_out(f.area(), self.layout.tide) };
+        if !layout.fits() {
+            f.render_widget(TooSmall, f.area());
+            return;
+        }
+        let daylight = self.game.clock.daylight();
+        let night = daylight < 0.5;
+        let mut rival_preview = None;
+        self.with_rival(|app| rival_preview = app.cast_preview());
+
+        let theme = self.theme();
+        let moon_size = self.moon.as_ref().map_or((0, 0), |m| (m.width() as u16, m.height() as u16));
+        let key = BackdropKey {
+            layout,
+            sky: SkyBackdrop { daylight, theme }.color(),
+            night,
+            theme,
+            moon: moon::position(layout.sky, moon_size, self.game.clock.time_of_day()),
+            moon_phase: (self.moon_phase() * MOON_PHASE_STEPS).round() as u8 % MOON_PHASE_STEPS as u8,
+        };
+        if self.backdrop.as_ref().is_none_or(|b| b.key != key) {
+            self.backdrop = Some(Backdrop::new(key, daylight, self.moon.as_ref()));
+        }
+        // A task's progress hangs the line in the water while the rod is free
+        let line_state = match (self.game.fishing_state, &self.progress) {
+            (FishingState::Idle, Some((percent, _))) => progress_hook(&layout, self.shore, *percent),
+            (state, _) => state,
+        };
+        let drawing = Drawing {
+            layout,
+            elapsed: self.elapsed,
+            daylight,
+            night,
+            theme,
+            covered: self.scene_covered(),
+            line_state,
+            preview: self.cast_preview(),
+            rival_preview,
+        };
+        let ctx = self.plugin_context();
+        let buf = f.buffer_mut();
+        for layer in Layer::ALL {
+            self.draw_layer(layer, buf, &drawing);
+            for plugin in self.config.plugins.iter().filter(|plugin| plugin.layer() == layer) {
+                plugin.render(buf, &ctx);
+            }
+        }
+        color::degrade(buf, self.config.color_depth);
+        if self.config.ascii {
+            symbols::asciify(buf);
+        }
+    }
+
+    /// Draw the parts of the scene on `layer`, over those on the layers behind it.
+    fn draw_layer(&mut self, layer: Layer, buf: &mut Buffer, drawing: &Drawing) {
+        let Some(ref backdrop) = self.backdrop else {
+            return;
+        };
+        let Drawing { layout, elapsed, daylight, night, theme, covered, line_state, .. } = *drawing;
+        let size = layout.size;
+        let game = &self.game;
+        let ocean_area = layout.ocean;
+        let sky_area = layout.sky;
+        let anchor = layout.anchor;
+        let dock_area = layout.dock;
+        let dock_y = ocean_area.y.saturating_sub(2);
+        let (rod_tip_x, rod_tip_y) = layout.anchor(self.shore).rod_tip();
+        match layer {
+            Layer::Sky => {
+                buf.merge(&backdrop.buffer);
+                // Stars twinkle behind the moon
+                if night {
+                    self.stars.clone().render(sky_area, buf);
+                    backdrop.restore(&backdrop.moon, buf);
+                    if self.moon.is_some() && !covered {
+                        self.sprites.draw(SpriteId::Moon, backdrop.key.moon, buf);
+                    }
+                } else if self.stars.shower_running() {
+                    self.stars.render_shooting_stars(sky_area, buf);
+                }
+            }
+            Layer::Ocean => StatefulWidget::render(Ocean { theme }, ocean_area, buf, &mut self.ocean),
+            Layer::Ambient => StatefulWidget::render(AmbientActors, sky_area, buf, &mut self.ambient),
+            Layer::Weather => {
+                // Rain falls in front of the moon but behind the dock
+                let weather_area = Rect::new(0, 0, size.width, ocean_area.bottom().min(size.height));
+                WeatherOverlay { weather: game.weather.current, elapsed, theme, still: self.config.reduce_motion }.render(weather_area, buf);
+                if let Some(ref drift) = self.seasonal.drift {
+                    DriftOverlay { drift, sky: sky_area, water: ocean_area, elapsed, still: self.config.reduce_motion }.render(weather_area, buf);
+                }
+                let wind_style = Style::default().fg(theme.cloud);
+                WindIndicator { strength: self.wind.strength(), style: wind_style }.render(Rect::new(2, 1, 10, 1).intersection(size), buf);
+                // What the conditions are doing to the fishing, beside the windsock and
+                // behind the moon
+                let conditions_area = Rect::new(12, 1, size.width.saturating_sub(13), 1).intersection(size);
+                ConditionsPanel { conditions: &self.conditions(), style: wind_style }.render(conditions_area, buf);
+                let moon: Vec<Position> = backdrop.moon.iter().copied().filter(|p| conditions_area.contains(*p)).collect();
+                backdrop.restore(&moon, buf);
+            }
+            Layer::Underwater => {
+                (&self.seabed).render(layout.seabed, buf);
+                // By day the fish overhead shade the floor, the biggest of their kind longest
+                if drawing.daylight > SHADOW_DAYLIGHT {
+                    for fish in &game.fishes {
+                        let sizes = self.traits.get(fish.species).map_or_else(SizeDistribution::default, |t| t.sizes);
+                        if let Some((x, length)) = fish::shadow(fish, layout.fish, &self.frames, &sizes, elapsed, self.config.smooth_motion) {
+                            self.seabed.cast_shadow(buf, x, length);
+                        }
+                    }
+                }
+                StatefulWidget::render(JellyfishLayer { theme }, layout.water, buf, &mut self.jellyfish);
+            }
+            Layer::Actors => {
+                // Fish in full view, clear of the docks, may be drawn as pictures instead
+                let mut pictured = Vec::new();
+                for (i, fish) in game.fishes.iter().enumerate() {
+                    let Some(rect) = fish::whole_sprite_rect(fish, layout.fish, &self.frames, &self.shiny_frames, elapsed, self.config.smooth_motion) else {
+                        continue;
+                    };
+                    let id = SpriteId::Fish { species: fish.species, facing_right: fish.facing_right, shiny: fish.shiny };
+                    if !covered && !rect.intersects(dock_area) && !rect.intersects(layout.far_dock) && !rect.intersects(layout.shoreline) && self.sprites.draw(id, rect, buf) {
+                        pictured.push(i);
+                    }
+                }
+                let fishes = game.fishes.iter().enumerate().filter(|(i, _)| !pictured.contains(i)).map(|(_, fish)| fish);
+                // Keep sprites off the planks and pilings, which reach into the water on short terminals
+                let holes = [dock_area, layout.far_dock, layout.shoreline];
+                for op in fish::compute_fish_render_ops(fishes, layout.fish, &self.baked, &self.baked_shiny, elapsed, self.config.smooth_motion) {
+                    op.blit(buf, &holes);
+                }
+            }
+            Layer::Line => {
+                let line = LineView {
+                    rod_tip: (rod_tip_x, rod_tip_y),
+                    state: line_state,
+                    bites: &self.bites,
+                    tension: self.line_tension,
+                    retrieved_fish: self.retrieved_fish.as_ref(),
+                    preview: drawing.preview,
+                };
+                self.draw_line(buf, size, line);
+                if let Some(ref rival) = self.rival {
+                    let line = LineView {
+                        rod_tip: layout.anchor(rival.shore).rod_tip(),
+                        state: rival.fishing_state,
+                        bites: &rival.bites,
+                        tension: rival.line_tension,
+                        retrieved_fish: rival.retrieved_fish.as_ref(),
+                        preview: drawing.rival_preview,
+                    };
+                    self.draw_line(buf, size, line);
+                }
+                // The label goes over the line, which can cross it on its way out
+                if let Some((percent, ref label)) = self.progress {
+                    let progress_text = if label.is_empty() {
+                        format!("{}%", percent)
+                    } else {
+                        format!("{}% {}", percent, label)
+                    };
+                    // Out to sea of the fisherman, ending or starting a column clear of them
+                    let length = progress_text.chars().count() as u16;
+                    let progress_x = match anchor.shore {
+                        Shore::Right => anchor.seaward(length + 1),
+                        Shore::Left => anchor.seaward(2),
+                    };
+                    let progress_y = anchor.top.saturating_sub(1);
+                    if progress_y < size.height {
+                        buf.set_string(progress_x, progress_y, progress_text, Style::default().fg(Color::Cyan));
+                    }
+                }
+            }
+            Layer::Dock => {
+                backdrop.restore(&backdrop.dock, buf);
+                self.draw_rod_holders(buf, &layout);
+                Shoreline { surface: ocean_area.y, theme }.render(layout.shoreline, buf);
+                DockLife { crab: &self.crab, sprites: &self.crab_sprites }.render(self.crab_lane(layout), buf);
+                // In front of the crab, which would hide a decoration at the end of the dock
+                if let Some(decoration) = self.seasonal.holiday.as_ref().and_then(|holiday| holiday.decoration.as_ref()) {
+                    let view = DecorationView { decoration, sky: sky_area, dock: dock_area, side: anchor.shore, night, elapsed, still: self.config.reduce_motion };
+                    view.render(size, buf);
+                }
+            }
+            Layer::Fisherman => {
+                draw_fisherman(buf, &layout, self.shore, &self.skin, self.fisherman_anim.pose(), theme);
+                if let Some(ref rival) = self.rival {
+                    draw_fisherman(buf, &layout, rival.shore, &self.skin, rival.anim.pose(), theme);
+                }
+                TaskAnglers { tasks: self.tasks.tasks(), skin: &self.skin, theme }.render(layout.anglers, buf);
+                if self.local_signal.is_some() {
+                    let (exclaim_x, exclaim_y) = anchor.exclamation();
+                    if exclaim_y < size.height {
+                        buf.set_string(exclaim_x, exclaim_y, "!", Style::default().fg(Color::Yellow));
+                    }
+                }
+            }
+            Layer::Effects => {
+                StatefulWidget::render(EffectLayer { theme, braille: self.config.braille }, size, buf, &mut self.effects);
+                // Fog over the surface and everything under it, thinning towards the fisherman
+                let fogged = Rect::new(size.x, ocean_area.y, size.width, layout.water.bottom().saturating_sub(ocean_area.y));
+                FogLayer { fog: &self.fog, near: rod_tip_x, color: theme.cloud }.render(fogged, buf);
+
+                let lantern = self.lantern_lit() && night;
+                let lantern_x = anchor.shoreward(1);
+                NightWater {
+                    darkness: 1.0 - daylight,
+                    lantern_x: lantern.then_some(lantern_x),
+                    theme,
+                }
+                .render(layout.water, buf);
+                if lantern {
+                    buf.set_string(
+                        lantern_x,
+                        dock_y.saturating_sub(1),
+                        "¤",
+                        Style::default().fg(theme.lantern),
+                    );
+                    // Its flame warms everything around it, steadily with reduced motion
+                    let flame = if self.config.reduce_motion { 1.0 } else { lighting::flicker(elapsed) };
+                    let glow = Glow { x: lantern_x, y: dock_y.saturating_sub(1), color: theme.lantern, strength: (1.0 - daylight) * flame };
+                    glow.render(size, buf);
+                }
+
+                Gloom { amount: SQUALL_GLOOM * self.agitation() }.render(size, buf);
+                effects::shift(buf, size, self.effects.shake_offset());
+            }
+            Layer::Ui => self.draw_ui(buf, drawing),
+        }
+    }
+
+    /// The panels, banners, menus and overlays over the scene.
+    fn draw_ui(&mut self, buf: &mut Buffer, drawing: &Drawing) {
+        let Drawing { layout, elapsed, theme, line_state, .. } = *drawing;
+        let size = layout.size;
+        let game = &self.game;
+        let ocean_area = layout.ocean;
+        self.draw_fight_gauge(buf, size, layout.anchor(self.shore).rod_tip(), line_state, &self.bites, self.line_tension);
+        if let Some(ref rival) = self.rival {
+            self.draw_fight_gauge(buf, size, layout.anchor(rival.shore).rod_tip(), rival.fishing_state, &rival.bites, rival.line_tension);
+        }
+        if self.underwater {
+            let mut hooks = Vec::new();
+            if let FishingState::Landed { landing_x, landing_y, depth } = line_state {
+                let biting = self.bites.phase() == Some(BitePhase::Biting);
+                hooks.push(SunkHook { x: landing_x, y: landing_y + depth, biting });
+            }
+            for rod in &self.rods {
+                if let FishingState::Landed { landing_x, landing_y, depth } = rod.fishing_state {
+                    let biting = rod.bites.phase() == Some(BitePhase::Biting);
+                    hooks.push(SunkHook { x: landing_x, y: landing_y + depth, biting });
+                }
+            }
+            if let Some(ref rival) = self.rival
+                && let FishingState::Landed { landing_x, landing_y, depth } = rival.fishing_state
+            {
+                let biting = rival.bites.phase() == Some(BitePhase::Biting);
+                hooks.push(SunkHook { x: landing_x, y: landing_y + depth, biting });
+            }
+            // Inside the frame and above the status bar
+            let bottom = if self.config.status_bar && size.height > 2 { size.height - 2 } else { size.height.saturating_sub(1) };
+            let view = Rect::new(1, 1, size.width.saturating_sub(2), bottom.saturating_sub(1));
+            let section = CrossSection {
+                fishes: &game.fishes,
+                frames: &self.frames,
+                shiny_frames: &self.shiny_frames,
+                fish_area: layout.fish,
+                surface_y: ocean_area.y,
+                hooks: &hooks,
+                seabed: &self.seabed,
+                ocean: self.ocean,
+                elapsed,
+                theme,
+            };
+            section.render(view, buf);
+        }
+
+        if self.config.status_bar && size.height > 2 {
+            let status_area = Rect::new(1, size.height - 2, size.width.saturating_sub(2), 1);
+            let status = StatusBar {
+                state: game.fishing_state,
+                biting: self.bites.phase() == Some(BitePhase::Biting),
+                paused: self.paused,
+                catches: self.session_stats.catches,
+                session_time: elapsed,
+                sources: &self.config.sources,
+                style: Style::default().fg(theme.cloud),
+            };
+            Clear.render(status_area, buf);
+            status.render(status_area, buf);
+        }
+
+        if let Some(ref tail) = self.config.tail {
+            let lines = tail.lines();
+            // Below the windsock and clear of the dock, as tall as the sky allows
+            let (x, width) = match layout.anchor.shore {
+                Shore::Right => (1, layout.dock.x.saturating_sub(1)),
+                Shore::Left => (layout.dock.right() + 1, size.width.saturating_sub(layout.dock.right() + 2)),
+            };
+            let height = (lines.len().max(1) as u16 + 2).min(ocean_area.y.saturating_sub(2));
+            let panel = TailPanel { title: &tail.name(), lines: &lines, style: Style::default().fg(theme.cloud) };
+            panel.render(Rect::new(x, 2, width, height).intersection(size), buf);
+        }
+
+        if let Some((ref caught, coins, _)) = self.caught {
+            // Show caught fish message
+            let message = format!("{}\n+{} coins", caught.format_catch(), coins);
+
+            // Center the message box, growing it for item sprites
+            let msg_width = message.lines().map(|l| l.chars().count() as u16 + 4).max().unwrap_or(0).max(40).min(size.width);
+            let msg_height = (message.lines().count() as u16 + 4).max(6).min(size.height);
+            let catch_par = Paragraph::new(Text::from(message))
+                .block(Block::default().title(caught.title()).borders(Borders::ALL))
+                .style(Style::default().fg(Color::Green));
+            let msg_x = size.width.saturating_sub(msg_width) / 2;
+            let msg_y = size.height.saturating_sub(msg_height) / 2;
+            let msg_area = Rect::new(msg_x, msg_y, msg_width, msg_height);
+            Clear.render(msg_area, buf);
+            catch_par.render(msg_area, buf);
+        } else {
+            let title = if self.config.show_clock {
+                let (hour, minute) = game.clock.hour_minute();
+                format!(
+                    "Fisherman — Day {} {:02}:{:02} · {}",
+                    game.clock.days_elapsed() + 1,
+                    hour,
+                    minute,
+                    game.weather.current.as_str()
+                )
+            } else {
+                "Fisherman".to_string()
+            };
+            let bait = match game.profile.selected_bait {
+                Some(bait) => format!("{} ×{}", bait.as_str(), game.profile.bait_count(bait)),
+                None => "bare hook".to_string(),
+            };
+            let mut title = format!("{} · {} coins · {}", title, game.profile.coins, bait);
+            if self.autopilot.is_some() {
+                title.push_str(" · demo");
+            }
+            let mut block = Block::default().title(title).borders(Borders::ALL);
+            // Reduced motion shows the bite here instead of twitching the line and flashing `!`
+            if self.config.reduce_motion {
+                let cast_key = self.config.keys.keys(Action::Cast).next().map(keymap::key_name).unwrap_or_default();
+                let status = match self.bites.phase() {
+                    Some(BitePhase::Nibbling) => Some("Something is nibbling".to_string()),
+                    Some(BitePhase::Biting) => Some(format!("Bite! Press {}", cast_key)),
+                    Some(BitePhase::Fighting) => Some("It's fighting!".to_string()),
+                    Some(BitePhase::Hooked) if self.bites.stamina() == Some(0.0) => Some("It's tired! Reel it in".to_string()),
+                    Some(BitePhase::Hooked) => Some("Hooked! Reel to tire it".to_string()),
+                    None => None,
+                };
+                if let Some(status) = status {
+                    block = block.title_bottom(Line::from(format!(" {} ", status)).centered());
+                }
+            }
+            block.render(size, buf);
+        }
+
+        if let Some(ref rival) = self.rival {
+            // Head to head, each tally on its player's side
+            let (home, away) = if rival.remote.is_some() { ("You", "Rival") } else { ("P1", "P2") };
+            let (home, away) = ((home, self.session_stats.catches), (away, rival.stats.catches));
+            let ((left, left_catches), (right, right_catches)) =
+                if self.shore == Shore::Left { (home, away) } else { (away, home) };
+            let score = format!(" {} {} – {} {} ", left, left_catches, right_catches, right);
+            let x = size.width.saturating_sub(score.chars().count() as u16 + 2);
+            buf.set_stringn(x, size.y, score, size.width as usize, Style::default().fg(Color::Yellow));
+        }
+
+        if let Some(ref round) = self.config.tournament {
+            let label = round.label();
+            let x = size.width.saturating_sub(label.chars().count() as u16 + 2);
+            let style = Style::default().fg(if round.remaining() < TOURNAMENT_HURRY { Color::Red } else { Color::Yellow });
+            buf.set_stringn(x, size.y, label, size.width as usize, style);
+        }
+
+        // A SUCCESS banner waits for the fireworks to burst, a FAILURE one for the squall
+        let signal = self.local_signal.as_ref().filter(|_| self.signal_shown_at.is_none_or(|at| at <= self.elapsed));
+        if let Some(&(is_success, ref message)) = signal
+            && let Some(ref art) = self.config.banners.for_outcome(is_success).art
+        {
+            let color = self.config.banners.for_outcome(is_success).color;
+            ArtBanner { art, message, color }.render(size, buf);
+        } else if let Some(&(is_success, ref message)) = signal {
+            let color = self.config.banners.for_outcome(is_success).color;
+            let signal_par = Paragraph::new(Text::from(message.as_str()))
+                .block(Block::default().borders(Borders::ALL))
+                .style(Style::default().fg(color))
+                .alignment(Alignment::Center);
+
+            // Position in the upper part of the sky
+            let msg_area = layout.banner(0, message.len().min(60) as u16 + 4);
+            Clear.render(msg_area, buf);
+            signal_par.render(msg_area, buf);
+        }
+
+        if let Some(ref watchdog) = self.watchdog
+            && watchdog.is_stuck()
+            && self.local_signal.is_none()
+        {
+            let text = format!("Task may be stuck: nothing heard for {}", watchdog.describe_window());
+            let warning = Paragraph::new(Text::from(text.as_str()))
+                .block(Block::default().borders(Borders::ALL))
+                .style(Style::default().fg(Color::Yellow))
+                .alignment(Alignment::Center);
+
+            // In the SUCCESS/FAILURE banner slot, since none is up
+            let msg_area = layout.banner(0, text.len().min(60) as u16 + 4);
+            Clear.render(msg_area, buf);
+            warning.render(msg_area, buf);
+        }
+
+        if let Some((ref text, _)) = self.banner {
+            let banner_par = Paragraph::new(Text::from(text.as_str()))
+                .block(Block::default().borders(Borders::ALL))
+                .style(Style::default().fg(Color::Cyan))
+                .alignment(Alignment::Center);
+
+            // Just below the SUCCESS/FAILURE banner slot
+            let banner_area = layout.banner(1, text.len().min(60) as u16 + 4);
+            Clear.render(banner_area, buf);
+            banner_par.render(banner_area, buf);
+        }
+
+        if self.show_stats {
+            let signal = match (&self.local_signal, &self.progress) {
+                (Some((true, message)), _) => format!("SUCCESS: {}", message),
+                (Some((false, message)), _) => format!("FAILURE: {}", message),
+                (None, Some((percent, label))) => format!("{}% {}", percent, label),
+                (None, None) => match self.last_outcome {
+                    Some(true) => "last: success".to_string(),
+                    Some(false) => "last: failure".to_string(),
+                    None if self.config.signals.is_some() => "waiting".to_string(),
+                    None => "no signal source".to_string(),
+                },
+            };
+            StatsPanel { stats: &self.session_stats, session_time: elapsed, signal: &signal }.render(size, buf);
+        }
+
+        if self.screen == Screen::Journal {
+            AchievementsView {
+                journal: &game.profile.journal,
+                species_count: self.species.len(),
+                stocks: &self.species_names.iter().map(|name| (name.as_str(), game.population.stock(name))).collect::<Vec<_>>(),
+            }
+            .render(size, buf);
+        }
+
+        if let Some(ref mut aquarium) = self.aquarium {
+            let tank = Aquarium { frames: &self.frames, shiny_frames: &self.shiny_frames, theme };
+            StatefulWidget::render(tank, size, buf, aquarium);
+        }
+
+        if let Some(ref mut encyclopedia) = self.encyclopedia {
+            let pages = Encyclopedia {
+                species: &self.species_names,
+                frames: &self.frames,
+                traits: &self.traits,
+                journal: &game.profile.journal,
+                theme,
+            };
+            StatefulWidget::render(pages, size, buf, encyclopedia);
+        }
+
+        if let Some(toast) = self.toasts.front() {
+            let (title, text, color) = match toast {
+                Toast::Achievement(text) => ("Achievement unlocked", text, Color::Yellow),
+                Toast::Info(text) => ("Info", text, Color::White),
+            };
+            let toast_width = (text.chars().count() as u16 + 4).max(title.chars().count() as u16 + 2).min(size.width);
+            let toast_area = Rect::new(size.width.saturating_sub(toast_width + 1), 1, toast_width, 3.min(size.height));
+            let toast_par = Paragraph::new(Text::from(text.as_str()))
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .style(Style::default().fg(color));
+            Clear.render(toast_area, buf);
+            toast_par.render(toast_area, buf);
+        }
+
+        if let Some(scroll) = self.history_scroll {
+            HistoryPanel { history: &self.signal_history, scroll }.render(size, buf);
+        }
+
+        if self.show_help {
+            HelpPanel { keys: &self.config.keys, sources: &self.config.sources, two_players: self.rival.as_ref().is_some_and(|rival| rival.remote.is_none()) }.render(size, buf);
+        }
+
+        if self.console_open {
+            ConsoleLine { console: &self.console }.render(size, buf);
+        }
+
+        if self.paused && size.height > 0 {
+            let pause_key = self.config.keys.keys(Action::Pause).next().map(keymap::key_name);
+            let text = match pause_key {
+                Some(key) => format!(" PAUSED - {} to resume ", key),
+                None => " PAUSED ".to_string(),
+            };
+            let x = size.width.saturating_sub(text.chars().count() as u16) / 2;
+            let style = Style::default().fg(Color::Black).bg(Color::Yellow);
+            buf.set_stringn(x, size.y, text, size.width as usize, style);
+        }
+
+        if self.screen == Screen::GameOver
+            && let Some(ref round) = self.config.tournament
+            && let Some(ref standing) = round.standing
+        {
+            let quit_key = self.config.keys.keys(Action::Quit).next().map(keymap::key_name);
+            Scoreboard { tournament: round, standing, quit_key }.render(size, buf);
+        }
+
+        match self.screen {
+            Screen::Title => {
+                let choices = TitleChoice::offered(self.can_continue);
+                TitleMenu { choices: &choices, selected: self.menu_selected }.render(size, buf);
+            }
+            Screen::EnterName => {
+                let points = self.config.tournament.as_ref().map_or(0, |round| round.points);
+                NamePrompt { entry: &self.name_entry, points }.render(size, buf);
+            }
+            Screen::Leaderboard => {
+                let shown = self.high_scores.lengths().get(self.menu_selected).copied();
+                let marked = self
+                    .config
+                    .tournament
+                    .as_ref()
+                    .filter(|round| Some(round.length().as_secs()) == shown)
+                    .and_then(|round| round.standing.as_ref())
+                    .map(|standing| standing.rank);
+                Leaderboard { scores: &self.high_scores, length: self.menu_selected, marked }.render(size, buf);
+            }
+            Screen::Settings => {
+                let menu = SettingsMenu { theme: self.theme_name(), settings: self.settings(), selected: self.menu_selected };
+                menu.render(size, buf);
+            }
+            _ => {}
+        }
+
+        if let Some(ref shop) = self.shop {
+            ShopView { shop, profile: &game.profile }.render(size, buf);
+        }
+
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+    use crate::economy::Bait;
+    use crate::tasks::ANGLER_WIDTH;
+
+    /// An app on a 100x40 screen with an empty sea and nothing to snag, so only the
+    /// line moves.
+    fn app() -> App {
+        let config = Config { items: ItemTable { items: Vec::new() }, ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        app.game.fishes.clear();
+        app
+    }
+
+    fn key(code: KeyCode, kind: KeyEventKind) -> Event {
+        Event::Key(KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind))
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_event(key(code, KeyEventKind::Press));
+    }
+
+    fn release(app: &mut App, code: KeyCode) {
+        app.handle_event(key(code, KeyEventKind::Release));
+    }
+
+    /// Charge, release and wait for the hook to hit the water.
+    fn cast_and_land(app: &mut App) {
+        press(app, KeyCode::Char(' '));
+        app.update(Duration::from_millis(1000));
+        release(app, KeyCode::Char(' '));
+        app.update(CAST_ANIMATION_DURATION);
+    }
+
+    fn depth(app: &App) -> Option<u16> {
+        match app.game.fishing_state {
+            FishingState::Landed { depth, .. } => Some(depth),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn full_cast_cycle() {
+        let mut app = app();
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+
+        press(&mut app, KeyCode::Char(' '));
+        app.update(Duration::from_millis(1000));
+        let FishingState::Charging { power } = app.game.fishing_state else {
+            panic!("expected Charging, got {:?}", app.game.fishing_state);
+        };
+        assert!((power - 0.5).abs() < 1e-3);
+
+        release(&mut app, KeyCode::Char(' '));
+        assert!(matches!(app.game.fishing_state, FishingState::Casting { progress, .. } if progress == 0.0));
+        assert_eq!(app.session_stats.casts, 1);
+
+        app.update(Duration::from_millis(400));
+        assert!(matches!(app.game.fishing_state, FishingState::Casting { progress, .. } if progress > 0.4));
+
+        app.update(Duration::from_millis(400));
+        assert_eq!(depth(&app), Some(0));
+        assert!(matches!(app.take_events()[..], [GameEvent::Cast, GameEvent::CastLanded { .. }]));
+        assert!(app.take_events().is_empty());
+
+        for _ in 0..3 {
+            press(&mut app, KeyCode::Down);
+        }
+        assert_eq!(depth(&app), Some(3));
+        press(&mut app, KeyCode::Up);
+        assert_eq!(depth(&app), Some(2));
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Up);
+        assert_eq!(depth(&app), Some(0));
+        press(&mut app, KeyCode::Up);
+        assert!(matches!(app.game.fishing_state, FishingState::Retrieving { progress, .. } if progress == 0.0));
+        app.update(RETRIEVE_DURATION / 2);
+        assert!(matches!(app.game.fishing_state, FishingState::Retrieving { progress, .. } if progress > 0.4));
+        app.update(RETRIEVE_DURATION / 2);
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+    }
+
+    #[test]
+    fn remapped_keys_move_the_hook() {
+        let table = ConfigFile::parse("[keys]\nreel_up = \"k\"\nreel_down = \"j\"\n").unwrap();
+        let keys = Keymap::from_table(table.section("keys").unwrap()).unwrap();
+        let mut app = app();
+        app.config.keys = keys;
+        cast_and_land(&mut app);
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Down);
+        assert_eq!(depth(&app), Some(2));
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(depth(&app), Some(1));
+    }
+
+    #[test]
+    fn charge_is_capped() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char(' '));
+        app.update(MAX_CAST_TIME * 3);
+        assert!(matches!(app.game.fishing_state, FishingState::Charging { power } if power == 1.0));
+    }
+
+    #[test]
+    fn second_press_casts_without_release() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char(' '));
+        app.update(Duration::from_millis(500));
+        press(&mut app, KeyCode::Char(' '));
+        assert!(matches!(app.game.fishing_state, FishingState::Casting { .. }));
+    }
+
+    #[test]
+    fn with_key_releases_only_letting_go_casts() {
+        let mut app = app();
+        app.config.key_release = true;
+        press(&mut app, KeyCode::Char(' '));
+        app.update(Duration::from_millis(500));
+        app.handle_event(key(KeyCode::Char(' '), KeyEventKind::Repeat));
+        press(&mut app, KeyCode::Char(' '));
+        assert!(matches!(app.game.fishing_state, FishingState::Charging { .. }));
+        release(&mut app, KeyCode::Char(' '));
+        assert!(matches!(app.game.fishing_state, FishingState::Casting { .. }));
+
+        // Letting go of a reel key doesn't move the hook a second time
+        app.update(CAST_ANIMATION_DURATION);
+        press(&mut app, KeyCode::Down);
+        release(&mut app, KeyCode::Down);
+        assert_eq!(depth(&app), Some(1));
+    }
+
+    #[test]
+    fn reeling_in_returns_unused_bait() {
+        let mut app = app();
+        app.game.profile.add_bait(Bait::Worms, 2);
+        app.game.profile.selected_bait = Some(Bait::Worms);
+
+        cast_and_land(&mut app);
+        assert_eq!(app.game.hook_bait, Some(Bait::Worms));
+        assert_eq!(app.game.profile.bait_count(Bait::Worms), 1);
+
+        press(&mut app, KeyCode::Up);
+        app.update(RETRIEVE_DURATION);
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+        assert_eq!(app.game.profile.bait_count(Bait::Worms), 2);
+    }
+
+    #[test]
+    fn reeling_and_bites_tighten_the_line() {
+        // Seeded, so the same fish swims by every run and none takes the resting hook
+        let config = Config { seed: Some(2), items: ItemTable { items: Vec::new() }, ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        app.game.fishes.clear();
+        cast_and_land(&mut app);
+        for _ in 0..3 {
+            press(&mut app, KeyCode::Down);
+        }
+        app.update(Duration::from_secs(2));
+        assert!(app.line_tension < 0.3);
+
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.line_tension, 1.0);
+        app.update(Duration::from_secs(2));
+        assert!(app.line_tension < 0.3);
+
+        // A fish on the hook keeps it taut
+        assert!(app.bites.start(&mut app.rng, 0, &mut app.game.fishes[0], 1.0));
+        app.bites.tick(Duration::from_secs(2), &mut app.game.fishes);
+        app.update(Duration::from_millis(250));
+        assert!(app.line_tension > 0.5);
+    }
+
+    #[test]
+    fn reeling_is_ignored_while_idle() {
+        let mut app = app();
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Up);
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+    }
+
+    #[test]
+    fn open_shop_takes_the_keys() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('t'));
+        assert!(app.shop.is_some());
+
+        press(&mut app, KeyCode::Char(' '));
+        app.update(Duration::from_millis(500));
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+
+        press(&mut app, KeyCode::Esc);
+        assert!(app.shop.is_none());
+    }
+
+    fn click(app: &mut App, kind: MouseEventKind, column: u16, row: u16) {
+        app.handle_event(Event::Mouse(MouseEvent { kind, column, row, modifiers: KeyModifiers::NONE }));
+    }
+
+    #[test]
+    fn clicking_the_water_casts_there() {
+        let mut near = app();
+        let water = near.layout.ocean.y + 2;
+        click(&mut near, MouseEventKind::Down(MouseButton::Left), 40, water);
+        // Give or take where the wind carries it
+        let landing = (40 + near.wind.drift(near.rod_tip_x() - 40)) as u16;
+        assert!(matches!(near.game.fishing_state, FishingState::Casting { target_x, .. } if target_x == landing));
+
+        // Out of the rod's reach lands as far as it casts
+        let mut far = app();
+        click(&mut far, MouseEventKind::Down(MouseButton::Left), 0, water);
+        let reach = (100.0 * far.game.profile.rod().cast_range) as u16;
+        let furthest = (far.rod_tip_x() - reach) as i32 + far.wind.drift(reach);
+        assert!(matches!(far.game.fishing_state, FishingState::Casting { target_x, .. } if target_x as i32 == furthest.max(0)));
+    }
+
+    #[test]
+    fn clicking_the_sky_does_nothing() {
+        let mut app = app();
+        click(&mut app, MouseEventKind::Down(MouseButton::Left), 40, 2);
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+    }
+
+    #[test]
+    fn scrolling_moves_the_hook() {
+        let mut app = app();
+        cast_and_land(&mut app);
+        click(&mut app, MouseEventKind::ScrollDown, 0, 0);
+        click(&mut app, MouseEventKind::ScrollDown, 0, 0);
+        assert_eq!(depth(&app), Some(2));
+        click(&mut app, MouseEventKind::ScrollUp, 0, 0);
+        assert_eq!(depth(&app), Some(1));
+    }
+
+    #[test]
+    fn the_underwater_view_follows_the_hook_down() {
+        let mut app = app();
+        cast_and_land(&mut app);
+        let FishingState::Landed { landing_x, .. } = app.game.fishing_state else {
+            panic!("expected the line in the water, got {:?}", app.game.fishing_state);
+        };
+        press(&mut app, KeyCode::Char('u'));
+        let hook_row = |app: &mut App| {
+            let frame = crate::headless::render_frames(app, 1).remove(0);
+            assert!(frame.contains("u to surface"));
+            frame.lines().position(|line| line.chars().nth(landing_x as usize) == Some('⌡')).unwrap()
+        };
+        let shallow = hook_row(&mut app);
+        for _ in 0..6 {
+            press(&mut app, KeyCode::Down);
+        }
+        assert!(hook_row(&mut app) > shallow);
+
+        press(&mut app, KeyCode::Char('u'));
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(!frame.contains("u to surface"));
+    }
+
+    #[test]
+    fn clicking_the_fisherman_toggles_stats() {
+        let mut app = app();
+        let fisherman = app.layout.fisherman_at(app.shore);
+        click(&mut app, MouseEventKind::Down(MouseButton::Left), fisherman.x + 8, fisherman.y + 2);
+        assert!(app.show_stats);
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+    }
+
+    #[test]
+    fn resize_lays_the_scene_out_again() {
+        let mut app = app();
+        let lanes = app.layout.lanes;
+        app.handle_event(Event::Resize(100, 60));
+        assert_eq!(app.size(), Rect::new(0, 0, 100, 60));
+        assert_eq!(app.layout, Layout::with_tide(Rect::new(0, 0, 100, 60), 0, 0, DockSettings::default()));
+        assert!(app.layout.lanes > lanes);
+    }
+
+    #[test]
+    fn the_tide_lifts_the_scene_and_the_hook_with_it() {
+        let size = Rect::new(0, 0, 100, 40);
+        let (still, high) = (Layout::with_tide(size, 0, 0, DockSettings::default()), Layout::with_tide(size, 2, 0, DockSettings::default()));
+        assert_eq!(high.ocean.y, still.ocean.y - 2);
+        assert_eq!(high.fish.y, still.fish.y - 2);
+        assert_eq!(high.anchor.top, still.anchor.top - 2);
+        assert_eq!((high.lanes, high.seabed), (still.lanes, still.seabed));
+
+        let mut app = app();
+        cast_and_land(&mut app);
+        let surface = app.layout.ocean.y;
+        assert!(matches!(app.game.fishing_state, FishingState::Landed { landing_y, .. } if landing_y == surface));
+        // A quarter of the way round the tide is in, and a hook resting on the surface
+        // is out of reach of the fish
+        for _ in 0..5 * 60 {
+            app.update(Duration::from_secs(1));
+        }
+        assert_eq!(app.layout.tide, TIDE_RANGE as i16);
+        let surface = app.layout.ocean.y;
+        assert!(matches!(app.game.fishing_state, FishingState::Landed { landing_y, .. } if landing_y == surface));
+    }
+
+    #[test]
+    fn the_demo_fisherman_plays_on_its_own() {
+        let config = Config { seed: Some(7), demo: true, ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        for _ in 0..10 * 60 * 20 {
+            app.update(Duration::from_millis(50));
+        }
+        assert!(app.session_stats.casts > 5);
+        assert!(app.session_stats.catches > 0);
+    }
+
+    #[test]
+    fn the_ai_fisherman_fills_in_while_the_player_is_away() {
+        let config = Config { seed: Some(7), attract_after: Some(Duration::from_secs(60)), ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        app.game.profile.add_bait(Bait::Worms, 3);
+        app.game.profile.selected_bait = Some(Bait::Worms);
+        for _ in 0..59 {
+            app.update(Duration::from_secs(1));
+        }
+        press(&mut app, KeyCode::Down);
+        for _ in 0..59 {
+            app.update(Duration::from_secs(1));
+        }
+        assert!(app.autopilot.is_none());
+
+        for _ in 0..5 * 60 * 20 {
+            app.update(Duration::from_millis(50));
+        }
+        assert!(app.autopilot.is_some());
+        assert!(app.session_stats.casts > 0);
+
+        // The key that brings the player back does nothing else, and they get their
+        // rod, bait and stats back as they left them
+        press(&mut app, KeyCode::Char('t'));
+        assert!(app.autopilot.is_none() && app.shop.is_none());
+        assert_eq!(app.session_stats.casts, 0);
+        assert_eq!(app.game.profile.bait_count(Bait::Worms), 3);
+        app.update(RETRIEVE_DURATION);
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+        assert_eq!(app.game.hook_bait, None);
+    }
+
+    #[test]
+    fn pause_freezes_the_scene() {
+        let config = Config { seed: Some(3), ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        let positions = |app: &App| app.game.fishes.iter().map(|f| f.x).collect::<Vec<_>>();
+        for fish in &mut app.game.fishes {
+            fish.spawn_delay_ms = 0;
+        }
+        app.update(Duration::from_secs(1));
+        press(&mut app, KeyCode::Char('p'));
+        let before = positions(&app);
+        app.update(Duration::from_secs(5));
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(positions(&app), before);
+        assert_eq!(app.elapsed, Duration::from_secs(1));
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+
+        press(&mut app, KeyCode::Char('p'));
+        app.update(Duration::from_secs(1));
+        assert_eq!(app.elapsed, Duration::from_secs(2));
+        assert_ne!(positions(&app), before);
+    }
+
+    #[test]
+    fn hitboxes_match_the_sprites() {
+        let mut app = app();
+        let area = app.layout.fish;
+        let mut fish = fish::spawn_species(&mut app.rng, &app.frames, &app.traits, 0, 100.0, app.layout.lanes);
+        fish.lane = 1;
+        fish.y_offset = 0.0;
+        fish.build = fish::Build::Normal;
+        let sizes: Vec<(u16, u16)> = app
+            .species
+            .iter()
+            .enumerate()
+            .map(|(i, _)| {
+                fish.species = i;
+                let (top, width, height) = hitbox(&app.species, &app.frames, &fish, area);
+                assert_eq!(top as i32, fish::sprite_top(&fish, area));
+                assert_eq!(fish::lane_at(area, top), Some(1));
+                (width, height)
+            })
+            .collect();
+        let goby = app.species.iter().position(|s| s.name == "Goby").unwrap();
+        assert_eq!(sizes[goby], (5, 3));
+
+        // A big one is drawn bigger, and its hitbox grows with it
+        fish.species = goby;
+        fish.build = fish::Build::Large;
+        let (_, width, height) = hitbox(&app.species, &app.frames, &fish, area);
+        assert_eq!((width, height), (7, 4));
+        let frame = fish::sprite(&fish, &app.baked[fish.build as usize], &[], Duration::ZERO).unwrap();
+        assert_eq!((frame.width, frame.height), (7, 4));
+    }
+
+    #[test]
+    fn bosses_take_three_hook_sets_to_land() {
+        let mut app = app();
+        app.game.profile.line = economy::LINES.len() - 1;
+        cast_and_land(&mut app);
+        let boss = fish::spawn_boss(&mut app.rng, &app.frames, &app.traits, 100.0, app.layout.lanes).unwrap();
+        let (_, width, height) = hitbox(&app.species, &app.frames, &boss, app.layout.fish);
+        let shark = app.species.iter().position(|s| s.name == "Shark").unwrap();
+        assert_eq!(boss.species, shark);
+        assert_eq!((width, height), (app.species[shark].size.0 * 2, app.species[shark].size.1 * 2));
+        app.game.fishes.push(boss);
+
+        assert!(app.bites.start(&mut app.rng, 0, &mut app.game.fishes[0], 1.0));
+        for fights_left in (0..fish::BOSS_FIGHTS).rev() {
+            app.bites.tick(Duration::from_secs(2), &mut app.game.fishes);
+            assert_eq!(app.bites.phase(), Some(BitePhase::Biting));
+            press(&mut app, KeyCode::Char(' '));
+            if fights_left > 0 {
+                assert_eq!(app.bites.phase(), Some(BitePhase::Fighting));
+                assert_eq!(app.game.fishes[0].boss_fights, fights_left);
+                assert_ne!(app.effects.shake_offset(), (0, 0));
+                app.bites.tick(Duration::from_secs(3), &mut app.game.fishes);
+            }
+        }
+        assert!(app.game.fishes.is_empty());
+        assert!(app.banner.as_ref().is_some_and(|(text, _)| text.starts_with("BOSS LANDED!")));
+        // The catch rides the hook back in before the line goes idle
+        assert!(matches!(app.game.fishing_state, FishingState::Retrieving { .. }));
+        assert!(app.retrieved_fish.as_ref().is_some_and(|f| f.species == shark));
+        app.update(RETRIEVE_DURATION);
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+        assert!(app.retrieved_fish.is_none());
+    }
+
+    #[test]
+    fn reduced_motion_slows_the_fish_and_stills_the_fisherman() {
+        let travel = |reduce_motion: bool| {
+            let config = Config { seed: Some(3), reduce_motion, ..Config::default() };
+            let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+            // Mid-screen, already swimming and alone, so none of them wraps, waits to
+            // start or keeps station in a school
+            for fish in app.game.fishes.iter_mut() {
+                fish.x = 50.0;
+                fish.spawn_delay_ms = 0;
+                fish.school = None;
+            }
+            let mut kicked = false;
+            for _ in 0..10 {
+                app.update(Duration::from_millis(100));
+                kicked |= app.fisherman_anim.pose() == crate::fisherman::Pose::Kick;
+            }
+            let moved: f32 = app.game.fishes.iter().map(|f| (f.x - 50.0).abs()).sum();
+            (moved, kicked)
+        };
+        let (normal, kicked) = travel(false);
+        let (calm, calm_kicked) = travel(true);
+        assert!(kicked && !calm_kicked);
+        assert!(calm > 0.0 && calm < normal / 3.0, "{} vs {}", calm, normal);
+    }
+
+    #[test]
+    fn quit_key() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.should_quit());
+    }
+
+    #[test]
+    fn ctrl_c_quits() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('c'));
+        assert!(!app.should_quit());
+        app.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert!(app.should_quit());
+    }
+
+    #[test]
+    fn exit_policy_quits_after_the_signal_is_shown() {
+        let mut app = app();
+        app.config.on_signal = SignalPolicy::Exit;
+        press(&mut app, KeyCode::Char('s'));
+        assert!(!app.should_quit());
+        app.update(Duration::from_millis(50));
+        assert!(app.should_quit());
+    }
+
+    #[test]
+    fn keep_playing_clears_the_signal() {
+        let mut app = app();
+        app.config.on_signal = SignalPolicy::KeepPlaying;
+        press(&mut app, KeyCode::Char('f'));
+        app.update(SQUALL_LEAD + SIGNAL_BANNER_DURATION);
+        assert!(app.local_signal.is_none());
+        assert!(!app.should_quit());
+    }
+
+    #[test]
+    fn a_failure_scatters_the_fish_before_its_banner_goes_up() {
+        let config = Config { seed: Some(3), ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        for fish in app.game.fishes.iter_mut() {
+            fish.spawn_delay_ms = 0;
+        }
+        let stocked = app.game.fishes.len();
+        press(&mut app, KeyCode::Char('f'));
+        for _ in 0..15 {
+            app.update(Duration::from_millis(100));
+        }
+        assert!(app.signal_shown_at.is_some_and(|at| at > app.elapsed));
+        assert_eq!(app.ocean.agitation, 1.0);
+        assert!(app.game.fishes[..stocked].iter().all(|fish| fish.vx.abs() > fish.cruise_speed + 10.0));
+
+        app.update(SQUALL_DURATION);
+        assert_eq!(app.agitation(), 0.0);
+        assert!(app.signal_shown_at.is_some_and(|at| at <= app.elapsed));
+    }
+
+    #[test]
+    fn a_quiet_task_puts_the_fisherman_to_sleep_until_a_heartbeat() {
+        let config = Config { timeout: Some(Duration::from_secs(60)), ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        app.update(Duration::from_secs(61));
+        assert_eq!(app.fisherman_anim.pose(), crate::fisherman::Pose::Asleep);
+        app.receive(SignalMessage::Heartbeat);
+        assert_ne!(app.fisherman_anim.pose(), crate::fisherman::Pose::Asleep);
+
+        app.config.on_timeout = TimeoutPolicy::Exit;
+        app.update(Duration::from_secs(61));
+        assert!(app.should_quit() && app.timed_out());
+    }
+
+    #[test]
+    fn same_seed_same_scene() {
+        let scene = || {
+            let config = Config { seed: Some(42), ..Config::default() };
+            let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+            for _ in 0..100 {
+                app.update(Duration::from_millis(50));
+            }
+            serde_json::to_string(&app.game).unwrap()
+        };
+        assert_eq!(scene(), scene());
+    }
+
+    #[test]
+    fn progress_reels_the_idle_line_in() {
+        let layout = Layout::with_tide(Rect::new(0, 0, 100, 40), 0, 0, DockSettings::default());
+        let hook = |percent| match progress_hook(&layout, Shore::Right, percent) {
+            FishingState::Landed { landing_x, landing_y, depth } => (landing_x, landing_y + depth),
+            state => panic!("expected the line in the water, got {:?}", state),
+        };
+        let (x, start) = hook(0);
+        assert_eq!(x, layout.rod_tip(Shore::Right) - PROGRESS_CAST_DISTANCE);
+        assert_eq!(start, layout.fish.bottom() - 1);
+        assert!(hook(50).1 < start);
+        assert_eq!(hook(100).1, layout.ocean.y);
+    }
+    #[test]
+    fn each_task_has_a_place_on_the_dock_and_the_last_ends_the_wait() {
+        let mut app = app();
+        let dock = app.layout.dock;
+        let task = |name: &str, message| SignalMessage::Task { name: name.to_string(), message: Box::new(message) };
+        app.receive(task("build", SignalMessage::Progress { percent: 30, label: String::new() }));
+        app.receive(task("deploy", SignalMessage::Success("live".to_string())));
+        assert_eq!(app.layout.dock.right(), dock.right());
+        assert_eq!(app.layout.anglers.width, 2 * ANGLER_WIDTH);
+        assert_eq!(app.layout.dock.x, app.layout.anglers.x);
+        assert!(app.layout.anglers.right() <= app.rod_tip_x());
+        assert!(app.layout.nearest_landing(Shore::Right) < app.layout.dock.x);
+        assert_eq!(app.banner.as_ref().map(|(text, _)| text.as_str()), Some("deploy: live"));
+        assert_eq!(app.local_signal, None);
+
+        app.receive(task("build", SignalMessage::Failure("linker error".to_string())));
+        assert_eq!(app.local_signal, Some((false, "1 of 2 tasks failed: build".to_string())));
+        assert_eq!(app.last_outcome, Some(false));
+    }
+
+    #[test]
+    fn two_players_fish_their_own_lines_from_opposite_shores() {
+        let config = Config { items: ItemTable { items: Vec::new() }, players: 2, ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        app.game.fishes.clear();
+        assert_eq!(app.layout.far_dock.x, 1);
+        let rod_tip_x = app.layout.rod_tip(Shore::Left);
+
+        press(&mut app, KeyCode::Char('x'));
+        app.update(Duration::from_millis(1000));
+        release(&mut app, KeyCode::Char('x'));
+        app.update(CAST_ANIMATION_DURATION);
+        cast_and_land(&mut app);
+        press(&mut app, KeyCode::Char('z'));
+        press(&mut app, KeyCode::Char('z'));
+        press(&mut app, KeyCode::Down);
+
+        let rival = app.rival.as_ref().unwrap();
+        let FishingState::Landed { landing_x, depth: rival_depth, .. } = rival.fishing_state else {
+            panic!("expected the second line in the water, got {:?}", rival.fishing_state);
+        };
+        assert!(landing_x >= rod_tip_x + MIN_CAST_DISTANCE);
+        assert_eq!(rival_depth, 2);
+        assert_eq!(depth(&app), Some(1));
+        assert_eq!((rival.stats.casts, app.session_stats.casts), (1, 1));
+
+        app.session_stats.catches = 3;
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(frame.lines().next().unwrap().contains(" P2 0 – 3 P1 "));
+    }
+
+    #[test]
+    fn the_title_menu_leads_to_settings_saved_to_the_config_and_into_the_game() {
+        let path = std::env::temp_dir().join(format!("fisherman-menu-{}.toml", std::process::id()));
+        std::fs::write(&path, "# keep me\n").unwrap();
+        let config = Config { menu: true, config_path: Some(path.clone()), ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        assert_eq!(app.screen, Screen::Title);
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(frame.contains("▸ New game") && !frame.contains("Continue"));
+
+        // New game, Journal, High scores, Settings
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.screen, Screen::Settings);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.theme_name(), "night");
+        assert!(!app.config.status_bar);
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, "# keep me\n\n[theme]\nbase = \"night\"\n\n[settings]\nstatus_bar = false\n");
+        std::fs::remove_file(&path).unwrap();
+
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.screen, Screen::Title);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.screen, Screen::Playing);
+        cast_and_land(&mut app);
+        assert_eq!(depth(&app), Some(0));
+    }
+
+    #[test]
+    fn a_tournament_ends_with_the_line_in_a_name_and_the_high_scores() {
+        let round = Tournament::new(Duration::from_secs(60), None);
+        let config = Config { items: ItemTable { items: Vec::new() }, tournament: Some(round), ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(frame.lines().next().unwrap().contains(" 1:00 · 0 pts "));
+
+        cast_and_land(&mut app);
+        app.update(Duration::from_secs(60));
+        assert!(app.config.tournament.as_ref().unwrap().is_over());
+        assert_eq!(app.game.fishing_state, FishingState::Idle);
+        assert_eq!(app.screen, Screen::EnterName);
+        for c in "Ann".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(frame.contains("Ann▏"));
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.game.fishing_state, FishingState::Idle);
+        assert_eq!(app.screen, Screen::Leaderboard);
+        press(&mut app, KeyCode::Esc);
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(frame.contains("Rank      #1 of 1"));
+
+        press(&mut app, KeyCode::Enter);
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(frame.contains("1:00 rounds"));
+        assert!(frame.contains(" 1. Ann "));
+    }
+
+    #[test]
+    fn a_joined_game_fishes_the_hosts_school_and_sees_their_line() {
+        let items = || ItemTable { items: Vec::new() };
+        let host_link = Link::host(0).unwrap();
+        let addr = format!("127.0.0.1:{}", host_link.local_addr().port());
+        let config = Config { items: items(), link: Some(host_link), ..Config::default() };
+        let mut host = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        let config = Config { items: items(), link: Some(Link::join(&addr).unwrap()), ..Config::default() };
+        let mut guest = App::new(config, Assets::load(), None, Rect::new(0, 0, 80, 40));
+        assert_eq!((host.shore, guest.shore), (Shore::Right, Shore::Left));
+        guest.game.fishes.clear();
+
+        cast_and_land(&mut host);
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5)
+            && (host.game.fishes.is_empty()
+                || guest.game.fishes.len() != host.game.fishes.len()
+                || !matches!(guest.rival.as_ref().unwrap().fishing_state, FishingState::Landed { .. }))
+        {
+            host.update(Duration::from_millis(100));
+            std::thread::sleep(Duration::from_millis(10));
+            guest.update(Duration::ZERO);
+        }
+        assert!(!host.game.fishes.is_empty());
+        assert_eq!(guest.game.fishes.len(), host.game.fishes.len());
+        let rival = guest.rival.as_ref().unwrap();
+        let FishingState::Landed { landing_x, .. } = rival.fishing_state else {
+            panic!("expected the host's line in the water, got {:?}", rival.fishing_state);
+        };
+        assert!(landing_x < guest.layout.rod_tip(Shore::Right));
+    }
+
+    #[test]
+    fn beating_a_species_best_is_a_new_record() {
+        let mut app = app();
+        let sizes = SizeDistribution::default();
+        let catch = |size| GameEvent::FishCaught {
+            fish: CaughtFish::new("Goby".to_string(), size, false, &sizes),
+            rarity: 0.0,
+            autopilot: false,
+        };
+        app.publish(catch(12.0));
+        app.publish(catch(9.0));
+        assert!(!app.toasts.iter().any(|toast| matches!(toast, Toast::Info(text) if text.starts_with("New record"))));
+        app.publish(catch(14.5));
+        assert!(app.toasts.iter().any(|toast| matches!(toast, Toast::Info(text) if text == "New record! A 14.5 cm Goby")));
+        assert!(app.game.population.stock("Goby") < 1.0);
+    }
+
+    #[test]
+    fn lines_left_in_rod_holders_keep_fishing() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('2'));
+        assert_eq!(app.rod_in_hand, 0);
+        assert!(app.banner.as_ref().is_some_and(|(text, _)| text.contains("tackle shop")));
+
+        app.game.profile.coins = 100;
+        app.game.profile.line = economy::LINES.len() - 1;
+        app.game.profile.buy(economy::ShopItem::RodHolder).unwrap();
+        app.fit_rod_holders();
+        cast_and_land(&mut app);
+        press(&mut app, KeyCode::Char('2'));
+        assert_eq!(app.rod_in_hand, 1);
+        assert!(matches!(app.game.fishing_state, FishingState::Idle));
+        assert!(matches!(app.rods[0].fishing_state, FishingState::Landed { .. }));
+
+        // A bite on the rod in the holder calls for taking it back up
+        let boss = fish::spawn_boss(&mut app.rng, &app.frames, &app.traits, 100.0, app.layout.lanes).unwrap();
+        app.game.fishes.push(boss);
+        assert!(app.rods[0].bites.start(&mut app.rng, 0, &mut app.game.fishes[0], 1.0));
+        app.update(Duration::from_secs(2));
+        assert_eq!(app.rods[0].bites.phase(), Some(BitePhase::Biting));
+        assert!(app.banner.as_ref().is_some_and(|(text, _)| text == "Bite on rod 1! Press 1"));
+        let (x, y) = app.layout.anchor.holder_tip(1);
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert_eq!(frame.lines().nth(y as usize - 1).unwrap().chars().nth(x as usize), Some('!'));
+
+        press(&mut app, KeyCode::Char('1'));
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.bites.phase(), Some(BitePhase::Fighting));
+        assert!(matches!(app.rods[1].fishing_state, FishingState::Idle));
+    }
+
+    #[test]
+    fn the_a11y_summary_describes_the_scene_in_a_sentence() {
+        let mut app = app();
+        let summary = app.summary(true);
+        assert!(summary.starts_with("21:00 night, "), "{}", summary);
+        assert!(summary.ends_with("line reeled in, 0 fish."), "{}", summary);
+        assert!(summary.is_ascii());
+
+        app.config.signals = Some(std::sync::Arc::default());
+        cast_and_land(&mut app);
+        app.progress = Some((40, "tests".to_string()));
+        let summary = app.summary(false);
+        assert!(summary.contains(" day, ") && summary.contains("line at depth "), "{}", summary);
+        assert!(summary.ends_with(" fish. Progress 40% tests. Waiting for signal."), "{}", summary);
+        app.local_signal = Some((false, "tests failed".to_string()));
+        assert!(app.summary(false).ends_with("Progress 40% tests. FAILURE: tests failed"));
+    }
+
+    #[test]
+    fn the_dock_lantern_is_lit_once_bought() {
+        let mut app = app();
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        let mut lit = |app: &mut App| {
+            terminal.draw(|f| app.draw(f)).unwrap();
+            crate::headless::buffer_text(terminal.backend().buffer()).contains('¤')
+        };
+        // The evening the scene starts in is dark enough, but there's no lantern yet
+        assert!(!lit(&mut app));
+
+        app.game.profile.coins = 200;
+        assert!(economy::ShopItem::catalog().contains(&economy::ShopItem::Lantern));
+        app.game.profile.buy(economy::ShopItem::Lantern).unwrap();
+        assert_eq!(app.game.profile.coins, 80);
+        assert_eq!(economy::ShopItem::Lantern.state(&app.game.profile), economy::ItemState::Owned);
+        assert!(lit(&mut app));
+
+        // The settings can put it out again
+        app.config.lantern = false;
+        assert!(!lit(&mut app));
+    }
+
+    #[test]
+    fn a_charging_cast_marks_where_it_will_land() {
+        let config = Config { seed: Some(2), items: ItemTable { items: Vec::new() }, ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        app.game.fishes.clear();
+        press(&mut app, KeyCode::Char(' '));
+        app.update(MAX_CAST_TIME);
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        let water = frame.lines().nth(app.layout.ocean.y as usize).unwrap();
+        let marker = water.chars().position(|c| c == '×').unwrap() as u16;
+        assert_eq!(app.cast_preview(), Some((marker, app.layout.ocean.y)));
+
+        release(&mut app, KeyCode::Char(' '));
+        assert!(matches!(app.game.fishing_state, FishingState::Casting { target_x, .. } if target_x == marker));
+    }
+
+    #[test]
+    fn a_fighting_fish_drags_the_hook_out_to_sea() {
+        let mut app = app();
+        app.game.profile.line = economy::LINES.len() - 1;
+        cast_and_land(&mut app);
+        let FishingState::Landed { landing_x, .. } = app.game.fishing_state else { panic!("the line didn't land") };
+        let mut boss = fish::spawn_boss(&mut app.rng, &app.frames, &app.traits, 100.0, app.layout.lanes).unwrap();
+        boss.x = landing_x as f32 - 10.0;
+        boss.facing_right = false;
+        boss.spawn_delay_ms = 0;
+        app.game.fishes.push(boss);
+        assert!(app.bites.start(&mut app.rng, 0, &mut app.game.fishes[0], 1.0));
+        app.bites.tick(Duration::from_secs(2), &mut app.game.fishes);
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.bites.phase(), Some(BitePhase::Fighting));
+
+        app.update(Duration::from_millis(500));
+        let FishingState::Landed { landing_x: dragged, .. } = app.game.fishing_state else { panic!("the line came in") };
+        assert!(dragged + 2 <= landing_x, "{} -> {}", landing_x, dragged);
+        assert!(app.line_tension > 0.9);
+        // The hook keeps to the spot on the fish it caught on
+        assert_eq!((dragged as f32 - app.game.fishes[0].x).round(), 10.0);
+    }
+
+    #[test]
+    fn winter_brings_its_colours_floes_and_species() {
+        let config = Config { date: Some("01-01".parse().unwrap()), items: ItemTable { items: Vec::new() }, ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        assert_ne!(app.theme().water, Theme::CLASSIC.water);
+        let weight = |app: &App, name: &str| app.spawn_weights()[app.species_names.iter().position(|n| n == name).unwrap()];
+        assert_eq!(weight(&app, "Sunfish"), 0.0);
+        assert!(weight(&app, "Icefish") > 0.0 && weight(&app, "Goby") > 0.0);
+        assert!(app.banner.as_ref().is_some_and(|(text, _)| text == "Happy New Year!"));
+
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        let surface: String = frame.lines().skip(app.layout.ocean.y as usize).take(app.layout.ocean.height as usize).collect();
+        assert!(surface.contains('▭') || surface.contains('▬'));
+
+        // A theme picked on purpose keeps its colours
+        app.set_theme(Theme::NIGHT);
+        assert_eq!(app.theme(), Theme::NIGHT);
+    }
+
+    #[test]
+    fn a_failure_jolts_the_screen_unless_motion_is_reduced() {
+        for reduce_motion in [false, true] {
+            let config = Config { reduce_motion, items: ItemTable { items: Vec::new() }, ..Config::default() };
+            let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+            app.receive(SignalMessage::Failure("tests failed".to_string()));
+            let (dx, dy) = app.effects.shake_offset();
+            assert_eq!(dx.abs(), if reduce_motion { 0 } else { 2 });
+            assert!(dy.abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn a_dock_on_the_left_casts_to_the_right_from_its_shore() {
+        let dock = DockSettings { side: Shore::Left, width: 24, shoreline: 6 };
+        let config = Config { seed: Some(2), dock, items: ItemTable { items: Vec::new() }, ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        app.game.fishes.clear();
+        assert_eq!(app.shore, Shore::Left);
+        // Six columns of shore inside the frame, then the planks
+        assert_eq!((app.layout.shoreline.x, app.layout.shoreline.right()), (0, 7));
+        assert_eq!(app.layout.dock.x, 7);
+        let anchor = app.layout.anchor;
+        assert_eq!(anchor.seat, app.layout.dock.x + 24 - 2);
+        assert_eq!(app.rod_tip_x(), anchor.seat + dock::ROD_REACH);
+
+        cast_and_land(&mut app);
+        let FishingState::Landed { landing_x, .. } = app.game.fishing_state else {
+            panic!("expected the line in the water, got {:?}", app.game.fishing_state);
+        };
+        assert!(landing_x >= app.rod_tip_x() + MIN_CAST_DISTANCE);
+
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        let planks = frame.lines().nth(app.layout.dock.y as usize).unwrap();
+        assert!(planks.starts_with("│▄▄▄▄▄▄═"), "{}", planks);
+    }
+
+    #[test]
+    fn a_left_dock_pushed_off_a_narrow_screen_still_casts() {
+        let dock = DockSettings { side: Shore::Left, shoreline: 20, ..DockSettings::default() };
+        let config = Config { seed: Some(1), dock, items: ItemTable { items: Vec::new() }, ..Config::default() };
+        let size = Rect::new(0, 0, crate::layout::MIN_WIDTH, crate::layout::MIN_HEIGHT);
+        let mut app = App::new(config, Assets::load(), None, size);
+        app.game.fishes.clear();
+        assert!(app.layout.nearest_landing(Shore::Left) >= size.width);
+
+        cast_and_land(&mut app);
+        let FishingState::Landed { landing_x, .. } = app.game.fishing_state else {
+            panic!("expected the line in the water, got {:?}", app.game.fishing_state);
+        };
+        assert_eq!(landing_x, size.width - 1);
+    }
+    #[test]
+    fn a_screen_too_small_for_the_scene_says_so_until_it_grows() {
+        let mut app = app();
+        app.handle_event(Event::Resize(30, 12));
+        assert_eq!(app.size(), Rect::new(0, 0, 30, 12));
+        assert!(!app.layout.fits());
+        cast_and_land(&mut app);
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(frame.contains("Terminal too small") && frame.contains("30×12"), "{}", frame);
+
+        // The line stayed out while the scene was hidden
+        app.handle_event(Event::Resize(100, 40));
+        assert!(matches!(app.game.fishing_state, FishingState::Landed { .. }));
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(!frame.contains("Terminal too small"));
+    }
+
+    #[test]
+    fn a_plugin_hears_the_signals_and_draws_over_the_sea() {
+        let plugins = vec![crate::plugin::PluginRegistry::builtin().create("whale", 1).unwrap()];
+        let config = Config { seed: Some(2), plugins, items: ItemTable { items: Vec::new() }, ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 100, 40));
+        app.receive(SignalMessage::Success("deployed".to_string()));
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        let surface = frame.lines().nth(app.layout.ocean.y as usize).unwrap();
+        assert!(surface.contains("(o") || surface.contains("o)"), "{}", frame);
+    }
+
+    #[test]
+    fn the_console_runs_commands_typed_into_it() {
+        let mut app = app();
+        app.game.fishes.clear();
+        let type_in = |app: &mut App, text: &str| {
+            for c in text.chars() {
+                app.handle_event(Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)));
+            }
+            app.handle_event(Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)));
+        };
+        type_in(&mut app, ":spawn goby 3");
+        assert_eq!(app.game.fishes.len(), 3);
+        type_in(&mut app, "weather storm");
+        assert_eq!(app.game.weather.current, Weather::Storm);
+        type_in(&mut app, "set density 0.5");
+        assert_eq!(app.config.spawning.density, 0.5);
+        // Keys go to the console while it's open, so the s typed didn't fake a SUCCESS
+        assert_eq!(app.last_outcome, None);
+        type_in(&mut app, "signal success hello");
+        assert_eq!(app.last_outcome, Some(true));
+
+        let frame = crate::headless::render_frames(&mut app, 1).remove(0);
+        assert!(frame.contains("Received SUCCESS:hello"), "{}", frame);
+        type_in(&mut app, "");
+        assert!(!app.console_open);
+    }
+
+    #[test]
+    fn the_line_is_drawn_over_the_fish() {
+        let mut app = app();
+        cast_and_land(&mut app);
+        for _ in 0..10 {
+            press(&mut app, KeyCode::Down);
+        }
+        let FishingState::Landed { landing_x, landing_y, depth } = app.game.fishing_state else {
+            panic!("not landed: {:?}", app.game.fishing_state);
+        };
+        // A fish swimming right where the line runs down to the hook
+        let line = Position::new(landing_x, landing_y + depth - 1);
+        let mut fish = fish::spawn_species(&mut app.rng, &app.frames, &app.traits, 0, 100.0, app.layout.lanes);
+        fish.lane = fish::lane_at(app.layout.fish, line.y).unwrap();
+        fish.x = (line.x - app.layout.fish.x) as f32 - 1.0;
+        fish.spawn_delay_ms = 0;
+        fish.build = fish::Build::Normal;
+        let rect = fish::whole_sprite_rect(&fish, app.layout.fish, &app.frames, &app.shiny_frames, app.elapsed, false);
+        assert!(rect.is_some_and(|rect| rect.contains(line)), "{:?} misses {:?}", rect, line);
+        app.game.fishes.push(fish);
+
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| app.draw(f)).unwrap();
+        assert_eq!(terminal.backend().buffer()[line].symbol(), "│");
+    }
+
+    #[test]
+    fn hooked_fish_are_tired_on_a_taut_line_before_they_reel_in() {
+        let mut app = app();
+        app.game.profile.line = economy::LINES.len() - 1;
+        cast_and_land(&mut app);
+        let sunfish = app.species.iter().position(|s| s.name == "Sunfish").unwrap();
+        assert_eq!(app.traits[sunfish].stamina, 3.0);
+        let mut fish = fish::spawn_species(&mut app.rng, &app.frames, &app.traits, sunfish, 100.0, app.layout.lanes);
+        fish.size = app.traits[sunfish].sizes.mean;
+        app.game.fishes.push(fish);
+        assert!(app.bites.start(&mut app.rng, 0, &mut app.game.fishes[0], 1.0));
+        app.bites.tick(Duration::from_secs(2), &mut app.game.fishes);
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.bites.phase(), Some(BitePhase::Hooked));
+        assert_eq!(app.bites.stamina(), Some(1.0));
+
+        // The reel only pulls the line taut while the fish has fight left in it
+        let before = depth(&app);
+        press(&mut app, KeyCode::Up);
+        assert_eq!(depth(&app), before);
+        assert_eq!(app.game.fishes.len(), 1);
+        // A slack line gives it its strength back
+        app.update(Duration::from_millis(500));
+        press(&mut app, KeyCode::Up);
+        app.update(Duration::from_millis(100));
+        assert!(app.bites.stamina().unwrap() < 1.0);
+        app.update(Duration::from_secs(2));
+        assert_eq!(app.bites.stamina(), Some(1.0));
+
+        // Kept taut, it tires in its three seconds of stamina
+        let mut turns = 0;
+        while app.bites.stamina() != Some(0.0) {
+            press(&mut app, KeyCode::Up);
+            app.update(Duration::from_millis(100));
+            turns += 1;
+        }
+        assert!((30..=31).contains(&turns), "{}", turns);
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| app.draw(f)).unwrap();
+        let screen = terminal.backend().buffer().clone();
+        let rows: Vec<String> = (0..40).map(|y| (0..100).map(|x| screen[(x, y)].symbol().to_string()).collect()).collect();
+        let fish_row = rows.iter().position(|row| row.contains("fish ░░░░░░░░")).expect("no stamina bar");
+        assert!(rows[fish_row + 1].contains("line █"), "{}", rows[fish_row + 1]);
+
+        // Tired, it comes in on the next turn of the reel
+        let swimming = app.game.fishes.len();
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.game.fishes.len(), swimming - 1);
+        assert!(app.retrieved_fish.as_ref().is_some_and(|f| f.species == sunfish));
+    }
+
+    #[test]
+    fn the_fight_gauge_stays_whole_at_the_edges_of_the_frame() {
+        let mut app = app();
+        app.game.profile.line = economy::LINES.len() - 1;
+        cast_and_land(&mut app);
+        let sunfish = app.species.iter().position(|s| s.name == "Sunfish").unwrap();
+        let fish = fish::spawn_species(&mut app.rng, &app.frames, &app.traits, sunfish, 100.0, app.layout.lanes);
+        app.game.fishes.push(fish);
+        assert!(app.bites.start(&mut app.rng, 0, &mut app.game.fishes[0], 1.0));
+        app.bites.tick(Duration::from_secs(2), &mut app.game.fishes);
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.bites.stamina(), Some(1.0));
+
+        // A line landing hard by either edge, with the rod on the far side, would put the
+        // gauge off the frame; it's drawn in full just inside it instead
+        let size = Rect::new(0, 0, 40, 10);
+        for (landing_x, rod_x) in [(38, 0), (1, 39)] {
+            let mut buf = Buffer::empty(size);
+            let state = FishingState::Landed { landing_x, landing_y: 6, depth: 0 };
+            app.draw_fight_gauge(&mut buf, size, (rod_x, 2), state, &app.bites, 0.0);
+            let rows: Vec<String> = crate::headless::buffer_text(&buf).lines().map(str::to_string).collect();
+            let fish_row = rows.iter().position(|row| row.contains("fish ████████")).expect("no stamina bar");
+            assert!(rows[fish_row + 1].contains("line ░░░░░░░░"), "{}", rows[fish_row + 1]);
+            let x = rows[fish_row].find("fish").unwrap() as u16;
+            assert!(x >= 1 && x + GAUGE_WIDTH < size.right(), "gauge at {} for a line at {}", x, landing_x);
+        }
+    }
+
+    #[test]
+    fn placeholder_fish_stand_in_for_sprites_that_fail_to_load() {
+        let mut assets = Assets::load();
+        assert_eq!(assets.warning(), None);
+        assets.species.clear();
+        assets.moon = None;
+        assets.stand_in();
+        assert_eq!(assets.missing, ["fish", "moon"]);
+        let warning = "Couldn't load sprites: fish, moon (placeholder fish swim instead)";
+        assert_eq!(assets.warning().as_deref(), Some(warning));
+        assert_eq!(assets.frames.len(), 3);
+
+        let config = Config { seed: Some(1), ..Config::default() };
+        let mut app = App::new(config, assets, None, Rect::new(0, 0, 100, 40));
+        assert_eq!(app.species_names, ["Minnow", "Perch", "Grouper"]);
+        assert_eq!(app.banner.as_ref().map(|(text, _)| text.as_str()), Some(warning));
+        assert!(!app.game.fishes.is_empty());
+        app.update(Duration::from_secs(5));
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| app.draw(f)).unwrap();
+        let screen = crate::headless::buffer_text(terminal.backend().buffer());
+        assert!(["><>", "<><", "°>", "<°"].iter().any(|glyphs| screen.contains(glyphs)), "{}", screen);
+    }
+}
@@ -0,0 +1,167 @@
+//! The aquarium (`a`): a full-screen tank with one of each species ever landed swimming
+//! in it, drawn bigger or smaller with the personal best of its species from the
+//! journal. A species ever landed shiny swims in its shiny colours.
+
+use std::time::Duration;
+
+use rand::Rng;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Widget};
+
+use crate::achievements::Journal;
+use crate::csv_frames::{SpeciesFrames, SpriteFrame, resize_text};
+use crate::fish::{self, Fish, SpeciesTraits};
+use crate::theme::Theme;
+
+/// How big the species with the biggest personal best is drawn, against its sprite.
+const MAX_SCALE: f32 = 2.5;
+/// The smallest a fish is drawn, however small its best.
+const MIN_SCALE: f32 = 0.5;
+
+/// How much to stretch the sprite of a species whose best is `best` cm, when the
+/// biggest best of any species is `biggest`.
+fn scale_for(best: Option<f32>, biggest: f32) -> f32 {
+    match best {
+        Some(best) if biggest > 0.0 => (MAX_SCALE * best / biggest).clamp(MIN_SCALE, MAX_SCALE),
+        // Landed before personal bests were kept
+        _ => 1.0,
+    }
+}
+
+/// The fish in the tank and how far they've swum.
+pub struct AquariumState {
+    fishes: Vec<Fish>,
+    scales: Vec<f32>,
+    /// `Goby 24 cm` for each fish, for the caption.
+    labels: Vec<String>,
+    elapsed: Duration,
+    width: u16,
+}
+
+impl AquariumState {
+    /// Stock the tank from `journal`, with a fish of every species landed that is
+    /// still in `species_names`.
+    pub fn new<R: Rng + ?Sized>(
+        rng: &mut R,
+        journal: &Journal,
+        species_names: &[String],
+        frames: &[SpeciesFrames],
+        traits: &[SpeciesTraits],
+        width: u16,
+    ) -> AquariumState {
+        let landed: Vec<(usize, &String)> = species_names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| journal.species.contains(*name))
+            .collect();
+        let biggest = journal.best.values().copied().fold(0.0, f32::max);
+        let mut tank = AquariumState { fishes: Vec::new(), scales: Vec::new(), labels: Vec::new(), elapsed: Duration::ZERO, width };
+        for (lane, (species, name)) in landed.iter().enumerate() {
+            let mut fish = fish::spawn_species(rng, frames, traits, *species, width as f32, landed.len());
+            // Each in a lane of its own, already in the water, and never leaving it
+            fish.lane = lane;
+            fish.wrap = true;
+            fish.x = rng.gen_range(0.0..width.max(1) as f32);
+            fish.shiny = journal.shiny.contains(*name);
+            let best = journal.best.get(*name).copied();
+            tank.fishes.push(fish);
+            tank.scales.push(scale_for(best, biggest));
+            tank.labels.push(match best {
+                Some(best) => format!("{} {:.0} cm", name, best),
+                None => name.to_string(),
+            });
+        }
+        tank
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.elapsed += dt;
+        fish::update_fishes(&mut self.fishes, dt, self.elapsed, self.width as f32, 0.0);
+    }
+}
+
+/// The tank, filling the area it's given.
+pub struct Aquarium<'a> {
+    pub frames: &'a [SpeciesFrames],
+    pub shiny_frames: &'a [SpeciesFrames],
+    pub theme: Theme,
+}
+
+impl StatefulWidget for Aquarium<'_> {
+    type State = AquariumState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut AquariumState) {
+        Clear.render(area, buf);
+        let title = format!("Aquarium · {} species", state.fishes.len());
+        let block = Block::default().title(title).borders(Borders::ALL).style(Style::default().bg(self.theme.water));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let caption = if state.labels.is_empty() { "Nothing landed yet".to_string() } else { state.labels.join(" · ") };
+        let caption_height = 1.min(inner.height);
+        let tank = Rect { height: inner.height - caption_height, ..inner };
+        Paragraph::new(Line::from(caption))
+            .style(Style::default().fg(self.theme.foam))
+            .render(Rect { y: tank.bottom(), height: caption_height, ..inner }, buf);
+        state.width = tank.width;
+
+        let count = state.fishes.len().max(1) as u16;
+        // Biggest first, so smaller fish swim in front of them
+        let mut order: Vec<usize> = (0..state.fishes.len()).collect();
+        order.sort_by(|&a, &b| state.scales[b].total_cmp(&state.scales[a]));
+        for i in order {
+            let fish = &state.fishes[i];
+            let Some(frame) = fish::sprite(fish, self.frames, self.shiny_frames, state.elapsed) else {
+                continue;
+            };
+            let scale = state.scales[i];
+            let width = ((frame.width() as f32 * scale).round() as u16).max(1);
+            let height = ((frame.height() as f32 * scale).round() as u16).clamp(1, tank.height.max(1));
+            let lane_top = tank.y + fish.lane as u16 * tank.height / count;
+            let y = (lane_top as i32 + fish.row_offset()).clamp(tank.y as i32, tank.bottom().saturating_sub(height) as i32);
+            let x = tank.x as i32 + fish.x.floor() as i32;
+            let sprite = resize_text(frame, width, height);
+            let left = x.max(tank.left() as i32);
+            let right = (x + width as i32).min(tank.right() as i32);
+            if left >= right {
+                continue;
+            }
+            let visible = Rect::new(left as u16, y as u16, (right - left) as u16, height).intersection(tank);
+            SpriteFrame::bake(&sprite).blit(buf, (x, y), 1, visible, &[]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Assets;
+    use crate::fishing_game::{CaughtFish, SizeCategory};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn fish_are_drawn_to_the_scale_of_their_personal_best() {
+        assert_eq!(scale_for(Some(80.0), 80.0), MAX_SCALE);
+        assert_eq!(scale_for(Some(40.0), 80.0), MAX_SCALE / 2.0);
+        assert_eq!(scale_for(Some(1.0), 80.0), MIN_SCALE);
+        assert_eq!(scale_for(None, 80.0), 1.0);
+
+        let assets = Assets::load();
+        let names: Vec<String> = assets.species.iter().map(|s| s.name.clone()).collect();
+        let mut journal = Journal::default();
+        let caught = CaughtFish { species_name: names[0].clone(), size: 30.0, size_category: SizeCategory::Average, shiny: false };
+        journal.record(&caught, false, &names);
+        let mut tank = AquariumState::new(&mut StdRng::seed_from_u64(1), &journal, &names, &assets.frames, &[], 80);
+        assert_eq!(tank.labels, [format!("{} 30 cm", names[0])]);
+        assert_eq!(tank.scales, [MAX_SCALE]);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 24));
+        Aquarium { frames: &assets.frames, shiny_frames: &assets.shiny, theme: Theme::default() }.render(buf.area, &mut buf, &mut tank);
+        let drawn = (1..22).flat_map(|y| (1..79).map(move |x| (x, y))).filter(|&(x, y)| buf[(x, y)].symbol() != " ").count();
+        assert!(drawn > 0);
+    }
+}
@@ -0,0 +1,197 @@
+//! An AI fisherman for `--demo`. It looks over the scene each tick and answers with the
+//! same actions a player's keys would: charge a cast towards a fish, let it go, reel
+//! the hook to that fish's depth, set it when one bites and reel a hooked one in. Now
+//! and then it gives up on a quiet spot and reels in to try elsewhere.
+
+use std::time::Duration;
+
+use crossterm::event::KeyEventKind;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::fishing_line::FishingState;
+use crate::keymap::Action;
+
+/// Milliseconds it pauses between casts, and between turns of the reel.
+const REST_MS: (u64, u64) = (800, 2500);
+const REEL_MS: (u64, u64) = (150, 350);
+/// Milliseconds it takes to react to a bite.
+const REACTION_MS: (u64, u64) = (120, 400);
+/// How far off its aim a cast's power can be.
+const AIM_ERROR: f32 = 0.1;
+/// Seconds it waits with the hook at depth before trying elsewhere.
+const PATIENCE: (f32, f32) = (10.0, 25.0);
+
+/// What the AI fisherman can see of the scene.
+#[derive(Debug, Clone, Default)]
+pub struct Glance {
+    pub state: FishingState,
+    /// A fish has bitten and the hook can be set.
+    pub biting: bool,
+    /// A fish is on the line, to be tired out and reeled in.
+    pub hooked: bool,
+    /// The column and the middle row of each fish in the water.
+    pub fish: Vec<(f32, u16)>,
+    pub rod_tip_x: u16,
+    /// How far a full-power cast goes, and how long it takes to charge.
+    pub reach: u16,
+    pub full_charge: Duration,
+    /// Rows the hook comes up for each turn of the reel.
+    pub reel_step: u16,
+}
+
+/// The AI fisherman and what it's up to.
+#[derive(Debug, Clone)]
+pub struct Autopilot {
+    rng: StdRng,
+    /// Time before it does anything more.
+    wait: Duration,
+    /// How much longer to hold the cast key down.
+    charge: Duration,
+    /// The row it wants the hook at, and how much longer it will wait there.
+    depth: Option<u16>,
+    patience: Duration,
+    reeling_in: bool,
+    striking: bool,
+    /// It has paused since the last cast came in.
+    rested: bool,
+}
+
+impl Autopilot {
+    pub fn new(seed: u64) -> Self {
+        Autopilot {
+            rng: StdRng::seed_from_u64(seed),
+            wait: Duration::ZERO,
+            charge: Duration::ZERO,
+            depth: None,
+            patience: Duration::ZERO,
+            reeling_in: false,
+            striking: false,
+            rested: false,
+        }
+    }
+
+    /// Look over the scene `dt` after the last look, and press or let go of a key if
+    /// it's time to.
+    pub fn tick(&mut self, dt: Duration, glance: &Glance) -> Option<(Action, KeyEventKind)> {
+        self.wait = self.wait.saturating_sub(dt);
+        self.patience = self.patience.saturating_sub(dt);
+        match glance.state {
+            FishingState::Idle if !self.rested => {
+                self.rested = true;
+                self.wait = self.pause(REST_MS);
+                None
+            }
+            FishingState::Idle if self.wait.is_zero() => Some(self.cast(glance)),
+            FishingState::Charging { .. } => {
+                self.charge = self.charge.saturating_sub(dt);
+                self.charge.is_zero().then_some((Action::Cast, KeyEventKind::Release))
+            }
+            FishingState::Landed { landing_x, landing_y, depth } => self.fish(glance, landing_x, landing_y + depth),
+            _ => None,
+        }
+    }
+
+    /// Pick a fish and start charging a cast that lands near it, or any old cast if the
+    /// water is empty.
+    fn cast(&mut self, glance: &Glance) -> (Action, KeyEventKind) {
+        let reach = glance.reach.max(1) as f32;
+        let (power, row) = match glance.fish.get(self.rng.gen_range(0..glance.fish.len().max(1))) {
+            Some(&(x, row)) => ((glance.rod_tip_x as f32 - x).abs() / reach, Some(row)),
+            None => (self.rng.gen_range(0.3..1.0), None),
+        };
+        let power = (power + self.rng.gen_range(-AIM_ERROR..AIM_ERROR)).clamp(0.0, 1.0);
+        self.charge = glance.full_charge.mul_f32(power);
+        self.depth = row;
+        self.patience = Duration::from_secs_f32(self.rng.gen_range(PATIENCE.0..PATIENCE.1));
+        self.striking = false;
+        self.reeling_in = false;
+        self.rested = false;
+        (Action::Cast, KeyEventKind::Press)
+    }
+
+    /// Strike at a bite, otherwise work the hook at `hook_y` towards the fish nearest
+    /// it, or reel in once patience runs out.
+    fn fish(&mut self, glance: &Glance, hook_x: u16, hook_y: u16) -> Option<(Action, KeyEventKind)> {
+        if glance.biting && !self.striking {
+            self.striking = true;
+            self.wait = self.pause(REACTION_MS);
+            return None;
+        }
+        if !self.wait.is_zero() {
+            return None;
+        }
+        if glance.biting {
+            self.striking = false;
+            return Some((Action::Cast, KeyEventKind::Press));
+        }
+        self.striking = false;
+        self.wait = self.pause(REEL_MS);
+        // Keep reeling a hooked fish until it tires and comes in
+        if glance.hooked {
+            return Some((Action::ReelUp, KeyEventKind::Press));
+        }
+        if self.patience.is_zero() {
+            self.reeling_in = true;
+        }
+        if self.reeling_in {
+            return Some((Action::ReelUp, KeyEventKind::Press));
+        }
+        // Follow whichever fish is swimming closest to the line
+        let nearest = glance.fish.iter().min_by(|a, b| (a.0 - hook_x as f32).abs().total_cmp(&(b.0 - hook_x as f32).abs()));
+        let target = nearest.map(|&(_, row)| row).or(self.depth)?;
+        self.depth = Some(target);
+        if hook_y < target {
+            Some((Action::ReelDown, KeyEventKind::Press))
+        } else if hook_y >= target + glance.reel_step.max(1) {
+            Some((Action::ReelUp, KeyEventKind::Press))
+        } else {
+            None
+        }
+    }
+
+    fn pause(&mut self, range_ms: (u64, u64)) -> Duration {
+        Duration::from_millis(self.rng.gen_range(range_ms.0..=range_ms.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aims_at_a_fish_and_strikes_when_it_bites() {
+        let mut pilot = Autopilot::new(1);
+        let mut glance = Glance {
+            fish: vec![(40.0, 20)],
+            rod_tip_x: 80,
+            reach: 60,
+            full_charge: Duration::from_secs(2),
+            reel_step: 1,
+            ..Glance::default()
+        };
+        assert_eq!(pilot.tick(Duration::ZERO, &glance), None);
+        assert_eq!(pilot.tick(Duration::from_secs(3), &glance), Some((Action::Cast, KeyEventKind::Press)));
+        // Two thirds of the reach away, give or take its aim
+        let charge = pilot.charge.as_secs_f32();
+        assert!((1.33 - 0.2..1.33 + 0.2).contains(&charge));
+
+        glance.state = FishingState::Charging { power: 0.5 };
+        assert_eq!(pilot.tick(Duration::from_secs(1), &glance), None);
+        assert_eq!(pilot.tick(Duration::from_secs(1), &glance), Some((Action::Cast, KeyEventKind::Release)));
+
+        glance.state = FishingState::Landed { landing_x: 40, landing_y: 10, depth: 0 };
+        assert_eq!(pilot.tick(Duration::ZERO, &glance), Some((Action::ReelDown, KeyEventKind::Press)));
+        glance.state = FishingState::Landed { landing_x: 40, landing_y: 10, depth: 10 };
+        assert_eq!(pilot.tick(Duration::from_secs(1), &glance), None);
+
+        glance.biting = true;
+        assert_eq!(pilot.tick(Duration::ZERO, &glance), None);
+        assert_eq!(pilot.tick(Duration::from_secs(1), &glance), Some((Action::Cast, KeyEventKind::Press)));
+
+        // A hooked fish is reeled until it comes in
+        glance.biting = false;
+        glance.hooked = true;
+        assert_eq!(pilot.tick(Duration::from_secs(1), &glance), Some((Action::ReelUp, KeyEventKind::Press)));
+    }
+}
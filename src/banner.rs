@@ -0,0 +1,179 @@
+//! How the SUCCESS and FAILURE banners look, from the `[banners]` section of the
+//! config file. Each outcome can have a colour and art of its own, a plain text file
+//! or a sprite CSV, drawn large over the scene with the signal's message beneath it:
+//!
+//! ```toml
+//! [banners]
+//! success_art = "deploy-ok.txt"
+//! success_color = "#40ff80"
+//! failure_art = "sad-fish.csv"
+//! failure_color = "magenta"
+//! ```
+//!
+//! Relative paths are taken from the config file's directory.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use crate::config_file::{Table, Value};
+use crate::csv_frames::{self, load_csv_frame};
+use crate::theme::parse_color;
+
+/// Largest art is blown up, so a few lines of text still make a banner.
+const MAX_SCALE: u16 = 3;
+
+/// The look of one outcome's banner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BannerStyle {
+    /// Drawn above the message instead of the message alone, if given.
+    pub art: Option<Text<'static>>,
+    pub color: Color,
+}
+
+/// The `[banners]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Banners {
+    pub success: BannerStyle,
+    pub failure: BannerStyle,
+}
+
+impl Default for Banners {
+    fn default() -> Self {
+        Banners {
+            success: BannerStyle { art: None, color: Color::Green },
+            failure: BannerStyle { art: None, color: Color::Red },
+        }
+    }
+}
+
+impl Banners {
+    /// Read the section, loading art from paths relative to `dir`.
+    pub fn from_table(table: &Table, dir: &Path) -> Result<Banners, String> {
+        let mut banners = Banners::default();
+        for (key, value) in table {
+            let (style, setting) = match key.split_once('_') {
+                Some(("success", setting)) => (&mut banners.success, setting),
+                Some(("failure", setting)) => (&mut banners.failure, setting),
+                _ => return Err(format!("unknown setting '{}' in [banners]", key)),
+            };
+            match (setting, value) {
+                ("color", value) => style.color = parse_color(value).map_err(|e| format!("[banners] {}: {}", key, e))?,
+                ("art", Value::String(path)) => {
+                    let path = dir.join(path);
+                    let art = load_art(&path).map_err(|e| format!("[banners] could not load {}: {}", path.display(), e))?;
+                    style.art = Some(art);
+                }
+                ("art", _) => return Err(format!("[banners] {} must be a path", key)),
+                _ => return Err(format!("unknown setting '{}' in [banners]", key)),
+            }
+        }
+        Ok(banners)
+    }
+
+    pub fn for_outcome(&self, success: bool) -> &BannerStyle {
+        if success { &self.success } else { &self.failure }
+    }
+}
+
+/// A sprite CSV with the colours it was drawn in, or the lines of any other file to be
+/// drawn in the banner's colour.
+fn load_art(path: &Path) -> io::Result<Text<'static>> {
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+        return load_csv_frame(&path.to_string_lossy());
+    }
+    let content = fs::read_to_string(path)?;
+    let lines: Vec<Line<'static>> = content.trim_end().lines().map(|line| Line::from(line.to_string())).collect();
+    if lines.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "the file is empty"));
+    }
+    Ok(Text::from(lines))
+}
+
+/// A banner's art with the message beneath it, in a box centred over the upper part of
+/// the screen. Art too small for the screen is blown up, art too big squeezed to fit.
+pub struct ArtBanner<'a> {
+    pub art: &'a Text<'static>,
+    pub message: &'a str,
+    pub color: Color,
+}
+
+impl Widget for ArtBanner<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Room inside the box's border, over the top two thirds and above the message
+        let room_width = area.width.saturating_sub(4);
+        let room_height = (area.height * 2 / 3).saturating_sub(4);
+        let (width, height) = (self.art.width() as u16, self.art.height() as u16);
+        if width == 0 || height == 0 || room_width == 0 || room_height == 0 {
+            return;
+        }
+        let scale = (room_width / width).min(room_height / height).min(MAX_SCALE);
+        let art = if scale >= 1 {
+            csv_frames::scale_text(self.art, scale)
+        } else {
+            csv_frames::resize_text(self.art, width.min(room_width), height.min(room_height))
+        };
+
+        let art_width = art.width() as u16;
+        let box_width = (art_width.max(self.message.chars().count() as u16) + 4).min(area.width);
+        let box_height = (art.height() as u16 + 3).min(area.height);
+        let rect = Rect::new(
+            area.x + area.width.saturating_sub(box_width) / 2,
+            area.y + (area.height * 2 / 3).saturating_sub(box_height) / 2,
+            box_width,
+            box_height,
+        );
+        Clear.render(rect, buf);
+        // Centring is per line, so pad them all to the art's width to keep it in shape
+        let mut lines: Vec<Line> = art
+            .lines
+            .into_iter()
+            .map(|mut line| {
+                let pad = art_width as usize - line.width();
+                line.spans.push(" ".repeat(pad).into());
+                line
+            })
+            .collect();
+        lines.push(Line::from(self.message.to_string()));
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL))
+            .style(Style::default().fg(self.color))
+            .alignment(Alignment::Center)
+            .render(rect, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+
+    #[test]
+    fn banners_come_from_the_config_and_art_is_blown_up_to_fit() {
+        let dir = std::env::temp_dir().join(format!("fisherman-banners-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("ok.txt"), "OK\n\n").unwrap();
+        let config = ConfigFile::parse("[banners]\nsuccess_art = \"ok.txt\"\nfailure_color = \"magenta\"").unwrap();
+        let banners = Banners::from_table(config.section("banners").unwrap(), &dir).unwrap();
+        assert_eq!(banners.success.art, Some(Text::from("OK")));
+        assert_eq!(banners.for_outcome(false).color, Color::Magenta);
+        let missing = ConfigFile::parse("[banners]\nfailure_art = \"nope.txt\"").unwrap();
+        assert!(Banners::from_table(missing.section("banners").unwrap(), &dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let area = Rect::new(0, 0, 40, 30);
+        let mut buf = Buffer::empty(area);
+        let art = Text::from("OK");
+        ArtBanner { art: &art, message: "deployed", color: Color::Green }.render(area, &mut buf);
+        let row = |y: u16| (0..40).map(|x| buf[(x, y)].symbol().to_string()).collect::<String>();
+        let art_rows: Vec<String> = (0..30).map(row).filter(|row| row.contains("OOOKKK")).collect();
+        assert_eq!(art_rows.len(), MAX_SCALE as usize);
+        assert!((0..30).map(row).any(|row| row.contains("deployed")));
+    }
+}
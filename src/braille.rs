@@ -0,0 +1,98 @@
+//! A canvas of braille dots, for curves finer than a cell can draw (`--braille`). Each
+//! character of the braille block is a grid of dots two wide and four tall, so a line
+//! drawn in them has twice the columns and four times the rows to bend through. The
+//! fishing line and the ripples use it when the setting is on.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+
+/// The empty braille character; each dot adds its bit to it.
+const BLANK: u32 = 0x2800;
+/// The bit of each dot, by row and then column within the cell.
+const DOT_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Dots over `area`, set by position in cells and drawn into a buffer in one go.
+#[derive(Debug, Clone)]
+pub struct BrailleCanvas {
+    area: Rect,
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    pub fn new(area: Rect) -> Self {
+        BrailleCanvas { area, cells: vec![0; area.width as usize * area.height as usize] }
+    }
+
+    /// Set the dot at (`x`, `y`), counted in cells with whole numbers at their centres,
+    /// the way the rest of the scene places things. Dots outside the area are dropped.
+    pub fn dot(&mut self, x: f32, y: f32) {
+        let (dot_x, dot_y) = (((x + 0.5) * 2.0).floor(), ((y + 0.5) * 4.0).floor());
+        let (column, row) = ((dot_x / 2.0).floor(), (dot_y / 4.0).floor());
+        if column < self.area.x as f32 || row < self.area.y as f32 {
+            return;
+        }
+        let (column, row) = (column as u16, row as u16);
+        if column >= self.area.right() || row >= self.area.bottom() {
+            return;
+        }
+        let bit = DOT_BITS[(dot_y as usize) % 4][(dot_x as usize) % 2];
+        let index = (row - self.area.y) as usize * self.area.width as usize + (column - self.area.x) as usize;
+        self.cells[index] |= bit;
+    }
+
+    /// Dots all along the straight line from `from` to `to`.
+    pub fn line(&mut self, from: (f32, f32), to: (f32, f32)) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let steps = (dx.abs() * 2.0).max(dy.abs() * 4.0).ceil().max(1.0) as usize;
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+            self.dot(from.0 + dx * t, from.1 + dy * t);
+        }
+    }
+
+    /// Draw every cell with a dot in it in `color`, adding to braille already drawn there
+    /// and leaving the other cells and the background alone.
+    pub fn render(&self, buf: &mut Buffer, color: Color) {
+        let area = self.area.intersection(buf.area);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let bits = self.cells[(y - self.area.y) as usize * self.area.width as usize + (x - self.area.x) as usize];
+                if bits == 0 {
+                    continue;
+                }
+                let cell = &mut buf[(x, y)];
+                let drawn = cell.symbol().chars().next().map_or(0, |c| c as u32);
+                let under = if (BLANK..BLANK + 0x100).contains(&drawn) { drawn - BLANK } else { 0 };
+                let ch = char::from_u32(BLANK + (under | bits as u32)).unwrap_or(' ');
+                cell.set_char(ch).set_fg(color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dots_fill_in_the_braille_cells_they_fall_in() {
+        let area = Rect::new(0, 0, 4, 2);
+        let mut canvas = BrailleCanvas::new(area);
+        // The top left and bottom right dots of cell (1, 0)
+        canvas.dot(0.6, -0.4);
+        canvas.dot(1.4, 0.4);
+        canvas.dot(9.0, 9.0);
+        let mut buf = Buffer::empty(area);
+        canvas.render(&mut buf, Color::White);
+        assert_eq!(buf[(1, 0)].symbol(), "⢁");
+        assert_eq!(buf[(0, 0)].symbol(), " ");
+
+        let mut canvas = BrailleCanvas::new(area);
+        canvas.line((0.0, 1.0), (3.0, 1.0));
+        canvas.render(&mut buf, Color::White);
+        let row: String = (0..4).map(|x| buf[(x, 1)].symbol().to_string()).collect();
+        assert_eq!(row, "⠠⠤⠤⠤");
+        assert_eq!(buf[(1, 0)].symbol(), "⢁");
+    }
+}
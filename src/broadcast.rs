@@ -0,0 +1,228 @@
+//! `--broadcast <addr>` and `fisherman spectate <host>:<port>`: the scene shown live
+//! on other terminals, read-only, say on a shared screen while the team waits for a
+//! deploy. Each frame drawn goes out to every spectator as the ANSI output that turns
+//! the last frame into it, as for `--export-cast`; one that has just connected, or
+//! whose host's screen changed size, gets the whole frame. Anything that prints what
+//! it receives can watch, even `nc`.
+
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::cursor::Hide;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use ratatui::buffer::Buffer;
+
+use crate::cast;
+use crate::daemon;
+use crate::terminal::TerminalGuard;
+
+/// How long a spectator may hold up a frame before it's dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+const USAGE: &str = "\
+usage: fisherman spectate <host>:<port>
+
+Shows the scene of a fisherman started with --broadcast <port> on that host, live
+and read-only. Press q or Esc to stop watching.";
+
+const EXIT_USAGE: i32 = 2;
+const EXIT_FAILED: i32 = 1;
+
+/// A spectator, and whether it still needs a whole frame to start from.
+pub(crate) struct Spectator {
+    stream: TcpStream,
+    fresh: bool,
+}
+
+/// Whoever is watching.
+pub(crate) type Spectators = Arc<Mutex<Vec<Spectator>>>;
+
+/// Take in a spectator, to be sent the whole of the next frame.
+pub(crate) fn join(spectators: &Spectators, stream: TcpStream) {
+    let _ = stream.set_nodelay(true);
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    if let Ok(mut spectators) = spectators.lock() {
+        spectators.push(Spectator { stream, fresh: true });
+    }
+}
+
+/// Write out each frame sent to the spectators, on a thread of its own so a slow
+/// spectator never holds up the game; frames drawn while it's busy are skipped.
+pub(crate) fn send_frames(spectators: Spectators) -> SyncSender<Buffer> {
+    let (frames, received) = mpsc::sync_channel::<Buffer>(1);
+    thread::spawn(move || {
+        let mut last: Option<Buffer> = None;
+        for buffer in received {
+            let Ok(mut spectators) = spectators.lock() else {
+                return;
+            };
+            if spectators.is_empty() {
+                last = None;
+                continue;
+            }
+            let changes = cast::to_ansi(last.as_ref().filter(|last| last.area == buffer.area), &buffer);
+            let whole = spectators.iter().any(|s| s.fresh).then(|| cast::to_ansi(None, &buffer));
+            spectators.retain_mut(|spectator| {
+                let out = if spectator.fresh { whole.as_deref().unwrap_or_default() } else { &changes };
+                spectator.fresh = false;
+                out.is_empty() || spectator.stream.write_all(out.as_bytes()).is_ok()
+            });
+            last = Some(buffer);
+        }
+    });
+    frames
+}
+
+/// The scene going out to whoever connects.
+pub struct Broadcast {
+    frames: SyncSender<Buffer>,
+    local_addr: SocketAddr,
+}
+
+impl Broadcast {
+    /// Listen on `addr` for spectators, in the background. A bare `:port` listens on
+    /// every interface.
+    pub fn bind(addr: &str) -> io::Result<Broadcast> {
+        let listener = TcpListener::bind(listen_addr(addr))?;
+        let local_addr = listener.local_addr()?;
+        let spectators: Spectators = Arc::default();
+        let joining = Arc::clone(&spectators);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                join(&joining, stream);
+            }
+        });
+        Ok(Broadcast { frames: send_frames(spectators), local_addr })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Send the frame just drawn to the spectators, unless they're still busy with the
+    /// last one.
+    pub fn frame(&self, buffer: &Buffer) {
+        let _ = self.frames.try_send(buffer.clone());
+    }
+}
+
+/// `addr` as a socket address to listen on: `:7878` is port 7878 on every interface.
+fn listen_addr(addr: &str) -> String {
+    match addr.strip_prefix(':') {
+        Some(port) => format!("0.0.0.0:{}", port),
+        None => addr.to_string(),
+    }
+}
+
+/// `fisherman spectate <host>:<port>`: watch a broadcast until it ends or q is pressed.
+pub fn run(args: &[String]) -> i32 {
+    let [addr] = args else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+    match watch(addr, false) {
+        Ok(true) => {
+            println!("The broadcast from {} ended", addr);
+            0
+        }
+        Ok(false) => 0,
+        Err(e) => {
+            eprintln!("fisherman spectate: {}: {}", addr, e);
+            EXIT_FAILED
+        }
+    }
+}
+
+/// Show the scene arriving from `addr` until it ends or the viewer leaves, and whether
+/// it ended. Attached to a `fisherman daemon`, keys and resizes go back to the scene
+/// and Ctrl+D or Ctrl+C detaches; only watching, q and Esc leave too.
+pub(crate) fn watch(addr: &str, attach: bool) -> io::Result<bool> {
+    let stream = TcpStream::connect(addr)?;
+    let mut incoming = stream.try_clone()?;
+    let mut outgoing = stream.try_clone()?;
+    let guard = TerminalGuard::new()?;
+    execute!(io::stdout(), Hide)?;
+    if attach {
+        let (width, height) = crossterm::terminal::size()?;
+        writeln!(outgoing, "{} {} {}", daemon::ATTACH, width, height)?;
+    }
+    let ended = Arc::new(AtomicBool::new(false));
+    let done = Arc::clone(&ended);
+    thread::spawn(move || {
+        let mut chunk = [0; 16 * 1024];
+        let mut out = io::stdout();
+        while let Ok(read @ 1..) = incoming.read(&mut chunk) {
+            if out.write_all(&chunk[..read]).and_then(|_| out.flush()).is_err() {
+                break;
+            }
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+    while !ended.load(Ordering::Relaxed) {
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let event = event::read()?;
+        if let Event::Key(key) = event
+            && key.kind == KeyEventKind::Press
+            && (key.modifiers.contains(KeyModifiers::CONTROL) && matches!(key.code, KeyCode::Char('c' | 'd'))
+                || !attach && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc))
+        {
+            break;
+        }
+        if attach
+            && matches!(event, Event::Key(_) | Event::Resize(..))
+            && let Ok(line) = serde_json::to_string(&event)
+            && writeln!(outgoing, "{}", line).is_err()
+        {
+            break;
+        }
+    }
+    let _ = stream.shutdown(Shutdown::Both);
+    drop(guard);
+    Ok(ended.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+    use std::time::Instant;
+
+    #[test]
+    fn spectators_get_the_whole_frame_then_the_changes() {
+        assert_eq!(listen_addr(":7878"), "0.0.0.0:7878");
+        let broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+        let mut spectator = TcpStream::connect(broadcast.local_addr()).unwrap();
+        spectator.set_nonblocking(true).unwrap();
+        // Frames only go out once the spectator has been taken in, so keep drawing
+        let mut watch = |frame: &Buffer| {
+            let started = Instant::now();
+            let mut chunk = [0; 256];
+            while started.elapsed() < Duration::from_secs(5) {
+                broadcast.frame(frame);
+                thread::sleep(Duration::from_millis(20));
+                if let Ok(read @ 1..) = spectator.read(&mut chunk) {
+                    return String::from_utf8_lossy(&chunk[..read]).into_owned();
+                }
+            }
+            String::new()
+        };
+
+        let area = Rect::new(0, 0, 6, 2);
+        let mut first = Buffer::empty(area);
+        first.set_string(0, 0, "fish", Style::default());
+        let mut second = first.clone();
+        second.set_string(2, 1, "~", Style::default());
+        let whole = watch(&first);
+        assert!(whole.starts_with("\x1b[0m\x1b[2J") && whole.contains("fish"), "{:?}", whole);
+        assert_eq!(watch(&second), "\x1b[2;3H\x1b[0m~\x1b[0m");
+    }
+}
@@ -0,0 +1,200 @@
+//! `fisherman cargo -- <cargo args>`: runs cargo in the background with
+//! `--message-format=json` and turns what it reports into signals. Each crate compiled
+//! moves the progress on the dock along and lets a fish into the sea, and the build's
+//! result ends the wait with SUCCESS or FAILURE.
+
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::Value;
+
+use crate::signals::{SharedSignal, SignalMessage};
+
+/// Follows the JSON messages of one cargo run.
+#[derive(Debug, Clone)]
+struct Tracker {
+    /// Packages in the dependency graph, if `cargo metadata` could tell.
+    total: Option<usize>,
+    built: HashSet<String>,
+    first_error: Option<String>,
+    species: Vec<String>,
+}
+
+impl Tracker {
+    fn new(total: Option<usize>, species: Vec<String>) -> Tracker {
+        Tracker { total, built: HashSet::new(), first_error: None, species }
+    }
+
+    /// The signals for one line of cargo's output.
+    fn message(&mut self, line: &str) -> Vec<SignalMessage> {
+        let Ok(message) = serde_json::from_str::<Value>(line) else {
+            return Vec::new();
+        };
+        match message["reason"].as_str() {
+            Some("compiler-artifact") => {
+                let package = message["package_id"].as_str().unwrap_or_default();
+                if !self.built.insert(package.to_string()) {
+                    return Vec::new();
+                }
+                let done = self.built.len();
+                let progress = match self.total {
+                    // The graph counts crates this build may not need, so it never quite gets there
+                    Some(total) => SignalMessage::Progress {
+                        percent: (done * 100 / total.max(1)).min(99) as u8,
+                        label: format!("{}/{} crates", done.min(total), total),
+                    },
+                    None => SignalMessage::Progress { percent: 0, label: format!("{} crates", done) },
+                };
+                let mut signals = vec![progress];
+                // Crates that were already up to date come in a rush; only fresh work earns a fish
+                if message["fresh"] == false
+                    && let Some(species) = self.species_for(message["target"]["name"].as_str().unwrap_or(package))
+                {
+                    signals.push(SignalMessage::Spawn(species));
+                }
+                signals
+            }
+            Some("compiler-message") if message["message"]["level"] == "error" => {
+                if self.first_error.is_none() {
+                    self.first_error = message["message"]["message"].as_str().map(str::to_string);
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The species a crate's fish is, the same every build.
+    fn species_for(&self, name: &str) -> Option<String> {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        let count = self.species.len() as u64;
+        (count > 0).then(|| self.species[(hasher.finish() % count) as usize].clone())
+    }
+
+    /// The signal that ends the wait, once cargo has exited.
+    fn finish(&self, command: &str, success: bool) -> SignalMessage {
+        if success {
+            let crates = self.built.len();
+            SignalMessage::Success(format!("cargo {} finished, {} crate{}", command, crates, if crates == 1 { "" } else { "s" }))
+        } else {
+            match self.first_error {
+                Some(ref error) => SignalMessage::Failure(format!("cargo {}: {}", command, error)),
+                None => SignalMessage::Failure(format!("cargo {} failed", command)),
+            }
+        }
+    }
+}
+
+/// A cargo run in the background, stopped if the game quits first.
+pub struct CargoRun {
+    child: Arc<Mutex<Option<Child>>>,
+}
+
+impl CargoRun {
+    /// Run cargo with `args` from a background thread, sending its progress to
+    /// `signal`. Fish for finished crates are picked from `species`.
+    pub fn spawn(args: Vec<String>, species: Vec<String>, signal: SharedSignal) -> CargoRun {
+        let child = Arc::new(Mutex::new(None));
+        let shared = Arc::clone(&child);
+        thread::spawn(move || {
+            let push = |message| signal.lock().unwrap().push_back(message);
+            let command = args.first().cloned().unwrap_or_default();
+            let mut tracker = Tracker::new(package_count(&args), species);
+            let mut cargo = match cargo_command(&args).spawn() {
+                Ok(cargo) => cargo,
+                Err(e) => {
+                    push(SignalMessage::Failure(format!("could not run cargo: {}", e)));
+                    return;
+                }
+            };
+            let stdout = cargo.stdout.take();
+            // Keep the human-readable output off the screen
+            if let Some(mut stderr) = cargo.stderr.take() {
+                thread::spawn(move || io::copy(&mut stderr, &mut io::sink()));
+            }
+            *shared.lock().unwrap() = Some(cargo);
+            for line in stdout.map(BufReader::new).into_iter().flat_map(BufRead::lines).map_while(Result::ok) {
+                tracker.message(&line).into_iter().for_each(push);
+            }
+            let status = shared.lock().unwrap().take().map(|mut cargo| cargo.wait());
+            let success = matches!(status, Some(Ok(status)) if status.success());
+            push(tracker.finish(&command, success));
+        });
+        CargoRun { child }
+    }
+
+    /// Stop cargo if it's still running.
+    pub fn kill(&self) {
+        if let Some(ref mut cargo) = *self.child.lock().unwrap() {
+            let _ = cargo.kill();
+            let _ = cargo.wait();
+        }
+    }
+}
+
+fn cargo_binary() -> String {
+    std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string())
+}
+
+/// Cargo with `args` and JSON messages, which go before any `--` that passes
+/// arguments on to a program cargo runs.
+fn cargo_command(args: &[String]) -> Command {
+    let split = args.iter().position(|arg| arg == "--").unwrap_or(args.len());
+    let mut command = Command::new(cargo_binary());
+    command
+        .args(&args[..split])
+        .arg("--message-format=json")
+        .args(&args[split..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    command
+}
+
+/// How many packages the build's dependency graph has, as `cargo metadata` sees it.
+fn package_count(args: &[String]) -> Option<usize> {
+    let mut command = Command::new(cargo_binary());
+    command.args(["metadata", "--format-version", "1"]);
+    if let Some(i) = args.iter().position(|arg| arg == "--manifest-path") {
+        command.args(&args[i..(i + 2).min(args.len())]);
+    }
+    let mut child = command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn().ok()?;
+    let mut json = String::new();
+    child.stdout.take()?.read_to_string(&mut json).ok()?;
+    child.wait().ok().filter(|status| status.success())?;
+    let metadata: Value = serde_json::from_str(&json).ok()?;
+    metadata["resolve"]["nodes"].as_array().map(Vec::len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifacts_move_progress_and_errors_fail_the_build() {
+        let mut tracker = Tracker::new(Some(4), vec!["Goby".to_string(), "Shark".to_string()]);
+        let artifact = |id: &str, fresh: bool| {
+            format!(r#"{{"reason":"compiler-artifact","package_id":"{}","target":{{"name":"{}"}},"fresh":{}}}"#, id, id, fresh)
+        };
+        assert_eq!(
+            tracker.message(&artifact("serde", true)),
+            [SignalMessage::Progress { percent: 25, label: "1/4 crates".to_string() }]
+        );
+        let signals = tracker.message(&artifact("rand", false));
+        assert_eq!(signals[0], SignalMessage::Progress { percent: 50, label: "2/4 crates".to_string() });
+        assert!(matches!(&signals[1], SignalMessage::Spawn(species) if species == "Goby" || species == "Shark"));
+        // A second artifact of the same package, e.g. its build script, isn't another crate
+        assert!(tracker.message(&artifact("rand", false)).is_empty());
+        assert!(tracker.message("Compiling rand").is_empty());
+
+        assert_eq!(tracker.finish("build", true), SignalMessage::Success("cargo build finished, 2 crates".to_string()));
+        tracker.message(r#"{"reason":"compiler-message","message":{"level":"error","message":"mismatched types"}}"#);
+        assert_eq!(tracker.finish("build", false), SignalMessage::Failure("cargo build: mismatched types".to_string()));
+    }
+}
@@ -0,0 +1,171 @@
+//! Asciicast v2 export (`--export-cast <path>`), for sharing a session or embedding it
+//! in docs with asciinema's player. Each drawn frame is compared with the one before
+//! and only the cells that changed are written, as ANSI output timed from the start.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::style::{Color, Modifier};
+use serde_json::json;
+use unicode_width::UnicodeWidthStr;
+
+/// Writes the frames of a session to an asciicast file as they're drawn.
+pub struct CastWriter {
+    out: BufWriter<File>,
+    last: Option<Buffer>,
+}
+
+impl CastWriter {
+    pub fn create(path: &Path, width: u16, height: u16) -> io::Result<CastWriter> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+        let header = json!({ "version": 2, "width": width, "height": height, "timestamp": timestamp });
+        writeln!(out, "{}", header)?;
+        Ok(CastWriter { out, last: None })
+    }
+
+    /// Add the frame in `buffer`, drawn `at` after the session started. Frames that look
+    /// the same as the last one are left out.
+    pub fn frame(&mut self, buffer: &Buffer, at: Duration) -> io::Result<()> {
+        let time = at.as_secs_f64();
+        let last = self.last.as_ref().filter(|last| last.area == buffer.area);
+        if self.last.is_some() && last.is_none() {
+            let size = format!("{}x{}", buffer.area.width, buffer.area.height);
+            writeln!(self.out, "{}", json!([time, "r", size]))?;
+        }
+        let output = to_ansi(last, buffer);
+        if !output.is_empty() {
+            writeln!(self.out, "{}", json!([time, "o", output]))?;
+        }
+        self.last = Some(buffer.clone());
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// The ANSI output that turns a screen showing `previous` into one showing `buffer`,
+/// or clears the screen and draws all of `buffer` without a previous frame.
+pub fn to_ansi(previous: Option<&Buffer>, buffer: &Buffer) -> String {
+    let mut out = String::new();
+    let blank;
+    let previous = match previous {
+        Some(previous) => previous,
+        None => {
+            out.push_str("\x1b[0m\x1b[2J");
+            blank = Buffer::empty(buffer.area);
+            &blank
+        }
+    };
+    let mut cursor = None;
+    let mut style = None;
+    for (x, y, cell) in previous.diff(buffer) {
+        if cursor != Some((x, y)) {
+            out.push_str(&format!("\x1b[{};{}H", y + 1, x + 1));
+        }
+        let cell_style = (cell.fg, cell.bg, cell.modifier);
+        if style != Some(cell_style) {
+            out.push_str(&sgr(cell));
+            style = Some(cell_style);
+        }
+        out.push_str(cell.symbol());
+        cursor = Some((x + cell.symbol().width().max(1) as u16, y));
+    }
+    if style.is_some() {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+/// The escape sequence that sets the colours and attributes of `cell` from scratch.
+pub fn sgr(cell: &Cell) -> String {
+    let mut codes = vec!["0".to_string()];
+    let attributes = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in attributes {
+        if cell.modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(color_code(cell.fg, 30));
+    codes.extend(color_code(cell.bg, 40));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// The SGR parameters for `color` as a foreground (`base` 30) or background (40).
+fn color_code(color: Color, base: u8) -> Option<String> {
+    let named = |n: u8| Some((base + n).to_string());
+    let bright = |n: u8| Some((base + 60 + n).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(i) => Some(format!("{};5;{}", base + 8, i)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn frames_are_written_as_changes_since_the_last() {
+        let area = Rect::new(0, 0, 6, 2);
+        let mut first = Buffer::empty(area);
+        first.set_string(0, 0, "fish", Style::default().fg(Color::Cyan));
+        let mut second = first.clone();
+        second.set_string(2, 1, "~", Style::default().fg(Color::Rgb(0, 0, 255)).add_modifier(Modifier::BOLD));
+
+        let drawn = to_ansi(None, &first);
+        assert!(drawn.starts_with("\x1b[0m\x1b[2J\x1b[1;1H\x1b[0;36mfish"));
+        assert_eq!(to_ansi(Some(&first), &second), "\x1b[2;3H\x1b[0;1;38;2;0;0;255m~\x1b[0m");
+        assert_eq!(to_ansi(Some(&second), &second), "");
+
+        let path = std::env::temp_dir().join(format!("fisherman-cast-test-{}.cast", std::process::id()));
+        let mut cast = CastWriter::create(&path, 6, 2).unwrap();
+        cast.frame(&first, Duration::ZERO).unwrap();
+        cast.frame(&first, Duration::from_millis(50)).unwrap();
+        cast.frame(&second, Duration::from_millis(100)).unwrap();
+        cast.finish().unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let lines: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 6);
+        assert_eq!(lines[2][0], 0.1);
+        assert_eq!(lines[2][1], "o");
+    }
+}
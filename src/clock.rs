@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const SECS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+/// Scenes start in the late evening so an ordinary session stays under the stars.
+const START_TIME_OF_DAY: f64 = 21.0 * 60.0 * 60.0;
+/// The fastest the scene clock runs: a day goes by in about a tenth of a second.
+pub const MAX_SCALE: f64 = 1_000_000.0;
+
+/// In-scene world clock, decoupled from wall-clock time.
+///
+/// The clock only moves when [`SimClock::advance`] is called, scaled by the time-lapse
+/// factor, so day/night and weather can run faster than real time (or be stepped
+/// manually in tests and replays) while sprite animation keeps using real time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimClock {
+    elapsed: Duration,
+    scale: f64,
+}
+
+/// A `--timelapse` factor: how many times faster than real time the scene runs.
+pub fn parse_scale(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(scale) if scale > 0.0 && scale <= MAX_SCALE => Ok(scale),
+        _ => Err(format!("invalid --timelapse factor '{}': expected a number above 0 and up to {}", value, MAX_SCALE)),
+    }
+}
+
+impl SimClock {
+    pub fn new(scale: f64) -> Self {
+        SimClock {
+            elapsed: Duration::ZERO,
+            scale: if scale.is_nan() { 0.0 } else { scale.clamp(0.0, MAX_SCALE) },
+        }
+    }
+
+    /// A clock that picks up from `elapsed`, e.g. after a restart.
+    pub fn resume(scale: f64, elapsed: Duration) -> Self {
+        SimClock {
+            elapsed,
+            ..SimClock::new(scale)
+        }
+    }
+
+    /// Advance by a real-time step and return the equivalent in-scene step.
+    pub fn advance(&mut self, real_dt: Duration) -> Duration {
+        let sim_dt = real_dt.mul_f64(self.scale);
+        self.elapsed += sim_dt;
+        sim_dt
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// In-scene time since the clock started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn seconds_since_midnight(&self) -> f64 {
+        (START_TIME_OF_DAY + self.elapsed.as_secs_f64()) % SECS_PER_DAY
+    }
+
+    /// Time of day as a fraction, 0.0 at midnight and 0.5 at noon.
+    pub fn time_of_day(&self) -> f32 {
+        (self.seconds_since_midnight() / SECS_PER_DAY) as f32
+    }
+
+    /// Hours and minutes on the in-scene clock.
+    pub fn hour_minute(&self) -> (u32, u32) {
+        let secs = self.seconds_since_midnight() as u32;
+        (secs / 3600, (secs / 60) % 60)
+    }
+
+    /// In-scene days that have passed, including the part of the current one.
+    pub fn days(&self) -> f64 {
+        self.elapsed.as_secs_f64() / SECS_PER_DAY
+    }
+
+    /// Number of whole in-scene days that have passed.
+    pub fn days_elapsed(&self) -> u64 {
+        (self.elapsed.as_secs_f64() / SECS_PER_DAY) as u64
+    }
+
+    /// Brightness of the sky from 0.0 (night) to 1.0 (noon), easing through dawn and dusk.
+    pub fn daylight(&self) -> f32 {
+        let angle = self.time_of_day() * std::f32::consts::TAU;
+        // -cos peaks at noon; shift it so the sun is up between roughly 06:00 and 18:00.
+        (-angle.cos() * 1.5 + 0.25).clamp(0.0, 1.0)
+    }
+
+    /// Human-readable length of the wait in scene time, e.g. "3 in-game days".
+    pub fn describe_elapsed(&self) -> String {
+        let days = self.days_elapsed();
+        if days >= 1 {
+            format!("{} in-game day{}", days, if days == 1 { "" } else { "s" })
+        } else {
+            let hours = (self.elapsed.as_secs() / 3600) as u32;
+            format!("{} in-game hour{}", hours, if hours == 1 { "" } else { "s" })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timelapse_factors_are_bounded() {
+        assert_eq!(parse_scale("720"), Ok(720.0));
+        for bad in ["0", "-2", "inf", "NaN", "1e300", "fast"] {
+            assert!(parse_scale(bad).is_err(), "{}", bad);
+        }
+        let mut clock = SimClock::new(f64::INFINITY);
+        assert_eq!(clock.advance(Duration::from_secs(1)), Duration::from_secs(1_000_000));
+        assert_eq!(SimClock::new(f64::NAN).scale(), 0.0);
+    }
+}
@@ -0,0 +1,212 @@
+//! Colour resolution for terminals without truecolor. The widgets paint in RGB; just
+//! before a frame goes out, [`degrade`] maps every colour in it to the nearest one the
+//! terminal can show, so nothing else has to know what the terminal supports.
+
+use std::env;
+use std::str::FromStr;
+
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit RGB, passed through untouched.
+    #[default]
+    TrueColor,
+    /// The xterm 256-colour palette.
+    Ansi256,
+    /// The 16 basic ANSI colours.
+    Ansi16,
+}
+
+impl FromStr for ColorDepth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "24" | "24bit" | "truecolor" => Ok(ColorDepth::TrueColor),
+            "256" => Ok(ColorDepth::Ansi256),
+            "16" => Ok(ColorDepth::Ansi16),
+            _ => Err(format!("invalid color depth '{}' (expected 16, 256 or truecolor)", s)),
+        }
+    }
+}
+
+impl ColorDepth {
+    /// Guess from the environment: `COLORTERM` announces truecolor, Windows Terminal
+    /// always has it, and a `TERM` ending in `256color` gets the 256-colour palette.
+    pub fn detect() -> ColorDepth {
+        let colorterm = env::var("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" || env::var_os("WT_SESSION").is_some() {
+            return ColorDepth::TrueColor;
+        }
+        let term = env::var("TERM").unwrap_or_default();
+        if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else if cfg!(windows) && term.is_empty() {
+            // The Windows console has handled 24-bit colour since Windows 10
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+}
+
+/// The 16 ANSI colours as most terminals show them (xterm defaults).
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel levels of the 6x6x6 cube in the 256-colour palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Rough perceptual distance; green differences show most, blue least.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    2 * d(a.0, b.0) + 4 * d(a.1, b.1) + 3 * d(a.2, b.2)
+}
+
+/// RGB of an entry in the 256-colour palette.
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let i = index - 16;
+            (CUBE_LEVELS[(i / 36) as usize], CUBE_LEVELS[(i / 6 % 6) as usize], CUBE_LEVELS[(i % 6) as usize])
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+fn nearest_ansi16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16.iter().min_by_key(|(_, c)| distance(rgb, *c)).map(|(color, _)| *color).unwrap_or(Color::Reset)
+}
+
+fn nearest_ansi256(rgb: (u8, u8, u8)) -> Color {
+    let level = |c: u8| {
+        (0..CUBE_LEVELS.len()).min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs()).unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+    let mean = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray = 232 + (mean.saturating_sub(3) / 10).min(23) as u8;
+    let index = if distance(rgb, indexed_rgb(gray)) < distance(rgb, indexed_rgb(cube)) { gray } else { cube };
+    Color::Indexed(index)
+}
+
+/// The colour to send to a terminal of `depth` in place of `color`.
+pub fn resolve(color: Color, depth: ColorDepth) -> Color {
+    match (depth, color) {
+        (ColorDepth::TrueColor, _) => color,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => nearest_ansi256((r, g, b)),
+        (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi16((r, g, b)),
+        (ColorDepth::Ansi16, Color::Indexed(i)) if i >= 16 => nearest_ansi16(indexed_rgb(i)),
+        _ => color,
+    }
+}
+
+/// `color` with its hue turned `degrees` around the colour wheel, keeping its
+/// lightness and saturation. Only RGB colours change.
+pub fn hue_shift(color: Color, degrees: f32) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    if chroma == 0.0 {
+        return color;
+    }
+    let hue = if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let hue = (hue + degrees).rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |c: f32| ((c + min) * 255.0).round() as u8;
+    Color::Rgb(channel(r), channel(g), channel(b))
+}
+
+/// `color` moved `amount` of the way (0.0 to 1.0) towards `toward`. Only RGB colours
+/// change.
+pub fn blend(color: Color, toward: Color, amount: f32) -> Color {
+    let (Color::Rgb(r, g, b), Color::Rgb(tr, tg, tb)) = (color, toward) else {
+        return color;
+    };
+    let amount = amount.clamp(0.0, 1.0);
+    let mix = |c: u8, t: u8| (c as f32 + (t as f32 - c as f32) * amount).round() as u8;
+    Color::Rgb(mix(r, tr), mix(g, tg), mix(b, tb))
+}
+
+/// Map every colour in `buf` to what a terminal of `depth` can show.
+pub fn degrade(buf: &mut Buffer, depth: ColorDepth) {
+    if depth == ColorDepth::TrueColor {
+        return;
+    }
+    for cell in buf.content.iter_mut() {
+        cell.fg = resolve(cell.fg, depth);
+        cell.bg = resolve(cell.bg, depth);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_to_the_nearest_palette_entry() {
+        assert_eq!(resolve(Color::Rgb(250, 5, 0), ColorDepth::Ansi16), Color::LightRed);
+        assert_eq!(resolve(Color::Rgb(51, 51, 51), ColorDepth::Ansi16), Color::Black);
+        assert_eq!(resolve(Color::Rgb(95, 135, 255), ColorDepth::Ansi256), Color::Indexed(69));
+        assert_eq!(resolve(Color::Rgb(51, 51, 51), ColorDepth::Ansi256), Color::Indexed(236));
+        assert_eq!(resolve(Color::Indexed(196), ColorDepth::Ansi16), Color::LightRed);
+        assert_eq!(resolve(Color::Cyan, ColorDepth::Ansi16), Color::Cyan);
+        assert_eq!(resolve(Color::Rgb(1, 2, 3), ColorDepth::TrueColor), Color::Rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn shifting_hue_keeps_greys_and_lightness() {
+        assert_eq!(hue_shift(Color::Rgb(255, 0, 0), 120.0), Color::Rgb(0, 255, 0));
+        assert_eq!(hue_shift(Color::Rgb(0, 0, 255), -240.0), Color::Rgb(255, 0, 0));
+        assert_eq!(hue_shift(Color::Rgb(200, 100, 50), 360.0), Color::Rgb(200, 100, 50));
+        assert_eq!(hue_shift(Color::Rgb(90, 90, 90), 90.0), Color::Rgb(90, 90, 90));
+        assert_eq!(hue_shift(Color::Cyan, 90.0), Color::Cyan);
+        assert_eq!(blend(Color::Rgb(0, 100, 200), Color::Rgb(200, 100, 0), 0.5), Color::Rgb(100, 100, 100));
+    }
+
+    #[test]
+    fn parses_depths() {
+        assert_eq!("256".parse(), Ok(ColorDepth::Ansi256));
+        assert_eq!("TrueColor".parse(), Ok(ColorDepth::TrueColor));
+        assert!("8".parse::<ColorDepth>().is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! `fisherman watch --cmd <command> --paths <path>...`: runs a command, and runs it
+//! again whenever a file under the watched paths changes, reporting each run as a
+//! SUCCESS or FAILURE signal while the scene carries on. Like cargo-watch, with a
+//! fisherman in place of the scrolling output.
+
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::signals::{SharedSignal, SignalMessage};
+use crate::sprite_watch;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A command being rerun in the background, stopped if the game quits.
+pub struct CommandWatch {
+    child: Arc<Mutex<Option<Child>>>,
+}
+
+impl CommandWatch {
+    /// Run `command` through the shell from a background thread, then again each time
+    /// something under `paths` changes, sending the result of each run to `signal`.
+    pub fn spawn(command: String, paths: Vec<PathBuf>, signal: SharedSignal) -> CommandWatch {
+        let child = Arc::new(Mutex::new(None));
+        let shared = Arc::clone(&child);
+        thread::spawn(move || {
+            let push = |message| signal.lock().unwrap().push_back(message);
+            let mut seen = sprite_watch::snapshot_of(&paths, watched);
+            loop {
+                push(SignalMessage::Banner(format!("Running {}", command)));
+                push(run(&command, &shared));
+                // Changes made while it ran count too, so wait from the snapshot before it
+                loop {
+                    thread::sleep(POLL_INTERVAL);
+                    let current = sprite_watch::snapshot_of(&paths, watched);
+                    if current != seen {
+                        seen = current;
+                        break;
+                    }
+                }
+            }
+        });
+        CommandWatch { child }
+    }
+
+    /// Stop the command if it's running.
+    pub fn kill(&self) {
+        if let Some(ref mut child) = *self.child.lock().unwrap() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Everything but hidden files and directories and cargo's `target`, which a build
+/// writes to and which would set the command off again.
+fn watched(path: &Path, is_dir: bool) -> bool {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    !(name.starts_with('.') || is_dir && name == "target")
+}
+
+/// `command` run through the platform's shell.
+pub fn shell(command: &str) -> Command {
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+    let mut shell = Command::new(shell);
+    shell.args([flag, command]);
+    shell
+}
+
+/// Run `command` once, keeping it in `slot` while it runs, and describe how it went.
+/// A failure quotes the last line it wrote to stderr.
+fn run(command: &str, slot: &Mutex<Option<Child>>) -> SignalMessage {
+    let mut child = match shell(command).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(e) => return SignalMessage::Failure(format!("could not run {}: {}", command, e)),
+    };
+    let stderr = child.stderr.take();
+    *slot.lock().unwrap() = Some(child);
+    let last_line = stderr
+        .map(BufReader::new)
+        .into_iter()
+        .flat_map(BufRead::lines)
+        .map_while(Result::ok)
+        .filter(|line| !line.trim().is_empty())
+        .last();
+    let status = slot.lock().unwrap().take().map(|mut child| child.wait());
+    match status {
+        Some(Ok(status)) if status.success() => SignalMessage::Success(format!("{} passed", command)),
+        Some(Ok(status)) => {
+            let code = status.code().map_or("a signal".to_string(), |code| code.to_string());
+            match last_line {
+                Some(line) => SignalMessage::Failure(format!("{} exited with {}: {}", command, code, line.trim())),
+                None => SignalMessage::Failure(format!("{} exited with {}", command, code)),
+            }
+        }
+        _ => SignalMessage::Failure(format!("{} was stopped", command)),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_run_reports_its_outcome() {
+        let slot = Mutex::new(None);
+        assert_eq!(run("true", &slot), SignalMessage::Success("true passed".to_string()));
+        assert_eq!(
+            run("echo building >&2; echo 'test failed' >&2; exit 3", &slot),
+            SignalMessage::Failure("echo building >&2; echo 'test failed' >&2; exit 3 exited with 3: test failed".to_string())
+        );
+        assert!(slot.lock().unwrap().is_none());
+        assert!(watched(Path::new("src/main.rs"), false));
+        assert!(!watched(Path::new("target"), true));
+        assert!(!watched(Path::new(".git"), true));
+    }
+}
@@ -0,0 +1,151 @@
+//! What the conditions do to the fishing. The weather, the time of day, the bait on the
+//! hook and the moon each make fish turn up or bite more or less often, as a
+//! [`Modifier`]; [`Conditions`] multiplies together those in force for the spawn and
+//! bite chances, and [`ConditionsPanel`] lists them along the top of the sky.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::Widget;
+
+use crate::economy::Bait;
+use crate::moon;
+use crate::weather::Weather;
+
+/// One condition's say in how often fish turn up and bite, as multipliers: 1.0 makes
+/// no difference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Modifier {
+    pub name: &'static str,
+    pub spawn: f64,
+    pub bite: f64,
+}
+
+impl Modifier {
+    const fn new(name: &'static str, spawn: f64, bite: f64) -> Modifier {
+        Modifier { name, spawn, bite }
+    }
+
+    /// `light rain: fish +20% bites +30%`, leaving out what it doesn't change.
+    pub fn describe(&self) -> String {
+        let mut text = format!("{}:", self.name);
+        for (what, factor) in [("fish", self.spawn), ("bites", self.bite)] {
+            let percent = ((factor - 1.0) * 100.0).round() as i32;
+            if percent != 0 {
+                text += &format!(" {} {:+}%", what, percent);
+            }
+        }
+        text
+    }
+}
+
+/// Fish bite in light rain and hang back in a storm.
+fn weather_modifier(weather: Weather) -> Option<Modifier> {
+    match weather {
+        Weather::Clear => None,
+        Weather::Cloudy => Some(Modifier::new("overcast", 1.0, 1.1)),
+        Weather::Rain => Some(Modifier::new("light rain", 1.2, 1.3)),
+        Weather::Storm => Some(Modifier::new("storm", 0.8, 0.7)),
+    }
+}
+
+/// Fish feed at dawn and dusk and laze in the midday sun. `time_of_day` is 0.0 at
+/// midnight and 0.5 at noon.
+fn time_modifier(time_of_day: f32) -> Option<Modifier> {
+    match time_of_day.rem_euclid(1.0) * 24.0 {
+        hour if (5.0..8.0).contains(&hour) => Some(Modifier::new("dawn", 1.2, 1.4)),
+        hour if (11.0..15.0).contains(&hour) => Some(Modifier::new("midday sun", 1.0, 0.8)),
+        hour if (18.0..21.0).contains(&hour) => Some(Modifier::new("dusk", 1.1, 1.3)),
+        _ => None,
+    }
+}
+
+/// Bait in the water draws fish in, worms most of all.
+fn bait_modifier(bait: Option<Bait>) -> Option<Modifier> {
+    match bait? {
+        Bait::Worms => Some(Modifier::new("worms", 1.2, 1.0)),
+        Bait::Lure => Some(Modifier::new("lure", 1.1, 1.0)),
+    }
+}
+
+/// Fish bite best under a full moon and worst under a new one.
+fn moon_modifier(moon_phase: f32) -> Option<Modifier> {
+    match moon::phase_name(moon_phase) {
+        "full moon" => Some(Modifier::new("full moon", 1.0, 1.2)),
+        "new moon" => Some(Modifier::new("new moon", 1.0, 0.9)),
+        _ => None,
+    }
+}
+
+/// The modifiers in force.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Conditions {
+    modifiers: Vec<Modifier>,
+}
+
+impl Conditions {
+    /// The conditions in `weather` at `time_of_day` (0.0 at midnight), with `bait` on
+    /// the hook and the moon at `moon_phase` (0.0 new, 0.5 full).
+    pub fn new(weather: Weather, time_of_day: f32, bait: Option<Bait>, moon_phase: f32) -> Conditions {
+        let modifiers = [weather_modifier(weather), time_modifier(time_of_day), bait_modifier(bait), moon_modifier(moon_phase)];
+        Conditions { modifiers: modifiers.into_iter().flatten().collect() }
+    }
+
+    pub fn modifiers(&self) -> &[Modifier] {
+        &self.modifiers
+    }
+
+    /// How much more often than usual fish turn up.
+    pub fn spawn(&self) -> f64 {
+        self.modifiers.iter().map(|m| m.spawn).product()
+    }
+
+    /// How much more likely than usual a fish at the hook is to take it.
+    pub fn bite(&self) -> f64 {
+        self.modifiers.iter().map(|m| m.bite).product()
+    }
+}
+
+/// The modifiers in force, on one line.
+pub struct ConditionsPanel<'a> {
+    pub conditions: &'a Conditions,
+    pub style: Style,
+}
+
+impl Widget for ConditionsPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let text: Vec<String> = self.conditions.modifiers().iter().map(Modifier::describe).collect();
+        if area.height > 0 {
+            buf.set_stringn(area.x, area.y, text.join(" · "), area.width as usize, self.style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions_multiply_into_the_spawn_and_bite_chances() {
+        let calm = Conditions::new(Weather::Clear, 0.0, None, 0.25);
+        assert!(calm.modifiers().is_empty());
+        assert_eq!((calm.spawn(), calm.bite()), (1.0, 1.0));
+
+        // Light rain at dawn, with worms on the hook under a full moon
+        let dawn = Conditions::new(Weather::Rain, 6.0 / 24.0, Some(Bait::Worms), 0.5);
+        let names: Vec<&str> = dawn.modifiers().iter().map(|m| m.name).collect();
+        assert_eq!(names, ["light rain", "dawn", "worms", "full moon"]);
+        assert!((dawn.spawn() - 1.2 * 1.2 * 1.2).abs() < 1e-9);
+        assert!((dawn.bite() - 1.3 * 1.4 * 1.2).abs() < 1e-9);
+        assert_eq!(dawn.modifiers()[0].describe(), "light rain: fish +20% bites +30%");
+        assert_eq!(dawn.modifiers()[2].describe(), "worms: fish +20%");
+
+        let storm = Conditions::new(Weather::Storm, 0.5, None, 0.0);
+        assert!(storm.spawn() < 1.0 && storm.bite() < 0.7);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 60, 1));
+        ConditionsPanel { conditions: &dawn, style: Style::default() }.render(buf.area, &mut buf);
+        let row: String = (0..60).map(|x| buf[(x, 0)].symbol().to_string()).collect();
+        assert_eq!(row, "light rain: fish +20% bites +30% · dawn: fish +20% bites +40");
+    }
+}
@@ -0,0 +1,312 @@
+//! The optional config file (`--config`, by default `fisherman/config.toml` in the
+//! user's config directory). It is read as a small subset of TOML: `[section]` headers
+//! and `key = value` lines, where a value is a string, number, boolean or an array of
+//! those, and `#` starts a comment.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The strings in a string or an array of strings.
+    pub fn as_strings(&self) -> Option<Vec<&str>> {
+        match self {
+            Value::String(s) => Some(vec![s]),
+            Value::Array(items) => items.iter().map(Value::as_str).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// The `key = value` lines of one section.
+pub type Table = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigFile {
+    sections: BTreeMap<String, Table>,
+}
+
+/// The user's fisherman config directory: `$XDG_CONFIG_HOME/fisherman`, falling back
+/// to `~/.config` and, on Windows, `%APPDATA%`.
+pub fn config_dir() -> PathBuf {
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .unwrap_or_else(env::temp_dir);
+    dir.join("fisherman")
+}
+
+/// Default config location, `config.toml` in [`config_dir`].
+pub fn default_config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+impl ConfigFile {
+    /// Read a config file; `Ok(None)` when there is none.
+    pub fn load(path: &Path) -> io::Result<Option<ConfigFile>> {
+        match fs::read_to_string(path) {
+            Ok(text) => ConfigFile::parse(&text)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<ConfigFile, String> {
+        let mut config = ConfigFile::default();
+        let mut section = String::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fail = |msg: &str| format!("line {}: {}", number + 1, msg);
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name.strip_suffix(']').ok_or_else(|| fail("unclosed section header"))?;
+                section = name.trim().to_string();
+                config.sections.entry(section.clone()).or_default();
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| fail("expected key = value"))?;
+            let key = key.trim().trim_matches('"');
+            if key.is_empty() {
+                return Err(fail("missing key"));
+            }
+            let (value, rest) = parse_value(value.trim()).map_err(|e| fail(&e))?;
+            if !rest.trim().is_empty() {
+                return Err(fail(&format!("unexpected '{}' after value", rest.trim())));
+            }
+            config.sections.entry(section.clone()).or_default().insert(key.to_string(), value);
+        }
+        Ok(config)
+    }
+
+    pub fn section(&self, name: &str) -> Option<&Table> {
+        self.sections.get(name)
+    }
+}
+
+/// Set `key` in `[section]` of the config file at `path` to `value`, creating the file
+/// if there is none. The rest of the file, comments and all, is left as it was.
+pub fn write_value(path: &Path, section: &str, key: &str, value: &Value) -> io::Result<()> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, set_value(&text, section, key, value))
+}
+
+/// `text` with `key` in `[section]` set to `value`: the line setting it now is
+/// replaced, keeping any comment after it, or else a line is added at the end of the
+/// section, adding the section at the end of the file if need be.
+fn set_value(text: &str, section: &str, key: &str, value: &Value) -> String {
+    let line = format!("{} = {}", key, format_value(value));
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let mut current = String::new();
+    // The last line of the section (its header, or the last setting in it) and the
+    // line setting the key, if any
+    let mut end = None;
+    let mut found = None;
+    for (i, text) in lines.iter().enumerate() {
+        let content = strip_comment(text).trim();
+        if let Some(name) = content.strip_prefix('[').and_then(|name| name.strip_suffix(']')) {
+            current = name.trim().to_string();
+            if current == section {
+                end = Some(i);
+            }
+            continue;
+        }
+        if current != section || content.is_empty() {
+            continue;
+        }
+        end = Some(i);
+        if content.split_once('=').is_some_and(|(k, _)| k.trim().trim_matches('"') == key) {
+            found = Some(i);
+        }
+    }
+    match (found, end) {
+        (Some(i), _) => {
+            let old = &lines[i];
+            let indent = &old[..old.len() - old.trim_start().len()];
+            let comment = &old[strip_comment(old).len()..];
+            lines[i] = if comment.is_empty() { format!("{}{}", indent, line) } else { format!("{}{} {}", indent, line, comment) };
+        }
+        (None, Some(i)) => lines.insert(i + 1, line),
+        // Top-level keys go before the first section
+        (None, None) if section.is_empty() => lines.insert(0, line),
+        (None, None) => {
+            if lines.last().is_some_and(|last| !last.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+            lines.push(line);
+        }
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// `value` as it would be written in the file.
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\t', "\\t")),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => format!("[{}]", items.iter().map(format_value).collect::<Vec<_>>().join(", ")),
+    }
+}
+
+/// Cut a `#` comment off a line, leaving any `#` inside a string alone.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Parse one value off the front of `s`, returning it and whatever follows.
+fn parse_value(s: &str) -> Result<(Value, &str), String> {
+    if let Some(rest) = s.strip_prefix('"') {
+        let mut out = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Ok((Value::String(out), &rest[i + 1..])),
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => out.push('\n'),
+                    Some((_, 't')) => out.push('\t'),
+                    Some((_, c @ ('"' | '\\'))) => out.push(c),
+                    _ => return Err("unknown escape in string".to_string()),
+                },
+                c => out.push(c),
+            }
+        }
+        return Err("unterminated string".to_string());
+    }
+    if let Some(rest) = s.strip_prefix('\'') {
+        let end = rest.find('\'').ok_or("unterminated string")?;
+        return Ok((Value::String(rest[..end].to_string()), &rest[end + 1..]));
+    }
+    if let Some(mut rest) = s.strip_prefix('[') {
+        let mut items = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(']') {
+                return Ok((Value::Array(items), after));
+            }
+            let (item, after) = parse_value(rest)?;
+            items.push(item);
+            rest = after.trim_start();
+            if let Some(after) = rest.strip_prefix(',') {
+                rest = after;
+            } else if !rest.starts_with(']') {
+                return Err("expected ',' or ']' in array".to_string());
+            }
+        }
+    }
+    let end = s.find([',', ']']).unwrap_or(s.len());
+    let (word, rest) = s.split_at(end);
+    let word = word.trim();
+    match word {
+        "true" => Ok((Value::Bool(true), rest)),
+        "false" => Ok((Value::Bool(false), rest)),
+        _ => word
+            .replace('_', "")
+            .parse()
+            .map(|n| (Value::Number(n), rest))
+            .map_err(|_| format!("invalid value '{}'", word)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sections_and_values() {
+        let config = ConfigFile::parse(
+            r#"
+            # top-level comment
+            [keys]
+            reel_up = ["k", "Up"]   # vim style
+            quit = 'q'
+            [other]
+            hash = "a # b"
+            speed = 1_000
+            on = true
+            "#,
+        )
+        .unwrap();
+        let keys = config.section("keys").unwrap();
+        assert_eq!(
+            keys["reel_up"],
+            Value::Array(vec![Value::String("k".into()), Value::String("Up".into())])
+        );
+        assert_eq!(keys["quit"].as_strings(), Some(vec!["q"]));
+        let other = config.section("other").unwrap();
+        assert_eq!(other["hash"], Value::String("a # b".into()));
+        assert_eq!(other["speed"], Value::Number(1000.0));
+        assert_eq!(other["on"], Value::Bool(true));
+    }
+
+    #[test]
+    fn settings_are_written_back_around_whatever_else_is_there() {
+        let text = "# mine\n[keys]\nquit = 'x'\n\n[theme]\nbase = \"night\"  # dark\nwave = \"blue\"\n";
+        let text = set_value(text, "theme", "base", &Value::String("arctic".into()));
+        let text = set_value(&text, "keys", "help", &Value::String("h".into()));
+        let text = set_value(&text, "settings", "lantern", &Value::Bool(true));
+        assert_eq!(
+            text,
+            "# mine\n[keys]\nquit = 'x'\nhelp = \"h\"\n\n[theme]\nbase = \"arctic\" # dark\nwave = \"blue\"\n\n[settings]\nlantern = true\n"
+        );
+        let config = ConfigFile::parse(&text).unwrap();
+        assert_eq!(config.section("settings").unwrap()["lantern"], Value::Bool(true));
+        assert_eq!(set_value("", "", "n", &Value::Number(3.0)), "n = 3\n");
+    }
+
+    #[test]
+    fn reports_the_bad_line() {
+        let err = ConfigFile::parse("[keys]\nquit = \"q\"\ncast\n").unwrap_err();
+        assert!(err.starts_with("line 3:"), "{}", err);
+        assert!(ConfigFile::parse("x = \"open").is_err());
+        assert!(ConfigFile::parse("x = [1, 2").is_err());
+    }
+}
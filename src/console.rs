@@ -0,0 +1,306 @@
+//! The `:` console, for development and demos: a line along the foot of the screen
+//! that takes commands such as `spawn shark 3`, `weather storm`, `signal success
+//! hello`, `set density 0.9` or `pack ocean-night`. Tab completes the command and what it's given, Up and
+//! Down go back through the commands run, and Esc closes it.
+
+use std::str::FromStr;
+
+use crossterm::event::KeyCode;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Clear, Widget};
+
+use crate::fish;
+use crate::pack;
+use crate::signals::SignalMessage;
+use crate::weather::Weather;
+
+const COMMANDS: [&str; 6] = ["help", "pack", "set", "signal", "spawn", "weather"];
+const SIGNALS: [&str; 6] = ["success", "failure", "progress", "info", "banner", "heartbeat"];
+const SETTINGS: [&str; 3] = ["density", "max_fish", "coins"];
+/// The most fish one `spawn` adds.
+const MAX_SPAWN: u32 = 20;
+const MAX_HISTORY: usize = 50;
+pub const HELP: &str = "spawn <fish> [count] | weather <kind> | signal <kind> [text] | set density|max_fish|coins <value> | pack <name>";
+
+/// A setting `set` changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    Density(f64),
+    MaxFish(usize),
+    Coins(u32),
+}
+
+/// A command typed into the console.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Add `count` fish of the named species.
+    Spawn { species: String, count: u32 },
+    Weather(Weather),
+    /// Take a signal in as if it had come from outside.
+    Signal(SignalMessage),
+    Set(Setting),
+    /// Swap in the sprites of the named asset pack.
+    Pack(String),
+    Help,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let Some(name) = words.next() else {
+            return Err("type a command, or help".to_string());
+        };
+        let command = match name.to_ascii_lowercase().as_str() {
+            "spawn" => {
+                let species = words.next().ok_or("spawn which fish?")?.to_string();
+                let count = match words.next() {
+                    Some(count) => count
+                        .parse()
+                        .ok()
+                        .filter(|count| (1..=MAX_SPAWN).contains(count))
+                        .ok_or_else(|| format!("invalid count '{}' (expected 1 to {})", count, MAX_SPAWN))?,
+                    None => 1,
+                };
+                Command::Spawn { species, count }
+            }
+            "weather" => Command::Weather(words.next().ok_or("weather: clear, cloudy, rain or storm?")?.parse()?),
+            "signal" => {
+                let kind = words.next().ok_or_else(|| format!("signal which? ({})", SIGNALS.join(", ")))?;
+                let text = words.by_ref().collect::<Vec<_>>().join(" ");
+                Command::Signal(parse_signal(kind, text)?)
+            }
+            "set" => {
+                let setting = words.next().ok_or_else(|| format!("set which? ({})", SETTINGS.join(", ")))?;
+                let value = words.next().ok_or_else(|| format!("set {} to what?", setting))?;
+                let number: f64 = value.parse().map_err(|_| format!("invalid value '{}' (expected a number)", value))?;
+                Command::Set(match setting.to_ascii_lowercase().as_str() {
+                    "density" => Setting::Density(fish::parse_density(number)?),
+                    "max_fish" => Setting::MaxFish(fish::parse_max_fish(number)?),
+                    "coins" if number.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&number) => Setting::Coins(number as u32),
+                    "coins" => return Err(format!("coins must be a whole number, not {}", value)),
+                    other => return Err(format!("unknown setting '{}' (expected one of {})", other, SETTINGS.join(", "))),
+                })
+            }
+            "pack" => Command::Pack(words.next().ok_or("pack which? (a name or a path)")?.to_string()),
+            "help" => Command::Help,
+            other => return Err(format!("unknown command '{}' (try help)", other)),
+        };
+        match words.next() {
+            Some(extra) => Err(format!("unexpected '{}'", extra)),
+            None => Ok(command),
+        }
+    }
+}
+
+/// `signal <kind> [text]` as the signal it fakes. A progress signal's text starts
+/// with its percent.
+fn parse_signal(kind: &str, text: String) -> Result<SignalMessage, String> {
+    Ok(match kind.to_ascii_lowercase().as_str() {
+        "success" => SignalMessage::Success(text),
+        "failure" => SignalMessage::Failure(text),
+        "info" => SignalMessage::Info(text),
+        "banner" => SignalMessage::Banner(text),
+        "heartbeat" => SignalMessage::Heartbeat,
+        "progress" => {
+            let (percent, label) = text.split_once(' ').unwrap_or((&text, ""));
+            let percent = percent
+                .parse()
+                .ok()
+                .filter(|percent| *percent <= 100)
+                .ok_or_else(|| format!("invalid percent '{}' (expected 0 to 100)", percent))?;
+            SignalMessage::Progress { percent, label: label.to_string() }
+        }
+        other => return Err(format!("unknown signal '{}' (expected one of {})", other, SIGNALS.join(", "))),
+    })
+}
+
+/// The ways the word being typed at the end of `line` could go on: a command, or
+/// what the command takes first.
+pub fn completions(line: &str, species: &[String]) -> Vec<String> {
+    let mut words: Vec<&str> = line.split_whitespace().collect();
+    if line.is_empty() || line.ends_with(char::is_whitespace) {
+        words.push("");
+    }
+    let typing = words.last().copied().unwrap_or("").to_ascii_lowercase();
+    let candidates: Vec<String> = match words.len() {
+        1 => COMMANDS.iter().map(|c| c.to_string()).collect(),
+        2 => match words[0].to_ascii_lowercase().as_str() {
+            "spawn" => species.iter().map(|s| s.to_lowercase()).collect(),
+            "weather" => Weather::ALL.iter().map(|w| w.as_str().to_lowercase()).collect(),
+            "signal" => SIGNALS.iter().map(|s| s.to_string()).collect(),
+            "set" => SETTINGS.iter().map(|s| s.to_string()).collect(),
+            "pack" => pack::names(&pack::packs_dir()),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    candidates.into_iter().filter(|candidate| candidate.starts_with(&typing)).collect()
+}
+
+/// What the console made of a key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleKey {
+    Typing,
+    /// Enter on a command, to be run.
+    Run(String),
+    Close,
+}
+
+/// The line being typed, what the last command said and the commands run before.
+#[derive(Debug, Clone, Default)]
+pub struct Console {
+    input: String,
+    output: Option<String>,
+    history: Vec<String>,
+    /// How far back Up has gone, counting from 1 for the last command run.
+    recalled: usize,
+}
+
+impl Console {
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Show `text` above the line, such as what a command did.
+    pub fn say(&mut self, text: impl Into<String>) {
+        self.output = Some(text.into());
+    }
+
+    /// Take a key, completing against the `species` names on Tab.
+    pub fn key(&mut self, code: KeyCode, species: &[String]) -> ConsoleKey {
+        match code {
+            KeyCode::Esc => return ConsoleKey::Close,
+            KeyCode::Enter => {
+                let line = std::mem::take(&mut self.input).trim().to_string();
+                self.recalled = 0;
+                if line.is_empty() {
+                    return ConsoleKey::Close;
+                }
+                self.history.retain(|earlier| *earlier != line);
+                self.history.push(line.clone());
+                let excess = self.history.len().saturating_sub(MAX_HISTORY);
+                self.history.drain(..excess);
+                return ConsoleKey::Run(line);
+            }
+            KeyCode::Tab => self.complete(species),
+            KeyCode::Up if self.recalled < self.history.len() => {
+                self.recalled += 1;
+                self.input = self.history[self.history.len() - self.recalled].clone();
+            }
+            KeyCode::Down if self.recalled > 0 => {
+                self.recalled -= 1;
+                self.input = match self.recalled {
+                    0 => String::new(),
+                    back => self.history[self.history.len() - back].clone(),
+                };
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() => self.input.push(c),
+            _ => {}
+        }
+        ConsoleKey::Typing
+    }
+
+    /// Finish the word being typed if only one thing fits, or as far as everything
+    /// that fits agrees, listing them.
+    fn complete(&mut self, species: &[String]) {
+        let options = completions(&self.input, species);
+        let Some(first) = options.first() else {
+            return;
+        };
+        let shared = options.iter().fold(first.chars().count(), |len, option| {
+            first.chars().zip(option.chars()).take_while(|(a, b)| a == b).count().min(len)
+        });
+        let start = self.input.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        self.input.truncate(start);
+        self.input.extend(first.chars().take(shared));
+        if options.len() == 1 {
+            self.input.push(' ');
+        } else {
+            self.say(options.join("  "));
+        }
+    }
+}
+
+/// The console along the foot of the screen, over the status bar.
+pub struct ConsoleLine<'a> {
+    pub console: &'a Console,
+}
+
+impl Widget for ConsoleLine<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+        let rows = (if self.console.output.is_some() { 2 } else { 1 }).min(area.height);
+        let rect = Rect::new(area.x, area.bottom() - rows, area.width, rows);
+        Clear.render(rect, buf);
+        buf.set_style(rect, Style::default().bg(Color::Black));
+        if let Some(ref output) = self.console.output
+            && rows == 2
+        {
+            buf.set_stringn(rect.x + 1, rect.y, output, rect.width.saturating_sub(2) as usize, Style::default().fg(Color::Gray));
+        }
+        // The end of a line longer than the screen, where the typing is
+        let line = format!(":{}▏", self.console.input);
+        let room = rect.width.saturating_sub(1) as usize;
+        let skip = line.chars().count().saturating_sub(room);
+        let shown: String = line.chars().skip(skip).collect();
+        buf.set_stringn(rect.x, rect.bottom() - 1, shown, room, Style::default().fg(Color::White));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_parse_and_complete() {
+        assert_eq!("spawn shark 3".parse(), Ok(Command::Spawn { species: "shark".to_string(), count: 3 }));
+        assert_eq!("weather Storm".parse(), Ok(Command::Weather(Weather::Storm)));
+        assert_eq!("signal success hello there".parse(), Ok(Command::Signal(SignalMessage::Success("hello there".to_string()))));
+        assert_eq!(
+            "signal progress 40 building docs".parse(),
+            Ok(Command::Signal(SignalMessage::Progress { percent: 40, label: "building docs".to_string() }))
+        );
+        assert_eq!("set density 0.9".parse(), Ok(Command::Set(Setting::Density(0.9))));
+        assert!("spawn shark 300".parse::<Command>().unwrap_err().contains("count"));
+        assert!("weather hail".parse::<Command>().is_err());
+        assert!("set density 0.9 now".parse::<Command>().unwrap_err().contains("now"));
+        assert!("fly".parse::<Command>().unwrap_err().contains("help"));
+        assert_eq!("pack ocean-night".parse(), Ok(Command::Pack("ocean-night".to_string())));
+
+        let species = ["Goby".to_string(), "Goldfish".to_string(), "Shark".to_string()];
+        assert_eq!(completions("sp", &species), ["spawn"]);
+        assert_eq!(completions("s", &species), ["set", "signal", "spawn"]);
+        assert_eq!(completions("spawn g", &species), ["goby", "goldfish"]);
+        assert_eq!(completions("weather ", &species).len(), 4);
+        assert!(completions("spawn shark ", &species).is_empty());
+        assert!(completions("pack ocean", &species).contains(&"ocean-night".to_string()));
+
+        let mut console = Console::default();
+        for c in "sp".chars() {
+            console.key(KeyCode::Char(c), &species);
+        }
+        console.key(KeyCode::Tab, &species);
+        console.key(KeyCode::Char('g'), &species);
+        console.key(KeyCode::Tab, &species);
+        assert_eq!(console.input(), "spawn go");
+        assert_eq!(console.output.as_deref(), Some("goby  goldfish"));
+        console.key(KeyCode::Char('b'), &species);
+        console.key(KeyCode::Tab, &species);
+        assert_eq!(console.key(KeyCode::Enter, &species), ConsoleKey::Run("spawn goby".to_string()));
+
+        // Up brings the last command back, and Enter on nothing closes
+        console.key(KeyCode::Up, &species);
+        assert_eq!(console.input(), "spawn goby");
+        console.key(KeyCode::Down, &species);
+        assert_eq!(console.key(KeyCode::Enter, &species), ConsoleKey::Close);
+    }
+}
@@ -1,13 +1,24 @@
 use std::io;
 use std::fs;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use serde::Deserialize;
-use ratatui::style::Color;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Position, Rect};
+use ratatui::style::{Color, Style};
 use ratatui::text::{Span, Line, Text};
 use include_dir::{include_dir, Dir};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::fish::{DEFAULT_FRAME_DURATION, SpeciesTraits};
+use crate::fishing_game::SizeDistribution;
 
 static FISH_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/fish");
 static MOON_CSV: &str = include_str!("../moon.csv");
+static CRAB_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/crab");
+/// How long each crab frame shows unless its file name says otherwise.
+const CRAB_FRAME_DURATION: Duration = Duration::from_millis(250);
 
 fn de_hex_to_color<'de, D>(deserializer: D) -> Result<Color, D::Error>
 where
@@ -24,95 +35,187 @@ where
     Ok(Color::Rgb(r, g, b))
 }
 
+/// Optional colour column: an empty cell leaves the colour unset, so whatever is
+/// behind the sprite shows through.
+fn de_optional_hex_to_color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+    if s.trim().is_empty() {
+        return Ok(None);
+    }
+    de_hex_to_color(serde::de::value::StringDeserializer::new(s)).map(Some)
+}
+
+type CellMap = HashMap<(u32, u32), (String, Style)>;
+
 #[derive(Debug, Deserialize)]
 struct CellRow {
     #[serde(rename = "X")] pub x: u32,
     #[serde(rename = "Y")] pub y: u32,
     #[serde(rename = "ASCII")] pub ascii: String,
     #[serde(rename = "Foreground", deserialize_with = "de_hex_to_color")] pub foreground: Color,
+    #[serde(rename = "Background", default, deserialize_with = "de_optional_hex_to_color")] pub background: Option<Color>,
 }
 
 pub fn load_csv_frame(path: &str) -> io::Result<Text<'static>> {
     let content = fs::read_to_string(path)?;
-    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    load_csv_frame_from_string(&content)
+}
 
-    let mut cells: HashMap<(u32, u32), (char, (u8, u8, u8))> = HashMap::new();
-    let mut max_x = 0;
-    let mut max_y = 0;
+/// One row of a sprite CSV, as edited by `fisherman edit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpriteCell {
+    pub x: u32,
+    pub y: u32,
+    /// One character and any combining marks on it. Wide characters cover the next
+    /// column too.
+    pub symbol: String,
+    pub fg: Color,
+    pub bg: Option<Color>,
+}
 
+pub fn read_sprite_cells(content: &str) -> io::Result<Vec<SpriteCell>> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let mut cells = Vec::new();
     for result in reader.deserialize() {
         let row: CellRow = result.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-        let x = row.x;
-        let y = row.y;
-        let ch = row.ascii.chars().next().unwrap_or(' ');
-
-        let fg_rgb = match row.foreground {
-            Color::Rgb(r, g, b) => (r, g, b),
-            _ => (255, 255, 255),
-        };
-
-        max_x = max_x.max(x);
-        max_y = max_y.max(y);
-        cells.insert((x, y), (ch, fg_rgb));
+        let symbol = cell_symbol(&row.ascii);
+        cells.push(SpriteCell { x: row.x, y: row.y, symbol, fg: row.foreground, bg: row.background });
     }
+    Ok(cells)
+}
 
-    let mut rows: Vec<Line> = Vec::with_capacity((max_y as usize) + 1);
-    for y in 0..=max_y {
-        let mut span_row: Vec<Span> = Vec::with_capacity((max_x as usize) + 1);
-        for x in 0..=max_x {
-            if let Some((ch, fg)) = cells.get(&(x, y)) {
-                let styled = Span::styled(
-                    ch.to_string(),
-                    ratatui::style::Style::default()
-                        .fg(Color::Rgb(fg.0, fg.1, fg.2))
-                );
-                span_row.push(styled);
-            } else {
-                span_row.push(Span::raw(" "));
+/// The CSV text for `cells`, readable by [`read_sprite_cells`] and the frame loaders.
+/// Colours other than RGB are written as white, since the format only has hex codes.
+/// The first character of `s` with the combining marks that follow it, or a space if
+/// that wouldn't take up a column.
+fn cell_symbol(s: &str) -> String {
+    let mut chars = s.chars();
+    let Some(first) = chars.next() else {
+        return " ".to_string();
+    };
+    let symbol: String = std::iter::once(first).chain(chars.take_while(|c| c.width() == Some(0))).collect();
+    if symbol.width() == 0 { " ".to_string() } else { symbol }
+}
+
+/// The cells of `line` in order: each character with the combining marks on it, and its
+/// style. A wide character is one cell two columns across.
+pub fn line_cells(line: &Line) -> Vec<(String, Style)> {
+    let mut cells: Vec<(String, Style)> = Vec::new();
+    for span in &line.spans {
+        for ch in span.content.chars() {
+            match cells.last_mut() {
+                Some((symbol, _)) if ch.width() == Some(0) => symbol.push(ch),
+                _ => cells.push((ch.to_string(), span.style)),
             }
         }
-        rows.push(Line::from(span_row));
     }
+    cells
+}
 
-    Ok(Text::from(rows))
+/// `text` blown up `factor` times: every cell repeated across and every row repeated
+/// down.
+pub fn scale_text(text: &Text, factor: u16) -> Text<'static> {
+    let factor = factor as usize;
+    let lines = text.lines.iter().flat_map(|line| {
+        let spans: Vec<Span<'static>> = line_cells(line)
+            .into_iter()
+            .map(|(symbol, style)| Span::styled(symbol.repeat(factor), style))
+            .collect();
+        std::iter::repeat_n(Line::from(spans), factor)
+    });
+    Text::from(lines.collect::<Vec<_>>())
 }
 
-pub fn load_csv_frame_from_string(content: &str) -> io::Result<Text<'static>> {
-    let mut reader = csv::Reader::from_reader(content.as_bytes());
+/// `text` stretched or squeezed to `width` by `height` cells, each cell taken from the
+/// nearest one in the original.
+pub fn resize_text(text: &Text, width: u16, height: u16) -> Text<'static> {
+    let rows: Vec<Vec<(String, Style)>> = text.lines.iter().map(line_cells).collect();
+    let from_height = rows.len().max(1);
+    let lines = (0..height as usize).map(|y| {
+        let row = rows.get(y * from_height / height.max(1) as usize).map(Vec::as_slice).unwrap_or_default();
+        let spans: Vec<Span<'static>> = (0..width as usize)
+            .filter_map(|x| row.get(x * row.len() / width.max(1) as usize))
+            .map(|(symbol, style)| Span::styled(symbol.clone(), *style))
+            .collect();
+        Line::from(spans)
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
+
+/// `text` with every foreground and background colour passed through `f`.
+pub fn recolor(text: &Text, f: impl Fn(Color) -> Color) -> Text<'static> {
+    let lines = text.lines.iter().map(|line| {
+        let spans: Vec<Span<'static>> = line
+            .spans
+            .iter()
+            .map(|span| {
+                let mut style = span.style;
+                style.fg = style.fg.map(&f);
+                style.bg = style.bg.map(&f);
+                Span::styled(span.content.to_string(), style)
+            })
+            .collect();
+        Line::from(spans)
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
+
+/// A species' frames, facing both ways, recoloured with `f`. Durations are kept.
+pub fn recolor_species(frames: &SpeciesFrames, f: impl Fn(Color) -> Color) -> SpeciesFrames {
+    let recolor_all = |frames: &AnimationFrames| frames.iter().map(|(text, d)| (recolor(text, &f), *d)).collect();
+    (recolor_all(&frames.0), recolor_all(&frames.1))
+}
 
-    let mut cells: HashMap<(u32, u32), (char, (u8, u8, u8))> = HashMap::new();
+pub fn write_sprite_cells(cells: &[SpriteCell]) -> String {
+    let hex = |color: Color| match color {
+        Color::Rgb(r, g, b) => format!("#{:02X}{:02X}{:02X}", r, g, b),
+        _ => "#FFFFFF".to_string(),
+    };
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let _ = writer.write_record(["X", "Y", "ASCII", "Foreground", "Background"]);
+    for cell in cells {
+        let _ = writer.write_record([
+            cell.x.to_string(),
+            cell.y.to_string(),
+            cell.symbol.clone(),
+            hex(cell.fg),
+            cell.bg.map(hex).unwrap_or_default(),
+        ]);
+    }
+    String::from_utf8(writer.into_inner().unwrap_or_default()).unwrap_or_default()
+}
+
+pub fn load_csv_frame_from_string(content: &str) -> io::Result<Text<'static>> {
+    let mut cells: CellMap = HashMap::new();
     let mut max_x = 0;
     let mut max_y = 0;
 
-    for result in reader.deserialize() {
-        let row: CellRow = result.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-        let x = row.x;
-        let y = row.y;
-        let ch = row.ascii.chars().next().unwrap_or(' ');
-
-        let fg_rgb = match row.foreground {
-            Color::Rgb(r, g, b) => (r, g, b),
-            _ => (255, 255, 255),
-        };
+    for cell in read_sprite_cells(content)? {
+        let mut style = Style::default().fg(cell.fg);
+        if let Some(bg) = cell.bg {
+            style = style.bg(bg);
+        }
 
-        max_x = max_x.max(x);
-        max_y = max_y.max(y);
-        cells.insert((x, y), (ch, fg_rgb));
+        max_x = max_x.max(cell.x);
+        max_y = max_y.max(cell.y);
+        cells.insert((cell.x, cell.y), (cell.symbol, style));
     }
 
     let mut rows: Vec<Line> = Vec::with_capacity((max_y as usize) + 1);
     for y in 0..=max_y {
         let mut span_row: Vec<Span> = Vec::with_capacity((max_x as usize) + 1);
-        for x in 0..=max_x {
-            if let Some((ch, fg)) = cells.get(&(x, y)) {
-                let styled = Span::styled(
-                    ch.to_string(),
-                    ratatui::style::Style::default()
-                        .fg(Color::Rgb(fg.0, fg.1, fg.2))
-                );
-                span_row.push(styled);
+        let mut x = 0;
+        while x <= max_x {
+            if let Some((symbol, style)) = cells.get(&(x, y)) {
+                span_row.push(Span::styled(symbol.clone(), *style));
+                // A wide character covers the next column, hiding any cell there
+                x += symbol.width() as u32;
             } else {
                 span_row.push(Span::raw(" "));
+                x += 1;
             }
         }
         rows.push(Line::from(span_row));
@@ -122,6 +225,21 @@ pub fn load_csv_frame_from_string(content: &str) -> io::Result<Text<'static>> {
 }
 
 pub fn load_frames_from_dir(dir: &str) -> io::Result<Vec<Text<'static>>> {
+    Ok(load_timed_frames_from_dir(Path::new(dir))?.into_iter().map(|(frame, _)| frame).collect())
+}
+
+/// A frame with the duration its file name asks for, if any.
+type TimedFrame = (Text<'static>, Option<Duration>);
+
+/// How long a frame shows, from a file name ending in `_<N>ms` such as `swim_02_300ms.csv`.
+pub fn duration_from_file_name(name: &str) -> Option<Duration> {
+    let stem = name.strip_suffix(".csv").unwrap_or(name);
+    let (_, last) = stem.rsplit_once('_')?;
+    let ms: u64 = last.strip_suffix("ms")?.parse().ok()?;
+    Some(Duration::from_millis(ms.max(1)))
+}
+
+fn load_timed_frames_from_dir(dir: &Path) -> io::Result<Vec<TimedFrame>> {
     let mut paths: Vec<std::path::PathBuf> = fs::read_dir(dir)?
         .filter_map(|e| e.ok())
         .map(|e| e.path())
@@ -133,37 +251,319 @@ pub fn load_frames_from_dir(dir: &str) -> io::Result<Vec<Text<'static>>> {
     let mut frames = Vec::with_capacity(paths.len());
     for p in paths {
         let s = p.to_string_lossy().to_string();
+        let duration = p.file_name().and_then(|n| n.to_str()).and_then(duration_from_file_name);
         match load_csv_frame(&s) {
-            Ok(t) => frames.push(t),
-            Err(e) => eprintln!("failed to load {}: {}", s, e),
+            Ok(t) => frames.push((t, duration)),
+            Err(e) => tracing::warn!("failed to load {}: {}", s, e),
         }
     }
 
     Ok(frames)
 }
 
-pub type SpeciesFrames = (Vec<Text<'static>>, Vec<Text<'static>>);
+/// The frames in `dir`, in file name order, each shown for the duration its name asks
+/// for or `default`.
+pub fn load_animation_from_dir(dir: &Path, default: Duration) -> io::Result<AnimationFrames> {
+    Ok(load_timed_frames_from_dir(dir)?.into_iter().map(|(frame, duration)| (frame, duration.unwrap_or(default))).collect())
+}
+
+/// An animation: each frame with how long it shows at the reference swimming speed.
+pub type AnimationFrames = Vec<(Text<'static>, Duration)>;
+/// A species' right- and left-facing animations.
+pub type SpeciesFrames = (AnimationFrames, AnimationFrames);
+
+/// A frame baked into a grid of cells for drawing straight into the buffer. A space
+/// with no colours of its own, such as a gap in the CSV, is left empty, so whatever is
+/// behind the sprite shows through it instead of being blanked.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpriteFrame {
+    pub width: u16,
+    pub height: u16,
+    /// Row by row. The column after a wide character is empty too.
+    pub cells: Vec<Option<(String, Style)>>,
+}
+
+impl SpriteFrame {
+    pub fn bake(text: &Text) -> SpriteFrame {
+        let (width, height) = (text.width() as u16, text.height() as u16);
+        let mut cells = vec![None; width as usize * height as usize];
+        for (y, line) in text.lines.iter().enumerate() {
+            let mut x = 0;
+            for (symbol, style) in line_cells(line) {
+                let cell_width = symbol.width();
+                if cell_width > 0 && !is_gap(&symbol, style) {
+                    cells[y * width as usize + x] = Some((symbol, style));
+                }
+                x += cell_width;
+            }
+        }
+        SpriteFrame { width, height, cells }
+    }
+
+    /// Draw the frame with its top-left corner at `origin`, blown up `scale` times,
+    /// into the cells of `buf` inside `clip` and outside every one of `holes` (e.g.
+    /// under the dock, or a message box). A wide character cut by an edge is blanked
+    /// where it shows.
+    pub fn blit(&self, buf: &mut Buffer, origin: (i32, i32), scale: u16, clip: Rect, holes: &[Rect]) {
+        let clip = clip.intersection(buf.area);
+        let drawn = |x: i32, y: u16| {
+            x >= clip.left() as i32
+                && x < clip.right() as i32
+                && !holes.iter().any(|hole| hole.contains(Position::new(x as u16, y)))
+        };
+        let scale = scale.max(1) as i32;
+        let rows = self.cells.chunks(self.width.max(1) as usize);
+        for (row, cells) in rows.enumerate() {
+            for copy in 0..scale {
+                let y = origin.1 + row as i32 * scale + copy;
+                if y < clip.top() as i32 {
+                    continue;
+                }
+                if y >= clip.bottom() as i32 {
+                    return;
+                }
+                let y = y as u16;
+                for (col, cell) in cells.iter().enumerate() {
+                    let Some((symbol, style)) = cell else {
+                        continue;
+                    };
+                    let width = symbol.width() as i32;
+                    for copy in 0..scale {
+                        let start = origin.0 + col as i32 * scale + copy * width;
+                        if (start..start + width).all(|x| drawn(x, y)) {
+                            buf[(start as u16, y)].set_symbol(symbol).set_style(*style);
+                        } else {
+                            for x in (start..start + width).filter(|&x| drawn(x, y)) {
+                                buf[(x as u16, y)].set_symbol(" ").set_style(*style);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether a cell of a frame's text is a gap the scene shows through: a space with no
+/// colours of its own, as the loader fills in for cells a CSV leaves out.
+pub fn is_gap(symbol: &str, style: Style) -> bool {
+    symbol == " " && style == Style::default()
+}
+
+/// A baked animation: each frame with how long it shows.
+pub type BakedFrames = Vec<(SpriteFrame, Duration)>;
+/// A species' right- and left-facing animations, baked.
+pub type BakedSpecies = (BakedFrames, BakedFrames);
+
 
 #[derive(Debug, Clone)]
 pub struct FishSpecies {
     pub name: String,
     pub frames: SpeciesFrames,
+    pub traits: SpeciesTraits,
+    /// Width and height of the largest frame, in cells; the fish's hitbox.
+    pub size: (u16, u16),
+}
+
+/// Width and height of the largest frame facing either way.
+pub fn frames_size(frames: &SpeciesFrames) -> (u16, u16) {
+    frames.0.iter().chain(&frames.1).fold((0, 0), |(w, h), (frame, _)| {
+        (w.max(frame.width() as u16), h.max(frame.height() as u16))
+    })
+}
+
+/// Optional per-species settings, read from `meta.csv` next to the frame folders.
+/// Every column may be left out.
+#[derive(Debug, Deserialize)]
+struct SpeciesMeta {
+    #[serde(rename = "FrameMs", default)] pub frame_ms: Option<u64>,
+    #[serde(rename = "Aggression", default)] pub aggression: Option<f32>,
+    #[serde(rename = "Pattern", default)] pub pattern: Option<String>,
+    #[serde(rename = "Depth", default)] pub depth: Option<String>,
+    #[serde(rename = "Rarity", default)] pub rarity: Option<f32>,
+    #[serde(rename = "School", default)] pub school: Option<usize>,
+    #[serde(rename = "Seasons", default)] pub seasons: Option<String>,
+    #[serde(rename = "SizeMean", default)] pub size_mean: Option<f32>,
+    #[serde(rename = "SizeStdDev", default)] pub size_stddev: Option<f32>,
+    #[serde(rename = "SizeMin", default)] pub size_min: Option<f32>,
+    #[serde(rename = "SizeMax", default)] pub size_max: Option<f32>,
+    #[serde(rename = "Stamina", default)] pub stamina: Option<f32>,
+}
+
+/// Preferred depth: `surface`, `mid`, `deep`, or a number from 0.0 (surface) to 1.0.
+fn parse_depth(s: &str) -> Option<f32> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "surface" => Some(0.0),
+        "mid" => Some(0.5),
+        "deep" => Some(1.0),
+        other => other.parse::<f32>().ok().map(|d| d.clamp(0.0, 1.0)),
+    }
+}
+
+fn parse_species_meta(content: &str) -> Option<SpeciesMeta> {
+    csv::Reader::from_reader(content.as_bytes())
+        .deserialize()
+        .next()
+        .and_then(|row| row.ok())
+}
+
+fn traits_from_meta(content: Option<&str>) -> SpeciesTraits {
+    let defaults = SpeciesTraits::default();
+    let Some(meta) = content.and_then(parse_species_meta) else {
+        return defaults;
+    };
+    SpeciesTraits {
+        frame_duration: meta
+            .frame_ms
+            .map(|ms| Duration::from_millis(ms.max(1)))
+            .unwrap_or(defaults.frame_duration),
+        aggression: meta.aggression.map(|a| a.clamp(0.0, 1.0)).unwrap_or(defaults.aggression),
+        pattern: meta
+            .pattern
+            .and_then(|p| p.parse().map_err(|e| tracing::warn!("{}", e)).ok())
+            .unwrap_or(defaults.pattern),
+        depth: meta.depth.as_deref().and_then(parse_depth).unwrap_or(defaults.depth),
+        rarity: meta.rarity.map(|r| r.clamp(0.0, 1.0)).unwrap_or(defaults.rarity),
+        school: meta.school.map(|n| n.max(1)).unwrap_or(defaults.school),
+        seasons: meta
+            .seasons
+            .and_then(|s| s.parse().map_err(|e| tracing::warn!("{}", e)).ok())
+            .unwrap_or(defaults.seasons),
+        sizes: meta
+            .size_mean
+            .map(|mean| SizeDistribution::around(mean, meta.size_stddev, meta.size_min, meta.size_max))
+            .unwrap_or(defaults.sizes),
+        stamina: meta.stamina.map(|s| s.max(0.0)).unwrap_or(defaults.stamina),
+    }
+}
+
+/// Characters that point one way, paired with their mirror images.
+const MIRRORED_PAIRS: [(char, char); 22] = [
+    ('<', '>'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('/', '\\'),
+    ('╱', '╲'),
+    ('◄', '►'),
+    ('◀', '▶'),
+    ('◢', '◣'),
+    ('◤', '◥'),
+    ('▌', '▐'),
+    ('▖', '▗'),
+    ('▘', '▝'),
+    ('▙', '▟'),
+    ('▛', '▜'),
+    ('┌', '┐'),
+    ('└', '┘'),
+    ('├', '┤'),
+    ('╭', '╮'),
+    ('╰', '╯'),
+    ('«', '»'),
+    ('⌐', '¬'),
+];
+
+fn mirror_symbol(symbol: String) -> String {
+    let mut chars = symbol.chars();
+    let (Some(ch), None) = (chars.next(), chars.next()) else {
+        return symbol;
+    };
+    MIRRORED_PAIRS
+        .iter()
+        .find_map(|&(a, b)| if ch == a { Some(b) } else if ch == b { Some(a) } else { None })
+        .map_or(symbol, String::from)
+}
+
+/// `frame` flipped left to right, with directional characters swapped for their mirror
+/// images. Short lines are padded to the frame's width first so the sprite keeps its shape.
+pub fn mirror_frame(frame: &Text<'static>) -> Text<'static> {
+    let width = frame.width();
+    let lines: Vec<Line<'static>> = frame
+        .lines
+        .iter()
+        .map(|line| {
+            let mut cells = line_cells(line);
+            let padding = width.saturating_sub(line.width());
+            cells.extend(std::iter::repeat_n((" ".to_string(), Style::default()), padding));
+            let spans: Vec<Span<'static>> =
+                cells.into_iter().rev().map(|(symbol, style)| Span::styled(mirror_symbol(symbol), style)).collect();
+            Line::from(spans)
+        })
+        .collect();
+    Text::from(lines)
+}
+
+fn mirror_frames(frames: &AnimationFrames) -> AnimationFrames {
+    frames.iter().map(|(frame, duration)| (mirror_frame(frame), *duration)).collect()
+}
+
+/// The traits in `species_dir/meta.csv`, or the defaults if it is missing.
+pub fn load_species_traits(species_dir: &Path) -> SpeciesTraits {
+    traits_from_meta(fs::read_to_string(species_dir.join("meta.csv")).ok().as_deref())
 }
 
 /// Expected file structure:
 /// base_dir/
 ///   species1/
-///     left/*.csv
-///     right/*.csv
+///     meta.csv      (optional; `FrameMs`, `Aggression`, `Pattern`, `Depth`, `Rarity`,
+///                   `School`, `Seasons` and `SizeMean`, `SizeStdDev`, `SizeMin` and
+///                   `SizeMax` columns)
+///     left/*.csv    (a name ending in `_<N>ms`, e.g. `swim_02_300ms.csv`, shows
+///     right/*.csv    that frame for N ms instead of the species' `FrameMs`)
 ///   species2/
 ///     left/*.csv
 ///     right/*.csv
 pub fn load_all_fish_species(base_dir: &str) -> io::Result<Vec<FishSpecies>> {
-    let mut per_species: Vec<FishSpecies> = Vec::new();
+    Ok(read_species_layers(Path::new(base_dir))?.into_iter().filter_map(SpeciesLayer::into_species).collect())
+}
+
+/// One species as found in one sprite source, before it is merged with the others.
+struct SpeciesLayer {
+    name: String,
+    right: Vec<TimedFrame>,
+    left: Vec<TimedFrame>,
+    meta: Option<String>,
+}
 
-    let base = std::path::Path::new(base_dir);
+impl SpeciesLayer {
+    fn into_species(self) -> Option<FishSpecies> {
+        if self.right.is_empty() && self.left.is_empty() {
+            return None;
+        }
+        let traits = traits_from_meta(self.meta.as_deref());
+        let timed = |frames: Vec<TimedFrame>| -> AnimationFrames {
+            frames.into_iter().map(|(frame, duration)| (frame, duration.unwrap_or(traits.frame_duration))).collect()
+        };
+        let (mut right, mut left) = (timed(self.right), timed(self.left));
+        // A species drawn facing one way gets the other by mirroring, so it can turn around
+        if left.is_empty() {
+            left = mirror_frames(&right);
+        } else if right.is_empty() {
+            right = mirror_frames(&left);
+        }
+        let frames = (right, left);
+        let size = frames_size(&frames);
+        Some(FishSpecies { name: self.name, frames, traits, size })
+    }
+
+    /// Lay `other` over this species: the directions and `meta.csv` it has replace ours.
+    fn merge(&mut self, other: SpeciesLayer) {
+        if !other.right.is_empty() {
+            self.right = other.right;
+        }
+        if !other.left.is_empty() {
+            self.left = other.left;
+        }
+        if other.meta.is_some() {
+            self.meta = other.meta;
+        }
+    }
+}
+
+fn read_species_layers(base: &Path) -> io::Result<Vec<SpeciesLayer>> {
+    let mut layers = Vec::new();
     if !base.exists() {
-        return Ok(per_species);
+        return Ok(layers);
     }
 
     for entry in std::fs::read_dir(base)? {
@@ -171,92 +571,321 @@ pub fn load_all_fish_species(base_dir: &str) -> io::Result<Vec<FishSpecies>> {
         let path = entry.path();
         if !path.is_dir() { continue; }
 
-        let species_name = path.file_name()
+        let frames = |dir: &str| {
+            let dir = path.join(dir);
+            if dir.is_dir() {
+                load_timed_frames_from_dir(&dir).unwrap_or_default()
+            } else {
+                Vec::new()
+            }
+        };
+        layers.push(SpeciesLayer {
+            name: path.file_name().and_then(|n| n.to_str()).unwrap_or("Unknown").to_string(),
+            right: frames("right"),
+            left: frames("left"),
+            meta: fs::read_to_string(path.join("meta.csv")).ok(),
+        });
+    }
+
+    Ok(layers)
+}
+
+fn embedded_species_layers() -> Vec<SpeciesLayer> {
+    let mut layers = Vec::new();
+
+    for species_dir in FISH_DIR.dirs() {
+        let species_name = species_dir.path()
+            .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("Unknown")
             .to_string();
 
-        let mut right_frames: Vec<Text<'static>> = Vec::new();
-        let mut left_frames: Vec<Text<'static>> = Vec::new();
-
-        let right_dir = path.join("right");
-        if right_dir.exists() && right_dir.is_dir() {
-            if let Ok(mut v) = load_frames_from_dir(right_dir.to_string_lossy().as_ref()) {
-                right_frames.append(&mut v);
-            }
-        }
+        let mut right_frames: Vec<TimedFrame> = Vec::new();
+        let mut left_frames: Vec<TimedFrame> = Vec::new();
 
-        let left_dir = path.join("left");
-        if left_dir.exists() && left_dir.is_dir() {
-            if let Ok(mut v) = load_frames_from_dir(left_dir.to_string_lossy().as_ref()) {
-                left_frames.append(&mut v);
+        for subdir in species_dir.dirs() {
+            let subdir_name = subdir.path().file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let frames = match subdir_name {
+                "right" => &mut right_frames,
+                "left" => &mut left_frames,
+                _ => continue,
+            };
+            for file in subdir.files() {
+                if let Some(ext) = file.path().extension()
+                    && ext == "csv"
+                    && let Ok(content) = std::str::from_utf8(file.contents())
+                    && let Ok(frame) = load_csv_frame_from_string(content)
+                {
+                    let name = file.path().file_name().and_then(|n| n.to_str()).unwrap_or("");
+                    frames.push((frame, duration_from_file_name(name)));
+                }
             }
         }
 
-        if !right_frames.is_empty() || !left_frames.is_empty() {
-            per_species.push(FishSpecies {
-                name: species_name,
-                frames: (right_frames, left_frames),
-            });
-        }
+        let meta = species_dir
+            .files()
+            .find(|f| f.path().file_name().is_some_and(|n| n == "meta.csv"))
+            .and_then(|f| f.contents_utf8());
+        layers.push(SpeciesLayer {
+            name: species_name,
+            right: right_frames,
+            left: left_frames,
+            meta: meta.map(str::to_string),
+        });
     }
 
-    Ok(per_species)
+    layers
 }
 
 pub fn load_moon_embedded() -> io::Result<Text<'static>> {
     load_csv_frame_from_string(MOON_CSV)
 }
+
 pub fn load_all_fish_species_embedded() -> io::Result<Vec<FishSpecies>> {
-    let mut per_species: Vec<FishSpecies> = Vec::new();
+    Ok(embedded_species_layers().into_iter().filter_map(SpeciesLayer::into_species).collect())
+}
 
-    for species_dir in FISH_DIR.dirs() {
-        let species_name = species_dir.path()
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("Unknown")
-            .to_string();
+/// The embedded fish with the sprite directories in `dirs` laid over them in order, each
+/// laid out like [`load_all_fish_species`]. A species found again replaces only the
+/// directions (and `meta.csv`) the later directory has; a new species is added.
+pub fn load_layered_fish_species(dirs: &[PathBuf]) -> Vec<FishSpecies> {
+    let mut merged = embedded_species_layers();
+    for dir in dirs {
+        let layers = match read_species_layers(dir) {
+            Ok(layers) => layers,
+            Err(e) => {
+                tracing::warn!("failed to read sprites in {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+        for layer in layers {
+            match merged.iter_mut().find(|m| m.name == layer.name) {
+                Some(existing) => existing.merge(layer),
+                None => merged.push(layer),
+            }
+        }
+    }
+    merged.into_iter().filter_map(SpeciesLayer::into_species).collect()
+}
 
-        let mut right_frames: Vec<Text<'static>> = Vec::new();
-        let mut left_frames: Vec<Text<'static>> = Vec::new();
+/// Fish drawn from a line of glyphs, to stock the sea when no sprites could be loaded:
+/// a common minnow near the surface, a perch in mid-water and a rare grouper deep down.
+pub fn placeholder_species() -> Vec<FishSpecies> {
+    [
+        ("Minnow", "><>", Color::Gray, 0.1, 0.0, 8.0),
+        ("Perch", "><((°>", Color::Yellow, 0.5, 0.3, 30.0),
+        ("Grouper", "><(((((°>", Color::LightRed, 0.9, 0.7, 90.0),
+    ]
+    .into_iter()
+    .map(|(name, glyphs, color, depth, rarity, size)| {
+        let right: AnimationFrames = vec![(Text::from(Line::styled(glyphs, Style::default().fg(color))), DEFAULT_FRAME_DURATION)];
+        let frames = (right.clone(), mirror_frames(&right));
+        let sizes = SizeDistribution::around(size, None, None, None);
+        let traits = SpeciesTraits { depth, rarity, sizes, ..SpeciesTraits::default() };
+        FishSpecies { name: name.to_string(), size: frames_size(&frames), frames, traits }
+    })
+    .collect()
+}
 
-        for subdir in species_dir.dirs() {
-            let subdir_name = subdir.path().file_name().and_then(|n| n.to_str()).unwrap_or("");
-            
-            if subdir_name == "right" {
-                for file in subdir.files() {
-                    if let Some(ext) = file.path().extension() {
-                        if ext == "csv" {
-                            if let Ok(content) = std::str::from_utf8(file.contents()) {
-                                if let Ok(frame) = load_csv_frame_from_string(content) {
-                                    right_frames.push(frame);
-                                }
-                            }
-                        }
-                    }
-                }
-            } else if subdir_name == "left" {
-                for file in subdir.files() {
-                    if let Some(ext) = file.path().extension() {
-                        if ext == "csv" {
-                            if let Ok(content) = std::str::from_utf8(file.contents()) {
-                                if let Ok(frame) = load_csv_frame_from_string(content) {
-                                    left_frames.push(frame);
-                                }
-                            }
-                        }
-                    }
-                }
+/// The crab on the dock: its walk, and the frame shown while it hides from a catch.
+#[derive(Debug, Clone, Default)]
+pub struct CrabSprites {
+    pub walk: AnimationFrames,
+    pub hide: Option<Text<'static>>,
+}
+
+impl CrabSprites {
+    /// Width and height of the largest frame.
+    pub fn size(&self) -> (u16, u16) {
+        self.walk.iter().map(|(frame, _)| frame).chain(&self.hide).fold((0, 0), |(w, h), frame| {
+            (w.max(frame.width() as u16), h.max(frame.height() as u16))
+        })
+    }
+
+    /// Frames named `hide*.csv` are the hiding frame; every other one is part of the walk.
+    fn from_files(mut files: Vec<(String, Text<'static>)>) -> CrabSprites {
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut sprites = CrabSprites::default();
+        for (name, frame) in files {
+            if name.starts_with("hide") {
+                sprites.hide = Some(frame);
+            } else {
+                let duration = duration_from_file_name(&name).unwrap_or(CRAB_FRAME_DURATION);
+                sprites.walk.push((frame, duration));
             }
         }
+        sprites
+    }
+}
+
+pub fn load_crab_embedded() -> CrabSprites {
+    let files = CRAB_DIR
+        .files()
+        .filter(|f| f.path().extension().is_some_and(|ext| ext == "csv"))
+        .filter_map(|f| {
+            let name = f.path().file_name()?.to_str()?.to_string();
+            Some((name, load_csv_frame_from_string(f.contents_utf8()?).ok()?))
+        })
+        .collect();
+    CrabSprites::from_files(files)
+}
+
+/// The crab from the last of `dirs` that holds any frames, or the embedded crab.
+pub fn load_layered_crab(dirs: &[PathBuf]) -> CrabSprites {
+    dirs.iter()
+        .rev()
+        .filter(|dir| dir.is_dir())
+        .find_map(|dir| {
+            let entries = fs::read_dir(dir).map_err(|e| tracing::warn!("failed to read {}: {}", dir.display(), e)).ok()?;
+            let files: Vec<_> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "csv"))
+                .filter_map(|p| {
+                    let frame = load_csv_frame(p.to_string_lossy().as_ref())
+                        .map_err(|e| tracing::warn!("failed to load {}: {}", p.display(), e))
+                        .ok()?;
+                    Some((p.file_name()?.to_str()?.to_string(), frame))
+                })
+                .collect();
+            let sprites = CrabSprites::from_files(files);
+            (!sprites.walk.is_empty()).then_some(sprites)
+        })
+        .unwrap_or_else(load_crab_embedded)
+}
+
+/// The moon from the last of `paths` that holds one, or the embedded moon.
+pub fn load_layered_moon(paths: &[PathBuf]) -> Option<Text<'static>> {
+    paths
+        .iter()
+        .rev()
+        .filter(|path| path.is_file())
+        .find_map(|path| {
+            load_csv_frame(path.to_string_lossy().as_ref())
+                .map_err(|e| tracing::warn!("failed to load {}: {}", path.display(), e))
+                .ok()
+        })
+        .or_else(|| load_moon_embedded().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fishing_game::SizeCategory;
+
+    #[test]
+    fn background_column_is_optional() {
+        let frame = load_csv_frame_from_string("X,Y,ASCII,Foreground,Background\n0,0,o,#ff0000,#0000ff\n1,0,>,#ff0000,\n").unwrap();
+        let spans = &frame.lines[0].spans;
+        assert_eq!(spans[0].style.bg, Some(Color::Rgb(0, 0, 255)));
+        assert_eq!(spans[1].style.bg, None);
+
+        let frame = load_csv_frame_from_string("X,Y,ASCII,Foreground\n0,0,o,#ff0000\n").unwrap();
+        assert_eq!(frame.lines[0].spans[0].style.fg, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(frame.lines[0].spans[0].style.bg, None);
+    }
 
-        if !right_frames.is_empty() || !left_frames.is_empty() {
-            per_species.push(FishSpecies {
-                name: species_name,
-                frames: (right_frames, left_frames),
-            });
+    #[test]
+    fn written_cells_read_back() {
+        let cells = vec![
+            SpriteCell { x: 0, y: 0, symbol: ",".to_string(), fg: Color::Rgb(255, 255, 255), bg: None },
+            SpriteCell { x: 3, y: 1, symbol: "█".to_string(), fg: Color::Rgb(250, 160, 60), bg: Some(Color::Rgb(0, 0, 40)) },
+        ];
+        let text = write_sprite_cells(&cells);
+        assert!(text.starts_with("X,Y,ASCII,Foreground,Background\n0,0,\",\",#FFFFFF,\n"));
+        assert_eq!(read_sprite_cells(&text).unwrap(), cells);
+    }
+
+    #[test]
+    fn file_names_can_set_frame_durations() {
+        let dir = std::env::temp_dir().join(format!("fisherman-timing-{}", std::process::id()));
+        fs::create_dir_all(dir.join("Eel/right")).unwrap();
+        fs::write(dir.join("Eel/meta.csv"), "FrameMs\n100\n").unwrap();
+        for name in ["swim_01.csv", "swim_02_400ms.csv"] {
+            fs::write(dir.join("Eel/right").join(name), "X,Y,ASCII,Foreground\n0,0,~,#00ff00\n").unwrap();
         }
+        let species = load_all_fish_species(dir.to_string_lossy().as_ref()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(species[0].size, (1, 1));
+        let durations: Vec<u128> = species[0].frames.0.iter().map(|(_, d)| d.as_millis()).collect();
+        assert_eq!(durations, [100, 400]);
+        assert_eq!(duration_from_file_name("idle_hold_1500ms.csv"), Some(Duration::from_millis(1500)));
+        assert_eq!(duration_from_file_name("fish1left.csv"), None);
+        assert_eq!(duration_from_file_name("frame_ms.csv"), None);
+    }
+
+    #[test]
+    fn wide_and_combining_characters_keep_columns_aligned() {
+        let csv = "X,Y,ASCII,Foreground\n0,0,魚,#ffffff\n1,0,x,#ffffff\n2,0,>,#ffffff\n0,1,e\u{301},#ffffff\n1,1,o,#ffffff\n";
+        let frame = load_csv_frame_from_string(csv).unwrap();
+        let rows: Vec<String> = frame.lines.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect()).collect();
+        assert_eq!(rows, ["魚>", "e\u{301}o "]);
+        assert_eq!(frame.width(), 3);
+
+        let mirrored = mirror_frame(&frame);
+        let rows: Vec<String> = mirrored.lines.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect()).collect();
+        assert_eq!(rows, ["<魚", " oe\u{301}"]);
     }
 
-    Ok(per_species)
+    #[test]
+    fn missing_directions_are_mirrored() {
+        let frame = load_csv_frame_from_string("X,Y,ASCII,Foreground\n0,0,<,#ff0000\n1,0,(,#00ff00\n0,1,/,#0000ff\n").unwrap();
+        let mirrored = mirror_frame(&frame);
+        let rows: Vec<String> = mirrored.lines.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect()).collect();
+        assert_eq!(rows, [")>", " \\"]);
+        assert_eq!(mirrored.lines[0].spans[1].style.fg, Some(Color::Rgb(255, 0, 0)));
+
+        for species in load_all_fish_species_embedded().unwrap() {
+            assert!(!species.frames.0.is_empty() && !species.frames.1.is_empty(), "{} can't turn", species.name);
+        }
+    }
+
+    #[test]
+    fn later_sprite_dirs_add_and_override_species() {
+        let dir = std::env::temp_dir().join(format!("fisherman-sprites-{}", std::process::id()));
+        fs::create_dir_all(dir.join("Eel/right")).unwrap();
+        fs::create_dir_all(dir.join("Shark")).unwrap();
+        fs::write(dir.join("Eel/right/eel.csv"), "X,Y,ASCII,Foreground\n0,0,~,#00ff00\n").unwrap();
+        fs::write(dir.join("Shark/meta.csv"), "Rarity,SizeMean,SizeMax\n0.25,120,150\n").unwrap();
+
+        let species = load_layered_fish_species(std::slice::from_ref(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let eel = species.iter().find(|s| s.name == "Eel").unwrap();
+        assert_eq!(eel.frames.0.len(), 1);
+        let shark = species.iter().find(|s| s.name == "Shark").unwrap();
+        assert!(!shark.frames.0.is_empty(), "the embedded frames stay");
+        assert_eq!(shark.traits.rarity, 0.25);
+        assert_eq!(shark.traits.sizes, SizeDistribution { mean: 120.0, stddev: 36.0, min: 12.0, max: 150.0 });
+        assert_eq!(shark.traits.sizes.category(200.0), SizeCategory::Massive);
+        assert_eq!(eel.traits.sizes.category(85.0), SizeCategory::Massive);
+        assert_eq!(species.len(), load_all_fish_species_embedded().unwrap().len() + 1);
+    }
+
+    #[test]
+    fn baked_frames_show_the_scene_through_their_gaps() {
+        // A fish with a gap in its middle, a space of its own colour and a wide eye
+        let csv = "X,Y,ASCII,Foreground\n0,0,<,#ff0000\n2,0, ,#ff0000\n3,0,魚,#ffffff\n5,0,>,#ff0000\n";
+        let frame = SpriteFrame::bake(&load_csv_frame_from_string(csv).unwrap());
+        assert_eq!((frame.width, frame.height), (6, 1));
+        assert_eq!(frame.cells.iter().filter(|cell| cell.is_some()).count(), 4);
+
+        let area = Rect::new(0, 0, 14, 3);
+        let row = |buf: &Buffer, y: u16| (0..14).map(|x| buf[(x, y)].symbol().to_string()).collect::<String>();
+        let mut buf = Buffer::filled(area, ratatui::buffer::Cell::new("."));
+        frame.blit(&mut buf, (1, 0), 1, area, &[]);
+        assert_eq!(row(&buf, 0), ".<. 魚.>.......");
+        assert_eq!(buf[(1, 0)].fg, Color::Rgb(255, 0, 0));
+
+        // Cut by the clip, under a hole and blown up twice
+        let mut buf = Buffer::filled(area, ratatui::buffer::Cell::new("."));
+        frame.blit(&mut buf, (-4, 0), 1, area, &[]);
+        assert_eq!(row(&buf, 0), " >............");
+        let mut buf = Buffer::filled(area, ratatui::buffer::Cell::new("."));
+        frame.blit(&mut buf, (0, 0), 2, Rect::new(0, 0, 14, 2), &[Rect::new(0, 1, 2, 1)]);
+        assert_eq!(row(&buf, 0), "<<..  魚.魚.>>..");
+        assert_eq!(row(&buf, 1), "....  魚.魚.>>..");
+        assert_eq!(row(&buf, 2), "..............");
+    }
 }
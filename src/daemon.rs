@@ -0,0 +1,272 @@
+//! `fisherman daemon` and `fisherman attach`: the scene kept running in the background,
+//! collecting signals as they come in, with terminals attaching to watch and play it
+//! and detaching again without stopping it. The daemon listens on a local port: a
+//! connection that opens with `ATTACH <width> <height>` is a terminal, sent the scene
+//! as ANSI output as for `--broadcast` and sending back its keys and resizes as JSON
+//! lines; any other connection's lines are signals, so `echo SUCCESS:done | nc
+//! localhost 7879` works as well as the usual signal sources.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader};
+use std::iter;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::Event;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+use crate::broadcast::{self, Spectators};
+use crate::signals::{self, SharedSignal};
+
+/// The port the daemon listens on unless told otherwise.
+pub const DEFAULT_PORT: u16 = 7879;
+/// The scene's size until a terminal attaches and it takes that terminal's size.
+pub const SCENE_SIZE: Rect = Rect::new(0, 0, 100, 30);
+/// How an attaching terminal opens, before its width and height.
+pub const ATTACH: &str = "ATTACH";
+
+const USAGE: &str = "\
+usage: fisherman attach [<host>:<port>]
+
+Shows the scene of a running `fisherman daemon` (on localhost:7879 unless given)
+and plays it from this terminal. Ctrl+D or Ctrl+C detaches, leaving the daemon
+running.";
+
+const EXIT_USAGE: i32 = 2;
+const EXIT_FAILED: i32 = 1;
+
+/// What an attached terminal did.
+enum Visit {
+    Attached { id: usize, width: u16, height: u16 },
+    Event { id: usize, event: Event },
+    Left(usize),
+}
+
+/// The daemon's side of its port: the attached terminals and what they send.
+pub struct Daemon {
+    visits: Receiver<Visit>,
+    frames: SyncSender<Buffer>,
+    /// Each attached terminal's connection, to cut it off.
+    streams: Arc<Mutex<HashMap<usize, TcpStream>>>,
+    /// The terminal the last event came from.
+    last: Option<usize>,
+    arrived: bool,
+    local_addr: SocketAddr,
+}
+
+impl Daemon {
+    /// Listen on `port` on this machine, putting signals that arrive there in `inbox`.
+    pub fn bind(port: u16, inbox: SharedSignal) -> io::Result<Daemon> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let local_addr = listener.local_addr()?;
+        let spectators: Spectators = Arc::default();
+        let streams: Arc<Mutex<HashMap<usize, TcpStream>>> = Arc::default();
+        let (visits, received) = mpsc::channel();
+        let (joining, attached) = (Arc::clone(&spectators), Arc::clone(&streams));
+        thread::spawn(move || {
+            for (id, stream) in listener.incoming().flatten().enumerate() {
+                let (spectators, streams, visits, inbox) =
+                    (Arc::clone(&joining), Arc::clone(&attached), visits.clone(), Arc::clone(&inbox));
+                thread::spawn(move || serve(id, stream, &spectators, &streams, &visits, &inbox));
+            }
+        });
+        Ok(Daemon {
+            visits: received,
+            frames: broadcast::send_frames(spectators),
+            streams,
+            last: None,
+            arrived: false,
+            local_addr,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The next key or resize from an attached terminal, waiting up to `timeout` for
+    /// one. A terminal attaching resizes the scene to fit it.
+    pub fn next_event(&mut self, timeout: Duration) -> Option<Event> {
+        match self.visits.recv_timeout(timeout).ok()? {
+            Visit::Attached { id, width, height } => {
+                self.last = Some(id);
+                self.arrived = true;
+                Some(Event::Resize(width, height))
+            }
+            Visit::Event { id, event } => {
+                self.last = Some(id);
+                Some(event)
+            }
+            Visit::Left(id) => {
+                self.streams.lock().unwrap().remove(&id);
+                None
+            }
+        }
+    }
+
+    /// Whether a terminal has attached since the last call.
+    pub fn take_arrival(&mut self) -> bool {
+        std::mem::take(&mut self.arrived)
+    }
+
+    /// Cut off the terminal the last event came from, which asked to quit.
+    pub fn detach_last(&mut self) {
+        if let Some(stream) = self.last.take().and_then(|id| self.streams.lock().unwrap().remove(&id)) {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Send the frame just drawn to the attached terminals.
+    pub fn frame(&self, buffer: &Buffer) {
+        let _ = self.frames.try_send(buffer.clone());
+    }
+}
+
+/// `ATTACH 120 40` as the size of the terminal attaching.
+fn parse_attach(line: &str) -> Option<(u16, u16)> {
+    let mut words = line.split_whitespace();
+    if words.next()? != ATTACH {
+        return None;
+    }
+    let (width, height) = (words.next()?.parse().ok()?, words.next()?.parse().ok()?);
+    words.next().is_none().then_some((width, height))
+}
+
+/// Look after one connection until it closes: a terminal attaching, or lines of signals.
+fn serve(
+    id: usize,
+    stream: TcpStream,
+    spectators: &Spectators,
+    streams: &Mutex<HashMap<usize, TcpStream>>,
+    visits: &Sender<Visit>,
+    inbox: &SharedSignal,
+) {
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let mut lines = BufReader::new(reader).lines().map_while(Result::ok);
+    let Some(first) = lines.next() else {
+        return;
+    };
+    let Some((width, height)) = parse_attach(&first) else {
+        for line in iter::once(first).chain(lines) {
+            if let Some(message) = signals::parse_signal_line(&line) {
+                inbox.lock().unwrap().push_back(message);
+            }
+        }
+        return;
+    };
+    if let Ok(cut_off) = stream.try_clone() {
+        streams.lock().unwrap().insert(id, cut_off);
+    }
+    broadcast::join(spectators, stream);
+    let _ = visits.send(Visit::Attached { id, width, height });
+    for event in lines.filter_map(|line| serde_json::from_str::<Event>(&line).ok()) {
+        if visits.send(Visit::Event { id, event }).is_err() {
+            return;
+        }
+    }
+    let _ = visits.send(Visit::Left(id));
+}
+
+/// `fisherman attach [<host>:<port>]`: play a running daemon's scene until detaching.
+pub fn run(args: &[String]) -> i32 {
+    let addr = match args {
+        [] => format!("localhost:{}", DEFAULT_PORT),
+        [addr] if !addr.starts_with('-') => addr.clone(),
+        _ => {
+            eprintln!("{}", USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    match broadcast::watch(&addr, true) {
+        Ok(true) => {
+            println!("Detached from the fisherman daemon at {}", addr);
+            0
+        }
+        Ok(false) => {
+            println!("Detached; the fisherman daemon at {} is still running", addr);
+            0
+        }
+        Err(e) => {
+            eprintln!("fisherman attach: {}: {}", addr, e);
+            EXIT_FAILED
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent};
+    use ratatui::style::Style;
+    use std::collections::VecDeque;
+    use std::io::{Read, Write};
+    use std::time::Instant;
+
+    /// The next event from an attached terminal, waiting a while for it.
+    fn next(daemon: &mut Daemon) -> Option<Event> {
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_secs(5) {
+            if let Some(event) = daemon.next_event(Duration::from_millis(50)) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn terminals_attach_play_and_detach_while_signals_collect() {
+        assert_eq!(parse_attach("ATTACH 120 40"), Some((120, 40)));
+        assert_eq!(parse_attach("ATTACH 120"), None);
+        assert_eq!(parse_attach("SUCCESS:done"), None);
+
+        let inbox: SharedSignal = Arc::new(Mutex::new(VecDeque::new()));
+        let mut daemon = Daemon::bind(0, Arc::clone(&inbox)).unwrap();
+        let addr = daemon.local_addr();
+
+        // A connection that doesn't attach only brings signals
+        let mut sender = TcpStream::connect(addr).unwrap();
+        sender.write_all(b"SUCCESS:deployed\nnot a signal\nPROGRESS:40:tests\n").unwrap();
+        drop(sender);
+        let started = Instant::now();
+        while inbox.lock().unwrap().len() < 2 && started.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(20));
+        }
+        let lines: Vec<String> = inbox.lock().unwrap().iter().map(|m| m.to_line()).collect();
+        assert_eq!(lines, ["SUCCESS:deployed", "PROGRESS:40:tests"]);
+
+        let mut terminal = TcpStream::connect(addr).unwrap();
+        terminal.write_all(b"ATTACH 40 12\n").unwrap();
+        assert_eq!(next(&mut daemon), Some(Event::Resize(40, 12)));
+        assert!(daemon.take_arrival() && !daemon.take_arrival());
+        let key = Event::Key(KeyEvent::from(KeyCode::Char(' ')));
+        writeln!(terminal, "{}", serde_json::to_string(&key).unwrap()).unwrap();
+        assert_eq!(next(&mut daemon), Some(key));
+
+        // Frames go out to the terminal, the whole of the first
+        let mut frame = Buffer::empty(Rect::new(0, 0, 6, 2));
+        frame.set_string(0, 0, "fish", Style::default());
+        terminal.set_read_timeout(Some(Duration::from_millis(50))).unwrap();
+        let mut chunk = [0; 256];
+        let started = Instant::now();
+        let shown = loop {
+            daemon.frame(&frame);
+            if let Ok(read @ 1..) = terminal.read(&mut chunk) {
+                break String::from_utf8_lossy(&chunk[..read]).into_owned();
+            }
+            assert!(started.elapsed() < Duration::from_secs(5), "no frame arrived");
+        };
+        assert!(shown.contains("fish"), "{:?}", shown);
+
+        // Asking to quit cuts the terminal off, and the daemon carries on
+        daemon.detach_last();
+        terminal.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        while let Ok(1..) = terminal.read(&mut chunk) {}
+        assert!(daemon.streams.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+//! The dock and the shore it runs out from: which side of the sea it's on, how far it
+//! reaches out, and whether a strip of shore shows at its land end. The fisherman sits
+//! at the sea end, and where the rod tip, the rod holders and the `!` over their head go
+//! are all worked out from that one [`Anchor`]. Set from the `[dock]` section of the
+//! config file or with `--dock-side`, `--dock-width` and `--shoreline`:
+//!
+//! ```toml
+//! [dock]
+//! side = "left"
+//! width = 24
+//! shoreline = 6
+//! ```
+
+use std::str::FromStr;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::Widget;
+
+use crate::config_file::{Table, Value};
+use crate::theme::Theme;
+
+/// Columns of dock from the fisherman's seat to the land, unless set otherwise.
+pub const DEFAULT_WIDTH: u16 = 16;
+/// The shortest dock that still leaves room for the rod holders behind the fisherman.
+pub const MIN_WIDTH: u16 = 10;
+/// The widest strip of shore.
+pub const MAX_SHORELINE: u16 = 20;
+/// The fisherman's sprite, which sits with its right-hand column on the seat.
+pub const FISHERMAN_WIDTH: u16 = 16;
+pub const FISHERMAN_HEIGHT: u16 = 9;
+/// Columns from where the fisherman sits out to the tip of the rod.
+pub const ROD_REACH: u16 = 6;
+/// Columns between the rod holders on the planks behind the fisherman.
+pub const HOLDER_SPACING: u16 = 3;
+
+/// Which side of the sea a fisherman casts from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shore {
+    Right,
+    Left,
+}
+
+impl Shore {
+    /// The other side of the sea.
+    pub fn opposite(self) -> Shore {
+        match self {
+            Shore::Right => Shore::Left,
+            Shore::Left => Shore::Right,
+        }
+    }
+}
+
+impl FromStr for Shore {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "right" => Ok(Shore::Right),
+            "left" => Ok(Shore::Left),
+            other => Err(format!("unknown side '{}' (expected left or right)", other)),
+        }
+    }
+}
+
+/// The `[dock]` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DockSettings {
+    /// The side the first player's dock is on; a second player takes the other.
+    pub side: Shore,
+    /// Columns from the fisherman's seat to the land end of the dock.
+    pub width: u16,
+    /// Columns of shore beyond the land end; none leaves the dock running off the screen.
+    pub shoreline: u16,
+}
+
+impl Default for DockSettings {
+    fn default() -> Self {
+        DockSettings { side: Shore::Right, width: DEFAULT_WIDTH, shoreline: 0 }
+    }
+}
+
+impl DockSettings {
+    pub fn from_table(table: &Table) -> Result<DockSettings, String> {
+        let mut settings = DockSettings::default();
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("side", Value::String(side)) => settings.side = side.parse()?,
+                ("side", _) => return Err("[dock] side must be \"left\" or \"right\"".to_string()),
+                ("width", Value::Number(width)) => settings.width = parse_width(*width)?,
+                ("width", _) => return Err(format!("[dock] width must be a number from {} up", MIN_WIDTH)),
+                ("shoreline", Value::Number(shoreline)) => settings.shoreline = parse_shoreline(*shoreline)?,
+                ("shoreline", _) => return Err(format!("[dock] shoreline must be a number from 0 to {}", MAX_SHORELINE)),
+                (key, _) => return Err(format!("unknown setting '{}' in [dock]", key)),
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// A dock width as given, if it's long enough.
+pub fn parse_width(width: f64) -> Result<u16, String> {
+    if width.fract() == 0.0 && width >= MIN_WIDTH as f64 && width <= u16::MAX as f64 {
+        Ok(width as u16)
+    } else {
+        Err(format!("dock width must be a whole number from {} up, not {}", MIN_WIDTH, width))
+    }
+}
+
+/// A shoreline width as given, if it isn't too wide.
+pub fn parse_shoreline(shoreline: f64) -> Result<u16, String> {
+    if shoreline.fract() == 0.0 && (0.0..=MAX_SHORELINE as f64).contains(&shoreline) {
+        Ok(shoreline as u16)
+    } else {
+        Err(format!("shoreline must be a whole number from 0 to {}, not {}", MAX_SHORELINE, shoreline))
+    }
+}
+
+/// Where a fisherman sits: the column of their seat at the sea end of the dock, the top
+/// row of their sprite and the shore they face out from. Everything else about where
+/// they are is measured from here, out to sea or back towards the land.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Anchor {
+    pub seat: u16,
+    pub top: u16,
+    pub shore: Shore,
+}
+
+impl Anchor {
+    /// The column `n` columns out to sea from the seat.
+    pub fn seaward(&self, n: u16) -> u16 {
+        match self.shore {
+            Shore::Right => self.seat.saturating_sub(n),
+            Shore::Left => self.seat.saturating_add(n),
+        }
+    }
+
+    /// The column `n` columns back along the dock from the seat.
+    pub fn shoreward(&self, n: u16) -> u16 {
+        match self.shore {
+            Shore::Right => self.seat.saturating_add(n),
+            Shore::Left => self.seat.saturating_sub(n),
+        }
+    }
+
+    /// Whichever of columns `a` and `b` is further out to sea.
+    pub fn further_out(&self, a: u16, b: u16) -> u16 {
+        match self.shore {
+            Shore::Right => a.min(b),
+            Shore::Left => a.max(b),
+        }
+    }
+
+    /// The area the fisherman is drawn in, facing out to sea.
+    pub fn fisherman(&self) -> Rect {
+        let x = match self.shore {
+            Shore::Right => (self.seat + 1).saturating_sub(FISHERMAN_WIDTH),
+            Shore::Left => self.seat,
+        };
+        Rect::new(x, self.top, FISHERMAN_WIDTH, FISHERMAN_HEIGHT)
+    }
+
+    /// The tip of the rod, where the line starts.
+    pub fn rod_tip(&self) -> (u16, u16) {
+        (self.seaward(ROD_REACH), self.top.saturating_sub(4).saturating_add(2).saturating_sub(1))
+    }
+
+    /// Where the `!` goes over the fisherman's head when a signal comes in.
+    pub fn exclamation(&self) -> (u16, u16) {
+        (self.seaward(FISHERMAN_WIDTH / 2), self.top.saturating_sub(1))
+    }
+
+    /// Where the line leaves the rod in holder `slot` (from 1), on the planks behind
+    /// the fisherman.
+    pub fn holder_tip(&self, slot: usize) -> (u16, u16) {
+        (self.shoreward(HOLDER_SPACING * slot as u16 - 1), self.top.saturating_sub(1))
+    }
+
+    /// The same seat seen from the other side of a screen `width` columns wide.
+    pub fn mirrored(&self, width: u16) -> Anchor {
+        Anchor { seat: width.saturating_sub(1).saturating_sub(self.seat), shore: self.shore.opposite(), ..*self }
+    }
+}
+
+/// Glyphs for the bank: its grassy top, the dry earth under it and the wet sand below
+/// the waterline.
+const GRASS: &str = "▄";
+const EARTH: &str = "█";
+const SAND: &str = "▓";
+
+/// The strip of shore at the land end of the dock: a grassy bank level with the planks,
+/// going down past the waterline at row `surface` to the sea floor. The area runs from
+/// the plank row down to the bottom of the sea.
+pub struct Shoreline {
+    pub surface: u16,
+    pub theme: Theme,
+}
+
+impl Widget for Shoreline {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        let grass = Style::default().fg(self.theme.seaweed);
+        let earth = Style::default().fg(self.theme.rock);
+        for y in area.top()..area.bottom() {
+            let (symbol, style) = match y {
+                _ if y == area.y => (GRASS, grass),
+                _ if y < self.surface => (EARTH, earth),
+                _ => (SAND, earth),
+            };
+            for x in area.left()..area.right() {
+                buf[(x, y)].set_symbol(symbol).set_style(style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+
+    #[test]
+    fn the_anchor_measures_out_to_sea_from_either_shore() {
+        let config = ConfigFile::parse("[dock]\nside = \"left\"\nwidth = 24\nshoreline = 4").unwrap();
+        let settings = DockSettings::from_table(config.section("dock").unwrap()).unwrap();
+        assert_eq!(settings, DockSettings { side: Shore::Left, width: 24, shoreline: 4 });
+        let short = ConfigFile::parse("[dock]\nwidth = 3").unwrap();
+        assert!(DockSettings::from_table(short.section("dock").unwrap()).is_err());
+
+        let right = Anchor { seat: 70, top: 16, shore: Shore::Right };
+        assert_eq!(right.rod_tip(), (64, 13));
+        assert_eq!(right.exclamation(), (62, 15));
+        assert_eq!(right.holder_tip(1), (72, 15));
+        assert_eq!(right.fisherman(), Rect::new(55, 16, FISHERMAN_WIDTH, FISHERMAN_HEIGHT));
+        assert_eq!(right.further_out(40, 50), 40);
+
+        // Seen from the other side, everything lands in the mirrored column
+        let left = right.mirrored(80);
+        assert_eq!(left.seat, 9);
+        assert_eq!(left.rod_tip(), (15, 13));
+        assert_eq!(left.holder_tip(1), (7, 15));
+        assert_eq!(left.fisherman(), Rect::new(9, 16, FISHERMAN_WIDTH, FISHERMAN_HEIGHT));
+        assert_eq!(left.further_out(40, 50), 50);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 4));
+        Shoreline { surface: 2, theme: Theme::CLASSIC }.render(Rect::new(3, 0, 3, 4), &mut buf);
+        let column = |x: u16| (0..4).map(|y| buf[(x, y)].symbol().to_string()).collect::<String>();
+        assert_eq!(column(2), "    ");
+        assert_eq!(column(4), "▄█▓▓");
+    }
+}
@@ -0,0 +1,286 @@
+//! Coins, tackle and the player profile they are saved in.
+//!
+//! Every catch pays coins (fish by size and rarity, treasure by its gold value), which
+//! buy better rods, lines and bait in the tackle shop, and a lantern for the dock. The profile holds these along
+//! with the catch journal and achievements, and is saved as part of the game state.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::achievements::Journal;
+use crate::fishing_game::{Catch, SizeCategory};
+
+/// How much a rarity of 1.0 multiplies a fish's value.
+const RARITY_BONUS: f32 = 3.0;
+/// How much a shiny fish multiplies its value.
+const SHINY_BONUS: f32 = 5.0;
+/// Most rod holders the dock has room for, each keeping one more line in the water.
+pub const MAX_ROD_HOLDERS: usize = 2;
+const ROD_HOLDER_PRICE: u32 = 80;
+const LANTERN_PRICE: u32 = 120;
+
+/// Coins paid for a catch: fish by size, species rarity and whether they are shiny,
+/// items by their gold value.
+pub fn coins_for(catch: &Catch, rarity: f32) -> u32 {
+    match catch {
+        Catch::Fish(fish) => {
+            let bonus = match fish.size_category {
+                SizeCategory::Tiny | SizeCategory::Small => 0.0,
+                SizeCategory::Average => 2.0,
+                SizeCategory::Large => 5.0,
+                SizeCategory::Massive => 15.0,
+            };
+            let shiny = if fish.shiny { SHINY_BONUS } else { 1.0 };
+            ((fish.size / 10.0 + bonus) * (1.0 + rarity.clamp(0.0, 1.0) * RARITY_BONUS) * shiny).round().max(1.0) as u32
+        }
+        Catch::Item(item) => item.gold,
+    }
+}
+
+pub struct Rod {
+    pub name: &'static str,
+    pub price: u32,
+    /// Longest cast as a fraction of the screen width.
+    pub cast_range: f32,
+    /// Rows the hook rises per press of Up.
+    pub reel_step: u16,
+}
+
+pub struct Line {
+    pub name: &'static str,
+    pub price: u32,
+    /// Fish up to this size (cm) never snap the line; bigger ones may.
+    pub tension_limit: f32,
+}
+
+pub const RODS: [Rod; 3] = [
+    Rod { name: "Bamboo Rod", price: 0, cast_range: 0.7, reel_step: 1 },
+    Rod { name: "Fiberglass Rod", price: 60, cast_range: 0.8, reel_step: 2 },
+    Rod { name: "Carbon Rod", price: 200, cast_range: 0.9, reel_step: 3 },
+];
+
+pub const LINES: [Line; 3] = [
+    Line { name: "Cotton Line", price: 0, tension_limit: 60.0 },
+    Line { name: "Nylon Line", price: 50, tension_limit: 80.0 },
+    Line { name: "Braided Line", price: 150, tension_limit: f32::INFINITY },
+];
+
+impl Line {
+    /// Whether the line survives landing a fish of `size`. Past the tension limit the
+    /// chance of snapping grows with every centimetre over it.
+    pub fn holds<R: Rng + ?Sized>(&self, rng: &mut R, size: f32) -> bool {
+        let over = size - self.tension_limit;
+        over <= 0.0 || !rng.gen_bool((over as f64 / 20.0).min(1.0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bait {
+    Worms,
+    Lure,
+}
+
+impl Bait {
+    pub const ALL: [Bait; 2] = [Bait::Worms, Bait::Lure];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Bait::Worms => "Worms",
+            Bait::Lure => "Lure",
+        }
+    }
+
+    /// Price of one pack and how many baits it holds.
+    pub fn pack(&self) -> (u32, u32) {
+        match self {
+            Bait::Worms => (5, 5),
+            Bait::Lure => (25, 3),
+        }
+    }
+
+    /// How much further fish notice a hook carrying this bait.
+    pub fn attraction(&self) -> f32 {
+        match self {
+            Bait::Worms => 1.5,
+            Bait::Lure => 2.0,
+        }
+    }
+
+    /// Multiplier on the spawn weight of a species of `rarity` while this bait is in the
+    /// water: worms draw in common fish, lures rare ones.
+    pub fn spawn_bias(&self, rarity: f32) -> f32 {
+        match self {
+            Bait::Worms => 1.0 + (1.0 - rarity) * 0.5,
+            Bait::Lure => 1.0 + rarity * 2.0,
+        }
+    }
+
+    /// Chance that a fish of `rarity` touching the hook takes this bait.
+    pub fn bite_chance(&self, rarity: f32) -> f64 {
+        match self {
+            Bait::Worms => 0.9 - 0.6 * rarity as f64,
+            Bait::Lure => 0.5 + 0.4 * rarity as f64,
+        }
+    }
+}
+
+/// Everything the player has earned, saved between runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub coins: u32,
+    /// Index into [`RODS`] of the best rod owned.
+    pub rod: usize,
+    /// Index into [`LINES`] of the best line owned.
+    pub line: usize,
+    pub bait: Vec<(Bait, u32)>,
+    /// Bait put on the hook at the next cast; `None` casts a bare hook.
+    pub selected_bait: Option<Bait>,
+    /// Rod holders bought, each a rod left fishing on the dock while another is in hand.
+    pub rod_holders: usize,
+    /// Whether the dock lantern has been bought, lighting the water under it at night.
+    pub lantern: bool,
+    pub journal: Journal,
+}
+
+impl Profile {
+    pub fn rod(&self) -> &'static Rod {
+        &RODS[self.rod.min(RODS.len() - 1)]
+    }
+
+    pub fn line(&self) -> &'static Line {
+        &LINES[self.line.min(LINES.len() - 1)]
+    }
+
+    pub fn bait_count(&self, bait: Bait) -> u32 {
+        self.bait.iter().find(|(b, _)| *b == bait).map_or(0, |(_, n)| *n)
+    }
+
+    pub fn add_bait(&mut self, bait: Bait, count: u32) {
+        match self.bait.iter_mut().find(|(b, _)| *b == bait) {
+            Some((_, n)) => *n += count,
+            None => self.bait.push((bait, count)),
+        }
+    }
+
+    /// Use up one of the selected bait for a cast. Returns the bait on the hook, or
+    /// `None` for a bare hook when nothing is selected or the selected bait ran out.
+    pub fn take_bait(&mut self) -> Option<Bait> {
+        let selected = self.selected_bait?;
+        let (_, n) = self.bait.iter_mut().find(|(b, n)| *b == selected && *n > 0)?;
+        *n -= 1;
+        Some(selected)
+    }
+
+    /// Select the next bait in stock, going through a bare hook after the last one.
+    pub fn cycle_bait(&mut self) -> Option<Bait> {
+        let stocked: Vec<Option<Bait>> = std::iter::once(None)
+            .chain(Bait::ALL.into_iter().filter(|b| self.bait_count(*b) > 0).map(Some))
+            .collect();
+        let current = stocked.iter().position(|b| *b == self.selected_bait).unwrap_or(0);
+        self.selected_bait = stocked[(current + 1) % stocked.len()];
+        self.selected_bait
+    }
+
+    /// Buy `item` if it's affordable and not already owned.
+    pub fn buy(&mut self, item: ShopItem) -> Result<(), &'static str> {
+        match item.state(self) {
+            ItemState::Owned => return Err("Already owned"),
+            ItemState::TooExpensive => return Err("Not enough coins"),
+            ItemState::ForSale => {}
+        }
+        self.coins -= item.price();
+        match item {
+            ShopItem::Rod(i) => self.rod = i,
+            ShopItem::Line(i) => self.line = i,
+            ShopItem::Bait(bait) => self.add_bait(bait, bait.pack().1),
+            ShopItem::RodHolder => self.rod_holders += 1,
+            ShopItem::Lantern => self.lantern = true,
+        }
+        Ok(())
+    }
+}
+
+/// One row of the tackle shop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShopItem {
+    Rod(usize),
+    Line(usize),
+    Bait(Bait),
+    RodHolder,
+    Lantern,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemState {
+    ForSale,
+    TooExpensive,
+    Owned,
+}
+
+impl ShopItem {
+    /// Everything the shop stocks, in display order.
+    pub fn catalog() -> Vec<ShopItem> {
+        (1..RODS.len())
+            .map(ShopItem::Rod)
+            .chain((1..LINES.len()).map(ShopItem::Line))
+            .chain(Bait::ALL.into_iter().map(ShopItem::Bait))
+            .chain([ShopItem::RodHolder, ShopItem::Lantern])
+            .collect()
+    }
+
+    pub fn price(&self) -> u32 {
+        match self {
+            ShopItem::Rod(i) => RODS[*i].price,
+            ShopItem::Line(i) => LINES[*i].price,
+            ShopItem::Bait(bait) => bait.pack().0,
+            ShopItem::RodHolder => ROD_HOLDER_PRICE,
+            ShopItem::Lantern => LANTERN_PRICE,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            ShopItem::Rod(i) => RODS[*i].name.to_string(),
+            ShopItem::Line(i) => LINES[*i].name.to_string(),
+            ShopItem::Bait(bait) => format!("{} x{}", bait.as_str(), bait.pack().1),
+            ShopItem::RodHolder => "Rod Holder".to_string(),
+            ShopItem::Lantern => "Dock Lantern".to_string(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            ShopItem::Rod(i) => format!(
+                "casts {:.0}% of the way, reels {} rows",
+                RODS[*i].cast_range * 100.0,
+                RODS[*i].reel_step
+            ),
+            ShopItem::Line(i) if LINES[*i].tension_limit.is_finite() => {
+                format!("holds fish up to {:.0} cm", LINES[*i].tension_limit)
+            }
+            ShopItem::Line(_) => "never snaps".to_string(),
+            ShopItem::Bait(bait) => format!("draws fish from {}x as far", bait.attraction()),
+            ShopItem::RodHolder => "keeps another line in the water".to_string(),
+            ShopItem::Lantern => "lights the water under the dock at night".to_string(),
+        }
+    }
+
+    pub fn state(&self, profile: &Profile) -> ItemState {
+        let owned = match self {
+            ShopItem::Rod(i) => profile.rod >= *i,
+            ShopItem::Line(i) => profile.line >= *i,
+            ShopItem::Bait(_) => false,
+            ShopItem::RodHolder => profile.rod_holders >= MAX_ROD_HOLDERS,
+            ShopItem::Lantern => profile.lantern,
+        };
+        if owned {
+            ItemState::Owned
+        } else if profile.coins < self.price() {
+            ItemState::TooExpensive
+        } else {
+            ItemState::ForSale
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Short-lived decorations on the water: the splash when the hook lands and the rings
+//! that spread out from it, plus any [`Particles`] in flight and the screen shaking
+//! while a boss fights. Effects are spawned at a point, aged by the main loop with
+//! [`Effects::tick`] and dropped once they have played out.
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::{StatefulWidget, Widget};
+
+use crate::braille::BrailleCanvas;
+use crate::particles::Particles;
+use crate::theme::Theme;
+
+/// How long a ring takes to spread to its full radius.
+const RIPPLE_DURATION: Duration = Duration::from_millis(1200);
+/// Columns the ring left by a landing hook spreads to.
+const SPLASH_RIPPLE_RADIUS: u16 = 6;
+/// Gap between a ripple's rings.
+const RING_SPACING: u16 = 2;
+/// Rows above and below the surface a ring drawn in braille bulges, a flat oval
+/// inside the surface row.
+const BRAILLE_RING_DEPTH: f32 = 0.3;
+/// Dots sampled around each ring drawn in braille.
+const BRAILLE_RING_SAMPLES: usize = 48;
+/// How long the screen holds each jolt of a shake before jolting the other way.
+const SHAKE_JOLT: Duration = Duration::from_millis(60);
+/// The way each jolt throws the scene, in turn: always sideways, and up or down too in
+/// a hard shake.
+const JITTER: [(i16, i16); 4] = [(1, 0), (-1, 1), (1, -1), (-1, 0)];
+/// The most cells a shake throws the scene sideways.
+pub const MAX_SHAKE_CELLS: u16 = 2;
+
+/// Rings spreading along the surface from `(x, y)` out to `radius` columns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ripple {
+    pub x: u16,
+    pub y: u16,
+    pub radius: u16,
+    pub age: Duration,
+}
+
+impl Ripple {
+    /// How far the rings have spread, from 0.0 to 1.0.
+    fn progress(&self) -> f32 {
+        (self.age.as_secs_f32() / RIPPLE_DURATION.as_secs_f32()).min(1.0)
+    }
+}
+
+/// The ripples and particles currently playing.
+#[derive(Debug, Clone)]
+pub struct Effects {
+    ripples: Vec<Ripple>,
+    pub particles: Particles,
+    /// Time left shaking the screen, and how many cells it jolts.
+    shake: Duration,
+    shake_cells: u16,
+}
+
+impl Effects {
+    pub fn new(seed: u64) -> Self {
+        Effects { ripples: Vec::new(), particles: Particles::new(seed), shake: Duration::ZERO, shake_cells: 0 }
+    }
+
+    /// The hook hitting the water at `(x, y)`: a splash of `color` drops and a wide ring.
+    pub fn splash(&mut self, x: u16, y: u16, color: Color) {
+        self.particles.splash(x, y, color);
+        self.ripple(x, y, SPLASH_RIPPLE_RADIUS);
+    }
+
+    /// Rings spreading from `(x, y)` out to `radius` columns.
+    pub fn ripple(&mut self, x: u16, y: u16, radius: u16) {
+        self.ripples.push(Ripple { x, y, radius, age: Duration::ZERO });
+    }
+
+    /// Shake the screen for `duration`, jolting it `cells` (1 or 2) either way, or longer
+    /// and harder if it is already shaking more.
+    pub fn shake(&mut self, duration: Duration, cells: u16) {
+        if self.shake.is_zero() {
+            self.shake_cells = 0;
+        }
+        self.shake = self.shake.max(duration);
+        self.shake_cells = self.shake_cells.max(cells.clamp(1, MAX_SHAKE_CELLS));
+    }
+
+    /// Columns and rows to shift the scene this frame: a jolt one way or the other while
+    /// shaking, else none. Only a hard shake moves it up and down.
+    pub fn shake_offset(&self) -> (i16, i16) {
+        if self.shake.is_zero() {
+            return (0, 0);
+        }
+        let (dx, dy) = JITTER[(self.shake.as_millis() / SHAKE_JOLT.as_millis()) as usize % JITTER.len()];
+        let cells = self.shake_cells as i16;
+        (dx * cells, if cells > 1 { dy } else { 0 })
+    }
+
+    /// Age every effect by `dt`, dropping those that have finished.
+    pub fn tick(&mut self, dt: Duration) {
+        self.shake = self.shake.saturating_sub(dt);
+        for ripple in &mut self.ripples {
+            ripple.age += dt;
+        }
+        self.ripples.retain(|r| r.age < RIPPLE_DURATION);
+        self.particles.tick(dt);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ripples.is_empty() && self.particles.is_empty() && self.shake.is_zero()
+    }
+}
+
+/// Draws [`Effects`] over the scene. Only the symbol and foreground of each cell are
+/// replaced, so the water and sky show through around the rings and drops.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EffectLayer {
+    pub theme: Theme,
+    /// Draw the rings as ovals of braille dots (`--braille`).
+    pub braille: bool,
+}
+
+impl StatefulWidget for EffectLayer {
+    type State = Effects;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Effects) {
+        for ripple in &state.ripples {
+            // Rings fade from foam to wave colour as they spread
+            let progress = ripple.progress();
+            let color = if progress < 0.5 { self.theme.foam } else { self.theme.wave_alt };
+            if self.braille {
+                // Each ring grows smoothly rather than a column at a time
+                let mut canvas = BrailleCanvas::new(area);
+                let mut r = 1.0 + (ripple.radius.saturating_sub(1) as f32 * progress);
+                while r > 0.0 {
+                    for i in 0..BRAILLE_RING_SAMPLES {
+                        let angle = i as f32 / BRAILLE_RING_SAMPLES as f32 * std::f32::consts::TAU;
+                        canvas.dot(ripple.x as f32 + r * angle.cos(), ripple.y as f32 + BRAILLE_RING_DEPTH * angle.sin());
+                    }
+                    r -= RING_SPACING as f32;
+                }
+                canvas.render(buf, color);
+                continue;
+            }
+            let mut r = 1 + (ripple.radius.saturating_sub(1) as f32 * progress) as u16;
+            while r > 0 {
+                put(buf, area, ripple.x as i32 - r as i32, ripple.y, '(', color);
+                put(buf, area, ripple.x as i32 + r as i32, ripple.y, ')', color);
+                r = r.saturating_sub(RING_SPACING);
+            }
+        }
+        (&state.particles).render(area, buf);
+    }
+}
+
+/// Shift everything drawn in `area` `dx` columns sideways and `dy` rows down, as a
+/// frame of a screen shake. Cells uncovered at the trailing edges are blanked.
+pub fn shift(buf: &mut Buffer, area: Rect, (dx, dy): (i16, i16)) {
+    let area = area.intersection(buf.area);
+    if (dx, dy) == (0, 0) || area.is_empty() {
+        return;
+    }
+    let cells: Vec<_> = area.positions().map(|p| buf[p].clone()).collect();
+    let width = area.width as i32;
+    for (i, position) in area.positions().enumerate() {
+        let (column, row) = (i as i32 % width - dx as i32, i as i32 / width - dy as i32);
+        let from = (column >= 0 && column < width && row >= 0).then(|| cells.get((row * width + column) as usize)).flatten();
+        match from {
+            Some(cell) => buf[position] = cell.clone(),
+            None => {
+                buf[position].reset();
+            }
+        }
+    }
+}
+
+fn put(buf: &mut Buffer, area: Rect, x: i32, y: u16, ch: char, color: Color) {
+    if x < area.x as i32 || x >= area.right() as i32 || y < area.y || y >= area.bottom() {
+        return;
+    }
+    buf[(x as u16, y)].set_char(ch).set_fg(color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect()
+    }
+
+    #[test]
+    fn splash_spreads_and_expires() {
+        let area = Rect::new(0, 0, 21, 4);
+        let mut effects = Effects::new(1);
+        effects.splash(10, 3, Color::White);
+
+        let mut buf = Buffer::empty(area);
+        EffectLayer::default().render(area, &mut buf, &mut effects);
+        assert_eq!(row(&buf, 2).trim(), "|");
+        assert_eq!(row(&buf, 3).trim(), "( )");
+
+        effects.tick(Duration::from_millis(1100));
+        let mut buf = Buffer::empty(area);
+        EffectLayer::default().render(area, &mut buf, &mut effects);
+        assert_eq!(row(&buf, 2).trim(), "");
+        assert_eq!(row(&buf, 3), "     ( ( ( ) ) )     ");
+
+        let mut buf = Buffer::empty(area);
+        EffectLayer { braille: true, ..EffectLayer::default() }.render(area, &mut buf, &mut effects);
+        assert!(row(&buf, 3).trim().chars().all(|c| ('\u{2801}'..='\u{28ff}').contains(&c)));
+        assert_eq!(row(&buf, 2).trim(), "");
+
+        effects.tick(Duration::from_millis(100));
+        assert!(effects.is_empty());
+    }
+
+    #[test]
+    fn shaking_jolts_the_scene_both_ways() {
+        let mut effects = Effects::new(1);
+        effects.shake(SHAKE_JOLT * 2, 1);
+        let first = effects.shake_offset();
+        effects.tick(SHAKE_JOLT);
+        assert_eq!(effects.shake_offset(), (-first.0, 0));
+        effects.tick(SHAKE_JOLT);
+        assert_eq!(effects.shake_offset(), (0, 0));
+        // A hard shake throws it further, and up and down too
+        effects.shake(SHAKE_JOLT * 4, 5);
+        let mut jolts = Vec::new();
+        for _ in 0..4 {
+            jolts.push(effects.shake_offset());
+            effects.tick(SHAKE_JOLT);
+        }
+        assert!(jolts.iter().all(|(dx, dy)| dx.abs() == 2 && dy.abs() <= 1) && jolts.iter().any(|(_, dy)| *dy != 0));
+
+        let area = Rect::new(0, 0, 5, 2);
+        let mut buf = Buffer::with_lines(["abcde", "fghij"]);
+        shift(&mut buf, area, (1, 0));
+        assert_eq!(row(&buf, 0), " abcd");
+        shift(&mut buf, area, (-2, 0));
+        assert_eq!(row(&buf, 0), "bcd  ");
+        shift(&mut buf, area, (0, 1));
+        assert_eq!((row(&buf, 0), row(&buf, 1)), ("     ".to_string(), "bcd  ".to_string()));
+    }
+}
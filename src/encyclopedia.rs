@@ -0,0 +1,186 @@
+//! The encyclopedia (`e`): every species in the sea, one at a time, with its sprite
+//! swimming beside how rare it is, how big it grows, how deep it keeps and how many
+//! have been landed, from the journal. A species not landed yet is only a silhouette,
+//! its name and size a mystery until the first is caught.
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Widget};
+
+use crate::achievements::Journal;
+use crate::csv_frames::{SpeciesFrames, SpriteFrame};
+use crate::fish::{self, SpeciesTraits};
+use crate::theme::Theme;
+
+/// How wide the list of species may grow, as a share of the panel.
+const LIST_SHARE: u16 = 3;
+
+/// Which species is open and how long its sprite has swum.
+#[derive(Debug, Clone, Default)]
+pub struct EncyclopediaState {
+    pub selected: usize,
+    elapsed: Duration,
+}
+
+impl EncyclopediaState {
+    pub fn tick(&mut self, dt: Duration) {
+        self.elapsed += dt;
+    }
+
+    /// Move `step` species down the list of `count`, wrapping round at either end.
+    pub fn select(&mut self, step: isize, count: usize) {
+        if count > 0 {
+            self.selected = (self.selected as isize + step).rem_euclid(count as isize) as usize;
+        }
+    }
+}
+
+/// How many of `species` have been landed: the catches logged, or at least one for a
+/// species landed before the journal began logging them.
+pub fn caught(journal: &Journal, species: &str) -> usize {
+    let logged = journal.log.iter().filter(|c| c.species == species).count();
+    logged.max(journal.species.contains(species) as usize)
+}
+
+/// `common`, `uncommon` or `rare`, for a rarity from 0.0 to 1.0.
+fn rarity_name(rarity: f32) -> &'static str {
+    match rarity {
+        r if r < 0.34 => "common",
+        r if r < 0.67 => "uncommon",
+        _ => "rare",
+    }
+}
+
+/// `surface`, `mid` or `deep`, for a depth from 0.0 (surface) to 1.0 (deepest lane).
+fn depth_name(depth: f32) -> &'static str {
+    match depth {
+        d if d < 0.25 => "surface",
+        d if d < 0.75 => "mid",
+        _ => "deep",
+    }
+}
+
+/// The shape of `frame` filled in solid, for a species not landed yet.
+fn silhouette(frame: &SpriteFrame, color: Color) -> SpriteFrame {
+    let style = Style::default().fg(color);
+    let cells = frame.cells.iter()
+        .map(|cell| cell.as_ref().filter(|(symbol, _)| !symbol.trim().is_empty()).map(|_| ("█".to_string(), style)))
+        .collect();
+    SpriteFrame { cells, ..*frame }
+}
+
+/// The encyclopedia, filling the area it's given.
+pub struct Encyclopedia<'a> {
+    pub species: &'a [String],
+    pub frames: &'a [SpeciesFrames],
+    pub traits: &'a [SpeciesTraits],
+    pub journal: &'a Journal,
+    pub theme: Theme,
+}
+
+impl StatefulWidget for Encyclopedia<'_> {
+    type State = EncyclopediaState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut EncyclopediaState) {
+        Clear.render(area, buf);
+        let landed = self.species.iter().filter(|name| caught(self.journal, name) > 0).count();
+        let title = format!("Encyclopedia · {}/{} species caught", landed, self.species.len());
+        let block = Block::default().title(title).borders(Borders::ALL).style(Style::default().bg(self.theme.water));
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if self.species.is_empty() || inner.is_empty() {
+            return;
+        }
+        state.selected = state.selected.min(self.species.len() - 1);
+
+        // The list down the left, scrolled to keep the open species in view
+        let longest = self.species.iter().map(|name| name.chars().count()).max().unwrap_or(0) as u16;
+        let list = Rect { width: (longest + 3).min(inner.width / LIST_SHARE), ..inner };
+        let top = (state.selected + 1).saturating_sub(list.height as usize);
+        let label = Style::default().fg(self.theme.foam);
+        for (row, (i, name)) in self.species.iter().enumerate().skip(top).take(list.height as usize).enumerate() {
+            let known = caught(self.journal, name) > 0;
+            let text = if known { name.as_str() } else { "???" };
+            let (marker, style) = if i == state.selected { ("▸ ", label.add_modifier(Modifier::REVERSED)) } else { ("  ", label) };
+            buf.set_stringn(list.x, list.y + row as u16, format!("{}{}", marker, text), list.width as usize, style);
+        }
+
+        let page = Rect { x: list.right() + 1, width: inner.width.saturating_sub(list.width + 1), ..inner };
+        let name = &self.species[state.selected];
+        let traits = self.traits.get(state.selected).copied().unwrap_or_default();
+        let count = caught(self.journal, name);
+        let mut lines = vec![
+            Line::styled(if count > 0 { name.as_str() } else { "???" }, label.add_modifier(Modifier::BOLD)),
+            Line::from(""),
+            Line::from(format!("Rarity  {}", rarity_name(traits.rarity))),
+        ];
+        lines.push(Line::from(match count {
+            0 => "Size    ?".to_string(),
+            _ => format!("Size    {:.0}–{:.0} cm, {:.0} on average", traits.sizes.min, traits.sizes.max, traits.sizes.mean),
+        }));
+        lines.push(Line::from(format!("Depth   {}", depth_name(traits.depth))));
+        lines.push(Line::from(match (count, self.journal.best.get(name)) {
+            (0, _) => "Not caught yet".to_string(),
+            (count, Some(best)) => format!("Caught  {}, the biggest {:.0} cm", count, best),
+            (count, None) => format!("Caught  {}", count),
+        }));
+        let text_height = (lines.len() as u16).min(page.height);
+        let text_area = Rect { y: page.bottom() - text_height, height: text_height, ..page };
+        Paragraph::new(lines).style(label).render(text_area, buf);
+
+        // The sprite swims on the spot above, centred in what room is left
+        let Some((right, _)) = self.frames.get(state.selected).filter(|(right, _)| !right.is_empty()) else {
+            return;
+        };
+        let sprite = SpriteFrame::bake(fish::frame_at(right, state.elapsed));
+        let sprite = if count > 0 { sprite } else { silhouette(&sprite, self.theme.rock) };
+        let stage = Rect { height: page.height - text_height, ..page };
+        let x = stage.x as i32 + (stage.width as i32 - sprite.width as i32) / 2;
+        let y = stage.y as i32 + (stage.height as i32 - sprite.height as i32).max(0) / 2;
+        sprite.blit(buf, (x, y), 1, stage, &[]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Assets;
+    use crate::fishing_game::{CaughtFish, SizeCategory};
+
+    #[test]
+    fn species_stay_silhouettes_until_the_first_is_landed() {
+        assert_eq!((rarity_name(0.0), rarity_name(0.5), rarity_name(0.9)), ("common", "uncommon", "rare"));
+        assert_eq!((depth_name(0.0), depth_name(0.5), depth_name(1.0)), ("surface", "mid", "deep"));
+        let mut state = EncyclopediaState::default();
+        state.select(-1, 3);
+        assert_eq!(state.selected, 2);
+        state.select(1, 3);
+        assert_eq!(state.selected, 0);
+
+        let assets = Assets::load();
+        let names: Vec<String> = assets.species.iter().map(|s| s.name.clone()).collect();
+        let traits: Vec<SpeciesTraits> = assets.species.iter().map(|s| s.traits).collect();
+        let mut journal = Journal::default();
+        let draw = |journal: &Journal, state: &mut EncyclopediaState| {
+            let mut buf = Buffer::empty(Rect::new(0, 0, 80, 24));
+            Encyclopedia { species: &names, frames: &assets.frames, traits: &traits, journal, theme: Theme::default() }.render(buf.area, &mut buf, state);
+            let rows: Vec<String> = (0..24).map(|y| (0..80).map(|x| buf[(x, y)].symbol().to_string()).collect()).collect();
+            rows.join("\n")
+        };
+        let locked = draw(&journal, &mut state);
+        assert!(locked.contains(&format!("0/{} species caught", names.len())), "{}", locked);
+        assert!(locked.contains("▸ ???") && !locked.contains(&names[0]) && locked.contains("Not caught yet"));
+        assert!(locked.contains('█'));
+
+        let caught_fish = CaughtFish { species_name: names[0].clone(), size: 30.0, size_category: SizeCategory::Average, shiny: false };
+        journal.record(&caught_fish, false, &names);
+        assert_eq!(caught(&journal, &names[0]), 1);
+        let unlocked = draw(&journal, &mut state);
+        assert!(unlocked.contains(&format!("▸ {}", names[0])), "{}", unlocked);
+        assert!(unlocked.contains("Caught  1, the biggest 30 cm"));
+    }
+}
@@ -0,0 +1,80 @@
+//! The event bus between the subsystems of the game. Input handling and the update
+//! publish what happened as a [`GameEvent`] through [`App`](crate::app::App), which
+//! hands it to each subscriber in turn: the scene's own reactions, the journal,
+//! `[hooks]` and the screen reader feed. Then it waits on an [`EventBus`] for layers
+//! outside the app, such as sound, to collect with
+//! [`App::take_events`](crate::app::App::take_events).
+//!
+//! Events are delivered as they are published, while the rod they're about is the one
+//! being played, so a subscriber sees the same game the publisher did.
+
+use std::collections::VecDeque;
+
+use crate::fishing_game::CaughtFish;
+use crate::weather::Weather;
+
+/// Events kept for a layer that never collects them, before the oldest are dropped.
+const MAX_PENDING: usize = 32;
+
+/// Something that happened in the game.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// The line went out.
+    Cast,
+    /// The hook hit the water at column `x`, row `y`.
+    CastLanded { x: u16, y: u16 },
+    /// A fish was landed. `rarity` is its species' from the sprite metadata.
+    FishCaught {
+        fish: CaughtFish,
+        rarity: f32,
+        /// Landed by the AI fisherman, whose catches aren't the player's to celebrate.
+        autopilot: bool,
+    },
+    /// A SUCCESS or FAILURE signal arrived, or was faked from the keyboard.
+    SignalReceived { success: bool, message: String },
+    /// The weather turned.
+    WeatherChanged(Weather),
+    /// A tournament round ran out of time.
+    RoundOver { points: u32, catches: u32 },
+}
+
+/// Published events waiting to be collected, oldest first.
+#[derive(Debug, Default)]
+pub struct EventBus {
+    pending: VecDeque<GameEvent>,
+}
+
+impl EventBus {
+    pub fn push(&mut self, event: GameEvent) {
+        if self.pending.len() == MAX_PENDING {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(event);
+    }
+
+    /// The events pushed since the last call, oldest first.
+    pub fn take(&mut self) -> Vec<GameEvent> {
+        self.pending.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_bus_nobody_collects_keeps_only_the_latest_events() {
+        let mut bus = EventBus::default();
+        bus.push(GameEvent::Cast);
+        bus.push(GameEvent::CastLanded { x: 3, y: 4 });
+        assert_eq!(bus.take(), [GameEvent::Cast, GameEvent::CastLanded { x: 3, y: 4 }]);
+        assert!(bus.take().is_empty());
+
+        for points in 0..MAX_PENDING as u32 + 5 {
+            bus.push(GameEvent::RoundOver { points, catches: 0 });
+        }
+        let kept = bus.take();
+        assert_eq!(kept.len(), MAX_PENDING);
+        assert_eq!(kept[0], GameEvent::RoundOver { points: 5, catches: 0 });
+    }
+}
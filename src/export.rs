@@ -0,0 +1,295 @@
+//! The `fisherman export` subcommand: dump the catch journal from the saved game, and
+//! stats totted up from it, as JSON or CSV for spreadsheets and dashboards.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::achievements::{Journal, LoggedCatch};
+use crate::game_state::{self, GameState};
+
+const USAGE: &str = "\
+usage: fisherman export [--format json|csv] [--out FILE] [--save FILE]
+                        [--since YYYY-MM-DD] [--species NAME]... [--table catches|species]
+
+Writes the catches in the journal, and each species' count, biggest and average
+size, to FILE (standard output if not given). JSON carries both along with the
+lifetime totals; CSV is one table, the catches unless --table species is given.
+--since keeps catches from that day on and --species keeps only the species named.
+The format defaults to csv when FILE ends in .csv and json otherwise.";
+
+const EXIT_USAGE: i32 = 2;
+const EXIT_FAILED: i32 = 1;
+
+/// How the export is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+/// Which table a CSV export holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Table {
+    Catches,
+    Species,
+}
+
+/// Which catches go in.
+#[derive(Debug, Clone, Default)]
+struct Filter {
+    /// `YYYY-MM-DD`, which sorts as the dates do.
+    since: Option<String>,
+    species: Vec<String>,
+}
+
+impl Filter {
+    fn keeps(&self, catch: &LoggedCatch) -> bool {
+        self.since.as_ref().is_none_or(|since| catch.date.as_str() >= since.as_str())
+            && (self.species.is_empty() || self.species.iter().any(|name| name.eq_ignore_ascii_case(&catch.species)))
+    }
+}
+
+/// One species' catches among those exported.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct SpeciesStats {
+    species: String,
+    catches: u32,
+    shiny: u32,
+    biggest: f32,
+    average: f32,
+}
+
+/// The journal's totals over the whole game, whatever the filters.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Lifetime {
+    catches: u32,
+    species: usize,
+    shiny_species: usize,
+    achievements: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Export<'a> {
+    lifetime: Lifetime,
+    species: Vec<SpeciesStats>,
+    catches: Vec<&'a LoggedCatch>,
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let mut format = None;
+    let mut out = None;
+    let mut save = None;
+    let mut table = Table::Catches;
+    let mut filter = Filter::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let parsed = match (arg.as_str(), args.next()) {
+            ("--format", Some(value)) => parse_format(value).map(|f| format = Some(f)),
+            ("--since", Some(value)) => parse_since(value).map(|since| filter.since = Some(since)),
+            ("--table", Some(value)) => parse_table(value).map(|t| table = t),
+            ("--out", Some(value)) => {
+                out = Some(PathBuf::from(value));
+                Ok(())
+            }
+            ("--save", Some(value)) => {
+                save = Some(PathBuf::from(value));
+                Ok(())
+            }
+            ("--species", Some(value)) => {
+                filter.species.push(value.clone());
+                Ok(())
+            }
+            _ => Err(String::new()),
+        };
+        if let Err(e) = parsed {
+            if !e.is_empty() {
+                eprintln!("fisherman export: {}", e);
+            }
+            eprintln!("{}", USAGE);
+            return EXIT_USAGE;
+        }
+    }
+    let format = format.unwrap_or(match out {
+        Some(ref path) if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) => Format::Csv,
+        _ => Format::Json,
+    });
+    let save = save.unwrap_or_else(game_state::default_save_path);
+
+    match export(&save, format, table, &filter) {
+        Ok(text) => match write_out(out.as_deref(), &text) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("fisherman export: {}", e);
+                EXIT_FAILED
+            }
+        },
+        Err(e) => {
+            eprintln!("fisherman export: {}", e);
+            EXIT_FAILED
+        }
+    }
+}
+
+fn parse_format(s: &str) -> Result<Format, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        other => Err(format!("unknown format '{}' (expected json or csv)", other)),
+    }
+}
+
+fn parse_table(s: &str) -> Result<Table, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "catches" => Ok(Table::Catches),
+        "species" => Ok(Table::Species),
+        other => Err(format!("unknown table '{}' (expected catches or species)", other)),
+    }
+}
+
+/// A `--since` date, checked to be `YYYY-MM-DD`.
+fn parse_since(s: &str) -> Result<String, String> {
+    let invalid = || format!("invalid --since '{}' (expected YYYY-MM-DD)", s);
+    let parts: Vec<&str> = s.split('-').collect();
+    let [year, month, day] = parts[..] else {
+        return Err(invalid());
+    };
+    let number = |part: &str, len: usize| (part.len() == len).then(|| part.parse::<u16>().ok()).flatten();
+    match (number(year, 4), number(month, 2), number(day, 2)) {
+        (Some(_), Some(1..=12), Some(1..=31)) => Ok(s.to_string()),
+        _ => Err(invalid()),
+    }
+}
+
+/// The export of the game saved at `save`.
+fn export(save: &Path, format: Format, table: Table, filter: &Filter) -> Result<String, String> {
+    let state = GameState::load(save).map_err(|e| format!("{}: {}", save.display(), e))?;
+    let Some(state) = state else {
+        return Err(format!("{}: no saved game", save.display()));
+    };
+    Ok(render(&state.profile.journal, format, table, filter))
+}
+
+fn render(journal: &Journal, format: Format, table: Table, filter: &Filter) -> String {
+    let catches: Vec<&LoggedCatch> = journal.log.iter().filter(|catch| filter.keeps(catch)).collect();
+    let species = species_stats(&catches);
+    match (format, table) {
+        (Format::Json, _) => {
+            let lifetime = Lifetime {
+                catches: journal.catches,
+                species: journal.species.len(),
+                shiny_species: journal.shiny.len(),
+                achievements: journal.unlocked.iter().map(|a| a.title()).collect(),
+            };
+            let export = Export { lifetime, species, catches };
+            // Nothing in it can fail to serialize
+            serde_json::to_string_pretty(&export).unwrap_or_default() + "\n"
+        }
+        (Format::Csv, Table::Catches) => {
+            let mut csv = String::from("date,species,size_cm,category,shiny\n");
+            for catch in catches {
+                let row = [catch.date.clone(), catch.species.clone(), format!("{:.1}", catch.size), catch.category.clone(), catch.shiny.to_string()];
+                csv.push_str(&csv_row(&row));
+            }
+            csv
+        }
+        (Format::Csv, Table::Species) => {
+            let mut csv = String::from("species,catches,shiny,biggest_cm,average_cm\n");
+            for stats in species {
+                let row = [
+                    stats.species,
+                    stats.catches.to_string(),
+                    stats.shiny.to_string(),
+                    format!("{:.1}", stats.biggest),
+                    format!("{:.1}", stats.average),
+                ];
+                csv.push_str(&csv_row(&row));
+            }
+            csv
+        }
+    }
+}
+
+/// Each species among `catches`, by name.
+fn species_stats(catches: &[&LoggedCatch]) -> Vec<SpeciesStats> {
+    let mut by_species: BTreeMap<&str, Vec<&LoggedCatch>> = BTreeMap::new();
+    for catch in catches {
+        by_species.entry(catch.species.as_str()).or_default().push(catch);
+    }
+    by_species
+        .into_iter()
+        .map(|(species, catches)| {
+            let total: f32 = catches.iter().map(|c| c.size).sum();
+            SpeciesStats {
+                species: species.to_string(),
+                catches: catches.len() as u32,
+                shiny: catches.iter().filter(|c| c.shiny).count() as u32,
+                biggest: catches.iter().map(|c| c.size).fold(0.0, f32::max),
+                average: total / catches.len() as f32,
+            }
+        })
+        .collect()
+}
+
+/// A CSV line, quoting the fields that need it.
+fn csv_row(fields: &[String]) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    quoted.join(",") + "\n"
+}
+
+fn write_out(out: Option<&Path>, text: &str) -> Result<(), String> {
+    match out {
+        Some(path) => fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => io::stdout().write_all(text.as_bytes()).map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fishing_game::{CaughtFish, SizeCategory};
+
+    #[test]
+    fn catches_export_filtered_with_stats_per_species() {
+        let mut journal = Journal::default();
+        let fish = |name: &str, size| CaughtFish { species_name: name.to_string(), size, size_category: SizeCategory::Average, shiny: false };
+        journal.record(&fish("Trout", 40.0), false, &[]);
+        journal.record(&fish("Trout", 20.0), false, &[]);
+        journal.record(&fish("Sea, Bass", 30.0), false, &[]);
+        journal.log[0].date = "2020-01-01".to_string();
+
+        assert!(parse_since("2024-02-29").is_ok());
+        assert!(parse_since("2024-2-29").is_err() && parse_since("yesterday").is_err());
+        assert!(parse_format("xml").is_err());
+
+        let since = Filter { since: Some("2021-01-01".to_string()), species: Vec::new() };
+        let csv = render(&journal, Format::Csv, Table::Catches, &since);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "date,species,size_cm,category,shiny");
+        assert!(lines[1].ends_with(",Trout,20.0,Average,false"));
+        assert!(lines[2].contains(",\"Sea, Bass\",30.0,"));
+
+        let trout = Filter { since: None, species: vec!["trout".to_string()] };
+        let csv = render(&journal, Format::Csv, Table::Species, &trout);
+        assert_eq!(csv, "species,catches,shiny,biggest_cm,average_cm\nTrout,2,0,40.0,30.0\n");
+
+        let json: serde_json::Value = serde_json::from_str(&render(&journal, Format::Json, Table::Catches, &trout)).unwrap();
+        assert_eq!(json["lifetime"]["catches"], 3);
+        assert_eq!(json["lifetime"]["achievements"][0], "First Catch");
+        assert_eq!(json["catches"].as_array().unwrap().len(), 2);
+        assert_eq!(json["species"][0]["biggest"], 40.0);
+    }
+}
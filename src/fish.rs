@@ -1,8 +1,19 @@
 use std::time::Duration;
-use ratatui::text::Text;
+use ratatui::buffer::Buffer;
+use ratatui::text::{Line, Span, Text};
 use ratatui::layout::Rect;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
 
-#[derive(Debug, Clone)]
+use crate::color;
+use crate::config_file::{Table, Value};
+use crate::csv_frames::{self, AnimationFrames, BakedSpecies, SpeciesFrames, SpriteFrame, frames_size, line_cells};
+use crate::economy::Bait;
+use crate::fishing_game::{SizeCategory, SizeDistribution};
+use crate::season::Seasons;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fish {
     pub lane: usize,
     pub x: f32,
@@ -10,9 +21,229 @@ pub struct Fish {
     pub wrap: bool,
     pub facing_right: bool,
     pub species: usize,
-    pub frame_duration: Duration,
+    /// Multiplies the species' frame durations, so fast fish beat their tails faster.
+    #[serde(default = "default_frame_scale")]
+    pub frame_scale: f32,
     pub spawn_delay_ms: u64,
     pub size: f32,
+    /// Speed the fish returns to when nothing attracts it.
+    pub cruise_speed: f32,
+    /// How strongly the fish is drawn to a hook in its lane, 0.0 (ignores it) to 1.0.
+    pub aggression: f32,
+    pub pattern: SwimPattern,
+    /// Per-fish time offset so fish sharing a pattern don't move in lockstep.
+    pub phase: f32,
+    /// Vertical offset from the lane in rows, set by the swim pattern.
+    pub y_offset: f32,
+    /// Time left before the fish looks at the hook again after turning down the bait.
+    #[serde(default)]
+    pub hook_shy: Duration,
+    /// The school the fish swims in, if any. The first fish of a school in the list
+    /// leads and the rest follow it.
+    #[serde(default)]
+    pub school: Option<u32>,
+    /// Where the fish keeps station in its school, in columns and rows. Followers stay
+    /// this far from the leader, less the leader's own offset.
+    #[serde(default)]
+    pub school_offset: (f32, f32),
+    /// Times the hook still has to be set to land a boss fish; 0 for ordinary fish.
+    #[serde(default)]
+    pub boss_fights: u8,
+    /// A rare variant drawn in shifted colours and worth more.
+    #[serde(default)]
+    pub shiny: bool,
+    /// Rows still to go on the way into this lane from the last, easing to 0.0.
+    #[serde(default)]
+    pub lane_shift: f32,
+    /// How big it's drawn against the rest of its species.
+    #[serde(default)]
+    pub build: Build,
+}
+
+impl Fish {
+    /// The swim pattern's vertical offset and what's left of a change of lane,
+    /// rounded to whole rows.
+    pub fn row_offset(&self) -> i32 {
+        (self.y_offset + self.lane_shift).round() as i32
+    }
+
+    pub fn is_boss(&self) -> bool {
+        self.boss_fights > 0
+    }
+
+    /// How many times larger than its species' sprite the fish is drawn.
+    pub fn scale(&self) -> u16 {
+        if self.is_boss() { BOSS_SCALE } else { 1 }
+    }
+
+    /// Seconds of a taut line it takes to tire once hooked: its species' stamina, more
+    /// for a bigger fish and less for a smaller one. Bosses fight in runs instead.
+    pub fn stamina(&self, traits: &SpeciesTraits) -> f32 {
+        if self.is_boss() { 0.0 } else { traits.stamina * self.size / traits.sizes.mean.max(0.1) }
+    }
+
+    /// Width and height on screen of a frame `size` cells across, at the fish's build
+    /// and scale: no taller than a lane, or the lanes a boss spans.
+    pub fn drawn_size(&self, size: (u16, u16)) -> (u16, u16) {
+        let (width, height) = self.build.apply(size);
+        (width * self.scale(), height.min(FISH_HEIGHT) * self.scale())
+    }
+}
+
+/// How big a fish is drawn against the rest of its species, from how its size
+/// compares: smaller for Tiny and Small fish and larger for Large and Massive ones,
+/// so a big one shows before it's hooked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Build {
+    Small,
+    #[default]
+    Normal,
+    Large,
+}
+
+impl Build {
+    pub const ALL: [Build; 3] = [Build::Small, Build::Normal, Build::Large];
+
+    pub fn of(category: &SizeCategory) -> Build {
+        match category {
+            SizeCategory::Tiny | SizeCategory::Small => Build::Small,
+            SizeCategory::Average => Build::Normal,
+            SizeCategory::Large | SizeCategory::Massive => Build::Large,
+        }
+    }
+
+    /// How much the species' sprite is stretched or squeezed for the build.
+    fn factor(self) -> f32 {
+        match self {
+            Build::Small => 0.75,
+            Build::Normal => 1.0,
+            Build::Large => 1.4,
+        }
+    }
+
+    /// The size a sprite `size` cells across is drawn at, at least a cell each way.
+    pub fn apply(self, size: (u16, u16)) -> (u16, u16) {
+        let scaled = |cells: u16| ((cells as f32 * self.factor()).round() as u16).max(1);
+        if self == Build::Normal { size } else { (scaled(size.0), scaled(size.1)) }
+    }
+}
+
+/// Species' frames baked for drawing at each [`Build`], indexed by it.
+pub type BuiltFrames = [Vec<BakedSpecies>; 3];
+
+/// `frames` baked at every build, each frame stretched or squeezed to the build's size.
+pub fn bake_builds(frames: &[SpeciesFrames]) -> BuiltFrames {
+    Build::ALL.map(|build| {
+        let bake = |text: &Text| match build {
+            Build::Normal => SpriteFrame::bake(text),
+            _ => {
+                let (width, height) = build.apply((text.width() as u16, text.height() as u16));
+                SpriteFrame::bake(&csv_frames::resize_text(text, width, height))
+            }
+        };
+        let bake_all = |frames: &AnimationFrames| frames.iter().map(|(text, d)| (bake(text), *d)).collect();
+        frames.iter().map(|species| (bake_all(&species.0), bake_all(&species.1))).collect()
+    })
+}
+
+/// How a species moves through the water, on top of its cruising speed and steering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SwimPattern {
+    /// Glide at a constant speed.
+    #[default]
+    Steady,
+    /// Short bursts of speed separated by pauses of varying length.
+    Darting,
+    /// Weave up and down through the lane.
+    Weave,
+    /// Cruise slowly along the bottom of the lane; spawns in the deepest lane.
+    BottomHugging,
+}
+
+impl std::str::FromStr for SwimPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "steady" => Ok(SwimPattern::Steady),
+            "darting" => Ok(SwimPattern::Darting),
+            "weave" | "sine-weave" => Ok(SwimPattern::Weave),
+            "bottom" | "bottom-hugging" => Ok(SwimPattern::BottomHugging),
+            other => Err(format!("unknown swim pattern '{}'", other)),
+        }
+    }
+}
+
+const DART_CYCLE_SECS: f32 = 1.6;
+const DART_BURST_SECS: f32 = 0.35;
+
+impl SwimPattern {
+    /// Multiplier on the fish's speed at pattern time `t` (seconds, phase included).
+    fn speed_factor(self, t: f32) -> f32 {
+        match self {
+            SwimPattern::Steady | SwimPattern::Weave => 1.0,
+            SwimPattern::BottomHugging => 0.6,
+            SwimPattern::Darting => {
+                let cycle = (t / DART_CYCLE_SECS).floor();
+                // Skip the burst in roughly a third of the cycles for uneven pauses.
+                let skip = (cycle * 12.9898).sin().abs().fract() < 0.33;
+                if !skip && t - cycle * DART_CYCLE_SECS < DART_BURST_SECS {
+                    3.5
+                } else {
+                    0.15
+                }
+            }
+        }
+    }
+
+    /// Vertical offset in rows at pattern time `t`, including a slow drift within the
+    /// lane's band for everything but bottom-huggers.
+    fn y_offset(self, t: f32) -> f32 {
+        let drift = (t * 0.3).sin() * DRIFT_ROWS;
+        match self {
+            SwimPattern::Weave => (t * 1.8).sin() * 1.2 + drift,
+            SwimPattern::BottomHugging => 1.0,
+            SwimPattern::Steady | SwimPattern::Darting => drift,
+        }
+    }
+}
+
+/// Per-species behavior, read from the species' `meta.csv`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeciesTraits {
+    /// How long each frame shows at the reference swimming speed, unless the frame's
+    /// file name sets its own.
+    pub frame_duration: Duration,
+    pub aggression: f32,
+    pub pattern: SwimPattern,
+    /// Preferred depth from 0.0 (surface) to 1.0 (deepest lane).
+    pub depth: f32,
+    /// From 0.0 (common) to 1.0 (rare): rare species spawn less often and are worth more.
+    pub rarity: f32,
+    /// The most fish in a school of this species; 1 swims alone.
+    pub school: usize,
+    /// The seasons it swims in; out of season it doesn't turn up.
+    pub seasons: Seasons,
+    pub sizes: SizeDistribution,
+    /// Seconds of a taut line it takes an average-sized fish to tire once hooked; 0
+    /// lands it on the strike.
+    pub stamina: f32,
+}
+
+impl Default for SpeciesTraits {
+    fn default() -> Self {
+        SpeciesTraits {
+            frame_duration: DEFAULT_FRAME_DURATION,
+            aggression: DEFAULT_AGGRESSION,
+            pattern: SwimPattern::Steady,
+            depth: DEFAULT_DEPTH,
+            rarity: 0.0,
+            school: 1,
+            seasons: Seasons::ALL,
+            sizes: SizeDistribution::default(),
+            stamina: 0.0,
+        }
+    }
 }
 
 pub const FISH_HEIGHT: u16 = 6;
@@ -23,31 +254,178 @@ const BASE_SCREEN_WIDTH: f32 = 80.0;
 const MAX_SPAWN_CHANCE: f64 = 0.95;
 const MIN_WIDTH_FACTOR: f32 = 0.5;
 const MAX_SPAWN_DELAY_MS: u64 = 5000;
-const DEFAULT_FRAME_DURATION_MS: u64 = 150;
+pub const DEFAULT_FRAME_DURATION: Duration = Duration::from_millis(150);
+/// Swimming speed at which a species animates at its own frame duration.
+const REFERENCE_SPEED: f32 = 6.0;
+pub const DEFAULT_AGGRESSION: f32 = 0.5;
+/// Preferred depth of species that don't set one: mid-water.
+pub const DEFAULT_DEPTH: f32 = 0.5;
+/// How much a rarity of 1.0 cuts a species' spawn weight.
+const RARITY_SPAWN_PENALTY: f32 = 0.8;
+/// How far (in rows) fish slowly drift up and down around their lane.
+const DRIFT_ROWS: f32 = 1.5;
+/// How far (in columns) a fully aggressive fish notices a hook in its lane.
+const ATTRACT_RANGE: f32 = 30.0;
+/// Columns per second on top of their cruising speed that fish bolt at in a fully
+/// agitated sea.
+const FLEE_SPEED: f32 = 30.0;
 const EDGE_SPAWN_OFFSET: f32 = 8.0;
+/// Columns between neighbours in a school, on top of the sprite's width.
+const SCHOOL_SPACING: f32 = 2.0;
+/// How far (in columns; half that in rows) schooling fish jostle around their station.
+const SCHOOL_JITTER: f32 = 0.8;
+/// Boss fish are drawn this many times their species' size, across that many lanes.
+pub const BOSS_SCALE: u16 = 2;
+/// Times the hook has to be set on a boss before it is landed.
+pub const BOSS_FIGHTS: u8 = 3;
+/// How much slower a boss cruises than an ordinary fish.
+const BOSS_SLOWDOWN: f32 = 0.5;
+/// Chance that a spawned fish is a shiny variant.
+const SHINY_CHANCE: f64 = 0.02;
+/// Degrees around the colour wheel shiny fish are recoloured by.
+const SHINY_HUE_SHIFT: f32 = 150.0;
+/// Chance a second that a lone fish moves up or down a lane, and how many rows a
+/// second it swims across on the way.
+const LANE_CHANGE_CHANCE: f64 = 0.02;
+const LANE_CHANGE_SPEED: f32 = 3.0;
+/// Columns of screen for each fish a lane holds before it counts as crowded, and the
+/// fewest it holds however narrow the screen.
+const LANE_ROOM: f32 = 16.0;
+/// How many columns from the edge a fish turning there starts to slow down.
+const EDGE_EASE: f32 = 6.0;
+/// How slow, as a share of its cruising speed, a fish has to be before it turns round.
+const TURN_SHARE: f32 = 0.2;
+const MIN_PER_LANE: usize = 2;
+/// The range `--density` can be set in; past the top, lanes fill past what they have
+/// room for.
+pub const MIN_DENSITY: f64 = 0.1;
+pub const MAX_DENSITY: f64 = 4.0;
 
-fn select_frames<'a>(
-    frames_by_species: &'a [(Vec<Text<'a>>, Vec<Text<'a>>)],
-    species_idx: usize,
-    facing_right: bool,
-) -> &'a [Text<'a>] {
+/// How busy the sea is kept, from the `[spawning]` section of the config file or
+/// `--density` and `--max-fish`:
+///
+/// ```toml
+/// [spawning]
+/// density = 2.0
+/// max_fish = 40
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpawnSettings {
+    /// Scales the chance a lane is stocked at each check, how often the checks come
+    /// and how many fish each lane is topped up to.
+    pub density: f64,
+    /// The most fish in the sea at once, whatever the size of the screen.
+    pub max_fish: Option<usize>,
+}
+
+impl Default for SpawnSettings {
+    fn default() -> Self {
+        SpawnSettings { density: 1.0, max_fish: None }
+    }
+}
+
+impl SpawnSettings {
+    pub fn from_table(table: &Table) -> Result<SpawnSettings, String> {
+        let mut settings = SpawnSettings::default();
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("density", Value::Number(density)) => settings.density = parse_density(*density)?,
+                ("density", _) => return Err(format!("[spawning] density must be a number from {} to {}", MIN_DENSITY, MAX_DENSITY)),
+                ("max_fish", Value::Number(max)) => settings.max_fish = Some(parse_max_fish(*max)?),
+                ("max_fish", _) => return Err("[spawning] max_fish must be a whole number from 1 up".to_string()),
+                (key, _) => return Err(format!("unknown setting '{}' in [spawning]", key)),
+            }
+        }
+        Ok(settings)
+    }
+
+    /// How many fish the sea is topped up to with `lanes` lanes across `width`
+    /// columns: one a lane on an 80-column screen, more on a wider one, scaled by the
+    /// density and capped at `max_fish`.
+    pub fn target(&self, lanes: usize, width: f32) -> usize {
+        let per_lane = (self.density * (width / BASE_SCREEN_WIDTH) as f64).round().max(1.0) as usize;
+        let target = lanes * per_lane;
+        self.max_fish.map_or(target, |max| target.min(max))
+    }
+
+    /// How long between checks for lanes to stock, given the time at normal density.
+    pub fn check_interval(&self, normal: Duration) -> Duration {
+        normal.div_f64(self.density)
+    }
+}
+
+/// A density as given, if it's in range.
+pub fn parse_density(density: f64) -> Result<f64, String> {
+    if (MIN_DENSITY..=MAX_DENSITY).contains(&density) {
+        Ok(density)
+    } else {
+        Err(format!("density must be from {} to {}, not {}", MIN_DENSITY, MAX_DENSITY, density))
+    }
+}
+
+/// A most fish as given, if it's a whole number of at least one.
+pub fn parse_max_fish(max: f64) -> Result<usize, String> {
+    if max.fract() == 0.0 && max >= 1.0 && max <= u16::MAX as f64 {
+        Ok(max as usize)
+    } else {
+        Err(format!("max fish must be a whole number from 1 up, not {}", max))
+    }
+}
+
+/// Every species' frames recoloured for its shiny variant, done once when the sprites
+/// are loaded.
+pub fn shiny_frames(frames_by_species: &[SpeciesFrames]) -> Vec<SpeciesFrames> {
+    frames_by_species
+        .iter()
+        .map(|frames| csv_frames::recolor_species(frames, shiny_color))
+        .collect()
+}
+
+/// A colour as a shiny fish wears it.
+pub fn shiny_color(color: Color) -> Color {
+    color::hue_shift(color, SHINY_HUE_SHIFT)
+}
+
+/// A species' animations, right- and left-facing, as text or baked.
+type Facing<T> = (Vec<(T, Duration)>, Vec<(T, Duration)>);
+
+fn select_frames<'a, T>(
+    frames_by_species: &'a [Facing<T>],
+    shiny_frames: &'a [Facing<T>],
+    fish: &Fish,
+) -> &'a [(T, Duration)] {
+    let frames_by_species = if fish.shiny && !shiny_frames.is_empty() { shiny_frames } else { frames_by_species };
     if frames_by_species.is_empty() {
         return &[];
     }
     
-    let species_idx = species_idx.min(frames_by_species.len() - 1);
+    let species_idx = fish.species.min(frames_by_species.len() - 1);
     let (ref_vec_right, ref_vec_left) = &frames_by_species[species_idx];
     
-    if facing_right || ref_vec_left.is_empty() {
-        ref_vec_right.as_slice()
+    if fish.facing_right || ref_vec_left.is_empty() {
+        ref_vec_right
     } else {
-        ref_vec_left.as_slice()
+        ref_vec_left
+    }
+}
+
+/// The frame showing `elapsed` into a looping animation. `frames` must not be empty.
+pub fn frame_at<T>(frames: &[(T, Duration)], elapsed: Duration) -> &T {
+    let cycle: u128 = frames.iter().map(|(_, d)| d.as_millis().max(1)).sum();
+    let mut t = elapsed.as_millis() % cycle;
+    for (frame, duration) in frames {
+        let duration = duration.as_millis().max(1);
+        if t < duration {
+            return frame;
+        }
+        t -= duration;
     }
+    &frames[0].0
 }
 
-fn compute_spawn_chance(screen_width: f32) -> f64 {
+fn compute_spawn_chance(screen_width: f32, density: f64) -> f64 {
     let w_factor = (screen_width / BASE_SCREEN_WIDTH).max(MIN_WIDTH_FACTOR);
-    let chance = BASE_SPAWN_CHANCE * (w_factor as f64);
+    let chance = BASE_SPAWN_CHANCE * (w_factor as f64) * density;
     chance.min(MAX_SPAWN_CHANCE)
 }
 
@@ -60,7 +438,7 @@ fn compute_spawn_x<R: rand::Rng + ?Sized>(rng: &mut R, dir_right: bool, screen_w
 }
 
 pub fn species_has_directions(
-    frames_by_species: &[(Vec<Text>, Vec<Text>)],
+    frames_by_species: &[SpeciesFrames],
     species_idx: usize,
 ) -> (bool, bool) {
     if species_idx >= frames_by_species.len() {
@@ -77,89 +455,758 @@ pub fn compute_fish_layout(area: ratatui::layout::Rect) -> (u16, u16, u16) {
     (lanes, lane_height, base_y)
 }
 
+/// Cut a sprite whose top-left corner sits at (`origin_x`, `origin_y`) down to the
+/// cells inside `region`. Sprites are assumed to be one column per character.
+pub fn crop_text<'a>(text: &Text<'a>, origin_x: i32, origin_y: i32, region: Rect) -> Text<'a> {
+    let skip_cols = (region.x as i32 - origin_x).max(0) as usize;
+    let skip_rows = (region.y as i32 - origin_y).max(0) as usize;
+    let lines = text
+        .lines
+        .iter()
+        .skip(skip_rows)
+        .take(region.height as usize)
+        .map(|line| {
+            let (left, right) = (skip_cols, skip_cols + region.width as usize);
+            let mut col = 0;
+            let mut spans = Vec::new();
+            for (symbol, style) in line_cells(line) {
+                let (start, end) = (col, col + symbol.width());
+                col = end;
+                if end <= left {
+                    continue;
+                }
+                if start >= right {
+                    break;
+                }
+                if start < left || end > right {
+                    // A wide character cut by the edge: blank out the part still showing
+                    spans.push(Span::styled(" ".repeat(end.min(right) - start.max(left)), style));
+                } else {
+                    spans.push(Span::styled(symbol, style));
+                }
+            }
+            Line::from(spans)
+        })
+        .collect::<Vec<_>>();
+    Text::from(lines)
+}
+
+/// The column `fish` is drawn from, counted from the left of the fish area. A terminal
+/// only has whole cells, so a slow fish steps a column at a time; with `smooth` it's
+/// rounded rather than cut down, offset by a share of a cell that depends on its
+/// phase, so fish swimming at the same speed don't all step on the same frame.
+pub fn drawn_x(fish: &Fish, smooth: bool) -> i32 {
+    if smooth {
+        let dither = (fish.phase * 0.618_034).fract() - 0.5;
+        (fish.x + dither * 0.5).round() as i32
+    } else {
+        fish.x.floor() as i32
+    }
+}
+
+/// The screen row the top of `fish`'s sprite is drawn on, which may be above `fish_area`.
+pub fn sprite_top(fish: &Fish, fish_area: Rect) -> i32 {
+    let (_lanes, lane_height, base_y) = compute_fish_layout(fish_area);
+    base_y as i32 + (fish.lane as u16 * lane_height + FISH_Y_OFFSET) as i32 + fish.row_offset()
+}
+
+/// The lane whose fish are drawn across screen row `y`, if any. Lanes start
+/// `FISH_Y_OFFSET` rows down, like the sprites in them.
+pub fn lane_at(fish_area: Rect, y: u16) -> Option<usize> {
+    let (_lanes, lane_height, base_y) = compute_fish_layout(fish_area);
+    y.checked_sub(base_y + FISH_Y_OFFSET).map(|offset| (offset / lane_height) as usize)
+}
+
+/// The frame `fish` shows `elapsed` into the run, at its species' own size, or `None`
+/// if its species has no frames.
+pub fn sprite<'a, T>(
+    fish: &Fish,
+    frames_by_species: &'a [Facing<T>],
+    shiny_frames: &'a [Facing<T>],
+    elapsed: Duration,
+) -> Option<&'a T> {
+    let frames = select_frames(frames_by_species, shiny_frames, fish);
+    (!frames.is_empty()).then(|| frame_at(frames, elapsed.div_f32(fish.frame_scale.max(0.01))))
+}
+
+/// Where `fish` is drawn, if the whole of it is inside `fish_area`, for drawing it as
+/// one piece instead of from its text. `smooth` is as for [`drawn_x`].
+pub fn whole_sprite_rect(
+    fish: &Fish,
+    fish_area: Rect,
+    frames_by_species: &[SpeciesFrames],
+    shiny_frames: &[SpeciesFrames],
+    elapsed: Duration,
+    smooth: bool,
+) -> Option<Rect> {
+    if elapsed.as_millis() < fish.spawn_delay_ms as u128 {
+        return None;
+    }
+    let frame = sprite(fish, frames_by_species, shiny_frames, elapsed)?;
+    let x = fish_area.x as i32 + drawn_x(fish, smooth);
+    let y = sprite_top(fish, fish_area);
+    let (width, height) = fish.drawn_size((frame.width() as u16, frame.height() as u16));
+    let (width, height) = (width as i32, height as i32);
+    let inside = x >= fish_area.left() as i32
+        && y >= fish_area.top() as i32
+        && x + width <= fish_area.right() as i32
+        && y + height <= fish_area.bottom() as i32;
+    (inside && width > 0 && height > 0).then(|| Rect::new(x as u16, y as u16, width as u16, height as u16))
+}
+
+/// The first column and length of the shadow `fish` casts on the sea floor, once it's
+/// in the water: centred under it and as long as it's drawn, stretched or shortened by
+/// how its size compares to the mean of `sizes`, so the longest shadows belong to the
+/// biggest fish of their kind. `smooth` is as for [`drawn_x`].
+pub fn shadow(
+    fish: &Fish,
+    fish_area: Rect,
+    frames_by_species: &[SpeciesFrames],
+    sizes: &SizeDistribution,
+    elapsed: Duration,
+    smooth: bool,
+) -> Option<(i32, u16)> {
+    if elapsed.as_millis() < fish.spawn_delay_ms as u128 {
+        return None;
+    }
+    let (width, _) = fish.drawn_size(frames_size(frames_by_species.get(fish.species)?));
+    if width == 0 {
+        return None;
+    }
+    let length = (width as f32 * fish.size / sizes.mean.max(0.1)).round().clamp(1.0, width as f32 * 2.0) as u16;
+    let centre = fish_area.x as i32 + drawn_x(fish, smooth) + width as i32 / 2;
+    Some((centre - length as i32 / 2, length))
+}
+
+/// Where each visible fish should be drawn, clipped to `fish_area` so sprites half off
+/// screen are cut at the edge instead of pushed inside it or drawn past it. Shiny fish
+/// are drawn from `shiny_frames`, if there are any. `smooth` is as for [`drawn_x`].
 pub fn compute_fish_render_ops<'a>(
-    fishes: &[Fish],
+    fishes: impl IntoIterator<Item = &'a Fish>,
     fish_area: Rect,
-    frames_by_species: &'a [(Vec<Text<'a>>, Vec<Text<'a>>)],
+    frames_by_species: &'a BuiltFrames,
+    shiny_frames: &'a BuiltFrames,
     elapsed: Duration,
-) -> Vec<(Rect, Text<'a>)> {
-    let (_lanes, lane_height, base_y) = compute_fish_layout(fish_area);
-    let mut out = Vec::new();
+    smooth: bool,
+) -> impl Iterator<Item = RenderOp<'a>> {
+    let (_lanes, lane_height, _base_y) = compute_fish_layout(fish_area);
+    fishes.into_iter().filter_map(move |fish| {
+        if elapsed.as_millis() < fish.spawn_delay_ms as u128 {
+            return None;
+        }
+        let build = fish.build as usize;
+        let frame = sprite(fish, &frames_by_species[build], &shiny_frames[build], elapsed)?;
+        let scale = fish.scale();
+        let origin_x = fish_area.x as i32 + drawn_x(fish, smooth);
+        let origin_y = sprite_top(fish, fish_area);
+        let sprite_w = frame.width as i32 * scale as i32;
+        let sprite_h = (frame.height as i32 * scale as i32).min((lane_height * scale) as i32);
 
-    for fish in fishes.iter() {
+        let left = origin_x.max(fish_area.left() as i32);
+        let top = origin_y.max(fish_area.top() as i32);
+        let right = (origin_x + sprite_w).min(fish_area.right() as i32);
+        let bottom = (origin_y + sprite_h).min(fish_area.bottom() as i32);
+        if left >= right || top >= bottom {
+            return None;
+        }
+
+        let visible = Rect::new(left as u16, top as u16, (right - left) as u16, (bottom - top) as u16);
+        Some(RenderOp { frame, origin: (origin_x, origin_y), scale, visible })
+    })
+}
+
+/// A fish to draw: the frame it shows, where the frame's top-left corner falls, how
+/// many times over it's blown up and the part of the screen it shows in.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOp<'a> {
+    pub frame: &'a SpriteFrame,
+    pub origin: (i32, i32),
+    pub scale: u16,
+    pub visible: Rect,
+}
+
+impl RenderOp<'_> {
+    /// Draw the fish into `buf`, leaving the cells of `holes` as they are.
+    pub fn blit(&self, buf: &mut Buffer, holes: &[Rect]) {
+        self.frame.blit(buf, self.origin, self.scale, self.visible, holes);
+    }
+}
+
+/// Advance every spawned fish by `dt` according to its swim pattern, and on into any
+/// lane it is moving to. Fish that wrap come back in at the other edge, and the rest
+/// swim on off the screen for [`lifecycle`] to take away. Fish wary of the hook get
+/// over it meanwhile. While the sea is agitated (`agitation` above 0.0) fish that
+/// wrap swim on out of sight too, and come back once it has calmed.
+pub fn update_fishes(
+    fishes: &mut [Fish],
+    dt: Duration,
+    elapsed: Duration,
+    width: f32,
+    agitation: f32,
+) {
+    for fish in fishes.iter_mut() {
+        fish.hook_shy = fish.hook_shy.saturating_sub(dt);
         if elapsed.as_millis() < fish.spawn_delay_ms as u128 {
             continue;
         }
+        let t = elapsed.as_secs_f32() + fish.phase;
+        fish.x += fish.vx * fish.pattern.speed_factor(t) * dt.as_secs_f32();
+        fish.y_offset = fish.pattern.y_offset(t);
+        let step = LANE_CHANGE_SPEED * dt.as_secs_f32();
+        fish.lane_shift -= fish.lane_shift.clamp(-step, step);
+        
+        if fish.wrap && agitation <= 0.0 {
+            if fish.x > width {
+                fish.x = 0.0;
+            } else if fish.x < 0.0 {
+                fish.x = width;
+            }
+        }
+    }
+    update_schools(fishes, elapsed);
+}
+
+/// Whether `fish`, `sprite_width` columns wide, has swum off a screen `width` columns
+/// wide and is still heading away from it.
+fn gone(fish: &Fish, sprite_width: f32, width: f32) -> bool {
+    (fish.vx > 0.0 && fish.x > width) || (fish.vx < 0.0 && fish.x + sprite_width < 0.0)
+}
+
+/// Comings and goings among the fish, beyond their swimming: fish that don't wrap are
+/// taken away once they have left the screen, lone fish now and then move to the lane
+/// above or below, and a lane holding more fish than a screen `width` columns wide has
+/// room for sends the newest on their way, taking away at once any not yet in sight.
+/// Fish listed in `held` are on a line and left alone. Returns the indices of the fish
+/// taken away, from the last down, so whatever tracks fish by index can follow.
+#[allow(clippy::too_many_arguments)]
+pub fn lifecycle<R: rand::Rng + ?Sized>(
+    rng: &mut R,
+    fishes: &mut Vec<Fish>,
+    frames_by_species: &[SpeciesFrames],
+    held: &[usize],
+    dt: Duration,
+    elapsed: Duration,
+    width: f32,
+    lanes: usize,
+) -> Vec<usize> {
+    let sprite_width = |fish: &Fish| {
+        frames_by_species.get(fish.species).map_or(0.0, |frames| fish.drawn_size(frames_size(frames)).0 as f32)
+    };
+    let swimming = |fish: &Fish| elapsed.as_millis() >= fish.spawn_delay_ms as u128;
+    let mut leaving = vec![false; fishes.len()];
+    for (i, fish) in fishes.iter_mut().enumerate() {
+        if held.contains(&i) {
+            continue;
+        }
+        let w = sprite_width(fish);
+        if !fish.wrap && gone(fish, w, width) {
+            leaving[i] = true;
+            continue;
+        }
+        let in_sight = fish.x + w > 0.0 && fish.x < width;
+        let settled = fish.school.is_none() && !fish.is_boss() && fish.lane_shift == 0.0;
+        if lanes > 1
+            && settled
+            && in_sight
+            && swimming(fish)
+            && fish.pattern != SwimPattern::BottomHugging
+            && rng.gen_bool((LANE_CHANGE_CHANCE * dt.as_secs_f64()).min(1.0))
+        {
+            let lane = match fish.lane {
+                0 => 1,
+                lane if lane + 1 >= lanes => lane - 1,
+                lane if rng.gen_bool(0.5) => lane - 1,
+                lane => lane + 1,
+            };
+            fish.lane_shift = (fish.lane as f32 - lane as f32) * FISH_HEIGHT as f32;
+            fish.lane = lane;
+        }
+    }
+
+    let room = ((width / LANE_ROOM) as usize).max(MIN_PER_LANE);
+    let mut counts = vec![0; lanes.max(1)];
+    for (i, fish) in fishes.iter_mut().enumerate() {
+        if leaving[i] {
+            continue;
+        }
+        let count = &mut counts[fish.lane.min(lanes.max(1) - 1)];
+        *count += 1;
+        if *count > room && !held.contains(&i) && !fish.is_boss() {
+            let w = sprite_width(fish);
+            if !swimming(fish) || fish.x + w <= 0.0 || fish.x >= width {
+                leaving[i] = true;
+            } else {
+                fish.wrap = false;
+            }
+        }
+    }
+
+    let mut removed = Vec::new();
+    for i in (0..fishes.len()).rev() {
+        if leaving[i] {
+            fishes.remove(i);
+            removed.push(i);
+        }
+    }
+    removed
+}
+
+/// Keep every school together: followers take their leader's lane, speed and heading,
+/// so the school turns as one, and hold their station around it with a little jitter.
+fn update_schools(fishes: &mut [Fish], elapsed: Duration) {
+    let mut leaders: Vec<(u32, usize)> = Vec::new();
+    for i in 0..fishes.len() {
+        let Some(school) = fishes[i].school else {
+            continue;
+        };
+        let Some(&(_, leader)) = leaders.iter().find(|(s, _)| *s == school) else {
+            leaders.push((school, i));
+            continue;
+        };
+        let lead = fishes[leader].clone();
+        let fish = &mut fishes[i];
+        let t = elapsed.as_secs_f32() + fish.phase;
+        fish.lane = lead.lane;
+        fish.vx = lead.vx;
+        fish.facing_right = lead.facing_right;
+        fish.x = lead.x + fish.school_offset.0 - lead.school_offset.0 + (t * 2.3).sin() * SCHOOL_JITTER;
+        fish.y_offset =
+            lead.y_offset + fish.school_offset.1 - lead.school_offset.1 + (t * 1.7).cos() * SCHOOL_JITTER / 2.0;
+    }
+}
+
+/// How much to stretch a species' frame durations at a swimming speed, so fast fish
+/// beat their tails faster and slow ones drift, within a factor of two either way.
+pub fn frame_scale(speed: f32) -> f32 {
+    (REFERENCE_SPEED / speed.abs().max(0.01)).clamp(0.5, 2.0)
+}
+
+fn default_frame_scale() -> f32 {
+    1.0
+}
 
-        let frames_vec = select_frames(frames_by_species, fish.species, fish.facing_right);
-        if frames_vec.is_empty() {
+/// Spawn up to one fish per lane. Species without an entry in `traits` use the
+/// default [`SpeciesTraits`].
+/// Steer fish toward the hooks in the water, each given as its column, lane and reach:
+/// fish in a hook's lane and within range turn (if their species can face both ways)
+/// and speed up toward the nearest, more eagerly the more aggressive they are. Everyone
+/// else eases back to cruising speed. Fish listed in `held` are controlled elsewhere
+/// (e.g. nibbling) and skipped. A hook's reach scales how far away fish notice it;
+/// bait raises it above 1.0. In an agitated sea, from 0.0 (calm) to 1.0, fish ignore
+/// the hooks and bolt the way they're facing instead.
+pub fn steer_toward_hook(
+    fishes: &mut [Fish],
+    frames_by_species: &[SpeciesFrames],
+    hooks: &[(u16, usize, f32)],
+    held: &[usize],
+    dt: Duration,
+    elapsed: Duration,
+    agitation: f32,
+) {
+    let dt = dt.as_secs_f32();
+    for (i, fish) in fishes.iter_mut().enumerate() {
+        if held.contains(&i) || elapsed.as_millis() < fish.spawn_delay_ms as u128 {
             continue;
         }
+        let facing = if fish.facing_right { 1.0 } else { -1.0 };
+        let mut target_vx = facing * fish.cruise_speed;
+        let mut rate = 1.0;
 
-        let frame_idx = ((elapsed.as_millis() / fish.frame_duration.as_millis()) as usize) % frames_vec.len();
-        let fish_text = frames_vec[frame_idx].clone();
+        if agitation > 0.0 {
+            target_vx = facing * (fish.cruise_speed + FLEE_SPEED * agitation.min(1.0));
+            rate = 4.0;
+        } else if fish.aggression > 0.0 && fish.hook_shy.is_zero() {
+            let width = frames_by_species.get(fish.species).map_or(0, |frames| frames_size(frames).0) * fish.scale();
+            let (has_right, has_left) = species_has_directions(frames_by_species, fish.species);
+            let can_turn = has_right && has_left;
+            let nearest = hooks
+                .iter()
+                .filter(|&&(_, lane, _)| lane == fish.lane)
+                .map(|&(hook_x, _, reach)| (hook_x as f32 - (fish.x + width as f32 / 2.0), reach))
+                .filter(|&(dx, reach)| dx.abs() <= ATTRACT_RANGE * reach * fish.aggression && (can_turn || dx.signum() == facing))
+                .map(|(dx, _)| dx)
+                .min_by(|a, b| a.abs().total_cmp(&b.abs()));
+            if let Some(dx) = nearest {
+                // Slow down on arrival so the fish lingers around the hook instead of overshooting.
+                let arrive = (dx.abs() / 4.0).min(1.0);
+                target_vx = dx.signum() * fish.cruise_speed * (1.0 + fish.aggression) * arrive;
+                rate = 1.0 + 3.0 * fish.aggression;
+                if can_turn && target_vx != 0.0 && (target_vx > 0.0) != fish.facing_right {
+                    fish.facing_right = !fish.facing_right;
+                }
+            }
+        }
 
-        let fish_x = fish.x.max(0.0) as u16;
-        let right_bound = fish_area.x.saturating_add(fish_area.width);
-        let rem_width = right_bound.saturating_sub(fish_x).min(right_bound);
-        let fish_h = lane_height.min(fish_area.height.saturating_sub(1));
-        let lane_y = base_y.saturating_add(fish.lane as u16 * lane_height) + FISH_Y_OFFSET;
+        fish.vx += (target_vx - fish.vx) * (rate * dt).min(1.0);
+    }
+}
 
-        let fish_render_area = Rect::new(fish_x, lane_y, rem_width, fish_h);
-        out.push((fish_render_area, fish_text));
+/// Turn fish round at the edges of a screen `width` columns wide instead of letting
+/// them wrap: a fish nearing the edge it faces eases to a stop, turns, and is eased
+/// back up to cruising speed by [`steer_toward_hook`]. Only fish that wrap and whose
+/// species can face both ways turn; a school turns with its leader, and fish listed in
+/// `held` are left alone.
+pub fn turn_at_edges(fishes: &mut [Fish], frames_by_species: &[SpeciesFrames], held: &[usize], elapsed: Duration, width: f32) {
+    for i in 0..fishes.len() {
+        let follower = fishes[i].school.is_some() && fishes[..i].iter().any(|other| other.school == fishes[i].school);
+        let fish = &mut fishes[i];
+        if held.contains(&i) || follower || !fish.wrap || elapsed.as_millis() < fish.spawn_delay_ms as u128 {
+            continue;
+        }
+        let Some(frames) = frames_by_species.get(fish.species) else {
+            continue;
+        };
+        if species_has_directions(frames_by_species, fish.species) != (true, true) {
+            continue;
+        }
+        let sprite_width = fish.drawn_size(frames_size(frames)).0 as f32;
+        let ahead = if fish.facing_right { width - (fish.x + sprite_width) } else { fish.x };
+        if ahead >= EDGE_EASE {
+            continue;
+        }
+        // No faster than its cruising speed shrunk by how close the edge is, so it comes
+        // to a stop gently however it was steered
+        let facing = if fish.facing_right { 1.0 } else { -1.0 };
+        let speed = (fish.vx * facing).min(fish.cruise_speed * ahead / EDGE_EASE);
+        fish.vx = facing * speed.max(0.0);
+        if speed <= fish.cruise_speed * TURN_SHARE {
+            fish.facing_right = !fish.facing_right;
+        }
     }
+}
 
-    out
+/// Choose a species for a lane at `lane_depth` (0.0 surface to 1.0 deepest), favoring
+/// species whose preferred depth is close and that the bait in the water, if any,
+/// draws in, in proportion to their `stocks` (1.0 for any not given). Every species
+/// keeps a small chance anywhere.
+fn pick_species_for_depth<R: rand::Rng + ?Sized>(
+    rng: &mut R,
+    traits: &[SpeciesTraits],
+    species_count: usize,
+    lane_depth: f32,
+    bait: Option<Bait>,
+    stocks: &[f32],
+) -> usize {
+    if species_count == 0 {
+        return 0;
+    }
+    let weights: Vec<f32> = (0..species_count)
+        .map(|i| {
+            let t = traits.get(i).copied().unwrap_or_default();
+            let bias = bait.map_or(1.0, |b| b.spawn_bias(t.rarity));
+            let stock = stocks.get(i).copied().unwrap_or(1.0);
+            (1.0 - (lane_depth - t.depth).abs() * 1.5).max(0.1) * (1.0 - t.rarity * RARITY_SPAWN_PENALTY) * bias * stock
+        })
+        .collect();
+    let mut pick = rng.gen_range(0.0..weights.iter().sum::<f32>());
+    for (i, w) in weights.iter().enumerate() {
+        if pick < *w {
+            return i;
+        }
+        pick -= w;
+    }
+    species_count - 1
 }
 
+/// Stock the lanes, each by chance, with a fish or a school, more likely the higher the
+/// `density`. `stocks` weighs the species by how many are left in the sea, by index;
+/// missing ones count as full.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_fishes<R: rand::Rng + ?Sized>(
     rng: &mut R,
-    frames_by_species: &[(Vec<Text>, Vec<Text>)],
+    frames_by_species: &[SpeciesFrames],
+    traits: &[SpeciesTraits],
     screen_width: f32,
     lanes: usize,
+    density: f64,
+    bait: Option<Bait>,
+    stocks: &[f32],
 ) -> Vec<Fish> {
     let mut fishes = Vec::new();
-    let spawn_chance = compute_spawn_chance(screen_width);
+    let spawn_chance = compute_spawn_chance(screen_width, density);
     let species_count = frames_by_species.len();
     
     for lane in 0..lanes {
         if rng.gen_bool(spawn_chance) {
-            let speed = rng.gen_range(2.0..10.0);
-            let species = if species_count == 0 { 
-                0 
-            } else { 
-                rng.gen_range(0..species_count) 
-            };
-            
-            let (has_right, has_left) = species_has_directions(frames_by_species, species);
-            
-            let dir_right = if has_left && has_right {
-                rng.gen_bool(0.5)
-            } else {
-                has_right
-            };
-            
-            let wrap = if has_left && has_right {
-                rng.gen_bool(0.5)
-            } else {
-                true
-            };
+            let lane_depth = if lanes > 1 { lane as f32 / (lanes - 1) as f32 } else { 0.5 };
+            let species = pick_species_for_depth(rng, traits, species_count, lane_depth, bait, stocks);
             let spawn_delay_ms = rng.gen_range(0..MAX_SPAWN_DELAY_MS);
-            let x = compute_spawn_x(rng, dir_right, screen_width);
-            let size = crate::fishing_game::generate_fish_size(rng);
-            
-            fishes.push(Fish {
-                lane,
-                x,
-                vx: if dir_right { speed } else { -speed },
-                wrap,
-                facing_right: dir_right,
-                species,
-                frame_duration: Duration::from_millis(DEFAULT_FRAME_DURATION_MS),
-                spawn_delay_ms,
-                size,
-            });
+            let leader = new_fish(rng, frames_by_species, traits, species, lane, lanes, screen_width, spawn_delay_ms);
+            let school = traits.get(species).map_or(1, |t| t.school);
+            if school > 1 {
+                let width = frames_by_species.get(species).map_or(0, |frames| frames_size(frames).0);
+                let count = rng.gen_range(2..=school);
+                let sizes = traits.get(species).map_or_else(SizeDistribution::default, |t| t.sizes);
+                fishes.extend(school_of(rng, leader, count, width, sizes));
+            } else {
+                fishes.push(leader);
+            }
         }
     }
     fishes
 }
+
+/// `leader` and `count - 1` followers trailing behind it, staggered above and below
+/// its row. Followers share the leader's speed and heading but not its size, which is
+/// drawn from the species' `sizes`, or its phase.
+fn school_of<R: rand::Rng + ?Sized>(rng: &mut R, leader: Fish, count: usize, width: u16, sizes: SizeDistribution) -> Vec<Fish> {
+    let school = rng.r#gen();
+    let behind = if leader.facing_right { -1.0 } else { 1.0 };
+    let spacing = width as f32 + SCHOOL_SPACING;
+    let mut fishes = vec![Fish { school: Some(school), school_offset: (0.0, 0.0), ..leader.clone() }];
+    for i in 1..count {
+        let rank = i.div_ceil(2) as f32;
+        let row = if i % 2 == 1 { -1.0 } else { 1.0 };
+        let offset = (behind * rank * spacing + rng.gen_range(-1.0..1.0), row);
+        let size = sizes.sample(rng);
+        fishes.push(Fish {
+            x: leader.x + offset.0,
+            size,
+            build: Build::of(&sizes.category(size)),
+            phase: rng.gen_range(0.0..10.0),
+            school: Some(school),
+            school_offset: offset,
+            ..leader.clone()
+        });
+    }
+    fishes
+}
+
+/// Spawn a boss: the rarest species, `BOSS_SCALE` times its size and spanning as many
+/// lanes, big and slow, entering from the edge it faces. It measures around the
+/// largest its species grows. There is no room for one
+/// with fewer lanes than that.
+pub fn spawn_boss<R: rand::Rng + ?Sized>(
+    rng: &mut R,
+    frames_by_species: &[SpeciesFrames],
+    traits: &[SpeciesTraits],
+    screen_width: f32,
+    lanes: usize,
+) -> Option<Fish> {
+    let span = BOSS_SCALE as usize;
+    if frames_by_species.is_empty() || lanes < span {
+        return None;
+    }
+    let species = (0..frames_by_species.len())
+        .max_by(|&a, &b| {
+            let rarity = |i: usize| traits.get(i).map_or(0.0, |t| t.rarity);
+            rarity(a).total_cmp(&rarity(b))
+        })
+        .unwrap_or(0);
+    let lane = rng.gen_range(0..=lanes - span);
+    let mut boss = new_fish(rng, frames_by_species, traits, species, lane, lanes, screen_width, 0);
+    // Start fully off screen, however wide the sprite has grown
+    let width = frames_size(&frames_by_species[species]).0 as f32 * BOSS_SCALE as f32;
+    if boss.facing_right {
+        boss.x -= width;
+    }
+    boss.lane = lane;
+    boss.pattern = SwimPattern::Steady;
+    boss.cruise_speed *= BOSS_SLOWDOWN;
+    boss.vx *= BOSS_SLOWDOWN;
+    boss.frame_scale = frame_scale(boss.cruise_speed);
+    let biggest = traits.get(species).map_or(SizeDistribution::default().max, |t| t.sizes.max);
+    boss.size = biggest * rng.gen_range(0.9..1.2);
+    // Big enough already at twice the size
+    boss.build = Build::Normal;
+    boss.boss_fights = BOSS_FIGHTS;
+    Some(boss)
+}
+
+/// Spawn one fish of a given species right away, in the lane closest to its preferred
+/// depth, entering from the edge it faces.
+pub fn spawn_species<R: rand::Rng + ?Sized>(
+    rng: &mut R,
+    frames_by_species: &[SpeciesFrames],
+    traits: &[SpeciesTraits],
+    species: usize,
+    screen_width: f32,
+    lanes: usize,
+) -> Fish {
+    let lanes = lanes.max(1);
+    let depth = traits.get(species).map_or(DEFAULT_DEPTH, |t| t.depth);
+    let lane = (depth * (lanes - 1) as f32).round() as usize;
+    new_fish(rng, frames_by_species, traits, species, lane, lanes, screen_width, 0)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn new_fish<R: rand::Rng + ?Sized>(
+    rng: &mut R,
+    frames_by_species: &[SpeciesFrames],
+    traits: &[SpeciesTraits],
+    species: usize,
+    lane: usize,
+    lanes: usize,
+    screen_width: f32,
+    spawn_delay_ms: u64,
+) -> Fish {
+    let speed = rng.gen_range(2.0..10.0);
+    let (has_right, has_left) = species_has_directions(frames_by_species, species);
+    
+    let dir_right = if has_left && has_right {
+        rng.gen_bool(0.5)
+    } else {
+        has_right
+    };
+    
+    let wrap = if has_left && has_right {
+        rng.gen_bool(0.5)
+    } else {
+        true
+    };
+    let x = compute_spawn_x(rng, dir_right, screen_width);
+    let species_traits = traits.get(species).copied().unwrap_or_default();
+    let size = species_traits.sizes.sample(rng);
+    let lane = if species_traits.pattern == SwimPattern::BottomHugging {
+        lanes - 1
+    } else {
+        lane
+    };
+    
+    Fish {
+        lane,
+        x,
+        vx: if dir_right { speed } else { -speed },
+        wrap,
+        facing_right: dir_right,
+        species,
+        frame_scale: frame_scale(speed),
+        spawn_delay_ms,
+        size,
+        cruise_speed: speed,
+        aggression: species_traits.aggression,
+        pattern: species_traits.pattern,
+        phase: rng.gen_range(0.0..10.0),
+        y_offset: 0.0,
+        hook_shy: Duration::ZERO,
+        school: None,
+        school_offset: (0.0, 0.0),
+        boss_fights: 0,
+        shiny: rng.gen_bool(SHINY_CHANCE),
+        lane_shift: 0.0,
+        build: Build::of(&species_traits.sizes.category(size)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cropping_blanks_wide_characters_cut_by_the_edge() {
+        let text = Text::from(Line::from(vec![Span::raw("a"), Span::raw("魚"), Span::raw("b"), Span::raw("鯨")]));
+        let row = |region: Rect| -> String {
+            crop_text(&text, 0, 0, region).lines[0].spans.iter().map(|s| s.content.as_ref()).collect()
+        };
+        assert_eq!(row(Rect::new(0, 0, 6, 1)), "a魚b鯨");
+        assert_eq!(row(Rect::new(0, 0, 5, 1)), "a魚b ");
+        assert_eq!(row(Rect::new(2, 0, 4, 1)), " b鯨");
+    }
+
+    #[test]
+    fn schools_swim_off_together_and_lone_fish_change_lanes() {
+        use rand::SeedableRng;
+
+        let frame = |s: &'static str| vec![(Text::from(s), DEFAULT_FRAME_DURATION)];
+        let frames = vec![(frame("><>"), frame("<><"))];
+        let traits = [SpeciesTraits { school: 3, ..SpeciesTraits::default() }];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut leader = new_fish(&mut rng, &frames, &traits, 0, 0, 1, 40.0, 0);
+        leader.wrap = false;
+        let mut fishes = school_of(&mut rng, leader, 3, 3, SizeDistribution::default());
+        assert!(fishes.iter().all(|f| f.school == fishes[0].school && f.school.is_some()));
+
+        // Out of one edge and gone, keeping station all the way
+        let heading = fishes[0].facing_right;
+        let mut elapsed = Duration::ZERO;
+        while !fishes.is_empty() {
+            let dt = Duration::from_millis(100);
+            elapsed += dt;
+            update_fishes(&mut fishes, dt, elapsed, 40.0, 0.0);
+            let removed = lifecycle(&mut rng, &mut fishes, &frames, &[], dt, elapsed, 40.0, 1);
+            assert!(removed.windows(2).all(|w| w[0] > w[1]));
+            for fish in fishes.iter().skip(1).filter(|_| removed.is_empty()) {
+                assert_eq!(fish.facing_right, heading);
+                assert!((fish.x - fishes[0].x - fish.school_offset.0).abs() <= SCHOOL_JITTER);
+            }
+            assert!(elapsed < Duration::from_secs(60), "the school never left");
+        }
+
+        // A lone fish moves a lane over, easing across rather than jumping
+        let mut fishes = vec![Fish { lane: 1, x: 20.0, wrap: true, y_offset: 0.0, ..new_fish(&mut rng, &frames, &[], 0, 1, 3, 40.0, 0) }];
+        let area = Rect::new(0, 0, 40, 16);
+        let top = sprite_top(&fishes[0], area);
+        let removed = lifecycle(&mut rng, &mut fishes, &frames, &[], Duration::from_secs(100), elapsed, 40.0, 3);
+        assert!(removed.is_empty());
+        assert!(fishes[0].lane != 1 && fishes[0].lane_shift.abs() == FISH_HEIGHT as f32);
+        assert_eq!(sprite_top(&fishes[0], area), top);
+        update_fishes(&mut fishes, Duration::from_secs(1), elapsed, 40.0, 0.0);
+        assert_eq!(fishes[0].lane_shift.abs(), FISH_HEIGHT as f32 - LANE_CHANGE_SPEED);
+        update_fishes(&mut fishes, Duration::from_secs(2), elapsed, 40.0, 0.0);
+        assert_eq!(fishes[0].lane_shift, 0.0);
+
+        // A crowded lane takes away the fish out of sight and sends the rest on their way
+        let mut crowd: Vec<Fish> = [5.0, 15.0, 25.0, -20.0, 30.0]
+            .iter()
+            .map(|&x| Fish { lane: 0, x, vx: 1.0, wrap: true, ..fishes[0].clone() })
+            .collect();
+        let removed = lifecycle(&mut rng, &mut crowd, &frames, &[4], Duration::ZERO, elapsed, 40.0, 1);
+        assert_eq!(removed, vec![3]);
+        assert_eq!(crowd.iter().map(|f| f.wrap).collect::<Vec<_>>(), vec![true, true, false, true]);
+    }
+    #[test]
+    fn density_fills_wide_seas_and_max_fish_caps_them() {
+        let config = crate::config_file::ConfigFile::parse("[spawning]\ndensity = 0.5\nmax_fish = 12").unwrap();
+        let sparse = SpawnSettings::from_table(config.section("spawning").unwrap()).unwrap();
+        assert_eq!(sparse, SpawnSettings { density: 0.5, max_fish: Some(12) });
+        let crowded = crate::config_file::ConfigFile::parse("[spawning]\ndensity = 9").unwrap();
+        assert!(SpawnSettings::from_table(crowded.section("spawning").unwrap()).is_err());
+        assert!(parse_max_fish(2.5).is_err());
+
+        // One a lane at 80 columns, more across an ultrawide screen, never none
+        let normal = SpawnSettings::default();
+        assert_eq!(normal.target(3, 80.0), 3);
+        assert_eq!(normal.target(3, 320.0), 12);
+        assert_eq!(sparse.target(3, 80.0), 3);
+        assert_eq!(sparse.target(8, 320.0), 12);
+        assert_eq!(sparse.check_interval(Duration::from_secs(3)), Duration::from_secs(6));
+        assert!(compute_spawn_chance(80.0, 2.0) > compute_spawn_chance(80.0, 1.0));
+    }
+
+    #[test]
+    fn smooth_fish_ease_round_at_the_edges() {
+        use rand::SeedableRng;
+
+        let frame = |s: &'static str| vec![(Text::from(s), DEFAULT_FRAME_DURATION)];
+        let frames = vec![(frame("><>"), frame("<><"))];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut fish = new_fish(&mut rng, &frames, &[SpeciesTraits::default()], 0, 0, 1, 40.0, 0);
+        fish.pattern = SwimPattern::Steady;
+        let column = |x: f32, phase: f32| drawn_x(&Fish { x, phase, ..fish.clone() }, true);
+        assert_eq!(drawn_x(&Fish { x: 4.9, ..fish.clone() }, false), 4);
+        assert_eq!(column(4.9, 0.0), 5);
+        // Fish at the same spot step into the next column at different moments
+        assert_ne!(column(4.6, 0.0), column(4.6, 0.5));
+
+        let mut fishes = vec![Fish { x: 20.0, vx: 8.0, cruise_speed: 8.0, facing_right: true, wrap: true, spawn_delay_ms: 0, ..fish }];
+        let (dt, mut elapsed) = (Duration::from_millis(50), Duration::ZERO);
+        let mut speeds = Vec::new();
+        while fishes[0].facing_right {
+            elapsed += dt;
+            steer_toward_hook(&mut fishes, &frames, &[], &[], dt, elapsed, 0.0);
+            turn_at_edges(&mut fishes, &frames, &[], elapsed, 40.0);
+            update_fishes(&mut fishes, dt, elapsed, 40.0, 0.0);
+            speeds.push(fishes[0].vx);
+            assert!(elapsed < Duration::from_secs(10) && fishes[0].x + 3.0 <= 40.0, "{:?}", fishes[0].x);
+        }
+        // It slowed before the edge instead of wrapping, then picks up speed the other way
+        assert!(speeds.iter().any(|&vx| vx < 4.0) && speeds.windows(2).rev().take(5).all(|w| w[1] <= w[0]));
+        for _ in 0..40 {
+            elapsed += dt;
+            steer_toward_hook(&mut fishes, &frames, &[], &[], dt, elapsed, 0.0);
+            turn_at_edges(&mut fishes, &frames, &[], elapsed, 40.0);
+            update_fishes(&mut fishes, dt, elapsed, 40.0, 0.0);
+        }
+        assert!(!fishes[0].facing_right && fishes[0].vx < -6.0 && fishes[0].x < 35.0);
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::widgets::StatefulWidget;
+
+use crate::csv_frames::SpeciesFrames;
+use crate::fish::{self, BuiltFrames, Fish};
+use crate::ocean::{Ocean, OceanState};
+use crate::stars::{StarrySky, StarsState};
+use crate::theme::Theme;
+
+const OCEAN_HEIGHT: u16 = 4;
+const SPAWN_CHECK_INTERVAL: Duration = Duration::from_secs(3);
+const STAR_DENSITY: f32 = 0.02;
+
+/// Everything a [`FishTank`] needs between frames: RNG, simulation clock, the fish
+/// population and the state of the sky and ocean layers. Host apps call
+/// [`FishTankState::tick`] from their own loop and render the widget whenever they like.
+pub struct FishTankState {
+    rng: StdRng,
+    species: Vec<SpeciesFrames>,
+    /// The frames baked for drawing, plain and shiny.
+    baked: BuiltFrames,
+    baked_shiny: BuiltFrames,
+    pub fishes: Vec<Fish>,
+    pub ocean: OceanState,
+    pub stars: StarsState,
+    elapsed: Duration,
+    since_spawn_check: Duration,
+    width: u16,
+    lanes: u16,
+}
+
+impl FishTankState {
+    pub fn new(seed: u64, species: Vec<SpeciesFrames>) -> Self {
+        FishTankState {
+            rng: StdRng::seed_from_u64(seed),
+            baked: fish::bake_builds(&species),
+            baked_shiny: fish::bake_builds(&fish::shiny_frames(&species)),
+            species,
+            fishes: Vec::new(),
+            ocean: OceanState::new(seed),
+            stars: StarsState::new(seed.wrapping_add(1), STAR_DENSITY),
+            elapsed: Duration::ZERO,
+            since_spawn_check: SPAWN_CHECK_INTERVAL,
+            width: 0,
+            lanes: 0,
+        }
+    }
+
+    /// Total simulated time since the tank was created.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Advance the scene by `dt`: swim the fish, let them come and go, twinkle the
+    /// stars, roll the waves, and top the population back up to one fish per lane every
+    /// few seconds.
+    pub fn tick(&mut self, dt: Duration) {
+        self.elapsed += dt;
+        self.stars.tick(dt);
+        self.ocean.advance(dt, 0.0);
+        fish::update_fishes(&mut self.fishes, dt, self.elapsed, self.width as f32, 0.0);
+        let lanes = self.lanes as usize;
+        fish::lifecycle(&mut self.rng, &mut self.fishes, &self.species, &[], dt, self.elapsed, self.width as f32, lanes);
+
+        self.since_spawn_check += dt;
+        if self.since_spawn_check >= SPAWN_CHECK_INTERVAL && self.width > 0 {
+            self.since_spawn_check = Duration::ZERO;
+            if self.fishes.len() < self.lanes as usize {
+                let mut new_fish = fish::spawn_fishes(
+                    &mut self.rng,
+                    &self.species,
+                    &[],
+                    self.width as f32,
+                    self.lanes as usize,
+                    1.0,
+                    None,
+                    &[],
+                );
+                // Spawn delays are relative to the tank clock, not to creation time.
+                let now_ms = self.elapsed.as_millis() as u64;
+                for f in new_fish.iter_mut() {
+                    f.spawn_delay_ms += now_ms;
+                }
+                self.fishes.append(&mut new_fish);
+            }
+        }
+    }
+}
+
+/// Split a tank area into sky, ocean surface and the water below it.
+fn tank_layout(area: Rect) -> (Rect, Rect, Rect) {
+    let ocean_y = area.y + area.height / 3;
+    let ocean_h = OCEAN_HEIGHT.min(area.bottom().saturating_sub(ocean_y));
+    let sky = Rect::new(area.x, area.y, area.width, ocean_y - area.y);
+    let ocean = Rect::new(area.x, ocean_y, area.width, ocean_h);
+    let water_y = ocean.bottom();
+    let water = Rect::new(area.x, water_y, area.width, area.bottom().saturating_sub(water_y));
+    (sky, ocean, water)
+}
+
+/// A self-contained scene of stars, ocean surface and swimming fish.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FishTank {
+    pub theme: Theme,
+}
+
+impl StatefulWidget for FishTank {
+    type State = FishTankState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut FishTankState) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let (sky, ocean, water) = tank_layout(area);
+        state.width = area.width;
+        state.lanes = (water.height / fish::FISH_HEIGHT).max(1);
+
+        StarrySky { theme: self.theme }.render(sky, buf, &mut state.stars);
+        StatefulWidget::render(Ocean { theme: self.theme }, ocean, buf, &mut state.ocean);
+
+        let ops = fish::compute_fish_render_ops(&state.fishes, water, &state.baked, &state.baked_shiny, state.elapsed, false);
+        for op in ops {
+            op.blit(buf, &[]);
+        }
+    }
+}
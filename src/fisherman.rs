@@ -1,16 +1,283 @@
+//! The fisherman on the dock: the poses they strike as things happen, and the skin
+//! they're drawn in. A skin is a folder of CSV frames, one per pose, in `src/skins` or
+//! a `skins` folder in a sprite directory, picked with `--skin`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use include_dir::{include_dir, Dir};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
 use ratatui::style::Style;
+use ratatui::text::Text;
 use ratatui::widgets::Widget;
+use unicode_width::UnicodeWidthStr;
+
+use crate::csv_frames;
+use crate::theme::Theme;
+
+static SKINS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/skins");
+/// The skin worn unless `--skin` picks another.
+pub const DEFAULT_SKIN: &str = "fisherman";
+
+const KICK_INTERVAL: Duration = Duration::from_millis(400);
+const CELEBRATION_FRAME: Duration = Duration::from_millis(200);
+const CELEBRATION_FRAMES: [Pose; 8] = [
+    Pose::ArmsUp,
+    Pose::Jump,
+    Pose::ArmsUp,
+    Pose::Jump,
+    Pose::ArmsUp,
+    Pose::Jump,
+    Pose::ArmsUp,
+    Pose::ArmsUp,
+];
+const SAG_LENGTH: Duration = Duration::from_millis(300);
+const SLUMP_LENGTH: Duration = Duration::from_secs(3);
+
+/// One drawable frame of the fisherman.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pose {
+    Standing,
+    /// Standing with the dangling leg kicked out.
+    Kick,
+    ArmsUp,
+    /// Arms up, one row off the dock with the legs tucked.
+    Jump,
+    /// Shoulders dropping, the start of a slump.
+    Sag,
+    HeadDown,
+    /// Nodding off, head on the chest and a `z` drifting up.
+    Asleep,
+}
+
+impl Pose {
+    pub const ALL: [Pose; 7] = [Pose::Standing, Pose::Kick, Pose::ArmsUp, Pose::Jump, Pose::Sag, Pose::HeadDown, Pose::Asleep];
+
+    /// The name of the pose's CSV file in a skin, without `.csv`.
+    pub fn file_stem(self) -> &'static str {
+        match self {
+            Pose::Standing => "standing",
+            Pose::Kick => "kick",
+            Pose::ArmsUp => "arms_up",
+            Pose::Jump => "jump",
+            Pose::Sag => "sag",
+            Pose::HeadDown => "head_down",
+            Pose::Asleep => "asleep",
+        }
+    }
+
+    /// The pose drawn instead when a skin has no frame for this one.
+    fn stand_in(self) -> Option<Pose> {
+        match self {
+            Pose::Standing => None,
+            Pose::Kick | Pose::ArmsUp | Pose::HeadDown => Some(Pose::Standing),
+            Pose::Jump => Some(Pose::ArmsUp),
+            Pose::Sag | Pose::Asleep => Some(Pose::HeadDown),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mood {
+    Idle,
+    Celebrating,
+    Slumping,
+    /// Until woken by [`FishermanAnimation::wake`] or something to react to.
+    Sleeping,
+}
+
+/// Picks the fisherman's pose over time: idle leg kicking, a jumping celebration after
+/// a success, a slump after a failure, or dozing while nothing happens. Call [`FishermanAnimation::tick`] every frame.
+#[derive(Debug, Clone)]
+pub struct FishermanAnimation {
+    mood: Mood,
+    mood_time: Duration,
+    kick: bool,
+    since_kick: Duration,
+    /// Kick the dangling leg while idle.
+    fidget: bool,
+}
+
+impl Default for FishermanAnimation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FishermanAnimation {
+    pub fn new() -> Self {
+        FishermanAnimation {
+            mood: Mood::Idle,
+            mood_time: Duration::ZERO,
+            kick: false,
+            since_kick: Duration::ZERO,
+            fidget: true,
+        }
+    }
+
+    pub fn with_fidget(mut self, fidget: bool) -> Self {
+        self.fidget = fidget;
+        self
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.mood_time += dt;
+        let mood_length = match self.mood {
+            Mood::Idle | Mood::Sleeping => None,
+            Mood::Celebrating => Some(CELEBRATION_FRAME * CELEBRATION_FRAMES.len() as u32),
+            Mood::Slumping => Some(SLUMP_LENGTH),
+        };
+        if mood_length.is_some_and(|length| self.mood_time >= length) {
+            self.set_mood(Mood::Idle);
+        }
+
+        self.since_kick += dt;
+        if self.fidget && self.since_kick >= KICK_INTERVAL {
+            self.kick = !self.kick;
+            self.since_kick = Duration::ZERO;
+        }
+    }
+
+    pub fn celebrate(&mut self) {
+        self.set_mood(Mood::Celebrating);
+    }
+
+    pub fn slump(&mut self) {
+        self.set_mood(Mood::Slumping);
+    }
+
+    pub fn fall_asleep(&mut self) {
+        self.set_mood(Mood::Sleeping);
+    }
 
-/// A small widget that draws a fisherman stick figure.
-pub struct Fisherman {
+    pub fn wake(&mut self) {
+        if self.mood == Mood::Sleeping {
+            self.set_mood(Mood::Idle);
+        }
+    }
+
+    fn set_mood(&mut self, mood: Mood) {
+        self.mood = mood;
+        self.mood_time = Duration::ZERO;
+    }
+
+    pub fn pose(&self) -> Pose {
+        match self.mood {
+            Mood::Idle if self.kick => Pose::Kick,
+            Mood::Idle => Pose::Standing,
+            Mood::Celebrating => {
+                let frame = (self.mood_time.as_millis() / CELEBRATION_FRAME.as_millis()) as usize;
+                CELEBRATION_FRAMES[frame.min(CELEBRATION_FRAMES.len() - 1)]
+            }
+            Mood::Slumping if self.mood_time < SAG_LENGTH => Pose::Sag,
+            Mood::Slumping => Pose::HeadDown,
+            Mood::Sleeping => Pose::Asleep,
+        }
+    }
+}
+
+/// How the fisherman looks: a frame for each pose, from the CSV files in a skin
+/// folder named after them (`standing.csv`, `kick.csv` and so on). Only
+/// `standing.csv` is needed; a pose without a frame borrows a similar one.
+#[derive(Debug, Clone, Default)]
+pub struct Skin {
+    frames: HashMap<Pose, Text<'static>>,
+}
+
+impl Skin {
+    /// The frame to draw for `pose`, or its stand-in's.
+    pub fn frame(&self, pose: Pose) -> Option<&Text<'static>> {
+        let mut pose = Some(pose);
+        while let Some(p) = pose {
+            if let Some(frame) = self.frames.get(&p) {
+                return Some(frame);
+            }
+            pose = p.stand_in();
+        }
+        None
+    }
+
+    /// Take the frames among `files` (by file name) named after a pose, replacing any
+    /// already there.
+    fn add_files(&mut self, files: impl IntoIterator<Item = (String, Text<'static>)>) {
+        for (name, frame) in files {
+            let stem = name.strip_suffix(".csv").unwrap_or(&name);
+            if let Some(pose) = Pose::ALL.into_iter().find(|pose| pose.file_stem() == stem) {
+                self.frames.insert(pose, frame);
+            }
+        }
+    }
+}
+
+fn embedded_skins() -> BTreeMap<String, Skin> {
+    let mut skins = BTreeMap::new();
+    for dir in SKINS_DIR.dirs() {
+        let Some(name) = dir.path().file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let files = dir.files().filter_map(|f| {
+            let frame = csv_frames::load_csv_frame_from_string(f.contents_utf8()?).ok()?;
+            Some((f.path().file_name()?.to_str()?.to_string(), frame))
+        });
+        skins.entry(name.to_string()).or_insert_with(Skin::default).add_files(files);
+    }
+    skins
+}
+
+/// The embedded skins with the skins folders in `dirs` laid over them in order: a skin
+/// found again replaces only the poses the later folder has, and a new one is added.
+/// Skins without a standing frame are left out.
+pub fn load_layered_skins(dirs: &[PathBuf]) -> BTreeMap<String, Skin> {
+    let mut skins = embedded_skins();
+    for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+        let Ok(entries) = fs::read_dir(dir).map_err(|e| tracing::warn!("failed to read {}: {}", dir.display(), e)) else {
+            continue;
+        };
+        for skin_dir in entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()) {
+            let Some(name) = skin_dir.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+                continue;
+            };
+            let Ok(files) = fs::read_dir(&skin_dir) else {
+                continue;
+            };
+            let files = files
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "csv"))
+                .filter_map(|p| {
+                    let frame = csv_frames::load_csv_frame(p.to_string_lossy().as_ref())
+                        .map_err(|e| tracing::warn!("failed to load {}: {}", p.display(), e))
+                        .ok()?;
+                    Some((p.file_name()?.to_str()?.to_string(), frame))
+                });
+            skins.entry(name).or_default().add_files(files);
+        }
+    }
+    skins.retain(|name, skin| {
+        let standing = skin.frames.contains_key(&Pose::Standing);
+        if !standing {
+            tracing::warn!("skin {} has no standing.csv and is left out", name);
+        }
+        standing
+    });
+    skins
+}
+
+/// The fisherman in `skin`, sitting `offset_from_right` columns in from the right edge
+/// of the area with the rod held out to the left. Each frame's bottom-left cell sits a
+/// column to the left of them on the legs row, the area's third, with a jump lifted a
+/// row higher. Cells in the classic theme's fisherman colour take the theme's.
+pub struct Fisherman<'a> {
+    pub skin: &'a Skin,
     pub offset_from_right: u16,
-    pub kick: bool,
+    pub pose: Pose,
+    pub theme: Theme,
 }
 
-impl Widget for Fisherman {
+impl Widget for Fisherman<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         if area.width == 0 || area.height == 0 {
             return;
@@ -19,57 +286,68 @@ impl Widget for Fisherman {
         let right_x = area.x.saturating_add(area.width.saturating_sub(1));
         let fx = right_x.saturating_sub(self.offset_from_right.min(area.width.saturating_sub(1)));
         let head_y = area.y;
+        let legs_y = area.y as i32 + 2 - i32::from(self.pose == Pose::Jump);
 
-        buf.set_string(
-            fx,
-            head_y,
-            "ö",
-            Style::default().fg(Color::Rgb(200, 200, 200)),
-        );
-        if head_y + 1 < area.y + area.height {
-            buf.set_string(
-                fx,
-                head_y + 1,
-                "┤",
-                Style::default().fg(Color::Rgb(200, 200, 200)),
-            );
-        }
-        if head_y + 2 < area.y + area.height {
-            if fx > area.x {
-                buf.set_string(
-                    fx,
-                    head_y + 2,
-                    "┘",
-                    Style::default().fg(Color::Rgb(200, 200, 200)),
-                );
-                if self.kick {
-                    buf.set_string(
-                        fx - 1,
-                        head_y + 2,
-                        "─",
-                        Style::default().fg(Color::Rgb(200, 200, 200)),
-                    );
-                } else {
-                    buf.set_string(
-                        fx - 1,
-                        head_y + 2,
-                        "┌",
-                        Style::default().fg(Color::Rgb(200, 200, 200)),
-                    );
+        if let Some(frame) = self.skin.frame(self.pose) {
+            let top = legs_y + 1 - frame.height() as i32;
+            for (row, line) in frame.lines.iter().enumerate() {
+                let y = top + row as i32;
+                let mut x = fx as i32 - 1;
+                for (symbol, mut style) in csv_frames::line_cells(line) {
+                    let width = symbol.width() as i32;
+                    if style.fg == Some(Theme::CLASSIC.fisherman) {
+                        style.fg = Some(self.theme.fisherman);
+                    }
+                    if symbol != " " && x >= 0 && y >= 0 && buf.area.contains((x as u16, y as u16).into()) {
+                        buf[(x as u16, y as u16)].set_symbol(&symbol).set_style(style);
+                    }
+                    x += width;
                 }
             }
         }
 
+        // The rod stays put in every pose so the fishing line remains attached to its tip.
         let rod_length = 4;
+        let rod_style = Style::default().fg(self.theme.rod);
         for i in 0..rod_length {
-            if fx > area.x + 1 && head_y >= area.y {
-                buf.set_string(
-                    fx - (i + 1),
-                    head_y - i,
-                    "\\",
-                    Style::default().fg(Color::Rgb(200, 200, 120)),
-                );
+            if fx > area.x + 1 && head_y >= i {
+                let (x, y) = (fx - (i + 1), head_y - i);
+                if buf.area.contains((x, y).into()) {
+                    buf.set_string(x, y, "\\", rod_style);
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_skin_draws_each_pose_or_a_stand_in() {
+        let skins = load_layered_skins(&[]);
+        assert!(skins.contains_key(DEFAULT_SKIN) && skins.contains_key("cat"));
+        let area = Rect::new(0, 0, 8, 5);
+        let draw = |skin: &Skin, pose: Pose| {
+            let mut buf = Buffer::empty(area);
+            Fisherman { skin, offset_from_right: 1, pose, theme: Theme::NIGHT }.render(Rect::new(0, 1, 8, 4), &mut buf);
+            (0..5).map(|y| (0..8).map(|x| buf[(x, y)].symbol().to_string()).collect::<String>()).collect::<Vec<_>>()
+        };
+
+        let fisherman = &skins[DEFAULT_SKIN];
+        assert_eq!(draw(fisherman, Pose::Standing), ["    \\   ", "     \\ö ", "      ┤ ", "     ┌┘ ", "        "]);
+        // A jump lifts the whole figure a row, off the planks
+        assert_eq!(draw(fisherman, Pose::Jump)[..3], ["    \\ ö/", "     \\┤ ", "     ╰╯ "]);
+
+        // The cat has no sagging frame and hangs its head straight away
+        let cat = &skins["cat"];
+        assert_eq!(draw(cat, Pose::Sag), draw(cat, Pose::HeadDown));
+        assert_ne!(draw(cat, Pose::Standing), draw(fisherman, Pose::Standing));
+
+        // The default skin takes on the theme's colours
+        let mut buf = Buffer::empty(area);
+        Fisherman { skin: fisherman, offset_from_right: 1, pose: Pose::Standing, theme: Theme::NIGHT }.render(Rect::new(0, 1, 8, 4), &mut buf);
+        assert_eq!(buf[(6, 1)].fg, Theme::NIGHT.fisherman);
+    }
+}
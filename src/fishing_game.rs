@@ -1,10 +1,19 @@
+use std::io;
+use std::time::Duration;
+
 use rand::Rng;
+use serde::Deserialize;
 
-#[derive(Debug, Clone)]
+use crate::economy::Bait;
+use crate::fish::{Fish, SpeciesTraits};
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CaughtFish {
     pub species_name: String,
     pub size: f32,
     pub size_category: SizeCategory,
+    /// A shiny variant, worth more and logged separately in the journal.
+    pub shiny: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -28,30 +37,57 @@ impl SizeCategory {
     }
 }
 
-pub fn generate_fish_size<R: Rng + ?Sized>(rng: &mut R) -> f32 {
-    let u1: f32 = rng.gen_range(0.001..1.0);
-    let u2: f32 = rng.gen_range(0.0..1.0);
-    
-    let z0 = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos();
-    
-    let mean = 50.0;
-    let stddev = 15.0;
-    let size = mean + z0 * stddev;
-    
-    size.clamp(1.0, 100.0)
+/// How big a species grows, in cm: sizes fall in a bell curve around `mean`, cut off
+/// at `min` and `max`. From the `Size*` columns of the species' `meta.csv`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeDistribution {
+    pub mean: f32,
+    pub stddev: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for SizeDistribution {
+    fn default() -> Self {
+        SizeDistribution { mean: 50.0, stddev: 15.0, min: 1.0, max: 100.0 }
+    }
 }
 
-pub fn categorize_size(size: f32) -> SizeCategory {
-    if size < 20.0 {
-        SizeCategory::Tiny
-    } else if size < 40.0 {
-        SizeCategory::Small
-    } else if size < 60.0 {
-        SizeCategory::Average
-    } else if size < 80.0 {
-        SizeCategory::Large
-    } else {
-        SizeCategory::Massive
+impl SizeDistribution {
+    /// The sizes around `mean`: `stddev` defaults to 30% of it and `min` and `max` to
+    /// three of those either side, but never under 1 mm.
+    pub fn around(mean: f32, stddev: Option<f32>, min: Option<f32>, max: Option<f32>) -> SizeDistribution {
+        let mean = mean.max(0.1);
+        let stddev = stddev.unwrap_or(mean * 0.3).max(0.0);
+        let min = min.unwrap_or(mean - 3.0 * stddev).max(0.1);
+        let max = max.unwrap_or(mean + 3.0 * stddev).max(min);
+        SizeDistribution { mean, stddev, min, max }
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        let u1: f32 = rng.gen_range(0.001..1.0);
+        let u2: f32 = rng.gen_range(0.0..1.0);
+
+        let z0 = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos();
+
+        (self.mean + z0 * self.stddev).clamp(self.min, self.max)
+    }
+
+    /// How `size` compares with the rest of its species, by how many standard
+    /// deviations it is from the mean: a Massive fish is two or more above it.
+    pub fn category(&self, size: f32) -> SizeCategory {
+        let z = (size - self.mean) / self.stddev.max(f32::EPSILON);
+        if z < -2.0 {
+            SizeCategory::Tiny
+        } else if z < -2.0 / 3.0 {
+            SizeCategory::Small
+        } else if z < 2.0 / 3.0 {
+            SizeCategory::Average
+        } else if z < 2.0 {
+            SizeCategory::Large
+        } else {
+            SizeCategory::Massive
+        }
     }
 }
 
@@ -63,26 +99,31 @@ pub fn check_collision(
     fish_width: u16,
     fish_height: u16,
 ) -> bool {
-    let fish_left = fish_x as u16;
-    let fish_right = fish_left.saturating_add(fish_width);
+    // Fish can be partly or wholly off the left edge
+    let fish_left = fish_x.floor() as i32;
+    let fish_right = fish_left + fish_width as i32;
     let fish_top = fish_y;
     let fish_bottom = fish_y.saturating_add(fish_height);
     
-    hook_x >= fish_left && hook_x < fish_right && hook_y >= fish_top && hook_y < fish_bottom
+    (fish_left..fish_right).contains(&(hook_x as i32)) && hook_y >= fish_top && hook_y < fish_bottom
 }
 
 impl CaughtFish {
-    pub fn new(species_name: String, size: f32) -> Self {
-        let size_category = categorize_size(size);
+    /// A landed fish of `size`, sized up against its species' `sizes`.
+    pub fn new(species_name: String, size: f32, shiny: bool, sizes: &SizeDistribution) -> Self {
+        let size_category = sizes.category(size);
         CaughtFish {
             species_name,
             size,
             size_category,
+            shiny,
         }
     }
     
     pub fn format_catch(&self) -> String {
-        let article = if self.size_category == SizeCategory::Average {
+        let article = if self.shiny {
+            "a shiny"
+        } else if self.size_category == SizeCategory::Average {
             "an"
         } else {
             "a"
@@ -96,3 +137,541 @@ impl CaughtFish {
         )
     }
 }
+
+const NIBBLE_MIN_MS: u64 = 600;
+const NIBBLE_MAX_MS: u64 = 1800;
+/// How long the player has to press space once a fish bites.
+pub const BITE_WINDOW: Duration = Duration::from_millis(700);
+/// How long a spooked fish flees at extra speed; no new bite starts meanwhile.
+const FLEE_TIME: Duration = Duration::from_millis(1500);
+const FLEE_SPEED_FACTOR: f32 = 3.0;
+const TWITCH_PERIOD_MS: u128 = 250;
+/// How long a boss runs with the line after each hook-set before it takes the bait again.
+const FIGHT_TIME: Duration = Duration::from_millis(2500);
+/// How long a fish that turned down the bait ignores the hook.
+pub const HOOK_SHY_TIME: Duration = Duration::from_secs(3);
+/// How taut the line has to be to tire a hooked fish, and how fast a fish on a slacker
+/// line gets its strength back, in seconds of stamina a second.
+pub const TAUT_TENSION: f32 = 0.6;
+const STAMINA_REGAIN: f32 = 0.4;
+/// Columns a second a fish that has bitten swims on with the hook, and a fighting boss.
+const DRAG_SPEED: f32 = 3.0;
+const FIGHT_DRAG_SPEED: f32 = 6.0;
+/// A fish dragging the hook stops this close to the left edge of the screen, rather
+/// than wrapping round with the hook left behind.
+const DRAG_EDGE: f32 = 2.0;
+
+/// Chance that a fish of `rarity` touching the hook takes it: bait decides, and a bare
+/// hook mostly tempts common fish.
+pub fn bite_chance(bait: Option<Bait>, rarity: f32) -> f64 {
+    match bait {
+        Some(bait) => bait.bite_chance(rarity),
+        None => 0.6 - 0.4 * rarity as f64,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitePhase {
+    /// The fish is testing the bait; setting the hook now spooks it.
+    Nibbling,
+    /// The fish has bitten; setting the hook now lands it.
+    Biting,
+    /// A boss fish was hooked but is still fighting. It goes back to nibbling once it
+    /// tires, and the hook has to be set again.
+    Fighting,
+    /// The fish is on the line with fight left in it. A taut line tires it and a slack
+    /// one lets it get its strength back; it can only be reeled in once tired.
+    Hooked,
+}
+
+/// What setting the hook on a biting fish did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookSet {
+    /// The fish at this index is landed.
+    Landed(usize),
+    /// The boss at this index took the hook but fights on.
+    Fighting(usize),
+    /// The fish at this index is on the line and has to be tired out.
+    Hooked(usize),
+}
+
+#[derive(Debug, Clone)]
+struct Bite {
+    fish: usize,
+    phase: BitePhase,
+    remaining: Duration,
+    fish_vx: f32,
+    /// Where the fish was when the hook last moved with it.
+    fish_x: f32,
+    /// Seconds of a taut line the hooked fish can still take, out of `endurance`.
+    stamina: f32,
+    endurance: f32,
+}
+
+#[derive(Debug, Clone)]
+struct Flee {
+    fish: usize,
+    fish_vx: f32,
+    remaining: Duration,
+}
+
+/// Tracks a fish at the hook: it nibbles for a while, bites, and gets away unless the
+/// hook is set within [`BITE_WINDOW`]. Fish are referred to by their index in the
+/// school; the fish at the hook is held in place until it is landed or flees.
+#[derive(Debug, Clone, Default)]
+pub struct BiteTracker {
+    bite: Option<Bite>,
+    flee: Option<Flee>,
+}
+
+impl BiteTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The phase of the current bite, if a fish is at the hook.
+    pub fn phase(&self) -> Option<BitePhase> {
+        self.bite.as_ref().map(|b| b.phase)
+    }
+
+    /// Fish whose movement the tracker currently controls (at the hook or fleeing).
+    pub fn held_fish(&self) -> Vec<usize> {
+        self.bite.iter().map(|b| b.fish).chain(self.flee.iter().map(|f| f.fish)).collect()
+    }
+
+    /// Whether a new fish may take the bait.
+    pub fn can_start(&self) -> bool {
+        self.bite.is_none() && self.flee.is_none()
+    }
+
+    /// A fish touched the hook: with probability `chance` it takes the bait and is held
+    /// there nibbling, breaking away from its school, otherwise it ignores the hook for
+    /// [`HOOK_SHY_TIME`]. Returns whether it took the bait.
+    pub fn start<R: Rng + ?Sized>(&mut self, rng: &mut R, index: usize, fish: &mut Fish, chance: f64) -> bool {
+        if !rng.gen_bool(chance.clamp(0.0, 1.0)) {
+            fish.hook_shy = HOOK_SHY_TIME;
+            return false;
+        }
+        self.bite = Some(Bite {
+            fish: index,
+            phase: BitePhase::Nibbling,
+            remaining: Duration::from_millis(rng.gen_range(NIBBLE_MIN_MS..=NIBBLE_MAX_MS)),
+            fish_vx: fish.vx,
+            fish_x: fish.x,
+            stamina: 0.0,
+            endurance: 0.0,
+        });
+        fish.vx = 0.0;
+        fish.school = None;
+        true
+    }
+
+    /// Advance the bite by `dt`. Returns true when a biting fish got away unhooked.
+    pub fn tick(&mut self, dt: Duration, fishes: &mut [Fish]) -> bool {
+        if let Some(ref mut flee) = self.flee {
+            flee.remaining = flee.remaining.saturating_sub(dt);
+            if flee.remaining.is_zero() {
+                if let Some(fish) = fishes.get_mut(flee.fish) {
+                    fish.vx = flee.fish_vx;
+                }
+                self.flee = None;
+            }
+        }
+
+        let Some(ref mut bite) = self.bite.as_mut().filter(|b| b.phase != BitePhase::Hooked) else { return false };
+        bite.remaining = bite.remaining.saturating_sub(dt);
+        if !bite.remaining.is_zero() {
+            return false;
+        }
+        match bite.phase {
+            BitePhase::Nibbling => {
+                bite.phase = BitePhase::Biting;
+                bite.remaining = BITE_WINDOW;
+                if let Some(fish) = fishes.get_mut(bite.fish) {
+                    fish.vx = if fish.facing_right { DRAG_SPEED } else { -DRAG_SPEED };
+                }
+                false
+            }
+            BitePhase::Biting => {
+                self.spook(fishes);
+                true
+            }
+            BitePhase::Fighting => {
+                bite.phase = BitePhase::Nibbling;
+                bite.remaining = Duration::from_millis(NIBBLE_MAX_MS);
+                if let Some(fish) = fishes.get_mut(bite.fish) {
+                    fish.vx = 0.0;
+                }
+                false
+            }
+            BitePhase::Hooked => false,
+        }
+    }
+
+    /// The player set the hook. A biting fish whose species has stamina, going by its
+    /// `traits`, is hooked and runs with the line, one without is landed, and a boss with fights left
+    /// in it runs with the line for [`FIGHT_TIME`]; setting the hook too early spooks a
+    /// nibbling fish instead. Nothing happens while a fish is fighting or hooked.
+    pub fn set_hook(&mut self, fishes: &mut [Fish], traits: &[SpeciesTraits]) -> Option<HookSet> {
+        match self.phase()? {
+            BitePhase::Biting => {
+                let bite = self.bite.as_mut()?;
+                let stamina = fishes.get(bite.fish).map_or(0.0, |fish| fish.stamina(&traits.get(fish.species).copied().unwrap_or_default()));
+                match fishes.get_mut(bite.fish) {
+                    Some(fish) if fish.boss_fights > 1 => {
+                        fish.boss_fights -= 1;
+                        fish.vx = if fish.facing_right { FIGHT_DRAG_SPEED } else { -FIGHT_DRAG_SPEED };
+                        bite.phase = BitePhase::Fighting;
+                        bite.remaining = FIGHT_TIME;
+                        Some(HookSet::Fighting(bite.fish))
+                    }
+                    Some(fish) if stamina > 0.0 => {
+                        fish.vx = if fish.facing_right { DRAG_SPEED } else { -DRAG_SPEED };
+                        bite.phase = BitePhase::Hooked;
+                        (bite.stamina, bite.endurance) = (stamina, stamina);
+                        Some(HookSet::Hooked(bite.fish))
+                    }
+                    _ => self.bite.take().map(|b| HookSet::Landed(b.fish)),
+                }
+            }
+            BitePhase::Nibbling => {
+                self.spook(fishes);
+                None
+            }
+            BitePhase::Fighting | BitePhase::Hooked => None,
+        }
+    }
+
+    /// Tire the hooked fish, if there is one, over `dt` with the line at `tension`: above
+    /// [`TAUT_TENSION`] it loses stamina, below it gets some back. A tired fish stops
+    /// running and one that gets its strength back runs on.
+    pub fn tire(&mut self, dt: Duration, tension: f32, fishes: &mut [Fish]) {
+        let Some(bite) = self.bite.as_mut().filter(|b| b.phase == BitePhase::Hooked) else { return };
+        let secs = dt.as_secs_f32();
+        bite.stamina = if tension > TAUT_TENSION {
+            (bite.stamina - secs).max(0.0)
+        } else if bite.stamina > 0.0 {
+            (bite.stamina + secs * STAMINA_REGAIN).min(bite.endurance)
+        } else {
+            0.0
+        };
+        if let Some(fish) = fishes.get_mut(bite.fish) {
+            let speed = if bite.stamina > 0.0 { DRAG_SPEED } else { 0.0 };
+            fish.vx = if fish.facing_right { speed } else { -speed };
+        }
+    }
+
+    /// How much fight the hooked fish has left, from 1 when first hooked to 0 once
+    /// tired, or `None` while no fish is hooked.
+    pub fn stamina(&self) -> Option<f32> {
+        let bite = self.bite.as_ref().filter(|b| b.phase == BitePhase::Hooked)?;
+        Some(bite.stamina / bite.endurance.max(f32::EPSILON))
+    }
+
+    /// Reel a tired fish in: the index of the fish to land, or `None` while it still
+    /// has fight in it.
+    pub fn reel_in(&mut self) -> Option<usize> {
+        if self.stamina()? > 0.0 {
+            return None;
+        }
+        self.bite.take().map(|b| b.fish)
+    }
+
+    /// A fish that has bitten swims on with the hook at `hook_x`, or a boss runs with
+    /// it: where it has dragged the hook to, moved as far as the fish has since last
+    /// time and kept between `low` and `high`. A fish that would pull it further, or
+    /// off the screen, is stopped. `None` while no fish has the hook.
+    pub fn drag(&mut self, hook_x: u16, (low, high): (u16, u16), fishes: &mut [Fish]) -> Option<u16> {
+        let bite = self.bite.as_mut().filter(|b| b.phase != BitePhase::Nibbling)?;
+        let fish = fishes.get_mut(bite.fish)?;
+        let x = hook_x as f32 + (fish.x - bite.fish_x).round();
+        bite.fish_x += (fish.x - bite.fish_x).round();
+        let off_left = x <= low as f32 || fish.x <= DRAG_EDGE;
+        if (off_left && fish.vx < 0.0) || (x >= high as f32 && fish.vx > 0.0) {
+            fish.vx = 0.0;
+        }
+        Some((x.round().max(0.0) as u16).clamp(low, high))
+    }
+
+    /// Send the fish at the hook (if any) swimming off at speed.
+    pub fn spook(&mut self, fishes: &mut [Fish]) {
+        let Some(bite) = self.bite.take() else { return };
+        if let Some(fish) = fishes.get_mut(bite.fish) {
+            fish.vx = bite.fish_vx * FLEE_SPEED_FACTOR;
+        }
+        self.flee = Some(Flee {
+            fish: bite.fish,
+            fish_vx: bite.fish_vx,
+            remaining: FLEE_TIME,
+        });
+    }
+
+    /// The fish at `index` left the school, landed on another line: forget it if it was
+    /// this one's, and follow the fish after it down one place.
+    pub fn removed(&mut self, index: usize) {
+        if self.bite.as_ref().is_some_and(|b| b.fish == index) {
+            self.bite = None;
+        }
+        if self.flee.as_ref().is_some_and(|f| f.fish == index) {
+            self.flee = None;
+        }
+        for fish in self.bite.iter_mut().map(|b| &mut b.fish).chain(self.flee.iter_mut().map(|f| &mut f.fish)) {
+            if *fish > index {
+                *fish -= 1;
+            }
+        }
+    }
+
+    /// Whether the line should be drawn tugged down this frame: in short jerks while
+    /// the fish nibbles and constantly once it bites or fights, until a hooked fish tires.
+    pub fn twitching(&self, elapsed: Duration) -> bool {
+        match self.phase() {
+            Some(BitePhase::Biting | BitePhase::Fighting) => true,
+            Some(BitePhase::Hooked) => self.stamina().is_some_and(|stamina| stamina > 0.0),
+            Some(BitePhase::Nibbling) => (elapsed.as_millis() / TWITCH_PERIOD_MS).is_multiple_of(3),
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Junk,
+    Treasure,
+}
+
+/// Something other than a fish that the hook can snag.
+#[derive(Debug, Clone)]
+pub struct Item {
+    pub name: String,
+    pub kind: ItemKind,
+    /// Relative chance of this item among all items in the table.
+    pub weight: u32,
+    pub gold: u32,
+    pub sprite: Vec<String>,
+    /// One of these is shown when the item is caught.
+    pub messages: Vec<String>,
+}
+
+impl Item {
+    fn new(name: &str, kind: ItemKind, weight: u32, gold: u32, sprite: &[&str], messages: &[&str]) -> Self {
+        Item {
+            name: name.to_string(),
+            kind,
+            weight,
+            gold,
+            sprite: sprite.iter().map(|s| s.to_string()).collect(),
+            messages: messages.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// Chance per second that a resting hook snags an item, at the surface and at the bottom.
+const SNAG_CHANCE_SURFACE: f64 = 0.01;
+const SNAG_CHANCE_BOTTOM: f64 = 0.05;
+
+/// Weighted table of junk and treasure the hook can snag.
+#[derive(Debug, Clone)]
+pub struct ItemTable {
+    pub items: Vec<Item>,
+}
+
+impl Default for ItemTable {
+    fn default() -> Self {
+        ItemTable {
+            items: vec![
+                Item::new(
+                    "Old Boot",
+                    ItemKind::Junk,
+                    30,
+                    0,
+                    &[" _", "| |__", "|____)"],
+                    &["Someone's been walking on water.", "Still no sign of the other one."],
+                ),
+                Item::new(
+                    "Rusty Can",
+                    ItemKind::Junk,
+                    30,
+                    0,
+                    &[" ___", "|can|", "|___|"],
+                    &["Best before... a long time ago."],
+                ),
+                Item::new(
+                    "Message in a Bottle",
+                    ItemKind::Junk,
+                    10,
+                    0,
+                    &["  _", " | |", "/   \\", "|~~~|", "\\___/"],
+                    &[
+                        "\"Whatever you're waiting for, it's almost done.\"",
+                        "\"Have you tried turning it off and on again?\"",
+                        "\"Patience is also a form of action.\"",
+                    ],
+                ),
+                Item::new("Gold Coin", ItemKind::Treasure, 8, 10, &["($)"], &["Shiny!"]),
+                Item::new(
+                    "Treasure Chest",
+                    ItemKind::Treasure,
+                    2,
+                    100,
+                    &[" ____", "/____\\", "|_$$_|"],
+                    &["A pirate's fortune!"],
+                ),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemRow {
+    #[serde(rename = "Name")] name: String,
+    #[serde(rename = "Weight")] weight: u32,
+    #[serde(rename = "Gold", default)] gold: u32,
+    #[serde(rename = "Kind", default)] kind: Option<String>,
+    #[serde(rename = "Message", default)] message: Option<String>,
+}
+
+impl ItemTable {
+    /// Load overrides from a CSV with `Name,Weight[,Gold,Kind,Message]` columns. Rows
+    /// naming a built-in item change its weight and value (weight 0 disables it);
+    /// other rows add new items drawn with a generic sprite.
+    pub fn load_csv(path: &str) -> io::Result<ItemTable> {
+        let mut table = ItemTable::default();
+        let mut reader = csv::Reader::from_path(path).map_err(io::Error::other)?;
+        for row in reader.deserialize() {
+            let row: ItemRow = row.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let kind = match row.kind.as_deref().map(str::to_ascii_lowercase).as_deref() {
+                Some("treasure") => ItemKind::Treasure,
+                Some("junk") | None => if row.gold > 0 { ItemKind::Treasure } else { ItemKind::Junk },
+                Some(other) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown item kind '{}'", other),
+                    ));
+                }
+            };
+            match table.items.iter_mut().find(|i| i.name.eq_ignore_ascii_case(&row.name)) {
+                Some(item) => {
+                    item.weight = row.weight;
+                    item.gold = row.gold;
+                    item.kind = kind;
+                    if let Some(message) = row.message {
+                        item.messages = vec![message];
+                    }
+                }
+                None => {
+                    let sprite: &[&str] = match kind {
+                        ItemKind::Junk => &["[?]"],
+                        ItemKind::Treasure => &["<$>"],
+                    };
+                    let message = row.message.unwrap_or_default();
+                    table.items.push(Item::new(&row.name, kind, row.weight, row.gold, sprite, &[&message]));
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// Whether a resting hook snags something during `dt`; deeper hooks (`depth` from
+    /// 0.0 at the surface to 1.0 on the bottom) snag more often.
+    pub fn snags<R: Rng + ?Sized>(&self, rng: &mut R, depth: f32, dt: Duration) -> bool {
+        let per_sec = SNAG_CHANCE_SURFACE + (SNAG_CHANCE_BOTTOM - SNAG_CHANCE_SURFACE) * depth.clamp(0.0, 1.0) as f64;
+        rng.gen_bool((per_sec * dt.as_secs_f64()).clamp(0.0, 1.0))
+    }
+
+    /// Pick an item by weight.
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<CaughtItem> {
+        let total: u32 = self.items.iter().map(|i| i.weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.gen_range(0..total);
+        let item = self.items.iter().find(|i| {
+            if pick < i.weight {
+                true
+            } else {
+                pick -= i.weight;
+                false
+            }
+        })?;
+        let message = if item.messages.is_empty() {
+            String::new()
+        } else {
+            item.messages[rng.gen_range(0..item.messages.len())].clone()
+        };
+        Some(CaughtItem {
+            name: item.name.clone(),
+            kind: item.kind,
+            gold: item.gold,
+            sprite: item.sprite.clone(),
+            message,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CaughtItem {
+    pub name: String,
+    pub kind: ItemKind,
+    pub gold: u32,
+    pub sprite: Vec<String>,
+    pub message: String,
+}
+
+impl CaughtItem {
+    pub fn format_catch(&self) -> String {
+        let article = match self.name.chars().next() {
+            Some(c) if "AEIOUaeiou".contains(c) => "an",
+            _ => "a",
+        };
+        let mut lines = vec![format!("You snagged {} {}!", article, self.name)];
+        lines.extend(self.sprite.iter().cloned());
+        if !self.message.is_empty() {
+            lines.push(self.message.clone());
+        }
+        if self.gold > 0 {
+            lines.push(format!("Worth {} gold", self.gold));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Anything the line can bring up.
+#[derive(Debug, Clone)]
+pub enum Catch {
+    Fish(CaughtFish),
+    Item(CaughtItem),
+}
+
+impl Catch {
+    pub fn title(&self) -> &str {
+        match self {
+            Catch::Fish(fish) if fish.shiny => "Shiny Catch!",
+            Catch::Fish(_) => "Nice Catch!",
+            Catch::Item(item) if item.kind == ItemKind::Treasure => "Treasure!",
+            Catch::Item(_) => "Junk",
+        }
+    }
+
+    pub fn format_catch(&self) -> String {
+        match self {
+            Catch::Fish(fish) => fish.format_catch(),
+            Catch::Item(item) => item.format_catch(),
+        }
+    }
+
+    /// One-line plain description, e.g. `Caught Large Trout, 64 cm`.
+    pub fn describe(&self) -> String {
+        match self {
+            Catch::Fish(fish) => format!(
+                "Caught {}{} {}, {:.0} cm",
+                if fish.shiny { "shiny " } else { "" },
+                fish.size_category.as_str().trim_end_matches('!'),
+                fish.species_name,
+                fish.size
+            ),
+            Catch::Item(item) if item.gold > 0 => format!("Snagged {}, worth {} gold", item.name, item.gold),
+            Catch::Item(item) => format!("Snagged {}", item.name),
+        }
+    }
+}
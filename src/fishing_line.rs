@@ -1,10 +1,15 @@
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Style};
+
+use crate::braille::BrailleCanvas;
+use crate::theme::Theme;
 use ratatui::widgets::Widget;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum FishingState {
+    #[default]
     Idle,
     Charging { power: f32 },
     Casting { 
@@ -18,13 +23,32 @@ pub enum FishingState {
         landing_y: u16,
         depth: u16,
     },
+    /// The hook travelling back up the line from where it hung, at (`from_x`, `from_y`),
+    /// to the rod tip.
+    Retrieving {
+        from_x: u16,
+        from_y: u16,
+        progress: f32,
+    },
 }
 
 pub struct FishingLine {
     pub rod_x: u16,
     pub rod_y: u16,
     pub state: FishingState,
-    pub color: Color,
+    pub theme: Theme,
+    /// A fish is tugging: the hook is drawn pulled one row deeper.
+    pub twitch: bool,
+    /// How taut the line is, from 0 (hanging slack) to 1 (pulled straight). A slack
+    /// line sags between the rod tip and the water.
+    pub tension: f32,
+    /// Columns the wind bows a line in flight, positive to the right.
+    pub wind_skew: f32,
+    /// Draw the line in the air in braille dots, for smoother curves (`--braille`).
+    pub braille: bool,
+    /// Where a cast let go of now would land on the water, shown while charging as a
+    /// faint arc out to a marker.
+    pub preview: Option<(u16, u16)>,
 }
 
 impl Default for FishingLine {
@@ -33,7 +57,12 @@ impl Default for FishingLine {
             rod_x: 0,
             rod_y: 0,
             state: FishingState::Idle,
-            color: Color::Rgb(200, 200, 120),
+            theme: Theme::default(),
+            twitch: false,
+            tension: 1.0,
+            wind_skew: 0.0,
+            braille: false,
+            preview: None,
         }
     }
 }
@@ -52,8 +81,72 @@ impl FishingLine {
         self.state = state;
         self
     }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_twitch(mut self, twitch: bool) -> Self {
+        self.twitch = twitch;
+        self
+    }
+
+    pub fn with_tension(mut self, tension: f32) -> Self {
+        self.tension = tension;
+        self
+    }
+
+    pub fn with_wind_skew(mut self, wind_skew: f32) -> Self {
+        self.wind_skew = wind_skew;
+        self
+    }
+
+    pub fn with_braille(mut self, braille: bool) -> Self {
+        self.braille = braille;
+        self
+    }
+
+    pub fn with_preview(mut self, preview: Option<(u16, u16)>) -> Self {
+        self.preview = preview;
+        self
+    }
 }
 
+/// The shortest cast, however little it was charged, in columns out from the rod tip.
+pub const MIN_CAST_DISTANCE: u16 = 10;
+
+/// Columns out from the rod tip the longest cast of a rod reaching `range` of a screen
+/// `width` columns wide flies.
+pub fn cast_reach(width: u16, range: f32) -> u16 {
+    (width as f32 * range) as u16
+}
+
+/// Columns out from the rod tip a cast let go of at `power` (0 to 1) flies, before the
+/// wind has its say.
+pub fn cast_distance(reach: u16, power: f32) -> u16 {
+    ((reach as f32 * power) as u16).max(MIN_CAST_DISTANCE)
+}
+
+/// The curve a cast flies along from the rod tip to where it lands on the water, bowed
+/// `wind_skew` columns by the wind: the start, control point and end of a quadratic
+/// curve, higher the further it goes.
+fn cast_arc(rod: (u16, u16), landing: (u16, u16), wind_skew: f32) -> ((f32, f32), (f32, f32), (f32, f32)) {
+    let (p0, p2) = ((rod.0 as f32, rod.1 as f32), (landing.0 as f32, landing.1 as f32));
+    let arc_height = ((p0.0 - p2.0).abs() * 0.3).clamp(5.0, 15.0);
+    (p0, ((p0.0 + p2.0) / 2.0 + wind_skew, p0.1 - arc_height), p2)
+}
+
+/// How far a fully slack line sags below the straight line, as a share of the distance
+/// from the rod tip to the water.
+const MAX_SAG: f32 = 0.4;
+/// Points sampled along a sagging line, joined up cell by cell.
+const SAG_SAMPLES: usize = 24;
+/// Points sampled along a curve drawn in braille, joined up dot by dot.
+const BRAILLE_SAMPLES: usize = 64;
+/// Dots along the arc a charging cast would fly.
+const PREVIEW_DOTS: usize = 20;
+
 fn bresenham_line(x0: i32, y0: i32, x1: i32, y1: i32) -> Vec<(i32, i32)> {
     let mut points = Vec::new();
     let dx = (x1 - x0).abs();
@@ -82,6 +175,71 @@ fn bresenham_line(x0: i32, y0: i32, x1: i32, y1: i32) -> Vec<(i32, i32)> {
     points
 }
 
+/// The glyph for a line running on to the next point `(dx, dy)` cells away.
+fn slope_glyph(dx: i32, dy: i32) -> &'static str {
+    if (dx > 0 && dy > 0) || (dx < 0 && dy < 0) {
+        "╲"
+    } else if (dx < 0 && dy > 0) || (dx > 0 && dy < 0) {
+        "╱"
+    } else if dy != 0 {
+        "│"
+    } else {
+        "─"
+    }
+}
+
+/// Where a retrieved hook is, `progress` of the way from `from` back to the rod tip.
+pub fn retrieving_hook(rod: (u16, u16), from: (u16, u16), progress: f32) -> (u16, u16) {
+    let t = progress.clamp(0.0, 1.0);
+    let lerp = |a: u16, b: u16| (a as f32 + (b as f32 - a as f32) * t).round() as u16;
+    (lerp(from.0, rod.0), lerp(from.1, rod.1))
+}
+
+/// The cells of a line from `from` down to `to` on the water, sagging by how slack
+/// `tension` leaves it. The sag hangs off a quadratic curve and never dips below the
+/// water row, so the slack lies on the surface.
+fn sagging_line(from: (i32, i32), to: (i32, i32), tension: f32) -> Vec<(i32, i32)> {
+    if tension >= 1.0 {
+        return bresenham_line(from.0, from.1, to.0, to.1);
+    }
+    let (p0, p2) = ((from.0 as f32, from.1 as f32), (to.0 as f32, to.1 as f32));
+    let p1 = sag_control(p0, p2, tension);
+    let mut points: Vec<(i32, i32)> = Vec::new();
+    let mut last = from;
+    for i in 1..=SAG_SAMPLES {
+        let (x, y) = bezier_point(p0, p1, p2, i as f32 / SAG_SAMPLES as f32);
+        let next = (x.round() as i32, (y.round() as i32).min(to.1));
+        for point in bresenham_line(last.0, last.1, next.0, next.1) {
+            if points.last() != Some(&point) {
+                points.push(point);
+            }
+        }
+        last = next;
+    }
+    points
+}
+
+/// The control point of the curve a line from `p0` to `p2` sags along.
+fn sag_control(p0: (f32, f32), p2: (f32, f32), tension: f32) -> (f32, f32) {
+    let length = (p2.0 - p0.0).hypot(p2.1 - p0.1);
+    let sag = (1.0 - tension.clamp(0.0, 1.0)) * MAX_SAG * length;
+    // Doubled, since a quadratic curve only reaches halfway to its control point
+    ((p0.0 + p2.0) / 2.0, (p0.1 + p2.1) / 2.0 + sag * 2.0)
+}
+
+/// Dots along the quadratic curve through `p0`, `p1` and `p2` from its start to
+/// `until` of the way along, kept above row `floor`.
+fn braille_curve(canvas: &mut BrailleCanvas, (p0, p1, p2): ((f32, f32), (f32, f32), (f32, f32)), until: f32, floor: f32) {
+    let steps = (BRAILLE_SAMPLES as f32 * until.clamp(0.0, 1.0)).ceil().max(1.0) as usize;
+    let mut last = p0;
+    for i in 1..=steps {
+        let (x, y) = bezier_point(p0, p1, p2, until * i as f32 / steps as f32);
+        let next = (x, y.min(floor));
+        canvas.line(last, next);
+        last = next;
+    }
+}
+
 fn bezier_point(p0: (f32, f32), p1: (f32, f32), p2: (f32, f32), t: f32) -> (f32, f32) {
     let t2 = 1.0 - t;
     let x = t2 * t2 * p0.0 + 2.0 * t2 * t * p1.0 + t * t * p2.0;
@@ -89,14 +247,42 @@ fn bezier_point(p0: (f32, f32), p1: (f32, f32), p2: (f32, f32), t: f32) -> (f32,
     (x, y)
 }
 
+impl FishingLine {
+    /// A faint dotted arc from the rod tip to `landing`, with a marker where the hook
+    /// would hit the water.
+    fn render_preview(&self, landing: (u16, u16), area: Rect, buf: &mut Buffer) {
+        let faint = Style::default().fg(Color::DarkGray);
+        let arc = cast_arc((self.rod_x, self.rod_y), landing, self.wind_skew);
+        if self.braille {
+            let mut canvas = BrailleCanvas::new(area);
+            for i in 1..PREVIEW_DOTS {
+                let (x, y) = bezier_point(arc.0, arc.1, arc.2, i as f32 / PREVIEW_DOTS as f32);
+                canvas.dot(x, y);
+            }
+            canvas.render(buf, Color::DarkGray);
+        } else {
+            for i in 1..PREVIEW_DOTS {
+                let (x, y) = bezier_point(arc.0, arc.1, arc.2, i as f32 / PREVIEW_DOTS as f32);
+                let (x, y) = (x.round(), y.round());
+                if x >= 0.0 && y >= 0.0 && area.contains((x as u16, y as u16).into()) {
+                    buf.set_string(x as u16, y as u16, "·", faint);
+                }
+            }
+        }
+        if area.contains(landing.into()) {
+            buf.set_string(landing.0, landing.1, "×", Style::default().fg(self.theme.hook));
+        }
+    }
+}
+
 impl Widget for FishingLine {
     fn render(self, area: Rect, buf: &mut Buffer) {
         if area.width == 0 || area.height == 0 {
             return;
         }
 
-        let style = Style::default().fg(self.color);
-        let hook_style = Style::default().fg(Color::Rgb(150, 150, 255));
+        let style = Style::default().fg(self.theme.line);
+        let hook_style = Style::default().fg(self.theme.hook);
 
         match self.state {
             FishingState::Idle => {
@@ -113,6 +299,9 @@ impl Widget for FishingLine {
                 }
             }
             FishingState::Charging { power } => {
+                if let Some(landing) = self.preview {
+                    self.render_preview(landing, area, buf);
+                }
                 let end_y = self.rod_y.saturating_add(3).min(area.y + area.height - 1);
                 for y in self.rod_y..=end_y {
                     if self.rod_x >= area.x && self.rod_x < area.x + area.width 
@@ -149,16 +338,19 @@ impl Widget for FishingLine {
                 }
             }
             FishingState::Casting { start_x: _, start_y, target_x, progress } => {
-                let p0 = (self.rod_x as f32, self.rod_y as f32);
-                let p2 = (target_x as f32, start_y as f32);
-                
-                let mid_x = (self.rod_x as f32 + target_x as f32) / 2.0;
-                let horizontal_distance = (self.rod_x as f32 - target_x as f32).abs();
-                let arc_height = (horizontal_distance * 0.3).min(15.0).max(5.0);
-                let p1 = (mid_x, self.rod_y as f32 - arc_height);
-
+                let (p0, p1, p2) = cast_arc((self.rod_x, self.rod_y), (target_x, start_y), self.wind_skew);
                 let current_pos = bezier_point(p0, p1, p2, progress);
-                
+                if self.braille {
+                    let mut canvas = BrailleCanvas::new(area);
+                    braille_curve(&mut canvas, (p0, p1, p2), progress, f32::MAX);
+                    canvas.render(buf, self.theme.line);
+                    let (x, y) = (current_pos.0 as u16, current_pos.1 as u16);
+                    if area.contains((x, y).into()) {
+                        buf.set_string(x, y, "⌡", hook_style);
+                    }
+                    return;
+                }
+
                 let points = bresenham_line(
                     self.rod_x as i32,
                     self.rod_y as i32,
@@ -180,12 +372,19 @@ impl Widget for FishingLine {
                 }
             }
             FishingState::Landed { landing_x, landing_y, depth } => {
-                let points_to_landing = bresenham_line(
-                    self.rod_x as i32,
-                    self.rod_y as i32,
-                    landing_x as i32,
-                    landing_y as i32,
-                );
+                let points_to_landing = if self.braille {
+                    let (p0, p2) = ((self.rod_x as f32, self.rod_y as f32), (landing_x as f32, landing_y as f32));
+                    let mut canvas = BrailleCanvas::new(area);
+                    braille_curve(&mut canvas, (p0, sag_control(p0, p2, self.tension), p2), 1.0, p2.1);
+                    canvas.render(buf, self.theme.line);
+                    Vec::new()
+                } else {
+                    sagging_line(
+                        (self.rod_x as i32, self.rod_y as i32),
+                        (landing_x as i32, landing_y as i32),
+                        self.tension,
+                    )
+                };
 
                 for (i, (x, y)) in points_to_landing.iter().enumerate() {
                     let x = *x as u16;
@@ -194,14 +393,7 @@ impl Widget for FishingLine {
                         && y >= area.y && y < area.y + area.height {
                         let char = if points_to_landing.len() > 1 && i < points_to_landing.len() - 1 {
                             let (nx, ny) = points_to_landing[i + 1];
-                            let dx = nx - (x as i32);
-                            let dy = ny - (y as i32);
-                            if dx > 0 && dy > 0 { "╲" }
-                            else if dx < 0 && dy > 0 { "╱" }
-                            else if dx > 0 && dy < 0 { "╱" }
-                            else if dx < 0 && dy < 0 { "╲" }
-                            else if dy != 0 { "│" }
-                            else { "─" }
+                            slope_glyph(nx - x as i32, ny - y as i32)
                         } else {
                             "│"
                         };
@@ -210,7 +402,7 @@ impl Widget for FishingLine {
                 }
 
                 let vertical_start = landing_y.saturating_add(1);
-                let hook_y = landing_y.saturating_add(depth);
+                let hook_y = landing_y.saturating_add(depth).saturating_add(self.twitch as u16);
                 for y in vertical_start..=hook_y {
                     if landing_x >= area.x && landing_x < area.x + area.width 
                         && y >= area.y && y < area.y + area.height {
@@ -222,6 +414,91 @@ impl Widget for FishingLine {
                     }
                 }
             }
+            FishingState::Retrieving { from_x, from_y, progress } => {
+                let (hook_x, hook_y) = retrieving_hook((self.rod_x, self.rod_y), (from_x, from_y), progress);
+                if self.braille {
+                    let mut canvas = BrailleCanvas::new(area);
+                    canvas.line((self.rod_x as f32, self.rod_y as f32), (hook_x as f32, hook_y as f32));
+                    canvas.render(buf, self.theme.line);
+                    if area.contains((hook_x, hook_y).into()) {
+                        buf.set_string(hook_x, hook_y, "⌡", hook_style);
+                    }
+                    return;
+                }
+                let points = bresenham_line(self.rod_x as i32, self.rod_y as i32, hook_x as i32, hook_y as i32);
+                for (i, &(x, y)) in points.iter().enumerate() {
+                    if x < area.x as i32 || x >= area.right() as i32 || y < area.y as i32 || y >= area.bottom() as i32 {
+                        continue;
+                    }
+                    match points.get(i + 1) {
+                        Some(&(nx, ny)) => buf.set_string(x as u16, y as u16, slope_glyph(nx - x, ny - y), style),
+                        None => buf.set_string(x as u16, y as u16, "⌡", hook_style),
+                    };
+                }
+            }
         }
     }
 }
+
+/// Cells in each bar of a [`FightGauge`], and the width of the whole gauge.
+const GAUGE_BAR: u16 = 8;
+pub const GAUGE_WIDTH: u16 = GAUGE_BAR + 5;
+
+/// Two bars beside a line with a fish hooked on it: how much fight the fish has left
+/// and how taut the line is, each from 0 to 1. The fish's bar greys out once it tires.
+pub struct FightGauge {
+    pub stamina: f32,
+    pub tension: f32,
+    /// How taut the line has to be to tire the fish; past it the line's bar lights up.
+    pub taut: f32,
+}
+
+impl Widget for FightGauge {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let label = Style::default().fg(Color::Gray);
+        let filled = |share: f32| (share.clamp(0.0, 1.0) * GAUGE_BAR as f32).round() as usize;
+        let fish_color = if self.stamina > 0.0 { Color::Green } else { Color::DarkGray };
+        let line_color = if self.tension > self.taut { Color::Yellow } else { Color::DarkGray };
+        for (row, (name, share, color)) in [("fish", self.stamina, fish_color), ("line", self.tension, line_color)].into_iter().enumerate() {
+            let y = area.y + row as u16;
+            if y >= area.bottom() {
+                break;
+            }
+            let full = filled(share);
+            let bar = "█".repeat(full) + &"░".repeat(GAUGE_BAR as usize - full);
+            // The gap before the bar is written too, so nothing drawn behind shows through
+            buf.set_stringn(area.x, y, format!("{} ", name), area.width as usize, label);
+            if area.width > 5 {
+                buf.set_stringn(area.x + 5, y, bar, area.width as usize - 5, Style::default().fg(color));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slack_lines_sag_and_taut_lines_run_straight() {
+        let (rod, water) = ((40, 2), (10, 12));
+        assert_eq!(sagging_line(rod, water, 1.0), bresenham_line(rod.0, rod.1, water.0, water.1));
+
+        // Halfway across, the slack line hangs lower but still ends at the hook
+        let row_at = |points: &[(i32, i32)], x: i32| points.iter().filter(|p| p.0 == x).map(|p| p.1).max().unwrap();
+        let slack = sagging_line(rod, water, 0.0);
+        assert!(row_at(&slack, 25) > row_at(&bresenham_line(rod.0, rod.1, water.0, water.1), 25));
+        assert_eq!(slack.first(), Some(&rod));
+        assert_eq!(slack.last(), Some(&water));
+        assert!(slack.iter().all(|p| p.1 <= water.1));
+
+        // In braille the line is dots, still ending at the water
+        let area = Rect::new(0, 0, 50, 14);
+        let mut buf = Buffer::empty(area);
+        let state = FishingState::Landed { landing_x: 10, landing_y: 12, depth: 0 };
+        FishingLine::new(40, 2).with_state(state).with_tension(0.0).with_braille(true).render(area, &mut buf);
+        let braille = |x: u16, y: u16| ('\u{2801}'..='\u{28ff}').contains(&buf[(x, y)].symbol().chars().next().unwrap());
+        assert!(braille(40, 2) && braille(10, 12));
+        assert!((0..50).all(|x| !braille(x, 13)));
+    }
+}
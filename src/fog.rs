@@ -0,0 +1,203 @@
+//! Fog over the water. Every so often, in calm weather, a spell of fog sets in: the far
+//! side of the sea fades from view, and banks of thicker fog roll across the water with
+//! the wind. The fog is drawn as a pass over everything already in the buffer, blending
+//! the fish, the line and the waves towards grey, more so the thicker it is.
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+
+use crate::color;
+use crate::weather::Weather;
+
+/// Seconds of clear air between spells of fog, and how long a spell lasts.
+const CLEAR_SPELL: (f32, f32) = (300.0, 900.0);
+const FOG_SPELL: (f32, f32) = (90.0, 240.0);
+/// How quickly the fog sets in and lifts, in thickness per second.
+const FADE_RATE: f32 = 0.05;
+/// The most banks on the water at once, and the chance a second of a new one forming.
+const MAX_BANKS: usize = 2;
+const BANKS_PER_SECOND: f64 = 0.08;
+const BANK_WIDTH: (f32, f32) = (16.0, 36.0);
+/// Columns a second a bank drifts in still air, and how much faster a full wind
+/// carries it.
+const BANK_DRIFT: f32 = 0.6;
+const BANK_WIND: f32 = 3.0;
+/// How thick a bank is at its middle.
+const BANK_DENSITY: f32 = 0.6;
+/// Share of the width around the fisherman that stays clear, and over which the far
+/// side fades out beyond it.
+const CLEAR_SHARE: f32 = 0.4;
+const FADE_SHARE: f32 = 0.35;
+/// The fog never quite hides everything.
+const MAX_DENSITY: f32 = 0.92;
+
+/// A bank of fog drifting across the water.
+#[derive(Debug, Clone, PartialEq)]
+struct Bank {
+    /// Its left edge and width in columns, and its speed in columns a second.
+    x: f32,
+    width: f32,
+    speed: f32,
+}
+
+/// The fog and its spells, with its own RNG so it never changes what bites.
+#[derive(Debug, Clone)]
+pub struct Fog {
+    rng: StdRng,
+    /// From 0.0 (clear) to 1.0 (the spell at its thickest).
+    level: f32,
+    /// Time left of the spell, or until the next one while clear.
+    until_change: Duration,
+    foggy: bool,
+    banks: Vec<Bank>,
+}
+
+fn spell(rng: &mut StdRng, range: (f32, f32)) -> Duration {
+    Duration::from_secs_f32(rng.gen_range(range.0..range.1))
+}
+
+impl Fog {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let until_change = spell(&mut rng, CLEAR_SPELL);
+        Fog { rng, level: 0.0, until_change, foggy: false, banks: Vec::new() }
+    }
+
+    /// Move the fog on by `dt` over water `width` columns wide, in `weather` and a wind
+    /// of `wind` (-1 blowing left to 1 blowing right). Rain and storms keep it away.
+    /// Returns true when a spell sets in or ends.
+    pub fn tick(&mut self, dt: Duration, width: u16, weather: Weather, wind: f32) -> bool {
+        let secs = dt.as_secs_f32();
+        let calm = matches!(weather, Weather::Clear | Weather::Cloudy);
+        self.until_change = self.until_change.saturating_sub(dt);
+        let mut changed = false;
+        if self.until_change.is_zero() || (self.foggy && !calm) {
+            // A spell due in the rain waits for it to pass
+            if self.foggy || calm {
+                self.foggy = !self.foggy;
+                changed = true;
+            }
+            self.until_change = spell(&mut self.rng, if self.foggy { FOG_SPELL } else { CLEAR_SPELL });
+        }
+        let target = if self.foggy { 1.0 } else { 0.0 };
+        let step = FADE_RATE * secs;
+        self.level += (target - self.level).clamp(-step, step);
+
+        let width = width as f32;
+        for bank in &mut self.banks {
+            bank.x += bank.speed * secs;
+        }
+        self.banks.retain(|bank| bank.x < width && bank.x + bank.width > 0.0);
+        if self.foggy && self.banks.len() < MAX_BANKS && self.rng.gen_bool((BANKS_PER_SECOND * secs as f64).min(1.0)) {
+            let bank_width = self.rng.gen_range(BANK_WIDTH.0..BANK_WIDTH.1);
+            let speed = BANK_DRIFT * if wind < 0.0 { -1.0 } else { 1.0 } + wind * BANK_WIND;
+            // In from the side the wind blows from
+            let x = if speed > 0.0 { -bank_width } else { width };
+            self.banks.push(Bank { x, width: bank_width, speed });
+        }
+        changed
+    }
+
+    /// Whether a spell of fog is setting in or has set in.
+    pub fn is_foggy(&self) -> bool {
+        self.foggy
+    }
+
+    /// How thick the fog is at column `x` of water `width` columns wide, seen by a
+    /// fisherman at column `near`: from 0.0 (clear) to nearly 1.0.
+    pub fn density(&self, x: u16, width: u16, near: u16) -> f32 {
+        if self.level <= 0.0 {
+            return 0.0;
+        }
+        let width = width.max(1) as f32;
+        let distance = x.abs_diff(near) as f32 / width;
+        let far = ((distance - CLEAR_SHARE) / FADE_SHARE).clamp(0.0, 1.0);
+        let far = far * far * (3.0 - 2.0 * far);
+        let column = x as f32 + 0.5;
+        let banks: f32 = self
+            .banks
+            .iter()
+            .filter(|bank| column > bank.x && column < bank.x + bank.width)
+            .map(|bank| BANK_DENSITY * (std::f32::consts::PI * (column - bank.x) / bank.width).sin())
+            .sum();
+        ((far + banks) * self.level).min(MAX_DENSITY)
+    }
+}
+
+/// The fog drawn over the water already in the buffer, seen from column `near`.
+pub struct FogLayer<'a> {
+    pub fog: &'a Fog,
+    pub near: u16,
+    pub color: Color,
+}
+
+impl Widget for FogLayer<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.fog.level <= 0.0 {
+            return;
+        }
+        let area = area.intersection(buf.area);
+        for x in area.left()..area.right() {
+            let density = self.fog.density(x - buf.area.x, buf.area.width, self.near);
+            if density <= 0.0 {
+                continue;
+            }
+            for y in area.top()..area.bottom() {
+                let cell = &mut buf[(x, y)];
+                cell.set_fg(color::blend(cell.fg, self.color, density));
+                cell.set_bg(color::blend(cell.bg, self.color, density));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Style;
+
+    #[test]
+    fn fog_hides_the_far_water_and_lifts_again() {
+        let mut fog = Fog::new(7);
+        fog.until_change = Duration::ZERO;
+        // Rain keeps it away until it passes
+        assert!(!fog.tick(Duration::from_secs(1), 80, Weather::Rain, 0.0));
+        fog.until_change = Duration::ZERO;
+        assert!(fog.tick(Duration::from_secs(1), 80, Weather::Clear, 0.5));
+        for _ in 0..30 {
+            fog.tick(Duration::from_secs(1), 80, Weather::Clear, 0.5);
+        }
+        assert!(fog.is_foggy() && fog.level == 1.0);
+        assert!(fog.banks.iter().all(|bank| bank.speed > 0.0));
+        fog.banks.clear();
+        assert_eq!(fog.density(75, 80, 75), 0.0);
+        assert_eq!(fog.density(0, 80, 75), MAX_DENSITY);
+
+        let area = Rect::new(0, 0, 80, 1);
+        let mut buf = Buffer::empty(area);
+        let fish = Style::default().fg(Color::Rgb(255, 120, 0)).bg(Color::Rgb(20, 20, 60));
+        buf.set_string(0, 0, "<><", fish);
+        buf.set_string(70, 0, "<><", fish);
+        let grey = Color::Rgb(150, 150, 150);
+        FogLayer { fog: &fog, near: 75, color: grey }.render(area, &mut buf);
+        assert_eq!(buf[(70, 0)].fg, Color::Rgb(255, 120, 0));
+        assert_eq!(buf[(0, 0)].fg, color::blend(Color::Rgb(255, 120, 0), grey, MAX_DENSITY));
+
+        // A bank rolling past thickens the fog where it is
+        fog.banks.push(Bank { x: 60.0, width: 20.0, speed: 1.0 });
+        assert!(fog.density(70, 80, 75) > 0.5);
+
+        fog.until_change = Duration::ZERO;
+        assert!(fog.tick(Duration::from_secs(1), 80, Weather::Clear, 0.5));
+        for _ in 0..30 {
+            fog.tick(Duration::from_secs(1), 80, Weather::Clear, 0.5);
+        }
+        assert_eq!(fog.density(0, 80, 75), 0.0);
+    }
+}
@@ -0,0 +1,151 @@
+//! Everything a game carries from one run to the next: the fish in the sea and what's
+//! left of each species, the line, coins and tackle, weather and the time of day. Saved
+//! on exit and restored on launch unless `--new-game` is given.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::clock::SimClock;
+use crate::csv_frames::SpeciesFrames;
+use crate::economy::{Bait, Profile};
+use crate::fish::{self, Fish, SpawnSettings, SpeciesTraits};
+use crate::fishing_line::FishingState;
+use crate::population::Population;
+use crate::weather::WeatherState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameState {
+    /// Species names by index when the state was saved, so fish can be matched up
+    /// again if the species folders change between runs.
+    #[serde(default)]
+    species: Vec<String>,
+    pub fishes: Vec<Fish>,
+    pub fishing_state: FishingState,
+    /// Bait on the hook while the line is out.
+    pub hook_bait: Option<Bait>,
+    pub profile: Profile,
+    pub weather: WeatherState,
+    pub clock: SimClock,
+    /// What's left of each species after the fish taken from the sea.
+    #[serde(default)]
+    pub population: Population,
+}
+
+/// Default save location: `$XDG_DATA_HOME/fisherman/save.json`, falling back to
+/// `~/.local/share` and, on Windows, `%APPDATA%`.
+pub fn default_save_path() -> PathBuf {
+    let dir = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share")))
+        .unwrap_or_else(env::temp_dir);
+    dir.join("fisherman").join("save.json")
+}
+
+impl GameState {
+    /// A fresh game: a sea newly stocked as busy as `spawning` keeps it, the line reeled
+    /// in and an empty wallet.
+    pub fn new<R: Rng + ?Sized>(
+        rng: &mut R,
+        frames_by_species: &[SpeciesFrames],
+        traits: &[SpeciesTraits],
+        screen_width: f32,
+        lanes: usize,
+        spawning: SpawnSettings,
+        time_scale: f64,
+    ) -> Self {
+        let mut fishes = fish::spawn_fishes(rng, frames_by_species, traits, screen_width, lanes, spawning.density, None, &[]);
+        fishes.truncate(spawning.max_fish.unwrap_or(usize::MAX));
+        GameState {
+            species: Vec::new(),
+            fishes,
+            fishing_state: FishingState::Idle,
+            hook_bait: None,
+            profile: Profile::default(),
+            weather: WeatherState::new(rng),
+            clock: SimClock::new(time_scale),
+            population: Population::default(),
+        }
+    }
+
+    /// Fit a restored state to this run: re-index fish against `species_names` (dropping
+    /// species that are gone and fish whose lane no longer fits), reel in a line that
+    /// landed off screen, keep this run's time-lapse factor and refill the stocks for the
+    /// time the game was closed.
+    pub fn adopt(&mut self, species_names: &[String], lanes: usize, screen_width: u16, time_scale: f64) {
+        let saved_names = std::mem::take(&mut self.species);
+        self.fishes.retain_mut(|fish| {
+            let name = saved_names.get(fish.species);
+            let Some(species) = name.and_then(|n| species_names.iter().position(|s| s == n)) else {
+                return false;
+            };
+            fish.species = species;
+            fish.spawn_delay_ms = 0;
+            fish.lane < lanes
+        });
+        if let FishingState::Landed { landing_x, .. } = self.fishing_state
+            && landing_x >= screen_width
+        {
+            self.reel_in();
+        }
+        self.clock = SimClock::resume(time_scale, self.clock.elapsed());
+        self.population.catch_up(SystemTime::now());
+    }
+
+    /// Bring the line in, returning untouched bait to the box.
+    pub fn reel_in(&mut self) {
+        self.fishing_state = FishingState::Idle;
+        if let Some(bait) = self.hook_bait.take() {
+            self.profile.add_bait(bait, 1);
+        }
+    }
+
+    /// A copy fit for saving. A cast that is still charging, in the air or coming back
+    /// in can't be resumed, so it is reeled in.
+    pub fn snapshot(&self, species_names: &[String]) -> GameState {
+        let mut state = self.clone();
+        state.species = species_names.to_vec();
+        state.population.stamp(SystemTime::now());
+        if !matches!(state.fishing_state, FishingState::Landed { .. }) {
+            state.reel_in();
+        }
+        state
+    }
+
+    /// Read a saved game; `Ok(None)` when there is none yet.
+    pub fn load(path: &Path) -> io::Result<Option<GameState>> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Save a snapshot, writing through a temporary file so a crash mid-write never
+    /// leaves a torn save.
+    pub fn save(&self, path: &Path, species_names: &[String]) -> io::Result<()> {
+        write_json(path, &self.snapshot(species_names))
+    }
+}
+
+/// Write `value` as JSON to `path` via a temporary file and a rename.
+pub(crate) fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string(value).map_err(io::Error::other)?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, path)
+}
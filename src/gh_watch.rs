@@ -0,0 +1,223 @@
+//! `fisherman gh-watch --repo owner/name --run <id>`: follow a GitHub Actions workflow
+//! run while fishing. The run is polled through the GitHub API and its state comes in
+//! as signals: a banner as it's queued and starts, heartbeats and progress by jobs
+//! finished while it runs, and a SUCCESS or FAILURE once it completes.
+//!
+//! Requests go through `curl`, so there's no TLS stack to build in; a token in
+//! `GITHUB_TOKEN` or `GH_TOKEN` is sent along for private repositories and a higher
+//! rate limit, and `GITHUB_API_URL` points it at GitHub Enterprise.
+
+use std::env;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::signals::{SignalMessage, SignalSink, SignalSource};
+
+/// Seconds between polls unless `--interval` says otherwise, and the fewest allowed.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(15);
+const MIN_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_API: &str = "https://api.github.com";
+/// Conclusions that count as the run passing.
+const PASSED: [&str; 3] = ["success", "neutral", "skipped"];
+
+/// `owner/name` for `--repo`.
+pub fn parse_repo(s: &str) -> Result<String, String> {
+    match s.trim().split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => Ok(s.trim().to_string()),
+        _ => Err(format!("invalid --repo '{}' (expected owner/name)", s)),
+    }
+}
+
+/// A run id for `--run`, bare or as the run's page, `.../actions/runs/<id>`.
+pub fn parse_run(s: &str) -> Result<u64, String> {
+    let id = match s.split_once("/runs/") {
+        Some((_, rest)) => rest.split('/').next().unwrap_or(""),
+        None => s.trim(),
+    };
+    id.parse().map_err(|_| format!("invalid --run '{}' (expected a workflow run id)", s))
+}
+
+/// Seconds between polls for `--interval`.
+pub fn parse_interval(s: &str) -> Result<Duration, String> {
+    match s.parse::<u64>() {
+        Ok(secs) if Duration::from_secs(secs) >= MIN_INTERVAL => Ok(Duration::from_secs(secs)),
+        _ => Err(format!("invalid --interval '{}' (expected seconds, at least {})", s, MIN_INTERVAL.as_secs())),
+    }
+}
+
+/// What the API says about a workflow run.
+#[derive(Debug, Clone, Deserialize)]
+struct Run {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    run_number: Option<u64>,
+    status: String,
+    #[serde(default)]
+    conclusion: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Jobs {
+    jobs: Vec<Job>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Job {
+    name: String,
+    status: String,
+}
+
+/// A workflow run followed until it completes.
+pub struct GhWatch {
+    pub repo: String,
+    pub run: u64,
+    pub interval: Duration,
+}
+
+impl GhWatch {
+    fn url(&self, path: &str) -> String {
+        let api = env::var("GITHUB_API_URL").unwrap_or_else(|_| DEFAULT_API.to_string());
+        format!("{}/repos/{}/actions/runs/{}{}", api.trim_end_matches('/'), self.repo, self.run, path)
+    }
+
+    /// The run, and its jobs while it's going.
+    fn poll(&self) -> Result<(Run, Option<Jobs>), String> {
+        let run: Run = serde_json::from_str(&fetch(&self.url(""))?).map_err(|e| e.to_string())?;
+        let jobs = match run.status.as_str() {
+            "in_progress" => serde_json::from_str(&fetch(&self.url("/jobs"))?).ok(),
+            _ => None,
+        };
+        Ok((run, jobs))
+    }
+}
+
+impl SignalSource for GhWatch {
+    fn run(self: Box<Self>, sink: SignalSink) {
+        let mut last_status = None;
+        let mut failing = false;
+        loop {
+            match self.poll() {
+                Ok((run, jobs)) => {
+                    failing = false;
+                    let done = run.status == "completed";
+                    for message in signals_for(&run, jobs.as_ref(), &mut last_status) {
+                        if !sink.send(message) {
+                            return;
+                        }
+                    }
+                    if done {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("gh-watch {} run {}: {}", self.repo, self.run, e);
+                    // Say so once, not on every poll until it comes back
+                    if !failing && !sink.send(SignalMessage::Info(format!("GitHub: {}", e))) {
+                        return;
+                    }
+                    failing = true;
+                }
+            }
+            let next = Instant::now() + self.interval;
+            while Instant::now() < next {
+                if sink.is_stopped() {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+    }
+}
+
+/// The signals for one look at `run`: a banner when its status changes from
+/// `last_status`, then a heartbeat and progress by `jobs` while it's going, or the
+/// outcome once it's done.
+fn signals_for(run: &Run, jobs: Option<&Jobs>, last_status: &mut Option<String>) -> Vec<SignalMessage> {
+    let name = match (&run.name, run.run_number) {
+        (Some(name), Some(number)) => format!("{} #{}", name, number),
+        (Some(name), None) => name.clone(),
+        (None, _) => "Workflow run".to_string(),
+    };
+    let mut messages = Vec::new();
+    let changed = last_status.as_deref() != Some(run.status.as_str());
+    *last_status = Some(run.status.clone());
+    match run.status.as_str() {
+        "completed" => {
+            let conclusion = run.conclusion.as_deref().unwrap_or("unknown");
+            messages.push(if PASSED.contains(&conclusion) {
+                SignalMessage::Success(format!("{} passed", name))
+            } else {
+                SignalMessage::Failure(format!("{} {}", name, conclusion.replace('_', " ")))
+            });
+        }
+        status => {
+            if changed {
+                messages.push(SignalMessage::Banner(format!("{} {}", name, status.replace('_', " "))));
+            }
+            messages.push(SignalMessage::Heartbeat);
+            if let Some(jobs) = jobs.filter(|jobs| !jobs.jobs.is_empty()) {
+                let finished = jobs.jobs.iter().filter(|job| job.status == "completed").count();
+                let running = jobs.jobs.iter().find(|job| job.status == "in_progress").map_or("", |job| job.name.as_str());
+                let percent = (finished * 100 / jobs.jobs.len()) as u8;
+                messages.push(SignalMessage::Progress { percent, label: running.to_string() });
+            }
+        }
+    }
+    messages
+}
+
+/// The body at `url`, fetched with curl.
+fn fetch(url: &str) -> Result<String, String> {
+    let mut curl = Command::new("curl");
+    curl.args(["--silent", "--show-error", "--fail", "--location", "--max-time", "20"])
+        .args(["--header", "Accept: application/vnd.github+json"])
+        .args(["--header", "User-Agent: fisherman"]);
+    if let Some(token) = env::var("GITHUB_TOKEN").ok().or_else(|| env::var("GH_TOKEN").ok()).filter(|token| !token.is_empty()) {
+        curl.args(["--header", &format!("Authorization: Bearer {}", token)]);
+    }
+    let output = curl.arg(url).stdin(Stdio::null()).output().map_err(|e| format!("could not run curl: {}", e))?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(error.lines().last().unwrap_or("request failed").trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_run_heartbeats_while_going_and_ends_in_its_conclusion() {
+        assert_eq!(parse_repo("octo/app"), Ok("octo/app".to_string()));
+        assert!(parse_repo("octo").is_err() && parse_repo("octo/app/x").is_err());
+        assert_eq!(parse_run("42"), Ok(42));
+        assert_eq!(parse_run("https://github.com/octo/app/actions/runs/42/job/7"), Ok(42));
+        assert!(parse_interval("2").is_err());
+
+        let run = |json: &str| serde_json::from_str::<Run>(json).unwrap();
+        let mut last = None;
+        let queued = run(r#"{"name":"CI","run_number":9,"status":"queued","conclusion":null}"#);
+        assert_eq!(signals_for(&queued, None, &mut last), [SignalMessage::Banner("CI #9 queued".to_string()), SignalMessage::Heartbeat]);
+        assert_eq!(signals_for(&queued, None, &mut last), [SignalMessage::Heartbeat]);
+
+        let going = run(r#"{"name":"CI","run_number":9,"status":"in_progress"}"#);
+        let jobs: Jobs = serde_json::from_str(
+            r#"{"total_count":4,"jobs":[{"name":"lint","status":"completed"},{"name":"test","status":"in_progress"},
+                {"name":"build","status":"queued"},{"name":"docs","status":"queued"}]}"#,
+        )
+        .unwrap();
+        let messages = signals_for(&going, Some(&jobs), &mut last);
+        assert_eq!(messages[0], SignalMessage::Banner("CI #9 in progress".to_string()));
+        assert_eq!(messages[2], SignalMessage::Progress { percent: 25, label: "test".to_string() });
+
+        let passed = run(r#"{"name":"CI","run_number":9,"status":"completed","conclusion":"success"}"#);
+        assert_eq!(signals_for(&passed, None, &mut last), [SignalMessage::Success("CI #9 passed".to_string())]);
+        let timed_out = run(r#"{"status":"completed","conclusion":"timed_out"}"#);
+        assert_eq!(signals_for(&timed_out, None, &mut last), [SignalMessage::Failure("Workflow run timed out".to_string())]);
+    }
+}
@@ -0,0 +1,430 @@
+//! Fish and the moon drawn as pictures (`--graphics`), on terminals that can show
+//! images through the kitty graphics protocol or sixel. A species gets pictures from a
+//! `right.png` and `left.png` in its folder, either one mirrored when the other is
+//! missing, and the moon from a `moon.png` beside `moon.csv`. Sprites without a
+//! picture, and every sprite on other terminals, are drawn from their CSV text as ever.
+//!
+//! The scene asks a [`SpriteRenderer`] for each sprite before drawing its text. The
+//! text renderer never takes one; the image renderer blanks the cells a picture will
+//! cover and, once the frame has gone out, writes the pictures over them.
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier};
+
+use crate::fish;
+use crate::png::{self, Image};
+
+/// Cell size in pixels to scale sixel pictures to, when the terminal doesn't say.
+const DEFAULT_CELL_PIXELS: (u16, u16) = (10, 20);
+/// Largest piece of a kitty image sent in one escape sequence, in base64 bytes.
+const KITTY_CHUNK: usize = 4096;
+/// Pixels less opaque than this are left out of sixel pictures.
+const SIXEL_ALPHA_CUTOFF: u8 = 128;
+
+/// How pictures get to the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+}
+
+/// The `--graphics` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsMode {
+    /// Text sprites only.
+    Off,
+    /// Pictures if the terminal looks like it can show them.
+    Auto,
+    Kitty,
+    Sixel,
+}
+
+impl FromStr for GraphicsMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" => Ok(GraphicsMode::Off),
+            "auto" => Ok(GraphicsMode::Auto),
+            "kitty" => Ok(GraphicsMode::Kitty),
+            "sixel" => Ok(GraphicsMode::Sixel),
+            _ => Err(format!("invalid graphics mode '{}' (expected auto, kitty, sixel or off)", s)),
+        }
+    }
+}
+
+impl GraphicsMode {
+    /// The protocol to draw pictures with, if any.
+    pub fn protocol(self) -> Option<GraphicsProtocol> {
+        match self {
+            GraphicsMode::Off => None,
+            GraphicsMode::Auto => GraphicsProtocol::detect(),
+            GraphicsMode::Kitty => Some(GraphicsProtocol::Kitty),
+            GraphicsMode::Sixel => Some(GraphicsProtocol::Sixel),
+        }
+    }
+}
+
+impl GraphicsProtocol {
+    /// Guess from the environment: kitty, WezTerm and Ghostty speak the kitty protocol,
+    /// and foot, mlterm and a `TERM` naming sixel get sixel. Anything else, `None`.
+    pub fn detect() -> Option<GraphicsProtocol> {
+        let term = env::var("TERM").unwrap_or_default();
+        let program = env::var("TERM_PROGRAM").unwrap_or_default();
+        if env::var_os("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" || program == "WezTerm" || program == "ghostty" {
+            Some(GraphicsProtocol::Kitty)
+        } else if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") {
+            Some(GraphicsProtocol::Sixel)
+        } else {
+            None
+        }
+    }
+}
+
+/// A sprite that may have a picture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpriteId {
+    Fish { species: usize, facing_right: bool, shiny: bool },
+    Moon,
+}
+
+/// The pictures found beside the CSV sprites.
+#[derive(Debug, Clone, Default)]
+pub struct SpriteImages {
+    /// Right- and left-facing pictures, by species index.
+    fish: Vec<Option<[Image; 2]>>,
+    /// `fish` with the shiny recolouring.
+    shiny: Vec<Option<[Image; 2]>>,
+    moon: Option<Image>,
+}
+
+impl SpriteImages {
+    /// Pictures for the species `names`, looked for in `<dir>/<name>/` for each of
+    /// `species_dirs`, and the moon from `moon_paths`; later ones win. Pictures that
+    /// can't be read are logged and skipped.
+    pub fn load(species_dirs: &[PathBuf], names: &[String], moon_paths: &[PathBuf]) -> SpriteImages {
+        let fish: Vec<Option<[Image; 2]>> = names
+            .iter()
+            .map(|name| {
+                let mut pictures = None;
+                for dir in species_dirs {
+                    let right = read_picture(&dir.join(name).join("right.png"));
+                    let left = read_picture(&dir.join(name).join("left.png"));
+                    pictures = match (right, left) {
+                        (Some(right), Some(left)) => Some([right, left]),
+                        (Some(right), None) => Some([right.clone(), mirror(&right)]),
+                        (None, Some(left)) => Some([mirror(&left), left]),
+                        (None, None) => pictures,
+                    };
+                }
+                pictures
+            })
+            .collect();
+        let shiny = fish.iter().map(|pictures| pictures.as_ref().map(|pair| pair.clone().map(|image| recolor(&image, fish::shiny_color)))).collect();
+        let moon = moon_paths.iter().rev().find_map(|path| read_picture(&path.with_extension("png")));
+        SpriteImages { fish, shiny, moon }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moon.is_none() && self.fish.iter().all(Option::is_none)
+    }
+
+    pub fn get(&self, id: SpriteId) -> Option<&Image> {
+        match id {
+            SpriteId::Fish { species, facing_right, shiny } => {
+                let pictures = if shiny { &self.shiny } else { &self.fish };
+                pictures.get(species)?.as_ref().map(|pair| &pair[if facing_right { 0 } else { 1 }])
+            }
+            SpriteId::Moon => self.moon.as_ref(),
+        }
+    }
+}
+
+/// The picture at `path`, if there is one that can be read.
+fn read_picture(path: &Path) -> Option<Image> {
+    let data = fs::read(path).ok()?;
+    png::decode(&data).map_err(|e| tracing::warn!("failed to load {}: {}", path.display(), e)).ok()
+}
+
+fn mirror(image: &Image) -> Image {
+    let pixels = (0..image.height).flat_map(|y| (0..image.width).rev().map(move |x| image.pixel(x, y))).collect();
+    Image { width: image.width, height: image.height, pixels }
+}
+
+fn recolor(image: &Image, recolor: impl Fn(Color) -> Color) -> Image {
+    let pixels = image
+        .pixels
+        .iter()
+        .map(|&[r, g, b, a]| match recolor(Color::Rgb(r, g, b)) {
+            Color::Rgb(r, g, b) => [r, g, b, a],
+            _ => [r, g, b, a],
+        })
+        .collect();
+    Image { width: image.width, height: image.height, pixels }
+}
+
+/// Puts sprites on screen, as text or otherwise.
+pub trait SpriteRenderer {
+    /// Take sprite `id` to show over `rect`, returning whether it did. If it didn't,
+    /// the caller draws the text sprite as usual.
+    fn draw(&mut self, id: SpriteId, rect: Rect, buf: &mut Buffer) -> bool;
+
+    /// Write out the sprites taken since the last call, once the frame they were taken
+    /// in has been drawn.
+    fn flush(&mut self, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Text sprites: takes nothing.
+pub struct TextSprites;
+
+impl SpriteRenderer for TextSprites {
+    fn draw(&mut self, _id: SpriteId, _rect: Rect, _buf: &mut Buffer) -> bool {
+        false
+    }
+
+    fn flush(&mut self, _out: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Pictures, for every sprite that has one.
+pub struct ImageSprites {
+    protocol: GraphicsProtocol,
+    images: SpriteImages,
+    /// Taken this frame, to be written out.
+    placed: Vec<(SpriteId, Rect)>,
+    /// Kitty image ids of the pictures sent to the terminal so far.
+    kitty_ids: HashMap<SpriteId, u32>,
+    /// Sixel pictures already encoded, by sprite and size in pixels.
+    sixels: HashMap<(SpriteId, u32, u32), String>,
+    /// Whether pictures may be on screen from the last frame, or from before this
+    /// renderer took over, to be taken down.
+    showing: bool,
+}
+
+impl ImageSprites {
+    pub fn new(protocol: GraphicsProtocol, images: SpriteImages) -> ImageSprites {
+        ImageSprites { protocol, images, placed: Vec::new(), kitty_ids: HashMap::new(), sixels: HashMap::new(), showing: true }
+    }
+
+    fn flush_kitty(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        // Take down last frame's placements but keep the pictures, to place again
+        write!(out, "\x1b_Ga=d,d=a,q=2\x1b\\")?;
+        let mut sent: HashSet<SpriteId> = self.kitty_ids.keys().copied().collect();
+        for (id, rect) in std::mem::take(&mut self.placed) {
+            let Some(image) = self.images.get(id) else {
+                continue;
+            };
+            let next = self.kitty_ids.len() as u32 + 1;
+            let image_id = *self.kitty_ids.entry(id).or_insert(next);
+            write!(out, "\x1b[{};{}H", rect.y + 1, rect.x + 1)?;
+            let place = format!("i={},c={},r={},C=1,q=2", image_id, rect.width, rect.height);
+            if sent.insert(id) {
+                let data = base64(&image.pixels.iter().flatten().copied().collect::<Vec<u8>>());
+                let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+                for (i, chunk) in chunks.iter().enumerate() {
+                    let more = (i + 1 < chunks.len()) as u8;
+                    if i == 0 {
+                        write!(out, "\x1b_Ga=T,f=32,s={},v={},{},m={};", image.width, image.height, place, more)?;
+                    } else {
+                        write!(out, "\x1b_Gm={};", more)?;
+                    }
+                    out.write_all(chunk)?;
+                    write!(out, "\x1b\\")?;
+                }
+            } else {
+                write!(out, "\x1b_Ga=p,{}\x1b\\", place)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush_sixel(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        let (cell_width, cell_height) = cell_pixels();
+        for (id, rect) in std::mem::take(&mut self.placed) {
+            let Some(image) = self.images.get(id) else {
+                continue;
+            };
+            let (width, height) = (rect.width as u32 * cell_width as u32, rect.height as u32 * cell_height as u32);
+            let sixel = self.sixels.entry((id, width, height)).or_insert_with(|| sixel(image, width, height));
+            write!(out, "\x1b[{};{}H", rect.y + 1, rect.x + 1)?;
+            out.write_all(sixel.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl SpriteRenderer for ImageSprites {
+    fn draw(&mut self, id: SpriteId, rect: Rect, buf: &mut Buffer) -> bool {
+        if self.images.get(id).is_none() || rect.is_empty() || buf.area.intersection(rect) != rect {
+            return false;
+        }
+        // Blank the cells so no text shows around the picture. Marking them tells them
+        // apart from plain water, so they're redrawn once the picture has moved on and
+        // don't leave a trail of stale sixel pixels behind.
+        for y in rect.top()..rect.bottom() {
+            for x in rect.left()..rect.right() {
+                let cell = &mut buf[(x, y)];
+                cell.set_char(' ');
+                cell.modifier.insert(Modifier::HIDDEN);
+            }
+        }
+        self.placed.push((id, rect));
+        true
+    }
+
+    fn flush(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        if self.placed.is_empty() && !self.showing {
+            return Ok(());
+        }
+        self.showing = !self.placed.is_empty();
+        // Put the cursor back where the frame left it
+        write!(out, "\x1b7")?;
+        match self.protocol {
+            GraphicsProtocol::Kitty => self.flush_kitty(out)?,
+            GraphicsProtocol::Sixel => self.flush_sixel(out)?,
+        }
+        write!(out, "\x1b8")?;
+        out.flush()
+    }
+}
+
+/// Pictures over text with `protocol`, or text alone without one or with nothing to
+/// show.
+pub fn renderer(protocol: Option<GraphicsProtocol>, images: SpriteImages) -> Box<dyn SpriteRenderer> {
+    match protocol {
+        Some(protocol) if !images.is_empty() => Box::new(ImageSprites::new(protocol, images)),
+        _ => Box::new(TextSprites),
+    }
+}
+
+/// Width and height of a cell in pixels, as the terminal reports them.
+fn cell_pixels() -> (u16, u16) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            (size.width / size.columns, size.height / size.rows)
+        }
+        _ => DEFAULT_CELL_PIXELS,
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// `image` scaled to `width` by `height` pixels as a sixel picture, its colours cut to
+/// a 6×6×6 cube and its see-through pixels left alone.
+fn sixel(image: &Image, width: u32, height: u32) -> String {
+    let level = |c: u8| (c as u32 * 5 + 127) / 255;
+    let indices: Vec<Option<u32>> = (0..width * height)
+        .map(|i| {
+            let (x, y) = (i % width * image.width / width, i / width * image.height / height);
+            let [r, g, b, a] = image.pixel(x, y);
+            (a >= SIXEL_ALPHA_CUTOFF).then(|| level(r) * 36 + level(g) * 6 + level(b))
+        })
+        .collect();
+
+    // Transparent background, one pixel per pixel, and the size up front
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    let mut used: Vec<u32> = indices.iter().flatten().copied().collect();
+    used.sort_unstable();
+    used.dedup();
+    for &index in &used {
+        let percent = |level: u32| level * 100 / 5;
+        let _ = write!(out, "#{};2;{};{};{}", index, percent(index / 36), percent(index / 6 % 6), percent(index % 6));
+    }
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let colors: Vec<u32> =
+            used.iter().copied().filter(|&c| rows.clone().any(|y| (0..width).any(|x| indices[(y * width + x) as usize] == Some(c)))).collect();
+        for (n, &c) in colors.iter().enumerate() {
+            if n > 0 {
+                out.push('$');
+            }
+            let _ = write!(out, "#{}", c);
+            let column = |x: u32| {
+                let bits = rows.clone().enumerate().fold(0u8, |bits, (bit, y)| {
+                    if indices[(y * width + x) as usize] == Some(c) { bits | 1 << bit } else { bits }
+                });
+                (63 + bits) as char
+            };
+            let mut x = 0;
+            while x < width {
+                let ch = column(x);
+                let run = (x..width).take_while(|&x| column(x) == ch).count();
+                if run > 3 {
+                    let _ = write!(out, "!{}{}", run, ch);
+                } else {
+                    out.extend(std::iter::repeat_n(ch, run));
+                }
+                x += run as u32;
+            }
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pictures_take_the_place_of_text_sprites_that_have_them() {
+        assert_eq!("Sixel".parse::<GraphicsMode>(), Ok(GraphicsMode::Sixel));
+        assert!("png".parse::<GraphicsMode>().is_err());
+        assert_eq!(base64(b"fish!"), "ZmlzaCE=");
+
+        // A 2×2 picture: red on top, a see-through pixel and blue beneath
+        let red = [255, 0, 0, 255];
+        let image = Image { width: 2, height: 2, pixels: vec![red, red, [0, 0, 0, 0], [0, 0, 255, 255]] };
+        assert_eq!(mirror(&image).pixel(0, 1), [0, 0, 255, 255]);
+        let encoded = sixel(&image, 2, 2);
+        assert!(encoded.starts_with("\x1bP0;1;0q\"1;1;2;2"), "{:?}", encoded);
+        // Blue fills only the second column's bottom row, red both columns' top
+        assert!(encoded.contains("#5?A$#180@@-"), "{:?}", encoded);
+
+        let images = SpriteImages { fish: vec![None, Some([image.clone(), mirror(&image)])], shiny: vec![None, None], moon: None };
+        let mut sprites = ImageSprites::new(GraphicsProtocol::Kitty, images);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 10, 4));
+        buf.set_string(0, 0, "><>", ratatui::style::Style::default());
+        let fish = |species| SpriteId::Fish { species, facing_right: true, shiny: false };
+        assert!(!sprites.draw(fish(0), Rect::new(0, 0, 3, 1), &mut buf));
+        assert!(!sprites.draw(fish(1), Rect::new(8, 0, 3, 1), &mut buf));
+        assert!(sprites.draw(fish(1), Rect::new(0, 0, 3, 1), &mut buf));
+        assert_eq!(buf[(0, 0)].symbol(), " ");
+        assert!(buf[(0, 0)].modifier.contains(Modifier::HIDDEN));
+
+        let mut out = Vec::new();
+        sprites.flush(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\x1b[1;1H\x1b_Ga=T,f=32,s=2,v=2,i=1,c=3,r=1,C=1,q=2,m=0;"), "{:?}", out);
+        let mut again = Vec::new();
+        sprites.draw(fish(1), Rect::new(2, 1, 3, 1), &mut buf);
+        sprites.flush(&mut again).unwrap();
+        assert!(String::from_utf8(again).unwrap().contains("\x1b[2;3H\x1b_Ga=p,i=1,"));
+    }
+}
@@ -0,0 +1,47 @@
+//! Warm-boot handoff (`--handoff <path>`): the live scene is written out on exit and
+//! picked up by the next start, so restarting or upgrading a long-running dashboard
+//! doesn't reset it to an empty sea.
+//!
+//! On top of the saved game this carries what only matters to a running dashboard:
+//! the progress bar, the banner and signals that arrived but were not handled yet.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game_state::{self, GameState};
+use crate::signals::{self, SignalMessage};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Handoff {
+    pub state: GameState,
+    pub progress: Option<(u8, String)>,
+    pub banner: Option<String>,
+    pub last_outcome: Option<bool>,
+    /// Signals that had arrived but not been handled yet, as wire-format lines.
+    pub pending: Vec<String>,
+}
+
+impl Handoff {
+    pub fn pending_messages(&self) -> impl Iterator<Item = SignalMessage> + '_ {
+        self.pending.iter().filter_map(|line| signals::parse_signal_line(line))
+    }
+
+    /// Read a handoff file; `Ok(None)` when there is nothing to restore.
+    pub fn load(path: &Path) -> io::Result<Option<Handoff>> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write through a temporary file so a crash mid-write never leaves a torn handoff.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        game_state::write_json(path, self)
+    }
+}
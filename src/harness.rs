@@ -0,0 +1,248 @@
+//! Scripted play for integration tests: an [`App`] driven by key events, signal lines
+//! and fixed steps of simulated time, the same [`Entry`]s a recording replays, and
+//! drawn off-screen after each step so tests can check the screen as a player would
+//! see it as well as the game state.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+use crate::app::{App, Assets, Config};
+use crate::events::GameEvent;
+use crate::headless::{self, FRAME_TIME};
+use crate::record::Entry;
+use crate::signals::SharedSignal;
+
+/// An app played from a script, with the screen it was last drawn to.
+pub struct Harness {
+    app: App,
+    terminal: Terminal<TestBackend>,
+    inbox: SharedSignal,
+    events: Vec<GameEvent>,
+}
+
+impl Harness {
+    /// A `size` scene from `config`, taking signals from the harness unless the config
+    /// brings its own inbox.
+    pub fn new(mut config: Config, size: Rect) -> Harness {
+        let inbox = config.signals.get_or_insert_with(|| Arc::new(Mutex::new(VecDeque::new()))).clone();
+        let app = App::new(config, Assets::load(), None, size);
+        let Ok(terminal) = Terminal::new(TestBackend::new(size.width, size.height));
+        let mut harness = Harness { app, terminal, inbox, events: Vec::new() };
+        harness.draw();
+        harness
+    }
+
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Play `entries` in order, drawing after each update.
+    pub fn play(&mut self, entries: &[Entry]) {
+        for entry in entries {
+            entry.apply(&mut self.app, Some(&self.inbox));
+            if let Entry::Update(_) = entry {
+                self.draw();
+            }
+        }
+    }
+
+    pub fn press(&mut self, code: KeyCode) {
+        self.key(code, KeyEventKind::Press);
+    }
+
+    pub fn release(&mut self, code: KeyCode) {
+        self.key(code, KeyEventKind::Release);
+    }
+
+    /// Press and let go of each key in turn, as typed.
+    pub fn type_keys(&mut self, text: &str) {
+        for c in text.chars() {
+            self.press(KeyCode::Char(c));
+            self.release(KeyCode::Char(c));
+        }
+    }
+
+    fn key(&mut self, code: KeyCode, kind: KeyEventKind) {
+        self.play(&[Entry::Event(Event::Key(KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind)))]);
+    }
+
+    /// Deliver a protocol line such as `SUCCESS:deployed`, taken in at the next update.
+    pub fn signal(&mut self, line: &str) {
+        self.play(&[Entry::Signal(line.to_string())]);
+    }
+
+    /// Let `time` pass, a frame of [`FRAME_TIME`] at a time.
+    pub fn wait(&mut self, time: Duration) {
+        let mut left = time;
+        while !left.is_zero() {
+            let dt = left.min(FRAME_TIME);
+            self.play(&[Entry::Update(dt)]);
+            left -= dt;
+        }
+    }
+
+    /// Step a frame at a time until `done` holds, for at most `limit`. Whether it came
+    /// to hold in time.
+    pub fn until(&mut self, limit: Duration, mut done: impl FnMut(&mut Harness) -> bool) -> bool {
+        let mut waited = Duration::ZERO;
+        while !done(self) {
+            if waited >= limit {
+                return false;
+            }
+            self.wait(FRAME_TIME);
+            waited += FRAME_TIME;
+        }
+        true
+    }
+
+    /// The events published since the harness last collected them, oldest first.
+    pub fn events(&mut self) -> &[GameEvent] {
+        self.collect();
+        &self.events
+    }
+
+    /// Forget the events collected so far.
+    pub fn clear_events(&mut self) {
+        self.collect();
+        self.events.clear();
+    }
+
+    fn collect(&mut self) {
+        self.events.extend(self.app.take_events());
+    }
+
+    /// The screen as last drawn.
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    /// The screen as last drawn, as plain text a row to a line.
+    pub fn screen(&self) -> String {
+        headless::buffer_text(self.buffer())
+    }
+
+    /// The symbol drawn at column `x`, row `y`.
+    pub fn cell(&self, x: u16, y: u16) -> &str {
+        self.buffer().cell((x, y)).map_or("", |cell| cell.symbol())
+    }
+
+    fn draw(&mut self) {
+        // Events are collected as they happen, before the next step can publish more
+        self.collect();
+        let Ok(_) = self.terminal.draw(|f| self.app.draw(f));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fish::{MAX_DENSITY, SpawnSettings};
+    use crate::fishing_line::FishingState;
+
+    const SEED: u64 = 1;
+
+    const SPACE: KeyCode = KeyCode::Char(' ');
+
+    fn harness() -> Harness {
+        Harness::new(Config { seed: Some(SEED), ..Config::default() }, Rect::new(0, 0, 100, 40))
+    }
+
+    /// A sea stocked as full as it goes, for a bite sooner.
+    fn crowded() -> Harness {
+        let spawning = SpawnSettings { density: MAX_DENSITY, max_fish: None };
+        Harness::new(Config { seed: Some(SEED), spawning, ..Config::default() }, Rect::new(0, 0, 100, 40))
+    }
+
+    /// Charge a cast for a second and let it fly, then wait for the hook to land.
+    fn cast(h: &mut Harness) -> (u16, u16) {
+        h.press(SPACE);
+        h.wait(Duration::from_secs(1));
+        assert!(matches!(h.app().game().fishing_state, FishingState::Charging { power } if power > 0.0));
+        h.release(SPACE);
+        assert!(h.until(Duration::from_secs(5), |h| matches!(h.app().game().fishing_state, FishingState::Landed { .. })));
+        let FishingState::Landed { landing_x, landing_y, .. } = h.app().game().fishing_state else {
+            unreachable!();
+        };
+        (landing_x, landing_y)
+    }
+
+    #[test]
+    fn a_cast_charges_flies_and_lands() {
+        let mut h = harness();
+        assert_eq!(h.app().game().fishing_state, FishingState::Idle);
+        let (x, y) = cast(&mut h);
+        assert_eq!(h.events()[..2], [GameEvent::Cast, GameEvent::CastLanded { x, y }]);
+
+        // Reeling all the way in brings the hook home
+        assert!(h.until(Duration::from_secs(30), |h| {
+            h.press(KeyCode::Up);
+            h.app().game().fishing_state == FishingState::Idle
+        }));
+    }
+
+    #[test]
+    fn a_biting_fish_is_landed_by_setting_the_hook() {
+        let mut h = crowded();
+        cast(&mut h);
+        h.clear_events();
+        // Sink the hook a row every two seconds, watching the bobber for the bite mark;
+        // reel in once the hook is set or has hit the bottom, and cast again
+        const STEP: usize = 40;
+        let (mut frames, mut reeling) = (0, false);
+        let caught = h.until(Duration::from_secs(600), |h| {
+            frames += 1;
+            match h.app().game().fishing_state {
+                FishingState::Landed { landing_x, landing_y, .. } if h.cell(landing_x, landing_y - 1) == "!" => {
+                    h.press(SPACE);
+                    reeling = true;
+                }
+                FishingState::Landed { .. } if reeling => h.press(KeyCode::Up),
+                FishingState::Landed { depth, .. } if frames % STEP == 0 => {
+                    h.press(KeyCode::Down);
+                    reeling = matches!(h.app().game().fishing_state, FishingState::Landed { depth: deeper, .. } if deeper == depth);
+                }
+                FishingState::Idle => {
+                    cast(h);
+                    reeling = false;
+                }
+                _ => {}
+            }
+            h.events().iter().any(|event| matches!(event, GameEvent::FishCaught { autopilot: false, .. }))
+        });
+        assert!(caught, "nothing was landed:\n{}", h.screen());
+        assert_eq!(h.app().game().profile.journal.log.len(), 1);
+        h.wait(FRAME_TIME);
+        assert!(h.screen().contains("1 caught"), "{}", h.screen());
+    }
+
+    #[test]
+    fn signals_raise_a_banner() {
+        let mut h = harness();
+        h.signal("SUCCESS:deployed to staging");
+        h.wait(FRAME_TIME);
+        assert!(h.events().contains(&GameEvent::SignalReceived { success: true, message: "deployed to staging".to_string() }));
+        // The banner goes up once the fireworks have burst
+        assert!(!h.screen().contains("deployed to staging"));
+        assert!(h.until(Duration::from_secs(5), |h| h.screen().contains("deployed to staging")), "{}", h.screen());
+
+        // With signals coming from the harness, the test keys are left alone
+        h.clear_events();
+        h.type_keys("f");
+        h.wait(FRAME_TIME);
+        assert!(h.events().is_empty());
+        h.signal("FAILURE:tests failed");
+        h.wait(FRAME_TIME);
+        assert_eq!(h.events(), [GameEvent::SignalReceived { success: false, message: "tests failed".to_string() }]);
+    }
+}
@@ -0,0 +1,101 @@
+//! Off-screen rendering for snapshot tests and CI: runs an [`App`] for a number of
+//! simulated frames and returns each as plain text, one string per frame. Also turns
+//! a drawn buffer into text for screenshots.
+
+use std::time::Duration;
+
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+
+use crate::app::App;
+use crate::cast;
+
+/// Simulated time between frames, the same as the interactive loop's poll interval.
+pub const FRAME_TIME: Duration = Duration::from_millis(50);
+
+/// Advance `app` by [`FRAME_TIME`] and draw it, `frames` times, into a screen the
+/// size the app was created for.
+pub fn render_frames(app: &mut App, frames: usize) -> Vec<String> {
+    let size = app.size();
+    let Ok(mut terminal) = Terminal::new(TestBackend::new(size.width, size.height));
+    (0..frames)
+        .map(|_| {
+            app.update(FRAME_TIME);
+            let Ok(frame) = terminal.draw(|f| app.draw(f));
+            buffer_text(frame.buffer)
+        })
+        .collect()
+}
+
+/// The symbols of `buffer` row by row, without styling.
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::with_capacity((area.width as usize + 1) * area.height as usize);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            text.push_str(buffer[(x, y)].symbol());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// The symbols of `buffer` row by row in their colours, as ANSI escapes that show it
+/// again when the text is printed to a terminal.
+pub fn buffer_ansi(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut style = None;
+        for x in area.left()..area.right() {
+            let cell = &buffer[(x, y)];
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                text.push_str(&cast::sgr(cell));
+                style = Some(cell_style);
+            }
+            text.push_str(cell.symbol());
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{Assets, Config};
+    use ratatui::layout::Rect;
+
+    fn frames(seed: u64) -> Vec<String> {
+        let config = Config { seed: Some(seed), ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 80, 24));
+        render_frames(&mut app, 3)
+    }
+
+    #[test]
+    fn frames_are_reproducible() {
+        let first = frames(7);
+        assert_eq!(first.len(), 3);
+        assert_eq!(first, frames(7));
+    }
+
+    #[test]
+    fn frame_shows_title_dock_and_water() {
+        let frame = &frames(7)[0];
+        assert_eq!(frame.lines().count(), 24);
+        assert!(frame.lines().all(|line| line.chars().count() == 80));
+        assert!(frame.starts_with("┌Fisherman"));
+        assert!(frame.contains("═╦═"));
+        assert!(frame.contains("~~~"));
+    }
+
+    #[test]
+    fn ansi_text_keeps_the_colours() {
+        use ratatui::style::{Color, Style};
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer.set_string(1, 0, "><>", Style::default().fg(Color::Yellow));
+        assert_eq!(buffer_ansi(&buffer), "\x1b[0m \x1b[0;33m><>\x1b[0m\n\x1b[0m    \x1b[0m\n");
+    }
+}
@@ -0,0 +1,54 @@
+//! The `?` overlay: the controls as currently bound and where signals come from.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use crate::keymap::{self, Action, Keymap};
+
+/// Centered panel; draws over whatever is beneath it.
+pub struct HelpPanel<'a> {
+    pub keys: &'a Keymap,
+    /// The signal sources in use, e.g. `stdin` or `socket /tmp/fisherman.sock`.
+    pub sources: &'a [String],
+    /// List the second player's keys too (`--players 2`).
+    pub two_players: bool,
+}
+
+impl Widget for HelpPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let heading = Style::default().fg(Color::Yellow);
+        let mut lines = vec![Line::from(Span::styled("Controls", heading))];
+        for action in Action::ALL.into_iter().filter(|a| self.two_players || !a.second_player()) {
+            let keys: Vec<String> = self.keys.keys(action).map(keymap::key_name).collect();
+            let keys = if keys.is_empty() { "-".to_string() } else { keys.join(" / ") };
+            lines.push(Line::from(format!(" {:<14} {}", keys, action.description())));
+        }
+        lines.push(Line::from(format!(" {:<14} {}", "Ctrl+C", "Quit")));
+        lines.push(Line::from(" Mouse          Click the water to cast, scroll to move the hook"));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Signal sources", heading)));
+        if self.sources.is_empty() {
+            lines.push(Line::from(" none (s / f fake a success / failure)"));
+        }
+        for source in self.sources {
+            lines.push(Line::from(format!(" {}", source)));
+        }
+
+        let width = 64.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let rect = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        Clear.render(rect, buf);
+        Paragraph::new(lines)
+            .block(Block::default().title("Help").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White))
+            .render(rect, buf);
+    }
+}
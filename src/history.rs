@@ -0,0 +1,145 @@
+//! Every signal received this session, with the session time it came in at, and the
+//! `h` panel that lists them newest first. Only the latest outcome shows anywhere
+//! else, so this is where the earlier steps of a pipeline can be looked back on.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use crate::signals::SignalMessage;
+
+/// Signals kept before the oldest are forgotten.
+const CAPACITY: usize = 200;
+
+/// A signal and when it arrived.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// Session time, as on the stats panel.
+    pub at: Duration,
+    pub message: SignalMessage,
+}
+
+/// The signals received so far, oldest first.
+#[derive(Debug, Default)]
+pub struct SignalHistory {
+    entries: VecDeque<HistoryEntry>,
+}
+
+impl SignalHistory {
+    /// Keep `message`, received at session time `at`. Heartbeats only say the task is
+    /// alive and would crowd out everything else, so they aren't kept.
+    pub fn record(&mut self, at: Duration, message: &SignalMessage) {
+        if *message == SignalMessage::Heartbeat {
+            return;
+        }
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(HistoryEntry { at, message: message.clone() });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entries, newest first.
+    pub fn newest_first(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev()
+    }
+}
+
+/// What a message is shown as, and in what colour: green for a success, red for a
+/// failure, cyan for progress.
+fn describe(message: &SignalMessage) -> (String, Color) {
+    match message {
+        SignalMessage::Success(msg) => (format!("SUCCESS {}", msg), Color::Green),
+        SignalMessage::Failure(msg) => (format!("FAILURE {}", msg), Color::Red),
+        SignalMessage::Progress { percent, label } => (format!("{}% {}", percent, label), Color::Cyan),
+        SignalMessage::Task { name, message } => {
+            let (text, color) = describe(message);
+            (format!("{}: {}", name, text), color)
+        }
+        other => (other.to_line(), Color::White),
+    }
+}
+
+/// The history, newest first, in a box centred over the scene. `scroll` is how many
+/// of the newest entries are scrolled past.
+pub struct HistoryPanel<'a> {
+    pub history: &'a SignalHistory,
+    pub scroll: usize,
+}
+
+impl Widget for HistoryPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = 64.min(area.width);
+        let height = 16.min(area.height);
+        let rect = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        let rows = height.saturating_sub(2) as usize;
+        let scroll = self.scroll.min(self.history.len().saturating_sub(1));
+        let lines: Vec<Line> = if self.history.is_empty() {
+            vec![Line::from(Span::styled("No signals yet", Style::default().fg(Color::DarkGray)))]
+        } else {
+            self.history
+                .newest_first()
+                .skip(scroll)
+                .take(rows)
+                .map(|entry| {
+                    let secs = entry.at.as_secs();
+                    let (text, color) = describe(&entry.message);
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}:{:02}:{:02}  ", secs / 3600, (secs / 60) % 60, secs % 60),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled(text, Style::default().fg(color)),
+                    ])
+                })
+                .collect()
+        };
+        let title = format!("Signals ({}) ↑↓ scroll", self.history.len());
+        Clear.render(rect, buf);
+        Paragraph::new(lines)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .style(Style::default().fg(Color::White))
+            .render(rect, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_keeps_the_latest_signals_and_lists_them_newest_first() {
+        let mut history = SignalHistory::default();
+        history.record(Duration::from_secs(1), &SignalMessage::Heartbeat);
+        for step in 0..CAPACITY + 3 {
+            history.record(Duration::from_secs(step as u64), &SignalMessage::Banner(format!("step {}", step)));
+        }
+        history.record(Duration::from_secs(3725), &SignalMessage::Failure("tests".to_string()));
+        assert_eq!(history.len(), CAPACITY);
+        assert_eq!(history.newest_first().last().unwrap().message, SignalMessage::Banner("step 4".to_string()));
+
+        let area = Rect::new(0, 0, 64, 16);
+        let mut buf = Buffer::empty(area);
+        HistoryPanel { history: &history, scroll: 0 }.render(area, &mut buf);
+        let row: String = (1..40).map(|x| buf[(x, 1)].symbol().to_string()).collect();
+        assert!(row.starts_with("1:02:05  FAILURE tests"), "{}", row);
+        assert_eq!(buf[(10, 1)].fg, Color::Red);
+    }
+}
@@ -0,0 +1,144 @@
+//! `[hooks]` in the config file: shell commands run when something happens in the
+//! game, to play a sound on every catch or flash the lights when a build fails. Each
+//! runs in the background with `FISHERMAN_*` environment variables describing the
+//! event, and its output is discarded.
+//!
+//! ```toml
+//! [hooks]
+//! on_catch = "paplay ~/sounds/splash.ogg"
+//! on_failure_signal = ["notify-send \"$FISHERMAN_MESSAGE\"", "lights red"]
+//! ```
+
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::command_watch;
+use crate::config_file::Table;
+use crate::events::GameEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// A fish was landed: `FISHERMAN_SPECIES`, `FISHERMAN_SIZE` (cm) and
+    /// `FISHERMAN_SHINY`.
+    Catch,
+    /// A SUCCESS signal arrived: `FISHERMAN_MESSAGE`.
+    SuccessSignal,
+    /// A FAILURE signal arrived: `FISHERMAN_MESSAGE`.
+    FailureSignal,
+}
+
+impl HookEvent {
+    pub const ALL: [HookEvent; 3] = [HookEvent::Catch, HookEvent::SuccessSignal, HookEvent::FailureSignal];
+
+    /// The event's key in `[hooks]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::Catch => "on_catch",
+            HookEvent::SuccessSignal => "on_success_signal",
+            HookEvent::FailureSignal => "on_failure_signal",
+        }
+    }
+
+    /// `FISHERMAN_EVENT` for the event.
+    fn label(self) -> &'static str {
+        match self {
+            HookEvent::Catch => "catch",
+            HookEvent::SuccessSignal => "success",
+            HookEvent::FailureSignal => "failure",
+        }
+    }
+}
+
+/// The commands to run for each event.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hooks {
+    commands: Vec<(HookEvent, String)>,
+}
+
+impl Hooks {
+    /// Read the `[hooks]` section, where each event takes a command or a list of them.
+    pub fn from_table(table: &Table) -> Result<Hooks, String> {
+        if let Some(name) = table.keys().find(|name| !HookEvent::ALL.iter().any(|e| e.name() == name.as_str())) {
+            return Err(format!("unknown event '{}' in [hooks]", name));
+        }
+        let mut commands = Vec::new();
+        for event in HookEvent::ALL {
+            if let Some(value) = table.get(event.name()) {
+                let lines = value
+                    .as_strings()
+                    .ok_or_else(|| format!("[hooks] {} must be a command or a list of them", event.name()))?;
+                commands.extend(lines.into_iter().map(|line| (event, line.to_string())));
+            }
+        }
+        Ok(Hooks { commands })
+    }
+
+    /// Start the commands for `event` with `vars` (names without the `FISHERMAN_`
+    /// prefix) in their environment, without waiting for them.
+    pub fn run(&self, event: HookEvent, vars: &[(&str, String)]) {
+        for (_, line) in self.commands.iter().filter(|(e, _)| *e == event) {
+            if let Ok(mut child) = command(line, event, vars).spawn() {
+                // Reap it once it's done so it doesn't linger as a zombie
+                thread::spawn(move || child.wait());
+            }
+        }
+    }
+
+    /// Run the commands for `event`, as a subscriber to the event bus.
+    pub fn notify(&self, event: &GameEvent) {
+        match event {
+            GameEvent::FishCaught { fish, autopilot: false, .. } => {
+                let vars = [
+                    ("SPECIES", fish.species_name.clone()),
+                    ("SIZE", format!("{:.1}", fish.size)),
+                    ("SHINY", fish.shiny.to_string()),
+                ];
+                self.run(HookEvent::Catch, &vars);
+            }
+            GameEvent::SignalReceived { success, message } => {
+                let event = if *success { HookEvent::SuccessSignal } else { HookEvent::FailureSignal };
+                self.run(event, &[("MESSAGE", message.clone())]);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn command(line: &str, event: HookEvent, vars: &[(&str, String)]) -> Command {
+    let mut command = command_watch::shell(line);
+    command
+        .env("FISHERMAN_EVENT", event.label())
+        .envs(vars.iter().map(|(name, value)| (format!("FISHERMAN_{}", name), value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn hooks_come_from_the_config_with_the_event_in_their_environment() {
+        let config = ConfigFile::parse("[hooks]\non_catch = \"play splash\"\non_failure_signal = [\"a\", \"b\"]").unwrap();
+        let hooks = Hooks::from_table(config.section("hooks").unwrap()).unwrap();
+        assert_eq!(
+            hooks.commands,
+            [
+                (HookEvent::Catch, "play splash".to_string()),
+                (HookEvent::FailureSignal, "a".to_string()),
+                (HookEvent::FailureSignal, "b".to_string()),
+            ]
+        );
+        let typo = ConfigFile::parse("[hooks]\non_cach = \"x\"").unwrap();
+        assert_eq!(Hooks::from_table(typo.section("hooks").unwrap()), Err("unknown event 'on_cach' in [hooks]".to_string()));
+
+        let command = command("play splash", HookEvent::Catch, &[("SPECIES", "Shark".to_string())]);
+        let envs: Vec<_> = command.get_envs().collect();
+        assert!(envs.contains(&(OsStr::new("FISHERMAN_EVENT"), Some(OsStr::new("catch")))));
+        assert!(envs.contains(&(OsStr::new("FISHERMAN_SPECIES"), Some(OsStr::new("Shark")))));
+    }
+}
@@ -0,0 +1,141 @@
+//! The `fisherman import-sprite` subcommand: turn a small PNG into a sprite CSV, so
+//! art can be drawn in an image editor instead of typed in cell by cell.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::png::{self, Image};
+
+const USAGE: &str = "\
+usage: fisherman import-sprite IMAGE.png --out DIR [--width COLUMNS]
+
+Writes DIR/IMAGE.csv with one cell per block of pixels. Cells are twice as tall as
+they are wide, so an image W pixels across becomes about W columns and H/2 rows.
+Transparent pixels are left empty. COLUMNS defaults to the image width, at most 40.";
+
+const EXIT_USAGE: i32 = 2;
+const EXIT_FAILED: i32 = 1;
+const DEFAULT_MAX_COLUMNS: u32 = 40;
+/// Shade characters from faintest to solid, picked by luminance.
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+/// Cells with less than this share of opaque pixels stay empty.
+const MIN_COVERAGE: f32 = 0.5;
+
+pub fn run(args: &[String]) -> i32 {
+    let mut image_path = None;
+    let mut out_dir = None;
+    let mut columns = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out_dir = args.next().map(PathBuf::from),
+            "--width" => match args.next().and_then(|w| w.parse::<u32>().ok()).filter(|w| *w > 0) {
+                Some(w) => columns = Some(w),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            _ if image_path.is_none() && !arg.starts_with("--") => image_path = Some(PathBuf::from(arg)),
+            _ => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+        }
+    }
+    let (Some(image_path), Some(out_dir)) = (image_path, out_dir) else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+
+    match import(&image_path, &out_dir, columns) {
+        Ok(written) => {
+            println!("Wrote {}", written.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("fisherman import-sprite: {}", e);
+            EXIT_FAILED
+        }
+    }
+}
+
+/// Convert the PNG at `image_path`, returning the path of the CSV written.
+fn import(image_path: &Path, out_dir: &Path, columns: Option<u32>) -> Result<PathBuf, String> {
+    let data = fs::read(image_path).map_err(|e| format!("{}: {}", image_path.display(), e))?;
+    let image = png::decode(&data).map_err(|e| format!("{}: {}", image_path.display(), e))?;
+    let columns = columns.unwrap_or(image.width.min(DEFAULT_MAX_COLUMNS));
+    let csv = to_csv(&image, columns);
+
+    let stem = image_path.file_stem().and_then(|s| s.to_str()).unwrap_or("sprite");
+    fs::create_dir_all(out_dir).map_err(|e| format!("{}: {}", out_dir.display(), e))?;
+    let out_path = out_dir.join(format!("{}.csv", stem));
+    fs::write(&out_path, csv).map_err(|e| format!("{}: {}", out_path.display(), e))?;
+    Ok(out_path)
+}
+
+/// Downsample `image` to `columns` cells across, in the `X,Y,ASCII,Foreground,Background`
+/// format the sprite loader reads.
+fn to_csv(image: &Image, columns: u32) -> String {
+    let cell_width = image.width as f32 / columns as f32;
+    let cell_height = cell_width * 2.0;
+    let rows = (image.height as f32 / cell_height).ceil().max(1.0) as u32;
+
+    let mut out = String::from("X,Y,ASCII,Foreground,Background\n");
+    for y in 0..rows {
+        for x in 0..columns {
+            let x0 = (x as f32 * cell_width) as u32;
+            let x1 = (((x + 1) as f32 * cell_width) as u32).clamp(x0 + 1, image.width);
+            let y0 = (y as f32 * cell_height) as u32;
+            let y1 = (((y + 1) as f32 * cell_height) as u32).clamp(y0 + 1, image.height);
+            if let Some((ch, [r, g, b])) = cell(image, x0..x1, y0..y1) {
+                out.push_str(&format!("{},{},{},#{:02X}{:02X}{:02X},\n", x, y, ch, r, g, b));
+            }
+        }
+    }
+    out
+}
+
+/// The character and colour for one block of pixels, or `None` if it's mostly transparent.
+fn cell(image: &Image, xs: std::ops::Range<u32>, ys: std::ops::Range<u32>) -> Option<(char, [u8; 3])> {
+    let mut total = 0u32;
+    let mut alpha = 0u32;
+    let mut sum = [0u32; 3];
+    for y in ys {
+        for x in xs.clone() {
+            let [r, g, b, a] = image.pixel(x, y);
+            total += 1;
+            alpha += a as u32;
+            for (s, c) in sum.iter_mut().zip([r, g, b]) {
+                *s += c as u32 * a as u32;
+            }
+        }
+    }
+    if total == 0 || (alpha as f32 / (total * 255) as f32) < MIN_COVERAGE {
+        return None;
+    }
+    let rgb = sum.map(|s| (s / alpha) as u8);
+    let luminance = (0.2126 * rgb[0] as f32 + 0.7152 * rgb[1] as f32 + 0.0722 * rgb[2] as f32) / 255.0;
+    let shade = ((luminance * SHADES.len() as f32) as usize).min(SHADES.len() - 1);
+    Some((SHADES[shade], rgb))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsamples_into_shaded_cells() {
+        // 2x4: a white top half over a dark-red bottom-left and a transparent bottom-right
+        let mut pixels = vec![[255, 255, 255, 255]; 4];
+        for _ in 0..2 {
+            pixels.extend([[80, 0, 0, 255], [0, 0, 0, 0]]);
+        }
+        let image = Image { width: 2, height: 4, pixels };
+        let csv = to_csv(&image, 2);
+        assert_eq!(
+            csv,
+            "X,Y,ASCII,Foreground,Background\n0,0,█,#FFFFFF,\n1,0,█,#FFFFFF,\n0,1,░,#500000,\n"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Jellyfish drifting through the water, pulsing up and sinking back down. They are a
+//! hazard rather than a catch: reeling the hook into one snaps the line. A few swim at
+//! a time, each new one arriving on its own timer once another has drifted away.
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::StatefulWidget;
+
+use crate::theme::Theme;
+
+/// Seconds between jellyfish arriving, picked at random from this range.
+const SPAWN_INTERVAL: (f32, f32) = (20.0, 60.0);
+const MAX_JELLIES: usize = 2;
+/// Columns per second they drift sideways.
+const DRIFT_SPEED: (f32, f32) = (0.3, 1.0);
+/// Rows per second a pulse lifts them, and they sink between pulses.
+const PULSE_RISE: f32 = 3.0;
+const SINK: f32 = 0.6;
+/// Seconds from one pulse to the next, and how much of that the pulse lasts.
+const PULSE_PERIOD: f32 = 3.0;
+const PULSE_LENGTH: f32 = 0.6;
+/// The bell over two frames of trailing tentacles.
+const BELL: &str = "(~)";
+const TENTACLES: [&str; 2] = [")|(", "(|)"];
+const WIDTH: u16 = 3;
+const HEIGHT: u16 = 2;
+
+/// One jellyfish, with its top-left corner `x` columns and `y` rows into the water.
+#[derive(Debug, Clone)]
+struct Jelly {
+    x: f32,
+    y: f32,
+    vx: f32,
+    /// Offset into the pulse cycle, so jellyfish don't pulse in step.
+    phase: f32,
+}
+
+impl Jelly {
+    fn pulsing(&self, elapsed: Duration) -> bool {
+        (elapsed.as_secs_f32() + self.phase).rem_euclid(PULSE_PERIOD) < PULSE_LENGTH
+    }
+}
+
+/// The jellyfish in the water and when the next one is due.
+#[derive(Debug, Clone)]
+pub struct Jellyfish {
+    rng: StdRng,
+    jellies: Vec<Jelly>,
+    until_spawn: Duration,
+    elapsed: Duration,
+}
+
+impl Jellyfish {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let until_spawn = interval(&mut rng);
+        Jellyfish { rng, jellies: Vec::new(), until_spawn, elapsed: Duration::ZERO }
+    }
+
+    /// Drift and bob the jellyfish by `dt` through water of `area`'s size, and send in a
+    /// new one when the timer runs out. They rise on each pulse, sink in between and
+    /// stay within the water, leaving once they drift off the side.
+    pub fn tick(&mut self, dt: Duration, area: Rect) {
+        self.elapsed += dt;
+        let secs = dt.as_secs_f32();
+        let floor = area.height.saturating_sub(HEIGHT) as f32;
+        for jelly in &mut self.jellies {
+            let vy = if jelly.pulsing(self.elapsed) { -PULSE_RISE } else { SINK };
+            jelly.x += jelly.vx * secs;
+            jelly.y = (jelly.y + vy * secs).clamp(0.0, floor);
+        }
+        let width = area.width as f32;
+        self.jellies.retain(|j| j.x > -(WIDTH as f32) - 1.0 && j.x < width + 1.0);
+
+        self.until_spawn = self.until_spawn.saturating_sub(dt);
+        if self.until_spawn.is_zero() {
+            if self.jellies.len() < MAX_JELLIES && area.height > HEIGHT {
+                self.spawn(area);
+            }
+            self.until_spawn = interval(&mut self.rng);
+        }
+    }
+
+    fn spawn(&mut self, area: Rect) {
+        let speed = self.rng.gen_range(DRIFT_SPEED.0..DRIFT_SPEED.1);
+        let rightward = self.rng.gen_bool(0.5);
+        self.jellies.push(Jelly {
+            x: if rightward { -(WIDTH as f32) } else { area.width as f32 },
+            y: self.rng.gen_range(0.0..(area.height - HEIGHT) as f32),
+            vx: if rightward { speed } else { -speed },
+            phase: self.rng.gen_range(0.0..PULSE_PERIOD),
+        });
+    }
+
+    /// Whether the screen cell `(x, y)` falls on a jellyfish in water drawn in `area`.
+    pub fn touches(&self, area: Rect, x: u16, y: u16) -> bool {
+        let (x, y) = (x as f32 - area.x as f32, y as f32 - area.y as f32);
+        self.jellies.iter().any(|j| {
+            let (left, top) = (j.x.round(), j.y.round());
+            x >= left && x < left + WIDTH as f32 && y >= top && y < top + HEIGHT as f32
+        })
+    }
+
+    /// Whether any jellyfish are in the water.
+    pub fn is_empty(&self) -> bool {
+        self.jellies.is_empty()
+    }
+}
+
+fn interval(rng: &mut StdRng) -> Duration {
+    Duration::from_secs_f32(rng.gen_range(SPAWN_INTERVAL.0..SPAWN_INTERVAL.1))
+}
+
+/// Draws the [`Jellyfish`] into the water. Only symbols and foregrounds are set, so
+/// the water shows through.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JellyfishLayer {
+    pub theme: Theme,
+}
+
+impl StatefulWidget for JellyfishLayer {
+    type State = Jellyfish;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Jellyfish) {
+        for jelly in &state.jellies {
+            // The tentacles trail the other way while the bell pushes up
+            let tentacles = TENTACLES[usize::from(jelly.pulsing(state.elapsed))];
+            let left = area.x as i32 + jelly.x.round() as i32;
+            let top = area.y as i32 + jelly.y.round() as i32;
+            for (row, line) in [BELL, tentacles].into_iter().enumerate() {
+                for (i, ch) in line.chars().enumerate() {
+                    put(buf, area, left + i as i32, top + row as i32, ch, self.theme.jellyfish);
+                }
+            }
+        }
+    }
+}
+
+fn put(buf: &mut Buffer, area: Rect, x: i32, y: i32, ch: char, color: Color) {
+    if x < area.x as i32 || x >= area.right() as i32 || y < area.y as i32 || y >= area.bottom() as i32 {
+        return;
+    }
+    buf[(x as u16, y as u16)].set_char(ch).set_style(Style::default().fg(color));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jellyfish_bob_within_the_water_and_sting() {
+        let area = Rect::new(0, 10, 30, 12);
+        let mut jellyfish = Jellyfish::new(4);
+        jellyfish.until_spawn = Duration::from_secs(1);
+        jellyfish.tick(Duration::from_secs(1), area);
+        assert!(!jellyfish.is_empty());
+
+        // Drifting in from off screen, it is in view and in the way a few seconds later
+        let mut rows = Vec::new();
+        for _ in 0..80 {
+            jellyfish.tick(Duration::from_millis(100), area);
+            rows.push(jellyfish.jellies[0].y);
+        }
+        assert!(rows.iter().all(|&y| (0.0..=10.0).contains(&y)));
+        assert!(rows.windows(2).any(|w| w[1] < w[0]) && rows.windows(2).any(|w| w[1] > w[0]));
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30, 22));
+        JellyfishLayer::default().render(area, &mut buf, &mut jellyfish);
+        let bell = area.positions().find(|&p| buf[p].symbol() == "~").unwrap();
+        assert!(jellyfish.touches(area, bell.x, bell.y));
+        assert!(jellyfish.touches(area, bell.x, bell.y + 1));
+        assert!(!jellyfish.touches(area, bell.x, bell.y + 2));
+    }
+}
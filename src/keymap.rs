@@ -0,0 +1,307 @@
+//! Keys for the game's actions, remappable from the `[keys]` section of the config
+//! file:
+//!
+//! ```toml
+//! [keys]
+//! reel_up = ["k", "Up"]
+//! reel_down = ["j", "Down"]
+//! ```
+//!
+//! An action listed there gets exactly the keys given; the others keep their defaults.
+
+use crossterm::event::KeyCode;
+
+use crate::config_file::Table;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Cast,
+    ReelUp,
+    ReelDown,
+    Bait,
+    Shop,
+    Journal,
+    Stats,
+    Pause,
+    Help,
+    Screenshot,
+    Quit,
+    Aquarium,
+    /// Every species, with what's known of it.
+    Encyclopedia,
+    History,
+    Underwater,
+    /// The debug console, for commands like `spawn shark 3`.
+    Console,
+    /// Take up the rod in hand or one of those left in rod holders.
+    Rod1,
+    Rod2,
+    Rod3,
+    /// The second player's rod in a hot-seat game (`--players 2`).
+    P2Cast,
+    P2ReelUp,
+    P2ReelDown,
+}
+
+impl Action {
+    pub const ALL: [Action; 22] = [
+        Action::Cast,
+        Action::ReelUp,
+        Action::ReelDown,
+        Action::Bait,
+        Action::Shop,
+        Action::Journal,
+        Action::Stats,
+        Action::Pause,
+        Action::Help,
+        Action::Screenshot,
+        Action::Quit,
+        Action::Aquarium,
+        Action::Encyclopedia,
+        Action::History,
+        Action::Underwater,
+        Action::Console,
+        Action::Rod1,
+        Action::Rod2,
+        Action::Rod3,
+        Action::P2Cast,
+        Action::P2ReelUp,
+        Action::P2ReelDown,
+    ];
+
+    /// Key of this action in the `[keys]` section.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Cast => "cast",
+            Action::ReelUp => "reel_up",
+            Action::ReelDown => "reel_down",
+            Action::Bait => "bait",
+            Action::Shop => "shop",
+            Action::Journal => "journal",
+            Action::Stats => "stats",
+            Action::Pause => "pause",
+            Action::Help => "help",
+            Action::Screenshot => "screenshot",
+            Action::Quit => "quit",
+            Action::Aquarium => "aquarium",
+            Action::Encyclopedia => "encyclopedia",
+            Action::History => "history",
+            Action::Underwater => "underwater",
+            Action::Console => "console",
+            Action::Rod1 => "rod_1",
+            Action::Rod2 => "rod_2",
+            Action::Rod3 => "rod_3",
+            Action::P2Cast => "p2_cast",
+            Action::P2ReelUp => "p2_reel_up",
+            Action::P2ReelDown => "p2_reel_down",
+        }
+    }
+
+    /// What the action does, for the help overlay.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Cast => "Charge and cast, set the hook",
+            Action::ReelUp => "Raise the hook",
+            Action::ReelDown => "Lower the hook",
+            Action::Bait => "Change bait",
+            Action::Shop => "Tackle shop",
+            Action::Journal => "Achievements",
+            Action::Stats => "Session stats",
+            Action::Pause => "Pause / resume",
+            Action::Help => "This help",
+            Action::Screenshot => "Save a screenshot",
+            Action::Quit => "Quit",
+            Action::Aquarium => "Aquarium of your catches",
+            Action::Encyclopedia => "Species encyclopedia",
+            Action::History => "Signals received so far",
+            Action::Underwater => "Underwater view",
+            Action::Console => "Debug console",
+            Action::Rod1 => "Take up rod 1",
+            Action::Rod2 => "Take up rod 2 (rod holder)",
+            Action::Rod3 => "Take up rod 3 (rod holder)",
+            Action::P2Cast => "Player 2: cast, set the hook",
+            Action::P2ReelUp => "Player 2: raise the hook",
+            Action::P2ReelDown => "Player 2: lower the hook",
+        }
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Action::Cast => vec![KeyCode::Char(' ')],
+            Action::ReelUp => vec![KeyCode::Up],
+            Action::ReelDown => vec![KeyCode::Down],
+            Action::Bait => vec![KeyCode::Char('b')],
+            Action::Shop => vec![KeyCode::Char('t')],
+            Action::Journal => vec![KeyCode::Char('g')],
+            Action::Stats => vec![KeyCode::Tab],
+            Action::Pause => vec![KeyCode::Char('p')],
+            Action::Help => vec![KeyCode::Char('?')],
+            Action::Screenshot => vec![KeyCode::F(12)],
+            Action::Quit => vec![KeyCode::Char('q')],
+            Action::Aquarium => vec![KeyCode::Char('a')],
+            Action::Encyclopedia => vec![KeyCode::Char('e')],
+            Action::History => vec![KeyCode::Char('h')],
+            Action::Underwater => vec![KeyCode::Char('u')],
+            Action::Console => vec![KeyCode::Char(':')],
+            Action::Rod1 => vec![KeyCode::Char('1')],
+            Action::Rod2 => vec![KeyCode::Char('2')],
+            Action::Rod3 => vec![KeyCode::Char('3')],
+            Action::P2Cast => vec![KeyCode::Char('x')],
+            Action::P2ReelUp => vec![KeyCode::Char('w')],
+            Action::P2ReelDown => vec![KeyCode::Char('z')],
+        }
+    }
+
+    /// Whether the action belongs to the second player's rod.
+    pub fn second_player(&self) -> bool {
+        matches!(self, Action::P2Cast | Action::P2ReelUp | Action::P2ReelDown)
+    }
+
+    /// Which rod a take-up action picks, counting from 0 for the first.
+    pub fn rod(&self) -> Option<usize> {
+        match self {
+            Action::Rod1 => Some(0),
+            Action::Rod2 => Some(1),
+            Action::Rod3 => Some(2),
+            _ => None,
+        }
+    }
+
+    /// The first player's action the second player's stands for.
+    pub fn first_player(&self) -> Action {
+        match self {
+            Action::P2Cast => Action::Cast,
+            Action::P2ReelUp => Action::ReelUp,
+            Action::P2ReelDown => Action::ReelDown,
+            other => *other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(KeyCode, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            bindings: Action::ALL
+                .into_iter()
+                .flat_map(|action| action.default_keys().into_iter().map(move |key| (key, action)))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from a `[keys]` section, refusing unknown actions and keys
+    /// bound to two actions.
+    pub fn from_table(table: &Table) -> Result<Keymap, String> {
+        if let Some(name) = table.keys().find(|name| !Action::ALL.iter().any(|a| a.name() == name.as_str())) {
+            return Err(format!("unknown action '{}' in [keys]", name));
+        }
+        let mut bindings: Vec<(KeyCode, Action)> = Vec::new();
+        for action in Action::ALL {
+            let keys = match table.get(action.name()) {
+                Some(value) => value
+                    .as_strings()
+                    .ok_or_else(|| format!("[keys] {} must be a key name or a list of them", action.name()))?
+                    .into_iter()
+                    .map(parse_key)
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => action.default_keys(),
+            };
+            for key in keys {
+                if let Some((_, other)) = bindings.iter().find(|(k, a)| *k == key && *a != action) {
+                    return Err(format!(
+                        "key '{}' is bound to both {} and {}",
+                        key_name(key),
+                        other.name(),
+                        action.name()
+                    ));
+                }
+                bindings.push((key, action));
+            }
+        }
+        Ok(Keymap { bindings })
+    }
+
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.bindings.iter().find(|(k, _)| *k == key).map(|(_, action)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = KeyCode> + '_ {
+        self.bindings.iter().filter(move |(_, a)| *a == action).map(|(key, _)| *key)
+    }
+}
+
+/// A key by name: a single character, `Space`, `Tab`, `Enter`, `Esc`, `Backspace`,
+/// an arrow (`Up`, `Down`, `Left`, `Right`) or a function key (`F1` to `F12`).
+pub fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+    let key = match name.to_ascii_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "backspace" => KeyCode::Backspace,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        other => match other.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(n @ 1..=12) => KeyCode::F(n),
+            _ => return Err(format!("unknown key '{}'", name)),
+        },
+    };
+    Ok(key)
+}
+
+/// How a key is written in the config file and shown in the game.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+
+    fn keymap(text: &str) -> Result<Keymap, String> {
+        let config = ConfigFile::parse(text).unwrap();
+        Keymap::from_table(config.section("keys").unwrap())
+    }
+
+    #[test]
+    fn remapped_actions_replace_their_defaults() {
+        let keys = keymap("[keys]\nreel_up = [\"k\", \"Up\"]\nreel_down = \"j\"\n").unwrap();
+        assert_eq!(keys.action(KeyCode::Char('k')), Some(Action::ReelUp));
+        assert_eq!(keys.action(KeyCode::Up), Some(Action::ReelUp));
+        assert_eq!(keys.action(KeyCode::Char('j')), Some(Action::ReelDown));
+        assert_eq!(keys.action(KeyCode::Down), None);
+        assert_eq!(keys.action(KeyCode::Char('q')), Some(Action::Quit));
+    }
+
+    #[test]
+    fn rejects_conflicts_and_unknown_names() {
+        assert!(keymap("[keys]\nquit = \"a\"\n").unwrap_err().contains("bound to both"));
+        assert!(keymap("[keys]\nfly = \"x\"\n").unwrap_err().contains("unknown action"));
+        assert!(keymap("[keys]\ncast = \"Hyper\"\n").unwrap_err().contains("unknown key"));
+    }
+
+    #[test]
+    fn key_names_round_trip() {
+        for key in [KeyCode::Char(' '), KeyCode::Char('k'), KeyCode::Tab, KeyCode::Up, KeyCode::F(5), KeyCode::Esc] {
+            assert_eq!(parse_key(&key_name(key)), Ok(key));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! The layers the scene is drawn in, back to front. Each frame the app draws every
+//! layer in turn over the ones behind it, so what ends up in front of what is settled
+//! here instead of by the order of the drawing code: the line is always over the fish,
+//! and the panels and banners over all of the scene. A [`ScenePlugin`] picks the layer
+//! it's drawn at the end of.
+//!
+//! [`ScenePlugin`]: crate::plugin::ScenePlugin
+
+/// A layer of the scene, from the back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    /// The sky, its stars and the moon.
+    Sky,
+    /// The surface of the sea.
+    Ocean,
+    /// Birds and boats, over the sky and riding the surface.
+    Ambient,
+    /// Rain, snow, drifting petals and the windsock.
+    Weather,
+    /// The seabed and the jellyfish.
+    Underwater,
+    /// The fish.
+    Actors,
+    /// The fishing lines and any catch on the way in.
+    Line,
+    /// The docks, the rod holders, the shore and the crab.
+    Dock,
+    /// The fishermen and the task anglers.
+    Fisherman,
+    /// Splashes, fog, night and lantern light, and the shake of a squall.
+    Effects,
+    /// The border, the status bar, banners, panels and menus.
+    Ui,
+}
+
+impl Layer {
+    /// Every layer, back to front.
+    pub const ALL: [Layer; 11] = [
+        Layer::Sky,
+        Layer::Ocean,
+        Layer::Ambient,
+        Layer::Weather,
+        Layer::Underwater,
+        Layer::Actors,
+        Layer::Line,
+        Layer::Dock,
+        Layer::Fisherman,
+        Layer::Effects,
+        Layer::Ui,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_are_listed_back_to_front() {
+        assert!(Layer::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(Layer::Line > Layer::Actors && Layer::Ui == Layer::ALL[Layer::ALL.len() - 1]);
+    }
+}
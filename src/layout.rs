@@ -0,0 +1,271 @@
+//! Where everything goes on the screen: the sky, the sea and the fish under it, the
+//! dock and the fishermen on it, and the slots banners drop into. All worked out from
+//! the terminal's size in one place, so nothing else has to guard its own sums against
+//! a small screen. Below [`MIN_WIDTH`] by [`MIN_HEIGHT`] the scene is still laid out as
+//! if the screen were that big, so the game carries on underneath, but [`TooSmall`] is
+//! shown instead of it.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::Text;
+use ratatui::widgets::{Paragraph, Widget, Wrap};
+
+use crate::dock::{self, Anchor, DockSettings, Shore};
+use crate::fish;
+use crate::fishing_line::MIN_CAST_DISTANCE;
+use crate::tasks::ANGLER_WIDTH;
+
+const OCEAN_HEIGHT: u16 = 4;
+const OCEAN_DESIRED_TOP: u16 = 20;
+const DOCK_HEIGHT: u16 = 4;
+const FISH_AREA_OFFSET_FROM_OCEAN: u16 = 5;
+/// Rows the tide lifts the sea above, and drops it below, where it sits at half tide.
+pub const TIDE_RANGE: u16 = 2;
+/// Rows kept above the sea at half tide: the rod tip, three above the fisherman who sits
+/// four above the water, clear of the frame's top edge at high tide.
+const ABOVE_OCEAN: u16 = 1 + 3 + 4 + TIDE_RANGE;
+/// Rows kept below the top of the sea at half tide: down to the fish, a lane of them
+/// (the last lane two rows short), and the status bar and the frame's bottom edge,
+/// clear of a low tide.
+const BELOW_OCEAN: u16 = FISH_AREA_OFFSET_FROM_OCEAN + fish::FISH_HEIGHT - 2 + 2 + TIDE_RANGE;
+/// The smallest screen the scene fits on: the dock with the fisherman's rod and a short
+/// cast out past it, and the sky and a lane of fish around the sea.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = ABOVE_OCEAN + BELOW_OCEAN;
+/// Rows a banner box takes up, border and all.
+const BANNER_HEIGHT: u16 = 3;
+
+/// Compute the ocean area placement given the scene's size, raised `tide` rows
+fn compute_ocean_area(size: Rect, tide: i16) -> Rect {
+    let still = OCEAN_DESIRED_TOP.min(size.height - BELOW_OCEAN);
+    let top = (still as i16 - tide).max(1) as u16;
+    Rect::new(size.x + 1, top, size.width - 2, OCEAN_HEIGHT)
+}
+
+/// How many lanes of fish fit below an ocean at row `ocean_y`, with the tide out
+fn lane_count(size: Rect, ocean_y: u16) -> u16 {
+    size.height.saturating_sub(ocean_y + BELOW_OCEAN) / fish::FISH_HEIGHT + 1
+}
+
+/// Compute placement of a fish area `lanes` deep based on ocean position
+fn compute_fish_area(size: Rect, ocean_y: u16, lanes: u16) -> Rect {
+    let lane_height = fish::FISH_HEIGHT;
+    let desired_top = ocean_y.saturating_add(FISH_AREA_OFFSET_FROM_OCEAN);
+    let fish_area_height = lane_height.saturating_mul(lanes).saturating_sub(2);
+    let base_y = if desired_top.saturating_add(fish_area_height) <= size.height {
+        desired_top
+    } else if size.height > fish_area_height {
+        size.height.saturating_sub(fish_area_height)
+    } else {
+        0
+    };
+    Rect::new(size.x, base_y, size.width, fish_area_height)
+}
+
+/// Where the parts of the scene go on a screen of a given size and state of the tide.
+/// Worked out once per resize or turn of the tide rather than every frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// The screen it was laid out for, which may be smaller than the scene.
+    pub size: Rect,
+    pub ocean: Rect,
+    pub sky: Rect,
+    pub fish: Rect,
+    /// Everything below the surface rows, down to the sea floor.
+    pub water: Rect,
+    /// The water inside the frame's border, where the seabed lies.
+    pub seabed: Rect,
+    pub lanes: usize,
+    /// The first player's dock, on the side it's set to, and where they sit on it.
+    pub dock: Rect,
+    pub anchor: Anchor,
+    /// Where the task fishermen stand, further along the dock than the main one, from
+    /// their markers down to their legs; empty without tasks.
+    pub anglers: Rect,
+    /// The strip of shore at the land end of the dock, down to the sea floor; empty
+    /// without one.
+    pub shoreline: Rect,
+    /// The second player's dock on the far shore, mirroring the first player's; empty
+    /// with one player.
+    pub far_dock: Rect,
+    /// Rows the tide has lifted the sea.
+    pub tide: i16,
+}
+
+impl Layout {
+    /// The layout with the sea raised `tide` rows and room on the dock for `anglers`
+    /// task fishermen. The sea floor, and how many lanes of fish swim above it, stay
+    /// where they are at half tide; everything else rides up and down with the water.
+    pub fn with_tide(size: Rect, tide: i16, anglers: u16, settings: DockSettings) -> Layout {
+        let screen = size;
+        let size = Rect { width: size.width.max(MIN_WIDTH), height: size.height.max(MIN_HEIGHT), ..size };
+        let tide = tide.clamp(-(TIDE_RANGE as i16), TIDE_RANGE as i16);
+        let still = compute_ocean_area(size, 0);
+        let lanes = lane_count(size, still.y);
+        let ocean = compute_ocean_area(size, tide);
+        let fish = compute_fish_area(size, ocean.y, lanes);
+        let water_top = ocean.bottom().min(size.height);
+        let floor_top = still.bottom().min(size.height);
+        // Laid out on the right and turned round for a dock on the left. A long dock
+        // stops halfway across, a wide shore at a quarter
+        let shoreline = settings.shoreline.min(size.width / 4);
+        let width = settings.width.min((size.width / 2).max(dock::DEFAULT_WIDTH));
+        let seat = size.x.saturating_add(size.width.saturating_sub(width + shoreline));
+        // Each task's fisherman lengthens the dock out from its land end, as far as fits,
+        // standing clear of the main fisherman's rod
+        let room = anglers.saturating_mul(ANGLER_WIDTH).min(seat.saturating_sub(dock::ROD_REACH + 2) / ANGLER_WIDTH * ANGLER_WIDTH);
+        let anglers_x = if room > 0 { seat - dock::ROD_REACH - room } else { seat.saturating_sub(1) };
+        let anchor = Anchor { seat, top: ocean.y.saturating_sub(4), shore: Shore::Right };
+        let anglers_top = anchor.top.saturating_sub(4);
+        let dock = Rect::new(anglers_x, ocean.y.saturating_sub(2), width + (seat - 1 - anglers_x), DOCK_HEIGHT);
+        let shore_width = if shoreline > 0 { size.right().saturating_sub(dock.right()) } else { 0 };
+        let layout = Layout {
+            size,
+            ocean,
+            sky: Rect::new(0, 0, size.width, ocean.y),
+            fish,
+            water: Rect::new(0, water_top, size.width, size.height - water_top),
+            seabed: Rect::new(1, floor_top, size.width.saturating_sub(2), size.height.saturating_sub(floor_top + 1)),
+            lanes: lanes as usize,
+            dock,
+            anchor,
+            anglers: Rect::new(anglers_x, anglers_top, room, anchor.top + 3 - anglers_top),
+            shoreline: Rect::new(dock.right(), dock.y, shore_width, size.height.saturating_sub(dock.y)),
+            far_dock: Rect::default(),
+            tide,
+        };
+        let layout = match settings.side {
+            Shore::Right => layout,
+            Shore::Left => Layout {
+                dock: layout.mirrored(layout.dock),
+                anchor: layout.anchor.mirrored(size.width),
+                anglers: layout.mirrored(layout.anglers),
+                shoreline: layout.mirrored(layout.shoreline),
+                ..layout
+            },
+        };
+        Layout { size: screen, ..layout }
+    }
+
+    /// The layout with a second dock and fisherman on the far shore, facing the first.
+    pub fn with_far_shore(mut self) -> Layout {
+        let seat = self.anchor.seat;
+        let near_dock = match self.anchor.shore {
+            Shore::Right => Rect { x: seat - 1, width: self.dock.right() - (seat - 1), ..self.dock },
+            Shore::Left => Rect { width: seat + 2 - self.dock.x, ..self.dock },
+        };
+        self.far_dock = self.mirrored(near_dock);
+        self
+    }
+
+    /// Whether the scene fits on the screen; if not, [`TooSmall`] goes up instead.
+    pub fn fits(&self) -> bool {
+        self.size.width >= MIN_WIDTH && self.size.height >= MIN_HEIGHT
+    }
+
+    /// The width the scene is laid out across, which is never less than [`MIN_WIDTH`].
+    fn width(&self) -> u16 {
+        self.size.width.max(MIN_WIDTH)
+    }
+
+    /// Where the fisherman on `shore` sits.
+    pub fn anchor(&self, shore: Shore) -> Anchor {
+        if shore == self.anchor.shore { self.anchor } else { self.anchor.mirrored(self.width()) }
+    }
+
+    /// The column at the rod tip of the fisherman on `shore`.
+    pub fn rod_tip(&self, shore: Shore) -> u16 {
+        self.anchor(shore).rod_tip().0
+    }
+
+    pub fn fisherman_at(&self, shore: Shore) -> Rect {
+        self.anchor(shore).fisherman()
+    }
+
+    pub fn mirrored(&self, area: Rect) -> Rect {
+        Rect { x: self.width().saturating_sub(area.right()), ..area }
+    }
+
+    /// The nearest a line cast from `shore` can land to the fisherman: a short cast out,
+    /// and clear of the first player's planks however far they reach.
+    pub fn nearest_landing(&self, shore: Shore) -> u16 {
+        let anchor = self.anchor(shore);
+        let nearest = anchor.seaward(dock::ROD_REACH + MIN_CAST_DISTANCE);
+        match shore {
+            _ if shore != self.anchor.shore => nearest,
+            Shore::Right => nearest.min(self.dock.x.saturating_sub(2)),
+            Shore::Left => nearest.max(self.dock.right() + 1),
+        }
+    }
+
+    /// A banner box `width` columns wide, centred in the sky: slot 0 for SUCCESS and
+    /// FAILURE, a third of the way down, and each slot after it just below the last.
+    pub fn banner(&self, slot: u16, width: u16) -> Rect {
+        let width = width.min(self.size.width);
+        let x = (self.size.width - width) / 2;
+        let y = self.ocean.y / 3 + slot * BANNER_HEIGHT;
+        Rect::new(x, y, width, BANNER_HEIGHT).intersection(self.size)
+    }
+}
+
+/// What goes up instead of the scene when the screen is smaller than it fits on.
+pub struct TooSmall;
+
+impl Widget for TooSmall {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let message = format!(
+            "Terminal too small\n{}×{} (need {}×{})",
+            area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+        );
+        let text = Text::from(message);
+        let height = (text.height() as u16).min(area.height);
+        let top = area.y + (area.height - height) / 2;
+        Paragraph::new(text)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .render(Rect { y: top, height: area.bottom() - top, ..area }, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_screen_size_lays_out_inside_the_scene() {
+        for width in [0, 1, 10, MIN_WIDTH - 1, MIN_WIDTH, 80, 300] {
+            for height in [0, 1, 10, MIN_HEIGHT - 1, MIN_HEIGHT, 24, 40, 100] {
+                let size = Rect::new(0, 0, width, height);
+                let scene = Rect { width: width.max(MIN_WIDTH), height: height.max(MIN_HEIGHT), ..size };
+                for tide in [-(TIDE_RANGE as i16), 0, TIDE_RANGE as i16] {
+                    let side = if width % 20 == 0 { Shore::Left } else { Shore::Right };
+                    let layout = Layout::with_tide(size, tide, 3, DockSettings { side, ..DockSettings::default() }).with_far_shore();
+                    assert_eq!(layout.fits(), width >= MIN_WIDTH && height >= MIN_HEIGHT);
+                    for area in [layout.ocean, layout.sky, layout.fish, layout.water, layout.seabed, layout.dock, layout.far_dock] {
+                        assert_eq!(scene.intersection(area), area, "{:?} off a {}x{} screen", area, width, height);
+                    }
+                    // The rod clears the top edge and a lane of fish swims below the waves,
+                    // clear of the status bar
+                    assert!(layout.anchor.rod_tip().1 >= 1);
+                    assert!(layout.fish.y >= layout.ocean.bottom());
+                    assert!(layout.fish.bottom() <= scene.bottom() - 2);
+                }
+            }
+        }
+
+        let layout = Layout::with_tide(Rect::new(0, 0, 100, 40), 0, 0, DockSettings::default());
+        assert_eq!(layout.ocean.y, OCEAN_DESIRED_TOP);
+        assert_eq!(layout.banner(1, 30), Rect::new(35, 9, 30, 3));
+        assert_eq!(layout.banner(0, 300), Rect::new(0, 6, 100, 3));
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30, 9));
+        TooSmall.render(buf.area, &mut buf);
+        let row = |y: u16| (0..30).map(|x| buf[(x, y)].symbol().to_string()).collect::<String>();
+        assert_eq!(row(3).trim(), "Terminal too small");
+        assert_eq!(row(4).trim(), "30×9 (need 40×23)");
+        // Nothing to draw on at all
+        TooSmall.render(Rect::default(), &mut buf);
+    }
+}
@@ -0,0 +1,152 @@
+//! The high-score screens: the leaderboard of the best tournament rounds of each
+//! length, from the title menu or once a round's scoreboard has been seen, and the
+//! prompt for the name a finished round goes down under.
+
+use crossterm::event::KeyCode;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+
+use crate::menu;
+use crate::tournament::HighScores;
+
+/// The longest name the table keeps.
+pub const MAX_NAME: usize = 16;
+
+/// A name being typed in, starting from one offered.
+#[derive(Debug, Clone, Default)]
+pub struct NameEntry {
+    name: String,
+    /// Still the name offered, which the first key typed replaces.
+    offered: bool,
+}
+
+impl NameEntry {
+    pub fn new(offered: &str) -> NameEntry {
+        NameEntry { name: offered.chars().take(MAX_NAME).collect(), offered: true }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Take a key: characters go in, Backspace takes one out, and Enter or Esc is done
+    /// with it. Returns true when done.
+    pub fn key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Enter | KeyCode::Esc => return true,
+            KeyCode::Backspace => {
+                self.name.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() => {
+                if self.offered {
+                    self.name.clear();
+                }
+                if self.name.chars().count() < MAX_NAME {
+                    self.name.push(c);
+                }
+            }
+            _ => return false,
+        }
+        self.offered = false;
+        false
+    }
+}
+
+/// The prompt for a name once time is up, centred over the scene.
+pub struct NamePrompt<'a> {
+    pub entry: &'a NameEntry,
+    pub points: u32,
+}
+
+impl Widget for NamePrompt<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines = vec![
+            Line::from(Span::styled(format!("Time's up! {} pts", self.points), Style::default().fg(Color::Yellow))),
+            Line::from(""),
+            Line::from(" Your name for the high scores:"),
+            Line::from(Span::styled(format!(" {}▏", self.entry.name), Style::default().fg(Color::Cyan))),
+            Line::from(""),
+            Line::from(Span::styled(" Enter to save", Style::default().fg(Color::DarkGray))),
+        ];
+        menu::render_box(lines, "Tournament", 36, area, buf);
+    }
+}
+
+/// The best rounds of one length, centred over the scene.
+pub struct Leaderboard<'a> {
+    pub scores: &'a HighScores,
+    /// Which of the table's lengths to show.
+    pub length: usize,
+    /// The place of a round just played, to mark it.
+    pub marked: Option<usize>,
+}
+
+impl Widget for Leaderboard<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lengths = self.scores.lengths();
+        let heading = Style::default().fg(Color::Yellow);
+        let mut lines = Vec::new();
+        match lengths.get(self.length) {
+            Some(&length_secs) => {
+                let arrows = if lengths.len() > 1 { "◂ ▸ " } else { "" };
+                let title = format!("{}{}:{:02} rounds", arrows, length_secs / 60, length_secs % 60);
+                lines.push(Line::from(Span::styled(title, heading)));
+                lines.push(Line::from(""));
+                for (i, round) in self.scores.best(length_secs).enumerate() {
+                    let name = if round.name.is_empty() { "—" } else { round.name.as_str() };
+                    let biggest = round
+                        .biggest
+                        .as_ref()
+                        .map(|catch| format!("{} {:.1} cm", catch.species, catch.size))
+                        .unwrap_or_default();
+                    let marker = if self.marked == Some(i + 1) { "◀" } else { "" };
+                    lines.push(Line::from(format!(
+                        "{:>2}. {:<16} {:>6} pts  {:<22} {:<10} {}",
+                        i + 1,
+                        name,
+                        round.points,
+                        biggest.chars().take(22).collect::<String>(),
+                        round.date,
+                        marker
+                    )));
+                }
+            }
+            None => {
+                lines.push(Line::from(" No rounds played yet."));
+                lines.push(Line::from(" Start one with --tournament 3m."));
+            }
+        }
+        lines.push(Line::from(""));
+        let keys = if lengths.len() > 1 { "  ←→ length · Enter back" } else { "  Enter back" };
+        lines.push(Line::from(Span::styled(keys, Style::default().fg(Color::DarkGray))));
+        menu::render_box(lines, "High scores", 72, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_first_key_replaces_the_offered_name() {
+        let mut entry = NameEntry::new("angler");
+        assert!(!entry.key(KeyCode::Char('A')));
+        entry.key(KeyCode::Char('n'));
+        entry.key(KeyCode::Char('x'));
+        entry.key(KeyCode::Backspace);
+        entry.key(KeyCode::Char('n'));
+        assert!(entry.key(KeyCode::Enter));
+        assert_eq!(entry.name(), "Ann");
+
+        let mut kept = NameEntry::new("angler");
+        kept.key(KeyCode::Backspace);
+        assert_eq!(kept.name(), "angle");
+        for _ in 0..30 {
+            kept.key(KeyCode::Char('r'));
+        }
+        assert_eq!(kept.name().chars().count(), MAX_NAME);
+    }
+}
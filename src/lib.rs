@@ -0,0 +1,96 @@
+//! A terminal fishing scene built on ratatui.
+//!
+//! The `fisherman` binary wires these modules into a full-screen game. Host
+//! applications can embed the scene themselves through the stateful widgets in
+//! [`ocean`], [`stars`] and [`fish_tank`], driving them from their own tick loop.
+
+pub mod a11y;
+pub mod achievements;
+pub mod ambient;
+pub mod app;
+pub mod aquarium;
+pub mod autopilot;
+pub mod banner;
+pub mod braille;
+pub mod broadcast;
+pub mod cargo_progress;
+pub mod cast;
+pub mod clock;
+pub mod color;
+pub mod command_watch;
+pub mod conditions;
+pub mod config_file;
+pub mod console;
+pub mod csv_frames;
+pub mod daemon;
+pub mod dock;
+pub mod economy;
+pub mod effects;
+pub mod encyclopedia;
+pub mod events;
+pub mod export;
+pub mod fish;
+pub mod fish_tank;
+pub mod fisherman;
+pub mod fishing_game;
+pub mod fishing_line;
+pub mod fog;
+pub mod game_state;
+pub mod gh_watch;
+pub mod graphics;
+pub mod handoff;
+pub mod harness;
+pub mod headless;
+pub mod hooks;
+pub mod import_sprite;
+pub mod jellyfish;
+pub mod help;
+pub mod history;
+pub mod keymap;
+pub mod layer;
+pub mod layout;
+pub mod leaderboard;
+pub mod lighting;
+pub mod logging;
+pub mod menu;
+pub mod metrics;
+pub mod moon;
+pub mod mqtt;
+pub mod netplay;
+pub mod ocean;
+pub mod pacing;
+pub mod pack;
+pub mod particles;
+pub mod photo;
+pub mod plugin;
+pub mod png;
+pub mod population;
+pub mod profiler;
+pub mod record;
+pub mod scene;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod seabed;
+pub mod season;
+pub mod send;
+pub mod shop;
+pub mod signals;
+#[cfg(feature = "sound")]
+pub mod sound;
+pub mod sprite_editor;
+pub mod sprite_watch;
+pub mod stars;
+pub mod stats;
+pub mod status_bar;
+pub mod symbols;
+pub mod tail;
+pub mod tasks;
+pub mod terminal;
+pub mod theme;
+pub mod tournament;
+pub mod underwater;
+pub mod watchdog;
+pub mod weather;
+pub mod whale;
+pub mod widgets;
+pub mod wind;
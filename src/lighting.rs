@@ -0,0 +1,90 @@
+//! Warm light from the dock lantern at night. Drawn as a pass over everything already
+//! in the buffer, so the planks, the fisherman and the water around the flame all pick
+//! up its colour, more so the closer they are, and the glow flickers from frame to
+//! frame the way a flame does.
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+
+use crate::color;
+
+/// Columns the glow reaches either side of the flame. Rows count double, since a cell
+/// is about twice as tall as it is wide.
+pub const GLOW_RADIUS: f32 = 9.0;
+/// How far a cell right by the flame is tinted towards its colour.
+const GLOW_TINT: f32 = 0.55;
+
+/// How bright the flame burns at `elapsed`, between about 0.7 and 1.0. Waves at rates
+/// that never line up, so it doesn't visibly repeat.
+pub fn flicker(elapsed: Duration) -> f32 {
+    let t = elapsed.as_secs_f32();
+    0.85 + 0.08 * (t * 11.0).sin() + 0.05 * (t * 23.7 + 1.3).sin() + 0.02 * (t * 41.3 + 0.4).sin()
+}
+
+/// Light around a flame at (`x`, `y`), tinting the colours already drawn near it.
+pub struct Glow {
+    pub x: u16,
+    pub y: u16,
+    pub color: Color,
+    /// 0.0 for no light to 1.0 for the flame at its brightest on a dark night.
+    pub strength: f32,
+}
+
+impl Widget for Glow {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let strength = self.strength.clamp(0.0, 1.0);
+        if strength <= 0.0 {
+            return;
+        }
+        let reach = GLOW_RADIUS.ceil() as u16;
+        let rows = (GLOW_RADIUS / 2.0).ceil() as u16;
+        let left = self.x.saturating_sub(reach).max(area.left());
+        let right = self.x.saturating_add(reach + 1).min(area.right());
+        let top = self.y.saturating_sub(rows).max(area.top());
+        let bottom = self.y.saturating_add(rows + 1).min(area.bottom());
+        for y in top..bottom {
+            for x in left..right {
+                let dx = x as f32 - self.x as f32;
+                let dy = (y as f32 - self.y as f32) * 2.0;
+                let falloff = 1.0 - (dx * dx + dy * dy).sqrt() / GLOW_RADIUS;
+                if falloff <= 0.0 {
+                    continue;
+                }
+                let amount = GLOW_TINT * strength * falloff;
+                let cell = &mut buf[(x, y)];
+                cell.set_fg(color::blend(cell.fg, self.color, amount));
+                cell.set_bg(color::blend(cell.bg, self.color, amount / 2.0));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_glow_warms_cells_near_the_flame_and_flickers() {
+        let area = Rect::new(0, 0, 40, 12);
+        let mut buf = Buffer::empty(area);
+        for cell in buf.content.iter_mut() {
+            cell.set_fg(Color::Rgb(40, 40, 80)).set_bg(Color::Rgb(10, 10, 30));
+        }
+        Glow { x: 20, y: 6, color: Color::Rgb(255, 200, 80), strength: 1.0 }.render(area, &mut buf);
+        let red = |x: u16, y: u16| match buf[(x, y)].fg {
+            Color::Rgb(r, _, _) => r,
+            _ => 0,
+        };
+        assert!(red(20, 6) > red(24, 6) && red(24, 6) > 40);
+        assert_eq!(red(20, 0), 40);
+        assert_eq!(buf[(2, 6)].fg, Color::Rgb(40, 40, 80));
+
+        let brightness: Vec<f32> = (0..20).map(|frame| flicker(Duration::from_millis(frame * 33))).collect();
+        assert!(brightness.iter().all(|b| (0.65..=1.0).contains(b)));
+        assert!(brightness.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+}
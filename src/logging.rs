@@ -0,0 +1,53 @@
+//! `--log <file>`: what goes on behind the scene, written to a file since anything
+//! printed would land on top of the game. Sprites that fail to load, signal lines that
+//! don't parse and the game's events and screen changes are logged, more of them with
+//! `-v` and `-vv`. Without `--log` nothing is.
+
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tracing::Level;
+
+/// How much to log for `-v` flags: warnings and errors with none, what happens with
+/// `-v`, and every detail with `-vv`. Repeated flags (`-v -v`) add up.
+pub fn verbosity(args: &[String]) -> Level {
+    let count: usize = args
+        .iter()
+        .filter_map(|arg| arg.strip_prefix('-'))
+        .filter(|flags| !flags.is_empty() && flags.chars().all(|c| c == 'v'))
+        .map(str::len)
+        .sum();
+    match count {
+        0 => Level::WARN,
+        1 => Level::INFO,
+        2 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Append logs at `level` and above to the file at `path`.
+pub fn init(path: &Path, level: Level) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_max_level(level)
+        .try_init()
+        .map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_v_logs_more() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(verbosity(&args(&["fisherman", "--log", "x.log"])), Level::WARN);
+        assert_eq!(verbosity(&args(&["fisherman", "-v"])), Level::INFO);
+        assert_eq!(verbosity(&args(&["fisherman", "-vv"])), Level::DEBUG);
+        assert_eq!(verbosity(&args(&["fisherman", "-v", "-vv", "--reduce-motion"])), Level::TRACE);
+    }
+}
@@ -1,88 +1,213 @@
-use std::io;
-use std::io::{BufRead, BufReader};
+use std::io::{self, Write};
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
-use std::thread;
 use std::sync::{Arc, Mutex};
 use std::env;
-use std::path::PathBuf;
-use std::fs;
+use std::path::{Path, PathBuf};
 
-#[cfg(windows)]
-use std::fs::OpenOptions;
 
-mod csv_frames;
-mod ocean;
-mod widgets;
-mod fisherman;
-mod fish;
-mod fishing_line;
-mod fishing_game;
-mod stars;
-
-use crossterm::{
-    event::{self, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{
-    prelude::*,
-    widgets::{Block, Borders, Paragraph},
-    Terminal,
-};
-use ratatui::text::Text;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{prelude::*, Terminal, TerminalOptions, Viewport};
 use ratatui::layout::Rect;
-use rand;
 
-use fish::{Fish, spawn_fishes};
-use ocean::Ocean;
-use widgets::FishermanDock;
-use fisherman::Fisherman;
-use fishing_line::{FishingLine, FishingState};
-use csv_frames::load_frames_from_dir;
+use fisherman::{clock, config_file, export, headless, import_sprite, logging, pacing, pack, photo, profiler, send, signals, sprite_editor, stats, symbols, tail, terminal};
+use fisherman::config_file::ConfigFile;
+use fisherman::color::ColorDepth;
+use fisherman::keymap::Keymap;
+use fisherman::season::{Date, Season};
+use fisherman::dock::{self, DockSettings};
+use fisherman::fish::{self, SpawnSettings};
+use fisherman::theme::Theme;
+use fisherman::terminal::TerminalGuard;
+use fisherman::pacing::FramePacer;
+use fisherman::profiler::{FrameTimes, ProfileOverlay, Profiler};
+use fisherman::sprite_watch::SpriteWatcher;
+use fisherman::app::{App, Assets, Config};
+use fisherman::fishing_game::ItemTable;
+use fisherman::graphics::GraphicsMode;
+use fisherman::signals::{HttpSource, PipeSource, SharedSignal, SignalFileSource, SignalPolicy, Signals, StdinSource, UnknownLines};
+use fisherman::send::InstanceInfo;
+use fisherman::a11y::A11yChannel;
+use fisherman::handoff::Handoff;
+use fisherman::game_state::{self, GameState};
+use fisherman::gh_watch::{self, GhWatch};
+use fisherman::cargo_progress::CargoRun;
+use fisherman::broadcast::{self, Broadcast};
+use fisherman::daemon::{self, Daemon};
+use fisherman::cast::CastWriter;
+use fisherman::command_watch::CommandWatch;
+use fisherman::tail::LogTail;
+use fisherman::stats::SummaryFormat;
+use fisherman::hooks::Hooks;
+use fisherman::banner::Banners;
+use fisherman::menu::Settings;
+use fisherman::metrics::Metrics;
+use fisherman::mqtt::{self, MqttSource};
+use fisherman::netplay::{self, Link};
+use fisherman::plugin::{self, PluginRegistry, ScenePlugin};
+use fisherman::tournament::{self, Tournament};
+use fisherman::watchdog::{self, TimeoutPolicy};
+#[cfg(feature = "sound")]
+use fisherman::sound::{Sound, SoundSettings};
+#[cfg(feature = "scripting")]
+use fisherman::scripting::Script;
+use fisherman::record::{Entry, Header, Recorder, Recording};
 
-// Layout constants
-const OCEAN_HEIGHT: u16 = 4;
-const OCEAN_DESIRED_TOP: u16 = 20;
-const DOCK_WIDTH: u16 = 16;
-const DOCK_HEIGHT: u16 = 4;
-const FISHERMAN_HEIGHT: u16 = 9;
-const FISH_AREA_OFFSET_FROM_OCEAN: u16 = 5;
-
-/// Compute the ocean area placement given the terminal size
-fn compute_ocean_area(size: Rect) -> Rect {
-    let top = if size.height > OCEAN_DESIRED_TOP + OCEAN_HEIGHT {
-        OCEAN_DESIRED_TOP
-    } else if size.height > OCEAN_HEIGHT {
-        size.height.saturating_sub(OCEAN_HEIGHT)
-    } else {
-        0
-    };
-    Rect::new(size.x + 1, top, size.width - 2, OCEAN_HEIGHT)
-}
-
-/// Compute fish area placement and lane count based on ocean position
-fn compute_fish_area(size: Rect, ocean_y: u16) -> (Rect, u16) {
-    let lane_height = fish::FISH_HEIGHT;
-    let desired_top = ocean_y.saturating_add(FISH_AREA_OFFSET_FROM_OCEAN);
-    let available_height = if desired_top < size.height {
-        size.height.saturating_sub(desired_top)
-    } else {
-        0
-    };
-    let lanes = std::cmp::max(1u16, available_height / lane_height);
-    let fish_area_height = lane_height.saturating_mul(lanes).saturating_sub(2);
-    let base_y = if desired_top.saturating_add(fish_area_height) <= size.height {
-        desired_top
-    } else if size.height > fish_area_height {
-        size.height.saturating_sub(fish_area_height)
-    } else {
-        0
-    };
-    (Rect::new(size.x, base_y, size.width, fish_area_height), lanes)
-}
+/// One in-game day every two real minutes
+const DEFAULT_TIMELAPSE_FACTOR: f64 = 720.0;
+/// Screen size for `--headless-frames`, fixed so the output only depends on the seed
+const HEADLESS_SIZE: Rect = Rect::new(0, 0, 80, 24);
+/// The screen `--bench` draws on unless `--bench-size` says otherwise: a large terminal.
+const BENCH_SIZE: Rect = Rect::new(0, 0, 240, 70);
+/// Exit code after a FAILURE signal unless `--fail-exit-code` overrides it
+const DEFAULT_FAIL_EXIT_CODE: i32 = 1;
 
 fn main() -> Result<(), io::Error> {
     let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("send") {
+        std::process::exit(send::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("import-sprite") {
+        std::process::exit(import_sprite::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("edit") {
+        std::process::exit(sprite_editor::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("export") {
+        std::process::exit(export::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("spectate") {
+        std::process::exit(broadcast::run(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("attach") {
+        std::process::exit(daemon::run(&args[2..]));
+    }
+    // `fisherman replay <recording>`: run with the recorded command line, fed from the recording
+    let replay = match args.get(1).map(String::as_str) {
+        Some("replay") => {
+            let path = args.get(2).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "usage: fisherman replay <recording>")
+            })?;
+            Some(Recording::load(path.as_ref()).map_err(|e| {
+                io::Error::new(e.kind(), format!("could not load recording {}: {}", path, e))
+            })?)
+        }
+        _ => None,
+    };
+    let args = match replay {
+        Some(ref recording) => recording.header.args.clone(),
+        None => args,
+    };
+    // `fisherman cargo [options] -- <cargo args>`: fish while cargo runs, its progress coming in as signals
+    let command_line = args.clone();
+    let (args, cargo_args) = match args.get(1).map(String::as_str) {
+        Some("cargo") => {
+            let (own, cargo) = match args.iter().position(|arg| arg == "--") {
+                Some(split) => (&args[2..split], &args[split + 1..]),
+                None => (&[][..], &args[2..]),
+            };
+            let cargo = if cargo.is_empty() { vec!["build".to_string()] } else { cargo.to_vec() };
+            (args[..1].iter().chain(own).cloned().collect(), Some(cargo))
+        }
+        _ => (args, None),
+    };
+    // `fisherman watch --cmd <command> [--paths <path>...] [options]`: rerun a command whenever files change
+    let (args, watch) = match args.get(1).map(String::as_str) {
+        Some("watch") => {
+            let mut own = vec![args[0].clone()];
+            let mut command = None;
+            let mut paths = Vec::new();
+            let mut rest = args[2..].iter().peekable();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--cmd" => command = rest.next().cloned(),
+                    "--paths" => {
+                        while let Some(path) = rest.next_if(|arg| !arg.starts_with("--")) {
+                            paths.push(PathBuf::from(path));
+                        }
+                    }
+                    _ => own.push(arg.clone()),
+                }
+            }
+            let command = command.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "usage: fisherman watch --cmd <command> [--paths <path>...]")
+            })?;
+            if paths.is_empty() {
+                paths.push(PathBuf::from("."));
+            }
+            (own, Some((command, paths)))
+        }
+        _ => (args, None),
+    };
+    // `fisherman gh-watch --repo <owner/name> --run <id> [--interval <secs>] [options]`: follow a GitHub Actions run
+    let (args, gh_run) = match args.get(1).map(String::as_str) {
+        Some("gh-watch") => {
+            let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+            let mut own = vec![args[0].clone()];
+            let (mut repo, mut run, mut interval) = (None, None, gh_watch::DEFAULT_INTERVAL);
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match (arg.as_str(), rest.clone().next()) {
+                    ("--repo", Some(value)) => repo = Some(gh_watch::parse_repo(value).map_err(invalid)?),
+                    ("--run", Some(value)) => run = Some(gh_watch::parse_run(value).map_err(invalid)?),
+                    ("--interval", Some(value)) => interval = gh_watch::parse_interval(value).map_err(invalid)?,
+                    _ => {
+                        own.push(arg.clone());
+                        continue;
+                    }
+                }
+                rest.next();
+            }
+            let (Some(repo), Some(run)) = (repo, run) else {
+                return Err(invalid("usage: fisherman gh-watch --repo <owner/name> --run <id> [--interval <secs>]".to_string()));
+            };
+            (own, Some(GhWatch { repo, run, interval }))
+        }
+        _ => (args, None),
+    };
+    // `fisherman host [--port <port>]` and `fisherman join <addr>`: fish the same sea as someone on another machine.
+    // A recorded network game plays back without the other player.
+    let live = replay.is_none();
+    let (args, link) = match args.get(1).map(String::as_str) {
+        Some("host") => {
+            let port: u16 = match args.iter().position(|arg| arg == "--port").and_then(|i| args.get(i + 1)) {
+                Some(value) => value.parse().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --port: {}", value))
+                })?,
+                None => netplay::DEFAULT_PORT,
+            };
+            let own = args[..1].iter().chain(&args[2..]).cloned().collect();
+            let link = live.then(|| Link::host(port)).transpose().map_err(|e| {
+                io::Error::new(e.kind(), format!("could not listen on port {}: {}", port, e))
+            })?;
+            (own, link)
+        }
+        Some("join") => {
+            let addr = args.get(2).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "usage: fisherman join <host>:<port>")
+            })?;
+            let own = args[..1].iter().chain(&args[3..]).cloned().collect();
+            let link = live.then(|| Link::join(addr)).transpose().map_err(|e| {
+                io::Error::new(e.kind(), format!("could not join {}: {}", addr, e))
+            })?;
+            (own, link)
+        }
+        _ => (args, None),
+    };
+    // `fisherman daemon [--port <port>]`: keep the scene running in the background for `fisherman attach`
+    let (args, daemon_port) = match args.get(1).map(String::as_str) {
+        Some("daemon") => {
+            let port: u16 = match args.iter().position(|arg| arg == "--port").and_then(|i| args.get(i + 1)) {
+                Some(value) => value.parse().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --port: {}", value))
+                })?,
+                None => daemon::DEFAULT_PORT,
+            };
+            let own = args[..1].iter().chain(&args[2..]).cloned().collect();
+            (own, Some(port))
+        }
+        _ => (args, None),
+    };
     let subprocess_mode = args.contains(&"--subprocess".to_string());
     
     // Check for --pipe argument (named pipe path)
@@ -91,542 +216,1280 @@ fn main() -> Result<(), io::Error> {
         .and_then(|i| args.get(i + 1))
         .map(PathBuf::from);
     
+    // Check for --task arguments (named tasks with a fisherman each, some with a pipe of
+    // their own: `--task build --task deploy=/tmp/deploy.pipe`)
+    let tasks: Vec<(String, Option<PathBuf>)> = args.windows(2)
+        .filter(|pair| pair[0] == "--task")
+        .map(|pair| match pair[1].split_once('=') {
+            Some((name, pipe)) => (name.to_string(), Some(PathBuf::from(pipe))),
+            None => (pair[1].clone(), None),
+        })
+        .collect();
+    let task_names: Vec<String> = tasks.iter().map(|(name, _)| name.clone()).collect();
+    
     // Check for --signal-file argument (backward compatibility)
     let signal_file: Option<PathBuf> = args.iter()
         .position(|arg| arg == "--signal-file")
         .and_then(|i| args.get(i + 1))
         .map(PathBuf::from);
     
+    // Check for --socket argument (Unix domain socket path)
+    let socket_path: Option<PathBuf> = args.iter()
+        .position(|arg| arg == "--socket")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    
+    // Check for --listen argument (HTTP signal endpoint address)
+    let listen_addr: Option<String> = args.iter()
+        .position(|arg| arg == "--listen")
+        .and_then(|i| args.get(i + 1))
+        .cloned();
+    
+    // Check for --mqtt and --topic (an MQTT broker and the topic to subscribe to there)
+    let mqtt_broker: Option<String> = match args.iter()
+        .position(|arg| arg == "--mqtt")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => Some(mqtt::parse_broker(value).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?),
+        None => None,
+    };
+    let mqtt_topic: String = args.iter()
+        .position(|arg| arg == "--topic")
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+        .unwrap_or_else(|| mqtt::DEFAULT_TOPIC.to_string());
+    
+    // Check for --on-signal argument (what to do once a signal arrives)
+    let on_signal: SignalPolicy = match args.iter()
+        .position(|arg| arg == "--on-signal")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        // Each run of a watched command ends with a signal, and a daemon collects them
+        // all, so keep fishing between them
+        None if watch.is_some() || daemon_port.is_some() => SignalPolicy::KeepPlaying,
+        None => SignalPolicy::default(),
+    };
+    
+    // Check for --unknown-lines argument (what to do with lines that aren't signals)
+    let unknown_lines: UnknownLines = match args.iter()
+        .position(|arg| arg == "--unknown-lines")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None => UnknownLines::default(),
+    };
+    
+    // Check for --eof-failure argument (seconds after stdin closes without an outcome to fail the wait)
+    let eof_failure: Option<Duration> = match args.iter()
+        .position(|arg| arg == "--eof-failure")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => Some(
+            value
+                .parse::<f32>()
+                .ok()
+                .and_then(|secs| Duration::try_from_secs_f32(secs).ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --eof-failure: {}", value)))?,
+        ),
+        None => None,
+    };
+    
+    // Check for --timeout argument (how long the task may go without a signal or heartbeat, e.g. 30m)
+    let timeout: Option<Duration> = match args.iter()
+        .position(|arg| arg == "--timeout")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => Some(watchdog::parse_window(value).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?),
+        None => None,
+    };
+    
+    // Check for --on-timeout argument (what to do once the --timeout window runs out)
+    let on_timeout: TimeoutPolicy = match args.iter()
+        .position(|arg| arg == "--on-timeout")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None => TimeoutPolicy::default(),
+    };
+    
+    // Check for --fail-exit-code argument (process exit code after a FAILURE signal)
+    let fail_exit_code: i32 = match args.iter()
+        .position(|arg| arg == "--fail-exit-code")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --fail-exit-code: {}", value))
+        })?,
+        None => DEFAULT_FAIL_EXIT_CODE,
+    };
+    
+    // Check for --log argument (what goes on is logged there, more of it with -v or -vv)
+    if let Some(path) = args.iter().position(|arg| arg == "--log").and_then(|i| args.get(i + 1)) {
+        logging::init(Path::new(path), logging::verbosity(&args))
+            .map_err(|e| io::Error::new(e.kind(), format!("could not open log {}: {}", path, e)))?;
+        tracing::info!("fisherman {} started", env!("CARGO_PKG_VERSION"));
+    }
+    
+    // Check for --a11y-fifo argument (event lines for screen readers and braille displays)
+    let a11y_path: Option<PathBuf> = args.iter()
+        .position(|arg| arg == "--a11y-fifo")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    
+    // Check for --braille (draws the line and ripples in braille dots)
+    let braille = args.contains(&"--braille".to_string());
+    
+    // Check for --constellations (picks out a few constellations among the stars)
+    let constellations = args.contains(&"--constellations".to_string());
+    
+    // Check for --smooth-motion (fish drawn to the nearest column and turned round at the edges)
+    let smooth_motion = args.contains(&"--smooth-motion".to_string());
+    
+    // Check for --ascii (plain ASCII without colour, for dumb terminals, serial consoles and screen readers)
+    let ascii = args.contains(&"--ascii".to_string());
+    
+    // Check for --graphics argument (fish and the moon from PNG pictures on kitty or sixel terminals)
+    let graphics = match args.iter().position(|arg| arg == "--graphics").and_then(|i| args.get(i + 1)) {
+        Some(mode) => mode.parse::<GraphicsMode>().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?.protocol(),
+        None => None,
+    };
+    
+    // Check for --reduce-motion (a calm scene for a background wait screen)
+    let reduce_motion = args.contains(&"--reduce-motion".to_string());
+    
+    // Check for --no-status-bar (hides the line of state and stats along the bottom)
+    let status_bar = !args.contains(&"--no-status-bar".to_string());
+    
+    // Check for --profile (frame timings and a frame rate graph in the top-right corner)
+    let profile = args.contains(&"--profile".to_string());
+    
+    // Check for --pack argument (a themed set of sprites laid over the built-in ones)
+    let pack_dir: Option<PathBuf> = match args.iter().position(|arg| arg == "--pack").and_then(|i| args.get(i + 1)) {
+        Some(name) => Some(pack::resolve(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?),
+        None => None,
+    };
+    
+    // Check for --strict-assets (fail to start if any sprites can't be loaded, rather than draw placeholders)
+    let strict_assets = args.contains(&"--strict-assets".to_string());
+    
+    // Check for --watch-sprites (reload sprite CSVs when they change on disk)
+    let watch_sprites = args.contains(&"--watch-sprites".to_string());
+    
+    // Check for --handoff argument (scene saved on exit and restored on the next start)
+    let handoff_path: Option<PathBuf> = args.iter()
+        .position(|arg| arg == "--handoff")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    
+    // Check for --record argument (everything that happens is written there for `fisherman replay`)
+    let record_path: Option<PathBuf> = args.iter()
+        .position(|arg| arg == "--record")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    
+    // Check for --export-cast argument (the frames drawn are written there as an asciinema recording)
+    let cast_path: Option<PathBuf> = args.iter()
+        .position(|arg| arg == "--export-cast")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    
+    // Check for --broadcast argument (the scene is shown live to `fisherman spectate` on other terminals)
+    let broadcast_addr: Option<String> = args.iter()
+        .position(|arg| arg == "--broadcast")
+        .and_then(|i| args.get(i + 1))
+        .cloned();
+    
+    // Check for --screenshot-on-exit argument (the last frame is saved there on quitting)
+    let exit_screenshot: Option<PathBuf> = args.iter()
+        .position(|arg| arg == "--screenshot-on-exit")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    
+    // Check for --screenshot-format argument (ANSI colours, or plain text)
+    let plain_screenshots = match args.iter()
+        .position(|arg| arg == "--screenshot-format")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+    {
+        None | Some("ansi") => false,
+        Some("plain") => true,
+        Some(other) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid --screenshot-format: {} (expected ansi or plain)", other),
+            ));
+        }
+    };
+    
+    // Check for --summary[=json|plain] (one line about the session printed on exit)
+    let summary_format: Option<SummaryFormat> = match args.iter().find_map(|arg| arg.strip_prefix("--summary")) {
+        None => None,
+        Some("") => Some(SummaryFormat::Plain),
+        Some(format) => Some(
+            format.strip_prefix('=').unwrap_or(format).parse()
+                .map_err(|e: String| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        ),
+    };
+    
+    // Check for --save argument (where the game is saved on exit and restored from)
+    let save_path: PathBuf = args.iter()
+        .position(|arg| arg == "--save")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+        .unwrap_or_else(game_state::default_save_path);
+    
+    // Check for --photo-dir argument (photo cards of record and Massive catches are saved there)
+    let photo_dir: Option<PathBuf> = args.iter()
+        .position(|arg| arg == "--photo-dir")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    
+    // Check for --new-game (ignore any saved game and start fresh)
+    let new_game = args.contains(&"--new-game".to_string());
+    
+    // Check for --demo (the game plays itself, from a fresh game that is never saved)
+    let demo = args.contains(&"--demo".to_string());
+    
+    // Check for --players argument (1, or 2 taking turns at one keyboard)
+    let players: u8 = match args.iter()
+        .position(|arg| arg == "--players")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().ok()
+            .filter(|players| (1..=2).contains(players))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --players: {} (expected 1 or 2)", value)))?,
+        None => 1,
+    };
+    if players > 1 && link.is_some() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "--players 2 is for one keyboard, not a network game"));
+    }
+    
+    // Check for --tournament argument (length of a timed round scoring the catches, e.g. 3m)
+    let tournament_length: Option<Duration> = match args.iter()
+        .position(|arg| arg == "--tournament")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => Some(tournament::parse_length(value).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?),
+        None => None,
+    };
+    if tournament_length.is_some() && (players > 1 || link.is_some()) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "--tournament is for one player"));
+    }
+    
+    // Check for --attract argument (minutes without input before the game plays itself)
+    let attract_after: Option<Duration> = match args.iter()
+        .position(|arg| arg == "--attract")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => Some(
+            value.parse().ok()
+                .filter(|minutes: &f64| *minutes > 0.0)
+                .and_then(|minutes| Duration::try_from_secs_f64(minutes * 60.0).ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --attract: {}", value)))?,
+        ),
+        None => None,
+    };
+    
+    // Check for --items argument (CSV overriding the junk and treasure table)
+    let item_table = match args.iter()
+        .position(|arg| arg == "--items")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(path) => ItemTable::load_csv(path).map_err(|e| {
+            io::Error::new(e.kind(), format!("could not load --items {}: {}", path, e))
+        })?,
+        None => ItemTable::default(),
+    };
+    
+    // Check for --timelapse [factor] (in-scene time runs this many times faster)
+    let timelapse: Option<f64> = match args.iter()
+        .position(|arg| arg == "--timelapse")
+        .map(|i| args.get(i + 1).filter(|v| !v.starts_with("--")))
+    {
+        Some(Some(value)) => Some(clock::parse_scale(value).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?),
+        Some(None) => Some(DEFAULT_TIMELAPSE_FACTOR),
+        None => None,
+    };
+    
+    // Check for --seed argument (makes fish, foam, stars and weather reproducible)
+    let seed: Option<u64> = match args.iter()
+        .position(|arg| arg == "--seed")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => Some(value.parse().map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --seed: {}", value))
+        })?),
+        None => None,
+    };
+    
+    // Check for --fps argument (target frame rate while something on screen is moving)
+    let fps: f64 = match args.iter()
+        .position(|arg| arg == "--fps")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().ok().filter(|fps| (pacing::MIN_FPS..=pacing::MAX_FPS).contains(fps)).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid --fps: {} (expected {} to {})", value, pacing::MIN_FPS, pacing::MAX_FPS),
+            )
+        })?,
+        None => pacing::DEFAULT_FPS,
+    };
+    
+    // Check for --tail argument (a log file followed in a panel above the ocean)
+    let tail_path: Option<PathBuf> = args.iter()
+        .position(|arg| arg == "--tail")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    
+    // Check for --tail-lines argument (how many of its last lines the panel keeps)
+    let tail_lines: usize = match args.iter()
+        .position(|arg| arg == "--tail-lines")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().ok().filter(|n| *n > 0).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --tail-lines: {}", value))
+        })?,
+        None => tail::DEFAULT_TAIL_LINES,
+    };
+    
+    // Check for --config argument (key bindings and other settings; the default file is optional)
+    let config_arg = args.iter()
+        .position(|arg| arg == "--config")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    let config_path = config_arg.clone().unwrap_or_else(config_file::default_config_path);
+    let config_file = match ConfigFile::load(&config_path) {
+        Ok(Some(file)) => file,
+        Ok(None) if config_arg.is_none() => ConfigFile::default(),
+        Ok(None) => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("could not load --config {}: no such file", config_path.display()),
+            ));
+        }
+        Err(e) => {
+            return Err(io::Error::new(e.kind(), format!("could not load config {}: {}", config_path.display(), e)));
+        }
+    };
+    let keys = match config_file.section("keys") {
+        Some(table) => Keymap::from_table(table).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        None => Keymap::default(),
+    };
+    let hooks = match config_file.section("hooks") {
+        Some(table) => Hooks::from_table(table).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        None => Hooks::default(),
+    };
+    let banners = match config_file.section("banners") {
+        Some(table) => Banners::from_table(table, config_path.parent().unwrap_or(Path::new(""))).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        None => Banners::default(),
+    };
+    let settings = match config_file.section("settings") {
+        Some(table) => Settings::from_table(table).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        None => Settings::default(),
+    };
+    // Each --plugin is added to the ones enabled in the config file
+    let mut plugin_names = match config_file.section("plugins") {
+        Some(table) => plugin::enabled_from_table(table).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        None => Vec::new(),
+    };
+    plugin_names.extend(args.windows(2).filter(|pair| pair[0] == "--plugin").map(|pair| pair[1].clone()));
+    // Each --script runs a Lua script beside them, in builds with the scripting feature
+    let script_paths: Vec<PathBuf> = args.windows(2).filter(|pair| pair[0] == "--script").map(|pair| PathBuf::from(&pair[1])).collect();
+    // The flags win over the settings in the config file
+    // Braille dots and pictures aren't ASCII
+    let braille = (braille || settings.braille) && !ascii;
+    let graphics = graphics.filter(|_| !ascii);
+    let reduce_motion = reduce_motion || settings.reduce_motion;
+    let status_bar = status_bar && settings.status_bar;
+    let constellations = constellations || settings.constellations;
+    // A lantern bought in the shop is lit unless the settings put it out
+    let lantern = settings.lantern;
+    // A --photo-dir wins over the one in [photos]
+    let photo_dir = match (photo_dir, config_file.section("photos")) {
+        (Some(dir), _) => Some(dir),
+        (None, Some(table)) => photo::dir_from_table(table).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        (None, None) => None,
+    };
+    #[cfg(feature = "sound")]
+    let sound_settings = match config_file.section("sound") {
+        Some(table) => SoundSettings::from_table(table).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        None => SoundSettings::default(),
+    };
+    
+    // Check for --color-depth argument (16, 256 or truecolor; detected from the environment otherwise)
+    let color_depth: ColorDepth = match args.iter()
+        .position(|arg| arg == "--color-depth")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None => ColorDepth::detect(),
+    };
+    
+    // Check for --theme argument (a built-in theme name or a TOML theme file)
+    let theme = match args.iter()
+        .position(|arg| arg == "--theme")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(name) => match Theme::named(name) {
+            Some(theme) => theme,
+            None => load_theme(name)?,
+        },
+        None => match config_file.section("theme") {
+            Some(table) => Theme::from_table(table).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+            })?,
+            None => Theme::default(),
+        },
+    };
+    
+    // Check for --dock-side, --dock-width and --shoreline (where the dock stands and the
+    // shore beyond it), over the [dock] section
+    let mut dock = match config_file.section("dock") {
+        Some(table) => DockSettings::from_table(table).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        None => DockSettings::default(),
+    };
+    let flag_value = |flag: &str| args.iter().position(|arg| arg == flag).and_then(|i| args.get(i + 1));
+    if let Some(side) = flag_value("--dock-side") {
+        dock.side = side.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --dock-side: {}", e)))?;
+    }
+    if let Some(width) = flag_value("--dock-width") {
+        dock.width = width.parse().map_err(|_| format!("not a number: {}", width)).and_then(dock::parse_width)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --dock-width: {}", e)))?;
+    }
+    if let Some(shoreline) = flag_value("--shoreline") {
+        dock.shoreline = shoreline.parse().map_err(|_| format!("not a number: {}", shoreline)).and_then(dock::parse_shoreline)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --shoreline: {}", e)))?;
+    }
+    
+    // Check for --density and --max-fish (how busy the sea is kept), over the [spawning]
+    // section
+    let mut spawning = match config_file.section("spawning") {
+        Some(table) => SpawnSettings::from_table(table).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid config {}: {}", config_path.display(), e))
+        })?,
+        None => SpawnSettings::default(),
+    };
+    if let Some(density) = flag_value("--density") {
+        spawning.density = density.parse().map_err(|_| format!("not a number: {}", density)).and_then(fish::parse_density)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --density: {}", e)))?;
+    }
+    if let Some(max) = flag_value("--max-fish") {
+        spawning.max_fish = Some(max.parse().map_err(|_| format!("not a number: {}", max)).and_then(fish::parse_max_fish)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --max-fish: {}", e)))?);
+    }
+    
+    // Check for --skin (what the fisherman looks like: a folder of pose frames under
+    // src/skins or the sprites directory)
+    let skin = match args.iter()
+        .position(|arg| arg == "--skin")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(name) => {
+            let skins = fisherman::fisherman::load_layered_skins(&[
+                PathBuf::from("src/skins"),
+                config_file::config_dir().join("sprites").join("skins"),
+            ]);
+            if !skins.contains_key(name) {
+                let names: Vec<&str> = skins.keys().map(String::as_str).collect();
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown --skin {}: not one of {}", name, names.join(", ")),
+                ));
+            }
+            name.clone()
+        }
+        None => fisherman::fisherman::DEFAULT_SKIN.to_string(),
+    };
+    
+    // Check for --date MM-DD (the day of the year the seasons and holidays follow) and
+    // --season (off, or a season to keep whatever the date)
+    let today = match args.iter()
+        .position(|arg| arg == "--date")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None => Date::today(),
+    };
+    let (date, season) = match args.iter()
+        .position(|arg| arg == "--season")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+    {
+        Some("off") => (None, None),
+        Some("auto") | None => (Some(today), None),
+        Some(name) => {
+            let season: Season = name.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --season: {}", e)))?;
+            (Some(today), Some(season))
+        }
+    };
+    
+    // Hidden --headless-frames N: print N simulated frames as text instead of opening the terminal
+    let headless_frames: Option<usize> = match args.iter()
+        .position(|arg| arg == "--headless-frames")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(frames) => Some(frames.parse().map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --headless-frames: {}", frames))
+        })?),
+        None => None,
+    };
+    // Check for --bench N (time N simulated frames off-screen, on a --bench-size screen, and print how long they took)
+    let bench_frames: Option<usize> = match args.iter()
+        .position(|arg| arg == "--bench")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(frames) => Some(frames.parse().ok().filter(|frames| *frames > 0).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --bench: {} (expected a number of frames)", frames))
+        })?),
+        None => None,
+    };
+    let bench_size = match args.iter()
+        .position(|arg| arg == "--bench-size")
+        .and_then(|i| args.get(i + 1))
+    {
+        Some(value) => value.split_once('x')
+            .and_then(|(width, height)| Some(Rect::new(0, 0, width.parse().ok()?, height.parse().ok()?)))
+            .filter(|size| size.width > 0 && size.height > 0)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid --bench-size: {} (expected columns x rows, e.g. 240x70)", value)))?,
+        None => BENCH_SIZE,
+    };
+    if headless_frames.is_some() || bench_frames.is_some() {
+        let config = Config {
+            on_signal,
+            lantern,
+            braille,
+            constellations,
+            smooth_motion,
+            ascii,
+            reduce_motion,
+            status_bar,
+            theme,
+            skin: skin.clone(),
+            dock,
+            spawning,
+            date,
+            season,
+            color_depth,
+            show_clock: timelapse.is_some(),
+            time_scale: timelapse.unwrap_or(1.0),
+            items: item_table,
+            keys,
+            banners,
+            seed,
+            demo,
+            plugins: scene_plugins(&plugin_names, &script_paths, seed)?,
+            ..Config::default()
+        };
+        if let Some(frames) = bench_frames {
+            let mut app = App::new(config, load_assets(&[], strict_assets)?, None, bench_size);
+            print!("{}", profiler::bench(&mut app, frames).report());
+            return Ok(());
+        }
+        let mut app = App::new(config, load_assets(&[], strict_assets)?, None, HEADLESS_SIZE);
+        for (i, frame) in headless::render_frames(&mut app, headless_frames.unwrap_or(0)).iter().enumerate() {
+            println!("--- frame {} ---", i + 1);
+            print!("{}", frame);
+        }
+        return Ok(());
+    }
+    
+    let external_signals = subprocess_mode
+        || cargo_args.is_some()
+        || watch.is_some()
+        || pipe_path.is_some()
+        || tasks.iter().any(|(_, pipe)| pipe.is_some())
+        || signal_file.is_some()
+        || socket_path.is_some()
+        || listen_addr.is_some()
+        || mqtt_broker.is_some()
+        || gh_run.is_some()
+        || daemon_port.is_some();
+    
+    // Check for --no-menu (straight into the game; runs driven by signals, tasks or the demo skip the menu anyway)
+    let menu = !args.contains(&"--no-menu".to_string()) && !demo && !external_signals && task_names.is_empty();
+    
+    // Listed in the help overlay
+    let mut sources = Vec::new();
+    if subprocess_mode {
+        sources.push("stdin".to_string());
+    }
+    if let Some(ref cargo) = cargo_args {
+        sources.push(format!("cargo {}", cargo.join(" ")));
+    }
+    if let Some((ref command, _)) = watch {
+        sources.push(format!("watch {}", command));
+    }
+    if let Some(ref path) = pipe_path {
+        sources.push(format!("pipe {}", path.display()));
+    }
+    for (name, pipe) in &tasks {
+        if let Some(path) = pipe {
+            sources.push(format!("{} pipe {}", name, path.display()));
+        }
+    }
+    if let Some(ref path) = signal_file {
+        sources.push(format!("signal file {}", path.display()));
+    }
+    if let Some(ref path) = socket_path {
+        sources.push(format!("socket {}", path.display()));
+    }
+    if let Some(ref addr) = listen_addr {
+        sources.push(format!("HTTP {}", addr));
+    }
+    if let Some(ref broker) = mqtt_broker {
+        sources.push(format!("MQTT {} {}", broker, mqtt_topic));
+    }
+    if let Some(ref watch) = gh_run {
+        sources.push(format!("GitHub {} run {}", watch.repo, watch.run));
+    }
+    
+    // Sprites in the user's config directory are laid over the built-in ones, and over
+    // the pack's if there is one
+    let mut sprite_dirs: Vec<PathBuf> = pack_dir.into_iter().collect();
+    sprite_dirs.push(config_file::config_dir().join("sprites"));
+    
+    if let Some(recording) = replay {
+        let inbox: SharedSignal = Arc::new(Mutex::new(VecDeque::new()));
+        let config = Config {
+            signals: external_signals.then(|| Arc::clone(&inbox)),
+            sources,
+            on_signal,
+            lantern,
+            braille,
+            constellations,
+            smooth_motion,
+            ascii,
+            reduce_motion,
+            status_bar,
+            theme,
+            skin: skin.clone(),
+            dock,
+            spawning,
+            date,
+            season,
+            color_depth,
+            show_clock: timelapse.is_some(),
+            time_scale: timelapse.unwrap_or(1.0),
+            keys,
+            items: item_table,
+            seed: Some(recording.header.seed),
+            demo,
+            attract_after,
+            key_release: recording.header.key_release,
+            tasks: task_names,
+            players,
+            tournament: tournament_length.map(|length| Tournament::new(length, None)),
+            plugins: scene_plugins(&plugin_names, &script_paths, Some(recording.header.seed))?,
+            menu,
+            ..Config::default()
+        };
+        return play_back(recording, config, load_assets(&sprite_dirs, strict_assets)?, &inbox);
+    }
+    
     // Shared signal state
-    let signal_received: Arc<Mutex<Option<(bool, String)>>> = Arc::new(Mutex::new(None));
+    let signal_received: SharedSignal = Arc::new(Mutex::new(VecDeque::new()));
+    
+    let mut signal_sources = Signals::new(Arc::clone(&signal_received), unknown_lines);
     
-    // If in subprocess mode, spawn a thread to read from stdin
+    // If in subprocess mode, read signals from stdin
     if subprocess_mode {
-        let signal_clone = Arc::clone(&signal_received);
-        thread::spawn(move || {
-            let stdin = io::stdin();
-            let reader = BufReader::new(stdin);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    let line = line.trim();
-                    if let Some(msg) = line.strip_prefix("SUCCESS:") {
-                        *signal_clone.lock().unwrap() = Some((true, msg.to_string()));
-                    } else if let Some(msg) = line.strip_prefix("FAILURE:") {
-                        *signal_clone.lock().unwrap() = Some((false, msg.to_string()));
-                    }
-                }
-            }
-        });
+        signal_sources.start(StdinSource { eof_failure });
     }
     
     // If named pipe is specified, read from it in a thread
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut created_fifo = false;
     if let Some(ref path) = pipe_path {
-        let signal_clone = Arc::clone(&signal_received);
-        let path = path.clone();
-        thread::spawn(move || {
-            #[cfg(windows)]
-            {
-                // Windows named pipe: \\.\pipe\name
-                loop {
-                    if let Ok(file) = OpenOptions::new().read(true).open(&path) {
-                        let reader = BufReader::new(file);
-                        for line in reader.lines() {
-                            if let Ok(line) = line {
-                                let line = line.trim();
-                                if let Some(msg) = line.strip_prefix("SUCCESS:") {
-                                    *signal_clone.lock().unwrap() = Some((true, msg.to_string()));
-                                } else if let Some(msg) = line.strip_prefix("FAILURE:") {
-                                    *signal_clone.lock().unwrap() = Some((false, msg.to_string()));
-                                }
-                            }
-                        }
-                    }
-                    thread::sleep(Duration::from_millis(100));
-                }
-            }
-            #[cfg(not(windows))]
-            {
-                // Unix named pipe (FIFO)
-                if let Ok(file) = std::fs::File::open(&path) {
-                    let reader = BufReader::new(file);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            let line = line.trim();
-                            if let Some(msg) = line.strip_prefix("SUCCESS:") {
-                                *signal_clone.lock().unwrap() = Some((true, msg.to_string()));
-                            } else if let Some(msg) = line.strip_prefix("FAILURE:") {
-                                *signal_clone.lock().unwrap() = Some((false, msg.to_string()));
-                            }
-                        }
-                    }
-                }
+        #[cfg(unix)]
+        {
+            created_fifo = signals::ensure_fifo(path)?;
+        }
+        signal_sources.start(PipeSource { path: path.clone(), task: None });
+    }
+    
+    // Each task with a pipe of its own gets a reader that tags what arrives with its name
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut task_fifos = Vec::new();
+    for (name, pipe) in &tasks {
+        if let Some(path) = pipe {
+            #[cfg(unix)]
+            if signals::ensure_fifo(path)? {
+                task_fifos.push(path.clone());
             }
-        });
+            signal_sources.start(PipeSource { path: path.clone(), task: Some(name.clone()) });
+        }
     }
     
-    // If signal file is specified, poll it in a thread (backward compatibility)
+    // If signal file is specified, poll it (backward compatibility)
     if let Some(ref path) = signal_file {
-        let signal_clone = Arc::clone(&signal_received);
-        let path = path.clone();
-        thread::spawn(move || {
-            loop {
-                thread::sleep(Duration::from_millis(100));
-                if let Ok(content) = fs::read_to_string(&path) {
-                    let content = content.trim();
-                    if !content.is_empty() {
-                        if let Some(msg) = content.strip_prefix("SUCCESS:") {
-                            *signal_clone.lock().unwrap() = Some((true, msg.to_string()));
-                            let _ = fs::write(&path, ""); // Clear the file
-                        } else if let Some(msg) = content.strip_prefix("FAILURE:") {
-                            *signal_clone.lock().unwrap() = Some((false, msg.to_string()));
-                            let _ = fs::write(&path, ""); // Clear the file
-                        }
-                    }
-                }
-            }
-        });
+        signal_sources.start(SignalFileSource { path: path.clone() });
     }
     
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    let moon_sprite = csv_frames::load_moon_embedded()
-        .ok()
-        .or_else(|| csv_frames::load_csv_frame("moon.csv").ok());
-
-    let species_list = match csv_frames::load_all_fish_species_embedded() {
-        Ok(v) if !v.is_empty() => v,
-        _ => {
-            match csv_frames::load_all_fish_species("src/fish") {
-                Ok(v) => v,
-                Err(_) => Vec::new(),
-            }
+    // If a socket path is specified, accept clients on it
+    if let Some(ref path) = socket_path {
+        #[cfg(unix)]
+        signal_sources.start(signals::SocketSource::bind(path.clone())?);
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "--socket requires a Unix platform"));
         }
-    };
-    let mut per_species: Vec<_> = species_list.iter().map(|s| s.frames.clone()).collect();
-    if per_species.is_empty() {
-        let fallback = load_frames_from_dir("src/fish").unwrap_or_else(|_| Vec::new());
-        let fr = load_frames_from_dir("src/fish/right").unwrap_or_else(|_| fallback.clone());
-        let fl = load_frames_from_dir("src/fish/left").unwrap_or_else(|_| Vec::new());
-        per_species.push((fr, fl));
     }
-
-    let mut rng = rand::thread_rng();
+    
+    // If a listen address is specified, serve the HTTP signal endpoint and the metrics
+    let metrics = listen_addr.as_ref().map(|_| Metrics::new());
+    if let Some(ref addr) = listen_addr {
+        let source = HttpSource::bind(addr)?;
+        signal_sources.start(match metrics {
+            Some(ref metrics) => source.with_metrics(metrics.clone()),
+            None => source,
+        });
+    }
+    
+    // Subscribe on an MQTT broker, reconnecting whenever it drops
+    if let Some(ref broker) = mqtt_broker {
+        signal_sources.start(MqttSource::new(broker.clone(), mqtt_topic.clone()));
+    }
+    
+    // Poll the GitHub Actions run until it completes
+    if let Some(watch) = gh_run {
+        signal_sources.start(watch);
+    }
+    
+    let a11y = match a11y_path {
+        Some(path) => Some(A11yChannel::open(path)?),
+        None => None,
+    };
+    
+    // Advertise the endpoints so `fisherman send` can find this instance
+    let instance = InstanceInfo {
+        pid: std::process::id(),
+        socket: socket_path.as_deref().and_then(|p| std::path::absolute(p).ok()),
+        listen: listen_addr.clone(),
+        pipe: pipe_path.as_deref().and_then(|p| std::path::absolute(p).ok()),
+        signal_file: signal_file.as_deref().and_then(|p| std::path::absolute(p).ok()),
+    };
+    let assets = load_assets(&sprite_dirs, strict_assets)?;
+    let instance_file = send::instance_file_path(instance.pid);
+    let wrote_instance_file = instance.has_endpoint() && instance.write(&instance_file).is_ok();
+    
+    let sprite_watcher = watch_sprites.then(|| SpriteWatcher::spawn(Assets::source_paths(&sprite_dirs)));
+    let saved = if new_game || demo {
+        None
+    } else {
+        GameState::load(&save_path).map_err(|e| {
+            io::Error::new(e.kind(), format!("could not load saved game {}: {}", save_path.display(), e))
+        })?
+    };
+    
+    // A daemon draws for the terminals attached to it rather than its own
+    let mut daemon = match daemon_port {
+        Some(port) => Some(Daemon::bind(port, Arc::clone(&signal_received)).map_err(|e| {
+            io::Error::new(e.kind(), format!("could not listen on port {}: {}", port, e))
+        })?),
+        None => None,
+    };
+    
+    terminal::install_panic_hook();
+    terminal::install_signal_handlers();
+    let guard = match daemon {
+        Some(_) => None,
+        None => Some(TerminalGuard::new()?),
+    };
+    let out: Box<dyn Write> = match daemon {
+        Some(_) => Box::new(io::sink()),
+        None => Box::new(io::stdout()),
+    };
+    let mut terminal = match daemon {
+        Some(_) => Terminal::with_options(CrosstermBackend::new(out), TerminalOptions { viewport: Viewport::Fixed(daemon::SCENE_SIZE) })?,
+        None => Terminal::new(CrosstermBackend::new(out))?,
+    };
 
     let initial_size = match terminal.size() {
+        _ if daemon.is_some() => daemon::SCENE_SIZE,
         Ok(s) => Rect::new(0, 0, s.width, s.height),
         Err(_) => Rect::new(0, 0, 80, 24),
     };
-    let ocean_area = compute_ocean_area(initial_size);
-    let (_, lanes) = compute_fish_area(initial_size, ocean_area.y);
-
-    let mut fishes: Vec<Fish> = spawn_fishes(
-        &mut rng,
-        &per_species,
-        initial_size.width as f32,
-        lanes as usize,
-    );
-
-    let start = Instant::now();
-
-    let mut last_update = Instant::now();
-    let mut fisherman_kick = false;
-    let mut last_kick_toggle = Instant::now();
-    let kick_interval = Duration::from_millis(400);
+    if let Some(ref daemon) = daemon {
+        eprintln!("fisherman daemon listening on {}; watch and play it with `fisherman attach`", daemon.local_addr());
+    }
+    // While recording, signals pass through a second inbox on their way to the scene so
+    // each one is written down as it's delivered
+    let delivered: SharedSignal = match record_path {
+        Some(_) => Arc::new(Mutex::new(VecDeque::new())),
+        None => Arc::clone(&signal_received),
+    };
+    let header_saved = record_path.is_some().then(|| saved.clone()).flatten();
+    let config = Config {
+        signals: external_signals.then(|| Arc::clone(&delivered)),
+        sources,
+        on_signal,
+        timeout,
+        on_timeout,
+        lantern,
+        braille,
+        constellations,
+        smooth_motion,
+        ascii,
+        graphics,
+        reduce_motion,
+        status_bar,
+        theme,
+        skin,
+        dock,
+        spawning,
+        date,
+        season,
+        color_depth,
+        show_clock: timelapse.is_some(),
+        time_scale: timelapse.unwrap_or(1.0),
+        keys,
+        save_path: (!demo).then(|| save_path.clone()),
+        photo_dir,
+        items: item_table,
+        a11y,
+        seed,
+        demo,
+        attract_after,
+        key_release: guard.as_ref().is_some_and(TerminalGuard::key_release),
+        tail: tail_path.map(|path| LogTail::spawn(path, tail_lines)),
+        tasks: task_names,
+        hooks,
+        banners,
+        players,
+        link,
+        // The demo's rounds don't go in the high-score table
+        tournament: tournament_length
+            .map(|length| Tournament::new(length, (!demo).then(|| Tournament::scores_path_beside(&save_path)))),
+        metrics: metrics.clone(),
+        plugins: scene_plugins(&plugin_names, &script_paths, seed)?,
+        menu,
+        config_path: Some(config_path.clone()),
+    };
+    let key_release = config.key_release;
+    let mut app = App::new(config, assets, saved, initial_size);
+    
+    // A handoff is newer than the saved game: pick up exactly where the previous run left off
+    let mut restored = None;
+    if let Some(ref path) = handoff_path
+        && !new_game
+    {
+        match Handoff::load(path) {
+            Ok(Some(saved)) => {
+                restored = record_path.is_some().then(|| saved.clone());
+                app.restore(saved);
+            }
+            Ok(None) => {}
+            Err(e) => app.show_banner(format!("Could not restore {}: {}", path.display(), e)),
+        }
+    }
     
-    let mut last_spawn_check = Instant::now();
-    let spawn_check_interval = Duration::from_secs(3);
+    let mut recorder = match record_path {
+        Some(ref path) => {
+            let header = Header {
+                args: command_line,
+                seed: app.seed(),
+                width: initial_size.width,
+                height: initial_size.height,
+                key_release,
+                saved: header_saved,
+                handoff: restored,
+            };
+            Some(Recorder::create(path, &header).map_err(|e| {
+                io::Error::new(e.kind(), format!("could not create recording {}: {}", path.display(), e))
+            })?)
+        }
+        None => None,
+    };
     
-    let mut fishing_state = FishingState::Idle;
-    let mut cast_charge_start: Option<Instant> = None;
-    let max_cast_time = Duration::from_secs(2);
-    let mut cast_animation_start: Option<Instant> = None;
-    let cast_animation_duration = Duration::from_millis(800);
+    let mut cast = match cast_path {
+        Some(ref path) => Some(CastWriter::create(path, initial_size.width, initial_size.height).map_err(|e| {
+            io::Error::new(e.kind(), format!("could not create cast {}: {}", path.display(), e))
+        })?),
+        None => None,
+    };
     
-    let mut caught_fish: Option<fishing_game::CaughtFish> = None;
-    let mut catch_message_shown_at: Option<Instant> = None;
+    let broadcast = match broadcast_addr {
+        Some(ref addr) => Some(Broadcast::bind(addr).map_err(|e| {
+            io::Error::new(e.kind(), format!("could not broadcast on {}: {}", addr, e))
+        })?),
+        None => None,
+    };
     
-    let mut local_signal: Option<(bool, String)> = None;
+    let cargo = cargo_args.map(|cargo| CargoRun::spawn(cargo, app.species_names().to_vec(), Arc::clone(&signal_received)));
+    let watch = watch.map(|(command, paths)| CommandWatch::spawn(command, paths, Arc::clone(&signal_received)));
     
-    let sky_height = ocean_area.y;
-    let sky_area = Rect::new(0, 0, initial_size.width, sky_height);
-    let mut stars_widget = stars::Stars::new(&mut rng, sky_area, 0.02);
-    let mut last_window_size = (initial_size.width, initial_size.height);
+    #[cfg(feature = "sound")]
+    let sound = Sound::open(sound_settings);
     
+    let mut pacer = FramePacer::new(fps);
+    let mut profiler = profile.then(Profiler::default);
+    let started = Instant::now();
+    let mut last_update = started;
     loop {
-        let now = Instant::now();
-        let dt = now.duration_since(last_update);
-        last_update = now;
-        let elapsed = start.elapsed();
-        
-        // Check for signals from subprocess stdin, pipe, or signal file
-        if subprocess_mode || pipe_path.is_some() || signal_file.is_some() {
-            if let Ok(mut sig) = signal_received.lock() {
-                if sig.is_some() {
-                    local_signal = sig.take();
-                    fisherman_kick = local_signal.as_ref().map(|(success, _)| *success).unwrap_or(false);
-                }
+        let frame_start = Instant::now();
+        let dt = frame_start.duration_since(last_update);
+        if recorder.is_some() {
+            let arrived: Vec<_> = signal_received.lock().unwrap().drain(..).collect();
+            for message in arrived {
+                record(&mut recorder, &mut app, Entry::Signal(message.to_line()));
+                delivered.lock().unwrap().push_back(message);
             }
+            record(&mut recorder, &mut app, Entry::Update(dt));
         }
-
-        if now.duration_since(last_kick_toggle) >= kick_interval {
-            fisherman_kick = !fisherman_kick;
-            last_kick_toggle = now;
+        let update_start = Instant::now();
+        app.update(dt);
+        let update_took = update_start.elapsed();
+        last_update = frame_start;
+        #[cfg(feature = "sound")]
+        if let Some(ref sound) = sound {
+            app.take_events().into_iter().for_each(|event| sound.play(event));
         }
-        
-        stars_widget.update(elapsed);
-
-        if now.duration_since(last_spawn_check) >= spawn_check_interval {
-            last_spawn_check = now;
-            if let Ok(size) = terminal.size() {
-                let ocean_area = compute_ocean_area(Rect::new(0, 0, size.width, size.height));
-                let (_, lanes) = compute_fish_area(Rect::new(0, 0, size.width, size.height), ocean_area.y);
-                
-                let current_fish_count = fishes.len();
-                let target_fish_count = lanes as usize;
-                
-                if current_fish_count < target_fish_count {
-                    let mut new_fish = spawn_fishes(
-                        &mut rng,
-                        &per_species,
-                        size.width as f32,
-                        lanes as usize,
-                    );
-                    fishes.append(&mut new_fish);
-                }
-            }
+        if let Some(ref watcher) = sprite_watcher
+            && watcher.take_change()
+        {
+            app.reload_sprites(Assets::load_layered(&sprite_dirs));
+            app.show_banner("Sprites reloaded");
         }
-
-        if let Some(anim_start) = cast_animation_start {
-            let anim_elapsed = now.duration_since(anim_start);
-            if anim_elapsed < cast_animation_duration {
-                if let FishingState::Casting { start_x, start_y, target_x, progress: _ } = fishing_state {
-                    let new_progress = anim_elapsed.as_secs_f32() / cast_animation_duration.as_secs_f32();
-                    fishing_state = FishingState::Casting {
-                        start_x,
-                        start_y,
-                        target_x,
-                        progress: new_progress,
-                    };
-                }
-            } else {
-                if let FishingState::Casting { target_x, start_y, .. } = fishing_state {
-                    fishing_state = FishingState::Landed {
-                        landing_x: target_x,
-                        landing_y: start_y,
-                        depth: 0,
-                    };
+        if let Some(name) = app.take_pack_request() {
+            match pack::resolve(&name) {
+                Ok(dir) => {
+                    sprite_dirs = vec![dir, config_file::config_dir().join("sprites")];
+                    let assets = Assets::load_layered(&sprite_dirs);
+                    // A warning about missing sprites stays up instead
+                    let complete = assets.missing.is_empty();
+                    app.reload_sprites(assets);
+                    if complete {
+                        app.show_banner(format!("Pack {} loaded", name));
+                    }
                 }
-                cast_animation_start = None;
+                Err(e) => app.show_banner(e),
             }
         }
-
-        if let Some(charge_start) = cast_charge_start {
-            let charge_elapsed = now.duration_since(charge_start);
-            let power = (charge_elapsed.as_secs_f32() / max_cast_time.as_secs_f32()).min(1.0);
-            fishing_state = FishingState::Charging { power };
+        if app.should_quit() || terminal::interrupted() {
+            break;
         }
-
-        if !fishes.is_empty() {
-            if let Ok(size) = terminal.size() {
-                let width = size.width as f32;
-                for fish in fishes.iter_mut() {
-                    if elapsed.as_millis() < fish.spawn_delay_ms as u128 {
-                        continue;
-                    }
-                    fish.x += fish.vx * dt.as_secs_f32();
-                    
-                    let out_of_bounds = if fish.x > width {
-                        Some((width, 0.0))
-                    } else if fish.x < 0.0 {
-                        Some((0.0, width))
-                    } else {
-                        None
-                    };
-                    
-                    if let Some((clamp_pos, wrap_pos)) = out_of_bounds {
-                        if fish.wrap {
-                            fish.x = wrap_pos;
-                        } else {
-                            fish.x = clamp_pos;
-                            let (species_has_right, species_has_left) = 
-                                fish::species_has_directions(&per_species, fish.species);
-                            if species_has_left && species_has_right {
-                                fish.vx = -fish.vx;
-                                fish.facing_right = !fish.facing_right;
-                            }
-                        }
-                    }
-                }
-                
-                if let FishingState::Landed { landing_x, landing_y, depth } = fishing_state {
-                    let hook_x = landing_x;
-                    let hook_y = landing_y.saturating_add(depth);
-                    let ocean_area = compute_ocean_area(Rect::new(0, 0, size.width, size.height));
-                    let (fish_area, _) = compute_fish_area(Rect::new(0, 0, size.width, size.height), ocean_area.y);
-                    
-                    // Check each fish for collision
-                    for (i, fish) in fishes.iter().enumerate() {
-                        if elapsed.as_millis() < fish.spawn_delay_ms as u128 {
-                            continue;
-                        }
-                        
-                        let fish_y = fish_area.y + (fish.lane as u16 * fish::FISH_HEIGHT) + fish::FISH_HEIGHT / 2;
-                        let fish_width = 22; // Approximate fish width from CSV
-                        let fish_height = fish::FISH_HEIGHT;
-                        
-                        if fishing_game::check_collision(hook_x, hook_y, fish.x, fish_y, fish_width, fish_height) {
-                            // Fish caught!
-                            let species_name = if fish.species < species_list.len() {
-                                species_list[fish.species].name.clone()
-                            } else {
-                                "Unknown Fish".to_string()
-                            };
-                            
-                            caught_fish = Some(fishing_game::CaughtFish::new(species_name, fish.size));
-                            catch_message_shown_at = Some(now);
-                            
-                            fishes.remove(i);
-                            
-                            fishing_state = FishingState::Idle;
-                            break;
-                        }
-                    }
+        
+        let draw_start = Instant::now();
+        let frame = terminal.draw(|f| {
+            app.draw(f);
+            if let Some(ref profiler) = profiler {
+                f.render_widget(ProfileOverlay { profiler }, f.area());
+                if ascii {
+                    symbols::asciify(f.buffer_mut());
                 }
             }
+        })?;
+        let draw_took = draw_start.elapsed();
+        if let Some(ref broadcast) = broadcast {
+            broadcast.frame(frame.buffer);
         }
-
-        terminal.draw(|f| {
-            let size = f.area();
-            
-            let ocean_area = compute_ocean_area(size);
-            f.render_widget(Ocean, ocean_area);
-            
-            let sky_area = Rect::new(0, 0, size.width, ocean_area.y);
-            f.render_widget(stars_widget.clone(), sky_area);
-            
-            if let Some(ref moon) = moon_sprite {
-                let moon_x = 8;
-                let moon_y = 3;
-                let moon_area = Rect::new(moon_x, moon_y, 10, 7);
-                let moon_par = Paragraph::new(moon.clone()).block(Block::default());
-                f.render_widget(moon_par, moon_area);
+        if let Some(ref daemon) = daemon {
+            daemon.frame(frame.buffer);
+        }
+        if let Some(ref mut out) = cast
+            && let Err(e) = out.frame(frame.buffer, frame_start.duration_since(started))
+        {
+            app.show_banner(format!("Cast export stopped: {}", e));
+            cast = None;
+        }
+        if app.take_screenshot_request() {
+            let path = screenshot_path(plain_screenshots);
+            match save_screenshot(frame.buffer, &path, plain_screenshots) {
+                Ok(()) => app.show_banner(format!("Screenshot saved to {}", path.display())),
+                Err(e) => app.show_banner(format!("Could not save screenshot: {}", e)),
             }
-            
-            let dock_x = size.x.saturating_add(size.width.saturating_sub(DOCK_WIDTH));
-            let dock_y = ocean_area.y.saturating_sub(2);
-            let dock_area = Rect::new(dock_x - 1, dock_y, DOCK_WIDTH, DOCK_HEIGHT);
-            f.render_widget(FishermanDock { width: DOCK_WIDTH }, dock_area);
-            
-            let fisher_y = dock_area.y - 2;
-            let fisher_area = Rect::new(dock_x - (DOCK_WIDTH - 1), fisher_y, DOCK_WIDTH, FISHERMAN_HEIGHT);
-            let fisher = Fisherman { offset_from_right: 1, kick: fisherman_kick };
-            f.render_widget(fisher, fisher_area);
-            
-            if local_signal.is_some() {
-                let exclaim_x = dock_x - (DOCK_WIDTH / 2);
-                let exclaim_y = fisher_y.saturating_sub(1);
-                if exclaim_y < size.height {
-                    let exclaim_style = ratatui::style::Style::default()
-                        .fg(ratatui::style::Color::Yellow);
-                    f.buffer_mut().set_string(exclaim_x, exclaim_y, "!", exclaim_style);
-                }
+        }
+        pacer.set_ambient(&app.ambient_areas());
+        pacer.frame_drawn(frame.buffer, Instant::now());
+        app.flush_sprites(terminal.backend_mut())?;
+        if let Some(ref metrics) = metrics {
+            metrics.frame(frame_start.elapsed());
+        }
+        
+        let poll_start = Instant::now();
+        let timeout = pacer.timeout(frame_start, Instant::now());
+        let event = match daemon {
+            Some(ref mut daemon) => daemon.next_event(timeout),
+            None => event::poll(timeout)?.then(event::read).transpose()?,
+        };
+        if let Some(event) = event {
+            pacer.input(Instant::now());
+            if recorder.is_some() {
+                record(&mut recorder, &mut app, Entry::Event(event.clone()));
             }
-
-            let rod_tip_x = dock_x - 1 - 4 - 1;
-            let rod_tip_y = fisher_y.saturating_sub(4).saturating_add(2).saturating_sub(1);
-            let fishing_line = FishingLine::new(rod_tip_x, rod_tip_y).with_state(fishing_state);
-            f.render_widget(fishing_line, size);
-
-            let (fish_group_area, _) = compute_fish_area(size, ocean_area.y);
-            let ops = fish::compute_fish_render_ops(&fishes, fish_group_area, &per_species, elapsed);
-            for (rect, text) in ops.into_iter() {
-                let fish_par = Paragraph::new(text).block(Block::default());
-                f.render_widget(fish_par, rect);
+            // The scene takes the size of whichever terminal attached or resized last
+            if daemon.is_some()
+                && let Event::Resize(width, height) = event
+            {
+                terminal.resize(Rect::new(0, 0, width, height))?;
             }
-
-            if let Some(ref caught) = caught_fish {
-                // Show caught fish message
-                let message = caught.format_catch();
-                let catch_par = Paragraph::new(Text::from(message))
-                    .block(Block::default().title("Nice Catch!").borders(Borders::ALL))
-                    .style(ratatui::style::Style::default().fg(ratatui::style::Color::Green));
-                
-                // Center the message box
-                let msg_width = 40;
-                let msg_height = 6;
-                let msg_x = size.width.saturating_sub(msg_width) / 2;
-                let msg_y = size.height.saturating_sub(msg_height) / 2;
-                let msg_area = Rect::new(msg_x, msg_y, msg_width, msg_height);
-                f.render_widget(catch_par, msg_area);
-            } else {
-                let block = Block::default().title("Fisherman").borders(Borders::ALL);
-                f.render_widget(block, size);
+            app.handle_event(event);
+            if daemon.as_mut().is_some_and(Daemon::take_arrival) {
+                app.show_banner("Attached · h lists the signals so far · Ctrl+D detaches");
             }
-            
-            if let Some((is_success, ref message)) = local_signal {
-                let color = if is_success {
-                    ratatui::style::Color::Green
-                } else {
-                    ratatui::style::Color::Red
+            if app.should_quit() {
+                // Quitting from an attached terminal only detaches it
+                let Some(ref mut daemon) = daemon else {
+                    break;
                 };
-                let signal_par = Paragraph::new(Text::from(message.as_str()))
-                    .block(Block::default().borders(Borders::ALL))
-                    .style(ratatui::style::Style::default().fg(color))
-                    .alignment(ratatui::layout::Alignment::Center);
-                
-                // Position in the upper part of the sky
-                let msg_width = message.len().min(60) as u16 + 4;
-                let msg_height = 3;
-                let msg_x = size.width.saturating_sub(msg_width) / 2;
-                let msg_y = ocean_area.y / 3; // Upper third of sky
-                let msg_area = Rect::new(msg_x, msg_y, msg_width, msg_height);
-                f.render_widget(signal_par, msg_area);
+                daemon.detach_last();
+                app.stay_open();
             }
-        })?;
+        }
+        if let Some(ref mut profiler) = profiler {
+            profiler.record(FrameTimes { update: update_took, draw: draw_took, poll: poll_start.elapsed() });
+        }
+    }
 
-        if let Some(shown_at) = catch_message_shown_at {
-            if now.duration_since(shown_at) > Duration::from_secs(3) {
-                caught_fish = None;
-                catch_message_shown_at = None;
+    let exit_frame = match exit_screenshot {
+        Some(_) => Some(terminal.draw(|f| app.draw(f))?.buffer.clone()),
+        None => None,
+    };
+    if let Some(ref cargo) = cargo {
+        cargo.kill();
+    }
+    if let Some(ref watch) = watch {
+        watch.kill();
+    }
+    signal_sources.shutdown();
+    app.end_attract();
+    let handoff = handoff_path.as_ref().map(|path| (path, app.handoff().save(path)));
+    
+    drop(guard);
+    
+    if let Some((path, Err(e))) = handoff {
+        eprintln!("Could not write handoff {}: {}", path.display(), e);
+    }
+    
+    if let Some(recorder) = recorder
+        && let Err(e) = recorder.finish()
+    {
+        eprintln!("Could not finish recording: {}", e);
+    }
+    if let (Some(path), Some(buffer)) = (&exit_screenshot, &exit_frame)
+        && let Err(e) = save_screenshot(buffer, path, plain_screenshots)
+    {
+        eprintln!("Could not save screenshot {}: {}", path.display(), e);
+    }
+    if let Some(cast) = cast
+        && let Err(e) = cast.finish()
+    {
+        eprintln!("Could not finish cast: {}", e);
+    }
+    
+    if timelapse.is_some() {
+        println!("{} waited", app.game().clock.describe_elapsed());
+    }
+    if let Some(format) = summary_format {
+        println!("{}", stats::summary(app.session_stats(), app.elapsed(), app.last_outcome(), format));
+    }
+    
+    if !demo
+        && let Err(e) = app.game().save(&save_path, app.species_names())
+    {
+        eprintln!("Could not save game {}: {}", save_path.display(), e);
+    }
+    
+    if let Some(ref path) = socket_path {
+        let _ = std::fs::remove_file(path);
+    }
+    if let Some(a11y) = app.a11y() {
+        a11y.cleanup();
+    }
+    if created_fifo && let Some(ref path) = pipe_path {
+        let _ = std::fs::remove_file(path);
+    }
+    for path in &task_fifos {
+        let _ = std::fs::remove_file(path);
+    }
+    if wrote_instance_file {
+        let _ = std::fs::remove_file(&instance_file);
+    }
+    
+    // Let shell pipelines chain on the result: `long_task | fisherman --subprocess && deploy`
+    if app.timed_out() {
+        std::process::exit(watchdog::TIMEOUT_EXIT_CODE);
+    }
+    if app.last_outcome() == Some(false) {
+        std::process::exit(fail_exit_code);
+    }
+    Ok(())
+}
+
+/// Write `entry` to the recording, or stop recording if that fails.
+fn record(recorder: &mut Option<Recorder>, app: &mut App, entry: Entry) {
+    if let Some(ref mut out) = *recorder
+        && let Err(e) = out.record(&entry)
+    {
+        app.show_banner(format!("Recording stopped: {}", e));
+        *recorder = None;
+    }
+}
+
+/// The sprites laid over from `dirs`, or with `strict` an error naming any that
+/// couldn't be loaded.
+fn load_assets(dirs: &[PathBuf], strict: bool) -> io::Result<Assets> {
+    let assets = Assets::load_layered(dirs);
+    match assets.warning() {
+        Some(warning) if strict => Err(io::Error::new(io::ErrorKind::NotFound, warning)),
+        _ => Ok(assets),
+    }
+}
+
+/// The plugins called `names` and the `scripts`, each made from a seed of its own
+/// following `seed`.
+fn scene_plugins(names: &[String], scripts: &[PathBuf], seed: Option<u64>) -> io::Result<Vec<Box<dyn ScenePlugin>>> {
+    let registry = PluginRegistry::builtin();
+    let seed = seed.unwrap_or_else(rand::random);
+    let mut plugins = names.iter()
+        .enumerate()
+        .map(|(i, name)| registry.create(name, seed.wrapping_add(i as u64)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    for (i, path) in scripts.iter().enumerate() {
+        plugins.push(script(path, seed.wrapping_add((names.len() + i) as u64))?);
+    }
+    Ok(plugins)
+}
+
+#[cfg(feature = "scripting")]
+fn script(path: &Path, seed: u64) -> io::Result<Box<dyn ScenePlugin>> {
+    match Script::load(path, seed) {
+        Ok(script) => Ok(Box::new(script)),
+        Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+fn script(path: &Path, _seed: u64) -> io::Result<Box<dyn ScenePlugin>> {
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("--script {}: this build has no scripting (build it with --features scripting)", path.display()),
+    ))
+}
+
+/// Where `F12` saves a screenshot: a new file in the current directory.
+fn screenshot_path(plain: bool) -> PathBuf {
+    let secs = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |t| t.as_secs());
+    let extension = if plain { "txt" } else { "ans" };
+    let mut path = PathBuf::from(format!("fisherman-{}.{}", secs, extension));
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = PathBuf::from(format!("fisherman-{}-{}.{}", secs, n, extension));
+    }
+    path
+}
+
+/// Write the frame in `buffer` to `path` as plain text, or with its colours as ANSI escapes.
+fn save_screenshot(buffer: &Buffer, path: &std::path::Path, plain: bool) -> io::Result<()> {
+    let text = if plain { headless::buffer_text(buffer) } else { headless::buffer_ansi(buffer) };
+    std::fs::write(path, text)
+}
+
+/// `fisherman replay`: step a fresh scene through a recording at the pace it was
+/// recorded, until it runs out or q, Esc or Ctrl+C stops it.
+fn play_back(recording: Recording, config: Config, assets: Assets, inbox: &SharedSignal) -> io::Result<()> {
+    let Recording { header, entries } = recording;
+    let area = Rect::new(0, 0, header.width, header.height);
+    terminal::install_panic_hook();
+    terminal::install_signal_handlers();
+    let _guard = TerminalGuard::new()?;
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(io::stdout()),
+        TerminalOptions { viewport: Viewport::Fixed(area) },
+    )?;
+    let mut app = App::new(config, assets, header.saved, area);
+    if let Some(saved) = header.handoff {
+        app.restore(saved);
+    }
+    
+    let mut entries = entries.into_iter();
+    loop {
+        // Play up to the next update, then leave its frame up for as long as it lasted
+        let mut dt = None;
+        for entry in entries.by_ref() {
+            if let Entry::Event(Event::Resize(width, height)) = entry {
+                terminal.resize(Rect::new(0, 0, width, height))?;
+            }
+            entry.apply(&mut app, Some(inbox));
+            if let Entry::Update(d) = entry {
+                dt = Some(d);
+                break;
             }
         }
-
-        if local_signal.is_some() {
-            thread::sleep(Duration::from_secs(3));
+        terminal.draw(|f| app.draw(f))?;
+        let Some(dt) = dt else { break };
+        if app.should_quit() || stop_requested(dt)? {
+            return Ok(());
+        }
+    }
+    
+    app.show_banner("Replay finished · press any key");
+    terminal.draw(|f| app.draw(f))?;
+    while !terminal::interrupted() {
+        if event::poll(Duration::from_millis(100))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
             break;
         }
-        
-        if event::poll(Duration::from_millis(50))? {
-            match event::read()? {
-                Event::Resize(width, height) => {
-                    if (width, height) != last_window_size {
-                        last_window_size = (width, height);
-                        let new_size = Rect::new(0, 0, width, height);
-                        let ocean_area = compute_ocean_area(new_size);
-                        let sky_height = ocean_area.y;
-                        let sky_area = Rect::new(0, 0, width, sky_height);
-                        stars_widget = stars::Stars::new(&mut rng, sky_area, 0.02);
-                        stars_widget.update(elapsed);
-                    }
-                }
-                Event::Key(key) => {
-                match key.code {
-                    KeyCode::Char('q') => break,
-                    KeyCode::Char(' ') => {
-                        match key.kind {
-                            event::KeyEventKind::Press => {
-                                if matches!(fishing_state, FishingState::Idle) {
-                                    cast_charge_start = Some(now);
-                                } else if let FishingState::Charging { power } = fishing_state {
-                                    // On Linux, key release may not fire, so allow pressing space again to cast
-                                    if let Ok(size) = terminal.size() {
-                                        let screen_width = size.width;
-                                        let ocean_area = compute_ocean_area(Rect::new(0, 0, size.width, size.height));
-                                        let rod_tip_x = screen_width.saturating_sub(DOCK_WIDTH)
-                                            .saturating_sub(1)
-                                            .saturating_sub(4)
-                                            .saturating_sub(1);
-                                        let dock_y = ocean_area.y.saturating_sub(2);
-                                        let _rod_tip_y = dock_y.saturating_sub(2).saturating_sub(4).saturating_add(2).saturating_sub(1);
-                                        
-                                        let max_distance = (screen_width as f32 * 0.7) as u16;
-                                        let cast_distance = (max_distance as f32 * power) as u16;
-                                        let target_x = rod_tip_x.saturating_sub(cast_distance.max(10));
-                                        let landing_y = ocean_area.y;
-                                        
-                                        fishing_state = FishingState::Casting {
-                                            start_x: rod_tip_x,
-                                            start_y: landing_y,
-                                            target_x,
-                                            progress: 0.0,
-                                        };
-                                        cast_animation_start = Some(now);
-                                    }
-                                    cast_charge_start = None;
-                                }
-                            }
-                            event::KeyEventKind::Release => {
-                                if let FishingState::Charging { power } = fishing_state {
-                                    if let Ok(size) = terminal.size() {
-                                        let screen_width = size.width;
-                                        let ocean_area = compute_ocean_area(Rect::new(0, 0, size.width, size.height));
-                                        let rod_tip_x = screen_width.saturating_sub(DOCK_WIDTH)
-                                            .saturating_sub(1)
-                                            .saturating_sub(4)
-                                            .saturating_sub(1);
-                                        let dock_y = ocean_area.y.saturating_sub(2);
-                                        let _rod_tip_y = dock_y.saturating_sub(2).saturating_sub(4).saturating_add(2).saturating_sub(1);
-                                        
-                                        let max_distance = (screen_width as f32 * 0.7) as u16;
-                                        let cast_distance = (max_distance as f32 * power) as u16;
-                                        let target_x = rod_tip_x.saturating_sub(cast_distance.max(10));
-                                        let landing_y = ocean_area.y;
-                                        
-                                        fishing_state = FishingState::Casting {
-                                            start_x: rod_tip_x,
-                                            start_y: landing_y,
-                                            target_x,
-                                            progress: 0.0,
-                                        };
-                                        cast_animation_start = Some(now);
-                                    }
-                                    cast_charge_start = None;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                    KeyCode::Down => {
-                        if let FishingState::Landed { landing_x, landing_y, depth } = fishing_state {
-                            let max_depth = terminal.size().map(|s| s.height.saturating_sub(landing_y)).unwrap_or(30);
-                            fishing_state = FishingState::Landed {
-                                landing_x,
-                                landing_y,
-                                depth: depth.saturating_add(1).min(max_depth),
-                            };
-                        }
-                    }
-                    KeyCode::Up => {
-                        if let FishingState::Landed { landing_x, landing_y, depth } = fishing_state {
-                            if depth == 0 {
-                                fishing_state = FishingState::Idle;
-                            } else {
-                                fishing_state = FishingState::Landed {
-                                    landing_x,
-                                    landing_y,
-                                    depth: depth.saturating_sub(1),
-                                };
-                            }
-                        }
-                    }
-                    KeyCode::Char('s') => {
-                        // Test signal: SUCCESS (works when not using external signals)
-                        if !subprocess_mode && pipe_path.is_none() && signal_file.is_none() {
-                            local_signal = Some((true, "Success! Task completed.".to_string()));
-                            fisherman_kick = true;
-                        }
-                    }
-                    KeyCode::Char('f') => {
-                        // Test signal: FAILURE (works when not using external signals)
-                        if !subprocess_mode && pipe_path.is_none() && signal_file.is_none() {
-                            local_signal = Some((false, "Failed! Please try again.".to_string()));
-                            fisherman_kick = false;
-                        }
-                    }
-                    _ => {}
-                }
-                }
-                _ => {}
-            }
+    }
+    Ok(())
+}
+
+/// Wait out `timeout`, or until q, Esc or Ctrl+C asks for the replay to stop.
+fn stop_requested(timeout: Duration) -> io::Result<bool> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if terminal::interrupted() {
+            return Ok(true);
+        }
+        if !event::poll(deadline.saturating_duration_since(Instant::now()))? {
+            return Ok(false);
+        }
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && (matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                || key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+        {
+            return Ok(true);
         }
     }
+}
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-    Ok(())
+/// A `--theme` that isn't a built-in name: the TOML file it names.
+fn load_theme(path: &str) -> io::Result<Theme> {
+    let names: Vec<&str> = Theme::BUILT_IN.iter().map(|(name, _)| *name).collect();
+    let file = ConfigFile::load(path.as_ref())
+        .map_err(|e| io::Error::new(e.kind(), format!("could not load --theme {}: {}", path, e)))?
+        .ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("unknown --theme {}: not one of {} or a theme file", path, names.join(", ")),
+        )
+    })?;
+    // Top-level keys, or a [theme] section like the one in the config file
+    let table = file.section("theme").or(file.section("")).cloned().unwrap_or_default();
+    Theme::from_table(&table)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("invalid --theme {}: {}", path, e)))
 }
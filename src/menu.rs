@@ -0,0 +1,253 @@
+//! The screens around the game: the title menu shown on launch, the settings screen
+//! and the [`Screen`] the app is on. What the settings screen changes is written back
+//! to the config file, the theme as `base` in `[theme]` and the rest in `[settings]`:
+//!
+//! ```toml
+//! [settings]
+//! reduce_motion = false
+//! status_bar = true
+//! lantern = true
+//! braille = false
+//! constellations = false
+//! ```
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use crate::config_file::{Table, Value};
+
+/// What the app is showing, and where its keys go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    Title,
+    Playing,
+    /// The achievements, from the title menu or the game.
+    Journal,
+    Settings,
+    /// A tournament round is over and wants a name to go down under.
+    EnterName,
+    /// A tournament round is over and its scoreboard is up.
+    GameOver,
+    /// The best tournament rounds, from the title menu or after the scoreboard.
+    Leaderboard,
+}
+
+/// The `[settings]` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub reduce_motion: bool,
+    pub status_bar: bool,
+    pub lantern: bool,
+    pub braille: bool,
+    pub constellations: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings { reduce_motion: false, status_bar: true, lantern: true, braille: false, constellations: false }
+    }
+}
+
+impl Settings {
+    pub fn from_table(table: &Table) -> Result<Settings, String> {
+        let mut settings = Settings::default();
+        for (key, value) in table {
+            let slot = match key.as_str() {
+                "reduce_motion" => &mut settings.reduce_motion,
+                "status_bar" => &mut settings.status_bar,
+                "lantern" => &mut settings.lantern,
+                "braille" => &mut settings.braille,
+                "constellations" => &mut settings.constellations,
+                key => return Err(format!("unknown setting '{}' in [settings]", key)),
+            };
+            let Value::Bool(on) = value else {
+                return Err(format!("[settings] {} must be true or false", key));
+            };
+            *slot = *on;
+        }
+        Ok(settings)
+    }
+}
+
+/// A choice on the title menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleChoice {
+    Continue,
+    NewGame,
+    Journal,
+    HighScores,
+    Settings,
+    Quit,
+}
+
+impl TitleChoice {
+    /// The choices on offer, with Continue only when there's a saved game to go on with.
+    pub fn offered(can_continue: bool) -> Vec<TitleChoice> {
+        let all = [
+            TitleChoice::Continue,
+            TitleChoice::NewGame,
+            TitleChoice::Journal,
+            TitleChoice::HighScores,
+            TitleChoice::Settings,
+            TitleChoice::Quit,
+        ];
+        all.into_iter().filter(|choice| can_continue || *choice != TitleChoice::Continue).collect()
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TitleChoice::Continue => "Continue",
+            TitleChoice::NewGame => "New game",
+            TitleChoice::Journal => "Journal",
+            TitleChoice::HighScores => "High scores",
+            TitleChoice::Settings => "Settings",
+            TitleChoice::Quit => "Quit",
+        }
+    }
+}
+
+/// A line on the settings screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsField {
+    Theme,
+    ReduceMotion,
+    StatusBar,
+    Lantern,
+    Braille,
+    Constellations,
+    Back,
+}
+
+impl SettingsField {
+    pub const ALL: [SettingsField; 7] = [
+        SettingsField::Theme,
+        SettingsField::ReduceMotion,
+        SettingsField::StatusBar,
+        SettingsField::Lantern,
+        SettingsField::Braille,
+        SettingsField::Constellations,
+        SettingsField::Back,
+    ];
+
+    /// Where the field is kept in the config file, as section and key.
+    pub fn key(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            SettingsField::Theme => Some(("theme", "base")),
+            SettingsField::ReduceMotion => Some(("settings", "reduce_motion")),
+            SettingsField::StatusBar => Some(("settings", "status_bar")),
+            SettingsField::Lantern => Some(("settings", "lantern")),
+            SettingsField::Braille => Some(("settings", "braille")),
+            SettingsField::Constellations => Some(("settings", "constellations")),
+            SettingsField::Back => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SettingsField::Theme => "Theme",
+            SettingsField::ReduceMotion => "Reduce motion",
+            SettingsField::StatusBar => "Status bar",
+            SettingsField::Lantern => "Lantern",
+            SettingsField::Braille => "Braille lines",
+            SettingsField::Constellations => "Constellations",
+            SettingsField::Back => "Back",
+        }
+    }
+}
+
+/// A box of lines centred in `area`, `width` wide.
+pub(crate) fn render_box(lines: Vec<Line>, title: &str, width: u16, area: Rect, buf: &mut Buffer) {
+    let width = width.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let rect = Rect::new(
+        area.x + area.width.saturating_sub(width) / 2,
+        area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    );
+    Clear.render(rect, buf);
+    Paragraph::new(lines)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .render(rect, buf);
+}
+
+/// `label`, marked and highlighted if it's the line selected.
+fn menu_line(label: String, selected: bool) -> Line<'static> {
+    if selected {
+        Line::from(Span::styled(format!(" ▸ {}", label), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)))
+    } else {
+        Line::from(format!("   {}", label))
+    }
+}
+
+/// The title menu, centred over the scene.
+pub struct TitleMenu<'a> {
+    pub choices: &'a [TitleChoice],
+    pub selected: usize,
+}
+
+impl Widget for TitleMenu<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(Span::styled("  ><(((º>  FISHERMAN  <º)))><", Style::default().fg(Color::Cyan))),
+            Line::from(""),
+        ];
+        for (i, choice) in self.choices.iter().enumerate() {
+            lines.push(menu_line(choice.label().to_string(), i == self.selected));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("  ↑↓ choose · Enter select", Style::default().fg(Color::DarkGray))));
+        render_box(lines, "", 34, area, buf);
+    }
+}
+
+/// The settings screen, centred over the scene.
+pub struct SettingsMenu<'a> {
+    pub theme: &'a str,
+    pub settings: Settings,
+    pub selected: usize,
+}
+
+impl Widget for SettingsMenu<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        let mut lines = Vec::new();
+        for (i, field) in SettingsField::ALL.into_iter().enumerate() {
+            let value = match field {
+                SettingsField::Theme => format!("◂ {} ▸", self.theme),
+                SettingsField::ReduceMotion => on_off(self.settings.reduce_motion).to_string(),
+                SettingsField::StatusBar => on_off(self.settings.status_bar).to_string(),
+                SettingsField::Lantern => on_off(self.settings.lantern).to_string(),
+                SettingsField::Braille => on_off(self.settings.braille).to_string(),
+                SettingsField::Constellations => on_off(self.settings.constellations).to_string(),
+                SettingsField::Back => String::new(),
+            };
+            lines.push(menu_line(format!("{:<15}{}", field.label(), value), i == self.selected));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("  ←→ / Enter change · Esc back", Style::default().fg(Color::DarkGray))));
+        render_box(lines, "Settings", 36, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+
+    #[test]
+    fn settings_come_from_the_config_and_continue_needs_a_save() {
+        let config = ConfigFile::parse("[settings]\nlantern = true\nstatus_bar = false").unwrap();
+        let settings = Settings::from_table(config.section("settings").unwrap()).unwrap();
+        assert_eq!(settings, Settings { reduce_motion: false, status_bar: false, lantern: true, braille: false, constellations: false });
+        let bad = ConfigFile::parse("[settings]\nlantern = 1").unwrap();
+        assert!(Settings::from_table(bad.section("settings").unwrap()).is_err());
+
+        assert_eq!(TitleChoice::offered(false)[0], TitleChoice::NewGame);
+        assert_eq!(TitleChoice::offered(true)[0], TitleChoice::Continue);
+    }
+}
@@ -0,0 +1,118 @@
+//! Counters for `GET /metrics` on the `--listen` endpoint, in the Prometheus text
+//! format, so a fisherman left running on a build box can be watched like any other
+//! service: signals received by status, fish landed by species, uptime and how long
+//! frames take.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct Counters {
+    started: Instant,
+    signals: BTreeMap<&'static str, u64>,
+    catches: BTreeMap<String, u64>,
+    frames: u64,
+    frame_time: Duration,
+}
+
+/// The counters, shared between the game, which counts, and the HTTP source, which
+/// reports them.
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    counters: Arc<Mutex<Counters>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics::new()
+    }
+}
+
+impl Metrics {
+    /// Counters starting from nothing, with the uptime counted from now.
+    pub fn new() -> Metrics {
+        let counters = Counters {
+            started: Instant::now(),
+            signals: BTreeMap::new(),
+            catches: BTreeMap::new(),
+            frames: 0,
+            frame_time: Duration::ZERO,
+        };
+        Metrics { counters: Arc::new(Mutex::new(counters)) }
+    }
+
+    /// A signal received, by its status (`success`, `progress`, ...).
+    pub fn signal(&self, status: &'static str) {
+        *self.counters.lock().unwrap().signals.entry(status).or_default() += 1;
+    }
+
+    /// A fish landed.
+    pub fn catch(&self, species: &str) {
+        *self.counters.lock().unwrap().catches.entry(species.to_string()).or_default() += 1;
+    }
+
+    /// A frame updated and drawn in `took`.
+    pub fn frame(&self, took: Duration) {
+        let mut counters = self.counters.lock().unwrap();
+        counters.frames += 1;
+        counters.frame_time += took;
+    }
+
+    /// The counters in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let counters = self.counters.lock().unwrap();
+        let mut out = String::new();
+        // Writing to a String can't fail
+        let _ = writeln!(out, "# HELP fisherman_signals_received_total Signals received, by status.");
+        let _ = writeln!(out, "# TYPE fisherman_signals_received_total counter");
+        for (status, count) in &counters.signals {
+            let _ = writeln!(out, "fisherman_signals_received_total{{status=\"{}\"}} {}", status, count);
+        }
+        let _ = writeln!(out, "# HELP fisherman_catches_total Fish landed, by species.");
+        let _ = writeln!(out, "# TYPE fisherman_catches_total counter");
+        for (species, count) in &counters.catches {
+            let _ = writeln!(out, "fisherman_catches_total{{species=\"{}\"}} {}", label(species), count);
+        }
+        let _ = writeln!(out, "# HELP fisherman_uptime_seconds Seconds since the game started.");
+        let _ = writeln!(out, "# TYPE fisherman_uptime_seconds gauge");
+        let _ = writeln!(out, "fisherman_uptime_seconds {:.3}", counters.started.elapsed().as_secs_f64());
+        let _ = writeln!(out, "# HELP fisherman_frame_time_seconds Time spent updating and drawing frames.");
+        let _ = writeln!(out, "# TYPE fisherman_frame_time_seconds summary");
+        let _ = writeln!(out, "fisherman_frame_time_seconds_sum {:.6}", counters.frame_time.as_secs_f64());
+        let _ = writeln!(out, "fisherman_frame_time_seconds_count {}", counters.frames);
+        out
+    }
+}
+
+/// `value` escaped for a label: backslashes, quotes and newlines.
+fn label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_come_out_in_the_prometheus_format() {
+        let metrics = Metrics::new();
+        metrics.signal("success");
+        metrics.signal("success");
+        metrics.signal("failure");
+        metrics.catch("Trout");
+        metrics.catch("Big \"Al\"");
+        metrics.frame(Duration::from_millis(4));
+        metrics.frame(Duration::from_millis(6));
+
+        let text = metrics.clone().render();
+        assert!(text.contains("fisherman_signals_received_total{status=\"success\"} 2\n"));
+        assert!(text.contains("fisherman_signals_received_total{status=\"failure\"} 1\n"));
+        assert!(text.contains("fisherman_catches_total{species=\"Trout\"} 1\n"));
+        assert!(text.contains("fisherman_catches_total{species=\"Big \\\"Al\\\"\"} 1\n"));
+        assert!(text.contains("fisherman_frame_time_seconds_sum 0.010000\n"));
+        assert!(text.contains("fisherman_frame_time_seconds_count 2\n"));
+        assert!(text.contains("# TYPE fisherman_uptime_seconds gauge\nfisherman_uptime_seconds "));
+    }
+}
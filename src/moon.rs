@@ -0,0 +1,110 @@
+//! The moon: which phase it is in and where it hangs in the night sky. Phases are drawn
+//! by shading the unlit part of the one moon sprite rather than from a sprite per phase.
+
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span, Text};
+use unicode_width::UnicodeWidthStr;
+
+use crate::csv_frames;
+
+/// Days from one new moon to the next.
+pub const LUNAR_CYCLE_DAYS: f64 = 29.53;
+/// Colour of the part of the moon in shadow.
+const SHADOW: Color = Color::DarkGray;
+
+/// How far through its cycle the moon is after `days`: 0.0 at new moon, 0.5 at full.
+pub fn phase(days: f64) -> f32 {
+    (days / LUNAR_CYCLE_DAYS).rem_euclid(1.0) as f32
+}
+
+/// Name of the phase, for the accessibility channel and status lines.
+pub fn phase_name(phase: f32) -> &'static str {
+    const NAMES: [&str; 8] = [
+        "new moon",
+        "waxing crescent",
+        "first quarter",
+        "waxing gibbous",
+        "full moon",
+        "waning gibbous",
+        "last quarter",
+        "waning crescent",
+    ];
+    NAMES[((phase.rem_euclid(1.0) * 8.0 + 0.5) as usize) % 8]
+}
+
+/// `moon` with the part out of the sunlight at `phase` shaded. The light grows from
+/// the right while the moon waxes and shrinks to the left while it wanes.
+pub fn shade(moon: &Text<'static>, phase: f32) -> Text<'static> {
+    let width = moon.width().max(1) as f32;
+    // Share of the disc that is lit, from 0.0 at new moon to 1.0 at full
+    let lit = (1.0 - (phase * std::f32::consts::TAU).cos()) / 2.0;
+    let waxing = phase < 0.5;
+    let lines = moon.lines.iter().map(|line| {
+        let mut column = 0;
+        let spans = csv_frames::line_cells(line).into_iter().map(|(symbol, style)| {
+            // Across the disc from -1.0 on the left edge to 1.0 on the right
+            let across = (column as f32 + 0.5) / width * 2.0 - 1.0;
+            column += symbol.width();
+            let in_light = if waxing { across > 1.0 - 2.0 * lit } else { across < 2.0 * lit - 1.0 };
+            // Gaps around the disc stay gaps, for the stars to show through
+            let shaded = in_light || csv_frames::is_gap(&symbol, style);
+            Span::styled(symbol, if shaded { style } else { style.patch(Style::default().fg(SHADOW)) })
+        });
+        Line::from(spans.collect::<Vec<_>>())
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
+
+/// Where a moon of `size` hangs in `sky` at `time_of_day` (0.0 midnight, 0.5 noon):
+/// rising on the left at dusk, highest at midnight and setting on the right at dawn.
+pub fn position(sky: Rect, size: (u16, u16), time_of_day: f32) -> Rect {
+    let (width, height) = (size.0.min(sky.width), size.1.min(sky.height));
+    // How far through the night it is, from 18:00 to 06:00
+    let night = ((time_of_day - 0.75).rem_euclid(1.0) / 0.5).clamp(0.0, 1.0);
+    let x = sky.x + (night * sky.width.saturating_sub(width) as f32).round() as u16;
+    let drop = sky.height.saturating_sub(height + 2) as f32;
+    let y = sky.y + 1 + ((1.0 - (night * std::f32::consts::PI).sin()) * drop).round() as u16;
+    Rect::new(x, y.min(sky.bottom().saturating_sub(height)), width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::buffer::Buffer;
+
+    fn symbols_in_light(text: &Text) -> String {
+        text.lines[0].spans.iter().map(|s| if s.style.fg == Some(SHADOW) { "." } else { "#" }).collect()
+    }
+
+    #[test]
+    fn phases_shade_the_dark_side() {
+        let moon = Text::from(Line::styled("OOOOOOOO", Style::default().fg(Color::White)));
+        assert_eq!(symbols_in_light(&shade(&moon, 0.0)), "........");
+        assert_eq!(symbols_in_light(&shade(&moon, 0.25)), "....####");
+        assert_eq!(symbols_in_light(&shade(&moon, 0.5)), "########");
+        assert_eq!(symbols_in_light(&shade(&moon, 0.75)), "####....");
+        assert_eq!(phase_name(phase(LUNAR_CYCLE_DAYS / 2.0)), "full moon");
+
+        // The gaps around a disc are left for the stars to show through, even in shadow
+        let white = Style::default().fg(Color::White);
+        let moon = Text::from(Line::from(vec![Span::raw(" "), Span::styled("OO", white), Span::raw(" ")]));
+        let shaded = shade(&moon, 0.0);
+        assert_eq!(symbols_in_light(&shaded), "#..#");
+        let area = Rect::new(0, 0, 4, 1);
+        let mut sky = Buffer::filled(area, ratatui::buffer::Cell::new("*"));
+        csv_frames::SpriteFrame::bake(&shaded).blit(&mut sky, (0, 0), 1, area, &[]);
+        assert_eq!((0..4).map(|x| sky[(x, 0)].symbol()).collect::<String>(), "*OO*");
+    }
+
+    #[test]
+    fn the_moon_crosses_the_sky_overnight() {
+        let sky = Rect::new(0, 0, 80, 20);
+        let dusk = position(sky, (10, 7), 0.75);
+        let midnight = position(sky, (10, 7), 0.0);
+        let dawn = position(sky, (10, 7), 0.25);
+        assert_eq!((dusk.x, midnight.x, dawn.x), (0, 35, 70));
+        assert!(midnight.y < dusk.y && midnight.y == 1);
+        assert!(dawn.bottom() <= sky.bottom());
+    }
+}
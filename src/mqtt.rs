@@ -0,0 +1,300 @@
+//! An MQTT signal source (`--mqtt broker:1883 --topic builds/#`): subscribe to a topic
+//! on a broker and turn what's published there into signals, so a home-automation hub
+//! or a sensor can make the fisherman react. Only as much of MQTT 3.1.1 as a subscriber
+//! at QoS 0 needs is spoken, so no client library is pulled in for it.
+//!
+//! A payload may be any signal line (`SUCCESS:deployed`, a JSON command and so on).
+//! Otherwise a plain word or number is mapped, with the topic as the message:
+//!
+//! | Payload | Signal |
+//! |---------|--------|
+//! | `success`, `ok`, `pass`, `done`, `on`, `true` | SUCCESS |
+//! | `failure`, `fail`, `error`, `off`, `false` | FAILURE |
+//! | `0`-`100`, with or without `%` | PROGRESS |
+
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::signals::{SignalMessage, SignalSink, SignalSource, Waker};
+
+/// The broker's port unless one is given.
+pub const DEFAULT_PORT: u16 = 1883;
+/// The topic subscribed to unless `--topic` says otherwise.
+pub const DEFAULT_TOPIC: &str = "fisherman/#";
+/// Seconds the broker waits without hearing from us before dropping the connection; a
+/// ping goes out well before.
+const KEEP_ALIVE: u16 = 60;
+const PING_EVERY: Duration = Duration::from_secs(30);
+/// How often a blocked read looks up to see whether to ping or stop.
+const POLL: Duration = Duration::from_millis(500);
+/// How long to wait before connecting again after losing the broker.
+const RECONNECT_WAIT: Duration = Duration::from_secs(5);
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PUBACK: u8 = 0x40;
+const SUBSCRIBE: u8 = 0x82;
+const SUBACK: u8 = 0x90;
+const PINGREQ: u8 = 0xc0;
+const DISCONNECT: u8 = 0xe0;
+
+/// `host:port` for `--mqtt`, adding the default port and dropping an `mqtt://` in front.
+pub fn parse_broker(s: &str) -> Result<String, String> {
+    let addr = s.strip_prefix("mqtt://").unwrap_or(s).trim_end_matches('/');
+    if addr.is_empty() {
+        return Err(format!("invalid --mqtt '{}' (expected host or host:port)", s));
+    }
+    match addr.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(addr.to_string()),
+        Some(_) if !addr.starts_with('[') => Err(format!("invalid --mqtt '{}' (expected host or host:port)", s)),
+        _ => Ok(format!("{}:{}", addr, DEFAULT_PORT)),
+    }
+}
+
+/// Signals published on `topic` at the broker at `broker`, connecting again whenever
+/// the connection drops.
+pub struct MqttSource {
+    pub broker: String,
+    pub topic: String,
+    /// The connection open, so stopping can break a read waiting on it.
+    connection: Arc<Mutex<Option<TcpStream>>>,
+}
+
+impl MqttSource {
+    pub fn new(broker: String, topic: String) -> MqttSource {
+        MqttSource { broker, topic, connection: Arc::default() }
+    }
+
+    /// Connect, subscribe and deliver what's published until the connection drops or
+    /// the sink is stopped.
+    fn session(&self, sink: &SignalSink) -> io::Result<()> {
+        let mut stream = TcpStream::connect(&self.broker)?;
+        stream.set_read_timeout(Some(RECONNECT_WAIT))?;
+        *self.connection.lock().unwrap() = Some(stream.try_clone()?);
+
+        let client_id = format!("fisherman-{}", std::process::id());
+        let mut connect = Vec::new();
+        put_string(&mut connect, "MQTT");
+        // Protocol level 4 (3.1.1), a clean session and the keep-alive
+        connect.extend([4, 0x02]);
+        connect.extend(KEEP_ALIVE.to_be_bytes());
+        put_string(&mut connect, &client_id);
+        write_packet(&mut stream, CONNECT, &connect)?;
+        let (kind, body) = read_packet(&mut stream)?;
+        if kind & 0xf0 != CONNACK || body.get(1) != Some(&0) {
+            return Err(io::Error::other(format!("broker refused the connection (code {:?})", body.get(1))));
+        }
+
+        let mut subscribe = vec![0, 1];
+        put_string(&mut subscribe, &self.topic);
+        subscribe.push(0);
+        write_packet(&mut stream, SUBSCRIBE, &subscribe)?;
+        tracing::info!("subscribed to {} on {}", self.topic, self.broker);
+
+        stream.set_read_timeout(Some(POLL))?;
+        let mut last_sent = Instant::now();
+        while !sink.is_stopped() {
+            if last_sent.elapsed() >= PING_EVERY {
+                write_packet(&mut stream, PINGREQ, &[])?;
+                last_sent = Instant::now();
+            }
+            let mut first = [0u8; 1];
+            match stream.read(&mut first) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(_) => {}
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => continue,
+                Err(e) => return Err(e),
+            }
+            let body = read_body(&mut stream)?;
+            match first[0] & 0xf0 {
+                PUBLISH => {
+                    let Some((topic, packet_id, payload)) = split_publish(first[0], &body) else {
+                        continue;
+                    };
+                    if let Some(id) = packet_id {
+                        write_packet(&mut stream, PUBACK, &id.to_be_bytes())?;
+                        last_sent = Instant::now();
+                    }
+                    for line in String::from_utf8_lossy(payload).lines() {
+                        let carry_on = match payload_signal(&topic, line) {
+                            Some(message) => sink.send(message),
+                            None => sink.send_line(line, None),
+                        };
+                        if !carry_on {
+                            return Ok(());
+                        }
+                    }
+                }
+                SUBACK if body.last() == Some(&0x80) => {
+                    return Err(io::Error::other(format!("broker refused the subscription to {}", self.topic)));
+                }
+                _ => {}
+            }
+        }
+        let _ = write_packet(&mut stream, DISCONNECT, &[]);
+        Ok(())
+    }
+}
+
+impl SignalSource for MqttSource {
+    fn run(self: Box<Self>, sink: SignalSink) {
+        while !sink.is_stopped() {
+            if let Err(e) = self.session(&sink) {
+                tracing::warn!("MQTT {}: {}", self.broker, e);
+            }
+            *self.connection.lock().unwrap() = None;
+            let retry_at = Instant::now() + RECONNECT_WAIT;
+            while !sink.is_stopped() && Instant::now() < retry_at {
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+    }
+
+    fn waker(&self) -> Option<Waker> {
+        let connection = Arc::clone(&self.connection);
+        Some(Box::new(move || {
+            if let Some(ref stream) = *connection.lock().unwrap() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }))
+    }
+}
+
+/// The signal a plain payload maps to, with `topic` as its message.
+fn payload_signal(topic: &str, payload: &str) -> Option<SignalMessage> {
+    let word = payload.trim().to_ascii_lowercase();
+    match word.as_str() {
+        "success" | "ok" | "pass" | "passed" | "done" | "on" | "true" => Some(SignalMessage::Success(topic.to_string())),
+        "failure" | "fail" | "failed" | "error" | "off" | "false" => Some(SignalMessage::Failure(topic.to_string())),
+        _ => {
+            let percent: f32 = word.trim_end_matches('%').trim().parse().ok()?;
+            (0.0..=100.0).contains(&percent).then(|| SignalMessage::Progress { percent: percent as u8, label: topic.to_string() })
+        }
+    }
+}
+
+/// A PUBLISH packet's topic, its id if it wants acknowledging, and its payload.
+fn split_publish(header: u8, body: &[u8]) -> Option<(String, Option<u16>, &[u8])> {
+    let len = u16::from_be_bytes([*body.first()?, *body.get(1)?]) as usize;
+    let topic = String::from_utf8_lossy(body.get(2..2 + len)?).into_owned();
+    let rest = &body[2 + len..];
+    if (header >> 1) & 0x03 == 0 {
+        return Some((topic, None, rest));
+    }
+    let id = u16::from_be_bytes([*rest.first()?, *rest.get(1)?]);
+    Some((topic, Some(id), &rest[2..]))
+}
+
+fn put_string(out: &mut Vec<u8>, s: &str) {
+    out.extend((s.len() as u16).to_be_bytes());
+    out.extend(s.as_bytes());
+}
+
+fn write_packet(stream: &mut impl Write, kind: u8, body: &[u8]) -> io::Result<()> {
+    let mut packet = vec![kind];
+    // The remaining length, seven bits a byte with the top bit saying more follow
+    let mut len = body.len();
+    loop {
+        let byte = (len % 128) as u8;
+        len /= 128;
+        packet.push(if len > 0 { byte | 0x80 } else { byte });
+        if len == 0 {
+            break;
+        }
+    }
+    packet.extend(body);
+    stream.write_all(&packet)
+}
+
+/// The rest of a packet whose first byte has been read.
+fn read_body(stream: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut len = 0usize;
+    for shift in (0..28).step_by(7) {
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte)?;
+        len |= ((byte[0] & 0x7f) as usize) << shift;
+        if byte[0] & 0x80 == 0 {
+            let mut body = vec![0u8; len];
+            stream.read_exact(&mut body)?;
+            return Ok(body);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "malformed remaining length"))
+}
+
+fn read_packet(stream: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut first = [0u8; 1];
+    stream.read_exact(&mut first)?;
+    Ok((first[0], read_body(stream)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signals::{SharedSignal, Signals, UnknownLines};
+    use std::net::TcpListener;
+
+    #[test]
+    fn published_payloads_become_signals() {
+        assert_eq!(parse_broker("localhost"), Ok("localhost:1883".to_string()));
+        assert_eq!(parse_broker("mqtt://hub.lan:1884"), Ok("hub.lan:1884".to_string()));
+        assert!(parse_broker("hub:port").is_err() && parse_broker("").is_err());
+
+        // A broker that takes the subscription and publishes to it
+        let broker = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = broker.local_addr().unwrap().to_string();
+        let fake = thread::spawn(move || {
+            let (mut stream, _) = broker.accept().unwrap();
+            let (kind, connect) = read_packet(&mut stream).unwrap();
+            assert_eq!(kind, CONNECT);
+            assert_eq!(&connect[..6], b"\0\x04MQTT");
+            write_packet(&mut stream, CONNACK, &[0, 0]).unwrap();
+            let (kind, subscribe) = read_packet(&mut stream).unwrap();
+            assert_eq!(kind, SUBSCRIBE);
+            assert_eq!(&subscribe[4..12], b"builds/#");
+            write_packet(&mut stream, SUBACK, &[0, 1, 0]).unwrap();
+            let publish = |topic: &str, payload: &str| {
+                let mut body = Vec::new();
+                put_string(&mut body, topic);
+                body.extend(payload.as_bytes());
+                body
+            };
+            write_packet(&mut stream, PUBLISH, &publish("builds/web", "PROGRESS:40:compiling")).unwrap();
+            write_packet(&mut stream, PUBLISH, &publish("builds/web", "75%")).unwrap();
+            // QoS 1, which wants a PUBACK
+            let mut qos1 = Vec::new();
+            put_string(&mut qos1, "builds/api");
+            qos1.extend([0, 7]);
+            qos1.extend(b"failed");
+            write_packet(&mut stream, PUBLISH | 0x02, &qos1).unwrap();
+            let (kind, ack) = read_packet(&mut stream).unwrap();
+            assert_eq!((kind, ack), (PUBACK, vec![0, 7]));
+            stream
+        });
+
+        let inbox: SharedSignal = Arc::default();
+        let mut signals = Signals::new(Arc::clone(&inbox), UnknownLines::Ignore);
+        signals.start(MqttSource::new(addr, "builds/#".to_string()));
+        let _stream = fake.join().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while inbox.lock().unwrap().len() < 3 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let received: Vec<_> = inbox.lock().unwrap().drain(..).collect();
+        assert_eq!(
+            received,
+            [
+                SignalMessage::Progress { percent: 40, label: "compiling".to_string() },
+                SignalMessage::Progress { percent: 75, label: "builds/web".to_string() },
+                SignalMessage::Failure("builds/api".to_string()),
+            ]
+        );
+        let started = Instant::now();
+        signals.shutdown();
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
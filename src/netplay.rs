@@ -0,0 +1,314 @@
+//! `fisherman host` and `fisherman join <addr>`: two people fishing the same sea from
+//! different machines. The host stands on the right shore and keeps the fish; the one
+//! who joins stands on the left and takes the host's school as it is sent over. Each
+//! side reports its own line and catches, and draws the other's.
+//!
+//! The wire protocol is one JSON [`NetMessage`] per line over TCP. Columns travel as
+//! shares of the sender's screen width and rows as rows below the surface, so the two
+//! terminals needn't be the same size.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fish::Fish;
+use crate::fishing_line::FishingState;
+
+/// Port `fisherman host` listens on unless `--port` says otherwise.
+pub const DEFAULT_PORT: u16 = 7777;
+/// How often the host sends its school over.
+const SCHOOL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A line as it travels between the players, free of either screen's size.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NetLine {
+    Idle,
+    Charging { power: f32 },
+    /// In flight to `target`, a share of the width across.
+    Casting { target: f32, progress: f32 },
+    /// Hanging `depth` rows below the surface at `x`.
+    Landed { x: f32, depth: u16 },
+    /// Coming back in from `depth` rows below the surface at `x`.
+    Retrieving { x: f32, depth: i16, progress: f32 },
+}
+
+impl NetLine {
+    /// The line in `state` on a screen `width` wide whose surface is at row `surface`.
+    pub fn from_state(state: FishingState, width: u16, surface: u16) -> NetLine {
+        let share = |x: u16| x as f32 / width.max(1) as f32;
+        match state {
+            FishingState::Idle => NetLine::Idle,
+            FishingState::Charging { power } => NetLine::Charging { power },
+            FishingState::Casting { target_x, progress, .. } => NetLine::Casting { target: share(target_x), progress },
+            FishingState::Landed { landing_x, depth, .. } => NetLine::Landed { x: share(landing_x), depth },
+            FishingState::Retrieving { from_x, from_y, progress } => {
+                NetLine::Retrieving { x: share(from_x), depth: from_y as i16 - surface as i16, progress }
+            }
+        }
+    }
+
+    /// The line on a screen `width` wide with its surface at row `surface`, cast from a
+    /// rod whose tip is at `rod_tip_x`.
+    pub fn to_state(self, width: u16, surface: u16, rod_tip_x: u16) -> FishingState {
+        let column = |share: f32| ((share * width as f32).round() as u16).min(width.saturating_sub(1));
+        match self {
+            NetLine::Idle => FishingState::Idle,
+            NetLine::Charging { power } => FishingState::Charging { power },
+            NetLine::Casting { target, progress } => {
+                FishingState::Casting { start_x: rod_tip_x, start_y: surface, target_x: column(target), progress }
+            }
+            NetLine::Landed { x, depth } => FishingState::Landed { landing_x: column(x), landing_y: surface, depth },
+            NetLine::Retrieving { x, depth, progress } => {
+                FishingState::Retrieving { from_x: column(x), from_y: (surface as i16 + depth).max(0) as u16, progress }
+            }
+        }
+    }
+}
+
+/// A fish landed by one of the players.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetCatch {
+    pub species: String,
+    pub size: f32,
+    pub shiny: bool,
+    /// Where it was caught, to find it in the other player's sea: its lane and how far
+    /// across it was as a share of the width.
+    pub lane: usize,
+    pub x: f32,
+}
+
+impl NetCatch {
+    pub fn new(fish: &Fish, species: String, width: u16) -> NetCatch {
+        NetCatch { species, size: fish.size, shiny: fish.shiny, lane: fish.lane, x: fish.x / width.max(1) as f32 }
+    }
+
+    /// The fish in `fishes` this catch was, if it's still there: the same species in
+    /// the same lane, nearest to where it was caught.
+    pub fn find(&self, fishes: &[Fish], species_names: &[String], width: u16) -> Option<usize> {
+        let species = species_names.iter().position(|name| *name == self.species)?;
+        let x = self.x * width as f32;
+        fishes
+            .iter()
+            .enumerate()
+            .filter(|(_, fish)| fish.species == species && fish.lane == self.lane)
+            .min_by(|(_, a), (_, b)| (a.x - x).abs().total_cmp(&(b.x - x).abs()))
+            .map(|(i, _)| i)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NetMessage {
+    /// The sender's line changed.
+    Line(NetLine),
+    /// The sender landed a fish.
+    Caught(NetCatch),
+    /// The host's school, on a screen `width` wide, with species by name.
+    School { width: u16, species: Vec<String>, fishes: Vec<Fish> },
+}
+
+/// Something that happened on the link.
+#[derive(Debug, Clone)]
+pub enum LinkEvent {
+    /// The other player is connected, from this address.
+    Joined(SocketAddr),
+    Message(NetMessage),
+    /// The other player went away.
+    Left,
+}
+
+/// One side of the connection between the two players' games.
+pub struct Link {
+    hosting: bool,
+    inbox: Arc<Mutex<VecDeque<LinkEvent>>>,
+    /// The stream to the other player, while there is one.
+    peer: Arc<Mutex<Option<TcpStream>>>,
+    local_addr: SocketAddr,
+    /// The last line sent, so it is only sent again once it changes.
+    sent_line: Option<NetLine>,
+    school_sent_at: Option<Duration>,
+}
+
+impl Link {
+    /// Listen on `port` for the other player, in the background. Once one leaves
+    /// another may join.
+    pub fn host(port: u16) -> io::Result<Link> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let link = Link::new(true, listener.local_addr()?);
+        let (inbox, peer) = (Arc::clone(&link.inbox), Arc::clone(&link.peer));
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Ok(stream) = greet(stream, &inbox, &peer) {
+                    serve(stream, &inbox, &peer);
+                }
+            }
+        });
+        Ok(link)
+    }
+
+    /// Connect to a game hosted at `addr`.
+    pub fn join(addr: &str) -> io::Result<Link> {
+        let stream = TcpStream::connect(addr)?;
+        let link = Link::new(false, stream.local_addr()?);
+        // Ready to send before this returns, so nothing sent straight away is lost
+        let stream = greet(stream, &link.inbox, &link.peer)?;
+        let (inbox, peer) = (Arc::clone(&link.inbox), Arc::clone(&link.peer));
+        thread::spawn(move || serve(stream, &inbox, &peer));
+        Ok(link)
+    }
+
+    fn new(hosting: bool, local_addr: SocketAddr) -> Link {
+        Link {
+            hosting,
+            inbox: Arc::new(Mutex::new(VecDeque::new())),
+            peer: Arc::new(Mutex::new(None)),
+            local_addr,
+            sent_line: None,
+            school_sent_at: None,
+        }
+    }
+
+    /// Whether this side keeps the fish.
+    pub fn hosting(&self) -> bool {
+        self.hosting
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The events since the last call, oldest first.
+    pub fn take(&self) -> Vec<LinkEvent> {
+        self.inbox.lock().map(|mut inbox| inbox.drain(..).collect()).unwrap_or_default()
+    }
+
+    /// Send `message` to the other player, if one is connected.
+    pub fn send(&self, message: &NetMessage) {
+        let Ok(mut peer) = self.peer.lock() else {
+            return;
+        };
+        let Some(ref mut stream) = *peer else {
+            return;
+        };
+        let Ok(mut line) = serde_json::to_string(message) else {
+            return;
+        };
+        line.push('\n');
+        if stream.write_all(line.as_bytes()).is_err() {
+            *peer = None;
+        }
+    }
+
+    /// Send the line in play if it changed since it was last sent.
+    pub fn send_line(&mut self, line: NetLine) {
+        if self.sent_line != Some(line) {
+            self.send(&NetMessage::Line(line));
+            self.sent_line = Some(line);
+        }
+    }
+
+    /// Whether it's time the host sent its school again, `now` into the run.
+    pub fn school_due(&mut self, now: Duration) -> bool {
+        let due = self.hosting && self.school_sent_at.is_none_or(|at| now - at >= SCHOOL_INTERVAL);
+        if due {
+            self.school_sent_at = Some(now);
+        }
+        due
+    }
+
+    /// The other player has just arrived: what's already been sent goes again.
+    pub fn resend(&mut self) {
+        self.sent_line = None;
+        self.school_sent_at = None;
+    }
+}
+
+/// Take `stream` as the way to the other player, who has just connected.
+fn greet(stream: TcpStream, inbox: &Mutex<VecDeque<LinkEvent>>, peer: &Mutex<Option<TcpStream>>) -> io::Result<TcpStream> {
+    let addr = stream.peer_addr()?;
+    stream.set_nodelay(true)?;
+    *peer.lock().unwrap() = Some(stream.try_clone()?);
+    inbox.lock().unwrap().push_back(LinkEvent::Joined(addr));
+    Ok(stream)
+}
+
+/// Read messages from the other player until they go.
+fn serve(stream: TcpStream, inbox: &Mutex<VecDeque<LinkEvent>>, peer: &Mutex<Option<TcpStream>>) {
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if let Ok(message) = serde_json::from_str(&line) {
+            inbox.lock().unwrap().push_back(LinkEvent::Message(message));
+        }
+    }
+    *peer.lock().unwrap() = None;
+    inbox.lock().unwrap().push_back(LinkEvent::Left);
+}
+
+/// The host's school for a sea of `lanes` lanes on a screen `width` wide: fish moved
+/// across in proportion, matched up with this side's species by name and dropped if
+/// their species or lane isn't here.
+pub fn adopt_school(
+    fishes: Vec<Fish>,
+    from_width: u16,
+    from_species: &[String],
+    species_names: &[String],
+    lanes: usize,
+    width: u16,
+) -> Vec<Fish> {
+    let scale = width as f32 / from_width.max(1) as f32;
+    fishes
+        .into_iter()
+        .filter_map(|mut fish| {
+            let name = from_species.get(fish.species)?;
+            fish.species = species_names.iter().position(|s| s == name)?;
+            fish.x *= scale;
+            fish.spawn_delay_ms = 0;
+            (fish.lane < lanes).then_some(fish)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn wait_for(link: &Link) -> Vec<LinkEvent> {
+        let start = Instant::now();
+        let mut events = Vec::new();
+        while events.len() < 2 && start.elapsed() < Duration::from_secs(5) {
+            events.extend(link.take());
+            thread::sleep(Duration::from_millis(10));
+        }
+        events
+    }
+
+    #[test]
+    fn lines_cross_the_wire_whatever_the_screen_size() {
+        let state = FishingState::Landed { landing_x: 50, landing_y: 20, depth: 3 };
+        let line = NetLine::from_state(state, 100, 20);
+        assert_eq!(line, NetLine::Landed { x: 0.5, depth: 3 });
+        assert_eq!(line.to_state(80, 12, 70), FishingState::Landed { landing_x: 40, landing_y: 12, depth: 3 });
+
+        let mut host = Link::host(0).unwrap();
+        let mut guest = Link::join(&format!("127.0.0.1:{}", host.local_addr().port())).unwrap();
+        assert!(host.hosting() && !guest.hosting());
+        guest.send_line(line);
+        guest.send_line(line);
+        let events = wait_for(&host);
+        assert!(matches!(events[0], LinkEvent::Joined(_)));
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], LinkEvent::Message(NetMessage::Line(sent)) if sent == line));
+        assert!(host.school_due(Duration::ZERO) && !host.school_due(Duration::from_millis(500)));
+        assert!(!guest.school_due(Duration::ZERO));
+
+        host.send_line(NetLine::Idle);
+        assert!(matches!(wait_for(&guest)[1], LinkEvent::Message(NetMessage::Line(NetLine::Idle))));
+    }
+}
@@ -1,75 +1,179 @@
+use std::time::Duration;
+
 use rand::rngs::StdRng;
 use rand::Rng;
 use rand::SeedableRng;
-use std::sync::OnceLock;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
 use ratatui::style::Style;
-use ratatui::widgets::Widget;
+use ratatui::widgets::{StatefulWidget, Widget};
 
-static FOAM_SEED: OnceLock<u64> = OnceLock::new();
+use crate::theme::Theme;
 
-fn foam_seed() -> u64 {
-    *FOAM_SEED.get_or_init(|| {
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_nanos() as u64)
-            .unwrap_or(0)
-    })
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ocean {
+    pub theme: Theme,
 }
 
-#[derive(Clone, Copy)]
-pub struct Ocean;
+/// Columns per second the waves roll by in calm weather; storms double it.
+const WAVE_SPEED: f32 = 1.5;
+/// Columns the foam rows sway either side of where they started, in calm weather.
+const FOAM_SWAY: f32 = 2.0;
+/// Radians per second of the foam's sway, and how far apart the rows are in it.
+const FOAM_RATE: f32 = 0.8;
+const FOAM_ROW_PHASE: f32 = 1.3;
+/// How quickly the swell follows the weather, per second.
+const SWELL_EASE: f32 = 0.2;
+
+/// State for rendering [`Ocean`] as a [`StatefulWidget`]: the seed the foam pattern is
+/// drawn from, how long the waves have been rolling and how rough the sea is. Rendered
+/// as a plain [`Widget`], or before [`OceanState::advance`] is called, the ocean holds
+/// still.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OceanState {
+    pub seed: u64,
+    pub elapsed: Duration,
+    /// From 0.0 (calm) to 1.0 (storm swell).
+    pub swell: f32,
+    /// From 0.0 (calm) to 1.0, churned up on top of the weather's swell: taller waves
+    /// and more foam. Unlike the swell it takes effect at once.
+    pub agitation: f32,
+}
+
+impl OceanState {
+    pub fn new(seed: u64) -> Self {
+        OceanState { seed, ..OceanState::default() }
+    }
+
+    /// Roll the waves on by `dt`, with the swell easing towards `roughness` (such as
+    /// [`crate::weather::Weather::intensity`]) rather than jumping to it.
+    pub fn advance(&mut self, dt: Duration, roughness: f32) {
+        self.elapsed += dt;
+        let ease = (SWELL_EASE * dt.as_secs_f32()).min(1.0);
+        self.swell += (roughness.clamp(0.0, 1.0) - self.swell) * ease;
+    }
+}
 
 impl Widget for Ocean {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let width = area.width as usize;
-        let surface_y = area.y;
-        let fg_wave1 = Color::Rgb(102, 178, 255);
-        let fg_wave2 = Color::Rgb(51, 120, 200);
-        let bg_ocean = Color::Rgb(51, 51, 51);
-
-        let mut x_off: usize = 0;
-        while x_off < width {
-            let x = area.x + x_off as u16;
-            let pat = if (x_off % 7) == 0 { "~~" } else if (x_off % 5) == 0 { "~~" } else { "~" };
-            let fg = if x_off % 2 == 0 { fg_wave1 } else { fg_wave2 };
-            buf.set_string(x, surface_y, pat, Style::default().fg(fg).bg(bg_ocean));
-            x_off += pat.chars().count();
+        render_ocean(area, buf, &OceanState::default(), &self.theme);
+    }
+}
+
+impl StatefulWidget for Ocean {
+    type State = OceanState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut OceanState) {
+        render_ocean(area, buf, state, &self.theme);
+    }
+}
+
+fn render_ocean(area: Rect, buf: &mut Buffer, state: &OceanState, theme: &Theme) {
+    let width = area.width as usize;
+    let surface_y = area.y;
+    let fg_wave1 = theme.wave;
+    let fg_wave2 = theme.wave_alt;
+    let bg_ocean = theme.water;
+    let t = state.elapsed.as_secs_f32();
+    let agitation = state.agitation.clamp(0.0, 1.0);
+    let swell = (state.swell + agitation).min(1.0);
+
+    // The pattern is laid out along the sea, which scrolls past the screen
+    let scroll = (t * WAVE_SPEED * (1.0 + swell)) as usize;
+    let mut x_off: usize = 0;
+    while x_off < width {
+        let x = area.x + x_off as u16;
+        let c = x_off + scroll;
+        let pat = if c.is_multiple_of(7) || c.is_multiple_of(5) { "~~" } else { "~" };
+        let fg = if c.is_multiple_of(2) { fg_wave1 } else { fg_wave2 };
+        buf.set_string(x, surface_y, pat, Style::default().fg(fg).bg(bg_ocean));
+        // Rough weather raises choppy crests that roll along with the waves
+        if swell > 0.05 {
+            for i in 0..pat.len() {
+                let height = ((c + i) as f32 * 0.45).sin() * 0.5 + ((c + i) as f32 * 0.13).sin() * 0.5;
+                if height > 1.0 - swell && x_off + i < width {
+                    buf.set_string(x + i as u16, surface_y, "≈", Style::default().fg(theme.foam).bg(bg_ocean));
+                }
+            }
         }
+        // A churned sea's crests tower a row higher
+        if agitation > 0.0 && area.height > 1 {
+            for i in 0..pat.len() {
+                let height = ((c + i) as f32 * 0.45).sin() * 0.5 + ((c + i) as f32 * 0.13).sin() * 0.5;
+                if height > 1.0 - agitation * 0.6 && x_off + i < width {
+                    buf.set_string(x + i as u16, surface_y + 1, "≈", Style::default().fg(theme.foam).bg(bg_ocean));
+                }
+            }
+        }
+        x_off += pat.chars().count();
+    }
+
+    for foam_row in 1..=3u16 {
+        let y = area.y + foam_row;
+        if y >= area.y + area.height { break; }
 
-        for foam_row in 1..=3u16 {
-            let y = area.y + foam_row;
-            if y >= area.y + area.height { break; }
-
-            let mut x_off: u16 = 0;
-            let base_seed = foam_seed();
-            let seed = base_seed
-                ^ ((area.x as u64) << 48)
-                ^ ((area.y as u64) << 32)
-                ^ ((foam_row as u64) << 16)
-                ^ (area.width as u64);
-            let mut rng = StdRng::seed_from_u64(seed);
-            while x_off < area.width {
-                    if rng.gen_bool(0.18) {
-                    let u1 = rng.gen_range(0.0f32..1.0f32);
-                    let u2 = rng.gen_range(0.0f32..1.0f32);
-                    let t = (u1 + u2) / 2.0;
-                    let mut len = (t * 6.0).floor() as u16 + 2; // 2..=7
-                    if len < 2 { len = 2; }
-                    if len > 7 { len = 7; }
-
-                    for i in 0..len {
-                        if x_off + i >= area.width { break; }
-                        let x = area.x + (x_off + i);
-                        buf.set_string(x, y, "^", Style::default().fg(Color::Rgb(200,220,255)).bg(bg_ocean));
-                    }
-                    x_off = x_off.saturating_add(len);
-                } else {
-                    x_off = x_off.saturating_add(1);
+        // Each row sways on its own phase, starting from where it would be drawn still
+        let row_phase = foam_row as f32 * FOAM_ROW_PHASE;
+        let sway = FOAM_SWAY * (1.0 + swell) * ((t * FOAM_RATE + row_phase).sin() - row_phase.sin());
+        let shift = (sway.round() as i32).rem_euclid(area.width.max(1) as i32) as u16;
+
+        let mut x_off: u16 = 0;
+        let seed = state.seed
+            ^ ((area.x as u64) << 48)
+            ^ ((area.y as u64) << 32)
+            ^ ((foam_row as u64) << 16)
+            ^ (area.width as u64);
+        let mut rng = StdRng::seed_from_u64(seed);
+        while x_off < area.width {
+            if rng.gen_bool((0.18 + 0.3 * agitation) as f64) {
+                let u1 = rng.gen_range(0.0f32..1.0f32);
+                let u2 = rng.gen_range(0.0f32..1.0f32);
+                let t = (u1 + u2) / 2.0;
+                let len = ((t * 6.0).floor() as u16 + 2).clamp(2, 7); // 2..=7
+
+                for i in 0..len {
+                    if x_off + i >= area.width { break; }
+                    let x = area.x + (x_off + i + shift) % area.width;
+                    buf.set_string(x, y, "^", Style::default().fg(theme.foam).bg(bg_ocean));
                 }
+                x_off = x_off.saturating_add(len);
+            } else {
+                x_off = x_off.saturating_add(1);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw(state: &mut OceanState) -> Buffer {
+        let area = Rect::new(0, 0, 40, 4);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(Ocean { theme: Theme::CLASSIC }, area, &mut buf, state);
+        buf
+    }
+
+    #[test]
+    fn waves_roll_and_swell_with_the_weather() {
+        let mut state = OceanState::new(7);
+        let still = draw(&mut state);
+        assert_eq!(draw(&mut OceanState::new(7)), still);
+
+        state.advance(Duration::from_secs(2), 0.0);
+        assert_ne!(draw(&mut state), still);
+        assert_eq!(state.swell, 0.0);
+
+        for _ in 0..100 {
+            state.advance(Duration::from_millis(100), 1.0);
+        }
+        assert!(state.swell > 0.8 && state.swell <= 1.0);
+        let stormy = draw(&mut state);
+        assert!((0..40).any(|x| stormy[(x, 0)].symbol() == "≈"));
+
+        let mut churned = OceanState { agitation: 1.0, ..OceanState::new(7) };
+        let churned = draw(&mut churned);
+        assert!((0..40).any(|x| churned[(x, 1)].symbol() == "≈"));
+    }
+}
@@ -0,0 +1,75 @@
+//! Frame pacing for the interactive loop: frames start at a steady target rate, and
+//! once the picture has stopped changing and no input arrives, drop to an idle rate so
+//! a quiet scene left open as a screensaver barely uses any CPU.
+
+use std::time::{Duration, Instant};
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+pub const DEFAULT_FPS: f64 = 20.0;
+/// The range `--fps` may be set in: a frame every ten seconds up to a thousand a second.
+pub const MIN_FPS: f64 = 0.1;
+pub const MAX_FPS: f64 = 1000.0;
+/// Frame rate once the scene has gone idle.
+pub const IDLE_FPS: f64 = 2.0;
+/// How long the picture must stay the same before the scene counts as idle. Long
+/// enough that slow swimmers, which move less than a cell per frame, don't trip it.
+const IDLE_AFTER: Duration = Duration::from_secs(1);
+
+pub struct FramePacer {
+    frame_time: Duration,
+    idle_frame_time: Duration,
+    last_frame: Option<Buffer>,
+    last_change: Instant,
+    ambient: Vec<Rect>,
+}
+
+impl FramePacer {
+    pub fn new(fps: f64) -> Self {
+        let fps = if fps.is_nan() { DEFAULT_FPS } else { fps.clamp(MIN_FPS, MAX_FPS) };
+        FramePacer {
+            frame_time: Duration::from_secs_f64(1.0 / fps),
+            idle_frame_time: Duration::from_secs_f64(1.0 / IDLE_FPS.min(fps)),
+            last_frame: None,
+            last_change: Instant::now(),
+            ambient: Vec::new(),
+        }
+    }
+
+    /// Ignore changes inside `areas`, such as the rolling waves, when deciding whether
+    /// the picture has stopped changing.
+    pub fn set_ambient(&mut self, areas: &[Rect]) {
+        self.ambient = areas.to_vec();
+    }
+
+    /// Note the frame just drawn; a frame that differs from the previous one keeps the
+    /// full frame rate going.
+    pub fn frame_drawn(&mut self, buffer: &Buffer, now: Instant) {
+        if !self.last_frame.as_ref().is_some_and(|last| self.same_picture(last, buffer)) {
+            self.last_frame = Some(buffer.clone());
+            self.last_change = now;
+        }
+    }
+
+    fn same_picture(&self, a: &Buffer, b: &Buffer) -> bool {
+        a.area == b.area
+            && a.area.positions().all(|p| self.ambient.iter().any(|r| r.contains(p)) || a[p] == b[p])
+    }
+
+    /// Input wakes the scene up again.
+    pub fn input(&mut self, now: Instant) {
+        self.last_change = now;
+    }
+
+    pub fn is_idle(&self, now: Instant) -> bool {
+        now.duration_since(self.last_change) >= IDLE_AFTER
+    }
+
+    /// How long to wait for input before starting the next frame, given when the
+    /// current one started.
+    pub fn timeout(&self, frame_start: Instant, now: Instant) -> Duration {
+        let frame_time = if self.is_idle(now) { self.idle_frame_time } else { self.frame_time };
+        (frame_start + frame_time).saturating_duration_since(now)
+    }
+}
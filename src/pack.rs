@@ -0,0 +1,324 @@
+//! Asset packs: themed sets of sprites, such as tropical, arctic or swamp fish, laid
+//! over the built-in ones with `--pack <name>` or swapped in while playing with the
+//! console's `pack <name>`. A pack is laid out like the `sprites` folder of the config
+//! directory and can be
+//!
+//! - built in, from `src/packs/<name>`,
+//! - a folder or a `.zip`, `.tar` or `.tar.gz` archive called `<name>` in the `packs`
+//!   folder of the config directory,
+//! - or the path of any of those.
+//!
+//! Archives and built-in packs are unpacked to a temporary folder first, so the pack is
+//! read from disk like any other sprite directory. Archives are read here rather than
+//! with a crate, as the PNG decoder already brings the DEFLATE decompressor they need.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use include_dir::{include_dir, Dir};
+
+use crate::config_file;
+use crate::png;
+
+static PACKS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/packs");
+
+/// The archive file name endings that can be unpacked.
+const ARCHIVES: [&str; 4] = [".zip", ".tar", ".tar.gz", ".tgz"];
+
+/// A file in a pack: its path inside the pack, with `/` between folders, and contents.
+type Entry = (String, Vec<u8>);
+
+/// Where packs are looked up by name: `packs` in the config directory.
+pub fn packs_dir() -> PathBuf {
+    config_file::config_dir().join("packs")
+}
+
+/// The packs that can be picked by name: those built in and those in `packs_dir`.
+pub fn names(packs_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = PACKS_DIR.dirs().filter_map(|dir| Some(dir.path().file_name()?.to_str()?.to_string())).collect();
+    for entry in fs::read_dir(packs_dir).into_iter().flatten().flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let name = ARCHIVES.iter().find_map(|ext| file_name.strip_suffix(ext)).unwrap_or(&file_name).to_string();
+        if entry.path().is_dir() || name != file_name {
+            names.push(name);
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// The sprite directory holding `pack`, by name or path, unpacked if need be.
+pub fn resolve(pack: &str) -> Result<PathBuf, String> {
+    resolve_in(pack, &packs_dir(), &std::env::temp_dir().join("fisherman-packs"))
+}
+
+fn resolve_in(pack: &str, packs_dir: &Path, unpack_root: &Path) -> Result<PathBuf, String> {
+    let path = Path::new(pack);
+    let found = if path.exists() {
+        Some(path.to_path_buf())
+    } else {
+        std::iter::once(packs_dir.join(pack))
+            .chain(ARCHIVES.iter().map(|ext| packs_dir.join(format!("{}{}", pack, ext))))
+            .find(|path| path.exists())
+    };
+    let (name, entries) = match found {
+        Some(dir) if dir.is_dir() => return Ok(dir),
+        Some(archive) => {
+            let file_name = archive.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let name = ARCHIVES.iter().find_map(|ext| file_name.strip_suffix(ext)).unwrap_or(&file_name).to_string();
+            (name, read_archive(&archive).map_err(|e| format!("{}: {}", archive.display(), e))?)
+        }
+        None => match PACKS_DIR.get_dir(pack) {
+            Some(dir) => (pack.to_string(), embedded_entries(dir, Path::new(pack))),
+            None => {
+                return Err(format!("unknown pack '{}' (expected one of {})", pack, names(packs_dir).join(", ")));
+            }
+        },
+    };
+    unpack(&entries, &unpack_root.join(name)).map_err(|e| format!("could not unpack pack '{}': {}", pack, e))
+}
+
+/// Every file under the built-in pack `dir`, by its path inside `root`.
+fn embedded_entries(dir: &Dir, root: &Path) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = dir
+        .files()
+        .filter_map(|file| {
+            let path = file.path().strip_prefix(root).ok()?;
+            Some((path.to_string_lossy().replace('\\', "/"), file.contents().to_vec()))
+        })
+        .collect();
+    for sub in dir.dirs() {
+        entries.extend(embedded_entries(sub, root));
+    }
+    entries
+}
+
+/// The files in the archive at `path`, read by the ending of its name.
+fn read_archive(path: &Path) -> Result<Vec<Entry>, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let name = path.to_string_lossy().to_ascii_lowercase();
+    if name.ends_with(".zip") {
+        zip_entries(&data)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        tar_entries(&gunzip(&data)?)
+    } else if name.ends_with(".tar") {
+        tar_entries(&data)
+    } else {
+        Err(format!("not a pack archive (expected a folder or one of {})", ARCHIVES.join(", ")))
+    }
+}
+
+/// Write `entries` out afresh under `dir`, and return the folder the pack starts in:
+/// `dir`, or the one folder it holds if the archive wrapped the pack in one.
+fn unpack(entries: &[Entry], dir: &Path) -> Result<PathBuf, String> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    for (name, contents) in entries {
+        let relative = Path::new(name);
+        // Never write outside the folder, whatever the archive says
+        if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(format!("'{}' is outside the pack", name));
+        }
+        let path = dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, contents).map_err(|e| e.to_string())?;
+    }
+    let children: Vec<PathBuf> = fs::read_dir(dir).map_err(|e| e.to_string())?.flatten().map(|e| e.path()).collect();
+    match children.as_slice() {
+        // A species folder holds `left` and `right`; a folder wrapping the pack doesn't
+        [only] if only.is_dir() && !only.join("left").exists() && !only.join("right").exists() => Ok(only.clone()),
+        _ => Ok(dir.to_path_buf()),
+    }
+}
+
+/// The little-endian number of `bytes` bytes at `at`.
+fn le(data: &[u8], at: usize, bytes: usize) -> Result<usize, String> {
+    let field = data.get(at..at + bytes).ok_or("archive is truncated")?;
+    Ok(field.iter().rev().fold(0, |n, &b| n << 8 | b as usize))
+}
+
+/// The files in a zip archive, found through its central directory, stored or
+/// deflated.
+fn zip_entries(data: &[u8]) -> Result<Vec<Entry>, String> {
+    const END: usize = 0x0605_4b50;
+    const CENTRAL: usize = 0x0201_4b50;
+    const LOCAL: usize = 0x0403_4b50;
+    let end = (0..=data.len().saturating_sub(22)).rev().find(|&at| le(data, at, 4) == Ok(END)).ok_or("not a zip archive")?;
+    let count = le(data, end + 10, 2)?;
+    let mut at = le(data, end + 16, 4)?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        if le(data, at, 4)? != CENTRAL {
+            return Err("damaged zip archive".to_string());
+        }
+        let method = le(data, at + 10, 2)?;
+        let size = le(data, at + 20, 4)?;
+        let name_length = le(data, at + 28, 2)?;
+        let local = le(data, at + 42, 4)?;
+        let name = data.get(at + 46..at + 46 + name_length).ok_or("archive is truncated")?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        at += 46 + name_length + le(data, at + 30, 2)? + le(data, at + 32, 2)?;
+        if name.ends_with('/') {
+            continue;
+        }
+        if le(data, local, 4)? != LOCAL {
+            return Err("damaged zip archive".to_string());
+        }
+        let start = local + 30 + le(data, local + 26, 2)? + le(data, local + 28, 2)?;
+        let raw = data.get(start..start + size).ok_or("archive is truncated")?;
+        let contents = match method {
+            0 => raw.to_vec(),
+            8 => png::inflate(raw).map_err(|e| format!("{}: {}", name, e))?,
+            other => return Err(format!("{}: unsupported compression method {}", name, other)),
+        };
+        entries.push((name, contents));
+    }
+    Ok(entries)
+}
+
+/// The regular files in a tar archive.
+fn tar_entries(data: &[u8]) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    let mut at = 0;
+    while let Some(header) = data.get(at..at + 512) {
+        // Two blocks of zeros end the archive
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let text = |from: usize, to: usize| {
+            let field = &header[from..to];
+            String::from_utf8_lossy(&field[..field.iter().position(|&b| b == 0).unwrap_or(field.len())]).into_owned()
+        };
+        let size = usize::from_str_radix(text(124, 136).trim(), 8).map_err(|_| "damaged tar archive")?;
+        let prefix = text(345, 500);
+        let name = if header[257..262] == *b"ustar" && !prefix.is_empty() { format!("{}/{}", prefix, text(0, 100)) } else { text(0, 100) };
+        let contents = data.get(at + 512..at + 512 + size).ok_or("archive is truncated")?;
+        if matches!(header[156], b'0' | 0) {
+            entries.push((name, contents.to_vec()));
+        }
+        at += 512 + size.div_ceil(512) * 512;
+    }
+    Ok(entries)
+}
+
+/// The contents of a gzip file.
+fn gunzip(data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < 18 || data[..3] != [0x1f, 0x8b, 8] {
+        return Err("not a gzip file".to_string());
+    }
+    let flags = data[3];
+    let mut at = 10;
+    if flags & 0x04 != 0 {
+        at += 2 + le(data, at, 2)?;
+    }
+    // A file name and a comment, each ended by a zero
+    for flag in [0x08, 0x10] {
+        if flags & flag != 0 {
+            at += data.get(at..).and_then(|rest| rest.iter().position(|&b| b == 0)).ok_or("damaged gzip file")? + 1;
+        }
+    }
+    if flags & 0x02 != 0 {
+        at += 2;
+    }
+    png::inflate(data.get(at..).ok_or("damaged gzip file")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Assets;
+
+    /// A tar archive of `files`.
+    fn tar(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for (name, contents) in files {
+            let mut header = [0u8; 512];
+            header[..name.len()].copy_from_slice(name.as_bytes());
+            header[124..135].copy_from_slice(format!("{:011o}", contents.len()).as_bytes());
+            header[156] = b'0';
+            data.extend_from_slice(&header);
+            data.extend_from_slice(contents);
+            data.resize(data.len().div_ceil(512) * 512, 0);
+        }
+        data.resize(data.len() + 1024, 0);
+        data
+    }
+
+    /// A zip archive of `files`, stored without compression.
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let (mut data, mut central) = (Vec::new(), Vec::new());
+        for (name, contents) in files {
+            let local = data.len() as u32;
+            let sizes = [(contents.len() as u32).to_le_bytes(), (contents.len() as u32).to_le_bytes()].concat();
+            data.extend_from_slice(&[0x50, 0x4b, 3, 4, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            data.extend_from_slice(&sizes);
+            data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            data.extend_from_slice(&[0, 0]);
+            data.extend_from_slice(name.as_bytes());
+            data.extend_from_slice(contents);
+            central.extend_from_slice(&[0x50, 0x4b, 1, 2, 20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            central.extend_from_slice(&sizes);
+            central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            central.extend_from_slice(&[0; 12]);
+            central.extend_from_slice(&local.to_le_bytes());
+            central.extend_from_slice(name.as_bytes());
+        }
+        let offset = data.len() as u32;
+        data.extend_from_slice(&central);
+        data.extend_from_slice(&[0x50, 0x4b, 5, 6, 0, 0, 0, 0]);
+        data.extend_from_slice(&[(files.len() as u16).to_le_bytes(), (files.len() as u16).to_le_bytes()].concat());
+        data.extend_from_slice(&(central.len() as u32).to_le_bytes());
+        data.extend_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(&[0, 0]);
+        data
+    }
+
+    #[test]
+    fn packs_are_found_by_name_or_path_and_unpacked() {
+        let dir = std::env::temp_dir().join(format!("fisherman-packs-test-{}", std::process::id()));
+        let (packs, unpacked) = (dir.join("packs"), dir.join("unpacked"));
+        fs::create_dir_all(&packs).unwrap();
+        let sprite = b"X,Y,ASCII,Foreground,Background\n0,0,>,#FF8800,\n";
+        let meta = b"Rarity\n0.5\n";
+
+        // A built-in pack, laid over the built-in sprites like any sprite directory
+        let night = resolve_in("ocean-night", &packs, &unpacked).unwrap();
+        assert_eq!(night, unpacked.join("ocean-night"));
+        let assets = Assets::load_layered(&[night]);
+        assert!(assets.species.iter().any(|s| s.name == "Lanternfish"));
+
+        // A tarball wrapping the pack in a folder of its own
+        let tarball = tar(&[("tropical/Clownfish/right/clown_01.csv", sprite), ("tropical/Clownfish/meta.csv", meta)]);
+        fs::write(packs.join("tropical.tar"), tarball).unwrap();
+        let tropical = resolve_in("tropical", &packs, &unpacked).unwrap();
+        assert_eq!(tropical, unpacked.join("tropical").join("tropical"));
+        assert_eq!(fs::read(tropical.join("Clownfish/meta.csv")).unwrap(), meta);
+
+        // A zip by path, and the same tar gzipped with a stored block
+        let archive = dir.join("arctic.zip");
+        fs::write(&archive, zip(&[("Narwhal/left/narwhal.csv", sprite)])).unwrap();
+        let arctic = resolve_in(archive.to_str().unwrap(), &packs, &unpacked).unwrap();
+        assert_eq!(fs::read(arctic.join("Narwhal/left/narwhal.csv")).unwrap(), sprite);
+        let mut gz = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 3, 1];
+        let tarball = tar(&[("Pike/right/pike.csv", sprite)]);
+        gz.extend_from_slice(&(tarball.len() as u16).to_le_bytes());
+        gz.extend_from_slice(&(!(tarball.len() as u16)).to_le_bytes());
+        gz.extend_from_slice(&tarball);
+        gz.extend_from_slice(&[0; 8]);
+        fs::write(packs.join("swamp.tgz"), gz).unwrap();
+        assert!(resolve_in("swamp", &packs, &unpacked).unwrap().join("Pike/right/pike.csv").exists());
+
+        assert_eq!(names(&packs), ["ocean-night", "swamp", "tropical"]);
+        let unknown = resolve_in("lava", &packs, &unpacked).unwrap_err();
+        assert!(unknown.contains("ocean-night, swamp, tropical"), "{}", unknown);
+        fs::write(packs.join("evil.tar"), tar(&[("../escaped.csv", sprite)])).unwrap();
+        assert!(resolve_in("evil", &packs, &unpacked).is_err());
+        assert!(!unpacked.join("escaped.csv").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
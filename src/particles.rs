@@ -0,0 +1,350 @@
+//! Short-lived animated glyphs: splash drops, bubbles, confetti, sparks, fireworks. Each particle has a
+//! position and velocity in cells, a lifetime, and a run of glyphs it plays through as
+//! it ages. Other modules emit them through the spawn helpers on [`Particles`] and
+//! tick and draw the whole set once per frame.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Widget;
+
+/// Rows per second squared pulling splash drops and confetti back down.
+const GRAVITY: f32 = 14.0;
+const SPLASH_DROPS: usize = 7;
+const SPLASH_LIFETIME: Duration = Duration::from_millis(600);
+const BUBBLE_LIFETIME: Duration = Duration::from_millis(1500);
+/// Rows per second bubbles rise.
+const BUBBLE_RISE: f32 = 2.0;
+const COLUMN_BUBBLES: u16 = 4;
+const RAIN_SPLASH_LIFETIME: Duration = Duration::from_millis(250);
+const CONFETTI_PIECES: usize = 24;
+const CONFETTI_LIFETIME: Duration = Duration::from_millis(1600);
+const CONFETTI_COLORS: [Color; 5] = [Color::Yellow, Color::Magenta, Color::Cyan, Color::Green, Color::Red];
+const ZAP_SPARKS: usize = 10;
+const ZAP_LIFETIME: Duration = Duration::from_millis(500);
+const FIREWORK_ROCKETS: usize = 5;
+/// The rockets of a display go up one after another over this long.
+const FIREWORK_SPREAD: Duration = Duration::from_millis(900);
+/// Rows per second a rocket climbs.
+const ROCKET_SPEED: f32 = 16.0;
+const FIREWORK_SPARKS: usize = 16;
+const FIREWORK_SPARK_LIFETIME: Duration = Duration::from_millis(900);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    pub x: f32,
+    pub y: f32,
+    /// Cells per second; positive `vy` is downwards.
+    pub vx: f32,
+    pub vy: f32,
+    /// Added to `vy` every second.
+    pub gravity: f32,
+    pub age: Duration,
+    pub lifetime: Duration,
+    /// Played through evenly over the particle's lifetime.
+    pub glyphs: &'static [char],
+    /// Applied over the cell, so a style without a background keeps what's behind it.
+    pub style: Style,
+}
+
+impl Particle {
+    pub fn new(x: f32, y: f32, glyphs: &'static [char], style: Style, lifetime: Duration) -> Self {
+        Particle { x, y, vx: 0.0, vy: 0.0, gravity: 0.0, age: Duration::ZERO, lifetime, glyphs, style }
+    }
+
+    pub fn with_velocity(mut self, vx: f32, vy: f32) -> Self {
+        self.vx = vx;
+        self.vy = vy;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: f32) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+
+    /// The glyph for the particle's current age.
+    pub fn glyph(&self) -> Option<char> {
+        let progress = self.age.as_secs_f32() / self.lifetime.as_secs_f32().max(f32::EPSILON);
+        let index = (progress * self.glyphs.len() as f32) as usize;
+        self.glyphs.get(index.min(self.glyphs.len().saturating_sub(1))).copied()
+    }
+
+    fn tick(&mut self, dt: Duration) {
+        let secs = dt.as_secs_f32();
+        self.vy += self.gravity * secs;
+        self.x += self.vx * secs;
+        self.y += self.vy * secs;
+        self.age += dt;
+    }
+}
+
+/// A firework rocket waiting to go up from `(x, y)` or on its way, bursting at row
+/// `burst_y`.
+#[derive(Debug, Clone, PartialEq)]
+struct Rocket {
+    x: u16,
+    y: u16,
+    burst_y: u16,
+    /// `None` once it has gone up.
+    launch_in: Option<Duration>,
+    burst_in: Duration,
+    color: Color,
+}
+
+/// The particles in flight, and the RNG the spawn helpers scatter them with. Kept apart
+/// from the game's RNG so cosmetic particles never change what bites.
+#[derive(Debug, Clone)]
+pub struct Particles {
+    particles: Vec<Particle>,
+    /// Fireworks yet to burst; each becomes a trail, then a burst of sparks.
+    rockets: Vec<Rocket>,
+    rng: StdRng,
+}
+
+impl Particles {
+    pub fn new(seed: u64) -> Self {
+        Particles { particles: Vec::new(), rockets: Vec::new(), rng: StdRng::seed_from_u64(seed) }
+    }
+
+    pub fn spawn(&mut self, particle: Particle) {
+        self.particles.push(particle);
+    }
+
+    /// Drops thrown up from `(x, y)` that arc back down into the water.
+    pub fn splash(&mut self, x: u16, y: u16, color: Color) {
+        for _ in 0..SPLASH_DROPS {
+            let vx = self.rng.gen_range(-6.0..6.0);
+            let vy = self.rng.gen_range(-9.0..-4.0);
+            self.spawn(
+                Particle::new(x as f32, y as f32 - 1.0, &['|', '\'', '.'], Style::default().fg(color), SPLASH_LIFETIME)
+                    .with_velocity(vx, vy)
+                    .with_gravity(GRAVITY * 2.0),
+            );
+        }
+    }
+
+    /// A bubble rising from `(x, y)`, wobbling a little as it goes.
+    pub fn bubble(&mut self, x: u16, y: u16, color: Color) {
+        let vx = self.rng.gen_range(-0.6..0.6);
+        self.spawn(
+            Particle::new(x as f32, y as f32, &['.', 'o', 'O', 'o'], Style::default().fg(color), BUBBLE_LIFETIME)
+                .with_velocity(vx, -BUBBLE_RISE),
+        );
+    }
+
+    /// A column of bubbles rising straight up from `(x, y)`, bursting `rise` rows up.
+    pub fn bubble_column(&mut self, x: u16, y: u16, rise: u16, color: Color) {
+        for i in 0..COLUMN_BUBBLES.min(rise) {
+            let lifetime = Duration::from_secs_f32((rise - i) as f32 / BUBBLE_RISE);
+            self.spawn(
+                Particle::new(x as f32, y.saturating_sub(i) as f32, &['.', 'o', 'O', 'o'], Style::default().fg(color), lifetime)
+                    .with_velocity(0.0, -BUBBLE_RISE),
+            );
+        }
+    }
+
+    /// Raindrops striking the surface row `y` across `columns`, `per_second` of them on
+    /// average, over the next `dt`.
+    pub fn rain(&mut self, columns: Range<u16>, y: u16, per_second: f32, dt: Duration, color: Color) {
+        if columns.is_empty() {
+            return;
+        }
+        for _ in 0..self.occurrences(per_second, dt) {
+            let x = self.rng.gen_range(columns.clone());
+            self.spawn(Particle::new(x as f32, y as f32 - 1.0, &['\'', '.'], Style::default().fg(color), RAIN_SPLASH_LIFETIME));
+        }
+    }
+
+    /// How many times something that happens `per_second` times on average happens in
+    /// `dt`, for spawning particles at a steady rate from frames of any length.
+    pub fn occurrences(&mut self, per_second: f32, dt: Duration) -> usize {
+        let expected = (per_second * dt.as_secs_f32()).max(0.0);
+        expected as usize + usize::from(self.rng.gen_bool(expected.fract().into()))
+    }
+
+    /// A burst of coloured confetti from `(x, y)`, for a good catch.
+    pub fn confetti(&mut self, x: u16, y: u16) {
+        for i in 0..CONFETTI_PIECES {
+            let vx = self.rng.gen_range(-12.0..12.0);
+            let vy = self.rng.gen_range(-10.0..-3.0);
+            let glyphs: &'static [char] = if self.rng.gen_bool(0.5) { &['*', '+', '·'] } else { &['•', '°', '·'] };
+            let color = CONFETTI_COLORS[i % CONFETTI_COLORS.len()];
+            self.spawn(
+                Particle::new(x as f32, y as f32, glyphs, Style::default().fg(color), CONFETTI_LIFETIME)
+                    .with_velocity(vx, vy)
+                    .with_gravity(GRAVITY / 2.0),
+            );
+        }
+    }
+
+    /// Sparks crackling out from `(x, y)`, for a line zapped by a jellyfish.
+    pub fn zap(&mut self, x: u16, y: u16, color: Color) {
+        for _ in 0..ZAP_SPARKS {
+            let vx = self.rng.gen_range(-8.0..8.0);
+            let vy = self.rng.gen_range(-4.0..4.0);
+            self.spawn(
+                Particle::new(x as f32, y as f32, &['*', '+', '\'', '.'], Style::default().fg(color), ZAP_LIFETIME)
+                    .with_velocity(vx, vy),
+            );
+        }
+    }
+
+    /// A fireworks display: rockets going up one after another from row `y` at
+    /// random `columns` and bursting somewhere in `burst_rows`. Returns how long until
+    /// the last of them bursts.
+    pub fn fireworks(&mut self, columns: Range<u16>, y: u16, burst_rows: Range<u16>) -> Duration {
+        if columns.is_empty() || burst_rows.is_empty() || burst_rows.end > y {
+            return Duration::ZERO;
+        }
+        let mut last_burst = Duration::ZERO;
+        for i in 0..FIREWORK_ROCKETS {
+            let burst_y = self.rng.gen_range(burst_rows.clone());
+            let launch_in = FIREWORK_SPREAD.mul_f32(i as f32 / FIREWORK_ROCKETS as f32);
+            let burst_in = launch_in + Duration::from_secs_f32((y - burst_y) as f32 / ROCKET_SPEED);
+            let x = self.rng.gen_range(columns.clone());
+            let color = CONFETTI_COLORS[self.rng.gen_range(0..CONFETTI_COLORS.len())];
+            self.rockets.push(Rocket { x, y, burst_y, launch_in: Some(launch_in), burst_in, color });
+            last_burst = last_burst.max(burst_in);
+        }
+        last_burst
+    }
+
+    /// Sparks flying out in a ring from `(x, y)`, where a firework went off.
+    fn burst(&mut self, x: u16, y: u16, color: Color) {
+        for i in 0..FIREWORK_SPARKS {
+            let angle = i as f32 / FIREWORK_SPARKS as f32 * std::f32::consts::TAU;
+            let speed = self.rng.gen_range(5.0..8.0);
+            // Cells are about twice as tall as they are wide
+            let (vx, vy) = (angle.cos() * speed * 2.0, angle.sin() * speed);
+            self.spawn(
+                Particle::new(x as f32, y as f32, &['*', '+', '·', '.'], Style::default().fg(color), FIREWORK_SPARK_LIFETIME)
+                    .with_velocity(vx, vy)
+                    .with_gravity(GRAVITY / 4.0),
+            );
+        }
+    }
+
+    /// Move every particle on by `dt`, dropping those that have lived out their time,
+    /// and send up or burst the rockets whose time has come.
+    pub fn tick(&mut self, dt: Duration) {
+        for particle in &mut self.particles {
+            particle.tick(dt);
+        }
+        self.particles.retain(Particle::is_alive);
+
+        let mut bursts = Vec::new();
+        for rocket in &mut self.rockets {
+            if let Some(launch_in) = rocket.launch_in {
+                if launch_in <= dt {
+                    let flight = rocket.burst_in.saturating_sub(launch_in);
+                    let style = Style::default().fg(rocket.color);
+                    self.particles.push(
+                        Particle::new(rocket.x as f32, rocket.y as f32, &['|', '\''], style, flight).with_velocity(0.0, -ROCKET_SPEED),
+                    );
+                    rocket.launch_in = None;
+                } else {
+                    rocket.launch_in = Some(launch_in - dt);
+                }
+            }
+            rocket.burst_in = rocket.burst_in.saturating_sub(dt);
+            if rocket.burst_in.is_zero() {
+                bursts.push((rocket.x, rocket.burst_y, rocket.color));
+            }
+        }
+        self.rockets.retain(|rocket| !rocket.burst_in.is_zero());
+        for (x, y, color) in bursts {
+            self.burst(x, y, color);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty() && self.rockets.is_empty()
+    }
+}
+
+impl Widget for &Particles {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for particle in &self.particles {
+            let (x, y) = (particle.x.round(), particle.y.round());
+            if x < area.x as f32 || x >= area.right() as f32 || y < area.y as f32 || y >= area.bottom() as f32 {
+                continue;
+            }
+            if let Some(glyph) = particle.glyph() {
+                buf[(x as u16, y as u16)].set_char(glyph).set_style(particle.style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn particles_move_age_and_expire() {
+        let mut particles = Particles::new(1);
+        particles.spawn(
+            Particle::new(2.0, 5.0, &['a', 'b'], Style::default(), Duration::from_secs(1))
+                .with_velocity(2.0, -4.0)
+                .with_gravity(4.0),
+        );
+
+        let area = Rect::new(0, 0, 10, 10);
+        let mut buf = Buffer::empty(area);
+        (&particles).render(area, &mut buf);
+        assert_eq!(buf[(2, 5)].symbol(), "a");
+
+        particles.tick(Duration::from_millis(500));
+        let mut buf = Buffer::empty(area);
+        (&particles).render(area, &mut buf);
+        // Moved 1 across and, slowing under gravity, 1 up
+        assert_eq!(buf[(3, 4)].symbol(), "b");
+
+        particles.tick(Duration::from_millis(500));
+        assert!(particles.is_empty());
+    }
+
+    #[test]
+    fn fireworks_go_up_and_burst_in_the_sky() {
+        let mut particles = Particles::new(3);
+        let last_burst = particles.fireworks(10..50, 20, 2..6);
+        assert!(last_burst > FIREWORK_SPREAD && last_burst < Duration::from_secs(3));
+        particles.tick(Duration::from_millis(10));
+        assert_eq!(particles.len(), 1);
+        assert!(particles.particles[0].vy < 0.0);
+
+        let mut time = Duration::from_millis(10);
+        while time < last_burst {
+            particles.tick(Duration::from_millis(50));
+            time += Duration::from_millis(50);
+        }
+        assert!(particles.rockets.is_empty());
+        let sparks: Vec<_> = particles.particles.iter().filter(|p| p.glyphs[0] == '*').collect();
+        assert!(sparks.len() >= FIREWORK_SPARKS);
+        // Burst high enough that the sparks stay in the sky while they last
+        assert!(sparks.iter().all(|p| p.y < 20.0));
+    }
+
+    #[test]
+    fn splash_drops_fall_back_down() {
+        let mut particles = Particles::new(7);
+        particles.splash(20, 10, Color::White);
+        assert_eq!(particles.len(), SPLASH_DROPS);
+        particles.tick(Duration::from_millis(590));
+        assert!(particles.particles.iter().all(|p| p.vy > 0.0));
+    }
+}
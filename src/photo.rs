@@ -0,0 +1,187 @@
+//! Photo cards (`--photo-dir`): a record or a Massive catch framed like a snapshot,
+//! with the fish's sprite in the water above its species, size, the date and the
+//! conditions it was landed in. Cards are saved as ANSI text, to `cat` in a terminal
+//! or paste into a chat that shows colour. The folder can also be set in the config
+//! file:
+//!
+//! ```toml
+//! [photos]
+//! dir = "/home/me/Pictures/fish"
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, Widget};
+
+use crate::config_file::{Table, Value};
+use crate::csv_frames::SpriteFrame;
+use crate::fishing_game::{CaughtFish, SizeCategory};
+use crate::headless;
+use crate::theme::Theme;
+
+/// The narrowest a card is drawn, however small the fish.
+const MIN_WIDTH: u16 = 32;
+/// Rows of water above and below the sprite.
+const WATER_MARGIN: u16 = 1;
+
+/// A catch worth a photo.
+pub struct PhotoCard<'a> {
+    pub fish: &'a CaughtFish,
+    /// The fish as it swims, facing right.
+    pub sprite: Option<&'a SpriteFrame>,
+    /// When it was landed, such as `2026-10-16 · 06:40`.
+    pub taken: String,
+    /// The weather, then what the conditions did to the fishing, a line each.
+    pub conditions: Vec<String>,
+    /// The biggest of its species landed so far.
+    pub record: bool,
+    pub theme: Theme,
+}
+
+/// Whether a catch is worth a photo: a new record, or a Massive fish.
+pub fn notable(fish: &CaughtFish, record: bool) -> bool {
+    record || fish.size_category == SizeCategory::Massive
+}
+
+impl PhotoCard<'_> {
+    /// The lines under the photo.
+    fn caption(&self) -> Vec<(String, Style)> {
+        let label = Style::default().fg(self.theme.foam);
+        let shiny = if self.fish.shiny { "✦ " } else { "" };
+        let mut lines = vec![
+            (format!("{}{}", shiny, self.fish.species_name), label.add_modifier(Modifier::BOLD)),
+            (format!("{:.1} cm · {}", self.fish.size, self.fish.size_category.as_str()), label),
+            (self.taken.clone(), label.add_modifier(Modifier::DIM)),
+        ];
+        lines.extend(self.conditions.iter().map(|line| (line.clone(), label.add_modifier(Modifier::DIM))));
+        lines
+    }
+
+    /// How big the card is drawn, in columns and rows.
+    pub fn size(&self) -> (u16, u16) {
+        let (sprite_width, sprite_height) = self.sprite.map_or((0, 0), |sprite| (sprite.width, sprite.height));
+        let caption = self.caption();
+        let text_width = caption.iter().map(|(line, _)| line.chars().count() as u16).max().unwrap_or(0);
+        let width = (sprite_width + 6).max(text_width + 4).max(MIN_WIDTH);
+        (width, sprite_height + 2 * WATER_MARGIN + caption.len() as u16 + 3)
+    }
+
+    /// The card on its own, sized to fit.
+    pub fn to_buffer(&self) -> Buffer {
+        let (width, height) = self.size();
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+        self.render(buf.area, &mut buf);
+        buf
+    }
+}
+
+impl Widget for &PhotoCard<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = if self.record { " New record " } else { " Massive catch " };
+        let block = Block::default().title(title).borders(Borders::ALL).style(Style::default().fg(self.theme.foam));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // The photo: the fish in open water
+        let sprite_height = self.sprite.map_or(0, |sprite| sprite.height);
+        let photo = Rect { height: (sprite_height + 2 * WATER_MARGIN).min(inner.height), ..inner };
+        buf.set_style(photo, Style::default().bg(self.theme.water));
+        if let Some(sprite) = self.sprite {
+            let x = photo.x as i32 + (photo.width as i32 - sprite.width as i32) / 2;
+            sprite.blit(buf, (x, (photo.y + WATER_MARGIN) as i32), 1, photo, &[]);
+        }
+
+        let caption = Rect { y: photo.bottom() + 1, height: inner.bottom().saturating_sub(photo.bottom() + 1), ..inner };
+        for ((line, style), y) in self.caption().into_iter().zip(caption.rows().map(|row| row.y)) {
+            let x = caption.x + caption.width.saturating_sub(line.chars().count() as u16) / 2;
+            buf.set_stringn(x, y, line, caption.width as usize, style);
+        }
+    }
+}
+
+/// The folder photos go in from the `[photos]` section, if it names one.
+pub fn dir_from_table(table: &Table) -> Result<Option<PathBuf>, String> {
+    let mut dir = None;
+    for (key, value) in table {
+        match (key.as_str(), value) {
+            ("dir", Value::String(path)) => dir = Some(PathBuf::from(path)),
+            ("dir", _) => return Err("[photos] dir must be a path".to_string()),
+            (key, _) => return Err(format!("unknown setting '{}' in [photos]", key)),
+        }
+    }
+    Ok(dir)
+}
+
+/// `2026-10-16-06-40-great-white` for a card taken at `stamp` (`YYYY-MM-DD HH:MM`).
+fn file_stem(stamp: &str, species: &str) -> String {
+    let clean = |text: &str| -> String {
+        let words: Vec<String> = text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(str::to_lowercase).collect();
+        words.join("-")
+    };
+    format!("{}-{}", clean(stamp), clean(species))
+}
+
+/// Save `card` in `dir` as ANSI text, named after when it was taken and the species,
+/// and return where it went.
+pub fn save(card: &PhotoCard, stamp: &str, dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stem = file_stem(stamp, &card.fish.species_name);
+    let mut path = dir.join(format!("{}.ans", stem));
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = dir.join(format!("{}-{}.ans", stem, n));
+    }
+    fs::write(&path, headless::buffer_ansi(&card.to_buffer()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+    use ratatui::text::Text;
+
+    #[test]
+    fn notable_catches_are_framed_and_saved() {
+        let fish = CaughtFish { species_name: "Great White".to_string(), size: 412.5, size_category: SizeCategory::Massive, shiny: false };
+        assert!(notable(&fish, false));
+        assert!(!notable(&CaughtFish { size_category: SizeCategory::Average, ..fish.clone() }, false));
+
+        let sprite = SpriteFrame::bake(&Text::from("><((((°>"));
+        let card = PhotoCard {
+            fish: &fish,
+            sprite: Some(&sprite),
+            taken: "2026-10-16 · 06:40".to_string(),
+            conditions: vec!["rain".to_string(), "light rain: fish +20% bites +30%".to_string()],
+            record: true,
+            theme: Theme::default(),
+        };
+        let buf = card.to_buffer();
+        let text = headless::buffer_text(&buf);
+        assert_eq!(card.size(), (36, 11));
+        for expected in ["New record", "><((((°>", "Great White", "412.5 cm · Massive!", "2026-10-16 · 06:40", "bites +30%"] {
+            assert!(text.contains(expected), "{} missing from\n{}", expected, text);
+        }
+        assert_eq!(buf[(2, 2)].bg, Theme::default().water);
+
+        let config = ConfigFile::parse("[photos]\ndir = \"/tmp/fish\"").unwrap();
+        assert_eq!(dir_from_table(config.section("photos").unwrap()), Ok(Some(PathBuf::from("/tmp/fish"))));
+        let bad = ConfigFile::parse("[photos]\ndir = 3").unwrap();
+        assert!(dir_from_table(bad.section("photos").unwrap()).is_err());
+
+        assert_eq!(file_stem("2026-10-16 06:40", "Great White"), "2026-10-16-06-40-great-white");
+        let dir = std::env::temp_dir().join(format!("fisherman-photos-{}", std::process::id()));
+        let first = save(&card, "2026-10-16 06:40", &dir).unwrap();
+        let second = save(&card, "2026-10-16 06:40", &dir).unwrap();
+        assert_ne!(first, second);
+        let saved = fs::read_to_string(&first).unwrap();
+        assert!(saved.contains("\x1b[") && saved.contains("Great White"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
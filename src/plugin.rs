@@ -0,0 +1,183 @@
+//! Scene plugins: actors of your own, such as a whale or a UFO, added to the scene
+//! without touching the main loop. A [`ScenePlugin`] is set up once, moved on every
+//! frame, drawn over the sky and the sea and told about each game event. The app
+//! holds whichever plugins its [`Config`](crate::app::Config) was given; the binary
+//! builds them by name from the [`PluginRegistry`] with `--plugin` or the `[plugins]`
+//! section of the config file:
+//!
+//! ```toml
+//! [plugins]
+//! enabled = ["whale"]
+//! ```
+//!
+//! Embedding the library, hand your own to the app directly, or [register] them to
+//! have them picked by name.
+//!
+//! [register]: PluginRegistry::register
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+use crate::config_file::{Table, Value};
+use crate::events::GameEvent;
+use crate::layer::Layer;
+use crate::theme::Theme;
+use crate::whale::Whale;
+
+/// What a plugin gets to know about the scene it's in.
+#[derive(Debug, Clone, Copy)]
+pub struct PluginContext {
+    /// The whole screen, and the sky and the sea within it.
+    pub screen: Rect,
+    pub sky: Rect,
+    pub ocean: Rect,
+    /// 0 at midnight to 1 at noon.
+    pub daylight: f32,
+    pub theme: Theme,
+    /// Motion is reduced: anything that would move should hold still.
+    pub still: bool,
+}
+
+/// An actor of its own in the scene.
+pub trait ScenePlugin {
+    /// The name it's picked by and logged under.
+    fn name(&self) -> &str;
+
+    /// Set up, once, before the first frame.
+    fn init(&mut self, _ctx: &PluginContext) {}
+
+    /// Move on by `dt`. Not called while paused.
+    fn update(&mut self, dt: Duration, ctx: &PluginContext);
+
+    /// Draw at the end of [`layer`](ScenePlugin::layer), over everything else on it.
+    /// Anything outside `ctx.screen` must be left alone.
+    fn render(&self, buf: &mut Buffer, ctx: &PluginContext);
+
+    /// The layer drawn on: by default over the sky and the sea, after the birds and
+    /// boats and before the weather.
+    fn layer(&self) -> Layer {
+        Layer::Ambient
+    }
+
+    /// React to something happening in the game.
+    fn on_event(&mut self, _event: &GameEvent) {}
+
+    /// How much more or less often new fish of `species` should turn up: 1 as usual,
+    /// 0 for never.
+    fn spawn_weight(&self, _species: &str) -> f32 {
+        1.0
+    }
+}
+
+/// Makes a plugin from the run's seed, so it can be as random as it likes and still
+/// play the same under `--seed`.
+pub type Factory = fn(u64) -> Box<dyn ScenePlugin>;
+
+/// The plugins that can be picked by name.
+#[derive(Debug, Clone, Default)]
+pub struct PluginRegistry {
+    factories: Vec<(&'static str, Factory)>,
+}
+
+impl PluginRegistry {
+    /// The plugins that come with the game.
+    pub fn builtin() -> PluginRegistry {
+        let mut registry = PluginRegistry::default();
+        registry.register("whale", |seed| Box::new(Whale::new(seed)));
+        registry
+    }
+
+    /// Make `factory` the plugin called `name`, in place of any already called that.
+    pub fn register(&mut self, name: &'static str, factory: Factory) {
+        self.factories.retain(|(known, _)| *known != name);
+        self.factories.push((name, factory));
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.factories.iter().map(|(name, _)| *name)
+    }
+
+    /// The plugin called `name`, made from `seed`.
+    pub fn create(&self, name: &str, seed: u64) -> Result<Box<dyn ScenePlugin>, String> {
+        match self.factories.iter().find(|(known, _)| known.eq_ignore_ascii_case(name)) {
+            Some((_, factory)) => Ok(factory(seed)),
+            None => Err(format!("unknown plugin '{}' (expected one of {})", name, self.names().collect::<Vec<_>>().join(", "))),
+        }
+    }
+}
+
+/// The plugins `enabled` in the `[plugins]` section.
+pub fn enabled_from_table(table: &Table) -> Result<Vec<String>, String> {
+    let mut enabled = Vec::new();
+    for (key, value) in table {
+        match (key.as_str(), value) {
+            ("enabled", Value::Array(names)) => {
+                for name in names {
+                    let Value::String(name) = name else {
+                        return Err("[plugins] enabled must be a list of plugin names".to_string());
+                    };
+                    enabled.push(name.clone());
+                }
+            }
+            ("enabled", _) => return Err("[plugins] enabled must be a list of plugin names".to_string()),
+            (key, _) => return Err(format!("unknown setting '{}' in [plugins]", key)),
+        }
+    }
+    Ok(enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+
+    /// Counts what it's told.
+    struct Counter {
+        frames: u32,
+        events: u32,
+    }
+
+    impl ScenePlugin for Counter {
+        fn name(&self) -> &str {
+            "counter"
+        }
+
+        fn update(&mut self, _dt: Duration, _ctx: &PluginContext) {
+            self.frames += 1;
+        }
+
+        fn render(&self, buf: &mut Buffer, ctx: &PluginContext) {
+            buf.set_string(ctx.screen.x, ctx.screen.y, self.frames.to_string(), ratatui::style::Style::default());
+        }
+
+        fn on_event(&mut self, _event: &GameEvent) {
+            self.events += 1;
+        }
+    }
+
+    #[test]
+    fn plugins_are_made_by_name_from_the_registry() {
+        let mut registry = PluginRegistry::builtin();
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["whale"]);
+        assert_eq!(registry.create("Whale", 1).unwrap().name(), "whale");
+        assert!(registry.create("ufo", 1).err().unwrap().contains("whale"));
+
+        registry.register("counter", |_| Box::new(Counter { frames: 0, events: 0 }));
+        let mut counter = registry.create("counter", 1).unwrap();
+        let area = Rect::new(0, 0, 10, 4);
+        let ctx = PluginContext { screen: area, sky: area, ocean: area, daylight: 1.0, theme: Theme::default(), still: false };
+        counter.init(&ctx);
+        counter.update(Duration::from_secs(1), &ctx);
+        counter.on_event(&GameEvent::Cast);
+        let mut buf = Buffer::empty(area);
+        counter.render(&mut buf, &ctx);
+        assert_eq!(buf[(0, 0)].symbol(), "1");
+
+        let config = ConfigFile::parse("[plugins]\nenabled = [\"whale\"]").unwrap();
+        assert_eq!(enabled_from_table(config.section("plugins").unwrap()), Ok(vec!["whale".to_string()]));
+        let bad = ConfigFile::parse("[plugins]\nenabled = \"whale\"").unwrap();
+        assert!(enabled_from_table(bad.section("plugins").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,442 @@
+//! Just enough PNG decoding for `fisherman import-sprite`: every colour type and bit
+//! depth, without interlacing, into 8-bit RGBA. Includes the DEFLATE decompressor PNG
+//! needs, since the sprites are small and speed doesn't matter.
+
+/// Decoded pixels, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+}
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+/// Refuse images that would take more than this many pixels of memory.
+const MAX_PIXELS: u64 = 4096 * 4096;
+
+pub fn decode(data: &[u8]) -> Result<Image, String> {
+    let mut rest = data.strip_prefix(&SIGNATURE[..]).ok_or("not a PNG file")?;
+    let mut header = None;
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut transparency: Vec<u8> = Vec::new();
+    let mut compressed = Vec::new();
+    while rest.len() >= 8 {
+        let length = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let kind = &rest[4..8];
+        let body = rest.get(8..8 + length).ok_or("truncated chunk")?;
+        match kind {
+            b"IHDR" => header = Some(Header::parse(body)?),
+            b"PLTE" => palette = body.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect(),
+            b"tRNS" => transparency = body.to_vec(),
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        // Skip the CRC as well
+        rest = rest.get(12 + length..).unwrap_or_default();
+    }
+    let header = header.ok_or("missing IHDR chunk")?;
+    let raw = zlib_decompress(&compressed)?;
+    let rows = header.unfilter(&raw)?;
+    header.to_rgba(&rows, &palette, &transparency)
+}
+
+struct Header {
+    width: u32,
+    height: u32,
+    depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    fn parse(body: &[u8]) -> Result<Header, String> {
+        if body.len() < 13 {
+            return Err("short IHDR chunk".to_string());
+        }
+        let header = Header {
+            width: u32::from_be_bytes([body[0], body[1], body[2], body[3]]),
+            height: u32::from_be_bytes([body[4], body[5], body[6], body[7]]),
+            depth: body[8],
+            color_type: body[9],
+        };
+        if body[12] != 0 {
+            return Err("interlaced PNGs are not supported; save it without interlacing".to_string());
+        }
+        let valid_depth = match header.color_type {
+            0 => matches!(header.depth, 1 | 2 | 4 | 8 | 16),
+            3 => matches!(header.depth, 1 | 2 | 4 | 8),
+            2 | 4 | 6 => matches!(header.depth, 8 | 16),
+            _ => false,
+        };
+        if !valid_depth {
+            return Err(format!("unsupported colour type {} at bit depth {}", header.color_type, header.depth));
+        }
+        if header.width == 0 || header.height == 0 || header.width as u64 * header.height as u64 > MAX_PIXELS {
+            return Err(format!("unsupported image size {}x{}", header.width, header.height));
+        }
+        Ok(header)
+    }
+
+    fn channels(&self) -> usize {
+        match self.color_type {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    fn stride(&self) -> usize {
+        (self.width as usize * self.channels() * self.depth as usize).div_ceil(8)
+    }
+
+    /// Undo the per-row filters, returning the rows without their filter bytes.
+    fn unfilter(&self, raw: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+        let stride = self.stride();
+        let bpp = (self.channels() * self.depth as usize).div_ceil(8);
+        let mut rows: Vec<Vec<u8>> = Vec::with_capacity(self.height as usize);
+        for line in raw.chunks(stride + 1).take(self.height as usize) {
+            let (&filter, data) = line.split_first().ok_or("truncated image data")?;
+            if data.len() != stride {
+                return Err("truncated image data".to_string());
+            }
+            let prior = rows.last().cloned().unwrap_or_else(|| vec![0; stride]);
+            let mut row = data.to_vec();
+            for i in 0..stride {
+                let a = if i >= bpp { row[i - bpp] } else { 0 };
+                let b = prior[i];
+                let c = if i >= bpp { prior[i - bpp] } else { 0 };
+                let predicted = match filter {
+                    0 => 0,
+                    1 => a,
+                    2 => b,
+                    3 => ((a as u16 + b as u16) / 2) as u8,
+                    4 => paeth(a, b, c),
+                    other => return Err(format!("unknown row filter {}", other)),
+                };
+                row[i] = row[i].wrapping_add(predicted);
+            }
+            rows.push(row);
+        }
+        if rows.len() != self.height as usize {
+            return Err("truncated image data".to_string());
+        }
+        Ok(rows)
+    }
+
+    fn to_rgba(&self, rows: &[Vec<u8>], palette: &[[u8; 3]], transparency: &[u8]) -> Result<Image, String> {
+        let channels = self.channels();
+        let depth = self.depth;
+        let scale = |v: u16| match depth {
+            16 => (v >> 8) as u8,
+            8 => v as u8,
+            d => (v as u32 * 255 / ((1u32 << d) - 1)) as u8,
+        };
+        // The one colour tRNS marks transparent in grey and RGB images, as raw samples
+        let key: Vec<u16> = transparency.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+        let mut pixels = Vec::with_capacity(self.width as usize * self.height as usize);
+        for row in rows {
+            for x in 0..self.width as usize {
+                let s: Vec<u16> = (0..channels).map(|c| sample(row, x * channels + c, depth)).collect();
+                let pixel = match self.color_type {
+                    0 => {
+                        let g = scale(s[0]);
+                        let alpha = if key.first() == Some(&s[0]) { 0 } else { 255 };
+                        [g, g, g, alpha]
+                    }
+                    2 => {
+                        let alpha = if key.len() == 3 && key[..] == s[..] { 0 } else { 255 };
+                        [scale(s[0]), scale(s[1]), scale(s[2]), alpha]
+                    }
+                    3 => {
+                        let index = s[0] as usize;
+                        let [r, g, b] = *palette.get(index).ok_or("palette index out of range")?;
+                        [r, g, b, transparency.get(index).copied().unwrap_or(255)]
+                    }
+                    4 => {
+                        let g = scale(s[0]);
+                        [g, g, g, scale(s[1])]
+                    }
+                    _ => [scale(s[0]), scale(s[1]), scale(s[2]), scale(s[3])],
+                };
+                pixels.push(pixel);
+            }
+        }
+        Ok(Image { width: self.width, height: self.height, pixels })
+    }
+}
+
+/// The `index`th sample of a row packed at `depth` bits per sample.
+fn sample(row: &[u8], index: usize, depth: u8) -> u16 {
+    match depth {
+        16 => u16::from_be_bytes([row[2 * index], row[2 * index + 1]]),
+        8 => row[index] as u16,
+        d => {
+            let per_byte = (8 / d) as usize;
+            let shift = 8 - d as usize * (index % per_byte + 1);
+            ((row[index / per_byte] >> shift) & ((1u8 << d) - 1)) as u16
+        }
+    }
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn zlib_decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    match data {
+        [cmf, flg, rest @ ..] if cmf & 0x0f == 8 && (*cmf as u16 * 256 + *flg as u16).is_multiple_of(31) => {
+            if flg & 0x20 != 0 {
+                return Err("zlib preset dictionaries are not supported".to_string());
+            }
+            inflate(rest)
+        }
+        _ => Err("image data is not zlib-compressed".to_string()),
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitReader<'_> {
+    /// The next `n` bits, least significant first.
+    fn bits(&mut self, n: u32) -> Result<u32, String> {
+        while self.count < n {
+            let byte = *self.data.get(self.pos).ok_or("unexpected end of compressed data")?;
+            self.pos += 1;
+            self.buffer |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u32 << n) - 1);
+        self.buffer >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+/// A canonical Huffman code, decoded a bit at a time.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Huffman {
+        let mut counts = [0u16; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0u16; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<u16, String> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..16 {
+            code |= bits.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code".to_string())
+    }
+}
+
+const LENGTH_BASE: [u16; 29] =
+    [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] =
+    [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+/// Order the code length code lengths are stored in.
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Undo DEFLATE compression, as in zlib streams, gzip files and zip archives.
+pub(crate) fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bits = BitReader { data, pos: 0, buffer: 0, count: 0 };
+    let mut out = Vec::new();
+    loop {
+        let last = bits.bits(1)? == 1;
+        match bits.bits(2)? {
+            0 => {
+                bits.align();
+                let header = data.get(bits.pos..bits.pos + 4).ok_or("truncated stored block")?;
+                let length = u16::from_le_bytes([header[0], header[1]]) as usize;
+                let start = bits.pos + 4;
+                out.extend_from_slice(data.get(start..start + length).ok_or("truncated stored block")?);
+                bits.pos = start + length;
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                inflate_block(&mut bits, &mut out, &Huffman::new(&lengths), &Huffman::new(&[5; 30]))?;
+            }
+            2 => {
+                let (literals, distances) = read_dynamic_codes(&mut bits)?;
+                inflate_block(&mut bits, &mut out, &literals, &distances)?;
+            }
+            _ => return Err("invalid compressed block type".to_string()),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn read_dynamic_codes(bits: &mut BitReader) -> Result<(Huffman, Huffman), String> {
+    let literal_count = bits.bits(5)? as usize + 257;
+    let distance_count = bits.bits(5)? as usize + 1;
+    let code_length_count = bits.bits(4)? as usize + 4;
+    let mut code_lengths = [0u8; 19];
+    for &i in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[i] = bits.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths);
+
+    let mut lengths: Vec<u8> = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match code_length_code.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last().ok_or("repeat with no previous length")?, 3 + bits.bits(2)?),
+            17 => (0, 3 + bits.bits(3)?),
+            18 => (0, 11 + bits.bits(7)?),
+            _ => return Err("invalid code length".to_string()),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+    if lengths.len() > literal_count + distance_count {
+        return Err("code lengths overrun".to_string());
+    }
+    Ok((Huffman::new(&lengths[..literal_count]), Huffman::new(&lengths[literal_count..])))
+}
+
+fn inflate_block(bits: &mut BitReader, out: &mut Vec<u8>, literals: &Huffman, distances: &Huffman) -> Result<(), String> {
+    loop {
+        let symbol = literals.decode(bits)? as usize;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let i = symbol - 257;
+                let base = *LENGTH_BASE.get(i).ok_or("invalid length code")?;
+                let length = base as usize + bits.bits(LENGTH_EXTRA[i] as u32)? as usize;
+                let d = distances.decode(bits)? as usize;
+                let base = *DISTANCE_BASE.get(d).ok_or("invalid distance code")?;
+                let distance = base as usize + bits.bits(DISTANCE_EXTRA[d] as u32)? as usize;
+                if distance > out.len() {
+                    return Err("distance points before the start of the data".to_string());
+                }
+                let start = out.len() - distance;
+                for k in 0..length {
+                    out.push(out[start + k]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wrap zlib data in a minimal PNG. The decoder skips CRCs, so they're left zero.
+    fn png(width: u32, height: u32, color_type: u8, zlib: &[u8]) -> Vec<u8> {
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        ihdr.extend_from_slice(&[8, color_type, 0, 0, 0]);
+        let mut data = SIGNATURE.to_vec();
+        for (kind, body) in [(b"IHDR", &ihdr[..]), (b"IDAT", zlib), (b"IEND", &[])] {
+            data.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            data.extend_from_slice(kind);
+            data.extend_from_slice(body);
+            data.extend_from_slice(&[0; 4]);
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_filtered_rgba() {
+        // Red, green, transparent over blue, white, grey; the second row uses the Up filter
+        let zlib = [
+            0x78, 0xda, 0x63, 0xf8, 0xcf, 0xc0, 0xf0, 0x1f, 0x0c, 0x81, 0x80, 0x89, 0x11, 0xca, 0x6e, 0x68, 0x68,
+            0xf8, 0x0f, 0x00, 0x72, 0x52, 0x09, 0x7c,
+        ];
+        let image = decode(&png(3, 2, 6, &zlib)).unwrap();
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(image.pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(image.pixel(1, 0), [0, 255, 0, 255]);
+        assert_eq!(image.pixel(2, 0)[3], 0);
+        assert_eq!(image.pixel(0, 1), [0, 0, 255, 255]);
+        assert_eq!(image.pixel(2, 1), [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn inflates_stored_and_dynamic_blocks() {
+        let stored = [0x01, 0x03, 0x00, 0xfc, 0xff, b'a', b'b', b'c'];
+        assert_eq!(inflate(&stored).unwrap(), b"abc");
+
+        // 200 random letters from "aaaaaaaabbbbccd", which zlib codes with a dynamic block
+        let dynamic = [
+            0x78, 0xda, 0x2d, 0x8e, 0xd1, 0x15, 0x00, 0x20, 0x08, 0x02, 0x67, 0xf5, 0x60, 0xff, 0x19, 0x02, 0xad,
+            0x0f, 0xe4, 0x01, 0xa1, 0x83, 0xa4, 0xc9, 0x0b, 0x30, 0xcb, 0x5c, 0x1a, 0x91, 0x8e, 0xa0, 0x2b, 0xd2,
+            0x59, 0xe5, 0x52, 0xcc, 0x8f, 0xc7, 0x94, 0x71, 0x1d, 0x56, 0x37, 0x90, 0xa4, 0xbc, 0x11, 0x8f, 0x68,
+            0xcb, 0x56, 0xed, 0x7f, 0x23, 0xb8, 0x42, 0x7d, 0x67, 0xfb, 0xa3, 0x72, 0x0b, 0xaa, 0x5b, 0x4b, 0x8b,
+            0x29, 0xab, 0x35, 0xb9, 0xd3, 0xdd, 0x81, 0xb8, 0x7b, 0x2f, 0xaf, 0xbb, 0xee, 0x01, 0x02, 0x80, 0x4c,
+            0x6c,
+        ];
+        let text = zlib_decompress(&dynamic).unwrap();
+        assert!(text.starts_with(b"abcccaaaacaabacaaaadc"));
+        let counts: Vec<usize> = b"abcd".iter().map(|c| text.iter().filter(|t| *t == c).count()).collect();
+        assert_eq!(counts, [96, 59, 31, 14]);
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        assert!(decode(b"GIF89a").is_err());
+    }
+}
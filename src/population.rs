@@ -0,0 +1,99 @@
+//! How many of each species are left in the local waters, saved with the game. Every
+//! fish landed takes from its species' stock, new fish are picked with the stocks
+//! weighing in, and the stocks refill over real time, including while the game is
+//! closed. Fishing one kind out for an evening turns up the others instead.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Share of a full stock each fish landed takes.
+const CATCH_TOLL: f32 = 0.15;
+/// Share of a full stock that comes back in an hour.
+const REFILL_PER_HOUR: f32 = 0.5;
+/// A fished-out species still turns up now and then, this much as often as at full
+/// stock.
+const MIN_SPAWN_WEIGHT: f32 = 0.1;
+
+/// The stocks, by species name, from 0.0 (fished out) to 1.0 (full). Species at full
+/// stock aren't listed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Population {
+    stocks: BTreeMap<String, f32>,
+    /// Seconds since the Unix epoch the stocks were last saved at, to refill them for
+    /// the time the game was closed.
+    saved_at: u64,
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs())
+}
+
+impl Population {
+    pub fn stock(&self, species: &str) -> f32 {
+        self.stocks.get(species).copied().unwrap_or(1.0)
+    }
+
+    /// Take a landed fish from its species' stock.
+    pub fn take(&mut self, species: &str) {
+        let stock = (self.stock(species) - CATCH_TOLL).max(0.0);
+        self.stocks.insert(species.to_string(), stock);
+    }
+
+    /// Refill every stock for `dt` of real time.
+    pub fn replenish(&mut self, dt: Duration) {
+        let refill = REFILL_PER_HOUR * dt.as_secs_f32() / 3600.0;
+        for stock in self.stocks.values_mut() {
+            *stock += refill;
+        }
+        self.stocks.retain(|_, stock| *stock < 1.0);
+    }
+
+    /// Note the time of saving.
+    pub fn stamp(&mut self, now: SystemTime) {
+        self.saved_at = unix_secs(now);
+    }
+
+    /// Refill the stocks for the time since they were saved.
+    pub fn catch_up(&mut self, now: SystemTime) {
+        if self.saved_at > 0 {
+            self.replenish(Duration::from_secs(unix_secs(now).saturating_sub(self.saved_at)));
+        }
+    }
+
+    /// How much each of `species` counts for when picking a new fish's species.
+    pub fn spawn_weights(&self, species: &[String]) -> Vec<f32> {
+        species.iter().map(|name| self.stock(name).max(MIN_SPAWN_WEIGHT)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catches_thin_a_stock_that_fills_back_up_over_time() {
+        let mut population = Population::default();
+        for _ in 0..10 {
+            population.take("Cod");
+        }
+        assert_eq!(population.stock("Cod"), 0.0);
+        assert_eq!(population.stock("Tuna"), 1.0);
+        let names = ["Cod".to_string(), "Tuna".to_string()];
+        assert_eq!(population.spawn_weights(&names), vec![MIN_SPAWN_WEIGHT, 1.0]);
+
+        population.replenish(Duration::from_secs(3600));
+        assert!((population.stock("Cod") - REFILL_PER_HOUR).abs() < 1e-4);
+
+        // The time the game was closed counts too, and a full stock drops off the list
+        let saved = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        population.stamp(saved);
+        let json = serde_json::to_string(&population).unwrap();
+        let mut restored: Population = serde_json::from_str(&json).unwrap();
+        restored.catch_up(saved + Duration::from_secs(2 * 3600));
+        assert_eq!(restored.stock("Cod"), 1.0);
+        assert!(restored.stocks.is_empty());
+    }
+}
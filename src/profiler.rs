@@ -0,0 +1,199 @@
+//! Frame timings, for checking the rendering keeps up on large terminals. `--profile`
+//! shows how long each frame spends updating, drawing and polling for input, with a
+//! rolling graph of the frame rate, in the top-right corner. `--bench N` runs N
+//! simulated frames off-screen and prints statistics of how long they took.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use crate::app::App;
+use crate::headless::FRAME_TIME;
+
+/// Frames kept for the averages and the graph.
+const HISTORY: usize = 120;
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const OVERLAY_WIDTH: u16 = 30;
+
+/// Where one frame's time went.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameTimes {
+    pub update: Duration,
+    pub draw: Duration,
+    /// Waiting for input and handling it, which is most of a frame when idle.
+    pub poll: Duration,
+}
+
+impl FrameTimes {
+    pub fn total(&self) -> Duration {
+        self.update + self.draw + self.poll
+    }
+}
+
+/// The timings of the latest frames.
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    frames: VecDeque<FrameTimes>,
+}
+
+impl Profiler {
+    pub fn record(&mut self, times: FrameTimes) {
+        if self.frames.len() == HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(times);
+    }
+
+    /// The mean of each part over the frames kept.
+    pub fn mean(&self) -> FrameTimes {
+        let count = self.frames.len().max(1) as u32;
+        let sum = |part: fn(&FrameTimes) -> Duration| self.frames.iter().map(part).sum::<Duration>() / count;
+        FrameTimes { update: sum(|t| t.update), draw: sum(|t| t.draw), poll: sum(|t| t.poll) }
+    }
+
+    /// Frames a second over the frames kept.
+    pub fn fps(&self) -> f64 {
+        let total: Duration = self.frames.iter().map(FrameTimes::total).sum();
+        if total.is_zero() { 0.0 } else { self.frames.len() as f64 / total.as_secs_f64() }
+    }
+}
+
+/// The `--profile` panel, in the top-right corner of the area.
+pub struct ProfileOverlay<'a> {
+    pub profiler: &'a Profiler,
+}
+
+impl Widget for ProfileOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = OVERLAY_WIDTH.min(area.width);
+        let rect = Rect::new(area.right().saturating_sub(width), area.y, width, 7.min(area.height));
+        let mean = self.profiler.mean();
+        let row = |label: &str, took: Duration| Line::from(format!(" {:<7}{:>8.2} ms", label, millis(took)));
+        // The rate frame by frame, the newest at the right, scaled to the fastest shown
+        let room = width.saturating_sub(2) as usize;
+        let rates: Vec<f64> = self.profiler.frames.iter().rev().take(room).rev().map(|t| 1.0 / t.total().as_secs_f64().max(1e-6)).collect();
+        let top = rates.iter().copied().fold(f64::EPSILON, f64::max);
+        let graph: String = rates.iter().map(|rate| BARS[((rate / top) * (BARS.len() - 1) as f64).round() as usize]).collect();
+        let lines = vec![
+            row("update", mean.update),
+            row("draw", mean.draw),
+            row("poll", mean.poll),
+            Line::from(Span::styled(format!(" {:.1} fps", self.profiler.fps()), Style::default().fg(Color::Yellow))),
+            Line::from(Span::styled(graph, Style::default().fg(Color::Green))),
+        ];
+        Clear.render(rect, buf);
+        Paragraph::new(lines)
+            .block(Block::default().title("Profile").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White))
+            .render(rect, buf);
+    }
+}
+
+fn millis(took: Duration) -> f64 {
+    took.as_secs_f64() * 1000.0
+}
+
+/// How long each of the frames of a `--bench` run took to update and to draw.
+#[derive(Debug, Clone, Default)]
+pub struct Bench {
+    pub size: Rect,
+    pub update: Vec<Duration>,
+    pub draw: Vec<Duration>,
+}
+
+/// Advance `app` by [`FRAME_TIME`] and draw it off-screen, `frames` times, timing both.
+pub fn bench(app: &mut App, frames: usize) -> Bench {
+    let size = app.size();
+    let Ok(mut terminal) = Terminal::new(TestBackend::new(size.width, size.height));
+    let mut bench = Bench { size, ..Bench::default() };
+    for _ in 0..frames {
+        let started = Instant::now();
+        app.update(FRAME_TIME);
+        let updated = Instant::now();
+        let Ok(_) = terminal.draw(|f| app.draw(f));
+        bench.update.push(updated - started);
+        bench.draw.push(updated.elapsed());
+    }
+    bench
+}
+
+impl Bench {
+    /// The run's statistics, a row for each of updating, drawing and both.
+    pub fn report(&self) -> String {
+        let total: Vec<Duration> = self.update.iter().zip(&self.draw).map(|(update, draw)| *update + *draw).collect();
+        let took: Duration = total.iter().sum();
+        let rate = if took.is_zero() { 0.0 } else { total.len() as f64 / took.as_secs_f64() };
+        let mut out = String::new();
+        // Writing to a String can't fail
+        let _ = writeln!(
+            out,
+            "{} frames at {}×{} in {:.3}s ({:.1} frames/s)",
+            total.len(),
+            self.size.width,
+            self.size.height,
+            took.as_secs_f64(),
+            rate
+        );
+        let _ = writeln!(out, "{:<8}{:>10}{:>10}{:>10}{:>10}{:>10}{:>10}", "", "min", "mean", "p50", "p95", "p99", "max");
+        for (label, times) in [("update", &self.update), ("draw", &self.draw), ("total", &total)] {
+            let _ = write!(out, "{:<8}", label);
+            for value in statistics(times) {
+                let _ = write!(out, "{:>7.3} ms", millis(value));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The least, the mean, the 50th, 95th and 99th percentiles and the most of `times`.
+fn statistics(times: &[Duration]) -> [Duration; 6] {
+    if times.is_empty() {
+        return [Duration::ZERO; 6];
+    }
+    let mut sorted = times.to_vec();
+    sorted.sort();
+    let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+    let mean = sorted.iter().sum::<Duration>() / sorted.len() as u32;
+    [sorted[0], mean, percentile(0.5), percentile(0.95), percentile(0.99), sorted[sorted.len() - 1]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{Assets, Config};
+
+    #[test]
+    fn frames_are_timed_for_the_overlay_and_the_bench() {
+        let mut profiler = Profiler::default();
+        for poll in [40, 40, 15] {
+            let times = FrameTimes { update: Duration::from_millis(2), draw: Duration::from_millis(8), poll: Duration::from_millis(poll) };
+            profiler.record(times);
+        }
+        assert_eq!(profiler.mean().draw, Duration::from_millis(8));
+        assert!((profiler.fps() - 24.0).abs() < 0.01);
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+        ProfileOverlay { profiler: &profiler }.render(area, &mut buf);
+        let row = |y: u16| (0..60).map(|x| buf[(x, y)].symbol().to_string()).collect::<String>();
+        assert!(row(2).contains("draw       8.00 ms"), "{}", row(2));
+        assert!(row(4).contains("24.0 fps"));
+        assert!(row(5).contains("▅▅█"), "{}", row(5));
+
+        let mut app = App::new(Config { seed: Some(1), ..Config::default() }, Assets::load(), None, Rect::new(0, 0, 120, 40));
+        let run = bench(&mut app, 5);
+        assert_eq!((run.update.len(), run.draw.len()), (5, 5));
+        let report = run.report();
+        assert!(report.starts_with("5 frames at 120×40 in "), "{}", report);
+        assert_eq!(report.lines().count(), 5);
+        assert!(report.lines().nth(3).unwrap().starts_with("draw "));
+    }
+}
@@ -0,0 +1,162 @@
+//! Session recording (`--record <path>`) and replay (`fisherman replay <path>`).
+//!
+//! Given the same seed, starting state and screen size, a scene only changes with the
+//! time that passes between updates, the input events and the signals that arrive.
+//! A recording is those, in order, as JSON lines after a header line holding the rest,
+//! so replaying it steps a fresh [`App`] through exactly the same frames.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crossterm::event::Event;
+use serde::{Deserialize, Serialize};
+
+use crate::app::App;
+use crate::game_state::GameState;
+use crate::handoff::Handoff;
+use crate::signals::{self, SharedSignal};
+
+/// How the recorded run started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Header {
+    /// The command line, so the replay runs with the same options.
+    pub args: Vec<String>,
+    pub seed: u64,
+    pub width: u16,
+    pub height: u16,
+    /// Whether the terminal reported key releases, which changes what a held key does.
+    pub key_release: bool,
+    /// The saved game the run picked up, if any.
+    pub saved: Option<GameState>,
+    /// The handoff the run restored on top of it, if any.
+    pub handoff: Option<Handoff>,
+}
+
+/// One thing that happened to the scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Entry {
+    /// An update after this much time.
+    Update(Duration),
+    Event(Event),
+    /// A signal line delivered before the next update.
+    Signal(String),
+}
+
+impl Entry {
+    /// Play the entry back into `app`, delivering signals to `inbox`.
+    pub fn apply(&self, app: &mut App, inbox: Option<&SharedSignal>) {
+        match self {
+            Entry::Update(dt) => app.update(*dt),
+            Entry::Event(event) => app.handle_event(event.clone()),
+            Entry::Signal(line) => {
+                if let Some(inbox) = inbox
+                    && let Some(message) = signals::parse_signal_line(line)
+                {
+                    inbox.lock().unwrap().push_back(message);
+                }
+            }
+        }
+    }
+}
+
+/// Writes a recording as the run goes.
+pub struct Recorder {
+    out: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, header: &Header) -> io::Result<Recorder> {
+        let mut out = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut out, header)?;
+        out.write_all(b"\n")?;
+        Ok(Recorder { out })
+    }
+
+    pub fn record(&mut self, entry: &Entry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, entry)?;
+        self.out.write_all(b"\n")
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// A recording read back in whole.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub header: Header,
+    pub entries: Vec<Entry>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> io::Result<Recording> {
+        let invalid = |line: usize, e: serde_json::Error| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line, e))
+        };
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?).map_err(|e| invalid(1, e))?,
+            None => return Err(io::Error::new(io::ErrorKind::InvalidData, "empty recording")),
+        };
+        let mut entries = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            // A run that was killed can leave a torn last line; keep what came before it
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) if e.is_eof() => break,
+                Err(e) => return Err(invalid(i + 2, e)),
+            }
+        }
+        Ok(Recording { header, entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{Assets, Config};
+    use crate::headless;
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::layout::Rect;
+
+    fn run(entries: &[Entry]) -> String {
+        let config = Config { seed: Some(11), ..Config::default() };
+        let mut app = App::new(config, Assets::load(), None, Rect::new(0, 0, 80, 24));
+        for entry in entries {
+            entry.apply(&mut app, None);
+        }
+        let Ok(mut terminal) = Terminal::new(TestBackend::new(80, 24));
+        let Ok(frame) = terminal.draw(|f| app.draw(f));
+        headless::buffer_text(frame.buffer)
+    }
+
+    #[test]
+    fn a_recording_replays_the_same_scene() {
+        let key = |kind| Entry::Event(Event::Key(KeyEvent::new_with_kind(KeyCode::Char(' '), KeyModifiers::NONE, kind)));
+        let mut entries = vec![key(KeyEventKind::Press)];
+        entries.extend((0..20).map(|_| Entry::Update(Duration::from_millis(50))));
+        entries.push(key(KeyEventKind::Release));
+        entries.extend((0..40).map(|_| Entry::Update(Duration::from_millis(50))));
+
+        let header = Header { args: Vec::new(), seed: 11, width: 80, height: 24, key_release: false, saved: None, handoff: None };
+        let path = std::env::temp_dir().join(format!("fisherman-record-test-{}.fish", std::process::id()));
+        let mut recorder = Recorder::create(&path, &header).unwrap();
+        for entry in &entries {
+            recorder.record(entry).unwrap();
+        }
+        recorder.finish().unwrap();
+        let recording = Recording::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(recording.entries, entries);
+        assert_eq!(run(&recording.entries), run(&entries));
+        assert_ne!(run(&entries), run(&entries[..1]));
+    }
+}
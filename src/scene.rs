@@ -0,0 +1,124 @@
+//! Animated actors with a common lifecycle. Each [`Entity`] is moved on every frame,
+//! draws itself into the area it lives in and says when it's done, at which point the
+//! [`Scene`] holding it lets it go. A new kind of actor is an `Entity` and whatever
+//! spawns it into a scene; nothing else needs to know about it.
+
+use std::any::Any;
+use std::fmt;
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+use crate::theme::Theme;
+
+/// What an entity gets to know about the world it moves in.
+#[derive(Debug, Clone, Copy)]
+pub struct SceneContext {
+    /// The area the scene is drawn into; positions are relative to its top-left.
+    pub area: Rect,
+    /// 0 at midnight to 1 at noon.
+    pub daylight: f32,
+    pub theme: Theme,
+}
+
+/// An actor in a [`Scene`].
+pub trait Entity: Any {
+    /// Move on by `dt`.
+    fn update(&mut self, dt: Duration, ctx: &SceneContext);
+
+    /// Draw into `area`, the scene's, clipping to it.
+    fn render(&self, buf: &mut Buffer, area: Rect);
+
+    /// Whether to keep it; once `false` it is dropped from the scene.
+    fn alive(&self) -> bool {
+        true
+    }
+}
+
+/// The entities in one part of the screen, drawn in the order they were spawned.
+#[derive(Default)]
+pub struct Scene {
+    entities: Vec<Box<dyn Entity>>,
+}
+
+impl fmt::Debug for Scene {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Scene").field("entities", &self.entities.len()).finish()
+    }
+}
+
+impl Scene {
+    pub fn spawn(&mut self, entity: impl Entity) {
+        self.entities.push(Box::new(entity));
+    }
+
+    /// Move every entity on by `dt`, then drop those that are done.
+    pub fn update(&mut self, dt: Duration, ctx: &SceneContext) {
+        for entity in &mut self.entities {
+            entity.update(dt, ctx);
+        }
+        self.entities.retain(|entity| entity.alive());
+    }
+
+    pub fn render(&self, buf: &mut Buffer, area: Rect) {
+        for entity in &self.entities {
+            entity.render(buf, area);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// How many of the entities are a `T`.
+    pub fn count<T: Entity>(&self) -> usize {
+        self.entities.iter().filter(|entity| (entity.as_ref() as &dyn Any).is::<T>()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dot falling a row a second until it leaves the area.
+    struct Drop {
+        y: f32,
+        gone: bool,
+    }
+
+    impl Entity for Drop {
+        fn update(&mut self, dt: Duration, ctx: &SceneContext) {
+            self.y += dt.as_secs_f32();
+            self.gone = self.y >= ctx.area.height as f32;
+        }
+
+        fn render(&self, buf: &mut Buffer, area: Rect) {
+            buf[(area.x, area.y + self.y as u16)].set_char('.');
+        }
+
+        fn alive(&self) -> bool {
+            !self.gone
+        }
+    }
+
+    #[test]
+    fn entities_move_draw_and_leave_the_scene_once_done() {
+        let area = Rect::new(0, 0, 4, 3);
+        let ctx = SceneContext { area, daylight: 1.0, theme: Theme::default() };
+        let mut scene = Scene::default();
+        scene.spawn(Drop { y: 0.0, gone: false });
+        scene.spawn(Drop { y: 1.0, gone: false });
+        assert_eq!(scene.count::<Drop>(), 2);
+
+        scene.update(Duration::from_secs(1), &ctx);
+        let mut buf = Buffer::empty(area);
+        scene.render(&mut buf, area);
+        assert_eq!((buf[(0, 1)].symbol(), buf[(0, 2)].symbol()), (".", "."));
+
+        scene.update(Duration::from_secs(1), &ctx);
+        assert_eq!(scene.count::<Drop>(), 1);
+        scene.update(Duration::from_secs(1), &ctx);
+        assert!(scene.is_empty());
+    }
+}
@@ -0,0 +1,366 @@
+//! Lua scripts, with the `scripting` feature: `--script frenzy.lua` runs a script that
+//! reacts to what happens in the game, changes which fish turn up and puts text over
+//! the sky, without recompiling. A script is a [`ScenePlugin`] like any other, and
+//! talks to the game through the `fisherman` table:
+//!
+//! ```lua
+//! -- A fish frenzy every ten minutes
+//! fisherman.every(600, function()
+//!   fisherman.overlay("Fish frenzy!", 60)
+//!   fisherman.spawn_weight("Goby", 5)
+//!   fisherman.after(60, function() fisherman.spawn_weight("Goby", 1) end)
+//! end)
+//!
+//! fisherman.on("catch", function(fish)
+//!   if fish.size > 40 then fisherman.overlay("What a " .. fish.species .. "!", 3) end
+//! end)
+//! ```
+//!
+//! - `fisherman.on(event, fn)` calls `fn` for each `"cast"`, `"landed"` (`x`, `y`),
+//!   `"catch"` (`species`, `size`, `category`, `shiny`, `rarity`, `autopilot`),
+//!   `"signal"` (`success`, `message`), `"weather"` (`weather`) or `"round_over"`
+//!   (`points`, `catches`)
+//! - `fisherman.every(seconds, fn)` and `fisherman.after(seconds, fn)` call `fn` on game
+//!   time, which stands still while paused
+//! - `fisherman.spawn_weight(species, factor)` makes new fish of a species turn up
+//!   `factor` times as often, 0 for never and 1 as usual
+//! - `fisherman.overlay(text, seconds)` shows a line of text in the sky, 5 seconds if
+//!   not given
+//!
+//! Scripts can't reach the files or the system: only the `table`, `string`, `math` and
+//! `utf8` libraries are loaded, `print` goes to the log, memory is capped and a call
+//! that runs too long is stopped. A script that fails is logged and left running.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use mlua::{Function, HookTriggers, IntoLua, Lua, LuaOptions, RegistryKey, StdLib, Table, Value, Variadic};
+use ratatui::buffer::Buffer;
+use ratatui::style::Style;
+use unicode_width::UnicodeWidthStr;
+
+use crate::events::GameEvent;
+use crate::plugin::{PluginContext, ScenePlugin};
+
+/// The events `fisherman.on` takes.
+const EVENTS: [&str; 6] = ["cast", "landed", "catch", "signal", "weather", "round_over"];
+const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+/// How long loading the script or one call into it may run.
+const TIME_LIMIT: Duration = Duration::from_millis(250);
+const OVERLAY_SECONDS: f32 = 5.0;
+const MAX_OVERLAYS: usize = 4;
+
+/// A call for later, from `fisherman.every` or `fisherman.after`.
+struct Timer {
+    /// Game time it's due at.
+    at: f32,
+    every: Option<f32>,
+    function: RegistryKey,
+}
+
+/// What the script has asked of the game, kept beside the Lua state.
+#[derive(Default)]
+struct State {
+    /// Game time, in seconds since the script started.
+    clock: f32,
+    handlers: Vec<(String, RegistryKey)>,
+    timers: Vec<Timer>,
+    /// Spawn factors by lowercase species name.
+    weights: HashMap<String, f32>,
+    /// Text in the sky and the game time it goes at.
+    overlays: Vec<(String, f32)>,
+}
+
+/// A loaded Lua script.
+pub struct Script {
+    name: String,
+    lua: Lua,
+    /// When the call running now has to be done by.
+    deadline: Rc<Cell<Option<Instant>>>,
+}
+
+impl Script {
+    /// The script in the file at `path`, run from `seed` so `math.random` plays the same
+    /// under `--seed`.
+    pub fn load(path: &Path, seed: u64) -> Result<Script, String> {
+        let source = fs::read_to_string(path).map_err(|e| format!("could not read script {}: {}", path.display(), e))?;
+        let name = path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into_owned());
+        Script::new(&name, &source, seed).map_err(|e| format!("script {}: {}", path.display(), e))
+    }
+
+    /// The script `source`, called `name`, run from `seed`.
+    pub fn new(name: &str, source: &str, seed: u64) -> Result<Script, String> {
+        let lua = Lua::new_with(StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::UTF8, LuaOptions::default())
+            .map_err(|e| e.to_string())?;
+        let script = Script { name: name.to_string(), lua, deadline: Rc::new(Cell::new(None)) };
+        script.sandbox(seed).map_err(|e| e.to_string())?;
+        // What went wrong, without the stack traceback after it
+        script
+            .with_deadline(|lua| lua.load(source).set_name(name).exec())
+            .map_err(|e| e.to_string().lines().next().unwrap_or_default().to_string())?;
+        Ok(script)
+    }
+
+    /// Take away the base library's ways to the files, cap the memory, stop calls that
+    /// run on too long, and set up the `fisherman` table.
+    fn sandbox(&self, seed: u64) -> mlua::Result<()> {
+        let lua = &self.lua;
+        lua.set_memory_limit(MEMORY_LIMIT)?;
+        let deadline = Rc::clone(&self.deadline);
+        lua.set_hook(HookTriggers::new().every_nth_instruction(1000), move |_, _| match deadline.get() {
+            Some(deadline) if Instant::now() > deadline => Err(mlua::Error::runtime("script ran for too long")),
+            _ => Ok(()),
+        });
+        lua.set_app_data(State::default());
+
+        let globals = lua.globals();
+        for name in ["dofile", "loadfile", "load"] {
+            globals.set(name, Value::Nil)?;
+        }
+        let name = self.name.clone();
+        let print = lua.create_function(move |_, values: Variadic<Value>| {
+            let words: Vec<String> = values.iter().map(|value| value.to_string().unwrap_or_default()).collect();
+            tracing::info!("script {}: {}", name, words.join("\t"));
+            Ok(())
+        })?;
+        globals.set("print", print)?;
+        lua.load(format!("math.randomseed({})", seed as i64)).exec()?;
+
+        let fisherman = lua.create_table()?;
+        fisherman.set("on", lua.create_function(|lua, (event, function): (String, Function)| {
+            if !EVENTS.contains(&event.as_str()) {
+                return Err(mlua::Error::runtime(format!("unknown event '{}' (expected one of {})", event, EVENTS.join(", "))));
+            }
+            let key = lua.create_registry_value(function)?;
+            state(lua).handlers.push((event, key));
+            Ok(())
+        })?)?;
+        fisherman.set("every", lua.create_function(|lua, (seconds, function): (f32, Function)| {
+            schedule(lua, seconds, Some(seconds), function)
+        })?)?;
+        fisherman.set("after", lua.create_function(|lua, (seconds, function): (f32, Function)| {
+            schedule(lua, seconds, None, function)
+        })?)?;
+        fisherman.set("spawn_weight", lua.create_function(|lua, (species, factor): (String, f32)| {
+            if !factor.is_finite() || factor < 0.0 {
+                return Err(mlua::Error::runtime(format!("invalid spawn weight {} (expected 0 or more)", factor)));
+            }
+            state(lua).weights.insert(species.to_lowercase(), factor);
+            Ok(())
+        })?)?;
+        fisherman.set("overlay", lua.create_function(|lua, (text, seconds): (String, Option<f32>)| {
+            let mut state = state(lua);
+            let until = state.clock + seconds.unwrap_or(OVERLAY_SECONDS).max(0.0);
+            state.overlays.push((text, until));
+            let excess = state.overlays.len().saturating_sub(MAX_OVERLAYS);
+            state.overlays.drain(..excess);
+            Ok(())
+        })?)?;
+        globals.set("fisherman", fisherman)
+    }
+
+    /// Run `f` against the clock.
+    fn with_deadline<T>(&self, f: impl FnOnce(&Lua) -> mlua::Result<T>) -> mlua::Result<T> {
+        self.deadline.set(Some(Instant::now() + TIME_LIMIT));
+        let result = f(&self.lua);
+        self.deadline.set(None);
+        result
+    }
+
+    /// Call each of `functions` with `args`, logging any that fail.
+    fn call_all<'lua>(&'lua self, functions: Vec<Function<'lua>>, args: Value<'lua>) {
+        for function in functions {
+            if let Err(e) = self.with_deadline(|_| function.call::<_, ()>(args.clone())) {
+                tracing::warn!("script {}: {}", self.name, e);
+            }
+        }
+    }
+}
+
+/// The script's state. Never held across a call into Lua, which may want it too.
+fn state(lua: &Lua) -> mlua::AppDataRefMut<'_, State> {
+    lua.app_data_mut::<State>().expect("the state is set before the script runs")
+}
+
+fn schedule(lua: &Lua, seconds: f32, every: Option<f32>, function: Function) -> mlua::Result<()> {
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err(mlua::Error::runtime(format!("invalid interval {} (expected seconds, more than 0)", seconds)));
+    }
+    let function = lua.create_registry_value(function)?;
+    let mut state = state(lua);
+    let at = state.clock + seconds;
+    state.timers.push(Timer { at, every, function });
+    Ok(())
+}
+
+/// `event`'s name for `fisherman.on` and what its handlers are passed.
+fn event_args<'lua>(lua: &'lua Lua, event: &GameEvent) -> mlua::Result<(&'static str, Value<'lua>)> {
+    let table = |pairs: Vec<(&str, Value<'lua>)>| -> mlua::Result<Value<'lua>> {
+        let table: Table = lua.create_table()?;
+        for (key, value) in pairs {
+            table.set(key, value)?;
+        }
+        Ok(Value::Table(table))
+    };
+    Ok(match event {
+        GameEvent::Cast => ("cast", Value::Nil),
+        GameEvent::CastLanded { x, y } => ("landed", table(vec![("x", x.into_lua(lua)?), ("y", y.into_lua(lua)?)])?),
+        GameEvent::FishCaught { fish, rarity, autopilot } => (
+            "catch",
+            table(vec![
+                ("species", fish.species_name.as_str().into_lua(lua)?),
+                ("size", fish.size.into_lua(lua)?),
+                ("category", fish.size_category.as_str().trim_end_matches('!').into_lua(lua)?),
+                ("shiny", fish.shiny.into_lua(lua)?),
+                ("rarity", rarity.into_lua(lua)?),
+                ("autopilot", autopilot.into_lua(lua)?),
+            ])?,
+        ),
+        GameEvent::SignalReceived { success, message } => {
+            ("signal", table(vec![("success", success.into_lua(lua)?), ("message", message.as_str().into_lua(lua)?)])?)
+        }
+        GameEvent::WeatherChanged(weather) => ("weather", table(vec![("weather", weather.as_str().into_lua(lua)?)])?),
+        GameEvent::RoundOver { points, catches } => {
+            ("round_over", table(vec![("points", points.into_lua(lua)?), ("catches", catches.into_lua(lua)?)])?)
+        }
+    })
+}
+
+impl ScenePlugin for Script {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&mut self, dt: Duration, _ctx: &PluginContext) {
+        let lua = &self.lua;
+        let due: Vec<Function> = {
+            let mut state = state(lua);
+            state.clock += dt.as_secs_f32();
+            let now = state.clock;
+            state.overlays.retain(|(_, until)| *until > now);
+            let mut due = Vec::new();
+            state.timers.retain_mut(|timer| {
+                if timer.at > now {
+                    return true;
+                }
+                due.extend(lua.registry_value(&timer.function).ok());
+                match timer.every {
+                    Some(every) => {
+                        // A long frame runs it once, not once for each interval missed
+                        timer.at = (timer.at + every).max(now);
+                        true
+                    }
+                    None => false,
+                }
+            });
+            due
+        };
+        if due.is_empty() {
+            return;
+        }
+        self.call_all(due, Value::Nil);
+        lua.expire_registry_values();
+    }
+
+    fn render(&self, buf: &mut Buffer, ctx: &PluginContext) {
+        let state = state(&self.lua);
+        let style = Style::default().fg(ctx.theme.foam);
+        let area = ctx.sky.intersection(buf.area);
+        for (row, (text, _)) in state.overlays.iter().enumerate() {
+            let y = area.y + 1 + row as u16;
+            if y >= area.bottom() {
+                break;
+            }
+            let x = area.x + area.width.saturating_sub(text.width() as u16) / 2;
+            buf.set_stringn(x, y, text, area.right().saturating_sub(x) as usize, style);
+        }
+    }
+
+    fn on_event(&mut self, event: &GameEvent) {
+        let lua = &self.lua;
+        let (name, args) = match event_args(lua, event) {
+            Ok(args) => args,
+            Err(e) => {
+                tracing::warn!("script {}: {}", self.name, e);
+                return;
+            }
+        };
+        let handlers: Vec<Function> = state(lua)
+            .handlers
+            .iter()
+            .filter(|(event, _)| event == name)
+            .filter_map(|(_, key)| lua.registry_value(key).ok())
+            .collect();
+        self.call_all(handlers, args);
+    }
+
+    fn spawn_weight(&self, species: &str) -> f32 {
+        state(&self.lua).weights.get(&species.to_lowercase()).copied().unwrap_or(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+
+    use crate::fishing_game::{CaughtFish, SizeCategory};
+    use crate::theme::Theme;
+
+    const FRENZY: &str = r#"
+        fisherman.every(600, function()
+          fisherman.overlay("Fish frenzy!", 60)
+          fisherman.spawn_weight("Goby", 5)
+          fisherman.after(60, function() fisherman.spawn_weight("Goby", 1) end)
+        end)
+        fisherman.on("catch", function(fish)
+          if fish.size > 40 then fisherman.overlay("What a " .. fish.species .. "!", 3) end
+        end)
+    "#;
+
+    #[test]
+    fn a_script_runs_its_frenzy_on_time_and_hears_the_catches() {
+        let area = Rect::new(0, 0, 40, 6);
+        let ctx = PluginContext { screen: area, sky: area, ocean: area, daylight: 1.0, theme: Theme::default(), still: false };
+        let sky = |script: &Script| {
+            let mut buf = Buffer::empty(area);
+            script.render(&mut buf, &ctx);
+            (0..area.height).map(|y| (0..area.width).map(|x| buf[(x, y)].symbol().to_string()).collect::<String>()).collect::<Vec<_>>()
+        };
+
+        let mut script = Script::new("frenzy", FRENZY, 1).unwrap();
+        script.update(Duration::from_secs(599), &ctx);
+        assert_eq!(script.spawn_weight("Goby"), 1.0);
+        script.update(Duration::from_secs(1), &ctx);
+        assert_eq!(script.spawn_weight("goby"), 5.0);
+        assert!(sky(&script)[1].contains("Fish frenzy!"));
+
+        let fish = CaughtFish { species_name: "Shark".to_string(), size: 80.0, size_category: SizeCategory::Massive, shiny: false };
+        script.on_event(&GameEvent::FishCaught { fish, rarity: 0.5, autopilot: false });
+        assert!(sky(&script)[2].contains("What a Shark!"));
+
+        script.update(Duration::from_secs(60), &ctx);
+        assert_eq!(script.spawn_weight("Goby"), 1.0);
+        assert!(sky(&script).iter().all(|row| row.trim().is_empty()));
+    }
+
+    #[test]
+    fn scripts_are_kept_from_the_system_and_from_running_on() {
+        assert!(Script::new("io", "io.open('/etc/passwd')", 1).is_err());
+        assert!(Script::new("os", "os.execute('true')", 1).is_err());
+        assert!(Script::new("dofile", "dofile('/etc/passwd')", 1).is_err());
+        assert!(Script::new("on", "fisherman.on('nibble', function() end)", 1).err().unwrap().contains("unknown event"));
+        let started = Instant::now();
+        assert!(Script::new("loop", "while true do end", 1).err().unwrap().contains("too long"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // A handler that fails is logged and the script carries on
+        let mut script = Script::new("cast", "fisherman.on('cast', function() error('boom') end)", 1).unwrap();
+        script.on_event(&GameEvent::Cast);
+        script.on_event(&GameEvent::Cast);
+    }
+}
@@ -0,0 +1,210 @@
+//! The sea floor: rocks, seaweed swaying on the bottom and vents that now and then send
+//! up a column of bubbles. The layout is drawn from the seed and the size of the water,
+//! so the same terminal always gets the same seabed and a resize lays out a new one.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::Widget;
+use std::time::Duration;
+
+use crate::theme::Theme;
+
+const ROCKS: [&str; 4] = ["▄▆▄", "▂▄▂", "▃▅▆▃", "▄▄"];
+/// Columns of sea floor per seaweed strand, rock and vent, on average.
+const WEED_SPACING: u16 = 9;
+const ROCK_SPACING: u16 = 16;
+const VENT_SPACING: u16 = 30;
+const MAX_WEED_HEIGHT: u16 = 5;
+/// How often, in flips a second, a strand sways the other way.
+const SWAY_RATE: f32 = 0.7;
+
+#[derive(Debug, Clone)]
+struct Weed {
+    x: u16,
+    height: u16,
+    phase: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Seabed {
+    seed: u64,
+    area: Rect,
+    weeds: Vec<Weed>,
+    rocks: Vec<(u16, &'static str)>,
+    vents: Vec<u16>,
+    elapsed: Duration,
+    theme: Theme,
+}
+
+impl Seabed {
+    /// A seabed for the water in `area`.
+    pub fn new(seed: u64, area: Rect) -> Self {
+        let mut seabed = Seabed {
+            seed,
+            area: Rect::default(),
+            weeds: Vec::new(),
+            rocks: Vec::new(),
+            vents: Vec::new(),
+            elapsed: Duration::ZERO,
+            theme: Theme::default(),
+        };
+        seabed.resize(area);
+        seabed
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Lay the seabed out afresh for water of a new size.
+    pub fn resize(&mut self, area: Rect) {
+        self.area = area;
+        self.weeds.clear();
+        self.rocks.clear();
+        self.vents.clear();
+        if area.width == 0 || area.height < 2 {
+            return;
+        }
+        let mut rng = StdRng::seed_from_u64(self.seed ^ ((area.width as u64) << 32 | area.height as u64));
+        let max_height = MAX_WEED_HEIGHT.min(area.height / 2).max(1);
+        for _ in 0..(area.width / WEED_SPACING).max(1) {
+            self.weeds.push(Weed {
+                x: area.x + rng.gen_range(0..area.width),
+                height: rng.gen_range(1..=max_height),
+                phase: rng.gen_range(0.0..1.0),
+            });
+        }
+        for _ in 0..area.width / ROCK_SPACING {
+            let rock = ROCKS[rng.gen_range(0..ROCKS.len())];
+            self.rocks.push((area.x + rng.gen_range(0..area.width), rock));
+        }
+        for _ in 0..(area.width / VENT_SPACING).max(1) {
+            self.vents.push(area.x + rng.gen_range(0..area.width));
+        }
+    }
+
+    pub fn update(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed;
+    }
+
+    /// The row along the sea floor.
+    pub fn floor(&self) -> u16 {
+        self.area.bottom().saturating_sub(1)
+    }
+
+    /// Columns on the floor where bubble columns rise from.
+    pub fn vents(&self) -> &[u16] {
+        &self.vents
+    }
+
+    /// The same seabed moved up or down to lie along row `floor`, for drawing it
+    /// somewhere other than the bottom of the water.
+    pub fn at_floor(&self, floor: u16) -> Seabed {
+        let mut moved = self.clone();
+        moved.area.y = (floor + 1).saturating_sub(self.area.height);
+        moved.area.height = floor + 1 - moved.area.y;
+        moved
+    }
+
+    /// Darken `length` columns of the floor from column `x`, under a fish swimming over.
+    /// Bare floor gets a faint streak and anything on it is dimmed.
+    pub fn cast_shadow(&self, buf: &mut Buffer, x: i32, length: u16) {
+        let area = self.area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+        let floor = self.floor();
+        let style = Style::default().fg(self.theme.rock).add_modifier(Modifier::DIM);
+        let (left, right) = (x.max(area.left() as i32), (x + length as i32).min(area.right() as i32));
+        for x in left..right {
+            let cell = &mut buf[(x as u16, floor)];
+            if cell.symbol() == " " {
+                cell.set_char('▁').set_style(style);
+            } else {
+                cell.modifier.insert(Modifier::DIM);
+            }
+        }
+    }
+
+    /// The rows the seaweed sways in, which change on their own without anything else
+    /// in the scene moving.
+    pub fn swaying_area(&self) -> Rect {
+        let height = self.weeds.iter().map(|w| w.height).max().unwrap_or(0);
+        Rect::new(self.area.x, self.area.bottom().saturating_sub(height), self.area.width, height)
+    }
+}
+
+impl Widget for &Seabed {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(self.area);
+        if area.is_empty() {
+            return;
+        }
+        let floor = area.bottom() - 1;
+        let secs = self.elapsed.as_secs_f32();
+
+        let weed_style = Style::default().fg(self.theme.seaweed);
+        for weed in &self.weeds {
+            if weed.x >= area.right() {
+                continue;
+            }
+            // Each strand zigzags up from the floor, flipping which way it leans now and then
+            let flip = (secs * SWAY_RATE + weed.phase) as u16;
+            for i in 0..weed.height.min(area.height) {
+                let glyph = if (i + flip).is_multiple_of(2) { "(" } else { ")" };
+                buf.set_string(weed.x, floor - i, glyph, weed_style);
+            }
+        }
+
+        let rock_style = Style::default().fg(self.theme.rock);
+        for &(x, rock) in &self.rocks {
+            for (i, ch) in rock.chars().enumerate() {
+                let x = x + i as u16;
+                if x < area.right() {
+                    buf[(x, floor)].set_char(ch).set_style(rock_style);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seabed_follows_the_size_of_the_water() {
+        let area = Rect::new(0, 10, 80, 12);
+        let a = Seabed::new(5, area);
+        let b = Seabed::new(5, area);
+        assert_eq!(a.weeds.iter().map(|w| w.x).collect::<Vec<_>>(), b.weeds.iter().map(|w| w.x).collect::<Vec<_>>());
+        assert_eq!(a.floor(), 21);
+        assert!(a.weeds.iter().all(|w| (1..=MAX_WEED_HEIGHT).contains(&w.height)));
+
+        let mut resized = a.clone();
+        resized.resize(Rect::new(0, 10, 120, 12));
+        assert_eq!(resized.weeds.len(), 120 / WEED_SPACING as usize);
+        assert_ne!(resized.weeds.iter().map(|w| w.x).collect::<Vec<_>>(), a.weeds.iter().map(|w| w.x).collect::<Vec<_>>());
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 22));
+        (&a).render(buf.area, &mut buf);
+        assert!((0..80).any(|x| matches!(buf[(x, 21)].symbol(), "(" | ")" | "▄" | "▂" | "▃" | "▅" | "▆")));
+        assert!((0..80).all(|x| buf[(x, 9)].symbol() == " "));
+
+        // A shadow streaks the bare floor and dims what's on it
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 22));
+        (&a).render(buf.area, &mut buf);
+        let before = buf.clone();
+        a.cast_shadow(&mut buf, -3, 83);
+        for x in 0..80 {
+            let cell = &buf[(x, 21)];
+            assert!(cell.modifier.contains(Modifier::DIM));
+            assert_eq!(cell.symbol(), if before[(x, 21)].symbol() == " " { "▁" } else { before[(x, 21)].symbol() });
+        }
+        assert_eq!(buf[(0, 20)], before[(0, 20)]);
+    }
+}
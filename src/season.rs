@@ -0,0 +1,606 @@
+//! Seasons and holidays, from the calendar date (`--date`, today by default). Each
+//! season can tint the default theme, send something drifting through the scene (ice
+//! floes in winter, blossom petals in spring) and bring out species that only swim
+//! then; a holiday puts up a decoration, such as fireworks on New Year's Eve.
+//!
+//! None of it is built in: `src/seasons/seasons.csv` lists what each season changes,
+//! `src/seasons/holidays.csv` the holidays and the folder of sprite frames each one
+//! decorates the scene with, and a `seasons` folder in a sprite directory lays its own
+//! over them.
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use include_dir::{include_dir, Dir};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::Text;
+use ratatui::widgets::Widget;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config_file::Value;
+use crate::csv_frames::{self, AnimationFrames};
+use crate::dock::Shore;
+use crate::fish;
+use crate::theme::{self, Theme};
+
+static SEASONS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/seasons");
+const SEASONS_FILE: &str = "seasons.csv";
+const HOLIDAYS_FILE: &str = "holidays.csv";
+/// How long each decoration frame shows unless its file name says otherwise.
+const DECORATION_FRAME_DURATION: Duration = Duration::from_millis(200);
+/// Dark sky between one firework and the next.
+const BURST_GAP: Duration = Duration::from_millis(700);
+/// One column in this many has something drifting down it.
+const DRIFT_DENSITY: u32 = 9;
+/// Columns of open water for each floe.
+const FLOE_SPACING: u16 = 14;
+
+/// A day of the year, without the year: seasons and holidays come round every year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Today, by the system clock in UTC.
+    pub fn today() -> Date {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+        Date::from_days((secs / 86_400) as i64)
+    }
+
+    /// The date `days` after 1 January 1970.
+    fn from_days(days: i64) -> Date {
+        let (_, month, day) = civil_from_days(days);
+        Date { month, day }
+    }
+}
+
+/// Today's date with its year, by the system clock in UTC, as `YYYY-MM-DD`.
+pub fn today_stamp() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+/// The year, month and day `days` after 1 January 1970.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    // Howard Hinnant's civil-from-days, counting from a March 1st epoch so the
+    // leap day falls at the end of the year
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month as u8, day as u8)
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// `MM-DD`, e.g. `12-31`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date '{}' (expected MM-DD)", s);
+        let (month, day) = s.trim().split_once('-').ok_or_else(invalid)?;
+        let (month, day): (u8, u8) = (month.parse().map_err(|_| invalid())?, day.parse().map_err(|_| invalid())?);
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(invalid());
+        }
+        Ok(Date { month, day })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}-{:02}", self.month, self.day)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+
+    /// The season `date` falls in, north of the equator: spring from March, summer
+    /// from June, autumn from September and winter from December.
+    pub fn of(date: Date) -> Season {
+        match date.month {
+            3..=5 => Season::Spring,
+            6..=8 => Season::Summer,
+            9..=11 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Season::Spring => "spring",
+            Season::Summer => "summer",
+            Season::Autumn => "autumn",
+            Season::Winter => "winter",
+        }
+    }
+}
+
+impl FromStr for Season {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "spring" => Ok(Season::Spring),
+            "summer" => Ok(Season::Summer),
+            "autumn" | "fall" => Ok(Season::Autumn),
+            "winter" => Ok(Season::Winter),
+            other => Err(format!("unknown season '{}'", other)),
+        }
+    }
+}
+
+/// The seasons a species swims in, from the `Seasons` column of its `meta.csv`: all of
+/// them unless it names some.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seasons(u8);
+
+impl Seasons {
+    pub const ALL: Seasons = Seasons(0b1111);
+
+    pub fn contains(&self, season: Season) -> bool {
+        self.0 & (1 << season as u8) != 0
+    }
+}
+
+impl Default for Seasons {
+    fn default() -> Self {
+        Seasons::ALL
+    }
+}
+
+impl FromStr for Seasons {
+    type Err = String;
+
+    /// Season names separated by `|` or spaces, such as `autumn|winter`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut seasons = 0;
+        for name in s.split(['|', ' ']).filter(|name| !name.is_empty()) {
+            seasons |= 1 << name.parse::<Season>()? as u8;
+        }
+        if seasons == 0 {
+            return Ok(Seasons::ALL);
+        }
+        Ok(Seasons(seasons))
+    }
+}
+
+/// Where something seasonal goes in the scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Place {
+    /// Falling through the sky, or for a decoration, bursting in the night sky.
+    Sky,
+    /// Floating along the surface of the water.
+    Water,
+    /// Standing on the dock.
+    Dock,
+}
+
+impl FromStr for Place {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sky" => Ok(Place::Sky),
+            "water" => Ok(Place::Water),
+            "dock" => Ok(Place::Dock),
+            other => Err(format!("unknown place '{}' (expected sky, water or dock)", other)),
+        }
+    }
+}
+
+/// What drifts through the scene in a season: one of `glyphs` at a time, in `color`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub glyphs: Vec<char>,
+    pub color: Color,
+    pub place: Place,
+}
+
+/// One row of `seasons.csv`.
+#[derive(Debug, Clone, PartialEq)]
+struct SeasonLook {
+    season: Season,
+    drift: Option<Drift>,
+    /// Theme colours to replace, by field name.
+    palette: Vec<(String, Color)>,
+}
+
+/// A holiday from `holidays.csv`, running from `from` to `to` inclusive; a range that
+/// ends before it starts runs over the new year.
+#[derive(Debug, Clone)]
+pub struct Holiday {
+    pub name: String,
+    from: Date,
+    to: Date,
+    pub greeting: String,
+    pub decoration: Option<Decoration>,
+}
+
+impl Holiday {
+    pub fn covers(&self, date: Date) -> bool {
+        if self.from <= self.to {
+            (self.from..=self.to).contains(&date)
+        } else {
+            date >= self.from || date <= self.to
+        }
+    }
+}
+
+/// A holiday's sprite, animated like the crab, and where it goes.
+#[derive(Debug, Clone)]
+pub struct Decoration {
+    pub frames: AnimationFrames,
+    pub place: Place,
+}
+
+/// Every season's look and every holiday, as the manifests describe them.
+#[derive(Debug, Clone, Default)]
+pub struct Calendar {
+    looks: Vec<SeasonLook>,
+    holidays: Vec<Holiday>,
+}
+
+/// A manifest row, by column name.
+type Row = Vec<(String, String)>;
+
+fn read_rows(content: &str) -> Result<Vec<Row>, String> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers: Vec<String> = reader.headers().map_err(|e| e.to_string())?.iter().map(|h| h.trim().to_string()).collect();
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| e.to_string())?;
+            Ok(headers.iter().cloned().zip(record.iter().map(|field| field.trim().to_string())).collect())
+        })
+        .collect()
+}
+
+fn field<'a>(row: &'a Row, name: &str) -> &'a str {
+    row.iter().find(|(column, _)| column == name).map_or("", |(_, value)| value)
+}
+
+fn parse_look(row: &Row) -> Result<SeasonLook, String> {
+    let season = field(row, "Season").parse()?;
+    let glyphs: Vec<char> = field(row, "Drift").chars().filter(|c| !c.is_whitespace()).collect();
+    let drift = if glyphs.is_empty() {
+        None
+    } else {
+        let color = theme::parse_color(&Value::String(field(row, "DriftColor").to_string()))?;
+        Some(Drift { glyphs, color, place: field(row, "DriftIn").parse()? })
+    };
+    let mut palette = Vec::new();
+    for (column, value) in row.iter().filter(|(column, value)| !value.is_empty() && column.chars().all(|c| c.is_ascii_lowercase() || c == '_')) {
+        if Theme::default().color_mut(column).is_none() {
+            return Err(format!("unknown theme colour '{}'", column));
+        }
+        let color = theme::parse_color(&Value::String(value.clone())).map_err(|e| format!("{}: {}", column, e))?;
+        palette.push((column.clone(), color));
+    }
+    Ok(SeasonLook { season, drift, palette })
+}
+
+/// A holiday row, its decoration looked up with `frames`.
+fn parse_holiday(row: &Row, frames: impl Fn(&str) -> AnimationFrames) -> Result<Holiday, String> {
+    let from: Date = field(row, "From").parse()?;
+    let to = match field(row, "To") {
+        "" => from,
+        to => to.parse()?,
+    };
+    let decoration = match field(row, "Decoration") {
+        "" => None,
+        name => {
+            let place = match field(row, "Place") {
+                "" => Place::Dock,
+                place => place.parse()?,
+            };
+            let frames = frames(name);
+            if frames.is_empty() {
+                tracing::warn!("no frames for decoration '{}'", name);
+            }
+            (!frames.is_empty()).then_some(Decoration { frames, place })
+        }
+    };
+    Ok(Holiday { name: field(row, "Name").to_string(), from, to, greeting: field(row, "Greeting").to_string(), decoration })
+}
+
+fn embedded_decoration(name: &str) -> AnimationFrames {
+    let Some(dir) = SEASONS_DIR.get_dir(name) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = dir.files().filter(|f| f.path().extension().is_some_and(|ext| ext == "csv")).collect();
+    files.sort_by(|a, b| a.path().cmp(b.path()));
+    files
+        .into_iter()
+        .filter_map(|f| {
+            let file_name = f.path().file_name()?.to_str()?;
+            let frame = csv_frames::load_csv_frame_from_string(f.contents_utf8()?).ok()?;
+            Some((frame, csv_frames::duration_from_file_name(file_name).unwrap_or(DECORATION_FRAME_DURATION)))
+        })
+        .collect()
+}
+
+impl Calendar {
+    /// The seasons and holidays built into the game.
+    pub fn embedded() -> Calendar {
+        let mut calendar = Calendar::default();
+        let file = |name: &str| SEASONS_DIR.get_file(name).and_then(|f| f.contents_utf8()).unwrap_or("");
+        if let Err(e) = calendar.add(file(SEASONS_FILE), file(HOLIDAYS_FILE), embedded_decoration) {
+            tracing::warn!("built-in seasons: {}", e);
+        }
+        calendar
+    }
+
+    /// The built-in calendar with each of `dirs` laid over it in order. Each may hold a
+    /// `seasons.csv`, whose rows replace the look of the seasons they name, and a
+    /// `holidays.csv`, whose rows replace the holidays of the same name or add new
+    /// ones, with decoration folders beside it.
+    pub fn load_layered(dirs: &[PathBuf]) -> Calendar {
+        let mut calendar = Calendar::embedded();
+        for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+            let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap_or_default();
+            let frames = |name: &str| {
+                let frames = csv_frames::load_animation_from_dir(&dir.join(name), DECORATION_FRAME_DURATION).unwrap_or_default();
+                if frames.is_empty() { embedded_decoration(name) } else { frames }
+            };
+            if let Err(e) = calendar.add(&read(SEASONS_FILE), &read(HOLIDAYS_FILE), frames) {
+                tracing::warn!("seasons in {}: {}", dir.display(), e);
+            }
+        }
+        calendar
+    }
+
+    fn add(&mut self, seasons: &str, holidays: &str, frames: impl Fn(&str) -> AnimationFrames) -> Result<(), String> {
+        if !seasons.is_empty() {
+            for row in read_rows(seasons)? {
+                let look = parse_look(&row)?;
+                self.looks.retain(|l| l.season != look.season);
+                self.looks.push(look);
+            }
+        }
+        if !holidays.is_empty() {
+            for row in read_rows(holidays)? {
+                let holiday = parse_holiday(&row, &frames).map_err(|e| format!("{}: {}", field(&row, "Name"), e))?;
+                self.holidays.retain(|h| h.name != holiday.name);
+                self.holidays.push(holiday);
+            }
+        }
+        Ok(())
+    }
+
+    /// How the scene looks on `date` (with no holiday when there's none), in `season`,
+    /// or the season of the date if it's `None`.
+    pub fn scene(&self, date: Option<Date>, season: Option<Season>) -> SeasonalScene {
+        let Some(season) = season.or(date.map(Season::of)) else {
+            return SeasonalScene::default();
+        };
+        let look = self.looks.iter().find(|l| l.season == season);
+        SeasonalScene {
+            season: Some(season),
+            palette: look.map(|l| l.palette.clone()).unwrap_or_default(),
+            drift: look.and_then(|l| l.drift.clone()),
+            holiday: date.and_then(|date| self.holidays.iter().find(|h| h.covers(date))).cloned(),
+        }
+    }
+}
+
+/// What the season and holiday bring to this session's scene. The default is out of
+/// season altogether.
+#[derive(Debug, Clone, Default)]
+pub struct SeasonalScene {
+    pub season: Option<Season>,
+    palette: Vec<(String, Color)>,
+    pub drift: Option<Drift>,
+    pub holiday: Option<Holiday>,
+}
+
+impl SeasonalScene {
+    /// `base` in the season's colours.
+    pub fn theme(&self, base: Theme) -> Theme {
+        let mut theme = base;
+        for (name, color) in &self.palette {
+            if let Some(slot) = theme.color_mut(name) {
+                *slot = *color;
+            }
+        }
+        theme
+    }
+
+    /// Whether a species that swims in `seasons` is about now.
+    pub fn in_season(&self, seasons: Seasons) -> bool {
+        self.season.is_none_or(|season| seasons.contains(season))
+    }
+}
+
+/// Cheap deterministic hash, so drifting things keep their paths between frames.
+fn hash(a: u32, b: u32) -> u32 {
+    let mut h = a.wrapping_mul(0x9E37_79B1) ^ b.wrapping_mul(0x85EB_CA77);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0xC2B2_AE3D);
+    h ^ (h >> 13)
+}
+
+/// The season's drift: falling slowly through `sky`, swaying as it goes, or floating
+/// along the rows of `water`.
+pub struct DriftOverlay<'a> {
+    pub drift: &'a Drift,
+    pub sky: Rect,
+    pub water: Rect,
+    pub elapsed: Duration,
+    /// Hold everything in place, for reduced motion.
+    pub still: bool,
+}
+
+impl Widget for DriftOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let secs = if self.still { 0.0 } else { self.elapsed.as_secs_f32() };
+        let style = Style::default().fg(self.drift.color);
+        let glyph = |h: u32| self.drift.glyphs[h as usize % self.drift.glyphs.len()];
+        match self.drift.place {
+            Place::Water => {
+                let water = self.water.intersection(area);
+                if water.is_empty() {
+                    return;
+                }
+                let span = water.width as u32 + 3;
+                for i in 0..(water.width / FLOE_SPACING).max(1) as u32 {
+                    let h = hash(i, 23);
+                    let y = water.y + (h % water.height as u32) as u16;
+                    // Floes on the lower rows are nearer and drift by faster
+                    let speed = 0.3 + 0.2 * (y - water.y) as f32;
+                    let start = (h / 7).wrapping_add((secs * speed) as u32) % span;
+                    let floe: String = std::iter::repeat_n(glyph(h / 3), 1 + (h / 11 % 3) as usize).collect();
+                    for (j, ch) in floe.chars().enumerate() {
+                        let x = (start + j as u32) as i32 - 3;
+                        if x >= 0 && (x as u16) < water.width {
+                            buf[(water.x + x as u16, y)].set_char(ch).set_style(style);
+                        }
+                    }
+                }
+            }
+            Place::Sky | Place::Dock => {
+                let sky = self.sky.intersection(area);
+                if sky.is_empty() {
+                    return;
+                }
+                for col in 0..sky.width as u32 {
+                    let h = hash(col, 11);
+                    if !h.is_multiple_of(DRIFT_DENSITY) {
+                        continue;
+                    }
+                    let fall = (h / DRIFT_DENSITY).wrapping_add((secs * 1.5) as u32) % sky.height as u32;
+                    let sway = ((secs * 0.8 + (h % 7) as f32).sin() * 1.5).round() as i32;
+                    let x = col as i32 + sway;
+                    if x >= 0 && (x as u16) < sky.width {
+                        buf.set_string(sky.x + x as u16, sky.y + fall as u16, glyph(h).to_string(), style);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draw `frame` with its top left at (`x`, `y`), leaving the cells under its spaces.
+fn draw_frame(frame: &Text, x: u16, y: u16, buf: &mut Buffer) {
+    for (row, line) in frame.lines.iter().enumerate() {
+        let mut column = x;
+        for (symbol, style) in csv_frames::line_cells(line) {
+            let position = (column, y + row as u16);
+            if symbol != " " && buf.area.contains(position.into()) {
+                buf[position].set_symbol(&symbol).set_style(style);
+            }
+            column += symbol.width() as u16;
+        }
+    }
+}
+
+/// A holiday decoration: on the shore end of `dock`, which runs out from the land on
+/// `side`, or at night bursting over and over at spots across `sky`.
+pub struct DecorationView<'a> {
+    pub decoration: &'a Decoration,
+    pub sky: Rect,
+    pub dock: Rect,
+    pub side: Shore,
+    pub night: bool,
+    pub elapsed: Duration,
+    pub still: bool,
+}
+
+impl Widget for DecorationView<'_> {
+    fn render(self, _area: Rect, buf: &mut Buffer) {
+        let frames = &self.decoration.frames;
+        let (width, height) = frames.iter().fold((0, 0), |(w, h), (frame, _)| (w.max(frame.width() as u16), h.max(frame.height() as u16)));
+        match self.decoration.place {
+            Place::Sky | Place::Water => {
+                if !self.night || self.still || self.sky.width <= width || self.sky.height <= height {
+                    return;
+                }
+                let show: Duration = frames.iter().map(|(_, d)| *d).sum();
+                let cycle = (show + BURST_GAP).as_millis().max(1);
+                let burst = (self.elapsed.as_millis() / cycle) as u32;
+                let t = Duration::from_millis((self.elapsed.as_millis() % cycle) as u64);
+                if t >= show {
+                    return;
+                }
+                let h = hash(burst, 31);
+                let x = self.sky.x + (h % (self.sky.width - width) as u32) as u16;
+                let y = self.sky.y + (h / 13 % (self.sky.height - height) as u32) as u16;
+                draw_frame(fish::frame_at(frames, t), x, y, buf);
+            }
+            Place::Dock => {
+                let elapsed = if self.still { Duration::ZERO } else { self.elapsed };
+                let x = match self.side {
+                    Shore::Right => self.dock.right().saturating_sub(width + 1),
+                    Shore::Left => self.dock.x + 1,
+                };
+                draw_frame(fish::frame_at(frames, elapsed), x, self.dock.y.saturating_sub(height), buf);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_date_picks_the_season_and_any_holiday() {
+        assert_eq!(Date::from_days(0), Date { month: 1, day: 1 });
+        // 29 February 2024
+        assert_eq!(Date::from_days(19_782), Date { month: 2, day: 29 });
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert!("13-01".parse::<Date>().is_err());
+        assert!(!"autumn|winter".parse::<Seasons>().unwrap().contains(Season::Spring));
+
+        let calendar = Calendar::embedded();
+        let scene = calendar.scene(Some("01-01".parse().unwrap()), None);
+        assert_eq!(scene.season, Some(Season::Winter));
+        assert_eq!(scene.drift.as_ref().map(|d| d.place), Some(Place::Water));
+        assert_ne!(scene.theme(Theme::CLASSIC).water, Theme::CLASSIC.water);
+        let holiday = scene.holiday.unwrap();
+        assert_eq!(holiday.name, "New Year");
+        assert!(holiday.covers("12-31".parse().unwrap()) && !holiday.covers("12-30".parse().unwrap()));
+        assert!(holiday.decoration.is_some_and(|d| d.place == Place::Sky && d.frames.len() > 1));
+
+        let scene = calendar.scene(Some("04-10".parse().unwrap()), None);
+        assert!(scene.holiday.is_none());
+        assert!(scene.drift.as_ref().is_some_and(|d| d.glyphs.contains(&'✿')));
+        assert!(!scene.in_season("winter".parse().unwrap()));
+        assert!(SeasonalScene::default().in_season("winter".parse().unwrap()));
+
+        // A sprite directory's manifests replace the built-in rows they name
+        let dir = std::env::temp_dir().join(format!("fisherman-seasons-{}", std::process::id()));
+        fs::create_dir_all(dir.join("kite")).unwrap();
+        fs::write(dir.join("seasons.csv"), "Season,Drift,DriftColor,DriftIn\nspring,~,#ffffff,water\n").unwrap();
+        fs::write(dir.join("holidays.csv"), "Name,From,Decoration,Place,Greeting\nKite Day,04-10,kite,sky,Up!\n").unwrap();
+        fs::write(dir.join("kite/kite.csv"), "X,Y,ASCII,Foreground\n0,0,◇,#ff0000\n").unwrap();
+        let calendar = Calendar::load_layered(std::slice::from_ref(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+        let scene = calendar.scene(Some("04-10".parse().unwrap()), None);
+        assert_eq!(scene.drift.map(|d| d.glyphs), Some(vec!['~']));
+        assert_eq!(scene.holiday.map(|h| h.greeting), Some("Up!".to_string()));
+        assert!(calendar.scene(Some("10-31".parse().unwrap()), None).holiday.is_some());
+    }
+}
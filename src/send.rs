@@ -0,0 +1,446 @@
+//! The `fisherman send` subcommand: deliver a message to a running instance without
+//! hand-written `echo "SUCCESS:..." > pipe` snippets.
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::signals::SignalMessage;
+
+const USAGE: &str = "\
+usage: fisherman send [--to ENDPOINT] [--task NAME] success|failure|banner|info [MESSAGE...]
+       fisherman send [--to ENDPOINT] [--task NAME] progress PERCENT [LABEL...]
+       fisherman send [--to ENDPOINT] [--task NAME] spawn SPECIES
+       fisherman send [--to ENDPOINT] [--task NAME] heartbeat
+       fisherman send [--to ENDPOINT] [--task NAME] status CODE [MESSAGE...]
+       fisherman send [--to ENDPOINT] [--task NAME] run -- COMMAND [ARGS...]
+
+ENDPOINT is socket:PATH, pipe:PATH, file:PATH or http://HOST:PORT. Without --to the
+running instance is found through its instance file, the newest if there are several.
+--task reports on one of the instance's named tasks rather than the whole wait.
+`status` and `run` report SUCCESS for exit code 0 and FAILURE otherwise, then exit
+with that same code.";
+
+const EXIT_USAGE: i32 = 2;
+const EXIT_UNDELIVERED: i32 = 1;
+
+/// The folder instance files are written to.
+fn instance_dir() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir)
+}
+
+/// How this user's instance files are named, before the pid.
+fn instance_prefix() -> String {
+    let user = env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_default();
+    format!("fisherman-{}-", user)
+}
+
+/// Where the instance with process id `pid` advertises its signal endpoints. Each
+/// instance has a file of its own, so one starting up never hides another.
+pub fn instance_file_path(pid: u32) -> PathBuf {
+    instance_dir().join(format!("{}{}.instance", instance_prefix(), pid))
+}
+
+/// The newest of the instances in `dir` whose files start with `prefix` that is still
+/// running and can be sent signals.
+fn find_instance(dir: &Path, prefix: &str) -> Option<InstanceInfo> {
+    let mut running: Vec<_> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(prefix) && name.ends_with(".instance")
+        })
+        .filter_map(|entry| {
+            let info = InstanceInfo::read(&entry.path()).ok()?;
+            let written = entry.metadata().and_then(|m| m.modified()).ok()?;
+            (info.is_alive() && info.has_endpoint()).then_some((written, info))
+        })
+        .collect();
+    running.sort_by_key(|(written, _)| *written);
+    running.pop().map(|(_, info)| info)
+}
+
+/// The signal endpoints of a running instance, as recorded in its instance file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceInfo {
+    pub pid: u32,
+    pub socket: Option<PathBuf>,
+    pub listen: Option<String>,
+    pub pipe: Option<PathBuf>,
+    pub signal_file: Option<PathBuf>,
+}
+
+impl InstanceInfo {
+    pub fn has_endpoint(&self) -> bool {
+        self.socket.is_some() || self.listen.is_some() || self.pipe.is_some() || self.signal_file.is_some()
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut out = format!("pid={}\n", self.pid);
+        if let Some(ref p) = self.socket {
+            out.push_str(&format!("socket={}\n", p.display()));
+        }
+        if let Some(ref a) = self.listen {
+            out.push_str(&format!("listen={}\n", a));
+        }
+        if let Some(ref p) = self.pipe {
+            out.push_str(&format!("pipe={}\n", p.display()));
+        }
+        if let Some(ref p) = self.signal_file {
+            out.push_str(&format!("signal-file={}\n", p.display()));
+        }
+        fs::write(path, out)
+    }
+
+    pub fn read(path: &Path) -> io::Result<InstanceInfo> {
+        let mut info = InstanceInfo::default();
+        for line in BufReader::new(fs::File::open(path)?).lines() {
+            let line = line?;
+            let Some((key, value)) = line.split_once('=') else { continue };
+            match key {
+                "pid" => info.pid = value.parse().unwrap_or(0),
+                "socket" => info.socket = Some(PathBuf::from(value)),
+                "listen" => info.listen = Some(value.to_string()),
+                "pipe" => info.pipe = Some(PathBuf::from(value)),
+                "signal-file" => info.signal_file = Some(PathBuf::from(value)),
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    /// Whether the recorded process still exists (always assumed on non-Unix).
+    pub fn is_alive(&self) -> bool {
+        #[cfg(unix)]
+        {
+            // SAFETY: signal 0 performs only the existence and permission check.
+            self.pid != 0 && unsafe { libc::kill(self.pid as libc::pid_t, 0) } == 0
+        }
+        #[cfg(not(unix))]
+        {
+            self.pid != 0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Endpoint {
+    Socket(PathBuf),
+    Http(String),
+    Pipe(PathBuf),
+    File(PathBuf),
+}
+
+impl Endpoint {
+    fn parse(s: &str) -> Option<Endpoint> {
+        if let Some(p) = s.strip_prefix("socket:") {
+            Some(Endpoint::Socket(PathBuf::from(p)))
+        } else if let Some(a) = s.strip_prefix("http://") {
+            Some(Endpoint::Http(a.trim_end_matches('/').to_string()))
+        } else if let Some(p) = s.strip_prefix("pipe:") {
+            Some(Endpoint::Pipe(PathBuf::from(p)))
+        } else {
+            s.strip_prefix("file:").map(|p| Endpoint::File(PathBuf::from(p)))
+        }
+    }
+
+    /// Pick the most reliable endpoint the newest running instance offers.
+    fn discover() -> io::Result<Endpoint> {
+        let dir = instance_dir();
+        find_instance(&dir, &instance_prefix()).and_then(Endpoint::best).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no running fisherman found in {}; pass --to", dir.display()),
+            )
+        })
+    }
+
+    /// Socket, then HTTP, then pipe, then signal file.
+    fn best(info: InstanceInfo) -> Option<Endpoint> {
+        info.socket.map(Endpoint::Socket)
+            .or(info.listen.map(Endpoint::Http))
+            .or(info.pipe.map(Endpoint::Pipe))
+            .or(info.signal_file.map(Endpoint::File))
+    }
+
+    fn deliver(&self, message: &SignalMessage) -> io::Result<()> {
+        let line = format!("{}\n", message.to_line());
+        match self {
+            #[cfg(unix)]
+            Endpoint::Socket(path) => {
+                let mut stream = std::os::unix::net::UnixStream::connect(path)?;
+                stream.write_all(line.as_bytes())
+            }
+            #[cfg(not(unix))]
+            Endpoint::Socket(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "sockets require a Unix platform")),
+            Endpoint::Http(addr) => post_signal(addr, message),
+            Endpoint::Pipe(path) => {
+                let path = pipe_client_path(path);
+                OpenOptions::new().write(true).open(path)?.write_all(line.as_bytes())
+            }
+            Endpoint::File(path) => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(line.as_bytes()),
+        }
+    }
+}
+
+#[cfg(windows)]
+fn pipe_client_path(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    if raw.starts_with(r"\\.\pipe\") {
+        path.to_path_buf()
+    } else {
+        PathBuf::from(format!(r"\\.\pipe\{}", raw))
+    }
+}
+
+#[cfg(not(windows))]
+fn pipe_client_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+fn url_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            b' ' => out.push('+'),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// `message` as the form fields the HTTP listener reads.
+fn signal_params(message: &SignalMessage) -> String {
+    match message {
+        SignalMessage::Success(msg) => format!("status=success&msg={}", url_encode(msg)),
+        SignalMessage::Failure(msg) => format!("status=failure&msg={}", url_encode(msg)),
+        SignalMessage::Progress { percent, label } => {
+            format!("status=progress&percent={}&msg={}", percent, url_encode(label))
+        }
+        SignalMessage::Banner(msg) => format!("status=banner&msg={}", url_encode(msg)),
+        SignalMessage::Info(msg) => format!("status=info&msg={}", url_encode(msg)),
+        SignalMessage::Spawn(species) => format!("status=spawn&species={}", url_encode(species)),
+        SignalMessage::Heartbeat => "status=heartbeat".to_string(),
+        SignalMessage::Task { name, message } => format!("task={}&{}", url_encode(name), signal_params(message)),
+    }
+}
+
+fn post_signal(addr: &str, message: &SignalMessage) -> io::Result<()> {
+    let body = signal_params(message);
+    let mut stream = TcpStream::connect(addr)?;
+    write!(
+        stream,
+        "POST /signal HTTP/1.1\r\nHost: {}\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        addr,
+        body.len(),
+        body
+    )?;
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    if status_line.split_whitespace().nth(1) == Some("200") {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("server replied: {}", status_line.trim())))
+    }
+}
+
+fn outcome_message(code: i32, text: String) -> SignalMessage {
+    if code == 0 {
+        SignalMessage::Success(text)
+    } else {
+        SignalMessage::Failure(text)
+    }
+}
+
+/// The `--to` endpoint and `--task` name given before the message, in either order,
+/// and the arguments after them; `None` if either is given without a usable value.
+fn split_options(mut args: &[String]) -> Option<(Option<Endpoint>, Option<String>, &[String])> {
+    let (mut endpoint, mut task) = (None, None);
+    loop {
+        match args.first().map(String::as_str) {
+            Some("--to") if endpoint.is_none() => endpoint = Some(Endpoint::parse(args.get(1)?)?),
+            Some("--task") if task.is_none() => task = Some(args.get(1).filter(|name| !name.is_empty())?.clone()),
+            _ => return Some((endpoint, task, args)),
+        }
+        args = &args[2..];
+    }
+}
+
+/// Run `fisherman send` with the arguments after `send`, returning the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let Some((endpoint, task, args)) = split_options(args) else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+
+    let Some((kind, rest)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+    let text = rest.join(" ");
+
+    // `status` and `run` forward the reported exit code; everything else reports delivery.
+    let (message, forward_code) = match kind.as_str() {
+        "success" => (SignalMessage::Success(text), None),
+        "failure" => (SignalMessage::Failure(text), None),
+        "banner" => (SignalMessage::Banner(text), None),
+        "info" => (SignalMessage::Info(text), None),
+        "spawn" if !text.is_empty() => (SignalMessage::Spawn(text), None),
+        "heartbeat" => (SignalMessage::Heartbeat, None),
+        "progress" => {
+            let Some(percent) = rest.first().and_then(|p| p.parse::<f32>().ok()) else {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            };
+            let label = rest[1..].join(" ");
+            (SignalMessage::Progress { percent: percent.clamp(0.0, 100.0) as u8, label }, None)
+        }
+        "status" => {
+            let Some(code) = rest.first().and_then(|c| c.parse::<i32>().ok()) else {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            };
+            let msg = rest[1..].join(" ");
+            let msg = if msg.is_empty() { format!("exit status {}", code) } else { msg };
+            (outcome_message(code, msg), Some(code))
+        }
+        "run" => {
+            let command = match rest.first().map(String::as_str) {
+                Some("--") => &rest[1..],
+                _ => rest,
+            };
+            let Some((program, program_args)) = command.split_first() else {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            };
+            let code = match Command::new(program).args(program_args).status() {
+                Ok(status) => status.code().unwrap_or(1),
+                Err(e) => {
+                    eprintln!("fisherman send: could not run {}: {}", program, e);
+                    127
+                }
+            };
+            let msg = format!("{} exited with {}", command.join(" "), code);
+            (outcome_message(code, msg), Some(code))
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    let message = match task {
+        Some(name) => SignalMessage::Task { name, message: Box::new(message) },
+        None => message,
+    };
+    let delivered = endpoint
+        .map(Ok)
+        .unwrap_or_else(Endpoint::discover)
+        .and_then(|e| e.deliver(&message));
+    if let Err(e) = delivered {
+        eprintln!("fisherman send: {}", e);
+        return forward_code.unwrap_or(EXIT_UNDELIVERED);
+    }
+    forward_code.unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signals::{SharedSignal, SignalFileSource, Signals, UnknownLines};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant, SystemTime};
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("fisherman-send-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn the_endpoint_and_task_come_in_either_order() {
+        let words = |line: &str| line.split(' ').map(String::from).collect::<Vec<_>>();
+        let socket = Some(Endpoint::Socket(PathBuf::from("/x")));
+        let build = Some("build".to_string());
+        for line in ["--to socket:/x --task build success", "--task build --to socket:/x success"] {
+            let args = words(line);
+            let (endpoint, task, rest) = split_options(&args).unwrap();
+            assert_eq!((&endpoint, &task, rest), (&socket, &build, &args[4..]), "{}", line);
+        }
+        let args = words("--task build success");
+        assert_eq!(split_options(&args), Some((None, build, &args[2..])));
+        assert!(split_options(&words("--task")).is_none() && split_options(&words("--to nowhere success")).is_none());
+    }
+
+    #[test]
+    fn the_newest_running_instance_is_found() {
+        assert_eq!(Endpoint::parse("file:/tmp/signals"), Some(Endpoint::File(PathBuf::from("/tmp/signals"))));
+        assert_eq!(Endpoint::parse("http://buildbox:7070/"), Some(Endpoint::Http("buildbox:7070".to_string())));
+        assert_eq!(Endpoint::parse("buildbox:7070"), None);
+
+        let dir = scratch("instances");
+        let pid = std::process::id();
+        let write = |name: &str, info: InstanceInfo, age: u64| {
+            let path = dir.join(name);
+            info.write(&path).unwrap();
+            let written = SystemTime::now() - Duration::from_secs(age);
+            fs::File::options().write(true).open(&path).unwrap().set_modified(written).unwrap();
+        };
+        let pipe = |name: &str| Some(PathBuf::from(name));
+        assert_eq!(find_instance(&dir, "fisherman-me-"), None);
+        write("fisherman-me-1.instance", InstanceInfo { pid, pipe: pipe("older"), ..InstanceInfo::default() }, 60);
+        write("fisherman-me-2.instance", InstanceInfo { pid, pipe: pipe("newer"), ..InstanceInfo::default() }, 30);
+        // Gone, deaf, or someone else's: all passed over
+        write("fisherman-me-3.instance", InstanceInfo { pid: 0, pipe: pipe("gone"), ..InstanceInfo::default() }, 0);
+        write("fisherman-me-4.instance", InstanceInfo { pid, ..InstanceInfo::default() }, 0);
+        write("fisherman-you-5.instance", InstanceInfo { pid, pipe: pipe("yours"), ..InstanceInfo::default() }, 0);
+
+        let found = find_instance(&dir, "fisherman-me-").unwrap();
+        assert_eq!(Endpoint::best(found), Some(Endpoint::Pipe(PathBuf::from("newer"))));
+        // Every instance keeps a file of its own
+        assert_ne!(instance_file_path(1), instance_file_path(2));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn lines_appended_to_a_signal_file_all_arrive() {
+        let dir = scratch("file");
+        let path = dir.join("signals");
+        let inbox: SharedSignal = Arc::default();
+        let mut signals = Signals::new(Arc::clone(&inbox), UnknownLines::Ignore);
+        signals.start(SignalFileSource { path: path.clone() });
+
+        // Sent as fast as they go for several polls, so some land while one is read
+        let endpoint = Endpoint::File(path);
+        let (started, mut sent) = (Instant::now(), Vec::new());
+        while started.elapsed() < Duration::from_millis(500) {
+            let message = SignalMessage::Info(sent.len().to_string());
+            endpoint.deliver(&message).unwrap();
+            sent.push(message);
+        }
+        let started = Instant::now();
+        while inbox.lock().unwrap().len() < sent.len() && started.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(20));
+        }
+        signals.shutdown();
+        let received: Vec<SignalMessage> = inbox.lock().unwrap().drain(..).collect();
+        assert_eq!(received.len(), sent.len());
+        assert!(received == sent);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
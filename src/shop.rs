@@ -0,0 +1,120 @@
+//! The tackle shop screen: a keyboard-navigable list of rods, lines and bait.
+
+use crossterm::event::KeyCode;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use crate::economy::{ItemState, Profile, ShopItem};
+
+const SHOP_WIDTH: u16 = 80;
+
+/// Cursor and last status line of an open shop.
+#[derive(Debug, Clone)]
+pub struct Shop {
+    items: Vec<ShopItem>,
+    selected: usize,
+    status: Option<String>,
+}
+
+/// What a key press in the shop asks the caller to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShopAction {
+    None,
+    /// A purchase went through; the profile changed and should be saved.
+    Bought,
+    Close,
+}
+
+impl Default for Shop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shop {
+    pub fn new() -> Self {
+        Shop { items: ShopItem::catalog(), selected: 0, status: None }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode, profile: &mut Profile) -> ShopAction {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.checked_sub(1).unwrap_or(self.items.len().saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1) % self.items.len().max(1);
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let Some(&item) = self.items.get(self.selected) else { return ShopAction::None };
+                match profile.buy(item) {
+                    Ok(()) => {
+                        self.status = Some(format!("Bought {}", item.name()));
+                        return ShopAction::Bought;
+                    }
+                    Err(reason) => self.status = Some(reason.to_string()),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('t') | KeyCode::Char('q') => return ShopAction::Close,
+            _ => {}
+        }
+        ShopAction::None
+    }
+}
+
+/// Draws an open [`Shop`] centered in the area.
+pub struct ShopView<'a> {
+    pub shop: &'a Shop,
+    pub profile: &'a Profile,
+}
+
+impl Widget for ShopView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(format!(
+                "Coins: {}   Rod: {}   Line: {}",
+                self.profile.coins,
+                self.profile.rod().name,
+                self.profile.line().name
+            )),
+            Line::from(""),
+        ];
+        for (i, item) in self.shop.items.iter().enumerate() {
+            let (tag, color) = match item.state(self.profile) {
+                ItemState::Owned => ("owned".to_string(), Color::DarkGray),
+                ItemState::TooExpensive => (format!("{} c", item.price()), Color::Red),
+                ItemState::ForSale => (format!("{} c", item.price()), Color::White),
+            };
+            let mut style = Style::default().fg(color);
+            if i == self.shop.selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let name = match item {
+                ShopItem::Bait(bait) => format!("{} (have {})", item.name(), self.profile.bait_count(*bait)),
+                ShopItem::RodHolder => format!("{} (have {})", item.name(), self.profile.rod_holders),
+                _ => item.name(),
+            };
+            lines.push(Line::from(Span::styled(format!(" {:<24} {:>7}  {}", name, tag, item.description()), style)));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            self.shop.status.clone().unwrap_or_else(|| "↑/↓ choose · Enter buy · Esc close".to_string()),
+            Style::default().fg(Color::Yellow),
+        )));
+
+        let width = SHOP_WIDTH.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let rect = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        Clear.render(rect, buf);
+        Paragraph::new(lines)
+            .block(Block::default().title("Tackle Shop").borders(Borders::ALL))
+            .render(rect, buf);
+    }
+}
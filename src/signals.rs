@@ -0,0 +1,830 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::metrics::Metrics;
+
+/// A message delivered over one of the signal channels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalMessage {
+    /// `SUCCESS:message`: the monitored task finished.
+    Success(String),
+    /// `FAILURE:message`: the monitored task failed.
+    Failure(String),
+    /// `PROGRESS:percent:label`: the task is partway done.
+    Progress { percent: u8, label: String },
+    /// `BANNER:message`: show a neutral message without ending the wait.
+    Banner(String),
+    /// `INFO:message`: pop a note up in the corner for a few seconds, less in the way
+    /// than a banner.
+    Info(String),
+    /// `SPAWN:species`: add a fish of the named species to the scene.
+    Spawn(String),
+    /// `HEARTBEAT`: the task is still alive, which keeps the `--timeout` watchdog
+    /// from calling it stuck.
+    Heartbeat,
+    /// `TASK:name:message`: any of the above about one of several named tasks
+    /// (`--task`), each with its own fisherman on the dock.
+    Task { name: String, message: Box<SignalMessage> },
+}
+
+impl SignalMessage {
+    /// The outcome carried by SUCCESS/FAILURE messages, `None` for informational ones
+    /// and for a single task's, which only ends its own wait.
+    pub fn outcome(&self) -> Option<(bool, &str)> {
+        match self {
+            SignalMessage::Success(msg) => Some((true, msg)),
+            SignalMessage::Failure(msg) => Some((false, msg)),
+            _ => None,
+        }
+    }
+
+    /// Render the message back into its line protocol form.
+    pub fn to_line(&self) -> String {
+        // The protocol is line based, so embedded newlines would split the message.
+        let flat = |s: &str| s.replace(['\r', '\n'], " ");
+        match self {
+            SignalMessage::Success(msg) => format!("SUCCESS:{}", flat(msg)),
+            SignalMessage::Failure(msg) => format!("FAILURE:{}", flat(msg)),
+            SignalMessage::Progress { percent, label } => format!("PROGRESS:{}:{}", percent, flat(label)),
+            SignalMessage::Banner(msg) => format!("BANNER:{}", flat(msg)),
+            SignalMessage::Info(msg) => format!("INFO:{}", flat(msg)),
+            SignalMessage::Spawn(species) => format!("SPAWN:{}", flat(species)),
+            SignalMessage::Heartbeat => "HEARTBEAT".to_string(),
+            SignalMessage::Task { name, message } => format!("TASK:{}:{}", flat(name), message.to_line()),
+        }
+    }
+
+    /// The status the message carries, as in the protocol but lower case; a task's is
+    /// that of the message about it.
+    pub fn status(&self) -> &'static str {
+        match self {
+            SignalMessage::Success(_) => "success",
+            SignalMessage::Failure(_) => "failure",
+            SignalMessage::Progress { .. } => "progress",
+            SignalMessage::Banner(_) => "banner",
+            SignalMessage::Info(_) => "info",
+            SignalMessage::Spawn(_) => "spawn",
+            SignalMessage::Heartbeat => "heartbeat",
+            SignalMessage::Task { message, .. } => message.status(),
+        }
+    }
+}
+
+/// Messages received by the reader threads, waiting for the main loop to pick them up.
+pub type SharedSignal = Arc<Mutex<VecDeque<SignalMessage>>>;
+
+/// What the main loop does once a SUCCESS/FAILURE signal arrives (`--on-signal`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignalPolicy {
+    /// Quit right after the signal has been drawn once.
+    Exit,
+    /// Show the banner for the given time, then quit.
+    ExitAfter(Duration),
+    /// Keep showing the banner until the user quits.
+    Hold,
+    /// Show the banner briefly, then clear it and carry on fishing.
+    KeepPlaying,
+}
+
+impl Default for SignalPolicy {
+    fn default() -> Self {
+        SignalPolicy::ExitAfter(Duration::from_secs(3))
+    }
+}
+
+impl std::str::FromStr for SignalPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exit" => Ok(SignalPolicy::Exit),
+            "hold" => Ok(SignalPolicy::Hold),
+            "keep-playing" => Ok(SignalPolicy::KeepPlaying),
+            _ => {
+                let secs = s
+                    .strip_prefix("exit-after=")
+                    .ok_or_else(|| format!("unknown --on-signal policy '{}'", s))?;
+                let secs: f32 = secs
+                    .parse()
+                    .map_err(|_| format!("invalid seconds in '{}'", s))?;
+                Duration::try_from_secs_f32(secs)
+                    .map(SignalPolicy::ExitAfter)
+                    .map_err(|_| format!("invalid seconds in '{}'", s))
+            }
+        }
+    }
+}
+
+/// What becomes of a line on stdin or a pipe that isn't a signal (`--unknown-lines`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownLines {
+    /// Drop it; it's still logged with `--log`.
+    #[default]
+    Ignore,
+    /// Show it as an `INFO:` note, for a task whose ordinary output is worth seeing.
+    Show,
+}
+
+impl std::str::FromStr for UnknownLines {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ignore" => Ok(UnknownLines::Ignore),
+            "show" => Ok(UnknownLines::Show),
+            _ => Err(format!("unknown --unknown-lines policy '{}' (expected ignore or show)", s)),
+        }
+    }
+}
+
+/// A JSON command line, e.g. `{"command":"spawn","species":"shark"}` or
+/// `{"status":"success","msg":"deploy done"}`.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct JsonCommand {
+    command: Option<String>,
+    status: Option<String>,
+    msg: String,
+    percent: f32,
+    species: String,
+    task: Option<String>,
+}
+
+fn parse_json_command(line: &str) -> Option<SignalMessage> {
+    let cmd: JsonCommand = serde_json::from_str(line).ok()?;
+    let kind = cmd.command.or(cmd.status)?.to_ascii_lowercase();
+    let message = match kind.as_str() {
+        "success" => Some(SignalMessage::Success(cmd.msg)),
+        "failure" => Some(SignalMessage::Failure(cmd.msg)),
+        "progress" => Some(SignalMessage::Progress {
+            percent: cmd.percent.clamp(0.0, 100.0) as u8,
+            label: cmd.msg,
+        }),
+        "banner" => Some(SignalMessage::Banner(cmd.msg)),
+        "info" => Some(SignalMessage::Info(cmd.msg)),
+        "heartbeat" => Some(SignalMessage::Heartbeat),
+        "spawn" if !cmd.species.is_empty() => Some(SignalMessage::Spawn(cmd.species)),
+        _ => None,
+    }?;
+    Some(for_task(cmd.task, message))
+}
+
+/// `message` tagged with the task it's about, if any.
+fn for_task(task: Option<String>, message: SignalMessage) -> SignalMessage {
+    match task.filter(|name| !name.is_empty()) {
+        Some(name) => SignalMessage::Task { name, message: Box::new(message) },
+        None => message,
+    }
+}
+
+/// Parse one protocol line such as `SUCCESS:message`, `PROGRESS:42:building`,
+/// `TASK:deploy:SUCCESS:live` or a JSON command object.
+pub fn parse_signal_line(line: &str) -> Option<SignalMessage> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        return parse_json_command(line);
+    }
+    if let Some(rest) = line.strip_prefix("TASK:") {
+        let (name, message) = rest.split_once(':')?;
+        let message = parse_signal_line(message).filter(|message| !matches!(message, SignalMessage::Task { .. }))?;
+        (!name.trim().is_empty()).then(|| for_task(Some(name.trim().to_string()), message))
+    } else if let Some(msg) = line.strip_prefix("SUCCESS:") {
+        Some(SignalMessage::Success(msg.to_string()))
+    } else if let Some(msg) = line.strip_prefix("FAILURE:") {
+        Some(SignalMessage::Failure(msg.to_string()))
+    } else if let Some(rest) = line.strip_prefix("PROGRESS:") {
+        let (percent, label) = rest.split_once(':').unwrap_or((rest, ""));
+        let percent: f32 = percent.trim().parse().ok()?;
+        Some(SignalMessage::Progress {
+            percent: percent.clamp(0.0, 100.0) as u8,
+            label: label.to_string(),
+        })
+    } else if let Some(species) = line.strip_prefix("SPAWN:") {
+        let species = species.trim();
+        (!species.is_empty()).then(|| SignalMessage::Spawn(species.to_string()))
+    } else if let Some(msg) = line.strip_prefix("INFO:") {
+        Some(SignalMessage::Info(msg.to_string()))
+    } else if line == "HEARTBEAT" || line.starts_with("HEARTBEAT:") {
+        Some(SignalMessage::Heartbeat)
+    } else {
+        line.strip_prefix("BANNER:").map(|msg| SignalMessage::Banner(msg.to_string()))
+    }
+}
+
+/// Where a [`SignalSource`]'s messages go, and how it learns the game has stopped
+/// listening.
+#[derive(Debug, Clone)]
+pub struct SignalSink {
+    inbox: SharedSignal,
+    stopped: Arc<AtomicBool>,
+    unknown_lines: UnknownLines,
+}
+
+impl SignalSink {
+    /// Deliver `message`, unless the game has stopped listening. Returns whether the
+    /// source should carry on.
+    pub fn send(&self, message: SignalMessage) -> bool {
+        if self.is_stopped() {
+            return false;
+        }
+        self.inbox.lock().unwrap().push_back(message);
+        true
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Deliver the message on `line`, or deal with a line that isn't one as the
+    /// [`UnknownLines`] policy says. Returns whether the source should carry on.
+    pub(crate) fn send_line(&self, line: &str, task: Option<&str>) -> bool {
+        let message = match parse_signal_line(line) {
+            Some(message) => message,
+            None if line.trim().is_empty() => return !self.is_stopped(),
+            None => {
+                tracing::warn!("ignored signal line {:?}", line);
+                match self.unknown_lines {
+                    UnknownLines::Ignore => return !self.is_stopped(),
+                    UnknownLines::Show => SignalMessage::Info(line.trim().to_string()),
+                }
+            }
+        };
+        self.send(for_task(task.map(str::to_string), message))
+    }
+}
+
+/// Wakes a source blocked waiting for a writer or a client, so it sees it's stopped.
+pub type Waker = Box<dyn FnOnce() + Send>;
+
+/// Somewhere signals come from. [`Signals`] runs each on a thread of its own.
+pub trait SignalSource: Send + 'static {
+    /// Deliver signals to `sink` until the source runs dry or the sink is stopped.
+    fn run(self: Box<Self>, sink: SignalSink);
+
+    /// How to wake `run` from a wait for a writer or client once the sink is stopped;
+    /// `None` for a source that notices by itself, or can't be woken.
+    fn waker(&self) -> Option<Waker> {
+        None
+    }
+}
+
+/// How long [`Signals::shutdown`] waits for the sources to stop.
+const SHUTDOWN_WAIT: Duration = Duration::from_millis(300);
+
+/// The signal sources running, all delivering to the one inbox.
+pub struct Signals {
+    sink: SignalSink,
+    wakers: Vec<Waker>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Signals {
+    pub fn new(inbox: SharedSignal, unknown_lines: UnknownLines) -> Signals {
+        let sink = SignalSink { inbox, stopped: Arc::new(AtomicBool::new(false)), unknown_lines };
+        Signals { sink, wakers: Vec::new(), threads: Vec::new() }
+    }
+
+    pub fn start(&mut self, source: impl SignalSource) {
+        self.wakers.extend(source.waker());
+        let sink = self.sink.clone();
+        self.threads.push(thread::spawn(move || Box::new(source).run(sink)));
+    }
+
+    /// Stop every source, waking those that are waiting and giving them a moment to
+    /// let go of their pipes and sockets. A source blocked in a read nothing can
+    /// interrupt, such as stdin's, is left to end with the process.
+    pub fn shutdown(self) {
+        self.sink.stopped.store(true, Ordering::Relaxed);
+        for wake in self.wakers {
+            wake();
+        }
+        let deadline = Instant::now() + SHUTDOWN_WAIT;
+        for thread in self.threads {
+            while !thread.is_finished() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+            if thread.is_finished() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+/// Send each message read from `reader`, tagged with `task` if the source belongs to
+/// one, until it runs dry or the sink is stopped.
+fn read_signal_lines<R: BufRead>(reader: R, task: Option<&str>, sink: &SignalSink) {
+    for line in reader.lines().map_while(Result::ok) {
+        if !sink.send_line(&line, task) {
+            return;
+        }
+    }
+}
+
+/// Signals on stdin (`--subprocess`).
+#[derive(Debug, Default)]
+pub struct StdinSource {
+    /// Once stdin closes without having carried a SUCCESS or FAILURE, fail the wait
+    /// this long after (`--eof-failure`): whatever fed it most likely died.
+    pub eof_failure: Option<Duration>,
+}
+
+impl SignalSource for StdinSource {
+    fn run(self: Box<Self>, sink: SignalSink) {
+        let mut decided = false;
+        for line in BufReader::new(io::stdin()).lines().map_while(Result::ok) {
+            decided |= parse_signal_line(&line).is_some_and(|message| message.outcome().is_some());
+            if !sink.send_line(&line, None) {
+                return;
+            }
+        }
+        let Some(timeout) = self.eof_failure.filter(|_| !decided) else {
+            return;
+        };
+        tracing::info!("stdin closed without an outcome");
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if sink.is_stopped() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        sink.send(SignalMessage::Failure("stdin closed without SUCCESS or FAILURE".to_string()));
+    }
+}
+
+/// A named pipe (`--pipe`, or `--task NAME=PIPE` for one task's): a Win32 pipe server
+/// on Windows, a FIFO elsewhere. Writers may come and go; each is read until it
+/// leaves.
+pub struct PipeSource {
+    pub path: PathBuf,
+    pub task: Option<String>,
+}
+
+impl SignalSource for PipeSource {
+    fn run(self: Box<Self>, sink: SignalSink) {
+        while !sink.is_stopped() {
+            #[cfg(windows)]
+            // Windows named pipe: \\.\pipe\name
+            let client = windows_pipe::accept_client(&self.path);
+            // Unix named pipe (FIFO). Opening blocks until a writer appears and the
+            // reader sees EOF once it leaves, so reopen to wait for the next one.
+            #[cfg(not(windows))]
+            let client = fs::File::open(&self.path);
+            if let Ok(file) = client {
+                read_signal_lines(BufReader::new(file), self.task.as_deref(), &sink);
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Connect as a writer that says nothing and leaves.
+    fn waker(&self) -> Option<Waker> {
+        let path = self.path.clone();
+        Some(Box::new(move || {
+            #[cfg(windows)]
+            let _ = windows_pipe::connect(&path);
+            // Without blocking, in case nothing is waiting to read
+            #[cfg(unix)]
+            let _ = {
+                use std::os::unix::fs::OpenOptionsExt;
+                fs::OpenOptions::new().write(true).custom_flags(libc::O_NONBLOCK).open(&path)
+            };
+            #[cfg(not(any(unix, windows)))]
+            let _ = path;
+        }))
+    }
+}
+
+/// Create a FIFO at `path` unless one is already there. Returns whether a new FIFO
+/// was made, in which case the caller is responsible for removing it. Anything else
+/// at `path` is an error: a regular file would read the same lines over and over.
+#[cfg(unix)]
+pub fn ensure_fifo(path: &std::path::Path) -> io::Result<bool> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::FileTypeExt;
+
+    if let Ok(metadata) = fs::metadata(path) {
+        if metadata.file_type().is_fifo() {
+            return Ok(false);
+        }
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} exists and is not a named pipe", path.display())));
+    }
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+    if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(true)
+}
+
+#[cfg(windows)]
+mod windows_pipe {
+    use std::ffi::OsStr;
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::{FromRawHandle, RawHandle};
+    use std::path::Path;
+    use std::ptr;
+
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_PIPE_CONNECTED, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Storage::FileSystem::PIPE_ACCESS_INBOUND;
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE,
+        PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    const PIPE_PREFIX: &str = r"\\.\pipe\";
+    const BUFFER_SIZE: u32 = 4096;
+
+    /// Accept `fisherman` as shorthand for `\\.\pipe\fisherman`.
+    fn pipe_name(path: &Path) -> Vec<u16> {
+        let raw = path.as_os_str();
+        let full = if raw.to_string_lossy().starts_with(PIPE_PREFIX) {
+            raw.to_os_string()
+        } else {
+            let mut name = OsStr::new(PIPE_PREFIX).to_os_string();
+            name.push(raw);
+            name
+        };
+        full.encode_wide().chain(std::iter::once(0)).collect()
+    }
+
+    /// Connect to the pipe at `path` as a writer.
+    pub fn connect(path: &Path) -> io::Result<File> {
+        let name = pipe_name(path);
+        std::fs::OpenOptions::new().write(true).open(String::from_utf16_lossy(&name[..name.len() - 1]))
+    }
+
+    /// Create a fresh pipe instance and block until a writer connects to it. The
+    /// returned file reads until that writer disconnects; dropping it closes the
+    /// instance so the next call can serve another client.
+    pub fn accept_client(path: &Path) -> io::Result<File> {
+        let name = pipe_name(path);
+        // SAFETY: `name` is a NUL-terminated UTF-16 string that outlives the call, and
+        // the handle is either closed here or owned by the returned `File`.
+        unsafe {
+            let handle = CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_INBOUND,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                ptr::null(),
+            );
+            if handle == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            // A client that connected between create and connect is reported as
+            // ERROR_PIPE_CONNECTED, which is still a success.
+            if ConnectNamedPipe(handle, ptr::null_mut()) == 0 && GetLastError() != ERROR_PIPE_CONNECTED {
+                let err = io::Error::last_os_error();
+                CloseHandle(handle);
+                return Err(err);
+            }
+            Ok(File::from_raw_handle(handle as RawHandle))
+        }
+    }
+}
+
+/// A plain file polled for signals (`--signal-file`), emptied once read.
+pub struct SignalFileSource {
+    pub path: PathBuf,
+}
+
+impl SignalSource for SignalFileSource {
+    fn run(self: Box<Self>, sink: SignalSink) {
+        let mut taken = self.path.clone().into_os_string();
+        taken.push(".taken");
+        while !sink.is_stopped() {
+            thread::sleep(Duration::from_millis(100));
+            if fs::metadata(&self.path).is_ok_and(|m| m.len() == 0) {
+                continue;
+            }
+            // Move the file aside before reading it, so a line appended meanwhile goes
+            // to a fresh file for the next poll instead of being cleared away unread
+            if fs::rename(&self.path, &taken).is_err() {
+                continue;
+            }
+            let _ = fs::OpenOptions::new().create(true).append(true).open(&self.path);
+            // A writer that opened the file just before it moved is let finish
+            thread::sleep(Duration::from_millis(20));
+            if let Ok(content) = fs::read_to_string(&taken) {
+                for line in content.lines() {
+                    sink.send_line(line, None);
+                }
+            }
+            let _ = fs::remove_file(&taken);
+        }
+    }
+}
+
+/// A Unix domain socket (`--socket`). Clients connect one after another and each may
+/// send any number of signal lines before disconnecting.
+#[cfg(unix)]
+pub struct SocketSource {
+    listener: std::os::unix::net::UnixListener,
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl SocketSource {
+    pub fn bind(path: PathBuf) -> io::Result<SocketSource> {
+        use std::os::unix::fs::FileTypeExt;
+
+        // A socket left behind by a previous run would make bind fail, but anything
+        // else at the path is left alone
+        if let Ok(metadata) = fs::symlink_metadata(&path) {
+            if !metadata.file_type().is_socket() {
+                return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} exists and is not a socket", path.display())));
+            }
+            fs::remove_file(&path)?;
+        }
+        let listener = std::os::unix::net::UnixListener::bind(&path)?;
+        Ok(SocketSource { listener, path })
+    }
+}
+
+#[cfg(unix)]
+impl SignalSource for SocketSource {
+    fn run(self: Box<Self>, sink: SignalSink) {
+        for stream in self.listener.incoming().flatten() {
+            if sink.is_stopped() {
+                return;
+            }
+            read_signal_lines(BufReader::new(stream), None, &sink);
+        }
+    }
+
+    fn waker(&self) -> Option<Waker> {
+        let path = self.path.clone();
+        Some(Box::new(move || {
+            let _ = std::os::unix::net::UnixStream::connect(path);
+        }))
+    }
+}
+
+/// A tiny HTTP endpoint (`--listen`) so remote machines can deliver signals with
+/// `POST /signal?status=success&msg=deploy+done`. Parameters may also be sent as a
+/// form-encoded body; progress updates add `percent=42`. Each client is served on a
+/// thread of its own, so one that connects and says nothing holds up no one else.
+pub struct HttpSource {
+    listener: TcpListener,
+    /// Served on `GET /metrics`, when given.
+    metrics: Option<Metrics>,
+}
+
+impl HttpSource {
+    pub fn bind(addr: &str) -> io::Result<HttpSource> {
+        Ok(HttpSource { listener: TcpListener::bind(addr)?, metrics: None })
+    }
+
+    /// Also report `metrics` on `GET /metrics`.
+    pub fn with_metrics(self, metrics: Metrics) -> HttpSource {
+        HttpSource { metrics: Some(metrics), ..self }
+    }
+}
+
+impl SignalSource for HttpSource {
+    fn run(self: Box<Self>, sink: SignalSink) {
+        for stream in self.listener.incoming().flatten() {
+            if sink.is_stopped() {
+                return;
+            }
+            let (sink, metrics) = (sink.clone(), self.metrics.clone());
+            thread::spawn(move || handle_http_client(stream, &sink, metrics.as_ref()));
+        }
+    }
+
+    fn waker(&self) -> Option<Waker> {
+        let mut addr = self.listener.local_addr().ok()?;
+        // Listening on every interface, it can be reached on this one
+        if addr.ip().is_unspecified() {
+            addr.set_ip(Ipv4Addr::LOCALHOST.into());
+        }
+        Some(Box::new(move || {
+            let _ = TcpStream::connect(addr);
+        }))
+    }
+}
+
+fn handle_http_client(mut stream: TcpStream, sink: &SignalSink, metrics: Option<&Metrics>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("");
+
+    let mut content_length = 0usize;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+
+    let mut body = vec![0u8; content_length.min(64 * 1024)];
+    reader.read_exact(&mut body)?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (status, reply) = match (method, path) {
+        ("POST", "/signal") => {
+            let body = String::from_utf8_lossy(&body);
+            match parse_signal_params(query, &body) {
+                Some(parsed) => {
+                    sink.send(parsed);
+                    ("200 OK", "ok\n".to_string())
+                }
+                None => {
+                    tracing::warn!("ignored HTTP signal {:?}", target);
+                    ("400 Bad Request", "expected status=success|failure|progress|banner|info|spawn|heartbeat\n".to_string())
+                }
+            }
+        }
+        (_, "/signal") => ("405 Method Not Allowed", "use POST\n".to_string()),
+        ("GET", "/metrics") if metrics.is_some() => ("200 OK", metrics.map(Metrics::render).unwrap_or_default()),
+        _ => ("404 Not Found", "not found\n".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reply.len(),
+        reply
+    )?;
+    stream.flush()
+}
+
+fn parse_signal_params(query: &str, body: &str) -> Option<SignalMessage> {
+    let mut status = String::new();
+    let mut msg = String::new();
+    let mut percent = String::new();
+    let mut species = String::new();
+    let mut task = None;
+    for pair in query.split('&').chain(body.trim().split('&')) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "status" => status = url_decode(value).to_ascii_lowercase(),
+            "msg" => msg = url_decode(value),
+            "percent" => percent = url_decode(value),
+            "species" => species = url_decode(value),
+            "task" => task = Some(url_decode(value)),
+            _ => {}
+        }
+    }
+    let message = match status.as_str() {
+        "success" => Some(SignalMessage::Success(msg)),
+        "failure" => Some(SignalMessage::Failure(msg)),
+        "progress" => parse_signal_line(&format!("PROGRESS:{}:{}", percent, msg)),
+        "banner" => Some(SignalMessage::Banner(msg)),
+        "info" => Some(SignalMessage::Info(msg)),
+        "heartbeat" => Some(SignalMessage::Heartbeat),
+        "spawn" => parse_signal_line(&format!("SPAWN:{}", species)),
+        _ => None,
+    }?;
+    Some(for_task(task, message))
+}
+
+fn url_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    Err(_) => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_deliver_until_shut_down_and_then_let_go() {
+        let inbox: SharedSignal = Arc::default();
+        let mut signals = Signals::new(Arc::clone(&inbox), UnknownLines::Show);
+        let metrics = Metrics::new();
+        metrics.signal("success");
+        let http = HttpSource::bind("127.0.0.1:0").unwrap().with_metrics(metrics);
+        let addr = http.listener.local_addr().unwrap();
+        signals.start(http);
+        #[cfg(unix)]
+        let fifo = {
+            let path = std::env::temp_dir().join(format!("fisherman-signals-{}.fifo", std::process::id()));
+            assert!(ensure_fifo(&path).unwrap());
+            assert!(!ensure_fifo(&path).unwrap());
+            signals.start(PipeSource { path: path.clone(), task: None });
+            // A regular file would be read again and again, so it's turned away
+            let plain = path.with_extension("txt");
+            fs::write(&plain, "SUCCESS:done\n").unwrap();
+            assert_eq!(ensure_fifo(&plain).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+            // Nor will --socket clear away a file that isn't a socket
+            assert_eq!(SocketSource::bind(plain.clone()).err().map(|e| e.kind()), Some(io::ErrorKind::AlreadyExists));
+            assert_eq!(fs::read_to_string(&plain).unwrap(), "SUCCESS:done\n");
+            fs::remove_file(plain).unwrap();
+            // while one left behind by an earlier run is replaced
+            let socket = path.with_extension("sock");
+            drop(SocketSource::bind(socket.clone()).unwrap());
+            drop(SocketSource::bind(socket.clone()).unwrap());
+            fs::remove_file(socket).unwrap();
+            path
+        };
+
+        // A client that connects and says nothing doesn't keep the next one waiting
+        let _idle = TcpStream::connect(addr).unwrap();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        write!(stream, "POST /signal?status=success&msg=done HTTP/1.1\r\nContent-Length: 0\r\n\r\n").unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        assert!(reply.starts_with("HTTP/1.1 200"));
+        assert_eq!(inbox.lock().unwrap().pop_front(), Some(SignalMessage::Success("done".to_string())));
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        assert!(reply.starts_with("HTTP/1.1 200"));
+        assert!(reply.contains("fisherman_signals_received_total{status=\"success\"} 1"));
+
+        // Both are woken from waiting for the next client, well before the deadline
+        let sink = signals.sink.clone();
+        let started = Instant::now();
+        signals.shutdown();
+        assert!(started.elapsed() < SHUTDOWN_WAIT);
+        assert!(!sink.send(SignalMessage::Banner("late".to_string())));
+        assert!(TcpListener::bind(addr).is_ok());
+        #[cfg(unix)]
+        fs::remove_file(fifo).unwrap();
+    }
+
+    #[test]
+    fn http_parameters_are_decoded() {
+        assert_eq!(url_decode("deploy+done%21"), "deploy done!");
+        assert_eq!(url_decode("caf%C3%A9%20au%2Blait"), "café au+lait");
+        assert_eq!(url_decode("100%"), "100%");
+        assert_eq!(url_decode("50%2"), "50%2");
+        assert_eq!(url_decode("%zz%4"), "%zz%4");
+
+        let parse = |query: &str, body: &str| parse_signal_params(query, body);
+        assert_eq!(parse("status=success&msg=deploy+done", ""), Some(SignalMessage::Success("deploy done".to_string())));
+        assert_eq!(parse("", "status=FAILURE&msg=tests%20failed\r\n"), Some(SignalMessage::Failure("tests failed".to_string())));
+        assert_eq!(
+            parse("status=progress&percent=42", "msg=compiling"),
+            Some(SignalMessage::Progress { percent: 42, label: "compiling".to_string() })
+        );
+        assert_eq!(parse("status=heartbeat", ""), Some(SignalMessage::Heartbeat));
+        assert_eq!(parse("status=success&task=build", ""), Some(for_task(Some("build".to_string()), SignalMessage::Success(String::new()))));
+        // Nothing to go on, or an unknown status, is no signal
+        assert_eq!(parse("", ""), None);
+        assert_eq!(parse("msg=done", ""), None);
+        assert_eq!(parse("status=maybe&msg=done", ""), None);
+        assert_eq!(parse("status=progress&msg=compiling", ""), None);
+        assert_eq!(parse("status=spawn", ""), None);
+    }
+
+    #[test]
+    fn signal_policies_parse() {
+        assert_eq!("hold".parse(), Ok(SignalPolicy::Hold));
+        assert_eq!("exit-after=1.5".parse(), Ok(SignalPolicy::ExitAfter(Duration::from_millis(1500))));
+        for bad in ["exit-after=-1", "exit-after=nan", "exit-after=inf", "exit-after=1e30", "exit-after=", "linger"] {
+            assert!(bad.parse::<SignalPolicy>().is_err(), "{}", bad);
+        }
+    }
+}
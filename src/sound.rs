@@ -0,0 +1,154 @@
+//! Audio cues for game events, with the `sound` feature: a whoosh as the line goes out,
+//! a splash, a jingle for a catch or a SUCCESS signal and a buzz for a FAILURE. The
+//! cues are synthesized rather than loaded, and played through rodio on the default
+//! output device. `[sound]` in the config file sets the volume or mutes them:
+//!
+//! ```toml
+//! [sound]
+//! volume = 0.5
+//! mute = false
+//! ```
+
+use std::f32::consts::PI;
+
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, OutputStreamHandle};
+
+use crate::events::GameEvent;
+use crate::config_file::{Table, Value};
+
+const SAMPLE_RATE: u32 = 22_050;
+const DEFAULT_VOLUME: f32 = 0.6;
+
+/// The `[sound]` settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundSettings {
+    /// From 0 (silent) to 1 (full).
+    pub volume: f32,
+    pub mute: bool,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        SoundSettings { volume: DEFAULT_VOLUME, mute: false }
+    }
+}
+
+impl SoundSettings {
+    pub fn from_table(table: &Table) -> Result<SoundSettings, String> {
+        let mut settings = SoundSettings::default();
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("volume", Value::Number(volume)) if (0.0..=1.0).contains(volume) => settings.volume = *volume as f32,
+                ("volume", _) => return Err("[sound] volume must be a number from 0 to 1".to_string()),
+                ("mute", Value::Bool(mute)) => settings.mute = *mute,
+                ("mute", _) => return Err("[sound] mute must be true or false".to_string()),
+                (key, _) => return Err(format!("unknown setting '{}' in [sound]", key)),
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// The open output device the cues play on.
+pub struct Sound {
+    // Playback stops when the stream is dropped
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+    volume: f32,
+}
+
+impl Sound {
+    /// Open the default output device, or `None` if muted or there is no device to
+    /// play on, in which case the game carries on silently.
+    pub fn open(settings: SoundSettings) -> Option<Sound> {
+        if settings.mute || settings.volume == 0.0 {
+            return None;
+        }
+        let (stream, handle) = OutputStream::try_default().ok()?;
+        Some(Sound { _stream: stream, handle, volume: settings.volume })
+    }
+
+    /// Start the cue for `event`, mixed over any still playing.
+    pub fn play(&self, event: GameEvent) {
+        let samples: Vec<f32> = cue(&event).into_iter().map(|sample| sample * self.volume).collect();
+        let _ = self.handle.play_raw(SamplesBuffer::new(1, SAMPLE_RATE, samples));
+    }
+}
+
+/// The samples of the cue for `event`, mono at [`SAMPLE_RATE`] and within -1 to 1;
+/// none for an event without one.
+fn cue(event: &GameEvent) -> Vec<f32> {
+    match event {
+        GameEvent::Cast => {
+            // Noise swelling and fading, smoothed so it rushes rather than hisses
+            let mut noise = Noise::new(7);
+            let mut level = 0.0;
+            samples(0.25, |t| {
+                level += (noise.sample() - level) * 0.2;
+                level * (PI * t / 0.25).sin() * 0.8
+            })
+        }
+        GameEvent::CastLanded { .. } => {
+            let mut noise = Noise::new(11);
+            samples(0.3, |t| noise.sample() * (-t * 14.0).exp() * 0.7)
+        }
+        GameEvent::FishCaught { .. } | GameEvent::SignalReceived { success: true, .. } | GameEvent::RoundOver { .. } => {
+            // A rising arpeggio, C E G C
+            let notes = [523.25, 659.25, 783.99, 1046.5];
+            samples(0.4, |t| {
+                let note = ((t / 0.1) as usize).min(notes.len() - 1);
+                let since = t - note as f32 * 0.1;
+                (2.0 * PI * notes[note] * t).sin() * (-since * 12.0).exp() * 0.5
+            })
+        }
+        GameEvent::SignalReceived { success: false, .. } => samples(0.35, |t| {
+            let square = if (t * 110.0).fract() < 0.5 { 1.0 } else { -1.0 };
+            square * (1.0 - t / 0.35) * 0.35
+        }),
+        GameEvent::WeatherChanged(_) => Vec::new(),
+    }
+}
+
+/// `seconds` of samples, each from `wave` at its time in seconds.
+fn samples(seconds: f32, mut wave: impl FnMut(f32) -> f32) -> Vec<f32> {
+    let count = (seconds * SAMPLE_RATE as f32) as usize;
+    (0..count).map(|i| wave(i as f32 / SAMPLE_RATE as f32).clamp(-1.0, 1.0)).collect()
+}
+
+/// White noise from a xorshift generator, the same every time for a seed.
+struct Noise(u32);
+
+impl Noise {
+    fn new(seed: u32) -> Noise {
+        Noise(seed.max(1))
+    }
+
+    fn sample(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+
+    #[test]
+    fn cues_are_short_and_settings_come_from_the_config() {
+        let signal = |success| GameEvent::SignalReceived { success, message: String::new() };
+        for event in [GameEvent::Cast, GameEvent::CastLanded { x: 0, y: 0 }, signal(true), signal(false)] {
+            let samples = cue(&event);
+            assert!(!samples.is_empty() && samples.len() <= SAMPLE_RATE as usize / 2);
+            assert!(samples.iter().all(|s| (-1.0..=1.0).contains(s)));
+        }
+        let config = ConfigFile::parse("[sound]\nvolume = 0.25\nmute = true").unwrap();
+        let settings = SoundSettings::from_table(config.section("sound").unwrap()).unwrap();
+        assert_eq!(settings, SoundSettings { volume: 0.25, mute: true });
+        let loud = ConfigFile::parse("[sound]\nvolume = 3").unwrap();
+        assert!(SoundSettings::from_table(loud.section("sound").unwrap()).is_err());
+    }
+}
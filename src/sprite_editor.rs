@@ -0,0 +1,423 @@
+//! The `fisherman edit` subcommand: a grid editor for sprite CSVs. The other CSVs in
+//! the same folder are opened alongside as the rest of the animation, so frames can be
+//! flipped through and played back while drawing.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Widget};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::csv_frames::{self, SpriteCell};
+use crate::terminal::{self, TerminalGuard};
+
+const USAGE: &str = "\
+usage: fisherman edit FILE.csv
+
+Opens FILE.csv in a grid editor, creating it on save if it doesn't exist. The other
+CSVs in the same folder are loaded as the rest of the animation.";
+
+const EXIT_USAGE: i32 = 2;
+const EXIT_FAILED: i32 = 1;
+/// The cursor can't leave this area; no sprite needs more.
+const MAX_CANVAS: (u32, u32) = (80, 30);
+/// Canvas shown around a small or empty sprite, so there's room to draw.
+const MIN_CANVAS: (u32, u32) = (24, 8);
+/// Colours offered besides the ones the frames already use.
+const DEFAULT_PALETTE: [Color; 10] = [
+    Color::Rgb(255, 255, 255),
+    Color::Rgb(160, 160, 160),
+    Color::Rgb(0, 0, 0),
+    Color::Rgb(255, 64, 64),
+    Color::Rgb(255, 165, 0),
+    Color::Rgb(255, 215, 0),
+    Color::Rgb(64, 192, 64),
+    Color::Rgb(0, 200, 200),
+    Color::Rgb(64, 128, 255),
+    Color::Rgb(200, 100, 255),
+];
+
+pub fn run(args: &[String]) -> i32 {
+    let [path] = args else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+    let mut editor = match Editor::open(Path::new(path)) {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("fisherman edit: {}: {}", path, e);
+            return EXIT_FAILED;
+        }
+    };
+    match edit(&mut editor) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("fisherman edit: {}", e);
+            EXIT_FAILED
+        }
+    }
+}
+
+fn edit(editor: &mut Editor) -> io::Result<()> {
+    terminal::install_panic_hook();
+    terminal::install_signal_handlers();
+    let _guard = TerminalGuard::new()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    while !editor.done && !terminal::interrupted() {
+        terminal.draw(|frame| editor.render(frame.area(), frame.buffer_mut()))?;
+        if event::poll(Duration::from_millis(50))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            editor.handle_key(key);
+        }
+        editor.tick(Instant::now());
+    }
+    Ok(())
+}
+
+/// One CSV of the animation.
+struct SpriteFrame {
+    path: PathBuf,
+    cells: BTreeMap<(u32, u32), SpriteCell>,
+    dirty: bool,
+}
+
+impl SpriteFrame {
+    fn load(path: PathBuf) -> io::Result<SpriteFrame> {
+        let cells = match fs::read_to_string(&path) {
+            Ok(content) => csv_frames::read_sprite_cells(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let cells = cells.into_iter().map(|cell| ((cell.x, cell.y), cell)).collect();
+        Ok(SpriteFrame { path, cells, dirty: false })
+    }
+
+    fn name(&self) -> String {
+        self.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        // Rows go out in reading order, like the hand-written sprites
+        let mut cells: Vec<SpriteCell> = self.cells.values().cloned().collect();
+        cells.sort_by_key(|cell| (cell.y, cell.x));
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, csv_frames::write_sprite_cells(&cells))?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+pub struct Editor {
+    frames: Vec<SpriteFrame>,
+    current: usize,
+    cursor: (u32, u32),
+    palette: Vec<Color>,
+    color: usize,
+    /// When the preview last flipped frame, while playing.
+    playing: Option<Instant>,
+    frame_duration: Duration,
+    status: String,
+    /// Esc was pressed once with unsaved changes; a second press discards them.
+    quit_armed: bool,
+    done: bool,
+}
+
+impl Editor {
+    /// Open `path` along with the other CSVs in its folder.
+    pub fn open(path: &Path) -> io::Result<Editor> {
+        let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "csv") && p.file_name() != path.file_name())
+            .collect();
+        paths.push(path.to_path_buf());
+        paths.sort_by_key(|p| p.file_name().map(|s| s.to_owned()));
+        let current = paths.iter().position(|p| p == path).unwrap_or(0);
+
+        let mut frames = Vec::with_capacity(paths.len());
+        for p in paths {
+            let is_target = p == path;
+            match SpriteFrame::load(p) {
+                Ok(frame) => frames.push(frame),
+                // A broken neighbour shouldn't stop the file asked for being edited
+                Err(_) if !is_target => {}
+                Err(e) => return Err(e),
+            }
+        }
+        let current = current.min(frames.len() - 1);
+
+        let mut palette: Vec<Color> = Vec::new();
+        let used = frames.iter().flat_map(|f| f.cells.values()).map(|cell| cell.fg);
+        for color in used.chain(DEFAULT_PALETTE) {
+            if !palette.contains(&color) {
+                palette.push(color);
+            }
+        }
+
+        // Frames live in <species>/<direction>/, with the species' meta.csv above them
+        let species_dir = dir.parent().unwrap_or(dir);
+        let frame_duration = csv_frames::load_species_traits(species_dir).frame_duration;
+
+        Ok(Editor {
+            frames,
+            current,
+            cursor: (0, 0),
+            palette,
+            color: 0,
+            playing: None,
+            frame_duration,
+            status: String::new(),
+            quit_armed: false,
+            done: false,
+        })
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let quit_armed = std::mem::take(&mut self.quit_armed);
+        self.status.clear();
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.done = true,
+            KeyCode::Char('s') if ctrl => self.save(),
+            KeyCode::Char('q') if ctrl => self.quit(quit_armed),
+            KeyCode::Esc => self.quit(quit_armed),
+            KeyCode::Left => self.cursor.0 = self.cursor.0.saturating_sub(1),
+            KeyCode::Right => self.cursor.0 = (self.cursor.0 + 1).min(MAX_CANVAS.0 - 1),
+            KeyCode::Up => self.cursor.1 = self.cursor.1.saturating_sub(1),
+            KeyCode::Down => self.cursor.1 = (self.cursor.1 + 1).min(MAX_CANVAS.1 - 1),
+            KeyCode::Home => self.cursor.0 = 0,
+            KeyCode::Tab => self.color = (self.color + 1) % self.palette.len(),
+            KeyCode::BackTab => self.color = (self.color + self.palette.len() - 1) % self.palette.len(),
+            KeyCode::PageDown => self.flip(1),
+            KeyCode::PageUp => self.flip(self.frames.len() - 1),
+            KeyCode::F(5) => self.playing = if self.playing.is_some() { None } else { Some(Instant::now()) },
+            KeyCode::Delete => self.erase(),
+            KeyCode::Backspace => {
+                self.cursor.0 = self.cursor.0.saturating_sub(1);
+                self.erase();
+            }
+            KeyCode::Enter => self.cursor = (0, (self.cursor.1 + 1).min(MAX_CANVAS.1 - 1)),
+            KeyCode::Char(ch) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                let width = self.draw_char(ch);
+                self.cursor.0 = (self.cursor.0 + width).min(MAX_CANVAS.0 - 1);
+            }
+            _ => {}
+        }
+    }
+
+    /// Advance the preview while it is playing.
+    pub fn tick(&mut self, now: Instant) {
+        if let Some(flipped) = self.playing
+            && now.duration_since(flipped) >= self.frame_duration
+        {
+            self.current = (self.current + 1) % self.frames.len();
+            self.playing = Some(now);
+        }
+    }
+
+    fn frame(&mut self) -> &mut SpriteFrame {
+        &mut self.frames[self.current]
+    }
+
+    fn flip(&mut self, by: usize) {
+        self.current = (self.current + by) % self.frames.len();
+    }
+
+    /// Set the character under the cursor in the selected colour, keeping any background.
+    /// Returns how many columns the character takes up. A combining mark goes on the
+    /// cell just left of the cursor and takes up none.
+    fn draw_char(&mut self, ch: char) -> u32 {
+        let (x, y) = self.cursor;
+        let fg = self.palette[self.color];
+        let frame = self.frame();
+        let width = ch.width().unwrap_or(1) as u32;
+        if width == 0 {
+            if let Some(cell) = x.checked_sub(1).and_then(|left| frame.cells.get_mut(&(left, y))) {
+                cell.symbol.push(ch);
+                frame.dirty = true;
+            }
+            return 0;
+        }
+        let bg = frame.cells.get(&(x, y)).and_then(|cell| cell.bg);
+        frame.cells.insert((x, y), SpriteCell { x, y, symbol: ch.to_string(), fg, bg });
+        frame.dirty = true;
+        width
+    }
+
+    fn erase(&mut self) {
+        let cursor = self.cursor;
+        let frame = self.frame();
+        if frame.cells.remove(&cursor).is_some() {
+            frame.dirty = true;
+        }
+    }
+
+    fn save(&mut self) {
+        let mut saved = Vec::new();
+        for frame in self.frames.iter_mut().filter(|f| f.dirty) {
+            if let Err(e) = frame.save() {
+                self.status = format!("Couldn't save {}: {}", frame.path.display(), e);
+                return;
+            }
+            saved.push(frame.name());
+        }
+        self.status =
+            if saved.is_empty() { "Nothing to save".to_string() } else { format!("Saved {}", saved.join(", ")) };
+    }
+
+    fn quit(&mut self, confirmed: bool) {
+        if confirmed || !self.frames.iter().any(|f| f.dirty) {
+            self.done = true;
+        } else {
+            self.status = "Unsaved changes: Ctrl+S saves, Esc again discards them".to_string();
+            self.quit_armed = true;
+        }
+    }
+
+    /// Cells across and down: the largest frame plus room to grow, and always the cursor.
+    fn canvas_size(&self) -> (u32, u32) {
+        let extent = self.frames.iter().flat_map(|f| f.cells.keys()).fold(MIN_CANVAS, |(w, h), &(x, y)| {
+            (w.max(x + 4), h.max(y + 2))
+        });
+        (extent.0.max(self.cursor.0 + 1).min(MAX_CANVAS.0), extent.1.max(self.cursor.1 + 1).min(MAX_CANVAS.1))
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let frame = &self.frames[self.current];
+        let modified = if frame.dirty { " [modified]" } else { "" };
+        let playing = if self.playing.is_some() { " - playing" } else { "" };
+        let title = format!(
+            " {} - frame {}/{}{}{} ",
+            frame.name(),
+            self.current + 1,
+            self.frames.len(),
+            modified,
+            playing
+        );
+
+        let (width, height) = self.canvas_size();
+        let canvas = Rect::new(area.x, area.y, (width as u16 + 2).min(area.width), (height as u16 + 2).min(area.height));
+        let block = Block::default().title(title).borders(Borders::ALL);
+        let inner = block.inner(canvas);
+        block.render(canvas, buf);
+        let empty = Style::default().fg(Color::DarkGray);
+        for y in 0..inner.height {
+            let mut x = 0;
+            while x < inner.width {
+                let (cx, cy) = (x as u32, y as u32);
+                let mut style = empty;
+                let mut symbol = "·";
+                if let Some(cell) = frame.cells.get(&(cx, cy)) {
+                    symbol = &cell.symbol;
+                    style = Style::default().fg(cell.fg);
+                    if let Some(bg) = cell.bg {
+                        style = style.bg(bg);
+                    }
+                }
+                // A wide character covers the next column, unless it's off the edge
+                let width = symbol.width().max(1) as u16;
+                if x + width > inner.width {
+                    symbol = " ";
+                }
+                if (cx, cy) == self.cursor && self.playing.is_none() {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                buf[(inner.x + x, inner.y + y)].set_symbol(symbol).set_style(style);
+                x += width;
+            }
+        }
+
+        let mut swatches = vec![Span::raw("Colour ")];
+        for (i, color) in self.palette.iter().enumerate() {
+            let (open, close) = if i == self.color { ("[", "]") } else { (" ", " ") };
+            swatches.push(Span::raw(open));
+            swatches.push(Span::styled("██", Style::default().fg(*color)));
+            swatches.push(Span::raw(close));
+        }
+        let help = "Arrows move  Type to draw  Del erase  Tab colour  PgUp/PgDn frame  F5 play  Ctrl+S save  Esc quit";
+        let lines = vec![
+            Line::from(swatches),
+            Line::from(Span::styled(help, Style::default().fg(Color::Gray))),
+            Line::from(Span::styled(self.status.as_str(), Style::default().fg(Color::Yellow))),
+        ];
+        let below = canvas.bottom().min(area.bottom());
+        Paragraph::new(lines).render(Rect::new(area.x, below, area.width, area.bottom() - below), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(editor: &mut Editor, code: KeyCode) {
+        editor.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn typing_draws_and_saving_writes_every_changed_frame() {
+        let dir = std::env::temp_dir().join(format!("fisherman-edit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.csv"), "X,Y,ASCII,Foreground,Background\n0,0,<,#FF0000,\n").unwrap();
+        let mut editor = Editor::open(&dir.join("b.csv")).unwrap();
+        assert_eq!((editor.current, editor.frames.len()), (1, 2));
+        assert_eq!(editor.palette[0], Color::Rgb(255, 0, 0), "colours in use come first");
+
+        press(&mut editor, KeyCode::Down);
+        for ch in "><>".chars() {
+            press(&mut editor, KeyCode::Char(ch));
+        }
+        press(&mut editor, KeyCode::Backspace);
+        press(&mut editor, KeyCode::PageUp);
+        press(&mut editor, KeyCode::Tab);
+        press(&mut editor, KeyCode::Char('o'));
+
+        press(&mut editor, KeyCode::Esc);
+        assert!(!editor.done, "unsaved changes need a second Esc");
+        editor.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        press(&mut editor, KeyCode::Esc);
+        assert!(editor.done);
+
+        let a = fs::read_to_string(dir.join("a.csv")).unwrap();
+        let b = fs::read_to_string(dir.join("b.csv")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(a, "X,Y,ASCII,Foreground,Background\n0,0,<,#FF0000,\n2,1,o,#FFFFFF,\n");
+        assert_eq!(b, "X,Y,ASCII,Foreground,Background\n0,1,>,#FF0000,\n1,1,<,#FF0000,\n");
+    }
+
+    #[test]
+    fn preview_cycles_frames() {
+        let dir = std::env::temp_dir().join(format!("fisherman-edit-play-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["1.csv", "2.csv", "3.csv"] {
+            fs::write(dir.join(name), "X,Y,ASCII,Foreground\n").unwrap();
+        }
+        let mut editor = Editor::open(&dir.join("1.csv")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        press(&mut editor, KeyCode::F(5));
+        let start = editor.playing.unwrap();
+        for step in 1..=3 {
+            editor.tick(start + editor.frame_duration * step);
+        }
+        assert_eq!(editor.current, 0);
+        editor.tick(start + editor.frame_duration * 4);
+        assert_eq!(editor.current, 1);
+    }
+}
@@ -0,0 +1,101 @@
+//! `--watch-sprites`: notices when sprite CSVs change on disk so they can be reloaded
+//! without restarting. Polls modification times, like the signal file poller, which
+//! works the same on every platform and is plenty fast for a person editing art.
+//! `fisherman watch` polls the files it watches the same way.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What a tree of files looked like at one poll: every file with its size and mtime.
+pub type Snapshot = Vec<(PathBuf, u64, Option<SystemTime>)>;
+
+pub struct SpriteWatcher {
+    changed: Arc<AtomicBool>,
+}
+
+impl SpriteWatcher {
+    /// Watch `paths` (files, or directories searched recursively for CSVs) from a
+    /// background thread.
+    pub fn spawn(paths: Vec<PathBuf>) -> SpriteWatcher {
+        let changed = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&changed);
+        thread::spawn(move || {
+            let mut last = snapshot(&paths);
+            loop {
+                thread::sleep(POLL_INTERVAL);
+                let current = snapshot(&paths);
+                if current != last {
+                    flag.store(true, Ordering::Relaxed);
+                    last = current;
+                }
+            }
+        });
+        SpriteWatcher { changed }
+    }
+
+    /// Whether anything changed since the last call.
+    pub fn take_change(&self) -> bool {
+        self.changed.swap(false, Ordering::Relaxed)
+    }
+}
+
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    snapshot_of(paths, |path, is_dir| is_dir || path.extension().is_some_and(|ext| ext == "csv"))
+}
+
+/// The files under `paths` (files, or directories searched recursively) that `keep`
+/// accepts, along with the directories it lets the search go into.
+pub fn snapshot_of(paths: &[PathBuf], keep: fn(&Path, bool) -> bool) -> Snapshot {
+    let mut files = Snapshot::new();
+    for path in paths {
+        collect(path, keep, &mut files);
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    files
+}
+
+fn collect(path: &Path, keep: fn(&Path, bool) -> bool, files: &mut Snapshot) {
+    let Ok(meta) = fs::metadata(path) else {
+        return;
+    };
+    if meta.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if keep(&path, path.is_dir()) {
+                collect(&path, keep, files);
+            }
+        }
+    } else {
+        files.push((path.to_path_buf(), meta.len(), meta.modified().ok()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_sees_new_and_edited_csvs() {
+        let dir = std::env::temp_dir().join(format!("fisherman-watch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("Eel/right")).unwrap();
+        let paths = vec![dir.clone()];
+        let empty = snapshot(&paths);
+
+        fs::write(dir.join("Eel/right/a.csv"), "X,Y,ASCII,Foreground\n").unwrap();
+        fs::write(dir.join("Eel/notes.txt"), "ignored").unwrap();
+        let added = snapshot(&paths);
+        assert_eq!(added.len(), 1);
+        assert_ne!(added, empty);
+
+        fs::write(dir.join("Eel/right/a.csv"), "X,Y,ASCII,Foreground\n0,0,~,#00ff00\n").unwrap();
+        let edited = snapshot(&paths);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_ne!(edited, added);
+    }
+}
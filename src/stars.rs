@@ -1,12 +1,127 @@
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
-    style::{Color, Style},
-    widgets::Widget,
+    layout::{Margin, Rect},
+    style::{Modifier, Style},
+    widgets::{StatefulWidget, Widget},
 };
 use std::time::Duration;
 
+use crate::theme::Theme;
+
+/// How often a shooting star crosses the sky on its own, on average.
+const SHOOTING_STARS_PER_MINUTE: f32 = 2.0;
+const SHOOTING_STAR_LIFETIME: Duration = Duration::from_millis(900);
+const METEOR_SHOWER_COUNT: usize = 12;
+/// Meteors in a shower set off spread over this long.
+const METEOR_SHOWER_SPREAD: Duration = Duration::from_millis(2500);
+/// Glyphs trailing behind a shooting star's head, nearest first.
+const TRAIL: [&str; 3] = ["-", "-", "·"];
+/// The sky size, in cells, at which stars are as dense as asked. Bigger skies thin out
+/// a little and smaller ones fill in, so neither looks crowded or bare.
+const REFERENCE_SKY: f32 = 80.0 * 12.0;
+/// How far the density bends with the sky's size, and how far at most.
+const DENSITY_EXPONENT: f32 = 0.2;
+const DENSITY_RANGE: (f32, f32) = (0.6, 1.6);
+
+/// A constellation's name and stars, in cells from the top-left corner of its shape.
+struct Constellation {
+    name: &'static str,
+    stars: &'static [(u16, u16)],
+}
+
+const CONSTELLATIONS: [Constellation; 4] = [
+    Constellation { name: "Big Dipper", stars: &[(0, 0), (4, 0), (8, 1), (11, 2), (12, 4), (16, 4), (17, 2)] },
+    Constellation { name: "Cassiopeia", stars: &[(0, 0), (3, 2), (6, 1), (9, 2), (12, 0)] },
+    Constellation { name: "Orion", stars: &[(0, 0), (8, 0), (3, 2), (4, 2), (5, 2), (1, 4), (7, 4)] },
+    Constellation { name: "Southern Cross", stars: &[(3, 0), (0, 2), (6, 1), (3, 4)] },
+];
+
+/// A star streaking across the sky, trailing a tail behind it.
+#[derive(Debug, Clone)]
+pub struct ShootingStar {
+    pub x: f32,
+    pub y: f32,
+    /// Cells per second.
+    pub vx: f32,
+    pub vy: f32,
+    /// Time left before it appears, for meteors in a shower.
+    pub delay: Duration,
+    pub age: Duration,
+    /// Part of a meteor shower rather than one that crossed the sky by chance.
+    pub shower: bool,
+}
+
+impl ShootingStar {
+    fn random<R: Rng + ?Sized>(rng: &mut R, area: Rect, shower: bool) -> Self {
+        // Streak down and away from a point in the upper sky, left or right
+        let speed = rng.gen_range(25.0..45.0);
+        let direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        ShootingStar {
+            x: rng.gen_range(0.0..area.width.max(1) as f32),
+            y: rng.gen_range(0.0..(area.height / 2).max(1) as f32),
+            vx: speed * direction,
+            vy: speed * rng.gen_range(0.15..0.35),
+            delay: Duration::ZERO,
+            age: Duration::ZERO,
+            shower,
+        }
+    }
+
+    fn advance(&mut self, dt: Duration) {
+        let mut dt = dt;
+        if !self.delay.is_zero() {
+            let waited = dt.min(self.delay);
+            self.delay -= waited;
+            dt -= waited;
+        }
+        self.x += self.vx * dt.as_secs_f32();
+        self.y += self.vy * dt.as_secs_f32();
+        self.age += dt;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.delay.is_zero() && self.age < SHOOTING_STAR_LIFETIME
+    }
+}
+
+/// How dense stars are in a sky of `area`: `density` for a sky of the usual size,
+/// bending smoothly towards fewer per cell as the sky grows and more as it shrinks.
+pub fn density_for(area: Rect, density: f32) -> f32 {
+    let cells = (area.width as f32 * area.height as f32).max(1.0);
+    let scale = (REFERENCE_SKY / cells).powf(DENSITY_EXPONENT);
+    density * scale.clamp(DENSITY_RANGE.0, DENSITY_RANGE.1)
+}
+
+/// Where the constellations sit in a sky of `area`, the same for the same `seed`. Each
+/// keeps its place as a share of the sky's width and height, so it slides rather than
+/// jumps when the sky changes size, and one that doesn't fit or would overlap another
+/// is left out.
+pub fn constellations(seed: u64, area: Rect) -> Vec<(&'static str, Vec<(u16, u16)>)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut placed = Vec::new();
+    let mut taken: Vec<Rect> = Vec::new();
+    for constellation in &CONSTELLATIONS {
+        // Drawn whether or not the constellation fits, so each keeps its own place
+        let (across, down): (f32, f32) = (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+        let width = constellation.stars.iter().map(|&(x, _)| x).max().unwrap_or(0) + 1;
+        let height = constellation.stars.iter().map(|&(_, y)| y).max().unwrap_or(0) + 1;
+        if width > area.width || height > area.height {
+            continue;
+        }
+        let x = ((area.width - width) as f32 * across).round() as u16;
+        let y = ((area.height - height) as f32 * down).round() as u16;
+        let bounds = Rect::new(x, y, width, height);
+        if taken.iter().any(|other| other.outer(Margin::new(1, 1)).intersects(bounds)) {
+            continue;
+        }
+        taken.push(bounds);
+        placed.push((constellation.name, constellation.stars.iter().map(|&(dx, dy)| (x + dx, y + dy)).collect()));
+    }
+    placed
+}
+
 #[derive(Debug, Clone)]
 pub struct Star {
     pub x: u16,
@@ -17,30 +132,135 @@ pub struct Star {
 #[derive(Clone)]
 pub struct Stars {
     stars: Vec<Star>,
+    shooting: Vec<ShootingStar>,
+    /// The seed the constellations are placed from, if they're shown.
+    constellation_seed: Option<u64>,
+    constellations: Vec<(u16, u16)>,
+    /// Where shooting stars are scattered from.
+    rng: StdRng,
+    area: Rect,
     elapsed: Duration,
+    theme: Theme,
 }
 
 impl Stars {
     pub fn new<R: Rng + ?Sized>(rng: &mut R, area: Rect, density: f32) -> Self {
-        let star_count = ((area.width as f32 * area.height as f32) * density) as usize;
-        let mut stars = Vec::with_capacity(star_count);
-        
-        for _ in 0..star_count {
-            stars.push(Star {
-                x: rng.gen_range(0..area.width),
-                y: rng.gen_range(0..area.height),
-                cycle_offset: rng.gen_range(0.0..1.0),
-            });
-        }
-        
-        Stars {
-            stars,
+        let mut stars = Stars {
+            stars: Vec::new(),
+            shooting: Vec::new(),
+            constellation_seed: None,
+            constellations: Vec::new(),
+            rng: StdRng::seed_from_u64(rng.next_u64()),
+            area: Rect::default(),
             elapsed: Duration::ZERO,
+            theme: Theme::default(),
+        };
+        stars.resize(area, density);
+        stars
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Show the constellations, placed from `seed`, or take them away with `None`.
+    pub fn with_constellations(mut self, seed: Option<u64>) -> Self {
+        self.set_constellations(seed);
+        self
+    }
+
+    pub fn set_constellations(&mut self, seed: Option<u64>) {
+        self.constellation_seed = seed;
+        self.constellations = seed.map(|seed| constellations(seed, self.area).into_iter().flat_map(|(_, stars)| stars).collect()).unwrap_or_default();
+    }
+
+    /// Fit the stars to a sky of `area` without starting over: those still inside stay
+    /// where they are, the sky uncovered gets stars of its own, and then the newest are
+    /// taken away or more scattered until the sky is as dense as [`density_for`] says.
+    pub fn resize(&mut self, area: Rect, density: f32) {
+        let old = Rect { x: 0, y: 0, ..self.area };
+        let new = Rect { x: 0, y: 0, ..area };
+        self.stars.retain(|star| star.x < new.width && star.y < new.height);
+        let density = density_for(area, density);
+        let target = (new.area() as f32 * density).round() as usize;
+
+        // The strips uncovered to the right and below
+        let kept = old.intersection(new);
+        let right = Rect { x: kept.width, width: new.width - kept.width, ..new };
+        let below = Rect { y: kept.height, height: new.height - kept.height, width: kept.width, ..new };
+        for strip in [right, below] {
+            let count = (strip.area() as f32 * density).round() as usize;
+            for _ in 0..count {
+                let star = self.random_star(strip);
+                self.stars.push(star);
+            }
+        }
+        // The newest go first, so the stars already shown are the last to go
+        self.stars.truncate(target);
+        while self.stars.len() < target {
+            let star = self.random_star(new);
+            self.stars.push(star);
+        }
+        self.area = area;
+        self.set_constellations(self.constellation_seed);
+    }
+
+    fn random_star(&mut self, within: Rect) -> Star {
+        Star {
+            x: within.x + self.rng.gen_range(0..within.width),
+            y: within.y + self.rng.gen_range(0..within.height),
+            cycle_offset: self.rng.gen_range(0.0..1.0),
         }
     }
     
+    /// Move the clock on to `elapsed`, now and then sending a shooting star across.
     pub fn update(&mut self, elapsed: Duration) {
+        let dt = elapsed.saturating_sub(self.elapsed);
         self.elapsed = elapsed;
+        for star in &mut self.shooting {
+            star.advance(dt);
+        }
+        self.shooting.retain(|s| !s.delay.is_zero() || s.age < SHOOTING_STAR_LIFETIME);
+
+        let chance = (SHOOTING_STARS_PER_MINUTE * dt.as_secs_f32() / 60.0).min(1.0);
+        if self.rng.gen_bool(chance.into()) {
+            let star = ShootingStar::random(&mut self.rng, self.area, false);
+            self.shooting.push(star);
+        }
+    }
+
+    /// A burst of shooting stars, set off one after another over a couple of seconds.
+    pub fn meteor_shower(&mut self) {
+        for _ in 0..METEOR_SHOWER_COUNT {
+            let mut star = ShootingStar::random(&mut self.rng, self.area, true);
+            star.delay = METEOR_SHOWER_SPREAD.mul_f32(self.rng.gen_range(0.0..1.0));
+            self.shooting.push(star);
+        }
+    }
+
+    /// Whether a meteor shower is still going, so it can be drawn even by day.
+    pub fn shower_running(&self) -> bool {
+        self.shooting.iter().any(|s| s.shower)
+    }
+
+    /// Draw just the shooting stars, such as a meteor shower across a daytime sky.
+    pub fn render_shooting_stars(&self, area: Rect, buf: &mut Buffer) {
+        let head = Style::default().fg(self.theme.stars).add_modifier(Modifier::BOLD);
+        let tail = Style::default().fg(self.theme.stars).add_modifier(Modifier::DIM);
+        for star in self.shooting.iter().filter(|s| s.is_visible()) {
+            // The tail runs back along the path the star came from
+            let step = (star.vx.signum(), star.vy / star.vx.abs());
+            for (i, glyph) in TRAIL.iter().enumerate().rev() {
+                let back = (i + 1) as f32;
+                put(buf, area, star.x - step.0 * back, star.y - step.1 * back, glyph, tail);
+            }
+            put(buf, area, star.x, star.y, "*", head);
+        }
     }
     
     fn get_star_char(cycle_offset: f32, elapsed_secs: f32) -> &'static str {
@@ -58,18 +278,150 @@ impl Stars {
 }
 
 impl Widget for Stars {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        (&self).render(area, buf);
+    }
+}
+
+impl Widget for &Stars {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let elapsed_secs = self.elapsed.as_secs_f32();
-        let style = Style::default().fg(Color::Rgb(200, 200, 255));
+        let style = Style::default().fg(self.theme.stars);
         
         for star in &self.stars {
             let x = area.x + star.x;
             let y = area.y + star.y;
             
             if x < area.x + area.width && y < area.y + area.height {
-                let char = Self::get_star_char(star.cycle_offset, elapsed_secs);
+                let char = Stars::get_star_char(star.cycle_offset, elapsed_secs);
                 buf.set_string(x, y, char, style);
             }
         }
+        // Constellations shine steadily, a little brighter than the stars around them
+        let bright = style.add_modifier(Modifier::BOLD);
+        for &(x, y) in &self.constellations {
+            put(buf, area, x as f32, y as f32, "*", bright);
+        }
+        self.render_shooting_stars(area, buf);
+    }
+}
+
+/// Set `glyph` at a position relative to `area`, if it falls inside.
+fn put(buf: &mut Buffer, area: Rect, x: f32, y: f32, glyph: &str, style: Style) {
+    let (x, y) = (x.round(), y.round());
+    if x < 0.0 || y < 0.0 || x >= area.width as f32 || y >= area.height as f32 {
+        return;
+    }
+    buf.set_string(area.x + x as u16, area.y + y as u16, glyph, style);
+}
+
+/// State for [`StarrySky`]: owns the RNG and the twinkle clock, and fits the star
+/// field to the area it is rendered into, keeping the stars that still fit.
+pub struct StarsState {
+    seed: u64,
+    density: f32,
+    stars: Stars,
+}
+
+impl StarsState {
+    pub fn new(seed: u64, density: f32) -> Self {
+        StarsState { seed, density, stars: Stars::new(&mut StdRng::seed_from_u64(seed), Rect::default(), density) }
+    }
+
+    /// Show the constellations, placed from the seed.
+    pub fn with_constellations(mut self) -> Self {
+        self.stars.set_constellations(Some(self.seed));
+        self
+    }
+
+    /// Advance the twinkle animation and any shooting stars by `dt`.
+    pub fn tick(&mut self, dt: Duration) {
+        let elapsed = self.stars.elapsed + dt;
+        self.stars.update(elapsed);
+    }
+
+    /// Set off a meteor shower across the sky.
+    pub fn meteor_shower(&mut self) {
+        self.stars.meteor_shower();
+    }
+}
+
+/// Stateful counterpart of [`Stars`] for host apps that drive their own tick loop.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StarrySky {
+    pub theme: Theme,
+}
+
+impl StatefulWidget for StarrySky {
+    type State = StarsState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut StarsState) {
+        if area != state.stars.area {
+            state.stars.resize(area, state.density);
+        }
+        state.stars.theme = self.theme;
+        (&state.stars).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stars_stay_put_when_the_sky_changes_size() {
+        let small = Rect::new(0, 0, 80, 12);
+        assert_eq!(density_for(small, 0.02), 0.02);
+        assert!(density_for(Rect::new(0, 0, 240, 30), 0.02) < 0.02);
+        assert!(density_for(Rect::new(0, 0, 40, 6), 0.02) > 0.02);
+
+        let mut stars = Stars::new(&mut StdRng::seed_from_u64(5), small, 0.02).with_constellations(Some(9));
+        assert_eq!(stars.stars.len(), 19);
+        let before: Vec<(u16, u16)> = stars.stars.iter().map(|s| (s.x, s.y)).collect();
+        // Growing only adds stars, in the sky uncovered
+        let big = Rect::new(0, 0, 120, 16);
+        stars.resize(big, 0.02);
+        assert_eq!(stars.stars.len(), (big.area() as f32 * density_for(big, 0.02)).round() as usize);
+        assert!(before.iter().all(|star| stars.stars.iter().any(|s| (s.x, s.y) == *star)));
+        // Shrinking keeps the stars that still fit
+        stars.resize(Rect::new(0, 0, 50, 8), 0.02);
+        assert!(stars.stars.iter().all(|s| s.x < 50 && s.y < 8));
+        assert!(stars.stars.iter().all(|star| before.contains(&(star.x, star.y))));
+
+        // Constellations are placed from the seed alone, and never overlap
+        let placed = constellations(9, big);
+        assert_eq!(placed, constellations(9, big));
+        assert_ne!(placed, constellations(10, big));
+        assert!(!placed.is_empty());
+        assert!(constellations(9, Rect::new(0, 0, 6, 3)).is_empty());
+        let all: Vec<(u16, u16)> = placed.iter().flat_map(|(_, stars)| stars.clone()).collect();
+        assert!(all.iter().all(|&(x, y)| x < big.width && y < big.height));
+        stars.resize(big, 0.02);
+        assert_eq!(stars.constellations, all);
+        let mut buf = Buffer::empty(big);
+        stars.render(big, &mut buf);
+        assert!(all.iter().all(|&(x, y)| buf[(x, y)].symbol() == "*"));
+    }
+
+    #[test]
+    fn meteor_showers_streak_across_and_burn_out() {
+        let area = Rect::new(0, 0, 60, 12);
+        let mut stars = Stars::new(&mut StdRng::seed_from_u64(3), area, 0.0);
+        stars.meteor_shower();
+        assert!(stars.shower_running());
+
+        stars.update(METEOR_SHOWER_SPREAD / 2);
+        let visible: Vec<(f32, f32)> = stars.shooting.iter().filter(|s| s.is_visible()).map(|s| (s.x, s.y)).collect();
+        assert!(!visible.is_empty());
+        let mut buf = Buffer::empty(area);
+        stars.render_shooting_stars(area, &mut buf);
+        assert!(area.positions().any(|p| buf[p].symbol() == "*"));
+
+        stars.update(METEOR_SHOWER_SPREAD / 2 + Duration::from_millis(100));
+        let moved = stars.shooting.iter().filter(|s| s.is_visible()).all(|s| !visible.contains(&(s.x, s.y)));
+        assert!(moved);
+
+        stars.update(METEOR_SHOWER_SPREAD + SHOOTING_STAR_LIFETIME);
+        assert!(!stars.shower_running());
     }
 }
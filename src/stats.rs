@@ -0,0 +1,164 @@
+//! Session statistics, the `Tab` overlay that shows them and the line `--summary`
+//! prints about them on exit.
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use serde_json::json;
+
+use crate::fishing_game::CaughtFish;
+
+/// Counters for the current run, reset on every start.
+#[derive(Debug, Clone, Default)]
+pub struct SessionStats {
+    pub casts: u32,
+    pub catches: u32,
+    pub snags: u32,
+    pub biggest: Option<CaughtFish>,
+}
+
+impl SessionStats {
+    pub fn record_catch(&mut self, fish: &CaughtFish) {
+        self.catches += 1;
+        if self.biggest.as_ref().is_none_or(|b| fish.size > b.size) {
+            self.biggest = Some(fish.clone());
+        }
+    }
+
+    /// Share of casts that landed a fish.
+    pub fn catch_rate(&self) -> f32 {
+        if self.casts == 0 {
+            0.0
+        } else {
+            self.catches as f32 / self.casts as f32
+        }
+    }
+}
+
+/// Compact panel in the top-left corner; draws over whatever is beneath it.
+pub struct StatsPanel<'a> {
+    pub stats: &'a SessionStats,
+    pub session_time: Duration,
+    /// One-line description of the external signal state, e.g. `waiting` or `SUCCESS: done`.
+    pub signal: &'a str,
+}
+
+impl Widget for StatsPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let secs = self.session_time.as_secs();
+        let per_hour = if secs > 0 {
+            self.stats.catches as f32 * 3600.0 / secs as f32
+        } else {
+            0.0
+        };
+        let biggest = match self.stats.biggest {
+            Some(ref fish) => format!("{} {:.0} cm", fish.species_name, fish.size),
+            None => "-".to_string(),
+        };
+        let lines = vec![
+            Line::from(format!("Session   {}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)),
+            Line::from(format!("Casts     {}", self.stats.casts)),
+            Line::from(format!("Catches   {} (+{} snagged)", self.stats.catches, self.stats.snags)),
+            Line::from(format!("Biggest   {}", biggest)),
+            Line::from(format!(
+                "Rate      {:.0}% of casts, {:.1}/h",
+                self.stats.catch_rate() * 100.0,
+                per_hour
+            )),
+            Line::from(format!("Signal    {}", self.signal)),
+        ];
+
+        let width = 40.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let rect = Rect::new(area.x + 1.min(area.width), area.y + 1.min(area.height), width, height)
+            .intersection(area);
+        Clear.render(rect, buf);
+        Paragraph::new(lines)
+            .block(Block::default().title("Stats").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White))
+            .render(rect, buf);
+    }
+}
+
+/// How the summary printed on exit is written (`--summary=json|plain`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryFormat {
+    Json,
+    Plain,
+}
+
+impl std::str::FromStr for SummaryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(SummaryFormat::Json),
+            "plain" => Ok(SummaryFormat::Plain),
+            _ => Err(format!("unknown --summary format '{}' (expected json or plain)", s)),
+        }
+    }
+}
+
+/// One line about the session for a shell prompt or script to pick up: the catches,
+/// the biggest of them, how the last signal went and how long it all took.
+pub fn summary(stats: &SessionStats, session_time: Duration, outcome: Option<bool>, format: SummaryFormat) -> String {
+    let signal = outcome.map(|success| if success { "success" } else { "failure" });
+    let secs = session_time.as_secs();
+    match format {
+        SummaryFormat::Json => json!({
+            "catches": stats.catches,
+            "biggest": stats.biggest.as_ref().map(|fish| json!({ "species": fish.species_name, "size_cm": fish.size })),
+            "signal": signal,
+            "elapsed_secs": secs,
+        })
+        .to_string(),
+        SummaryFormat::Plain => {
+            let mut line = format!(
+                "caught {} fish in {}:{:02}:{:02}",
+                stats.catches,
+                secs / 3600,
+                (secs / 60) % 60,
+                secs % 60
+            );
+            if let Some(ref fish) = stats.biggest {
+                line.push_str(&format!(", biggest a {} of {:.0} cm", fish.species_name, fish.size));
+            }
+            line.push_str(&format!("; signal: {}", signal.unwrap_or("none")));
+            line
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fishing_game::SizeCategory;
+
+    #[test]
+    fn summary_comes_as_json_or_plain_text() {
+        let mut stats = SessionStats::default();
+        for size in [12.0, 82.0] {
+            stats.record_catch(&CaughtFish {
+                species_name: "Shark".to_string(),
+                size,
+                size_category: SizeCategory::Large,
+                shiny: false,
+            });
+        }
+        let time = Duration::from_secs(754);
+        assert_eq!(
+            summary(&stats, time, Some(true), SummaryFormat::Json),
+            r#"{"biggest":{"size_cm":82.0,"species":"Shark"},"catches":2,"elapsed_secs":754,"signal":"success"}"#
+        );
+        assert_eq!(
+            summary(&stats, time, Some(false), SummaryFormat::Plain),
+            "caught 2 fish in 0:12:34, biggest a Shark of 82 cm; signal: failure"
+        );
+        let quiet = summary(&SessionStats::default(), time, None, SummaryFormat::Json);
+        assert_eq!(quiet, r#"{"biggest":null,"catches":0,"elapsed_secs":754,"signal":null}"#);
+    }
+}
@@ -0,0 +1,102 @@
+//! The one-line status bar along the bottom of the frame: what the line is doing, the
+//! session so far, and where signals can come from.
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::Widget;
+use unicode_width::UnicodeWidthStr;
+
+use crate::fishing_line::FishingState;
+
+pub struct StatusBar<'a> {
+    pub state: FishingState,
+    /// A fish is on the hook and can be struck.
+    pub biting: bool,
+    pub paused: bool,
+    pub catches: u32,
+    pub session_time: Duration,
+    /// The signal sources in use, as listed in the help overlay.
+    pub sources: &'a [String],
+    pub style: Style,
+}
+
+impl StatusBar<'_> {
+    /// What the fisherman is up to, e.g. `Charging 40%` or `Bite!`.
+    fn mode(&self) -> String {
+        if self.paused {
+            return "Paused".to_string();
+        }
+        match self.state {
+            FishingState::Idle => "Ready to cast".to_string(),
+            FishingState::Charging { power } => format!("Charging {:.0}%", power * 100.0),
+            FishingState::Casting { .. } => "Casting".to_string(),
+            FishingState::Landed { .. } if self.biting => "Bite!".to_string(),
+            FishingState::Landed { .. } => "Fishing".to_string(),
+            FishingState::Retrieving { .. } => "Reeling in".to_string(),
+        }
+    }
+}
+
+impl Widget for StatusBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+        let secs = self.session_time.as_secs();
+        let left = format!(
+            " {} · {} caught · {}:{:02}:{:02}",
+            self.mode(),
+            self.catches,
+            secs / 3600,
+            (secs / 60) % 60,
+            secs % 60
+        );
+        let right = match self.sources {
+            [] => "no signal source ".to_string(),
+            sources => format!("signals: {} ", sources.join(", ")),
+        };
+        buf.set_stringn(area.x, area.y, &left, area.width as usize, self.style);
+        // The sources go on the right when there's room for them
+        let (left_width, right_width) = (left.width() as u16, right.width() as u16);
+        if left_width + 2 + right_width <= area.width {
+            buf.set_string(area.right() - right_width, area.y, right, self.style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(bar: StatusBar, width: u16) -> String {
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, 1));
+        bar.render(buf.area, &mut buf);
+        buf.content.iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn shows_the_mode_session_and_sources() {
+        let sources = ["stdin".to_string(), "HTTP 127.0.0.1:7777".to_string()];
+        let bar = |state, width| {
+            let bar = StatusBar {
+                state,
+                biting: false,
+                paused: false,
+                catches: 3,
+                session_time: Duration::from_secs(754),
+                sources: &sources,
+                style: Style::default(),
+            };
+            row(bar, width)
+        };
+        let wide = bar(FishingState::Charging { power: 0.4 }, 80);
+        assert!(wide.starts_with(" Charging 40% · 3 caught · 0:12:34"));
+        assert!(wide.ends_with("signals: stdin, HTTP 127.0.0.1:7777 "));
+        // Too narrow for the sources, which give way first
+        let narrow = bar(FishingState::Idle, 40);
+        assert_eq!(narrow.trim_end(), " Ready to cast · 3 caught · 0:12:34");
+    }
+}
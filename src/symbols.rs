@@ -0,0 +1,82 @@
+//! Plain ASCII for `--ascii`, on dumb terminals, serial consoles and screen readers.
+//! Like colours for [`degrade`](crate::color::degrade), glyphs are swapped on the way
+//! out: the widgets draw as usual, and [`asciify`] looks every cell of the finished
+//! frame up in one table of stand-ins and takes its colour away.
+
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+/// The ASCII stand-in for `ch`: the glyphs the scene draws by name, and the rest by
+/// the block of Unicode they come from.
+pub fn ascii(ch: char) -> char {
+    match ch {
+        c if c.is_ascii() => c,
+        // Lines and boxes
+        '─' | '┈' | '–' | '—' | '¬' | '⌐' => '-',
+        '│' | '║' | '▏' => '|',
+        '═' | '≡' | '▬' | '▭' => '=',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '╔' | '╗' | '╦' | '╭' | '╮' | '╯' | '╰' => '+',
+        '╱' | '◢' | '◤' => '/',
+        '╲' | '◣' | '◥' => '\\',
+        // Blocks, lowest first
+        '▁' | '▂' | '▃' => '_',
+        '░' | '▖' | '▗' | '▘' | '▝' => '.',
+        '▒' => ':',
+        '▄' | '▅' | '▆' | '▇' | '█' | '▌' | '▐' | '▓' | '▙' | '▛' | '▜' | '▟' => '#',
+        // Arrows and pointers
+        '←' | '◀' | '◂' | '◄' | '«' => '<',
+        '→' | '▶' | '▸' | '►' | '»' => '>',
+        '↑' => '^',
+        '↓' | '✓' => 'v',
+        // The hook, the sun, stars and flowers
+        '⌡' => 'J',
+        '☼' | '★' | '☆' | '✿' | '❀' | '•' | '¤' => '*',
+        '❦' => '@',
+        '◇' | '°' | 'º' => 'o',
+        '≈' | '∽' => '~',
+        '·' => '.',
+        '¸' => ',',
+        '´' => '\'',
+        '×' | '✗' => 'x',
+        '©' => 'c',
+        'ö' => 'o',
+        '\u{a0}' | '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28ff}' => '.',
+        '\u{2500}'..='\u{257f}' => '+',
+        '\u{2580}'..='\u{259f}' => '#',
+        _ => '?',
+    }
+}
+
+/// Swap every glyph in `buf` for its ASCII stand-in and draw it all in the
+/// terminal's own colours.
+pub fn asciify(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        if !cell.symbol().is_ascii() {
+            let symbol: String = cell.symbol().chars().map(ascii).collect();
+            cell.set_symbol(&symbol);
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn every_glyph_becomes_plain_ascii_without_colour() {
+        assert_eq!("⌡☼╦═ö▄▆▄⠤".chars().map(ascii).collect::<String>(), "J*+=o###.");
+        assert_eq!(ascii('魚'), '?');
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 1));
+        buf.set_string(0, 0, "╔═魚>", Style::default().fg(Color::Rgb(10, 20, 30)).bg(Color::Blue));
+        asciify(&mut buf);
+        let row: String = (0..6).map(|x| buf[(x, 0)].symbol().to_string()).collect();
+        assert_eq!(row, "+=? > ");
+        assert!(buf.content.iter().all(|cell| cell.symbol().is_ascii() && cell.fg == Color::Reset && cell.bg == Color::Reset));
+    }
+}
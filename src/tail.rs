@@ -0,0 +1,190 @@
+//! `--tail <file>`: follows a log file from a background thread and keeps its last
+//! lines for a see-through panel in the sky, so a build log can be watched while
+//! fishing. Polls the file's length like the signal file poller, picking up what was
+//! appended and starting over when the file is truncated or replaced.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, Widget};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Lines kept unless `--tail-lines` asks for more or fewer.
+pub const DEFAULT_TAIL_LINES: usize = 8;
+/// How much of the end of an existing file is read at the start.
+const BACKLOG_BYTES: u64 = 64 * 1024;
+
+/// The last lines of a followed file, shared with the thread following it.
+#[derive(Debug, Clone)]
+pub struct LogTail {
+    path: PathBuf,
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogTail {
+    /// Follow `path`, keeping its last `max_lines` lines. A file that doesn't exist yet
+    /// is picked up once it appears.
+    pub fn spawn(path: PathBuf, max_lines: usize) -> LogTail {
+        let lines = Arc::new(Mutex::new(VecDeque::new()));
+        let shared = Arc::clone(&lines);
+        let followed = path.clone();
+        thread::spawn(move || {
+            let mut follower = Follower::new(max_lines);
+            loop {
+                if follower.poll(&followed).unwrap_or(false) {
+                    *shared.lock().unwrap() = follower.lines.clone();
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+        LogTail { path, lines }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// The file's name, for the panel's title.
+    pub fn name(&self) -> String {
+        self.path.file_name().unwrap_or(self.path.as_os_str()).to_string_lossy().into_owned()
+    }
+}
+
+/// Reads what was appended to a file since the last poll.
+struct Follower {
+    offset: u64,
+    /// The start of a line whose end hasn't been written yet.
+    partial: Vec<u8>,
+    lines: VecDeque<String>,
+    max_lines: usize,
+}
+
+impl Follower {
+    fn new(max_lines: usize) -> Follower {
+        Follower { offset: 0, partial: Vec::new(), lines: VecDeque::new(), max_lines }
+    }
+
+    /// Read anything new in the file at `path`, returning whether the lines changed.
+    fn poll(&mut self, path: &Path) -> io::Result<bool> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let mut changed = false;
+        if len < self.offset {
+            // Truncated or replaced: start over from the top
+            self.offset = 0;
+            self.partial.clear();
+            self.lines.clear();
+            changed = true;
+        }
+        if self.offset == 0 && len > BACKLOG_BYTES {
+            // Only the end of a long log matters; skip to the first whole line there
+            self.offset = len - BACKLOG_BYTES;
+            file.seek(SeekFrom::Start(self.offset))?;
+            let mut skipped = Vec::new();
+            file.by_ref().take(BACKLOG_BYTES).read_to_end(&mut skipped)?;
+            let start = skipped.iter().position(|&b| b == b'\n').map_or(skipped.len(), |i| i + 1);
+            self.offset += start as u64;
+        }
+        if len == self.offset {
+            return Ok(changed);
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut appended = Vec::new();
+        file.take(len - self.offset).read_to_end(&mut appended)?;
+        self.offset += appended.len() as u64;
+        self.partial.extend(appended);
+        while let Some(end) = self.partial.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=end).collect();
+            self.lines.push_back(clean(&String::from_utf8_lossy(&line)));
+            if self.lines.len() > self.max_lines {
+                self.lines.pop_front();
+            }
+            changed = true;
+        }
+        Ok(changed)
+    }
+}
+
+/// `line` without its line ending, colour escapes or other control characters, and
+/// with tabs expanded.
+fn clean(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // A CSI sequence runs to its final byte, a letter or one of a few symbols
+            '\x1b' => {
+                if chars.next() == Some('[') {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+            }
+            '\t' => text.push_str("    "),
+            c if c.is_control() => {}
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+/// The followed lines in a bordered panel that lets the scene show through: the cells
+/// behind it are dimmed rather than cleared.
+pub struct TailPanel<'a> {
+    pub title: &'a str,
+    pub lines: &'a [String],
+    pub style: Style,
+}
+
+impl Widget for TailPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 3 || area.height < 3 {
+            return;
+        }
+        buf.set_style(area, Style::default().add_modifier(Modifier::DIM));
+        Block::default().title(format!(" {} ", self.title)).borders(Borders::ALL).border_style(self.style).render(area, buf);
+        let rows = (area.height - 2) as usize;
+        let width = (area.width - 2) as usize;
+        let shown = &self.lines[self.lines.len().saturating_sub(rows)..];
+        for (i, line) in shown.iter().enumerate() {
+            buf.set_stringn(area.x + 1, area.y + 1 + i as u16, line, width, self.style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    #[test]
+    fn follows_appends_partial_lines_and_truncation() {
+        let path = std::env::temp_dir().join(format!("fisherman-tail-{}.log", std::process::id()));
+        fs::write(&path, "one\ntwo\nthr").unwrap();
+        let mut follower = Follower::new(2);
+        assert!(follower.poll(&path).unwrap());
+        assert_eq!(follower.lines, ["one", "two"]);
+        assert!(!follower.poll(&path).unwrap());
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"ee\n\x1b[1;32mFinished\x1b[0m\tok\n").unwrap();
+        assert!(follower.poll(&path).unwrap());
+        assert_eq!(follower.lines, ["three", "Finished    ok"]);
+
+        fs::write(&path, "fresh\n").unwrap();
+        assert!(follower.poll(&path).unwrap());
+        let _ = fs::remove_file(&path);
+        assert_eq!(follower.lines, ["fresh"]);
+    }
+}
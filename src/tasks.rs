@@ -0,0 +1,185 @@
+//! `--task <name>`: several jobs waited on at once, each with a small fisherman of its
+//! own further along the dock. Messages tagged with a task's name (`TASK:build:...`)
+//! move only that task's fisherman, and the wait as a whole ends once every task has
+//! finished.
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Widget;
+
+use crate::fisherman::{Fisherman, FishermanAnimation, Skin};
+use crate::signals::SignalMessage;
+use crate::theme::Theme;
+
+/// Columns of dock each task's fisherman takes up.
+pub const ANGLER_WIDTH: u16 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    Waiting,
+    Progress(u8),
+    /// Finished, successfully or not.
+    Done(bool),
+}
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub name: String,
+    pub status: TaskStatus,
+    anim: FishermanAnimation,
+}
+
+impl Task {
+    fn new(name: &str) -> Task {
+        Task { name: name.to_string(), status: TaskStatus::Waiting, anim: FishermanAnimation::new().with_fidget(false) }
+    }
+
+    /// What's shown over the task's fisherman, e.g. `build 42%` or `deploy ✓`.
+    pub fn marker(&self) -> String {
+        match self.status {
+            TaskStatus::Waiting => self.name.clone(),
+            TaskStatus::Progress(percent) => format!("{} {}%", self.name, percent),
+            TaskStatus::Done(true) => format!("{} ✓", self.name),
+            TaskStatus::Done(false) => format!("{} ✗", self.name),
+        }
+    }
+}
+
+/// The named tasks, in the order they were given or first heard of.
+#[derive(Debug, Clone, Default)]
+pub struct TaskBoard {
+    tasks: Vec<Task>,
+}
+
+impl TaskBoard {
+    pub fn new(names: &[String]) -> TaskBoard {
+        let mut board = TaskBoard::default();
+        for name in names {
+            board.find_or_add(name);
+        }
+        board
+    }
+
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        for task in &mut self.tasks {
+            task.anim.tick(dt);
+        }
+    }
+
+    fn find_or_add(&mut self, name: &str) -> &mut Task {
+        let i = match self.tasks.iter().position(|task| task.name == name) {
+            Some(i) => i,
+            None => {
+                self.tasks.push(Task::new(name));
+                self.tasks.len() - 1
+            }
+        };
+        &mut self.tasks[i]
+    }
+
+    /// Apply a PROGRESS, SUCCESS or FAILURE message about the task called `name`,
+    /// taking it on if it wasn't known. Other messages leave the board as it is.
+    pub fn update(&mut self, name: &str, message: &SignalMessage) {
+        let status = match message {
+            SignalMessage::Progress { percent, .. } => TaskStatus::Progress(*percent),
+            SignalMessage::Success(_) => TaskStatus::Done(true),
+            SignalMessage::Failure(_) => TaskStatus::Done(false),
+            _ => return,
+        };
+        let task = self.find_or_add(name);
+        task.status = status;
+        match status {
+            TaskStatus::Done(true) => task.anim.celebrate(),
+            TaskStatus::Done(false) => task.anim.slump(),
+            _ => {}
+        }
+    }
+
+    /// Once every task has finished: whether they all succeeded, and a line saying so.
+    pub fn outcome(&self) -> Option<(bool, String)> {
+        if self.tasks.is_empty() || !self.tasks.iter().all(|task| matches!(task.status, TaskStatus::Done(_))) {
+            return None;
+        }
+        let failed: Vec<&str> = self
+            .tasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Done(false))
+            .map(|task| task.name.as_str())
+            .collect();
+        Some(if failed.is_empty() {
+            (true, format!("All {} tasks finished", self.tasks.len()))
+        } else {
+            (false, format!("{} of {} tasks failed: {}", failed.len(), self.tasks.len(), failed.join(", ")))
+        })
+    }
+}
+
+/// The tasks' fishermen side by side along the dock, each under its marker. The area
+/// runs from the marker row down to the fishermen's legs.
+pub struct TaskAnglers<'a> {
+    pub tasks: &'a [Task],
+    pub skin: &'a Skin,
+    pub theme: Theme,
+}
+
+impl Widget for TaskAnglers<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 4 {
+            return;
+        }
+        let head_y = area.bottom() - 3;
+        for (i, task) in self.tasks.iter().enumerate() {
+            let x = area.x + i as u16 * ANGLER_WIDTH;
+            if x + ANGLER_WIDTH > area.right() {
+                break;
+            }
+            let fisher = Fisherman { skin: self.skin, offset_from_right: 1, pose: task.anim.pose(), theme: self.theme };
+            fisher.render(Rect::new(x, head_y, ANGLER_WIDTH, 3), buf);
+            let color = match task.status {
+                TaskStatus::Done(true) => Color::Green,
+                TaskStatus::Done(false) => Color::Red,
+                _ => Color::Cyan,
+            };
+            buf.set_stringn(x + 1, area.y, task.marker(), (ANGLER_WIDTH - 2) as usize, Style::default().fg(color));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_wait_ends_once_every_task_has() {
+        let mut board = TaskBoard::new(&["build".to_string(), "deploy".to_string()]);
+        board.update("build", &SignalMessage::Progress { percent: 42, label: String::new() });
+        assert_eq!(board.tasks()[0].marker(), "build 42%");
+        board.update("build", &SignalMessage::Success("built".to_string()));
+        assert_eq!(board.outcome(), None);
+
+        // A task nobody named up front joins the board when it reports in
+        board.update("lint", &SignalMessage::Failure("warnings".to_string()));
+        board.update("deploy", &SignalMessage::Success("live".to_string()));
+        assert_eq!(board.len(), 3);
+        assert_eq!(board.tasks()[2].marker(), "lint ✗");
+        assert_eq!(board.outcome(), Some((false, "1 of 3 tasks failed: lint".to_string())));
+
+        board.update("lint", &SignalMessage::Success("clean".to_string()));
+        assert_eq!(board.outcome(), Some((true, "All 3 tasks finished".to_string())));
+    }
+}
@@ -0,0 +1,114 @@
+//! Taking over the terminal and handing it back intact, however the run ends: the
+//! [`TerminalGuard`] restores it when dropped, the panic hook before the panic message
+//! is printed, and termination signals stop the loop so the normal exit path runs.
+//! Where the terminal speaks the kitty keyboard protocol it is asked to report key
+//! releases too, so a cast can be held and let go.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crossterm::cursor::Show;
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement,
+};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// Whether keyboard enhancement flags were pushed and need popping on the way out.
+static ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// Raw mode, the alternate screen, mouse capture and, where the terminal has it, the
+/// kitty keyboard protocol for as long as it lives.
+pub struct TerminalGuard {
+    key_release: bool,
+}
+
+impl TerminalGuard {
+    pub fn new() -> io::Result<TerminalGuard> {
+        enable_raw_mode()?;
+        if let Err(e) = execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture) {
+            let _ = disable_raw_mode();
+            return Err(e);
+        }
+        // Plain keys only report their release once they are sent as escape codes too,
+        // and alternate keys keep shifted ones such as `?` arriving as typed
+        let flags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+            | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+            | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS;
+        let enhanced = matches!(supports_keyboard_enhancement(), Ok(true))
+            && execute!(io::stdout(), PushKeyboardEnhancementFlags(flags)).is_ok();
+        ENHANCED.store(enhanced, Ordering::Relaxed);
+        // The Windows console reports releases without being asked
+        Ok(TerminalGuard { key_release: enhanced || cfg!(windows) })
+    }
+
+    /// Whether letting go of a key arrives as a release event. Without them a held key
+    /// can't be told from one pressed again.
+    pub fn key_release(&self) -> bool {
+        self.key_release
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+fn restore() {
+    if ENHANCED.swap(false, Ordering::Relaxed) {
+        let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+    }
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen, Show);
+}
+
+/// Put the terminal back before the panic message is printed, so it is readable and
+/// the shell keeps working.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        default_hook(info);
+    }));
+}
+
+/// Whether SIGINT, SIGTERM or SIGHUP (or a console close on Windows) has arrived since
+/// [`install_signal_handlers`].
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+#[cfg(unix)]
+pub fn install_signal_handlers() {
+    extern "C" fn on_signal(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+    }
+    for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+        unsafe {
+            libc::signal(signal, on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        }
+    }
+}
+
+#[cfg(windows)]
+pub fn install_signal_handlers() {
+    use windows_sys::Win32::Foundation::{BOOL, TRUE};
+    use windows_sys::Win32::System::Console::SetConsoleCtrlHandler;
+
+    unsafe extern "system" fn on_ctrl(_: u32) -> BOOL {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+        TRUE
+    }
+    unsafe {
+        SetConsoleCtrlHandler(Some(on_ctrl), TRUE);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn install_signal_handlers() {}
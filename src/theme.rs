@@ -0,0 +1,327 @@
+//! Colour palettes for the scene. Every widget that paints the sky, sea, dock,
+//! fisherman, line or weather takes its colours from a [`Theme`]; pick a built-in one
+//! with `--theme <name>` or describe your own in TOML:
+//!
+//! ```toml
+//! base = "arctic"        # start from a built-in theme
+//! water = "#202830"
+//! hook = "light_red"
+//! stars = [255, 240, 200]
+//! ```
+//!
+//! The same keys can go in the `[theme]` section of the config file.
+
+use ratatui::style::Color;
+
+use crate::config_file::{Table, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Sky at noon; it fades towards `sky_dusk` as the light goes.
+    pub sky_day: Color,
+    pub sky_dusk: Color,
+    pub wave: Color,
+    pub wave_alt: Color,
+    /// Background of the surface rows.
+    pub water: Color,
+    pub foam: Color,
+    pub plank: Color,
+    pub post: Color,
+    pub fisherman: Color,
+    pub rod: Color,
+    pub line: Color,
+    pub hook: Color,
+    pub stars: Color,
+    pub cloud: Color,
+    pub storm_cloud: Color,
+    pub rain: Color,
+    pub lightning: Color,
+    pub lantern: Color,
+    /// Background right under a lit lantern, fading with depth.
+    pub lantern_glow: Color,
+    pub seaweed: Color,
+    pub rock: Color,
+    pub bird: Color,
+    pub boat: Color,
+    pub jellyfish: Color,
+}
+
+impl Theme {
+    /// The original colours.
+    pub const CLASSIC: Theme = Theme {
+        sky_day: Color::Rgb(110, 160, 220),
+        sky_dusk: Color::Rgb(10, 10, 40),
+        wave: Color::Rgb(102, 178, 255),
+        wave_alt: Color::Rgb(51, 120, 200),
+        water: Color::Rgb(51, 51, 51),
+        foam: Color::Rgb(200, 220, 255),
+        plank: Color::Rgb(101, 67, 33),
+        post: Color::Rgb(80, 50, 20),
+        fisherman: Color::Rgb(200, 200, 200),
+        rod: Color::Rgb(200, 200, 120),
+        line: Color::Rgb(200, 200, 120),
+        hook: Color::Rgb(150, 150, 255),
+        stars: Color::Rgb(200, 200, 255),
+        cloud: Color::Rgb(160, 160, 170),
+        storm_cloud: Color::Rgb(90, 90, 100),
+        rain: Color::Rgb(120, 150, 200),
+        lightning: Color::Rgb(200, 200, 220),
+        lantern: Color::Rgb(255, 210, 90),
+        lantern_glow: Color::Rgb(40, 40, 13),
+        seaweed: Color::Rgb(60, 140, 70),
+        rock: Color::Rgb(110, 105, 95),
+        bird: Color::Rgb(40, 40, 50),
+        boat: Color::Rgb(70, 60, 55),
+        jellyfish: Color::Rgb(200, 150, 230),
+    };
+
+    pub const NIGHT: Theme = Theme {
+        sky_day: Color::Rgb(40, 60, 110),
+        sky_dusk: Color::Rgb(5, 5, 20),
+        wave: Color::Rgb(70, 110, 190),
+        wave_alt: Color::Rgb(40, 70, 140),
+        water: Color::Rgb(20, 20, 30),
+        foam: Color::Rgb(150, 170, 210),
+        plank: Color::Rgb(70, 50, 30),
+        post: Color::Rgb(50, 35, 15),
+        fisherman: Color::Rgb(160, 160, 170),
+        rod: Color::Rgb(160, 160, 100),
+        line: Color::Rgb(160, 160, 100),
+        hook: Color::Rgb(120, 120, 220),
+        stars: Color::Rgb(230, 230, 255),
+        cloud: Color::Rgb(100, 100, 115),
+        storm_cloud: Color::Rgb(60, 60, 70),
+        rain: Color::Rgb(90, 110, 160),
+        lightning: Color::Rgb(170, 170, 200),
+        lantern: Color::Rgb(255, 200, 80),
+        lantern_glow: Color::Rgb(50, 45, 15),
+        seaweed: Color::Rgb(40, 90, 55),
+        rock: Color::Rgb(70, 70, 75),
+        bird: Color::Rgb(30, 30, 40),
+        boat: Color::Rgb(45, 40, 40),
+        jellyfish: Color::Rgb(150, 110, 200),
+    };
+
+    pub const SUNSET: Theme = Theme {
+        sky_day: Color::Rgb(250, 150, 90),
+        sky_dusk: Color::Rgb(60, 20, 50),
+        wave: Color::Rgb(255, 170, 120),
+        wave_alt: Color::Rgb(200, 90, 90),
+        water: Color::Rgb(60, 35, 45),
+        foam: Color::Rgb(255, 220, 190),
+        plank: Color::Rgb(110, 60, 30),
+        post: Color::Rgb(80, 40, 20),
+        fisherman: Color::Rgb(230, 200, 180),
+        rod: Color::Rgb(220, 190, 110),
+        line: Color::Rgb(230, 200, 120),
+        hook: Color::Rgb(255, 140, 120),
+        stars: Color::Rgb(255, 220, 200),
+        cloud: Color::Rgb(200, 140, 140),
+        storm_cloud: Color::Rgb(110, 70, 80),
+        rain: Color::Rgb(200, 130, 140),
+        lightning: Color::Rgb(255, 220, 200),
+        lantern: Color::Rgb(255, 200, 90),
+        lantern_glow: Color::Rgb(50, 30, 10),
+        seaweed: Color::Rgb(120, 130, 60),
+        rock: Color::Rgb(120, 80, 70),
+        bird: Color::Rgb(60, 30, 40),
+        boat: Color::Rgb(50, 25, 30),
+        jellyfish: Color::Rgb(230, 130, 200),
+    };
+
+    pub const ARCTIC: Theme = Theme {
+        sky_day: Color::Rgb(190, 220, 240),
+        sky_dusk: Color::Rgb(20, 40, 60),
+        wave: Color::Rgb(200, 240, 255),
+        wave_alt: Color::Rgb(120, 190, 230),
+        water: Color::Rgb(40, 55, 65),
+        foam: Color::Rgb(255, 255, 255),
+        plank: Color::Rgb(150, 140, 130),
+        post: Color::Rgb(110, 100, 95),
+        fisherman: Color::Rgb(240, 240, 250),
+        rod: Color::Rgb(200, 210, 220),
+        line: Color::Rgb(210, 220, 230),
+        hook: Color::Rgb(120, 200, 255),
+        stars: Color::Rgb(220, 240, 255),
+        cloud: Color::Rgb(210, 220, 230),
+        storm_cloud: Color::Rgb(120, 130, 140),
+        rain: Color::Rgb(170, 210, 240),
+        lightning: Color::Rgb(240, 250, 255),
+        lantern: Color::Rgb(255, 230, 160),
+        lantern_glow: Color::Rgb(35, 40, 40),
+        seaweed: Color::Rgb(90, 160, 140),
+        rock: Color::Rgb(150, 160, 170),
+        bird: Color::Rgb(70, 80, 90),
+        boat: Color::Rgb(90, 90, 100),
+        jellyfish: Color::Rgb(170, 220, 240),
+    };
+
+    pub const MONOCHROME: Theme = Theme {
+        sky_day: Color::Rgb(150, 150, 150),
+        sky_dusk: Color::Rgb(20, 20, 20),
+        wave: Color::Rgb(220, 220, 220),
+        wave_alt: Color::Rgb(150, 150, 150),
+        water: Color::Rgb(40, 40, 40),
+        foam: Color::Rgb(255, 255, 255),
+        plank: Color::Rgb(120, 120, 120),
+        post: Color::Rgb(90, 90, 90),
+        fisherman: Color::Rgb(210, 210, 210),
+        rod: Color::Rgb(180, 180, 180),
+        line: Color::Rgb(200, 200, 200),
+        hook: Color::Rgb(255, 255, 255),
+        stars: Color::Rgb(230, 230, 230),
+        cloud: Color::Rgb(170, 170, 170),
+        storm_cloud: Color::Rgb(100, 100, 100),
+        rain: Color::Rgb(160, 160, 160),
+        lightning: Color::Rgb(220, 220, 220),
+        lantern: Color::Rgb(255, 255, 255),
+        lantern_glow: Color::Rgb(40, 40, 40),
+        seaweed: Color::Rgb(130, 130, 130),
+        rock: Color::Rgb(100, 100, 100),
+        bird: Color::Rgb(60, 60, 60),
+        boat: Color::Rgb(80, 80, 80),
+        jellyfish: Color::Rgb(190, 190, 190),
+    };
+
+    /// The built-in themes by name.
+    pub const BUILT_IN: [(&'static str, Theme); 5] = [
+        ("classic", Theme::CLASSIC),
+        ("night", Theme::NIGHT),
+        ("sunset", Theme::SUNSET),
+        ("arctic", Theme::ARCTIC),
+        ("monochrome", Theme::MONOCHROME),
+    ];
+
+    pub fn named(name: &str) -> Option<Theme> {
+        Theme::BUILT_IN.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, theme)| *theme)
+    }
+
+    /// A theme from TOML keys: `base` names the built-in theme to start from (classic
+    /// by default) and any colour named after a field replaces that one.
+    pub fn from_table(table: &Table) -> Result<Theme, String> {
+        let mut theme = match table.get("base") {
+            Some(value) => {
+                let name = value.as_str().ok_or("theme base must be a theme name")?;
+                Theme::named(name).ok_or_else(|| format!("unknown base theme '{}'", name))?
+            }
+            None => Theme::default(),
+        };
+        for (key, value) in table.iter().filter(|(key, _)| *key != "base") {
+            let slot = theme.color_mut(key).ok_or_else(|| format!("unknown theme colour '{}'", key))?;
+            *slot = parse_color(value).map_err(|e| format!("theme colour {}: {}", key, e))?;
+        }
+        Ok(theme)
+    }
+
+    /// The colour named after its field, such as `"water"`.
+    pub fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "sky_day" => &mut self.sky_day,
+            "sky_dusk" => &mut self.sky_dusk,
+            "wave" => &mut self.wave,
+            "wave_alt" => &mut self.wave_alt,
+            "water" => &mut self.water,
+            "foam" => &mut self.foam,
+            "plank" => &mut self.plank,
+            "post" => &mut self.post,
+            "fisherman" => &mut self.fisherman,
+            "rod" => &mut self.rod,
+            "line" => &mut self.line,
+            "hook" => &mut self.hook,
+            "stars" => &mut self.stars,
+            "cloud" => &mut self.cloud,
+            "storm_cloud" => &mut self.storm_cloud,
+            "rain" => &mut self.rain,
+            "lightning" => &mut self.lightning,
+            "lantern" => &mut self.lantern,
+            "lantern_glow" => &mut self.lantern_glow,
+            "seaweed" => &mut self.seaweed,
+            "rock" => &mut self.rock,
+            "bird" => &mut self.bird,
+            "boat" => &mut self.boat,
+            "jellyfish" => &mut self.jellyfish,
+            _ => return None,
+        })
+    }
+
+    /// The sky for a daylight level between 0.0 (dusk) and 1.0 (noon).
+    pub fn sky(&self, daylight: f32) -> Color {
+        let d = daylight.clamp(0.0, 1.0);
+        match (self.sky_dusk, self.sky_day) {
+            (Color::Rgb(r0, g0, b0), Color::Rgb(r1, g1, b1)) => {
+                let lerp = |night: u8, day: u8| (night as f32 + (day as f32 - night as f32) * d) as u8;
+                Color::Rgb(lerp(r0, r1), lerp(g0, g1), lerp(b0, b1))
+            }
+            // Named colours can't be blended, so switch over halfway
+            (dusk, day) => {
+                if d < 0.5 {
+                    dusk
+                } else {
+                    day
+                }
+            }
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::CLASSIC
+    }
+}
+
+/// A colour as `"#rrggbb"`, `[r, g, b]` or a terminal colour name such as `"cyan"` or
+/// `"dark_gray"`.
+pub fn parse_color(value: &Value) -> Result<Color, String> {
+    match value {
+        Value::String(s) => s.parse().map_err(|_| format!("unknown colour '{}'", s)),
+        Value::Array(items) => {
+            let channels: Option<Vec<u8>> = items
+                .iter()
+                .map(|item| match item {
+                    Value::Number(n) if (0.0..=255.0).contains(n) => Some(*n as u8),
+                    _ => None,
+                })
+                .collect();
+            match channels.as_deref() {
+                Some(&[r, g, b]) => Ok(Color::Rgb(r, g, b)),
+                _ => Err("expected [r, g, b] with values from 0 to 255".to_string()),
+            }
+        }
+        _ => Err("expected a colour".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::ConfigFile;
+
+    fn theme(text: &str) -> Result<Theme, String> {
+        let file = ConfigFile::parse(text).unwrap();
+        Theme::from_table(file.section("").unwrap())
+    }
+
+    #[test]
+    fn custom_theme_starts_from_its_base() {
+        let custom = theme("base = \"arctic\"\nwater = \"#202830\"\nhook = \"light_red\"\nstars = [1, 2, 3]\n").unwrap();
+        assert_eq!(custom.water, Color::Rgb(0x20, 0x28, 0x30));
+        assert_eq!(custom.hook, Color::LightRed);
+        assert_eq!(custom.stars, Color::Rgb(1, 2, 3));
+        assert_eq!(custom.foam, Theme::ARCTIC.foam);
+    }
+
+    #[test]
+    fn rejects_unknown_names_and_bad_colours() {
+        assert!(theme("base = \"neon\"\n").unwrap_err().contains("unknown base theme"));
+        assert!(theme("sea = \"blue\"\n").unwrap_err().contains("unknown theme colour"));
+        assert!(theme("water = \"#12345\"\n").unwrap_err().contains("unknown colour"));
+        assert!(theme("water = [1, 2]\n").is_err());
+    }
+
+    #[test]
+    fn classic_sky_matches_the_original_blend() {
+        assert_eq!(Theme::CLASSIC.sky(1.0), Color::Rgb(110, 160, 220));
+        assert_eq!(Theme::CLASSIC.sky(0.5), Color::Rgb(60, 85, 130));
+    }
+}
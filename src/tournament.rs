@@ -0,0 +1,366 @@
+//! `--tournament <length>`: a timed round against the clock. A countdown runs along
+//! the top edge and every fish landed scores points for its size and rarity. When time
+//! is up the line comes in, the player puts a name to the round, and a scoreboard shows
+//! the total and where it ranks among past rounds of the same length, kept in a
+//! high-score table beside the saved game with the best ten of each length.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+use serde::{Deserialize, Serialize};
+
+use crate::fishing_game::CaughtFish;
+use crate::game_state;
+use crate::menu;
+use crate::season;
+
+/// Points per unit of rarity on top of the size, so a rare fish scores up to three
+/// times a common one of the same size.
+const RARITY_POINTS: f32 = 2.0;
+/// Shiny fish score double.
+const SHINY_POINTS: f32 = 2.0;
+/// Rounds kept in the high-score table for each length.
+const KEPT_PER_LENGTH: usize = 10;
+
+/// The length of a round from `--tournament`: minutes and seconds as `3m`, `90s` or
+/// `1m30s`, or a bare number of minutes.
+pub fn parse_length(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid --tournament: {} (expected a length like 3m or 90s)", s);
+    if let Ok(minutes) = s.parse::<u64>() {
+        return minutes.checked_mul(60).filter(|&secs| secs > 0).map(Duration::from_secs).ok_or_else(invalid);
+    }
+    let mut secs: u64 = 0;
+    let mut rest = s;
+    for (unit, scale) in [('m', 60), ('s', 1)] {
+        if let Some((count, after)) = rest.split_once(unit) {
+            let count = count.parse::<u64>().map_err(|_| invalid())?;
+            secs = count.checked_mul(scale).and_then(|part| secs.checked_add(part)).ok_or_else(invalid)?;
+            rest = after;
+        }
+    }
+    if !rest.is_empty() || secs == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// What `fish` scores: a point a centimetre, more for rare and shiny ones.
+pub fn points_for(fish: &CaughtFish, rarity: f32) -> u32 {
+    let shiny = if fish.shiny { SHINY_POINTS } else { 1.0 };
+    (fish.size * (1.0 + rarity.clamp(0.0, 1.0) * RARITY_POINTS) * shiny).round().max(1.0) as u32
+}
+
+/// The biggest fish of a round.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Catch {
+    pub species: String,
+    pub size: f32,
+}
+
+/// One finished round in the high-score table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Round {
+    pub length_secs: u64,
+    pub points: u32,
+    pub catches: u32,
+    /// Who fished it, their biggest fish and the day, as `YYYY-MM-DD`; tables kept
+    /// before these were go without.
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub biggest: Option<Catch>,
+    #[serde(default)]
+    pub date: String,
+}
+
+/// Where a finished round placed among those of its length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    /// 1 for the best.
+    pub rank: usize,
+    pub of: usize,
+    /// The best rounds of this length, best first, this one among them if it made it.
+    pub best: Vec<Round>,
+}
+
+/// The high-score table, best rounds first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    rounds: Vec<Round>,
+    /// The name the last round went down under, offered for the next.
+    #[serde(default)]
+    last_name: Option<String>,
+}
+
+impl HighScores {
+    /// Read the table; empty when there is none yet.
+    pub fn load(path: &Path) -> io::Result<HighScores> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HighScores::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        game_state::write_json(path, self)
+    }
+
+    /// Enter `round`, ranked after any with as many points, keeping only the best of
+    /// its length.
+    pub fn record(&mut self, round: Round) -> Standing {
+        let same_length = |r: &&Round| r.length_secs == round.length_secs;
+        let rank = self.rounds.iter().filter(same_length).filter(|r| r.points >= round.points).count() + 1;
+        let of = self.rounds.iter().filter(same_length).count() + 1;
+        let at = self.rounds.iter().position(|r| r.points < round.points).unwrap_or(self.rounds.len());
+        self.rounds.insert(at, round.clone());
+        let mut kept = 0;
+        self.rounds.retain(|r| {
+            if r.length_secs != round.length_secs {
+                return true;
+            }
+            kept += 1;
+            kept <= KEPT_PER_LENGTH
+        });
+        if !round.name.is_empty() {
+            self.last_name = Some(round.name.clone());
+        }
+        let best = self.rounds.iter().filter(same_length).take(5).cloned().collect();
+        Standing { rank, of, best }
+    }
+
+    /// The lengths of round in the table, shortest first.
+    pub fn lengths(&self) -> Vec<u64> {
+        let mut lengths: Vec<u64> = self.rounds.iter().map(|r| r.length_secs).collect();
+        lengths.sort_unstable();
+        lengths.dedup();
+        lengths
+    }
+
+    /// The best rounds of `length_secs`, best first.
+    pub fn best(&self, length_secs: u64) -> impl Iterator<Item = &Round> {
+        self.rounds.iter().filter(move |r| r.length_secs == length_secs)
+    }
+
+    pub fn last_name(&self) -> Option<&str> {
+        self.last_name.as_deref()
+    }
+}
+
+/// A round in play.
+#[derive(Debug, Clone)]
+pub struct Tournament {
+    length: Duration,
+    played: Duration,
+    /// Where the high-score table is kept; `None` keeps no table.
+    scores_path: Option<PathBuf>,
+    pub points: u32,
+    pub catches: u32,
+    biggest: Option<Catch>,
+    over: bool,
+    /// Set once the finished round has gone in the table, with the table as it was
+    /// left.
+    pub standing: Option<Standing>,
+    pub scores: HighScores,
+}
+
+impl Tournament {
+    pub fn new(length: Duration, scores_path: Option<PathBuf>) -> Tournament {
+        Tournament {
+            length,
+            played: Duration::ZERO,
+            scores_path,
+            points: 0,
+            catches: 0,
+            biggest: None,
+            over: false,
+            standing: None,
+            scores: HighScores::default(),
+        }
+    }
+
+    /// The high-score table beside the saved game at `save_path`.
+    pub fn scores_path_beside(save_path: &Path) -> PathBuf {
+        save_path.with_file_name("tournament.json")
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.length.saturating_sub(self.played)
+    }
+
+    pub fn length(&self) -> Duration {
+        self.length
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    /// Score a fish landed while the clock is running. Returns the points it scored.
+    pub fn score(&mut self, fish: &CaughtFish, rarity: f32) -> Option<u32> {
+        if self.is_over() {
+            return None;
+        }
+        let points = points_for(fish, rarity);
+        self.points += points;
+        self.catches += 1;
+        if self.biggest.as_ref().is_none_or(|biggest| fish.size > biggest.size) {
+            self.biggest = Some(Catch { species: fish.species_name.clone(), size: fish.size });
+        }
+        Some(points)
+    }
+
+    /// Run the clock on by `dt`. Returns true the once, when time runs out.
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        if self.over {
+            return false;
+        }
+        self.played += dt;
+        self.over = self.played >= self.length;
+        self.over
+    }
+
+    /// The name to offer for the round: the last one entered, or the login name.
+    pub fn default_name(&self) -> String {
+        let last = self.scores_path.as_ref().and_then(|path| HighScores::load(path).ok()).and_then(|scores| scores.last_name);
+        last.or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| "Angler".to_string())
+    }
+
+    /// Put the finished round in the high-score table under `name`, with any error
+    /// saving it; only the once.
+    pub fn finish(&mut self, name: &str) -> io::Result<()> {
+        if !self.over || self.standing.is_some() {
+            return Ok(());
+        }
+        let round = Round {
+            length_secs: self.length.as_secs(),
+            points: self.points,
+            catches: self.catches,
+            name: name.trim().to_string(),
+            biggest: self.biggest.clone(),
+            date: season::today_stamp(),
+        };
+        let Some(ref path) = self.scores_path else {
+            self.standing = Some(self.scores.record(round));
+            return Ok(());
+        };
+        match HighScores::load(path) {
+            Ok(scores) => {
+                self.scores = scores;
+                let standing = self.scores.record(round);
+                self.standing = Some(standing);
+                self.scores.save(path)
+            }
+            // A table that can't be read is left alone rather than written over
+            Err(e) => {
+                self.standing = Some(self.scores.record(round));
+                Err(e)
+            }
+        }
+    }
+
+    /// The countdown and score for the top edge, e.g. ` 2:31 · 120 pts `.
+    pub fn label(&self) -> String {
+        let secs = self.remaining().as_secs_f32().ceil() as u64;
+        format!(" {}:{:02} · {} pts ", secs / 60, secs % 60, self.points)
+    }
+}
+
+/// The scoreboard shown when a round is over, centred over the scene.
+pub struct Scoreboard<'a> {
+    pub tournament: &'a Tournament,
+    pub standing: &'a Standing,
+    /// The key that quits, to say how to leave.
+    pub quit_key: Option<String>,
+}
+
+impl Widget for Scoreboard<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let heading = Style::default().fg(Color::Yellow);
+        let mins = self.tournament.length.as_secs() / 60;
+        let secs = self.tournament.length.as_secs() % 60;
+        let mut lines = vec![
+            Line::from(Span::styled(format!("Time's up after {}:{:02}", mins, secs), heading)),
+            Line::from(format!(" Points    {}", self.tournament.points)),
+            Line::from(format!(" Catches   {}", self.tournament.catches)),
+            Line::from(format!(" Rank      #{} of {}", self.standing.rank, self.standing.of)),
+            Line::from(""),
+            Line::from(Span::styled("Best rounds", heading)),
+        ];
+        for (i, round) in self.standing.best.iter().enumerate() {
+            let marker = if i + 1 == self.standing.rank { "◀" } else { "" };
+            let name: String = round.name.chars().take(10).collect();
+            lines.push(Line::from(format!(" {}. {:<10} {:>5} pts {:>3} fish {}", i + 1, name, round.points, round.catches, marker)));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(" Enter for the high scores"));
+        if let Some(key) = self.quit_key {
+            lines.push(Line::from(format!(" {} to leave", key)));
+        }
+        menu::render_box(lines, "Tournament", 42, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fishing_game::SizeCategory;
+
+    #[test]
+    fn rounds_score_catches_and_rank_under_a_name_in_the_table() {
+        assert_eq!(parse_length("3m"), Ok(Duration::from_secs(180)));
+        assert_eq!(parse_length("1m30s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_length("2"), Ok(Duration::from_secs(120)));
+        assert!(parse_length("soon").is_err() && parse_length("0s").is_err() && parse_length("3h").is_err());
+        for overflowing in ["999999999999999999", "999999999999999999m", "307445734561825860m16s"] {
+            assert!(parse_length(overflowing).is_err(), "{}", overflowing);
+        }
+
+        let fish = |size, shiny| CaughtFish { species_name: "Goby".to_string(), size, size_category: SizeCategory::Small, shiny };
+        assert_eq!(points_for(&fish(20.0, false), 0.0), 20);
+        assert_eq!(points_for(&fish(20.0, true), 0.5), 80);
+
+        let path = std::env::temp_dir().join(format!("fisherman-tournament-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut first = Tournament::new(Duration::from_secs(60), Some(path.clone()));
+        first.score(&fish(30.0, false), 0.0);
+        first.score(&fish(12.0, false), 0.0);
+        assert!(!first.tick(Duration::from_secs(59)));
+        assert!(first.tick(Duration::from_secs(1)));
+        assert!(!first.tick(Duration::from_secs(1)));
+        assert_eq!(first.score(&fish(30.0, false), 0.0), None);
+        first.finish("Ann").unwrap();
+        assert_eq!(first.standing.as_ref().map(|s| (s.rank, s.of)), Some((1, 1)));
+
+        let mut second = Tournament::new(Duration::from_secs(60), Some(path.clone()));
+        assert_eq!(second.default_name(), "Ann");
+        second.score(&fish(10.0, false), 0.0);
+        second.tick(Duration::from_secs(60));
+        second.finish("Bo").unwrap();
+        let standing = second.standing.unwrap();
+        assert_eq!((standing.rank, standing.of), (2, 2));
+        assert_eq!(standing.best.iter().map(|r| r.points).collect::<Vec<_>>(), [42, 10]);
+
+        let scores = HighScores::load(&path).unwrap();
+        assert_eq!(scores.lengths(), [60]);
+        let best = scores.best(60).next().unwrap();
+        assert_eq!(best.name, "Ann");
+        assert_eq!(best.biggest, Some(Catch { species: "Goby".to_string(), size: 30.0 }));
+        assert_eq!(best.date.len(), "2024-02-29".len());
+        assert_eq!(scores.last_name(), Some("Bo"));
+        // Tables kept before rounds had names still read
+        let old: HighScores = serde_json::from_str(r#"{"rounds":[{"length_secs":60,"points":5,"catches":1}]}"#).unwrap();
+        assert_eq!(old.best(60).next().unwrap().name, "");
+        fs::remove_file(&path).unwrap();
+    }
+}
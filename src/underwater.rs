@@ -0,0 +1,185 @@
+//! The underwater view (`u`): the water from the surface down to the sea floor, stretched
+//! to fill the screen, with every fish at its depth, the line going down to the hook and
+//! the lanes marked off. From the dock the hook's depth is hard to judge against the
+//! lanes; here it's plain which fish it hangs among.
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::Text;
+use ratatui::widgets::{StatefulWidget, Widget};
+
+use crate::csv_frames::SpeciesFrames;
+use crate::fish::{self, Fish};
+use crate::ocean::{Ocean, OceanState};
+use crate::seabed::Seabed;
+use crate::theme::Theme;
+
+/// A hook in the water: its column, the screen row it hangs at in the scene, and
+/// whether a fish is biting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SunkHook {
+    pub x: u16,
+    pub y: u16,
+    pub biting: bool,
+}
+
+/// The cross-section, drawn over the whole scene. Depths are given as the scene lays
+/// them out, from `surface_y` down through `fish_area`, and stretched to the area the
+/// view is drawn in.
+pub struct CrossSection<'a> {
+    pub fishes: &'a [Fish],
+    pub frames: &'a [SpeciesFrames],
+    pub shiny_frames: &'a [SpeciesFrames],
+    pub fish_area: Rect,
+    pub surface_y: u16,
+    pub hooks: &'a [SunkHook],
+    pub seabed: &'a Seabed,
+    pub ocean: OceanState,
+    pub elapsed: Duration,
+    pub theme: Theme,
+}
+
+impl CrossSection<'_> {
+    /// The view's row for scene row `y`: the surface at the top of `area`, the bottom
+    /// of the fish lanes just above the floor.
+    fn row(&self, y: u16, area: Rect) -> u16 {
+        let deepest = self.fish_area.bottom().max(self.surface_y + 1);
+        let depth = y.clamp(self.surface_y, deepest) - self.surface_y;
+        let rows = area.height.saturating_sub(2);
+        area.y + (depth as u32 * rows as u32 / (deepest - self.surface_y) as u32) as u16
+    }
+}
+
+/// The first colour a sprite is drawn in, to tint its fish's marker with.
+fn sprite_color(sprite: &Text) -> Option<Color> {
+    sprite.lines.iter().flat_map(|line| &line.spans).find_map(|span| span.style.fg)
+}
+
+impl Widget for CrossSection<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 4 || area.width == 0 {
+            return;
+        }
+        let water = Style::default().bg(self.theme.water);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf[(x, y)].reset();
+                buf[(x, y)].set_style(water);
+            }
+        }
+        let mut ocean = self.ocean;
+        StatefulWidget::render(Ocean { theme: self.theme }, Rect::new(area.x, area.y, area.width, 2), buf, &mut ocean);
+        let floor = area.bottom() - 1;
+        (&self.seabed.at_floor(floor)).render(area, buf);
+
+        // A dotted rule where each lane starts, numbered from the surface down
+        let guide = Style::default().fg(Color::DarkGray).bg(self.theme.water);
+        let mut lane = None;
+        for y in self.surface_y..self.fish_area.bottom() {
+            let here = fish::lane_at(self.fish_area, y);
+            if here.is_some() && here != lane {
+                let row = self.row(y, area);
+                if row > area.y + 1 && row < floor {
+                    buf.set_string(area.x, row, "┈".repeat(area.width as usize), guide);
+                    buf.set_string(area.x, row, format!("{} ", here.unwrap_or(0) + 1), guide);
+                }
+                lane = here;
+            }
+        }
+
+        for fish in self.fishes {
+            if self.elapsed.as_millis() < fish.spawn_delay_ms as u128 {
+                continue;
+            }
+            let Some(sprite) = fish::sprite(fish, self.frames, self.shiny_frames, self.elapsed) else {
+                continue;
+            };
+            let (width, height) = fish.drawn_size((sprite.width() as u16, sprite.height() as u16));
+            let middle = fish::sprite_top(fish, self.fish_area) + height as i32 / 2;
+            let row = self.row(middle.max(0) as u16, area).max(area.y + 2).min(floor.saturating_sub(1));
+            let marker = match (fish.is_boss(), fish.facing_right) {
+                (true, true) => "><≡≡≡>",
+                (true, false) => "<≡≡≡><",
+                (false, true) => "><>",
+                (false, false) => "<><",
+            };
+            let x = self.fish_area.x as i32 + fish.x.floor() as i32 + (width as i32 - marker.chars().count() as i32) / 2;
+            let style = Style::default().fg(sprite_color(sprite).unwrap_or(Color::White)).bg(self.theme.water);
+            for (i, ch) in marker.chars().enumerate() {
+                let column = x + i as i32;
+                if column >= area.left() as i32 && column < area.right() as i32 {
+                    buf[(column as u16, row)].set_char(ch).set_style(style);
+                }
+            }
+        }
+
+        let line_style = Style::default().fg(self.theme.line).bg(self.theme.water);
+        for hook in self.hooks {
+            if hook.x < area.left() || hook.x >= area.right() {
+                continue;
+            }
+            let row = self.row(hook.y, area).clamp(area.y + 1, floor.saturating_sub(1));
+            for y in area.y + 1..row {
+                buf.set_string(hook.x, y, "│", line_style);
+            }
+            buf.set_string(hook.x, row, "⌡", Style::default().fg(self.theme.hook).bg(self.theme.water));
+            if hook.biting && hook.x + 1 < area.right() {
+                buf.set_string(hook.x + 1, row, "!", Style::default().fg(Color::Yellow).bg(self.theme.water));
+            }
+        }
+
+        let hint = "underwater · u to surface";
+        let x = area.right().saturating_sub(hint.chars().count() as u16 + 1).max(area.x);
+        buf.set_stringn(x, area.y + 2, hint, area.width as usize, guide);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn the_hook_and_fish_show_at_their_depths() {
+        let frame = |s: &'static str| vec![(Text::from(s), fish::DEFAULT_FRAME_DURATION)];
+        let frames = vec![(frame("><(((°>"), frame("<°)))><"))];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut fishes = Vec::new();
+        while fishes.is_empty() {
+            fishes = fish::spawn_fishes(&mut rng, &frames, &[], 80.0, 1, 1.0, None, &[]);
+        }
+        fishes.truncate(1);
+        fishes[0].lane = 3;
+        fishes[0].x = 10.0;
+        fishes[0].spawn_delay_ms = 0;
+        let seabed = Seabed::new(1, Rect::new(1, 40, 78, 8));
+        let hooks = [SunkHook { x: 60, y: 26, biting: true }];
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+        CrossSection {
+            fishes: &fishes,
+            frames: &frames,
+            shiny_frames: &frames,
+            fish_area: Rect::new(0, 25, 80, 22),
+            surface_y: 20,
+            hooks: &hooks,
+            seabed: &seabed,
+            ocean: OceanState::new(1),
+            elapsed: Duration::ZERO,
+            theme: Theme::CLASSIC,
+        }
+        .render(area, &mut buf);
+
+        let row = |y: u16| (0..80).map(|x| buf[(x, y)].symbol().to_string()).collect::<String>();
+        let hook_row = (0..24).find(|&y| buf[(60, y)].symbol() == "⌡").unwrap();
+        assert!(hook_row < 6 && buf[(61, hook_row)].symbol() == "!");
+        assert_eq!(buf[(60, 1)].symbol(), "│");
+        let fish_row = (0..24).find(|&y| row(y).contains("><>") || row(y).contains("<><")).unwrap();
+        assert!(fish_row > 15, "{}", fish_row);
+        assert!((0..24).any(|y| row(y).starts_with("4 ┈")));
+        assert!(row(23).chars().any(|c| "()▄▂▃▅▆".contains(c)));
+    }
+}
@@ -0,0 +1,121 @@
+//! `--timeout <window>`: notice when the task being waited on has gone quiet. Every
+//! signal, and the `HEARTBEAT` lines a task can send just to say it's alive, restarts
+//! the window. If it runs out the fisherman nods off and a banner warns that the task
+//! may be stuck, or with `--on-timeout exit` the game quits with [`TIMEOUT_EXIT_CODE`].
+
+use std::time::Duration;
+
+/// Exit code after the window ran out under `--on-timeout exit`, the same as
+/// coreutils `timeout` uses.
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// The window from `--timeout`: hours, minutes and seconds as `30m`, `90s`, `1h` or
+/// `1h30m`, or a bare number of minutes.
+pub fn parse_window(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid --timeout: {} (expected a length like 30m or 90s)", s);
+    if let Ok(minutes) = s.parse::<u64>() {
+        return minutes.checked_mul(60).filter(|&secs| secs > 0).map(Duration::from_secs).ok_or_else(invalid);
+    }
+    let mut secs: u64 = 0;
+    let mut rest = s;
+    for (unit, scale) in [('h', 3600), ('m', 60), ('s', 1)] {
+        if let Some((count, after)) = rest.split_once(unit) {
+            let count = count.parse::<u64>().map_err(|_| invalid())?;
+            secs = count.checked_mul(scale).and_then(|part| secs.checked_add(part)).ok_or_else(invalid)?;
+            rest = after;
+        }
+    }
+    if !rest.is_empty() || secs == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// What happens once the window runs out (`--on-timeout`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeoutPolicy {
+    /// Put the fisherman to sleep and warn, but keep waiting.
+    #[default]
+    Warn,
+    /// Quit with [`TIMEOUT_EXIT_CODE`].
+    Exit,
+}
+
+impl std::str::FromStr for TimeoutPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(TimeoutPolicy::Warn),
+            "exit" => Ok(TimeoutPolicy::Exit),
+            _ => Err(format!("unknown --on-timeout policy '{}' (expected warn or exit)", s)),
+        }
+    }
+}
+
+/// How long it's been since the task was last heard from.
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    window: Duration,
+    quiet_for: Duration,
+    stuck: bool,
+}
+
+impl Watchdog {
+    pub fn new(window: Duration) -> Self {
+        Watchdog { window, quiet_for: Duration::ZERO, stuck: false }
+    }
+
+    /// The window the way `--timeout` takes it, e.g. `1h30m`.
+    pub fn describe_window(&self) -> String {
+        let secs = self.window.as_secs();
+        let parts = [(secs / 3600, 'h'), (secs / 60 % 60, 'm'), (secs % 60, 's')];
+        parts.iter().filter(|(count, _)| *count > 0).map(|(count, unit)| format!("{}{}", count, unit)).collect()
+    }
+
+    /// Count `dt` more of real time without a word. Returns `true` on the tick the
+    /// window runs out, and only then.
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        self.quiet_for += dt;
+        let ran_out = !self.stuck && self.quiet_for >= self.window;
+        self.stuck |= ran_out;
+        ran_out
+    }
+
+    /// The task was heard from. Returns whether it had been thought stuck.
+    pub fn heard(&mut self) -> bool {
+        self.quiet_for = Duration::ZERO;
+        std::mem::take(&mut self.stuck)
+    }
+
+    pub fn is_stuck(&self) -> bool {
+        self.stuck
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_quiet_task_is_stuck_once_until_heard_from() {
+        assert_eq!(parse_window("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_window("1h30s"), Ok(Duration::from_secs(3630)));
+        assert_eq!(parse_window("5"), Ok(Duration::from_secs(300)));
+        assert!(parse_window("0s").is_err() && parse_window("soon").is_err());
+        for overflowing in ["999999999999999999", "9999999999999999h", "5124095576030431h1m"] {
+            assert!(parse_window(overflowing).is_err(), "{}", overflowing);
+        }
+
+        let mut watchdog = Watchdog::new(Duration::from_secs(10));
+        assert!(!watchdog.tick(Duration::from_secs(6)));
+        assert!(!watchdog.heard());
+        assert!(!watchdog.tick(Duration::from_secs(6)));
+        assert!(watchdog.tick(Duration::from_secs(6)));
+        assert!(!watchdog.tick(Duration::from_secs(6)));
+        assert!(watchdog.is_stuck());
+        assert!(watchdog.heard());
+        assert!(!watchdog.is_stuck());
+        assert_eq!(Watchdog::new(Duration::from_secs(3630)).describe_window(), "1h30s");
+    }
+}
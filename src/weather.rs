@@ -0,0 +1,179 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use rand::Rng;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::Widget;
+use serde::{Deserialize, Serialize};
+
+use crate::theme::Theme;
+
+const MIN_SPELL: Duration = Duration::from_secs(60 * 60);
+const MAX_SPELL: Duration = Duration::from_secs(4 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Weather {
+    Clear,
+    Cloudy,
+    Rain,
+    Storm,
+}
+
+impl Weather {
+    pub const ALL: [Weather; 4] = [Weather::Clear, Weather::Cloudy, Weather::Rain, Weather::Storm];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Weather::Clear => "Clear",
+            Weather::Cloudy => "Cloudy",
+            Weather::Rain => "Rain",
+            Weather::Storm => "Storm",
+        }
+    }
+
+    /// Rough roughness of the weather from 0.0 (calm) to 1.0 (storm).
+    pub fn intensity(&self) -> f32 {
+        match self {
+            Weather::Clear => 0.0,
+            Weather::Cloudy => 0.2,
+            Weather::Rain => 0.5,
+            Weather::Storm => 1.0,
+        }
+    }
+
+    /// Weather only drifts to a neighbouring kind, so a storm is always preceded by rain.
+    fn next<R: Rng + ?Sized>(self, rng: &mut R) -> Weather {
+        let calmer = rng.gen_bool(0.5);
+        match (self, calmer) {
+            (Weather::Clear, _) => Weather::Cloudy,
+            (Weather::Cloudy, true) => Weather::Clear,
+            (Weather::Cloudy, false) => Weather::Rain,
+            (Weather::Rain, true) => Weather::Cloudy,
+            (Weather::Rain, false) => Weather::Storm,
+            (Weather::Storm, _) => Weather::Rain,
+        }
+    }
+}
+
+impl FromStr for Weather {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Weather::ALL
+            .into_iter()
+            .find(|weather| weather.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown weather '{}'", s.trim()))
+    }
+}
+
+/// Current weather plus the in-scene time left until it changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherState {
+    pub current: Weather,
+    until_change: Duration,
+}
+
+impl WeatherState {
+    pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        WeatherState {
+            current: Weather::Clear,
+            until_change: random_spell(rng),
+        }
+    }
+
+    /// Turn to `weather` now, for a spell of its own.
+    pub fn set<R: Rng + ?Sized>(&mut self, rng: &mut R, weather: Weather) {
+        self.current = weather;
+        self.until_change = random_spell(rng);
+    }
+
+    /// Advance by an in-scene time step, returning true when the weather changed.
+    pub fn update<R: Rng + ?Sized>(&mut self, rng: &mut R, sim_dt: Duration) -> bool {
+        let mut changed = false;
+        let mut remaining = sim_dt;
+        // A large time-lapse step can span several spells.
+        while remaining >= self.until_change {
+            remaining -= self.until_change;
+            self.current = self.current.next(rng);
+            self.until_change = random_spell(rng);
+            changed = true;
+        }
+        self.until_change -= remaining;
+        changed
+    }
+}
+
+fn random_spell<R: Rng + ?Sized>(rng: &mut R) -> Duration {
+    Duration::from_secs(rng.gen_range(MIN_SPELL.as_secs()..=MAX_SPELL.as_secs()))
+}
+
+/// Cheap deterministic hash so drops and clouds stay put between frames without state.
+fn cell_hash(a: u32, b: u32) -> u32 {
+    let mut h = a.wrapping_mul(0x9E37_79B1) ^ b.wrapping_mul(0x85EB_CA77);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0xC2B2_AE3D);
+    h ^ (h >> 13)
+}
+
+/// Draws clouds, rain and lightning for the current weather over the sky and sea.
+pub struct WeatherOverlay {
+    pub weather: Weather,
+    pub elapsed: Duration,
+    pub theme: Theme,
+    /// Hold clouds and rain in place and skip the lightning, for reduced motion.
+    pub still: bool,
+}
+
+impl Widget for WeatherOverlay {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let secs = if self.still { 0.0 } else { self.elapsed.as_secs_f32() };
+
+        if self.weather != Weather::Clear {
+            let cloud_style = Style::default().fg(match self.weather {
+                Weather::Storm => self.theme.storm_cloud,
+                _ => self.theme.cloud,
+            });
+            let cloud_rows = (area.height / 4).clamp(1, 3);
+            let drift = (secs * 0.5) as u32;
+            for row in 0..cloud_rows {
+                let y = area.y + row;
+                for col in 0..area.width {
+                    let world_col = (col as u32).wrapping_add(drift) / 6;
+                    if cell_hash(world_col, row as u32).is_multiple_of(3) {
+                        buf.set_string(area.x + col, y, "▒", cloud_style);
+                    }
+                }
+            }
+        }
+
+        let (density, glyph) = match self.weather {
+            Weather::Rain => (12, "|"),
+            Weather::Storm => (5, "/"),
+            _ => return,
+        };
+        let rain_style = Style::default().fg(self.theme.rain);
+        let fall = (secs * 12.0) as u32;
+        for col in 0..area.width {
+            let h = cell_hash(col as u32, 7);
+            if !h.is_multiple_of(density) {
+                continue;
+            }
+            let y = area.y + ((h / density).wrapping_add(fall) % area.height as u32) as u16;
+            buf.set_string(area.x + col, y, glyph, rain_style);
+        }
+
+        // Storms flash the top rows briefly every few seconds.
+        if self.weather == Weather::Storm && !self.still && (secs % 7.0) < 0.12 {
+            for y in area.y..area.y + (area.height / 3).max(1) {
+                for x in area.x..area.x + area.width {
+                    buf[(x, y)].set_bg(self.theme.lightning);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! The example [`ScenePlugin`]: a whale that surfaces out at sea now and then, rolls
+//! slowly along the top of the water blowing a spout every so often, and dives again.
+//! A SUCCESS brings one up to blow in celebration. Enable it with `--plugin whale`.
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::buffer::Buffer;
+use ratatui::style::Style;
+
+use crate::events::GameEvent;
+use crate::plugin::{PluginContext, ScenePlugin};
+
+/// Seconds between whales, picked at random from this range.
+const SURFACE_INTERVAL: (f32, f32) = (180.0, 480.0);
+/// Columns per second.
+const SPEED: f32 = 1.2;
+/// Seconds between spouts, and how long one hangs in the air.
+const SPOUT_EVERY: f32 = 7.0;
+const SPOUT_FOR: f32 = 1.5;
+/// The back showing above the water, for a whale heading left; mirrored for right.
+const BACK_LEFT: [&str; 2] = ["  ,-----.__ ", " (o       _>"];
+const BACK_RIGHT: [&str; 2] = [" __.-----,  ", "<_       o) "];
+const WIDTH: i32 = 12;
+/// Columns from the whale's left edge to its blowhole, heading left.
+const BLOWHOLE: i32 = 4;
+const SPOUT: [&str; 2] = ["'.:.'", "  :  "];
+
+/// A whale on the way across.
+#[derive(Debug, Clone)]
+struct Surfaced {
+    x: f32,
+    vx: f32,
+    /// Time since it last blew, counting up to the next spout.
+    since_spout: f32,
+}
+
+pub struct Whale {
+    rng: StdRng,
+    until_next: Duration,
+    surfaced: Option<Surfaced>,
+    /// A spout is due as soon as a whale is up.
+    blow: bool,
+}
+
+impl Whale {
+    pub fn new(seed: u64) -> Whale {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let until_next = interval(&mut rng);
+        Whale { rng, until_next, surfaced: None, blow: false }
+    }
+
+    /// Bring a whale up: swimming in from one side, or when one is wanted to blow, in
+    /// full view and blowing straight away.
+    fn surface(&mut self, width: f32) {
+        let rightward = self.rng.gen_bool(0.5);
+        let x = match (self.blow, rightward) {
+            (true, _) => self.rng.gen_range(0.0..(width - WIDTH as f32).max(1.0)),
+            (false, true) => -(WIDTH as f32),
+            (false, false) => width,
+        };
+        let since_spout = if self.blow { SPOUT_EVERY } else { 0.0 };
+        self.blow = false;
+        self.surfaced = Some(Surfaced { x, vx: if rightward { SPEED } else { -SPEED }, since_spout });
+    }
+}
+
+fn interval(rng: &mut StdRng) -> Duration {
+    Duration::from_secs_f32(rng.gen_range(SURFACE_INTERVAL.0..SURFACE_INTERVAL.1))
+}
+
+impl ScenePlugin for Whale {
+    fn name(&self) -> &str {
+        "whale"
+    }
+
+    fn update(&mut self, dt: Duration, ctx: &PluginContext) {
+        if ctx.still {
+            return;
+        }
+        let secs = dt.as_secs_f32();
+        let width = ctx.ocean.width as f32;
+        if let Some(ref mut whale) = self.surfaced {
+            whale.x += whale.vx * secs;
+            whale.since_spout += secs;
+            if self.blow {
+                whale.since_spout = SPOUT_EVERY;
+                self.blow = false;
+            }
+            if whale.since_spout >= SPOUT_EVERY + SPOUT_FOR {
+                whale.since_spout = 0.0;
+            }
+            if whale.x < -(WIDTH as f32) - 1.0 || whale.x > width + 1.0 {
+                self.surfaced = None;
+                self.until_next = interval(&mut self.rng);
+            }
+            return;
+        }
+        self.until_next = self.until_next.saturating_sub(dt);
+        if self.until_next.is_zero() || self.blow {
+            self.surface(width);
+        }
+    }
+
+    fn render(&self, buf: &mut Buffer, ctx: &PluginContext) {
+        let Some(ref whale) = self.surfaced else {
+            return;
+        };
+        let style = Style::default().fg(ctx.theme.rock);
+        let spout_style = Style::default().fg(ctx.theme.foam);
+        let area = ctx.screen.intersection(buf.area);
+        let mut put = |x: i32, y: i32, ch: char, style: Style| {
+            if x >= area.x as i32 && x < area.right() as i32 && y >= area.y as i32 && y < area.bottom() as i32 {
+                buf[(x as u16, y as u16)].set_char(ch).set_style(style);
+            }
+        };
+        let left = ctx.ocean.x as i32 + whale.x.round() as i32;
+        // The back rides the surface, the top of it just above
+        let surface = ctx.ocean.y as i32;
+        let back = if whale.vx < 0.0 { BACK_LEFT } else { BACK_RIGHT };
+        for (row, line) in back.iter().enumerate() {
+            for (i, ch) in line.chars().enumerate().filter(|(_, ch)| *ch != ' ') {
+                put(left + i as i32, surface - 1 + row as i32, ch, style);
+            }
+        }
+        if whale.since_spout >= SPOUT_EVERY {
+            let blowhole = if whale.vx < 0.0 { left + BLOWHOLE } else { left + WIDTH - 1 - BLOWHOLE };
+            for (row, line) in SPOUT.iter().enumerate() {
+                for (i, ch) in line.chars().enumerate().filter(|(_, ch)| *ch != ' ') {
+                    put(blowhole - 2 + i as i32, surface - 3 + row as i32, ch, spout_style);
+                }
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: &GameEvent) {
+        if let GameEvent::SignalReceived { success: true, .. } = event {
+            self.blow = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+
+    use crate::theme::Theme;
+
+    #[test]
+    fn a_success_brings_a_whale_up_to_blow() {
+        let screen = Rect::new(0, 0, 60, 12);
+        let ctx = PluginContext {
+            screen,
+            sky: Rect::new(0, 0, 60, 6),
+            ocean: Rect::new(0, 6, 60, 6),
+            daylight: 1.0,
+            theme: Theme::default(),
+            still: false,
+        };
+        let mut whale = Whale::new(3);
+        whale.update(Duration::from_secs(1), &ctx);
+        assert!(whale.surfaced.is_none());
+
+        whale.on_event(&GameEvent::SignalReceived { success: true, message: "done".to_string() });
+        whale.update(Duration::from_millis(100), &ctx);
+        let mut buf = Buffer::empty(screen);
+        whale.render(&mut buf, &ctx);
+        let row = |y: u16| (0..60).map(|x| buf[(x, y)].symbol().to_string()).collect::<String>();
+        assert!(row(6).contains('o'));
+        assert!(row(3).contains("'.:.'"));
+        assert!(row(4).contains(':'));
+
+        // Across and gone, it dives until the next one is due
+        whale.update(Duration::from_secs(80), &ctx);
+        assert!(whale.surfaced.is_none());
+    }
+}
@@ -1,11 +1,19 @@
-use ratatui::buffer::Buffer;
+use std::time::Duration;
+
+use ratatui::buffer::{Buffer, Cell};
 use ratatui::layout::Rect;
 use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::widgets::Widget;
+use unicode_width::UnicodeWidthStr;
+
+use crate::csv_frames::{self, CrabSprites};
+use crate::fish;
+use crate::theme::Theme;
 
 pub struct FishermanDock {
     pub width: u16,
+    pub theme: Theme,
 }
 
 impl Widget for FishermanDock {
@@ -14,8 +22,8 @@ impl Widget for FishermanDock {
         let plank_post = "╦";
         let post = "║";
         let end_plank = "╔";
-        let plank_color = Color::Rgb(101, 67, 33);
-        let post_color = Color::Rgb(80, 50, 20);
+        let plank_color = self.theme.plank;
+        let post_color = self.theme.post;
 
         let total_height = area.height.min(4);
         let y = area.y + area.height.saturating_sub(total_height);
@@ -33,11 +41,11 @@ impl Widget for FishermanDock {
             }
         }
 
-        for x_off in 0..dock_w {
+        for (x_off, &post_here) in has_post.iter().enumerate() {
             let x = right_x.saturating_sub(x_off as u16);
             if x_off == dock_w - 1 {
                 buf.set_string(x, y, end_plank, Style::default().fg(plank_color));
-            } else if has_post[x_off] {
+            } else if post_here {
                 buf.set_string(x, y, plank_post, Style::default().fg(plank_color));
             } else {
                 buf.set_string(x, y, plank, Style::default().fg(plank_color));
@@ -45,8 +53,8 @@ impl Widget for FishermanDock {
         }
 
         let post_h: u16 = 2;
-        for x_off in 0..dock_w {
-            if !has_post[x_off] { continue; }
+        for (x_off, &post_here) in has_post.iter().enumerate() {
+            if !post_here { continue; }
             let x = right_x.saturating_sub(x_off as u16);
             for r in 1..=post_h {
                 let yy = y + r;
@@ -57,3 +65,290 @@ impl Widget for FishermanDock {
         }
     }
 }
+
+/// Placeholder symbol for the cells a mirrored widget leaves alone.
+const UNDRAWN: &str = "\0";
+
+/// Another widget drawn flipped left to right within its area, for the fisherman and
+/// dock on the far shore. Glyphs that lean or point one way are turned round too. Rows
+/// the widget draws above or below its area are flipped along with the rest.
+pub struct Mirrored<W>(pub W);
+
+impl<W: Widget> Widget for Mirrored<W> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut scratch = Buffer::filled(buf.area, Cell::new(UNDRAWN));
+        self.0.render(area, &mut scratch);
+        for position in scratch.area.positions() {
+            if scratch[position].symbol() == UNDRAWN || position.x < area.x || position.x >= area.right() {
+                continue;
+            }
+            let mut cell = scratch[position].clone();
+            let symbol = mirror_glyph(cell.symbol()).to_string();
+            cell.set_symbol(&symbol);
+            buf[(area.x + area.right() - 1 - position.x, position.y)] = cell;
+        }
+    }
+}
+
+fn mirror_glyph(symbol: &str) -> &str {
+    match symbol {
+        "/" => "\\",
+        "\\" => "/",
+        "╱" => "╲",
+        "╲" => "╱",
+        "╔" => "╗",
+        "╗" => "╔",
+        "┌" => "┐",
+        "┐" => "┌",
+        "└" => "┘",
+        "┘" => "└",
+        "├" => "┤",
+        "┤" => "├",
+        "╰" => "╯",
+        "╯" => "╰",
+        other => other,
+    }
+}
+
+/// Columns a second the crab scuttles along the planks.
+const CRAB_SPEED: f32 = 2.0;
+/// How long the crab rests at each end of the dock before heading back.
+const CRAB_REST: Duration = Duration::from_millis(1500);
+/// How long the crab stays hidden after a catch nearby.
+const CRAB_HIDE: Duration = Duration::from_secs(6);
+
+/// A crab scuttling back and forth along the dock, `x` columns from the left end of
+/// its walk.
+#[derive(Debug, Clone, Default)]
+pub struct Crab {
+    x: f32,
+    heading_left: bool,
+    rest: Duration,
+    hidden: Duration,
+    elapsed: Duration,
+}
+
+impl Crab {
+    /// Walk on by `dt` along a stretch of dock `room` columns long, resting and turning
+    /// around at each end.
+    pub fn tick(&mut self, dt: Duration, room: u16) {
+        self.elapsed += dt;
+        if !self.hidden.is_zero() {
+            self.hidden = self.hidden.saturating_sub(dt);
+            return;
+        }
+        if !self.rest.is_zero() {
+            self.rest = self.rest.saturating_sub(dt);
+            return;
+        }
+        let step = CRAB_SPEED * dt.as_secs_f32();
+        self.x += if self.heading_left { -step } else { step };
+        let end = room as f32;
+        if self.x <= 0.0 || self.x >= end {
+            self.x = self.x.clamp(0.0, end);
+            self.heading_left = !self.heading_left;
+            self.rest = CRAB_REST;
+        }
+    }
+
+    /// Duck down between the planks for a while.
+    pub fn hide(&mut self) {
+        self.hidden = CRAB_HIDE;
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        !self.hidden.is_zero()
+    }
+
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+}
+
+/// Life on the dock planks: the crab, drawn over whatever is behind it so only its
+/// own cells change. Render it into the row just above the planks.
+pub struct DockLife<'a> {
+    pub crab: &'a Crab,
+    pub sprites: &'a CrabSprites,
+}
+
+impl Widget for DockLife<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let crab = self.crab;
+        let frame = if crab.is_hidden() {
+            self.sprites.hide.as_ref()
+        } else if !crab.rest.is_zero() {
+            self.sprites.walk.first().map(|(frame, _)| frame)
+        } else if self.sprites.walk.is_empty() {
+            None
+        } else {
+            Some(fish::frame_at(&self.sprites.walk, crab.elapsed))
+        };
+        let Some(frame) = frame else {
+            return;
+        };
+        let left = area.x + crab.x.round() as u16;
+        for (row, line) in frame.lines.iter().enumerate() {
+            let y = area.y + row as u16;
+            let mut x = left;
+            for (symbol, style) in csv_frames::line_cells(line) {
+                let width = symbol.width() as u16;
+                if symbol != " " && x < area.right() && y < area.bottom() {
+                    buf[(x, y)].set_symbol(&symbol).set_style(style);
+                }
+                x += width;
+            }
+        }
+    }
+}
+
+/// Fills the sky with a daylight tint: deep blue at dawn and dusk, bright at noon.
+/// Draws nothing at full night so the terminal background shows through.
+pub struct SkyBackdrop {
+    pub daylight: f32,
+    pub theme: Theme,
+}
+
+impl SkyBackdrop {
+    /// Background colour of the sky, or `None` at full night when it is left unpainted.
+    pub fn color(&self) -> Option<Color> {
+        if self.daylight <= 0.0 {
+            return None;
+        }
+        Some(self.theme.sky(self.daylight))
+    }
+}
+
+impl Widget for SkyBackdrop {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let Some(bg) = self.color() else {
+            return;
+        };
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf[(x, y)].set_bg(bg);
+            }
+        }
+    }
+}
+
+/// Darkens the water at night, more so with depth, except inside the cone of light
+/// cast down from a dock lantern. Render it over the fish so they are dimmed too.
+pub struct NightWater {
+    /// 0.0 in daylight, 1.0 at full night.
+    pub darkness: f32,
+    /// Column of the lantern above the water, if one is lit.
+    pub lantern_x: Option<u16>,
+    pub theme: Theme,
+}
+
+const LANTERN_REACH: u16 = 12;
+
+impl NightWater {
+    /// Whether a cell lies inside the lantern cone, and how strongly it is lit (1.0 at the apex).
+    fn lantern_light(&self, area: Rect, x: u16, y: u16) -> Option<f32> {
+        let apex = self.lantern_x?;
+        let row = y - area.y;
+        if row >= LANTERN_REACH || x.abs_diff(apex) > row / 2 + 1 {
+            return None;
+        }
+        Some(1.0 - row as f32 / LANTERN_REACH as f32)
+    }
+}
+
+fn scale_color(color: Color, factor: f32) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => {
+            let s = |c: u8| (c as f32 * factor) as u8;
+            Color::Rgb(s(r), s(g), s(b))
+        }
+        Color::Reset => Color::Reset,
+        _ if factor < 0.5 => Color::DarkGray,
+        other => other,
+    }
+}
+
+impl Widget for NightWater {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let darkness = self.darkness.clamp(0.0, 1.0);
+        if darkness <= 0.0 || area.height == 0 {
+            return;
+        }
+        for y in area.top()..area.bottom() {
+            let depth = (y - area.y) as f32 / area.height as f32;
+            let dim = darkness * (0.35 + 0.5 * depth);
+            for x in area.left()..area.right() {
+                let cell = &mut buf[(x, y)];
+                if let Some(light) = self.lantern_light(area, x, y) {
+                    cell.set_bg(scale_color(self.theme.lantern_glow, light * darkness));
+                } else {
+                    cell.set_fg(scale_color(cell.fg, 1.0 - dim));
+                }
+            }
+        }
+    }
+}
+
+/// Darkens everything already drawn, foreground and background, as storm clouds roll
+/// over. Render it over the scene before anything that should stay bright.
+pub struct Gloom {
+    /// 0.0 for none, 1.0 for black.
+    pub amount: f32,
+}
+
+impl Widget for Gloom {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let amount = self.amount.clamp(0.0, 1.0);
+        if amount <= 0.0 {
+            return;
+        }
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = &mut buf[(x, y)];
+                cell.set_fg(scale_color(cell.fg, 1.0 - amount));
+                cell.set_bg(scale_color(cell.bg, 1.0 - amount));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crab_walks_the_dock_and_hides() {
+        let sprites = csv_frames::load_crab_embedded();
+        assert_eq!(sprites.size(), (6, 1));
+        let mut crab = Crab::default();
+        let area = Rect::new(0, 0, 12, 1);
+        let draw = |crab: &Crab| {
+            let mut buf = Buffer::empty(area);
+            DockLife { crab, sprites: &sprites }.render(area, &mut buf);
+            (0..12).map(|x| buf[(x, 0)].symbol().to_string()).collect::<String>()
+        };
+        assert_eq!(draw(&crab), "\\(oo)/      ");
+
+        // Reaches the far end, rests there, then heads back
+        crab.tick(Duration::from_secs(3), 6);
+        assert_eq!(crab.x(), 6.0);
+        crab.tick(CRAB_REST, 6);
+        crab.tick(Duration::from_secs(1), 6);
+        assert_eq!(crab.x(), 4.0);
+
+        crab.hide();
+        assert_eq!(draw(&crab), "      oo    ");
+        crab.tick(CRAB_HIDE, 6);
+        assert!(!crab.is_hidden());
+    }
+
+    #[test]
+    fn mirrored_widgets_face_the_other_way() {
+        let area = Rect::new(0, 0, 6, 1);
+        let mut buf = Buffer::empty(area);
+        buf.set_string(0, 0, "......", Style::default());
+        Mirrored(FishermanDock { width: 4, theme: Theme::default() }).render(area, &mut buf);
+        let row: String = (0..6).map(|x| buf[(x, 0)].symbol().to_string()).collect();
+        assert_eq!(row, "═╦═╗..");
+    }
+}
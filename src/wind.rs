@@ -0,0 +1,151 @@
+//! Wind over the water. It veers and freshens slowly, blows harder the rougher the
+//! weather, and gusts in storms. A cast carries downwind: the line bows in flight and
+//! lands short or long depending on which way it blows.
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::Widget;
+
+use crate::weather::Weather;
+
+/// Seconds between the wind settling on a new direction and strength.
+const SHIFT_INTERVAL: (f32, f32) = (20.0, 60.0);
+/// The strongest steady wind in calm weather and in a storm.
+const CALM_STRENGTH: f32 = 0.3;
+const STORM_STRENGTH: f32 = 0.8;
+/// How far the wind turns towards where it's heading, per second.
+const VEER_RATE: f32 = 0.05;
+/// Gusts a second in a storm, how hard they blow and how quickly they die away.
+const GUSTS_PER_SECOND: f64 = 0.2;
+const GUST_STRENGTH: (f32, f32) = (0.3, 0.6);
+const GUST_DECAY: f32 = 1.5;
+/// How much of a cast's distance a full wind carries it.
+const CAST_DRIFT: f32 = 0.25;
+/// Cells a full wind bows a line in flight.
+const ARC_SKEW: f32 = 8.0;
+
+/// The wind and where it's heading, with its own RNG so it never changes what bites.
+#[derive(Debug, Clone)]
+pub struct Wind {
+    rng: StdRng,
+    /// From -1 (a full wind blowing left, out to sea) to 1 (blowing right, towards the
+    /// dock).
+    speed: f32,
+    target: f32,
+    until_shift: Duration,
+    gust: f32,
+}
+
+impl Wind {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let speed = rng.gen_range(-CALM_STRENGTH..CALM_STRENGTH);
+        let until_shift = interval(&mut rng);
+        Wind { rng, speed, target: speed, until_shift, gust: 0.0 }
+    }
+
+    /// Move the wind on by `dt` in `weather`: it veers slowly towards a new heading
+    /// every so often, and storms throw in gusts.
+    pub fn tick(&mut self, dt: Duration, weather: Weather) {
+        let secs = dt.as_secs_f32();
+        self.until_shift = self.until_shift.saturating_sub(dt);
+        if self.until_shift.is_zero() {
+            let strongest = CALM_STRENGTH + (STORM_STRENGTH - CALM_STRENGTH) * weather.intensity();
+            self.target = self.rng.gen_range(-strongest..strongest);
+            self.until_shift = interval(&mut self.rng);
+        }
+        let step = VEER_RATE * secs;
+        self.speed += (self.target - self.speed).clamp(-step, step);
+
+        self.gust *= (-GUST_DECAY * secs).exp();
+        if weather == Weather::Storm && self.rng.gen_bool((GUSTS_PER_SECOND * secs as f64).min(1.0)) {
+            self.gust = self.rng.gen_range(GUST_STRENGTH.0..GUST_STRENGTH.1) * self.speed.signum();
+        }
+    }
+
+    /// How hard the wind blows right now, gusts included, from -1 (left) to 1 (right).
+    pub fn strength(&self) -> f32 {
+        (self.speed + self.gust).clamp(-1.0, 1.0)
+    }
+
+    /// Columns a cast of `distance` columns is carried downwind.
+    pub fn drift(&self, distance: u16) -> i32 {
+        (self.strength() * CAST_DRIFT * distance as f32).round() as i32
+    }
+
+    /// Columns a line in flight bows downwind at its highest point.
+    pub fn skew(&self) -> f32 {
+        self.strength() * ARC_SKEW
+    }
+
+    /// "calm", or how strong it is and where it blows from, for the screen reader summary.
+    pub fn describe(&self) -> String {
+        let strength = self.strength();
+        let force = match strength.abs() {
+            s if s < 0.05 => return "calm".to_string(),
+            s if s < 0.3 => "light",
+            s if s < 0.6 => "fresh",
+            _ => "strong",
+        };
+        let from = if strength > 0.0 { "west" } else { "east" };
+        format!("{} wind from the {}", force, from)
+    }
+}
+
+fn interval(rng: &mut StdRng) -> Duration {
+    Duration::from_secs_f32(rng.gen_range(SHIFT_INTERVAL.0..SHIFT_INTERVAL.1))
+}
+
+/// A small windsock for a corner of the sky: up to three arrows the way the wind blows,
+/// one per third of full strength, or `~` when it's calm.
+pub struct WindIndicator {
+    pub strength: f32,
+    pub style: Style,
+}
+
+impl Widget for WindIndicator {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let arrows = (self.strength.abs() * 3.0).ceil() as usize;
+        let arrow = if self.strength > 0.0 { "→" } else { "←" };
+        let text = if self.strength.abs() < 0.05 { "wind ~".to_string() } else { format!("wind {}", arrow.repeat(arrows.min(3))) };
+        if area.width as usize >= text.chars().count() && area.height > 0 {
+            buf.set_string(area.x, area.y, text, self.style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wind_veers_slowly_and_gusts_in_storms() {
+        let mut wind = Wind::new(5);
+        wind.target = 0.5;
+        wind.until_shift = Duration::from_secs(600);
+        let before = wind.strength();
+        wind.tick(Duration::from_secs(1), Weather::Clear);
+        assert!((wind.strength() - before - VEER_RATE).abs() < 1e-4);
+
+        // Calm weather never gusts; a storm soon does, blowing the same way
+        let steady = |wind: &Wind| wind.strength() == wind.speed;
+        for _ in 0..100 {
+            wind.tick(Duration::from_millis(100), Weather::Clear);
+            assert!(steady(&wind));
+        }
+        let mut gusted = false;
+        for _ in 0..600 {
+            wind.tick(Duration::from_millis(100), Weather::Storm);
+            gusted |= wind.gust > 0.1;
+        }
+        assert!(gusted);
+
+        assert!(wind.drift(40) > 0);
+        assert_eq!(Wind { speed: 0.0, target: 0.0, gust: 0.0, ..wind }.drift(40), 0);
+    }
+}